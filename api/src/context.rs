@@ -35,7 +35,9 @@ use aptos_types::{
     contract_event::EventWithVersion,
     event::EventKey,
     ledger_info::LedgerInfoWithSignatures,
-    on_chain_config::{GasSchedule, GasScheduleV2, OnChainConfig, OnChainExecutionConfig},
+    on_chain_config::{
+        BlockGasLimitType, GasSchedule, GasScheduleV2, OnChainConfig, OnChainExecutionConfig,
+    },
     state_store::{
         state_key::{StateKey, StateKeyInner},
         state_key_prefix::StateKeyPrefix,
@@ -98,7 +100,7 @@ impl Context {
             })),
             gas_limit_cache: Arc::new(RwLock::new(GasLimitCache {
                 last_updated_epoch: None,
-                block_gas_limit: None,
+                block_gas_limit_type: BlockGasLimitType::NoLimit,
             })),
         }
     }
@@ -898,7 +900,7 @@ impl Context {
     ) -> Result<GasEstimation, E> {
         let config = &self.node_config.api.gas_estimation;
         let min_gas_unit_price = self.min_gas_unit_price(ledger_info)?;
-        let block_gas_limit = self.block_gas_limit(ledger_info)?;
+        let block_gas_limit_type = self.block_gas_limit_type(ledger_info)?;
         if !config.enabled {
             return Ok(self.default_gas_estimation(min_gas_unit_price));
         }
@@ -976,6 +978,16 @@ impl Context {
         let remaining = max_block_history - blocks_len;
 
         // 2. Get gas prices per block
+        // A block cut by the conflict-aware limit used at least its effective limit, as the
+        // effective cost of a block never exceeds the gas it used
+        let full_block_gas_used = match block_gas_limit_type {
+            BlockGasLimitType::NoLimit => None,
+            BlockGasLimitType::Limit(limit) => Some(limit),
+            BlockGasLimitType::ConflictAwareV1 {
+                effective_block_gas_limit,
+                ..
+            } => Some(effective_block_gas_limit),
+        };
         let mut min_inclusion_prices = vec![];
         // TODO: if multiple calls to db is a perf issue, combine into a single call and then split
         for (first, last) in blocks {
@@ -987,7 +999,7 @@ impl Context {
                 Ok(prices_and_used) => {
                     let is_full_block = if prices_and_used.len() >= config.full_block_txns {
                         true
-                    } else if let Some(full_block_gas_used) = block_gas_limit {
+                    } else if let Some(full_block_gas_used) = full_block_gas_used {
                         prices_and_used.iter().map(|(_, used)| *used).sum::<u64>()
                             >= full_block_gas_used
                     } else {
@@ -1170,16 +1182,16 @@ impl Context {
         }
     }
 
-    pub fn block_gas_limit<E: InternalError>(
+    pub fn block_gas_limit_type<E: InternalError>(
         &self,
         ledger_info: &LedgerInfo,
-    ) -> Result<Option<u64>, E> {
+    ) -> Result<BlockGasLimitType, E> {
         // If it's the same epoch, use the cached results
         {
             let cache = self.gas_limit_cache.read().unwrap();
             if let Some(ref last_updated_epoch) = cache.last_updated_epoch {
                 if *last_updated_epoch == ledger_info.epoch.0 {
-                    return Ok(cache.block_gas_limit);
+                    return Ok(cache.block_gas_limit_type);
                 }
             }
         }
@@ -1190,7 +1202,7 @@ impl Context {
             // If a different thread updated the cache, we can exit early
             if let Some(ref last_updated_epoch) = cache.last_updated_epoch {
                 if *last_updated_epoch == ledger_info.epoch.0 {
-                    return Ok(cache.block_gas_limit);
+                    return Ok(cache.block_gas_limit_type);
                 }
             }
 
@@ -1203,13 +1215,15 @@ impl Context {
                 })?;
             let storage_adapter = StorageAdapter::new(&state_view);
 
-            let block_gas_limit = OnChainExecutionConfig::fetch_config(&storage_adapter)
-                .and_then(|config| config.block_gas_limit());
+            let block_gas_limit_type = OnChainExecutionConfig::fetch_config(&storage_adapter)
+                .map_or(BlockGasLimitType::NoLimit, |config| {
+                    config.block_gas_limit_type()
+                });
 
             // Update the cache
-            cache.block_gas_limit = block_gas_limit;
+            cache.block_gas_limit_type = block_gas_limit_type;
             cache.last_updated_epoch = Some(ledger_info.epoch.0);
-            Ok(block_gas_limit)
+            Ok(block_gas_limit_type)
         }
     }
 
@@ -1261,5 +1275,5 @@ pub struct GasEstimationCache {
 
 pub struct GasLimitCache {
     last_updated_epoch: Option<u64>,
-    block_gas_limit: Option<u64>,
+    block_gas_limit_type: BlockGasLimitType,
 }
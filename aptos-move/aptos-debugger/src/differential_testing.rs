@@ -0,0 +1,545 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Differential testing of the block executors on real blocks: every block is executed with
+//! sequential execution and with Block-STM (or the sharded executor), and their outputs are
+//! expected to be identical. Divergent blocks are minimized to a reproducer.
+
+use anyhow::{format_err, Result};
+use aptos_block_executor::txn_commit_hook::NoOpTransactionCommitHook;
+use aptos_block_partitioner::sharded_block_partitioner::ShardedBlockPartitioner;
+use aptos_state_view::StateView;
+use aptos_types::{
+    access_path::AccessPath,
+    account_address::AccountAddress,
+    account_config::{Aggregator, CoinInfoResource},
+    block_executor::partitioner::SubBlocksForShard,
+    on_chain_config::BlockGasLimitType,
+    state_store::state_key::StateKey,
+    transaction::{
+        analyzed_transaction::AnalyzedTransaction, Transaction, TransactionOutput, Version,
+    },
+    write_set::WriteSet,
+};
+use aptos_vm::{
+    aptos_vm::RAYON_EXEC_POOL,
+    block_executor::{AptosTransactionOutput, BlockAptosVM},
+    sharded_block_executor::{
+        local_executor_shard::{LocalExecutorClient, LocalExecutorService},
+        ShardedBlockExecutor,
+    },
+};
+use move_core_types::vm_status::VMStatus;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    fmt,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+/// How a block is executed, to be compared with sequential execution.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum ExecutionMode {
+    Parallel {
+        concurrency_level: usize,
+    },
+    Sharded {
+        num_shards: usize,
+        concurrency_level_per_shard: usize,
+    },
+}
+
+impl fmt::Display for ExecutionMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExecutionMode::Parallel { concurrency_level } => {
+                write!(f, "parallel_{}", concurrency_level)
+            },
+            ExecutionMode::Sharded {
+                num_shards,
+                concurrency_level_per_shard,
+            } => write!(f, "sharded_{}x{}", num_shards, concurrency_level_per_shard),
+        }
+    }
+}
+
+/// A field of the output of a transaction that differs from the one of sequential execution.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct OutputMismatch {
+    /// Index of the transaction in the execution order, which is the one of the partitioning for
+    /// sharded execution.
+    pub index: usize,
+    pub field: String,
+    pub expected: String,
+    pub actual: String,
+}
+
+impl fmt::Display for OutputMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Transaction {} {} mismatch:\n  expected: {}\n  actual: {}",
+            self.index, self.field, self.expected, self.actual
+        )
+    }
+}
+
+/// A block whose outputs with `mode` differ from the ones of sequential execution, reduced to
+/// the transactions needed to reproduce the divergence.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct DivergentBlock {
+    /// Version of the first transaction of the block, the state view is taken at this version.
+    pub version: Version,
+    pub mode: ExecutionMode,
+    pub num_original_transactions: usize,
+    pub transactions: Vec<Transaction>,
+    pub mismatches: Vec<OutputMismatch>,
+}
+
+impl DivergentBlock {
+    fn file_stem(&self) -> String {
+        format!("block_{}_{}", self.version, self.mode)
+    }
+
+    /// Writes the block as BCS (to be loaded back as a reproducer) and its mismatches as text,
+    /// returns the path of the reproducer.
+    pub fn save(&self, dir: &Path) -> Result<PathBuf> {
+        std::fs::create_dir_all(dir)?;
+        let path = dir.join(format!("{}.bcs", self.file_stem()));
+        std::fs::write(&path, bcs::to_bytes(self)?)?;
+        std::fs::write(
+            dir.join(format!("{}.txt", self.file_stem())),
+            self.mismatches
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join("\n"),
+        )?;
+        Ok(path)
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        Ok(bcs::from_bytes(&std::fs::read(path)?)?)
+    }
+}
+
+/// Executes blocks with the given modes and compares their outputs with sequential execution.
+pub struct DifferentialExecutor<S: StateView + Sync + Send + 'static> {
+    modes: Vec<ExecutionMode>,
+    // Set up once per sharded mode, as the shards run on their own threads.
+    sharded_executors: Vec<(
+        ExecutionMode,
+        ShardedBlockPartitioner,
+        ShardedBlockExecutor<S, LocalExecutorClient<S>>,
+    )>,
+}
+
+impl<S: StateView + Sync + Send + 'static> DifferentialExecutor<S> {
+    pub fn new(modes: Vec<ExecutionMode>) -> Self {
+        let sharded_executors = modes
+            .iter()
+            .filter_map(|mode| match mode {
+                ExecutionMode::Sharded {
+                    num_shards,
+                    concurrency_level_per_shard,
+                } => Some((
+                    *mode,
+                    ShardedBlockPartitioner::new(*num_shards),
+                    ShardedBlockExecutor::new(LocalExecutorService::setup_local_executor_shards(
+                        *num_shards,
+                        Some(*concurrency_level_per_shard),
+                    )),
+                )),
+                _ => None,
+            })
+            .collect();
+        Self {
+            modes,
+            sharded_executors,
+        }
+    }
+
+    pub fn modes(&self) -> &[ExecutionMode] {
+        &self.modes
+    }
+
+    /// Executes the block with every mode, and returns the mismatches of each mode with
+    /// sequential execution. Modes that can't execute the block are left out.
+    pub fn test_block(
+        &self,
+        state_view: &Arc<S>,
+        transactions: &[Transaction],
+    ) -> Result<Vec<(ExecutionMode, Vec<OutputMismatch>)>> {
+        let mut sequential_outputs = None;
+        let mut results = vec![];
+        for mode in &self.modes {
+            let mismatches = match mode {
+                ExecutionMode::Parallel { concurrency_level } => {
+                    if sequential_outputs.is_none() {
+                        sequential_outputs = Some(execute_block(
+                            state_view.as_ref(),
+                            transactions.to_vec(),
+                            1,
+                        )?);
+                    }
+                    let outputs = execute_block(
+                        state_view.as_ref(),
+                        transactions.to_vec(),
+                        *concurrency_level,
+                    )?;
+                    diff_outputs(sequential_outputs.as_ref().unwrap(), &outputs, None)
+                },
+                ExecutionMode::Sharded { .. } => {
+                    match self.check_sharded(state_view, transactions, *mode)? {
+                        Some(mismatches) => mismatches,
+                        None => continue,
+                    }
+                },
+            };
+            results.push((*mode, mismatches));
+        }
+        Ok(results)
+    }
+
+    /// Returns the mismatches between `mode` and sequential execution on the block, or `None` if
+    /// `mode` can't execute it.
+    pub fn check_mode(
+        &self,
+        state_view: &Arc<S>,
+        transactions: &[Transaction],
+        mode: ExecutionMode,
+    ) -> Result<Option<Vec<OutputMismatch>>> {
+        match mode {
+            ExecutionMode::Parallel { concurrency_level } => {
+                let expected = execute_block(state_view.as_ref(), transactions.to_vec(), 1)?;
+                let actual = execute_block(
+                    state_view.as_ref(),
+                    transactions.to_vec(),
+                    concurrency_level,
+                )?;
+                Ok(Some(diff_outputs(&expected, &actual, None)))
+            },
+            ExecutionMode::Sharded { .. } => self.check_sharded(state_view, transactions, mode),
+        }
+    }
+
+    /// Removes transactions from a divergent block as long as the rest still diverges with
+    /// `mode`. Parallel execution isn't deterministic, so a divergence that only shows up
+    /// occasionally may not be minimized much.
+    pub fn minimize(
+        &self,
+        state_view: &Arc<S>,
+        transactions: Vec<Transaction>,
+        mode: ExecutionMode,
+    ) -> Vec<Transaction> {
+        minimize_transactions(transactions, |candidate| {
+            matches!(
+                self.check_mode(state_view, candidate, mode),
+                Ok(Some(mismatches)) if !mismatches.is_empty()
+            )
+        })
+    }
+
+    fn check_sharded(
+        &self,
+        state_view: &Arc<S>,
+        transactions: &[Transaction],
+        mode: ExecutionMode,
+    ) -> Result<Option<Vec<OutputMismatch>>> {
+        // The partitioner only knows the read / write sets of a few user transactions, so the
+        // others (including the block prologue) can't be executed by the sharded executor.
+        let user_transactions: Vec<_> = transactions
+            .iter()
+            .filter(|txn| matches!(txn, Transaction::UserTransaction(_)))
+            .cloned()
+            .collect();
+        if user_transactions.is_empty()
+            || !user_transactions
+                .iter()
+                .all(AnalyzedTransaction::is_supported_user_transaction)
+        {
+            return Ok(None);
+        }
+        let (_, partitioner, sharded_executor) = self
+            .sharded_executors
+            .iter()
+            .find(|(sharded_mode, ..)| *sharded_mode == mode)
+            .ok_or_else(|| format_err!("Executor not set up for {}", mode))?;
+        let concurrency_level_per_shard = match mode {
+            ExecutionMode::Sharded {
+                concurrency_level_per_shard,
+                ..
+            } => concurrency_level_per_shard,
+            _ => unreachable!(),
+        };
+
+        let partitioned_txns = partitioner.partition(
+            user_transactions
+                .into_iter()
+                .map(AnalyzedTransaction::from)
+                .collect(),
+            4,
+            0.9,
+        );
+        // Sharded execution commits the transactions in the order of the partitioning.
+        let execution_ordered_txns = SubBlocksForShard::flatten(partitioned_txns.clone())
+            .into_iter()
+            .map(|txn| txn.into_txn())
+            .collect();
+        let expected = execute_block(state_view.as_ref(), execution_ordered_txns, 1)?;
+        let actual = sharded_executor
+            .execute_block(
+                state_view.clone(),
+                partitioned_txns,
+                concurrency_level_per_shard,
+                BlockGasLimitType::NoLimit,
+            )
+            .map_err(|err| format_err!("Unexpected VM Error: {:?}", err))?;
+        // Like in the sharded executor tests, the coin supply aggregator isn't tracked by sharded
+        // execution yet. The other table items are compared.
+        let coin_supply_state_key = coin_supply_state_key(state_view.as_ref())?;
+        Ok(Some(diff_outputs(
+            &expected,
+            &actual,
+            coin_supply_state_key.as_ref(),
+        )))
+    }
+}
+
+/// The state key of the table item holding the supply of the Aptos coin, if it is tracked by an
+/// aggregator.
+fn coin_supply_state_key(state_view: &impl StateView) -> Result<Option<StateKey>> {
+    let coin_info_state_key = StateKey::access_path(AccessPath::resource_access_path(
+        AccountAddress::ONE,
+        CoinInfoResource::struct_tag(),
+    )?);
+    let coin_info: CoinInfoResource =
+        match state_view.get_state_value_bytes(&coin_info_state_key)? {
+            Some(bytes) => bcs::from_bytes(&bytes)?,
+            None => return Ok(None),
+        };
+    Ok(coin_info
+        .supply()
+        .as_ref()
+        .and_then(|supply| supply.aggregator.as_ref())
+        .map(Aggregator::state_key))
+}
+
+/// Splits consecutive transactions starting at version `begin` into blocks, each starting with
+/// its block prologue (except for a first partial block).
+pub fn split_into_blocks(
+    begin: Version,
+    transactions: Vec<Transaction>,
+) -> Vec<(Version, Vec<Transaction>)> {
+    let mut blocks: Vec<(Version, Vec<Transaction>)> = vec![];
+    for (idx, txn) in transactions.into_iter().enumerate() {
+        let starts_block = matches!(
+            txn,
+            Transaction::BlockMetadata(_) | Transaction::GenesisTransaction(_)
+        );
+        match blocks.last_mut() {
+            Some((_, block)) if !starts_block => block.push(txn),
+            _ => blocks.push((begin + idx as Version, vec![txn])),
+        }
+    }
+    blocks
+}
+
+fn execute_block<S: StateView + Sync>(
+    state_view: &S,
+    transactions: Vec<Transaction>,
+    concurrency_level: usize,
+) -> Result<Vec<TransactionOutput>> {
+    BlockAptosVM::execute_block::<_, NoOpTransactionCommitHook<AptosTransactionOutput, VMStatus>>(
+        Arc::clone(&RAYON_EXEC_POOL),
+        transactions,
+        state_view,
+        concurrency_level,
+        BlockGasLimitType::NoLimit,
+        None,
+    )
+    .map_err(|err| format_err!("Unexpected VM Error: {:?}", err))
+}
+
+/// Compares the status, gas used, write set (except for the write to `ignored_state_key`) and
+/// events of every output.
+pub fn diff_outputs(
+    expected: &[TransactionOutput],
+    actual: &[TransactionOutput],
+    ignored_state_key: Option<&StateKey>,
+) -> Vec<OutputMismatch> {
+    let mut mismatches = vec![];
+    if expected.len() != actual.len() {
+        mismatches.push(OutputMismatch {
+            index: expected.len().min(actual.len()),
+            field: "number of outputs".to_string(),
+            expected: expected.len().to_string(),
+            actual: actual.len().to_string(),
+        });
+    }
+    for (index, (expected, actual)) in expected.iter().zip(actual.iter()).enumerate() {
+        let mut push = |field: &str, expected: String, actual: String| {
+            mismatches.push(OutputMismatch {
+                index,
+                field: field.to_string(),
+                expected,
+                actual,
+            })
+        };
+        if expected.status() != actual.status() {
+            push(
+                "status",
+                format!("{:?}", expected.status()),
+                format!("{:?}", actual.status()),
+            );
+        }
+        if expected.gas_used() != actual.gas_used() {
+            push(
+                "gas used",
+                expected.gas_used().to_string(),
+                actual.gas_used().to_string(),
+            );
+        }
+        let expected_writes = write_set_entries(expected.write_set(), ignored_state_key);
+        let actual_writes = write_set_entries(actual.write_set(), ignored_state_key);
+        if expected_writes != actual_writes {
+            // Only show the entries that differ, write sets can be large.
+            let differing = |writes: &BTreeMap<String, String>,
+                             other: &BTreeMap<String, String>| {
+                format!(
+                    "{:?}",
+                    writes
+                        .iter()
+                        .filter(|(key, op)| other.get(*key) != Some(*op))
+                        .collect::<Vec<_>>()
+                )
+            };
+            push(
+                "write set",
+                differing(&expected_writes, &actual_writes),
+                differing(&actual_writes, &expected_writes),
+            );
+        }
+        if expected.events() != actual.events() {
+            push(
+                "events",
+                format!("{:?}", expected.events()),
+                format!("{:?}", actual.events()),
+            );
+        }
+    }
+    mismatches
+}
+
+fn write_set_entries(
+    write_set: &WriteSet,
+    ignored_state_key: Option<&StateKey>,
+) -> BTreeMap<String, String> {
+    write_set
+        .iter()
+        .filter(|(key, _)| Some(*key) != ignored_state_key)
+        // The cached hash of the key is left out of its formatting
+        .map(|(key, op)| (format!("{:?}", key.inner()), format!("{:?}", op)))
+        .collect()
+}
+
+/// Removes chunks of transactions, halving the chunk size down to single transactions, as long
+/// as `diverges` holds for the remaining ones.
+fn minimize_transactions<F>(mut transactions: Vec<Transaction>, mut diverges: F) -> Vec<Transaction>
+where
+    F: FnMut(&[Transaction]) -> bool,
+{
+    let mut chunk_size = transactions.len() / 2;
+    while chunk_size > 0 {
+        let mut start = 0;
+        while start < transactions.len() {
+            let end = (start + chunk_size).min(transactions.len());
+            let candidate: Vec<_> = transactions[..start]
+                .iter()
+                .chain(transactions[end..].iter())
+                .cloned()
+                .collect();
+            if !candidate.is_empty() && diverges(&candidate) {
+                transactions = candidate;
+            } else {
+                start = end;
+            }
+        }
+        chunk_size /= 2;
+    }
+    transactions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_crypto::HashValue;
+    use aptos_types::{
+        state_store::table::TableHandle,
+        transaction::{ExecutionStatus, TransactionStatus},
+        write_set::{WriteOp, WriteSetMut},
+    };
+
+    #[test]
+    fn test_minimize_transactions() {
+        let transactions: Vec<_> = (0..37)
+            .map(|_| Transaction::StateCheckpoint(HashValue::random()))
+            .collect();
+        let culprits = [transactions[3].clone(), transactions[30].clone()];
+
+        let minimized = minimize_transactions(transactions, |candidate| {
+            culprits.iter().all(|txn| candidate.contains(txn))
+        });
+        assert_eq!(minimized, culprits.to_vec());
+    }
+
+    #[test]
+    fn test_diff_outputs() {
+        let output = |gas_used| {
+            TransactionOutput::new(
+                WriteSetMut::new(vec![]).freeze().unwrap(),
+                vec![],
+                gas_used,
+                TransactionStatus::Keep(ExecutionStatus::Success),
+            )
+        };
+        let expected = vec![output(10), output(20)];
+        assert!(diff_outputs(&expected, &expected.clone(), None).is_empty());
+
+        let mismatches = diff_outputs(&expected, &[output(10), output(21), output(5)], None);
+        assert_eq!(mismatches.len(), 2);
+        assert_eq!(mismatches[0].field, "number of outputs");
+        assert_eq!(mismatches[1].index, 1);
+        assert_eq!(mismatches[1].field, "gas used");
+    }
+
+    #[test]
+    fn test_diff_table_items() {
+        let supply_key = StateKey::table_item(TableHandle(AccountAddress::ONE), vec![1]);
+        let other_key = StateKey::table_item(TableHandle(AccountAddress::ONE), vec![2]);
+        let output = |supply: u8, other: u8| {
+            TransactionOutput::new(
+                WriteSetMut::new(vec![
+                    (supply_key.clone(), WriteOp::Modification(vec![supply])),
+                    (other_key.clone(), WriteOp::Modification(vec![other])),
+                ])
+                .freeze()
+                .unwrap(),
+                vec![],
+                10,
+                TransactionStatus::Keep(ExecutionStatus::Success),
+            )
+        };
+
+        // Only the write to the ignored key may differ
+        let expected = vec![output(0, 0)];
+        assert!(diff_outputs(&expected, &[output(1, 0)], Some(&supply_key)).is_empty());
+        let mismatches = diff_outputs(&expected, &[output(1, 1)], Some(&supply_key));
+        assert_eq!(mismatches.len(), 1);
+        assert_eq!(mismatches[0].field, "write set");
+        // Only the differing entry is shown
+        assert!(mismatches[0].expected.contains("key: [2]"));
+        assert!(!mismatches[0].expected.contains("key: [1]"));
+    }
+}
@@ -3,7 +3,9 @@
 
 use anyhow::{format_err, Result};
 use aptos_gas_meter::{StandardGasAlgebra, StandardGasMeter};
-use aptos_gas_profiling::{GasProfiler, TransactionGasLog};
+use aptos_gas_profiling::{
+    AggregatedGasReport, GasProfiler, GasReportAggregator, TransactionGasLog,
+};
 use aptos_gas_schedule::{MiscGasParameters, NativeGasParameters, LATEST_GAS_FEATURE_VERSION};
 use aptos_memory_usage_tracker::MemoryTrackedGasMeter;
 use aptos_resource_viewer::{AnnotatedAccountStateBlob, AptosValueAnnotator};
@@ -12,7 +14,7 @@ use aptos_state_view::TStateView;
 use aptos_types::{
     account_address::AccountAddress,
     chain_id::ChainId,
-    on_chain_config::{Features, OnChainConfig, TimedFeatures},
+    on_chain_config::{BlockGasLimitType, Features, OnChainConfig, TimedFeatures},
     transaction::{
         SignedTransaction, Transaction, TransactionInfo, TransactionOutput, TransactionPayload,
         Version,
@@ -29,9 +31,14 @@ use aptos_vm::{
 };
 use aptos_vm_logging::log_schema::AdapterLogSchema;
 use aptos_vm_types::{change_set::VMChangeSet, output::VMOutput, storage::ChangeSetConfigs};
+use differential_testing::{
+    split_into_blocks, DifferentialExecutor, DivergentBlock, OutputMismatch,
+};
 use move_binary_format::errors::VMResult;
 use std::{path::Path, sync::Arc};
 
+pub mod differential_testing;
+
 pub struct AptosDebugger {
     debugger: Arc<dyn AptosValidatorInterface + Send>,
 }
@@ -57,7 +64,7 @@ impl AptosDebugger {
         txns: Vec<Transaction>,
     ) -> Result<Vec<TransactionOutput>> {
         let state_view = DebuggerStateView::new(self.debugger.clone(), version);
-        AptosVM::execute_block(txns, &state_view, None)
+        AptosVM::execute_block(txns, &state_view, BlockGasLimitType::NoLimit)
             .map_err(|err| format_err!("Unexpected VM Error: {:?}", err))
     }
 
@@ -103,6 +110,132 @@ impl AptosDebugger {
         Ok((status, output, gas_profiler.finish()))
     }
 
+    /// Replays the user transactions in the version range one by one with the gas profiler
+    /// attached, and aggregates their gas usage (per block as well) into a report listing the
+    /// `top_n` most expensive functions, modules and storage items.
+    pub async fn profile_gas_of_past_transactions(
+        &self,
+        begin: Version,
+        limit: u64,
+        top_n: usize,
+    ) -> Result<AggregatedGasReport> {
+        let (txns, _txn_infos) = self
+            .debugger
+            .get_committed_transactions(begin, limit)
+            .await?;
+
+        let mut aggregator = GasReportAggregator::new();
+        let mut num_skipped = 0;
+        for (idx, txn) in txns.into_iter().enumerate() {
+            let version = begin + idx as Version;
+            match txn {
+                Transaction::BlockMetadata(_) => aggregator.start_block(version),
+                Transaction::UserTransaction(txn) => {
+                    // The gas profiler can't be attached to these.
+                    if matches!(
+                        txn.payload(),
+                        TransactionPayload::ModuleBundle(..) | TransactionPayload::Multisig(..)
+                    ) {
+                        num_skipped += 1;
+                        continue;
+                    }
+                    match self.execute_transaction_at_version_with_gas_profiler(version, txn) {
+                        Ok((_status, output, gas_log)) if !output.status().is_discarded() => {
+                            aggregator.add_transaction(&gas_log)
+                        },
+                        Ok((status, ..)) => {
+                            println!("Transaction at version {} discarded: {:?}", version, status);
+                            num_skipped += 1;
+                        },
+                        Err(err) => {
+                            println!(
+                                "Failed to replay transaction at version {}: {}",
+                                version, err
+                            );
+                            num_skipped += 1;
+                        },
+                    }
+                },
+                _ => (),
+            }
+            if (idx + 1) % 1000 == 0 {
+                println!("Profiled {} out of {} transactions", idx + 1, limit);
+            }
+        }
+        if num_skipped > 0 {
+            println!("Skipped {} user transactions", num_skipped);
+        }
+
+        Ok(aggregator.finish(top_n))
+    }
+
+    /// Executes every block of the version range sequentially and with the modes of `executor`,
+    /// and returns the blocks whose outputs differ. With `minimize`, the divergent blocks are
+    /// reduced to the transactions needed to reproduce the divergence.
+    pub async fn differential_test_past_blocks(
+        &self,
+        begin: Version,
+        limit: u64,
+        executor: &DifferentialExecutor<DebuggerStateView>,
+        minimize: bool,
+    ) -> Result<Vec<DivergentBlock>> {
+        let (txns, _txn_infos) = self
+            .debugger
+            .get_committed_transactions(begin, limit)
+            .await?;
+
+        let mut divergent_blocks = vec![];
+        for (version, block) in split_into_blocks(begin, txns) {
+            let state_view = Arc::new(DebuggerStateView::new(self.debugger.clone(), version));
+            for (mode, mismatches) in executor.test_block(&state_view, &block)? {
+                if mismatches.is_empty() {
+                    continue;
+                }
+                println!(
+                    "Block at version {} diverges with {} execution in {} outputs",
+                    version,
+                    mode,
+                    mismatches.len()
+                );
+                let mut divergent_block = DivergentBlock {
+                    version,
+                    mode,
+                    num_original_transactions: block.len(),
+                    transactions: block.clone(),
+                    mismatches,
+                };
+                if minimize {
+                    let transactions = executor.minimize(&state_view, block.clone(), mode);
+                    // Parallel execution isn't deterministic, only keep a minimized block that
+                    // diverges again.
+                    if let Some(mismatches) =
+                        executor.check_mode(&state_view, &transactions, mode)?
+                    {
+                        if !mismatches.is_empty() {
+                            divergent_block.transactions = transactions;
+                            divergent_block.mismatches = mismatches;
+                        }
+                    }
+                }
+                divergent_blocks.push(divergent_block);
+            }
+        }
+        Ok(divergent_blocks)
+    }
+
+    /// Re-executes a divergent block saved by the differential testing, and returns its
+    /// mismatches with sequential execution.
+    pub fn check_reproducer(&self, reproducer: &DivergentBlock) -> Result<Vec<OutputMismatch>> {
+        let executor = DifferentialExecutor::new(vec![reproducer.mode]);
+        let state_view = Arc::new(DebuggerStateView::new(
+            self.debugger.clone(),
+            reproducer.version,
+        ));
+        executor
+            .check_mode(&state_view, &reproducer.transactions, reproducer.mode)?
+            .ok_or_else(|| format_err!("{} can't execute the reproducer", reproducer.mode))
+    }
+
     pub async fn execute_past_transactions(
         &self,
         mut begin: Version,
@@ -1,8 +1,14 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use anyhow::Result;
-use aptos_debugger::AptosDebugger;
+use anyhow::{ensure, Result};
+use aptos_block_executor::dependency_graph::{
+    export_recorded_dependency_graphs, set_dependency_graph_recording,
+};
+use aptos_debugger::{
+    differential_testing::{DifferentialExecutor, DivergentBlock, ExecutionMode},
+    AptosDebugger,
+};
 use aptos_rest_client::Client;
 use aptos_vm::AptosVM;
 use clap::{Parser, Subcommand};
@@ -21,14 +27,54 @@ pub struct Argument {
     #[clap(subcommand)]
     target: Target,
 
-    #[clap(long)]
-    begin_version: u64,
+    #[clap(long, required_unless_present = "check_reproducer")]
+    begin_version: Option<u64>,
 
-    #[clap(long)]
-    limit: u64,
+    #[clap(long, required_unless_present = "check_reproducer")]
+    limit: Option<u64>,
 
     #[clap(long, default_value_t = 1)]
     concurrency_level: usize,
+
+    /// Replay the user transactions with the gas profiler attached and write an aggregated gas
+    /// report (`gas_report.json` and `gas_report.html`) to this directory, instead of printing
+    /// the transaction outputs.
+    #[clap(long, value_parser)]
+    gas_report_dir: Option<PathBuf>,
+
+    /// Number of entries in each of the "top" lists of the gas report.
+    #[clap(long, default_value_t = 50)]
+    gas_report_top_n: usize,
+
+    /// Record the dependency graphs of the replayed blocks and write them to this directory, as
+    /// JSON and DOT files. Needs a concurrency level above 1, as they are only recorded for
+    /// parallel execution.
+    #[clap(long, value_parser)]
+    dependency_graph_dir: Option<PathBuf>,
+
+    /// Execute every block sequentially and in parallel with each of the
+    /// `--differential-concurrency-levels`, and report the blocks whose outputs differ, instead
+    /// of printing the transaction outputs.
+    #[clap(long)]
+    differential_testing: bool,
+
+    #[clap(long, num_args = 1.., default_values_t = [2, 4, 8])]
+    differential_concurrency_levels: Vec<usize>,
+
+    /// Also compare sequential execution with the sharded executor, with this many shards of
+    /// `--concurrency-level` threads. Only blocks of coin transfers and account creations can be
+    /// sharded.
+    #[clap(long, requires = "differential_testing")]
+    differential_num_shards: Option<usize>,
+
+    /// Minimize the divergent blocks found by differential testing and save them to this
+    /// directory, as reproducers for `--check-reproducer`.
+    #[clap(long, value_parser, requires = "differential_testing")]
+    reproducer_dir: Option<PathBuf>,
+
+    /// Re-execute a reproducer saved by differential testing, instead of a version range.
+    #[clap(long, value_parser)]
+    check_reproducer: Option<PathBuf>,
 }
 
 #[tokio::main]
@@ -44,13 +90,113 @@ async fn main() -> Result<()> {
         Target::DB { path } => AptosDebugger::db(path)?,
     };
 
+    if let Some(reproducer_path) = args.check_reproducer {
+        let reproducer = DivergentBlock::load(&reproducer_path)?;
+        let mismatches = debugger.check_reproducer(&reproducer)?;
+        println!(
+            "Reproducer with {} transactions at version {} has {} mismatches with {} execution",
+            reproducer.transactions.len(),
+            reproducer.version,
+            mismatches.len(),
+            reproducer.mode
+        );
+        for mismatch in mismatches {
+            println!("{}", mismatch);
+        }
+        return Ok(());
+    }
+    let begin_version = args.begin_version.unwrap();
+    let limit = args.limit.unwrap();
+    ensure!(limit > 0, "--limit must be at least 1");
+
+    if args.differential_testing {
+        let mut modes: Vec<_> = args
+            .differential_concurrency_levels
+            .iter()
+            .map(|concurrency_level| ExecutionMode::Parallel {
+                concurrency_level: *concurrency_level,
+            })
+            .collect();
+        if let Some(num_shards) = args.differential_num_shards {
+            modes.push(ExecutionMode::Sharded {
+                num_shards,
+                concurrency_level_per_shard: args.concurrency_level,
+            });
+        }
+        let executor = DifferentialExecutor::new(modes);
+        let divergent_blocks = debugger
+            .differential_test_past_blocks(
+                begin_version,
+                limit,
+                &executor,
+                args.reproducer_dir.is_some(),
+            )
+            .await?;
+        println!("Found {} divergent blocks", divergent_blocks.len());
+        for divergent_block in divergent_blocks {
+            match &args.reproducer_dir {
+                Some(reproducer_dir) => {
+                    let path = divergent_block.save(reproducer_dir)?;
+                    println!(
+                        "Block at version {} with {} execution reduced from {} to {} transactions, saved to {}",
+                        divergent_block.version,
+                        divergent_block.mode,
+                        divergent_block.num_original_transactions,
+                        divergent_block.transactions.len(),
+                        path.display()
+                    );
+                },
+                None => {
+                    for mismatch in &divergent_block.mismatches {
+                        println!("{}", mismatch);
+                    }
+                },
+            }
+        }
+        return Ok(());
+    }
+
+    if let Some(report_dir) = args.gas_report_dir {
+        let report = debugger
+            .profile_gas_of_past_transactions(begin_version, limit, args.gas_report_top_n)
+            .await?;
+        std::fs::create_dir_all(&report_dir)?;
+        std::fs::write(report_dir.join("gas_report.json"), report.to_json()?)?;
+        std::fs::write(
+            report_dir.join("gas_report.html"),
+            report.to_html(&format!(
+                "Gas report for versions {} to {}",
+                begin_version,
+                begin_version.saturating_add(limit - 1)
+            )),
+        )?;
+        println!("Gas report saved to {}", report_dir.display());
+        return Ok(());
+    }
+
+    if args.dependency_graph_dir.is_some() {
+        if args.concurrency_level <= 1 {
+            println!("Dependency graphs are only recorded with a concurrency level above 1");
+        }
+        set_dependency_graph_recording(true);
+    }
+
     println!(
         "{:#?}",
         debugger
-            .execute_past_transactions(args.begin_version, args.limit)
+            .execute_past_transactions(begin_version, limit)
             .await?
     );
 
+    if let Some(dependency_graph_dir) = args.dependency_graph_dir {
+        let num_graphs = export_recorded_dependency_graphs(&dependency_graph_dir)?;
+        println!(
+            "Exported {} dependency graphs to {}",
+            num_graphs,
+            dependency_graph_dir.display()
+        );
+    }
+
     Ok(())
 }
 
@@ -0,0 +1,666 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    log::{CallFrame, ExecutionGasEvent, FrameName, TransactionGasLog},
+    render::Render,
+};
+use aptos_gas_algebra::{Fee, InternalGas};
+use aptos_types::state_store::state_key::StateKeyInner;
+use move_core_types::{identifier::IdentStr, language_storage::ModuleId};
+use serde::Serialize;
+use std::{
+    collections::{btree_map, BTreeMap},
+    fmt::Write,
+    ops::Deref,
+};
+
+/// Gas usage of a single function (or native function), summed over all transactions.
+#[derive(Debug, Serialize)]
+pub struct FunctionGas {
+    pub name: String,
+    pub is_native: bool,
+    pub calls: usize,
+    /// Execution & IO gas spent in the function itself, excluding its callees, in gas units.
+    pub gas: f64,
+}
+
+/// Gas usage of a module, i.e. of all of its functions, summed over all transactions.
+#[derive(Debug, Serialize)]
+pub struct ModuleGas {
+    pub name: String,
+    /// Execution & IO gas, in gas units.
+    pub gas: f64,
+}
+
+/// Storage fee of one kind of storage item, summed over all transactions.
+#[derive(Debug, Serialize)]
+pub struct StorageFeeItem {
+    pub name: String,
+    pub count: usize,
+    /// In Octa.
+    pub fee: u64,
+}
+
+/// Statistics of a value observed once per transaction.
+#[derive(Debug, Default, Serialize)]
+pub struct Distribution {
+    pub mean: f64,
+    pub p50: f64,
+    pub p90: f64,
+    pub p99: f64,
+    pub max: f64,
+}
+
+impl Distribution {
+    fn new(mut values: Vec<f64>) -> Self {
+        if values.is_empty() {
+            return Self::default();
+        }
+        values.sort_by(|a, b| a.total_cmp(b));
+        let percentile = |p: f64| values[((values.len() - 1) as f64 * p).round() as usize];
+        Self {
+            mean: values.iter().sum::<f64>() / values.len() as f64,
+            p50: percentile(0.5),
+            p90: percentile(0.9),
+            p99: percentile(0.99),
+            max: values[values.len() - 1],
+        }
+    }
+}
+
+/// Per-transaction gas usage of all transactions with the same entry function.
+#[derive(Debug, Serialize)]
+pub struct EntryFunctionGas {
+    pub name: String,
+    pub count: usize,
+    /// Execution & IO gas, in gas units.
+    pub gas: Distribution,
+    /// Storage fee, in Octa.
+    pub storage_fee: Distribution,
+}
+
+/// Gas usage of the transactions in a block.
+#[derive(Debug, Serialize)]
+pub struct BlockGas {
+    pub first_version: u64,
+    pub num_transactions: usize,
+    /// Execution & IO gas, in gas units.
+    pub gas: f64,
+    /// In Octa.
+    pub storage_fee: u64,
+}
+
+/// A gas report aggregated over many transactions, e.g. all user transactions in a range of
+/// versions. All the lists are sorted by gas usage, from high to low.
+#[derive(Debug, Serialize)]
+pub struct AggregatedGasReport {
+    pub num_transactions: usize,
+    /// Execution & IO gas, in gas units.
+    pub total_gas: f64,
+    /// In Octa.
+    pub total_storage_fee: u64,
+    pub intrinsic_gas: f64,
+    pub write_set_gas: f64,
+    pub functions: Vec<FunctionGas>,
+    pub modules: Vec<ModuleGas>,
+    pub storage_fees: Vec<StorageFeeItem>,
+    pub entry_functions: Vec<EntryFunctionGas>,
+    /// Only present if the transactions were aggregated block by block.
+    pub blocks: Vec<BlockGas>,
+}
+
+#[derive(Default)]
+struct EntryFunctionSamples {
+    gas: Vec<f64>,
+    storage_fee: Vec<f64>,
+}
+
+/// Aggregates the gas logs of many transactions into an `AggregatedGasReport`.
+#[derive(Default)]
+pub struct GasReportAggregator {
+    num_transactions: usize,
+    total_gas: f64,
+    total_storage_fee: u64,
+    intrinsic_gas: f64,
+    write_set_gas: f64,
+    // Name -> (is native, calls, gas)
+    functions: BTreeMap<String, (bool, usize, f64)>,
+    modules: BTreeMap<String, f64>,
+    // Name -> (count, fee)
+    storage_fees: BTreeMap<String, (usize, u64)>,
+    entry_functions: BTreeMap<String, EntryFunctionSamples>,
+    blocks: Vec<BlockGas>,
+}
+
+fn function_name(module_id: &ModuleId, name: &IdentStr) -> String {
+    // Type arguments are left out, so that all instantiations of a generic function add up.
+    format!("{}::{}", module_id.short_str_lossless(), name)
+}
+
+fn add_storage_fee(storage_fees: &mut BTreeMap<String, (usize, u64)>, key: String, fee: Fee) {
+    if fee.is_zero() {
+        return;
+    }
+    let fee = u64::from(fee);
+    match storage_fees.entry(key) {
+        btree_map::Entry::Occupied(entry) => {
+            let r = entry.into_mut();
+            r.0 += 1;
+            r.1 += fee;
+        },
+        btree_map::Entry::Vacant(entry) => {
+            entry.insert((1, fee));
+        },
+    }
+}
+
+impl GasReportAggregator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Starts a new block. The transactions added from now on are accounted to it.
+    pub fn start_block(&mut self, first_version: u64) {
+        self.blocks.push(BlockGas {
+            first_version,
+            num_transactions: 0,
+            gas: 0.0,
+            storage_fee: 0,
+        });
+    }
+
+    pub fn add_transaction(&mut self, log: &TransactionGasLog) {
+        let scaling_factor = u64::from(log.exec_io.gas_scaling_factor) as f64;
+        let to_gas_units = |gas: InternalGas| u64::from(gas) as f64 / scaling_factor;
+
+        let gas = to_gas_units(log.exec_io.total);
+        self.num_transactions += 1;
+        self.total_gas += gas;
+        self.total_storage_fee += u64::from(log.storage.total);
+        self.intrinsic_gas += to_gas_units(log.exec_io.intrinsic_cost);
+        for write in &log.exec_io.write_set_transient {
+            self.write_set_gas += to_gas_units(write.cost);
+        }
+        self.add_frame(&log.exec_io.call_graph, &to_gas_units);
+
+        for write in &log.storage.write_set_storage {
+            // Aggregated by the type of the storage item rather than by the individual item.
+            let key = match write.key.deref() {
+                StateKeyInner::AccessPath(ap) => format!("{}", Render(&ap.get_path())),
+                StateKeyInner::TableItem { handle, .. } => {
+                    format!("table_item<{}>", Render(handle))
+                },
+                StateKeyInner::Raw(..) => "raw".to_string(),
+            };
+            add_storage_fee(
+                &mut self.storage_fees,
+                format!("write_set;{}<{}>", Render(&write.op_type), key),
+                write.cost,
+            );
+        }
+        for event in &log.storage.events {
+            add_storage_fee(
+                &mut self.storage_fees,
+                format!("events;{}", event.ty),
+                event.cost,
+            );
+        }
+        add_storage_fee(
+            &mut self.storage_fees,
+            "transaction".to_string(),
+            log.storage.txn_storage,
+        );
+
+        let entry_function = match log.entry_point() {
+            FrameName::Script => "<script>".to_string(),
+            FrameName::Function {
+                module_id, name, ..
+            } => function_name(module_id, name),
+        };
+        let samples = self.entry_functions.entry(entry_function).or_default();
+        samples.gas.push(gas);
+        samples
+            .storage_fee
+            .push(u64::from(log.storage.total) as f64);
+
+        if let Some(block) = self.blocks.last_mut() {
+            block.num_transactions += 1;
+            block.gas += gas;
+            block.storage_fee += u64::from(log.storage.total);
+        }
+    }
+
+    fn add_frame(&mut self, frame: &CallFrame, to_gas_units: &impl Fn(InternalGas) -> f64) {
+        let mut frame_cost = InternalGas::new(0);
+        for event in &frame.events {
+            use ExecutionGasEvent::*;
+
+            match event {
+                Loc(_) => (),
+                Bytecode { cost, .. } | LoadResource { cost, .. } => frame_cost += *cost,
+                Call(inner_frame) => self.add_frame(inner_frame, to_gas_units),
+                CallNative {
+                    module_id,
+                    fn_name,
+                    cost,
+                    ..
+                } => {
+                    let gas = to_gas_units(*cost);
+                    let entry = self
+                        .functions
+                        .entry(function_name(module_id, fn_name))
+                        .or_insert((true, 0, 0.0));
+                    entry.1 += 1;
+                    entry.2 += gas;
+                    *self
+                        .modules
+                        .entry(module_id.short_str_lossless())
+                        .or_default() += gas;
+                },
+            }
+        }
+
+        let (name, module) = match &frame.name {
+            FrameName::Script => ("<script>".to_string(), "<script>".to_string()),
+            FrameName::Function {
+                module_id, name, ..
+            } => (
+                function_name(module_id, name),
+                module_id.short_str_lossless(),
+            ),
+        };
+        let gas = to_gas_units(frame_cost);
+        let entry = self.functions.entry(name).or_insert((false, 0, 0.0));
+        entry.1 += 1;
+        entry.2 += gas;
+        *self.modules.entry(module).or_default() += gas;
+    }
+
+    /// Builds the report. Only the `top_n` entries of the function, module and storage fee lists
+    /// are kept.
+    pub fn finish(self, top_n: usize) -> AggregatedGasReport {
+        let mut functions = self
+            .functions
+            .into_iter()
+            .map(|(name, (is_native, calls, gas))| FunctionGas {
+                name,
+                is_native,
+                calls,
+                gas,
+            })
+            .collect::<Vec<_>>();
+        functions.sort_by(|a, b| b.gas.total_cmp(&a.gas));
+        functions.truncate(top_n);
+        let mut modules = self
+            .modules
+            .into_iter()
+            .map(|(name, gas)| ModuleGas { name, gas })
+            .collect::<Vec<_>>();
+        modules.sort_by(|a, b| b.gas.total_cmp(&a.gas));
+        modules.truncate(top_n);
+        let mut storage_fees = self
+            .storage_fees
+            .into_iter()
+            .map(|(name, (count, fee))| StorageFeeItem { name, count, fee })
+            .collect::<Vec<_>>();
+        storage_fees.sort_by(|a, b| b.fee.cmp(&a.fee));
+        storage_fees.truncate(top_n);
+        let mut entry_functions = self
+            .entry_functions
+            .into_iter()
+            .map(|(name, samples)| EntryFunctionGas {
+                name,
+                count: samples.gas.len(),
+                gas: Distribution::new(samples.gas),
+                storage_fee: Distribution::new(samples.storage_fee),
+            })
+            .collect::<Vec<_>>();
+        // Sorted by the total gas of the transactions.
+        entry_functions.sort_by(|a, b| {
+            (b.gas.mean * b.count as f64).total_cmp(&(a.gas.mean * a.count as f64))
+        });
+
+        AggregatedGasReport {
+            num_transactions: self.num_transactions,
+            total_gas: self.total_gas,
+            total_storage_fee: self.total_storage_fee,
+            intrinsic_gas: self.intrinsic_gas,
+            write_set_gas: self.write_set_gas,
+            functions,
+            modules,
+            storage_fees,
+            entry_functions,
+            blocks: self.blocks,
+        }
+    }
+}
+
+fn escape_html(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn percentage(part: f64, total: f64) -> String {
+    if total > 0.0 {
+        format!("{:.2}%", part / total * 100.0)
+    } else {
+        "-".to_string()
+    }
+}
+
+fn render_html_table(output: &mut String, title: &str, header: &[&str], rows: Vec<Vec<String>>) {
+    // Writing to a `String` can't fail.
+    let _ = writeln!(output, "<h2>{}</h2>\n<table>\n<tr>", escape_html(title));
+    for column in header {
+        let _ = write!(output, "<th>{}</th>", escape_html(column));
+    }
+    let _ = writeln!(output, "</tr>");
+    for row in rows {
+        let _ = write!(output, "<tr>");
+        for cell in row {
+            let _ = write!(output, "<td>{}</td>", escape_html(&cell));
+        }
+        let _ = writeln!(output, "</tr>");
+    }
+    let _ = writeln!(output, "</table>");
+}
+
+impl AggregatedGasReport {
+    pub fn to_json(&self) -> anyhow::Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    pub fn to_html(&self, title: &str) -> String {
+        let mut output = String::new();
+        let _ = writeln!(
+            output,
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n\
+             <style>\nbody {{ font-family: sans-serif; }}\n\
+             table {{ border-collapse: collapse; margin-bottom: 2em; }}\n\
+             th, td {{ border: 1px solid #ccc; padding: 2px 8px; text-align: right; }}\n\
+             td:first-child {{ text-align: left; font-family: monospace; }}\n\
+             </style>\n</head>\n<body>\n<h1>{title}</h1>",
+            title = escape_html(title)
+        );
+
+        render_html_table(
+            &mut output,
+            "Summary",
+            &["", "value"],
+            vec![
+                vec![
+                    "transactions".to_string(),
+                    self.num_transactions.to_string(),
+                ],
+                vec![
+                    "execution & IO (gas units)".to_string(),
+                    format!("{:.2}", self.total_gas),
+                ],
+                vec![
+                    "of which intrinsic (gas units)".to_string(),
+                    format!("{:.2}", self.intrinsic_gas),
+                ],
+                vec![
+                    "of which write set (gas units)".to_string(),
+                    format!("{:.2}", self.write_set_gas),
+                ],
+                vec![
+                    "storage fee (Octa)".to_string(),
+                    self.total_storage_fee.to_string(),
+                ],
+            ],
+        );
+
+        render_html_table(
+            &mut output,
+            "Top functions (execution & IO, excluding callees)",
+            &["function", "calls", "gas units", "share"],
+            self.functions
+                .iter()
+                .map(|f| {
+                    vec![
+                        if f.is_native {
+                            format!("{} (native)", f.name)
+                        } else {
+                            f.name.clone()
+                        },
+                        f.calls.to_string(),
+                        format!("{:.2}", f.gas),
+                        percentage(f.gas, self.total_gas),
+                    ]
+                })
+                .collect(),
+        );
+
+        render_html_table(
+            &mut output,
+            "Top modules (execution & IO)",
+            &["module", "gas units", "share"],
+            self.modules
+                .iter()
+                .map(|m| {
+                    vec![
+                        m.name.clone(),
+                        format!("{:.2}", m.gas),
+                        percentage(m.gas, self.total_gas),
+                    ]
+                })
+                .collect(),
+        );
+
+        render_html_table(
+            &mut output,
+            "Top storage fees",
+            &["item", "count", "Octa", "share"],
+            self.storage_fees
+                .iter()
+                .map(|s| {
+                    vec![
+                        s.name.clone(),
+                        s.count.to_string(),
+                        s.fee.to_string(),
+                        percentage(s.fee as f64, self.total_storage_fee as f64),
+                    ]
+                })
+                .collect(),
+        );
+
+        render_html_table(
+            &mut output,
+            "Entry functions (per transaction)",
+            &[
+                "entry function",
+                "count",
+                "gas mean",
+                "gas p50",
+                "gas p90",
+                "gas p99",
+                "gas max",
+                "storage fee mean",
+                "storage fee p50",
+                "storage fee p99",
+                "storage fee max",
+            ],
+            self.entry_functions
+                .iter()
+                .map(|e| {
+                    vec![
+                        e.name.clone(),
+                        e.count.to_string(),
+                        format!("{:.2}", e.gas.mean),
+                        format!("{:.2}", e.gas.p50),
+                        format!("{:.2}", e.gas.p90),
+                        format!("{:.2}", e.gas.p99),
+                        format!("{:.2}", e.gas.max),
+                        format!("{:.0}", e.storage_fee.mean),
+                        format!("{:.0}", e.storage_fee.p50),
+                        format!("{:.0}", e.storage_fee.p99),
+                        format!("{:.0}", e.storage_fee.max),
+                    ]
+                })
+                .collect(),
+        );
+
+        if !self.blocks.is_empty() {
+            render_html_table(
+                &mut output,
+                "Blocks",
+                &[
+                    "first version",
+                    "transactions",
+                    "gas units",
+                    "storage fee (Octa)",
+                ],
+                self.blocks
+                    .iter()
+                    .map(|b| {
+                        vec![
+                            b.first_version.to_string(),
+                            b.num_transactions.to_string(),
+                            format!("{:.2}", b.gas),
+                            b.storage_fee.to_string(),
+                        ]
+                    })
+                    .collect(),
+            );
+        }
+
+        let _ = writeln!(output, "</body>\n</html>");
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::log::{ExecutionAndIOCosts, StorageFees};
+    use aptos_gas_algebra::GasScalingFactor;
+    use move_binary_format::file_format_common::Opcodes;
+    use move_core_types::{account_address::AccountAddress, identifier::Identifier};
+
+    fn function_frame(module: &str, name: &str, events: Vec<ExecutionGasEvent>) -> CallFrame {
+        let mut frame = CallFrame::new_function(
+            ModuleId::new(AccountAddress::ONE, Identifier::new(module).unwrap()),
+            Identifier::new(name).unwrap(),
+            vec![],
+        );
+        frame.events = events;
+        frame
+    }
+
+    // A coin transfer spending 300 internal gas units itself, 200 in a callee and 100 in a native
+    // function, with a scaling factor of 100.
+    fn transfer_log() -> TransactionGasLog {
+        let call_graph = function_frame("coin", "transfer", vec![
+            ExecutionGasEvent::Bytecode {
+                op: Opcodes::RET,
+                cost: InternalGas::new(300),
+            },
+            ExecutionGasEvent::Call(function_frame("coin", "withdraw", vec![
+                ExecutionGasEvent::Bytecode {
+                    op: Opcodes::RET,
+                    cost: InternalGas::new(200),
+                },
+            ])),
+            ExecutionGasEvent::CallNative {
+                module_id: ModuleId::new(AccountAddress::ONE, Identifier::new("hash").unwrap()),
+                fn_name: Identifier::new("sha3_256").unwrap(),
+                ty_args: vec![],
+                cost: InternalGas::new(100),
+            },
+        ]);
+        TransactionGasLog {
+            exec_io: ExecutionAndIOCosts {
+                gas_scaling_factor: GasScalingFactor::new(100),
+                total: InternalGas::new(700),
+                intrinsic_cost: InternalGas::new(100),
+                call_graph,
+                write_set_transient: vec![],
+            },
+            storage: StorageFees {
+                total: Fee::new(500),
+                write_set_storage: vec![],
+                events: vec![],
+                event_discount: Fee::new(0),
+                txn_storage: Fee::new(500),
+            },
+        }
+    }
+
+    fn transfer_report(top_n: usize) -> AggregatedGasReport {
+        let mut aggregator = GasReportAggregator::new();
+        aggregator.start_block(10);
+        aggregator.add_transaction(&transfer_log());
+        aggregator.add_transaction(&transfer_log());
+        aggregator.finish(top_n)
+    }
+
+    #[test]
+    fn test_aggregation() {
+        let report = transfer_report(10);
+        assert_eq!(report.num_transactions, 2);
+        assert_eq!(report.total_gas, 14.0);
+        assert_eq!(report.intrinsic_gas, 2.0);
+        assert_eq!(report.total_storage_fee, 1000);
+
+        // The gas of a function excludes the one of its callees
+        let functions: Vec<_> = report
+            .functions
+            .iter()
+            .map(|f| (f.name.as_str(), f.is_native, f.calls, f.gas))
+            .collect();
+        assert_eq!(functions, vec![
+            ("0x1::coin::transfer", false, 2, 6.0),
+            ("0x1::coin::withdraw", false, 2, 4.0),
+            ("0x1::hash::sha3_256", true, 2, 2.0),
+        ]);
+        let modules: Vec<_> = report
+            .modules
+            .iter()
+            .map(|m| (m.name.as_str(), m.gas))
+            .collect();
+        assert_eq!(modules, vec![("0x1::coin", 10.0), ("0x1::hash", 2.0)]);
+
+        assert_eq!(report.storage_fees.len(), 1);
+        assert_eq!(report.storage_fees[0].name, "transaction");
+        assert_eq!(report.storage_fees[0].count, 2);
+        assert_eq!(report.storage_fees[0].fee, 1000);
+
+        assert_eq!(report.entry_functions.len(), 1);
+        assert_eq!(report.entry_functions[0].name, "0x1::coin::transfer");
+        assert_eq!(report.entry_functions[0].count, 2);
+        assert_eq!(report.entry_functions[0].gas.mean, 7.0);
+        assert_eq!(report.entry_functions[0].storage_fee.max, 500.0);
+
+        assert_eq!(report.blocks.len(), 1);
+        assert_eq!(report.blocks[0].first_version, 10);
+        assert_eq!(report.blocks[0].num_transactions, 2);
+
+        // Only the top entries are kept
+        let report = transfer_report(1);
+        assert_eq!(report.functions.len(), 1);
+        assert_eq!(report.functions[0].name, "0x1::coin::transfer");
+        assert_eq!(report.modules.len(), 1);
+    }
+
+    #[test]
+    fn test_rendering() {
+        let report = transfer_report(10);
+
+        let html = report.to_html("Gas report for <versions>");
+        assert!(html.contains("<title>Gas report for &lt;versions&gt;</title>"));
+        assert!(html.contains("<td>0x1::hash::sha3_256 (native)</td>"));
+        // The share of a function is relative to the total execution & IO gas
+        assert!(html.contains(
+            "<tr><td>0x1::coin::transfer</td><td>2</td><td>6.00</td><td>42.86%</td></tr>"
+        ));
+        assert!(html.contains("<h2>Blocks</h2>"));
+
+        let json: serde_json::Value = serde_json::from_str(&report.to_json().unwrap()).unwrap();
+        assert_eq!(json["num_transactions"], 2);
+        assert_eq!(json["functions"][0]["name"], "0x1::coin::transfer");
+    }
+}
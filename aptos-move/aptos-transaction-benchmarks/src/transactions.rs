@@ -346,7 +346,7 @@ where
             transactions,
             self.state_view.as_ref(),
             1,
-            maybe_block_gas_limit,
+            maybe_block_gas_limit.into(),
             None,
         )
         .expect("VM should not fail to start");
@@ -379,7 +379,7 @@ where
                     self.state_view.clone(),
                     partitioned_block,
                     concurrency_level_per_shard,
-                    maybe_block_gas_limit,
+                    maybe_block_gas_limit.into(),
                 )
                 .expect("VM should not fail to start");
         } else {
@@ -391,7 +391,7 @@ where
                 transactions,
                 self.state_view.as_ref(),
                 concurrency_level_per_shard,
-                maybe_block_gas_limit,
+                maybe_block_gas_limit.into(),
                 None,
             )
             .expect("VM should not fail to start");
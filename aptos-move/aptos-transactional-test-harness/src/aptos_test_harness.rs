@@ -18,6 +18,7 @@ use aptos_types::{
     block_metadata::BlockMetadata,
     chain_id::ChainId,
     contract_event::ContractEvent,
+    on_chain_config::BlockGasLimitType,
     state_store::{state_key::StateKey, table::TableHandle},
     transaction::{
         EntryFunction as TransactionEntryFunction, ExecutionStatus, Module as TransactionModule,
@@ -472,7 +473,8 @@ impl<'a> AptosTestAdapter<'a> {
     /// Should error if the transaction ends up being discarded, or having a status other than
     /// EXECUTED.
     fn run_transaction(&mut self, txn: Transaction) -> Result<TransactionOutput> {
-        let mut outputs = AptosVM::execute_block(vec![txn], &self.storage.clone(), None)?;
+        let mut outputs =
+            AptosVM::execute_block(vec![txn], &self.storage.clone(), BlockGasLimitType::NoLimit)?;
 
         assert_eq!(outputs.len(), 1);
 
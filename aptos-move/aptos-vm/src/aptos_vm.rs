@@ -13,6 +13,7 @@ use crate::{
     errors::expect_only_successful_execution,
     move_vm_ext::{MoveResolverExt, RespawnedSession, SessionExt, SessionId},
     sharded_block_executor::{executor_client::ExecutorClient, ShardedBlockExecutor},
+    speculative_execution::SpeculativeExecutor,
     system_module_names::*,
     transaction_metadata::TransactionMetadata,
     verifier, VMExecutor, VMValidator,
@@ -34,7 +35,7 @@ use aptos_types::{
     block_executor::partitioner::SubBlocksForShard,
     block_metadata::BlockMetadata,
     fee_statement::FeeStatement,
-    on_chain_config::{new_epoch_event_key, FeatureFlag, TimedFeatureOverride},
+    on_chain_config::{new_epoch_event_key, BlockGasLimitType, FeatureFlag, TimedFeatureOverride},
     transaction::{
         analyzed_transaction::AnalyzedTransaction, EntryFunction, ExecutionError, ExecutionStatus,
         ModuleBundle, Multisig, MultisigTransactionPayload, SignatureCheckedTransaction,
@@ -88,6 +89,8 @@ static NUM_PROOF_READING_THREADS: OnceCell<usize> = OnceCell::new();
 static PARANOID_TYPE_CHECKS: OnceCell<bool> = OnceCell::new();
 static PROCESSED_TRANSACTIONS_DETAILED_COUNTERS: OnceCell<bool> = OnceCell::new();
 static TIMED_FEATURE_OVERRIDE: OnceCell<TimedFeatureOverride> = OnceCell::new();
+static SPECULATIVE_EXECUTION_THREADS: OnceCell<usize> = OnceCell::new();
+static SPECULATIVE_EXECUTION_CACHE_SIZE: OnceCell<usize> = OnceCell::new();
 
 pub static RAYON_EXEC_POOL: Lazy<Arc<rayon::ThreadPool>> = Lazy::new(|| {
     Arc::new(
@@ -216,6 +219,92 @@ impl AptosVM {
         }
     }
 
+    /// Sets the # of threads speculatively pre-executing transactions, 0 disables speculative
+    /// execution.
+    pub fn set_speculative_execution_threads_once(num_threads: usize) {
+        // Only the first call succeeds, due to OnceCell semantics.
+        SPECULATIVE_EXECUTION_THREADS.set(num_threads).ok();
+    }
+
+    /// Returns the # of speculative execution threads if already set, otherwise return default
+    /// value (0, disabled).
+    pub fn get_speculative_execution_threads() -> usize {
+        match SPECULATIVE_EXECUTION_THREADS.get() {
+            Some(num_threads) => *num_threads,
+            None => 0,
+        }
+    }
+
+    /// Sets the max # of speculatively executed transactions to keep the outputs of.
+    pub fn set_speculative_execution_cache_size_once(cache_size: usize) {
+        // Only the first call succeeds, due to OnceCell semantics.
+        SPECULATIVE_EXECUTION_CACHE_SIZE.set(cache_size).ok();
+    }
+
+    /// Returns the speculative execution cache size if already set, otherwise return default
+    /// value (10000).
+    pub fn get_speculative_execution_cache_size() -> usize {
+        match SPECULATIVE_EXECUTION_CACHE_SIZE.get() {
+            Some(cache_size) => *cache_size,
+            None => 10_000,
+        }
+    }
+
+    /// Executes the block like `VMExecutor::execute_block`, but reuses the outputs of the
+    /// transactions that were pre-executed by the speculative executor, if still valid.
+    pub fn execute_block_with_speculative_executor(
+        transactions: Vec<Transaction>,
+        state_view: &(impl StateView + Sync),
+        block_gas_limit_type: BlockGasLimitType,
+        speculative_executor: &SpeculativeExecutor,
+    ) -> Result<Vec<TransactionOutput>, VMStatus> {
+        Self::execute_block_impl(
+            transactions,
+            state_view,
+            block_gas_limit_type,
+            Some(speculative_executor),
+        )
+    }
+
+    fn execute_block_impl(
+        transactions: Vec<Transaction>,
+        state_view: &(impl StateView + Sync),
+        block_gas_limit_type: BlockGasLimitType,
+        speculative_executor: Option<&SpeculativeExecutor>,
+    ) -> Result<Vec<TransactionOutput>, VMStatus> {
+        fail_point!("move_adapter::execute_block", |_| {
+            Err(VMStatus::error(
+                StatusCode::UNKNOWN_INVARIANT_VIOLATION_ERROR,
+                None,
+            ))
+        });
+        let log_context = AdapterLogSchema::new(state_view.id(), 0);
+        info!(
+            log_context,
+            "Executing block, transaction count: {}",
+            transactions.len()
+        );
+
+        let count = transactions.len();
+        let ret = BlockAptosVM::execute_block_with_speculative_executor::<
+            _,
+            NoOpTransactionCommitHook<AptosTransactionOutput, VMStatus>,
+        >(
+            Arc::clone(&RAYON_EXEC_POOL),
+            transactions,
+            state_view,
+            Self::get_concurrency_level(),
+            block_gas_limit_type,
+            None,
+            speculative_executor,
+        );
+        if ret.is_ok() {
+            // Record the histogram count for transactions per block.
+            BLOCK_TRANSACTION_COUNT.observe(count as f64);
+        }
+        ret
+    }
+
     pub fn internals(&self) -> AptosVMInternals {
         AptosVMInternals::new(&self.0)
     }
@@ -1479,45 +1568,16 @@ impl VMExecutor for AptosVM {
     fn execute_block(
         transactions: Vec<Transaction>,
         state_view: &(impl StateView + Sync),
-        maybe_block_gas_limit: Option<u64>,
+        block_gas_limit_type: BlockGasLimitType,
     ) -> Result<Vec<TransactionOutput>, VMStatus> {
-        fail_point!("move_adapter::execute_block", |_| {
-            Err(VMStatus::error(
-                StatusCode::UNKNOWN_INVARIANT_VIOLATION_ERROR,
-                None,
-            ))
-        });
-        let log_context = AdapterLogSchema::new(state_view.id(), 0);
-        info!(
-            log_context,
-            "Executing block, transaction count: {}",
-            transactions.len()
-        );
-
-        let count = transactions.len();
-        let ret = BlockAptosVM::execute_block::<
-            _,
-            NoOpTransactionCommitHook<AptosTransactionOutput, VMStatus>,
-        >(
-            Arc::clone(&RAYON_EXEC_POOL),
-            transactions,
-            state_view,
-            Self::get_concurrency_level(),
-            maybe_block_gas_limit,
-            None,
-        );
-        if ret.is_ok() {
-            // Record the histogram count for transactions per block.
-            BLOCK_TRANSACTION_COUNT.observe(count as f64);
-        }
-        ret
+        Self::execute_block_impl(transactions, state_view, block_gas_limit_type, None)
     }
 
     fn execute_block_sharded<S: StateView + Sync + Send + 'static, C: ExecutorClient<S>>(
         sharded_block_executor: &ShardedBlockExecutor<S, C>,
         transactions: Vec<SubBlocksForShard<AnalyzedTransaction>>,
         state_view: Arc<S>,
-        maybe_block_gas_limit: Option<u64>,
+        block_gas_limit_type: BlockGasLimitType,
     ) -> Result<Vec<TransactionOutput>, VMStatus> {
         let log_context = AdapterLogSchema::new(state_view.id(), 0);
         info!(
@@ -1531,7 +1591,7 @@ impl VMExecutor for AptosVM {
             state_view,
             transactions,
             AptosVM::get_concurrency_level(),
-            maybe_block_gas_limit,
+            block_gas_limit_type,
         );
         if ret.is_ok() {
             // Record the histogram count for transactions per block.
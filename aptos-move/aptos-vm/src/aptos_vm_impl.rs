@@ -43,7 +43,7 @@ use move_core_types::{
 };
 use move_vm_runtime::logging::expect_no_verification_errors;
 use move_vm_types::gas::UnmeteredGasMeter;
-use std::sync::Arc;
+use std::{collections::BTreeSet, sync::Arc};
 
 pub const MAXIMUM_APPROVED_TRANSACTION_SIZE: u64 = 1024 * 1024;
 
@@ -163,6 +163,10 @@ impl AptosVMImpl {
         self.move_vm.mark_loader_cache_as_invalid();
     }
 
+    pub(crate) fn get_and_clear_module_cache_hits(&self) -> BTreeSet<ModuleId> {
+        self.move_vm.get_and_clear_module_cache_hits()
+    }
+
     /// Provides access to some internal APIs of the VM.
     pub fn internals(&self) -> AptosVMInternals {
         AptosVMInternals(self)
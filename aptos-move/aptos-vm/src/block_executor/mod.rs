@@ -11,10 +11,12 @@ use crate::{
         BLOCK_EXECUTOR_CONCURRENCY, BLOCK_EXECUTOR_EXECUTE_BLOCK_SECONDS,
         BLOCK_EXECUTOR_SIGNATURE_VERIFICATION_SECONDS,
     },
+    speculative_execution::SpeculativeExecutor,
     AptosVM,
 };
 use aptos_aggregator::delta_change_set::DeltaOp;
 use aptos_block_executor::{
+    effective_gas::ConflictAwareGasLimit,
     errors::Error,
     executor::BlockExecutor,
     task::{
@@ -24,10 +26,11 @@ use aptos_block_executor::{
     txn_commit_hook::TransactionCommitHook,
 };
 use aptos_infallible::Mutex;
-use aptos_state_view::{StateView, StateViewId};
+use aptos_state_view::{StateView, StateViewId, TStateView};
 use aptos_types::{
     executable::ExecutableTestType,
     fee_statement::FeeStatement,
+    on_chain_config::BlockGasLimitType,
     state_store::state_key::StateKey,
     transaction::{Transaction, TransactionOutput, TransactionStatus},
     write_set::WriteOp,
@@ -127,6 +130,18 @@ impl BlockExecutorTransactionOutput for AptosTransactionOutput {
         );
     }
 
+    fn materialize_deltas(&self, view: &impl TStateView<Key = StateKey>) {
+        let mut vm_output = self.vm_output.lock();
+        let output = vm_output
+            .take()
+            .expect("Output must be set to materialize deltas");
+        *vm_output = Some(
+            output
+                .try_materialize(view)
+                .expect("Delta materialization failed"),
+        );
+    }
+
     /// Return the fee statement of the transaction.
     /// Should never be called after vm_output is consumed.
     fn fee_statement(&self) -> FeeStatement {
@@ -158,8 +173,33 @@ impl BlockAptosVM {
         transactions: Vec<Transaction>,
         state_view: &S,
         concurrency_level: usize,
-        maybe_block_gas_limit: Option<u64>,
+        block_gas_limit_type: BlockGasLimitType,
         transaction_commit_listener: Option<L>,
+    ) -> Result<Vec<TransactionOutput>, VMStatus> {
+        Self::execute_block_with_speculative_executor(
+            executor_thread_pool,
+            transactions,
+            state_view,
+            concurrency_level,
+            block_gas_limit_type,
+            transaction_commit_listener,
+            None,
+        )
+    }
+
+    /// Executes the block like `execute_block`, but reuses the outputs of the transactions that
+    /// were pre-executed by the speculative executor, if everything they read is unchanged.
+    pub fn execute_block_with_speculative_executor<
+        S: StateView + Sync,
+        L: TransactionCommitHook<Output = AptosTransactionOutput>,
+    >(
+        executor_thread_pool: Arc<ThreadPool>,
+        transactions: Vec<Transaction>,
+        state_view: &S,
+        concurrency_level: usize,
+        block_gas_limit_type: BlockGasLimitType,
+        transaction_commit_listener: Option<L>,
+        speculative_executor: Option<&SpeculativeExecutor>,
     ) -> Result<Vec<TransactionOutput>, VMStatus> {
         let _timer = BLOCK_EXECUTOR_EXECUTE_BLOCK_SECONDS.start_timer();
         // Verify the signatures of all the transactions in parallel.
@@ -189,11 +229,25 @@ impl BlockAptosVM {
         >::new(
             concurrency_level,
             executor_thread_pool,
-            maybe_block_gas_limit,
+            block_gas_limit_type.block_gas_limit(),
             transaction_commit_listener,
         );
+        let executor = match block_gas_limit_type {
+            BlockGasLimitType::ConflictAwareV1 {
+                effective_block_gas_limit,
+                parallelism,
+            } => executor.with_conflict_aware_gas_limit(ConflictAwareGasLimit {
+                effective_block_gas_limit,
+                parallelism,
+            }),
+            _ => executor,
+        };
 
-        let ret = executor.execute_block(state_view, signature_verified_block, state_view);
+        let ret = executor.execute_block(
+            (state_view, speculative_executor),
+            signature_verified_block,
+            state_view,
+        );
         match ret {
             Ok(outputs) => {
                 let output_vec: Vec<TransactionOutput> = outputs
@@ -212,9 +266,6 @@ impl BlockAptosVM {
 
                 Ok(output_vec)
             },
-            Err(Error::ModulePathReadWrite) => {
-                unreachable!("[Execution]: Must be handled by sequential fallback")
-            },
             Err(Error::UserError(err)) => Err(err),
         }
     }
@@ -6,11 +6,13 @@ use crate::{
     adapter_common::{PreprocessedTransaction, VMAdapter},
     aptos_vm::AptosVM,
     block_executor::AptosTransactionOutput,
+    speculative_execution::SpeculativeExecutor,
 };
 use aptos_block_executor::task::{ExecutionStatus, ExecutorTask};
 use aptos_logger::{enabled, Level};
 use aptos_mvhashmap::types::TxnIndex;
 use aptos_state_view::StateView;
+use aptos_types::{access_path::AccessPath, state_store::state_key::StateKey};
 use aptos_vm_logging::{log_schema::AdapterLogSchema, prelude::*};
 use move_core_types::{
     ident_str,
@@ -21,16 +23,17 @@ use move_core_types::{
 pub(crate) struct AptosExecutorTask<'a, S> {
     vm: AptosVM,
     base_view: &'a S,
+    speculative_executor: Option<&'a SpeculativeExecutor>,
 }
 
 impl<'a, S: 'a + StateView + Sync> ExecutorTask for AptosExecutorTask<'a, S> {
-    type Argument = &'a S;
+    type Argument = (&'a S, Option<&'a SpeculativeExecutor>);
     type Error = VMStatus;
     type Output = AptosTransactionOutput;
     type Txn = PreprocessedTransaction;
 
-    fn init(argument: &'a S) -> Self {
-        let vm = AptosVM::new(argument);
+    fn init((base_view, speculative_executor): Self::Argument) -> Self {
+        let vm = AptosVM::new(base_view);
 
         // Loading `0x1::account` and its transitive dependency into the code cache.
         //
@@ -42,12 +45,15 @@ impl<'a, S: 'a + StateView + Sync> ExecutorTask for AptosExecutorTask<'a, S> {
 
         let _ = vm.load_module(
             &ModuleId::new(CORE_CODE_ADDRESS, ident_str!("account").to_owned()),
-            &vm.as_move_resolver(argument),
+            &vm.as_move_resolver(base_view),
         );
+        // Modules loaded during the warm up are not used by any transaction yet.
+        let _ = vm.0.get_and_clear_module_cache_hits();
 
         Self {
             vm,
-            base_view: argument,
+            base_view,
+            speculative_executor,
         }
     }
 
@@ -63,10 +69,24 @@ impl<'a, S: 'a + StateView + Sync> ExecutorTask for AptosExecutorTask<'a, S> {
     ) -> ExecutionStatus<AptosTransactionOutput, VMStatus> {
         let log_context = AdapterLogSchema::new(self.base_view.id(), txn_idx as usize);
 
-        match self
-            .vm
-            .execute_single_transaction(txn, &self.vm.as_move_resolver(view), &log_context)
-        {
+        // Reuse the output of a speculative pre-execution if its reads are still valid, the reads
+        // are done through `view` so the reuse is validated like an execution would be.
+        let speculative_result = match (txn, self.speculative_executor) {
+            (PreprocessedTransaction::UserTransaction(signed_txn), Some(speculative_executor)) => {
+                speculative_executor.reusable_output(signed_txn, view).map(
+                    |(vm_status, vm_output)| {
+                        Ok((vm_status, vm_output, Some(signed_txn.sender().to_string())))
+                    },
+                )
+            },
+            _ => None,
+        };
+        let result = speculative_result.unwrap_or_else(|| {
+            self.vm
+                .execute_single_transaction(txn, &self.vm.as_move_resolver(view), &log_context)
+        });
+
+        match result {
             Ok((vm_status, mut vm_output, sender)) => {
                 if materialize_deltas {
                     // TODO: Integrate delta application failure.
@@ -105,4 +125,18 @@ impl<'a, S: 'a + StateView + Sync> ExecutorTask for AptosExecutorTask<'a, S> {
             Err(err) => ExecutionStatus::Abort(err),
         }
     }
+
+    fn flush_code_cache(&self) {
+        // The loader cache is flushed when the next session is created.
+        self.vm.0.mark_loader_cache_as_invalid();
+    }
+
+    fn take_code_cache_hits(&self) -> Vec<StateKey> {
+        self.vm
+            .0
+            .get_and_clear_module_cache_hits()
+            .iter()
+            .map(|module_id| StateKey::access_path(AccessPath::from(module_id)))
+            .collect()
+    }
 }
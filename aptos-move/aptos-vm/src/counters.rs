@@ -137,3 +137,12 @@ pub static TXN_GAS_USAGE: Lazy<Histogram> = Lazy::new(|| {
     )
     .unwrap()
 });
+
+pub static SPECULATIVE_EXECUTION_TRANSACTIONS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_vm_speculative_execution_transactions",
+        "Number of user transactions by the outcome of their speculative pre-execution",
+        &["status"]
+    )
+    .unwrap()
+});
@@ -117,6 +117,7 @@ mod errors;
 pub mod move_vm_ext;
 pub mod natives;
 pub mod sharded_block_executor;
+pub mod speculative_execution;
 pub mod system_module_names;
 pub mod transaction_metadata;
 mod transaction_validation;
@@ -127,6 +128,7 @@ use crate::sharded_block_executor::{executor_client::ExecutorClient, ShardedBloc
 use aptos_state_view::StateView;
 use aptos_types::{
     block_executor::partitioner::SubBlocksForShard,
+    on_chain_config::BlockGasLimitType,
     transaction::{
         analyzed_transaction::AnalyzedTransaction, SignedTransaction, Transaction,
         TransactionOutput, VMValidatorResult,
@@ -157,7 +159,7 @@ pub trait VMExecutor: Send + Sync {
     fn execute_block(
         transactions: Vec<Transaction>,
         state_view: &(impl StateView + Sync),
-        maybe_block_gas_limit: Option<u64>,
+        block_gas_limit_type: BlockGasLimitType,
     ) -> Result<Vec<TransactionOutput>, VMStatus>;
 
     /// Executes a block of transactions using a sharded block executor and returns the results.
@@ -165,7 +167,7 @@ pub trait VMExecutor: Send + Sync {
         sharded_block_executor: &ShardedBlockExecutor<S, E>,
         block: Vec<SubBlocksForShard<AnalyzedTransaction>>,
         state_view: Arc<S>,
-        maybe_block_gas_limit: Option<u64>,
+        block_gas_limit_type: BlockGasLimitType,
     ) -> Result<Vec<TransactionOutput>, VMStatus>;
 }
 
@@ -2,7 +2,10 @@
 // Parts of the project are originally copyright © Meta Platforms, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use aptos_metrics_core::{register_histogram_vec, register_int_gauge, HistogramVec, IntGauge};
+use aptos_metrics_core::{
+    register_histogram_vec, register_int_counter_vec, register_int_gauge, HistogramVec,
+    IntCounterVec, IntGauge,
+};
 use once_cell::sync::Lazy;
 
 pub static NUM_EXECUTOR_SHARDS: Lazy<IntGauge> = Lazy::new(|| {
@@ -21,3 +24,12 @@ pub static SHARDED_BLOCK_EXECUTION_SECONDS: Lazy<HistogramVec> = Lazy::new(|| {
     )
     .unwrap()
 });
+
+pub static SHARDED_EXECUTOR_CROSS_SHARD_MESSAGES: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "sharded_executor_cross_shard_messages",
+        "Number of cross-shard messages sent by a shard in sharded execution",
+        &["shard_id"]
+    )
+    .unwrap()
+});
@@ -3,6 +3,7 @@
 use crate::{
     block_executor::AptosTransactionOutput,
     sharded_block_executor::{
+        counters::SHARDED_EXECUTOR_CROSS_SHARD_MESSAGES,
         cross_shard_state_view::CrossShardStateView,
         messages::{CrossShardMsg, CrossShardMsg::RemoteTxnWriteMsg, RemoteTxnWrite},
     },
@@ -19,7 +20,10 @@ use aptos_types::{
 };
 use std::{
     collections::{HashMap, HashSet},
-    sync::Arc,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
 };
 
 pub struct CrossShardCommitReceiver {}
@@ -58,6 +62,8 @@ pub struct CrossShardCommitSender {
     // The offset of the first transaction in the sub-block. This is used to convert the local index
     // in parallel execution to the global index.
     index_offset: TxnIndex,
+    // The number of cross-shard messages sent, shared with the executor shard.
+    num_messages_sent: Arc<AtomicU64>,
 }
 
 impl CrossShardCommitSender {
@@ -65,6 +71,7 @@ impl CrossShardCommitSender {
         shard_id: ShardId,
         cross_shard_client: Arc<dyn CrossShardClient>,
         sub_block: &SubBlock<AnalyzedTransaction>,
+        num_messages_sent: Arc<AtomicU64>,
     ) -> Self {
         let mut dependent_edges = HashMap::new();
         let mut num_dependent_edges = 0;
@@ -99,6 +106,7 @@ impl CrossShardCommitSender {
             cross_shard_client,
             dependent_edges,
             index_offset: sub_block.start_index as TxnIndex,
+            num_messages_sent,
         }
     }
 
@@ -111,9 +119,13 @@ impl CrossShardCommitSender {
         let output = txn_output.committed_output();
         let write_set = output.write_set();
 
+        let num_messages =
+            SHARDED_EXECUTOR_CROSS_SHARD_MESSAGES.with_label_values(&[&self.shard_id.to_string()]);
         for (state_key, write_op) in write_set.iter() {
             if let Some(dependent_shard_ids) = edges.get(state_key) {
                 for (dependent_shard_id, round_id) in dependent_shard_ids.iter() {
+                    num_messages.inc();
+                    self.num_messages_sent.fetch_add(1, Ordering::Relaxed);
                     trace!("Sending remote update for success for shard id {:?} and txn_idx: {:?}, state_key: {:?}, dependent shard id: {:?}", self.shard_id, txn_idx, state_key, dependent_shard_id);
                     let message = RemoteTxnWriteMsg(RemoteTxnWrite::new(
                         state_key.clone(),
@@ -1,10 +1,11 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::sharded_block_executor::ShardExecutionResult;
 use aptos_state_view::StateView;
 use aptos_types::{
-    block_executor::partitioner::SubBlocksForShard,
-    transaction::{analyzed_transaction::AnalyzedTransaction, TransactionOutput},
+    block_executor::partitioner::SubBlocksForShard, on_chain_config::BlockGasLimitType,
+    transaction::analyzed_transaction::AnalyzedTransaction,
 };
 use move_core_types::vm_status::VMStatus;
 use std::sync::Arc;
@@ -19,10 +20,10 @@ pub trait ExecutorClient<S: StateView + Sync + Send + 'static>: Send + Sync {
         state_view: Arc<S>,
         block: Vec<SubBlocksForShard<AnalyzedTransaction>>,
         concurrency_level_per_shard: usize,
-        maybe_block_gas_limit: Option<u64>,
+        block_gas_limit_type: BlockGasLimitType,
     );
 
     // Blocking call that waits for the execution results from the executor shards. It returns the execution results
     // from each shard and in the sub-block order.
-    fn get_execution_result(&self) -> Result<Vec<Vec<Vec<TransactionOutput>>>, VMStatus>;
+    fn get_execution_result(&self) -> Result<Vec<ShardExecutionResult>, VMStatus>;
 }
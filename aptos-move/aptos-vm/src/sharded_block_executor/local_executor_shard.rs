@@ -3,14 +3,15 @@
 use crate::sharded_block_executor::{
     coordinator_client::CoordinatorClient, cross_shard_client::CrossShardClient,
     executor_client::ExecutorClient, messages::CrossShardMsg,
-    sharded_executor_service::ShardedExecutorService, ExecutorShardCommand,
+    sharded_executor_service::ShardedExecutorService, ExecutorShardCommand, ShardExecutionResult,
 };
 use aptos_block_partitioner::sharded_block_partitioner::MAX_ALLOWED_PARTITIONING_ROUNDS;
 use aptos_logger::trace;
 use aptos_state_view::StateView;
 use aptos_types::{
     block_executor::partitioner::{RoundId, ShardId, SubBlocksForShard},
-    transaction::{analyzed_transaction::AnalyzedTransaction, TransactionOutput},
+    on_chain_config::BlockGasLimitType,
+    transaction::analyzed_transaction::AnalyzedTransaction,
 };
 use crossbeam_channel::{unbounded, Receiver, Sender};
 use move_core_types::vm_status::VMStatus;
@@ -29,7 +30,7 @@ impl<S: StateView + Sync + Send + 'static> LocalExecutorService<S> {
         num_shards: usize,
         num_threads: usize,
         command_rx: Receiver<ExecutorShardCommand<S>>,
-        result_tx: Sender<Result<Vec<Vec<TransactionOutput>>, VMStatus>>,
+        result_tx: Sender<Result<ShardExecutionResult, VMStatus>>,
         cross_shard_client: LocalCrossShardClient,
     ) -> Self {
         let coordinator_client = Arc::new(LocalCoordinatorClient::new(command_rx, result_tx));
@@ -61,8 +62,8 @@ impl<S: StateView + Sync + Send + 'static> LocalExecutorService<S> {
             Vec<Receiver<ExecutorShardCommand<S>>>,
         ) = (0..num_shards).map(|_| unbounded()).unzip();
         let (result_txs, result_rxs): (
-            Vec<Sender<Result<Vec<Vec<TransactionOutput>>, VMStatus>>>,
-            Vec<Receiver<Result<Vec<Vec<TransactionOutput>>, VMStatus>>>,
+            Vec<Sender<Result<ShardExecutionResult, VMStatus>>>,
+            Vec<Receiver<Result<ShardExecutionResult, VMStatus>>>,
         ) = (0..num_shards).map(|_| unbounded()).unzip();
         // We need to create channels for each shard and each round. This is needed because individual
         // shards might send cross shard messages to other shards that will be consumed in different rounds.
@@ -103,7 +104,7 @@ pub struct LocalExecutorClient<S: StateView + Sync + Send + 'static> {
     // Channels to send execute block commands to the executor shards.
     command_txs: Vec<Sender<ExecutorShardCommand<S>>>,
     // Channels to receive execution results from the executor shards.
-    result_rxs: Vec<Receiver<Result<Vec<Vec<TransactionOutput>>, VMStatus>>>,
+    result_rxs: Vec<Receiver<Result<ShardExecutionResult, VMStatus>>>,
 
     executor_services: Vec<LocalExecutorService<S>>,
 }
@@ -111,7 +112,7 @@ pub struct LocalExecutorClient<S: StateView + Sync + Send + 'static> {
 impl<S: StateView + Sync + Send + 'static> LocalExecutorClient<S> {
     pub fn new(
         command_tx: Vec<Sender<ExecutorShardCommand<S>>>,
-        result_rx: Vec<Receiver<Result<Vec<Vec<TransactionOutput>>, VMStatus>>>,
+        result_rx: Vec<Receiver<Result<ShardExecutionResult, VMStatus>>>,
         executor_shards: Vec<LocalExecutorService<S>>,
     ) -> Self {
         Self {
@@ -132,7 +133,7 @@ impl<S: StateView + Sync + Send + 'static> ExecutorClient<S> for LocalExecutorCl
         state_view: Arc<S>,
         block: Vec<SubBlocksForShard<AnalyzedTransaction>>,
         concurrency_level_per_shard: usize,
-        maybe_block_gas_limit: Option<u64>,
+        block_gas_limit_type: BlockGasLimitType,
     ) {
         assert_eq!(block.len(), self.num_shards());
         for (i, sub_blocks_for_shard) in block.into_iter().enumerate() {
@@ -141,13 +142,13 @@ impl<S: StateView + Sync + Send + 'static> ExecutorClient<S> for LocalExecutorCl
                     state_view.clone(),
                     sub_blocks_for_shard,
                     concurrency_level_per_shard,
-                    maybe_block_gas_limit,
+                    block_gas_limit_type,
                 ))
                 .unwrap();
         }
     }
 
-    fn get_execution_result(&self) -> Result<Vec<Vec<Vec<TransactionOutput>>>, VMStatus> {
+    fn get_execution_result(&self) -> Result<Vec<ShardExecutionResult>, VMStatus> {
         trace!("LocalExecutorClient Waiting for results");
         let mut results = vec![];
         for (i, rx) in self.result_rxs.iter().enumerate() {
@@ -176,13 +177,13 @@ impl<S: StateView + Sync + Send + 'static> Drop for LocalExecutorClient<S> {
 pub struct LocalCoordinatorClient<S> {
     command_rx: Receiver<ExecutorShardCommand<S>>,
     // Channel to send execution results to the coordinator.
-    result_tx: Sender<Result<Vec<Vec<TransactionOutput>>, VMStatus>>,
+    result_tx: Sender<Result<ShardExecutionResult, VMStatus>>,
 }
 
 impl<S> LocalCoordinatorClient<S> {
     pub fn new(
         command_rx: Receiver<ExecutorShardCommand<S>>,
-        result_tx: Sender<Result<Vec<Vec<TransactionOutput>>, VMStatus>>,
+        result_tx: Sender<Result<ShardExecutionResult, VMStatus>>,
     ) -> Self {
         Self {
             command_rx,
@@ -196,7 +197,7 @@ impl<S: StateView + Sync + Send + 'static> CoordinatorClient<S> for LocalCoordin
         self.command_rx.recv().unwrap()
     }
 
-    fn send_execution_result(&self, result: Result<Vec<Vec<TransactionOutput>>, VMStatus>) {
+    fn send_execution_result(&self, result: Result<ShardExecutionResult, VMStatus>) {
         self.result_tx.send(result).unwrap()
     }
 }
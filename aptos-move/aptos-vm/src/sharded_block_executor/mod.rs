@@ -5,13 +5,17 @@
 use crate::sharded_block_executor::{
     counters::NUM_EXECUTOR_SHARDS, executor_client::ExecutorClient,
 };
+use aptos_block_partitioner::sharded_block_partitioner::ShardExecutionStats;
+use aptos_infallible::Mutex;
 use aptos_logger::{info, trace};
 use aptos_state_view::StateView;
 use aptos_types::{
     block_executor::partitioner::SubBlocksForShard,
+    on_chain_config::BlockGasLimitType,
     transaction::{analyzed_transaction::AnalyzedTransaction, TransactionOutput},
 };
 use move_core_types::vm_status::VMStatus;
+use serde::{Deserialize, Serialize};
 use std::{marker::PhantomData, sync::Arc};
 
 pub mod coordinator_client;
@@ -30,6 +34,8 @@ mod tests;
 /// Coordinator for sharded block executors that manages multiple shards and aggregates the results.
 pub struct ShardedBlockExecutor<S: StateView + Sync + Send + 'static, C: ExecutorClient<S>> {
     executor_client: C,
+    /// Cumulative statistics of the blocks executed so far
+    execution_stats: Arc<Mutex<ShardExecutionStats>>,
     phantom: PhantomData<S>,
 }
 
@@ -38,19 +44,35 @@ pub enum ExecutorShardCommand<S> {
         Arc<S>,
         SubBlocksForShard<AnalyzedTransaction>,
         usize,
-        Option<u64>,
+        BlockGasLimitType,
     ),
     Stop,
 }
 
+/// The result of the sub-blocks of a block executed by a shard.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ShardExecutionResult {
+    /// The outputs of the sub-blocks, in round order.
+    pub outputs: Vec<Vec<TransactionOutput>>,
+    /// Time spent executing the sub-blocks.
+    pub execution_seconds: f64,
+    /// Number of cross-shard messages sent while executing the sub-blocks.
+    pub cross_shard_messages: u64,
+}
+
 impl<S: StateView + Sync + Send + 'static, C: ExecutorClient<S>> ShardedBlockExecutor<S, C> {
     pub fn new(executor_client: C) -> Self {
         info!(
             "Creating a new ShardedBlockExecutor with {} shards",
             executor_client.num_shards()
         );
+        let num_shards = executor_client.num_shards();
         Self {
             executor_client,
+            execution_stats: Arc::new(Mutex::new(ShardExecutionStats {
+                execution_seconds: vec![0.0; num_shards],
+                cross_shard_messages: vec![0; num_shards],
+            })),
             phantom: PhantomData,
         }
     }
@@ -59,6 +81,14 @@ impl<S: StateView + Sync + Send + 'static, C: ExecutorClient<S>> ShardedBlockExe
         self.executor_client.num_shards()
     }
 
+    /// Cumulative execution statistics of the blocks executed by this executor, updated as blocks
+    /// are executed. They are meant to be fed back into an adaptive block partitioner (see
+    /// `ShardedBlockPartitioner::record_execution_stats`), which may run concurrently with the
+    /// execution.
+    pub fn execution_stats(&self) -> Arc<Mutex<ShardExecutionStats>> {
+        self.execution_stats.clone()
+    }
+
     /// Execute a block of transactions in parallel by splitting the block into num_remote_executors partitions and
     /// dispatching each partition to a remote executor shard.
     pub fn execute_block(
@@ -66,7 +96,7 @@ impl<S: StateView + Sync + Send + 'static, C: ExecutorClient<S>> ShardedBlockExe
         state_view: Arc<S>,
         block: Vec<SubBlocksForShard<AnalyzedTransaction>>,
         concurrency_level_per_shard: usize,
-        maybe_block_gas_limit: Option<u64>,
+        block_gas_limit_type: BlockGasLimitType,
     ) -> Result<Vec<TransactionOutput>, VMStatus> {
         let num_executor_shards = self.executor_client.num_shards();
         NUM_EXECUTOR_SHARDS.set(num_executor_shards as i64);
@@ -80,16 +110,23 @@ impl<S: StateView + Sync + Send + 'static, C: ExecutorClient<S>> ShardedBlockExe
             state_view,
             block,
             concurrency_level_per_shard,
-            maybe_block_gas_limit,
+            block_gas_limit_type,
         );
         // wait for all remote executors to send the result back and append them in order by shard id
         let results = self.executor_client.get_execution_result()?;
         trace!("ShardedBlockExecutor Received all results");
-        let num_rounds = results[0].len();
+        {
+            let mut execution_stats = self.execution_stats.lock();
+            for (shard_id, result) in results.iter().enumerate() {
+                execution_stats.execution_seconds[shard_id] += result.execution_seconds;
+                execution_stats.cross_shard_messages[shard_id] += result.cross_shard_messages;
+            }
+        }
+        let num_rounds = results[0].outputs.len();
         let mut aggreate_results = vec![];
         let mut ordered_results = vec![vec![]; num_executor_shards * num_rounds];
         for (shard_id, results_from_shard) in results.into_iter().enumerate() {
-            for (round, result) in results_from_shard.into_iter().enumerate() {
+            for (round, result) in results_from_shard.outputs.into_iter().enumerate() {
                 ordered_results[round * num_executor_shards + shard_id] = result;
             }
         }
@@ -8,18 +8,26 @@ use crate::{
         cross_shard_client::{CrossShardClient, CrossShardCommitReceiver, CrossShardCommitSender},
         cross_shard_state_view::CrossShardStateView,
         messages::CrossShardMsg,
-        ExecutorShardCommand,
+        ExecutorShardCommand, ShardExecutionResult,
     },
 };
 use aptos_logger::{info, trace};
 use aptos_state_view::StateView;
 use aptos_types::{
     block_executor::partitioner::{ShardId, SubBlock, SubBlocksForShard},
+    on_chain_config::BlockGasLimitType,
     transaction::{analyzed_transaction::AnalyzedTransaction, TransactionOutput},
 };
 use futures::{channel::oneshot, executor::block_on};
 use move_core_types::vm_status::VMStatus;
-use std::{collections::HashSet, sync::Arc};
+use std::{
+    collections::HashSet,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::Instant,
+};
 
 pub struct ShardedExecutorService<S: StateView + Sync + Send + 'static> {
     shard_id: ShardId,
@@ -76,15 +84,20 @@ impl<S: StateView + Sync + Send + 'static> ShardedExecutorService<S> {
         round: usize,
         state_view: &S,
         concurrency_level: usize,
-        maybe_block_gas_limit: Option<u64>,
+        block_gas_limit_type: BlockGasLimitType,
+        num_cross_shard_messages: Arc<AtomicU64>,
     ) -> Result<Vec<TransactionOutput>, VMStatus> {
         trace!(
             "executing sub block for shard {} and round {}",
             self.shard_id,
             round
         );
-        let cross_shard_commit_sender =
-            CrossShardCommitSender::new(self.shard_id, self.cross_shard_client.clone(), &sub_block);
+        let cross_shard_commit_sender = CrossShardCommitSender::new(
+            self.shard_id,
+            self.cross_shard_client.clone(),
+            &sub_block,
+            num_cross_shard_messages,
+        );
 
         let (callback, callback_receiver) = oneshot::channel();
 
@@ -111,7 +124,7 @@ impl<S: StateView + Sync + Send + 'static> ShardedExecutorService<S> {
                         .collect(),
                     cross_shard_state_view.as_ref(),
                     concurrency_level,
-                    maybe_block_gas_limit,
+                    block_gas_limit_type,
                     Some(cross_shard_commit_sender),
                 );
                 trace!(
@@ -136,8 +149,10 @@ impl<S: StateView + Sync + Send + 'static> ShardedExecutorService<S> {
         transactions: SubBlocksForShard<AnalyzedTransaction>,
         state_view: &S,
         concurrency_level: usize,
-        maybe_block_gas_limit: Option<u64>,
-    ) -> Result<Vec<Vec<TransactionOutput>>, VMStatus> {
+        block_gas_limit_type: BlockGasLimitType,
+    ) -> Result<ShardExecutionResult, VMStatus> {
+        let start_time = Instant::now();
+        let num_cross_shard_messages = Arc::new(AtomicU64::new(0));
         let mut result = vec![];
         for (round, sub_block) in transactions.into_sub_blocks().into_iter().enumerate() {
             let _timer = SHARDED_BLOCK_EXECUTION_SECONDS
@@ -154,7 +169,8 @@ impl<S: StateView + Sync + Send + 'static> ShardedExecutorService<S> {
                 round,
                 state_view,
                 concurrency_level,
-                maybe_block_gas_limit,
+                block_gas_limit_type,
+                num_cross_shard_messages.clone(),
             )?);
             trace!(
                 "Finished executing sub block for shard {} and round {}",
@@ -162,7 +178,11 @@ impl<S: StateView + Sync + Send + 'static> ShardedExecutorService<S> {
                 round
             );
         }
-        Ok(result)
+        Ok(ShardExecutionResult {
+            outputs: result,
+            execution_seconds: start_time.elapsed().as_secs_f64(),
+            cross_shard_messages: num_cross_shard_messages.load(Ordering::Relaxed),
+        })
     }
 
     pub fn start(&self) {
@@ -178,7 +198,7 @@ impl<S: StateView + Sync + Send + 'static> ShardedExecutorService<S> {
                     state_view,
                     transactions,
                     concurrency_level_per_shard,
-                    maybe_block_gas_limit,
+                    block_gas_limit_type,
                 ) => {
                     trace!(
                         "Shard {} received ExecuteBlock command of block size {} ",
@@ -189,7 +209,7 @@ impl<S: StateView + Sync + Send + 'static> ShardedExecutorService<S> {
                         transactions,
                         state_view.as_ref(),
                         concurrency_level_per_shard,
-                        maybe_block_gas_limit,
+                        block_gas_limit_type,
                     );
                     drop(state_view);
                     self.coordinator_client.send_execution_result(ret);
@@ -12,8 +12,12 @@ use aptos_language_e2e_tests::{
 };
 use aptos_types::{
     block_executor::partitioner::SubBlocksForShard,
+    on_chain_config::BlockGasLimitType,
     state_store::state_key::StateKeyInner,
-    transaction::{analyzed_transaction::AnalyzedTransaction, Transaction, TransactionOutput},
+    transaction::{
+        analyzed_transaction::AnalyzedTransaction, ExecutionStatus, Transaction, TransactionOutput,
+        TransactionStatus,
+    },
 };
 use move_core_types::account_address::AccountAddress;
 use rand::{rngs::OsRng, Rng};
@@ -119,13 +123,60 @@ pub fn test_sharded_block_executor_no_conflict<E: ExecutorClient<FakeDataStore>>
             Arc::new(executor.data_store().clone()),
             partitioned_txns,
             2,
-            None,
+            BlockGasLimitType::NoLimit,
         )
         .unwrap();
     let unsharded_txn_output = AptosVM::execute_block(
         transactions.into_iter().map(|t| t.into_txn()).collect(),
         &executor.data_store(),
-        None,
+        BlockGasLimitType::NoLimit,
+    )
+    .unwrap();
+    compare_txn_outputs(unsharded_txn_output, sharded_txn_output);
+}
+
+pub fn sharded_block_executor_with_conflict_aware_gas_limit<E: ExecutorClient<FakeDataStore>>(
+    sharded_block_executor: ShardedBlockExecutor<FakeDataStore, E>,
+) {
+    let num_txns = 100;
+    let mut executor = FakeExecutor::from_head_genesis();
+    let mut transactions = Vec::new();
+    for _ in 0..num_txns {
+        transactions.push(generate_non_conflicting_p2p(&mut executor).0)
+    }
+    let partitioner = ShardedBlockPartitioner::new(sharded_block_executor.num_shards());
+    let partitioned_txns = partitioner.partition(transactions, 2, 0.9);
+    let execution_ordered_txns = SubBlocksForShard::flatten(partitioned_txns.clone())
+        .into_iter()
+        .map(|t| t.into_txn())
+        .collect();
+
+    // The limit only leaves room for the first transaction of every sub-block
+    let block_gas_limit_type = BlockGasLimitType::ConflictAwareV1 {
+        effective_block_gas_limit: 1,
+        parallelism: 1,
+    };
+    let sharded_txn_output = sharded_block_executor
+        .execute_block(
+            Arc::new(executor.data_store().clone()),
+            partitioned_txns,
+            2,
+            block_gas_limit_type,
+        )
+        .unwrap();
+    assert_eq!(
+        sharded_txn_output.first().unwrap().status(),
+        &TransactionStatus::Keep(ExecutionStatus::Success)
+    );
+    assert_eq!(
+        sharded_txn_output.last().unwrap().status(),
+        &TransactionStatus::Retry
+    );
+
+    let unsharded_txn_output = AptosVM::execute_block(
+        execution_ordered_txns,
+        &executor.data_store(),
+        block_gas_limit_type,
     )
     .unwrap();
     compare_txn_outputs(unsharded_txn_output, sharded_txn_output);
@@ -170,12 +221,16 @@ pub fn sharded_block_executor_with_conflict<E: ExecutorClient<FakeDataStore>>(
             Arc::new(executor.data_store().clone()),
             partitioned_txns,
             concurrency,
-            None,
+            BlockGasLimitType::NoLimit,
         )
         .unwrap();
 
-    let unsharded_txn_output =
-        AptosVM::execute_block(execution_ordered_txns, &executor.data_store(), None).unwrap();
+    let unsharded_txn_output = AptosVM::execute_block(
+        execution_ordered_txns,
+        &executor.data_store(),
+        BlockGasLimitType::NoLimit,
+    )
+    .unwrap();
     compare_txn_outputs(unsharded_txn_output, sharded_txn_output);
 }
 
@@ -222,11 +277,15 @@ pub fn sharded_block_executor_with_random_transfers<E: ExecutorClient<FakeDataSt
             Arc::new(executor.data_store().clone()),
             partitioned_txns,
             concurrency,
-            None,
+            BlockGasLimitType::NoLimit,
         )
         .unwrap();
 
-    let unsharded_txn_output =
-        AptosVM::execute_block(execution_ordered_txns, &executor.data_store(), None).unwrap();
+    let unsharded_txn_output = AptosVM::execute_block(
+        execution_ordered_txns,
+        &executor.data_store(),
+        BlockGasLimitType::NoLimit,
+    )
+    .unwrap();
     compare_txn_outputs(unsharded_txn_output, sharded_txn_output);
 }
@@ -14,6 +14,15 @@ fn test_sharded_block_executor_no_conflict() {
     test_utils::test_sharded_block_executor_no_conflict(sharded_block_executor);
 }
 
+#[test]
+fn test_sharded_block_executor_conflict_aware_gas_limit() {
+    // The limit applies to every sub-block on its own, so a single shard executes the block
+    // like the unsharded executor
+    let client = LocalExecutorService::setup_local_executor_shards(1, Some(2));
+    let sharded_block_executor = ShardedBlockExecutor::new(client);
+    test_utils::sharded_block_executor_with_conflict_aware_gas_limit(sharded_block_executor);
+}
+
 #[test]
 // Sharded execution with cross shard conflict doesn't work for now because we don't have
 // cross round dependency tracking yet.
@@ -0,0 +1,523 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Speculative pre-execution of the user transactions of blocks that are likely to be executed
+//! soon, e.g. blocks that were proposed but are not ordered yet.
+//!
+//! Every transaction is executed on its own against the state the block will (likely) be executed
+//! on, and its output is cached together with everything it read. When the block is executed for
+//! real, the block executor reuses a cached output instead of running the VM if all the recorded
+//! reads still return the same values. These reads go through the view of the block executor, so
+//! Block-STM validates a reused output like any other and re-executes the transaction if an
+//! earlier transaction of the block wrote one of the locations.
+
+use crate::{
+    adapter_common::{preprocess_transaction, PreprocessedTransaction, VMAdapter},
+    counters::SPECULATIVE_EXECUTION_TRANSACTIONS,
+    AptosVM,
+};
+use anyhow::Result;
+use aptos_infallible::Mutex;
+use aptos_logger::{error, info};
+use aptos_state_view::{StateView, StateViewId, TStateView};
+use aptos_types::{
+    access_path::AccessPath,
+    account_address::AccountAddress,
+    state_store::{
+        state_key::StateKey, state_storage_usage::StateStorageUsage, state_value::StateValue,
+    },
+    transaction::{SignedTransaction, Transaction, TransactionStatus},
+    write_set::{TransactionWrite, WriteSet},
+};
+use aptos_vm_logging::log_schema::AdapterLogSchema;
+use aptos_vm_types::output::VMOutput;
+use move_core_types::vm_status::VMStatus;
+use rayon::prelude::*;
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet, VecDeque},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+
+struct SpeculativeResult {
+    txn: SignedTransaction,
+    // Everything the execution read, including the configs read when creating the VM.
+    reads: Vec<(StateKey, Option<StateValue>)>,
+    vm_status: VMStatus,
+    output: VMOutput,
+}
+
+struct SpeculativeExecutionCache {
+    capacity: usize,
+    results: HashMap<(AccountAddress, u64), Arc<SpeculativeResult>>,
+    // Insertion order, to evict the oldest results first.
+    order: VecDeque<(AccountAddress, u64)>,
+}
+
+impl SpeculativeExecutionCache {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            results: HashMap::new(),
+            order: VecDeque::new(),
+        }
+    }
+
+    fn get(&self, txn: &SignedTransaction) -> Option<Arc<SpeculativeResult>> {
+        self.results
+            .get(&(txn.sender(), txn.sequence_number()))
+            .filter(|result| &result.txn == txn)
+            .cloned()
+    }
+
+    fn insert(&mut self, result: SpeculativeResult) {
+        let key = (result.txn.sender(), result.txn.sequence_number());
+        if self.results.insert(key, Arc::new(result)).is_none() {
+            self.order.push_back(key);
+        }
+        while self.order.len() > self.capacity {
+            if let Some(key) = self.order.pop_front() {
+                self.results.remove(&key);
+            }
+        }
+    }
+}
+
+/// Records all the reads of the VM.
+struct ReadRecordingView<'a, S> {
+    base: &'a S,
+    reads: RefCell<Vec<(StateKey, Option<StateValue>)>>,
+}
+
+impl<'a, S: StateView> ReadRecordingView<'a, S> {
+    fn new(base: &'a S) -> Self {
+        Self {
+            base,
+            reads: RefCell::new(Vec::new()),
+        }
+    }
+
+    fn take_reads(&self) -> Vec<(StateKey, Option<StateValue>)> {
+        self.reads.take()
+    }
+}
+
+impl<'a, S: StateView> TStateView for ReadRecordingView<'a, S> {
+    type Key = StateKey;
+
+    fn id(&self) -> StateViewId {
+        self.base.id()
+    }
+
+    fn get_state_value(&self, state_key: &StateKey) -> Result<Option<StateValue>> {
+        let value = self.base.get_state_value(state_key)?;
+        self.reads
+            .borrow_mut()
+            .push((state_key.clone(), value.clone()));
+        Ok(value)
+    }
+
+    fn is_genesis(&self) -> bool {
+        self.base.is_genesis()
+    }
+
+    fn get_usage(&self) -> Result<StateStorageUsage> {
+        self.base.get_usage()
+    }
+}
+
+/// The base state with the writes of the block prologue applied.
+struct PrologueStateView<'a, S> {
+    base: &'a S,
+    write_set: WriteSet,
+}
+
+impl<'a, S: StateView> TStateView for PrologueStateView<'a, S> {
+    type Key = StateKey;
+
+    fn id(&self) -> StateViewId {
+        self.base.id()
+    }
+
+    fn get_state_value(&self, state_key: &StateKey) -> Result<Option<StateValue>> {
+        match self.write_set.get(state_key) {
+            Some(write_op) => Ok(write_op.as_state_value()),
+            None => self.base.get_state_value(state_key),
+        }
+    }
+
+    fn is_genesis(&self) -> bool {
+        self.base.is_genesis()
+    }
+
+    fn get_usage(&self) -> Result<StateStorageUsage> {
+        self.base.get_usage()
+    }
+}
+
+pub fn is_speculative_execution_enabled() -> bool {
+    AptosVM::get_speculative_execution_threads() > 0
+}
+
+/// Pre-executes the transactions of upcoming blocks and keeps their outputs, for the block
+/// executor to reuse (see `BlockAptosVM::execute_block_with_speculative_executor`).
+pub struct SpeculativeExecutor {
+    cache: Mutex<SpeculativeExecutionCache>,
+    thread_pool: rayon::ThreadPool,
+    // Pre-executions are best effort, a block is dropped if the previous one is still executing.
+    pre_execution_in_progress: AtomicBool,
+}
+
+impl SpeculativeExecutor {
+    pub fn new(num_threads: usize, cache_size: usize) -> Self {
+        Self {
+            cache: Mutex::new(SpeculativeExecutionCache::new(cache_size)),
+            thread_pool: rayon::ThreadPoolBuilder::new()
+                .num_threads(num_threads.max(1))
+                .thread_name(|index| format!("speculative_exe_{}", index))
+                .build()
+                .unwrap(),
+            pre_execution_in_progress: AtomicBool::new(false),
+        }
+    }
+
+    /// Creates a speculative executor as configured for the VM, or returns `None` if speculative
+    /// execution is disabled.
+    pub fn from_vm_config() -> Option<Self> {
+        is_speculative_execution_enabled().then(|| {
+            Self::new(
+                AptosVM::get_speculative_execution_threads(),
+                AptosVM::get_speculative_execution_cache_size(),
+            )
+        })
+    }
+
+    /// Speculatively executes the user transactions of a block against `state_view`, which should
+    /// be the state the block is going to be executed on. The block metadata transaction, if any,
+    /// is applied first, as every user transaction reads e.g. the timestamp it updates. Returns
+    /// immediately if the previous block is still being pre-executed.
+    pub fn pre_execute_block(
+        &self,
+        transactions: Vec<Transaction>,
+        state_view: &(impl StateView + Sync),
+    ) {
+        let _guard = match PreExecutionGuard::try_acquire(&self.pre_execution_in_progress) {
+            Some(guard) => guard,
+            None => return,
+        };
+        if let Err(err) = self.pre_execute_block_impl(transactions, state_view) {
+            error!("Speculative execution failed: {:?}", err);
+        }
+    }
+
+    fn pre_execute_block_impl(
+        &self,
+        transactions: Vec<Transaction>,
+        state_view: &(impl StateView + Sync),
+    ) -> Result<(), VMStatus> {
+        let mut block_metadata = None;
+        let mut user_txns = Vec::with_capacity(transactions.len());
+        {
+            let cache = self.cache.lock();
+            for txn in transactions {
+                match txn {
+                    Transaction::BlockMetadata(_) if block_metadata.is_none() => {
+                        block_metadata = Some(txn)
+                    },
+                    Transaction::UserTransaction(signed_txn) => {
+                        if cache.get(&signed_txn).is_none() {
+                            user_txns.push(Transaction::UserTransaction(signed_txn));
+                        }
+                    },
+                    _ => {},
+                }
+            }
+        }
+        if user_txns.is_empty() {
+            return Ok(());
+        }
+
+        let write_set = match block_metadata {
+            Some(block_metadata) => {
+                let vm = AptosVM::new(state_view);
+                let log_context = AdapterLogSchema::new(state_view.id(), 0);
+                let (_, output, _) = vm.execute_single_transaction(
+                    &preprocess_transaction::<AptosVM>(block_metadata),
+                    &vm.as_move_resolver(state_view),
+                    &log_context,
+                )?;
+                output.try_materialize(state_view)?.write_set().clone()
+            },
+            None => WriteSet::default(),
+        };
+        let prologue_view = PrologueStateView {
+            base: state_view,
+            write_set,
+        };
+
+        let num_txns = user_txns.len();
+        let results: Vec<SpeculativeResult> = self.thread_pool.install(|| {
+            user_txns
+                .into_par_iter()
+                .map_init(
+                    || {
+                        let recording_view = ReadRecordingView::new(&prologue_view);
+                        let vm = AptosVM::new(&recording_view);
+                        (vm, recording_view.take_reads())
+                    },
+                    |(vm, vm_init_reads), txn| {
+                        pre_execute_transaction(vm, vm_init_reads, txn, &prologue_view)
+                    },
+                )
+                .flatten()
+                .collect()
+        });
+
+        SPECULATIVE_EXECUTION_TRANSACTIONS
+            .with_label_values(&["pre_executed"])
+            .inc_by(results.len() as u64);
+        info!(
+            "Speculatively executed {} out of {} user transactions",
+            results.len(),
+            num_txns
+        );
+        let mut cache = self.cache.lock();
+        for result in results {
+            cache.insert(result);
+        }
+        Ok(())
+    }
+
+    /// Returns the cached output of `txn` if everything its speculative execution read still has
+    /// the same value in `view`.
+    pub(crate) fn reusable_output(
+        &self,
+        txn: &SignedTransaction,
+        view: &impl StateView,
+    ) -> Option<(VMStatus, VMOutput)> {
+        let result = match self.cache.lock().get(txn) {
+            Some(result) => result,
+            None => {
+                SPECULATIVE_EXECUTION_TRANSACTIONS
+                    .with_label_values(&["miss"])
+                    .inc();
+                return None;
+            },
+        };
+        if !reads_still_valid(&result, view) {
+            SPECULATIVE_EXECUTION_TRANSACTIONS
+                .with_label_values(&["invalidated"])
+                .inc();
+            return None;
+        }
+        SPECULATIVE_EXECUTION_TRANSACTIONS
+            .with_label_values(&["reused"])
+            .inc();
+        Some((result.vm_status.clone(), result.output.clone()))
+    }
+}
+
+/// Marks a pre-execution as in progress until dropped, also if the pre-execution panics.
+struct PreExecutionGuard<'a> {
+    in_progress: &'a AtomicBool,
+}
+
+impl<'a> PreExecutionGuard<'a> {
+    /// Returns `None` if another pre-execution is already in progress.
+    fn try_acquire(in_progress: &'a AtomicBool) -> Option<Self> {
+        (!in_progress.swap(true, Ordering::SeqCst)).then_some(Self { in_progress })
+    }
+}
+
+impl<'a> Drop for PreExecutionGuard<'a> {
+    fn drop(&mut self) {
+        self.in_progress.store(false, Ordering::SeqCst);
+    }
+}
+
+fn pre_execute_transaction(
+    vm: &AptosVM,
+    vm_init_reads: &[(StateKey, Option<StateValue>)],
+    txn: Transaction,
+    state_view: &impl StateView,
+) -> Option<SpeculativeResult> {
+    let preprocessed_txn = preprocess_transaction::<AptosVM>(txn);
+    let signed_txn = match &preprocessed_txn {
+        PreprocessedTransaction::UserTransaction(txn) => (**txn).clone(),
+        _ => return None,
+    };
+
+    let recording_view = ReadRecordingView::new(state_view);
+    let log_context = AdapterLogSchema::new(state_view.id(), 0);
+    let (vm_status, output, _) = vm
+        .execute_single_transaction(
+            &preprocessed_txn,
+            &vm.as_move_resolver(&recording_view),
+            &log_context,
+        )
+        .ok()?;
+    if matches!(output.status(), TransactionStatus::Retry) {
+        return None;
+    }
+
+    let mut reads = vm_init_reads.to_vec();
+    reads.extend(recording_view.take_reads());
+    // Modules served from the code cache of the VM were read by an earlier transaction.
+    for module_id in vm.0.get_and_clear_module_cache_hits() {
+        let state_key = StateKey::access_path(AccessPath::from(&module_id));
+        let value = state_view.get_state_value(&state_key).ok()?;
+        reads.push((state_key, value));
+    }
+    let mut seen = HashSet::new();
+    reads.retain(|(state_key, _)| seen.insert(state_key.clone()));
+
+    Some(SpeculativeResult {
+        txn: signed_txn,
+        reads,
+        vm_status,
+        output,
+    })
+}
+
+fn reads_still_valid(result: &SpeculativeResult, view: &impl StateView) -> bool {
+    result.reads.iter().all(|(state_key, value)| {
+        matches!(view.get_state_value(state_key), Ok(current) if &current == value)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::block_executor::{AptosTransactionOutput, BlockAptosVM};
+    use aptos_block_executor::txn_commit_hook::NoOpTransactionCommitHook;
+    use aptos_language_e2e_tests::{
+        account::Account,
+        common_transactions::{empty_txn, peer_to_peer_txn},
+        data_store::FakeDataStore,
+        executor::FakeExecutor,
+    };
+    use aptos_types::{
+        on_chain_config::BlockGasLimitType,
+        transaction::{ExecutionStatus, TransactionOutput},
+    };
+
+    fn result(
+        txn: SignedTransaction,
+        reads: Vec<(StateKey, Option<StateValue>)>,
+    ) -> SpeculativeResult {
+        SpeculativeResult {
+            txn,
+            reads,
+            vm_status: VMStatus::Executed,
+            output: VMOutput::empty_with_status(TransactionStatus::Keep(ExecutionStatus::Success)),
+        }
+    }
+
+    #[test]
+    fn cache_evicts_oldest_results() {
+        let account = Account::new();
+        let txns = (0..3)
+            .map(|seq_num| empty_txn(&account, seq_num, 1_000, 1))
+            .collect::<Vec<_>>();
+        let mut cache = SpeculativeExecutionCache::new(2);
+        for txn in &txns {
+            cache.insert(result(txn.clone(), vec![]));
+        }
+        assert!(cache.get(&txns[0]).is_none());
+        assert!(cache.get(&txns[1]).is_some());
+        assert!(cache.get(&txns[2]).is_some());
+
+        // A different transaction with the same sender and sequence number is not a hit.
+        let other_txn = empty_txn(&account, 2, 2_000, 1);
+        assert!(cache.get(&other_txn).is_none());
+    }
+
+    #[test]
+    fn reads_are_validated() {
+        let account = Account::new();
+        let txn = empty_txn(&account, 0, 1_000, 1);
+        let read_key = StateKey::raw(b"read".to_vec());
+        let absent_key = StateKey::raw(b"absent".to_vec());
+        let mut data_store = FakeDataStore::default();
+        data_store.set(read_key.clone(), StateValue::new_legacy(vec![1]));
+
+        let result = result(
+            txn,
+            vec![
+                (read_key, Some(StateValue::new_legacy(vec![1]))),
+                (absent_key.clone(), None),
+            ],
+        );
+        assert!(reads_still_valid(&result, &data_store));
+
+        data_store.set(absent_key, StateValue::new_legacy(vec![2]));
+        assert!(!reads_still_valid(&result, &data_store));
+    }
+
+    #[test]
+    fn reused_outputs_match_execution() {
+        let mut executor = FakeExecutor::from_head_genesis();
+        let accounts: Vec<_> = (0..5)
+            .map(|_| {
+                let account_data = executor.create_raw_account_data(1_000_000, 0);
+                executor.add_account_data(&account_data);
+                account_data.account().clone()
+            })
+            .collect();
+        let txn = peer_to_peer_txn(&accounts[0], &accounts[1], 0, 100, 100);
+        let other_txn = peer_to_peer_txn(&accounts[2], &accounts[3], 0, 100, 100);
+        let block = vec![
+            Transaction::UserTransaction(txn.clone()),
+            Transaction::UserTransaction(other_txn.clone()),
+        ];
+
+        let speculative_executor = SpeculativeExecutor::new(2, 100);
+        speculative_executor.pre_execute_block(block.clone(), executor.data_store());
+
+        // Commit a transfer to the receiver of the first transaction, which invalidates its
+        // pre-execution (but not the one of the other transaction).
+        executor.execute_and_apply(peer_to_peer_txn(&accounts[4], &accounts[1], 0, 1_000, 100));
+        let state_view = executor.data_store();
+        assert!(speculative_executor
+            .reusable_output(&txn, state_view)
+            .is_none());
+        assert!(speculative_executor
+            .reusable_output(&other_txn, state_view)
+            .is_some());
+
+        // Verify that executing the block with the speculative results has the same outputs as
+        // executing it from scratch.
+        let executor_thread_pool = Arc::new(
+            rayon::ThreadPoolBuilder::new()
+                .num_threads(2)
+                .build()
+                .unwrap(),
+        );
+        let execute = |speculative_executor: Option<&SpeculativeExecutor>| {
+            BlockAptosVM::execute_block_with_speculative_executor::<
+                _,
+                NoOpTransactionCommitHook<AptosTransactionOutput, VMStatus>,
+            >(
+                executor_thread_pool.clone(),
+                block.clone(),
+                state_view,
+                2,
+                BlockGasLimitType::NoLimit,
+                None,
+                speculative_executor,
+            )
+            .unwrap()
+        };
+        let outputs: Vec<TransactionOutput> = execute(Some(&speculative_executor));
+        assert_eq!(outputs, execute(None));
+        for output in outputs {
+            assert_eq!(
+                output.status(),
+                &TransactionStatus::Keep(ExecutionStatus::Success)
+            );
+        }
+    }
+}
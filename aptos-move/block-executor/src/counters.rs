@@ -11,6 +11,8 @@ use once_cell::sync::Lazy;
 pub struct GasType;
 
 impl GasType {
+    pub const CRITICAL_PATH_GAS: &'static str = "critical_path_gas";
+    pub const EFFECTIVE_GAS: &'static str = "effective_gas";
     pub const EXECUTION_GAS: &'static str = "execution_gas";
     pub const IO_GAS: &'static str = "io_gas";
     pub const NON_STORAGE_GAS: &'static str = "non_storage_gas";
@@ -54,15 +56,6 @@ fn observe_sequential_execution_txn_gas(cost: u64, gas_type: &'static str) {
         .observe(cost as f64);
 }
 
-/// Count of times the module publishing fallback was triggered in parallel execution.
-pub static MODULE_PUBLISHING_FALLBACK_COUNT: Lazy<IntCounter> = Lazy::new(|| {
-    register_int_counter!(
-        "aptos_execution_module_publishing_fallback_count",
-        "Count times module was read and written in parallel execution (sequential fallback)"
-    )
-    .unwrap()
-});
-
 /// Count of speculative transaction re-executions due to a failed validation.
 pub static SPECULATIVE_ABORT_COUNT: Lazy<IntCounter> = Lazy::new(|| {
     register_int_counter!(
@@ -240,6 +233,15 @@ pub(crate) fn update_parallel_txn_gas_counters(txn_fee_statements: &Vec<FeeState
     }
 }
 
+/// Record the effective gas of a block executed in parallel under the conflict-aware limit.
+pub(crate) fn update_parallel_block_effective_gas_counters(
+    effective_gas: u64,
+    critical_path_gas: u64,
+) {
+    observe_parallel_execution_block_gas(effective_gas, GasType::EFFECTIVE_GAS);
+    observe_parallel_execution_block_gas(critical_path_gas, GasType::CRITICAL_PATH_GAS);
+}
+
 pub(crate) fn update_sequential_block_gas_counters(
     accumulated_fee_statement: &FeeStatement,
     num_committed: usize,
@@ -273,6 +275,15 @@ pub(crate) fn update_sequential_block_gas_counters(
         .observe(num_committed as f64);
 }
 
+/// Record the effective gas of a block executed sequentially under the conflict-aware limit.
+pub(crate) fn update_sequential_block_effective_gas_counters(
+    effective_gas: u64,
+    critical_path_gas: u64,
+) {
+    observe_sequential_execution_block_gas(effective_gas, GasType::EFFECTIVE_GAS);
+    observe_sequential_execution_block_gas(critical_path_gas, GasType::CRITICAL_PATH_GAS);
+}
+
 pub(crate) fn update_sequential_txn_gas_counters(fee_statement: &FeeStatement) {
     observe_sequential_execution_txn_gas(fee_statement.gas_used(), GasType::TOTAL_GAS);
     observe_sequential_execution_txn_gas(
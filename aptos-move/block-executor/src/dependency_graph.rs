@@ -0,0 +1,254 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Opt-in instrumentation of parallel execution, recording for every block how its transactions
+//! depended on each other: the incarnations of each transaction, the keys they read and wrote,
+//! which transaction's write caused an incarnation to be aborted, and the dependencies of the
+//! committed incarnations. The recorded graphs can be exported as JSON or DOT.
+
+use crate::{
+    errors::Error,
+    task::{ExecutionStatus, TransactionOutput},
+    txn_last_input_output::ReadDescriptor,
+};
+use anyhow::Result;
+use aptos_mvhashmap::types::{Incarnation, TxnIndex};
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::{
+    collections::BTreeSet,
+    fmt::{Debug, Write},
+    path::Path,
+    sync::atomic::{AtomicBool, AtomicUsize, Ordering},
+};
+
+static RECORDING_ENABLED: AtomicBool = AtomicBool::new(false);
+static NUM_RECORDED_BLOCKS: AtomicUsize = AtomicUsize::new(0);
+static RECORDED_GRAPHS: Lazy<Mutex<Vec<BlockDependencyGraph>>> =
+    Lazy::new(|| Mutex::new(Vec::new()));
+
+/// Enables recording the dependency graphs of the blocks executed in parallel by block executors
+/// created from now on. The graphs are kept in memory until taken with
+/// `take_recorded_dependency_graphs`.
+pub fn set_dependency_graph_recording(enable: bool) {
+    RECORDING_ENABLED.store(enable, Ordering::Relaxed);
+}
+
+pub fn is_dependency_graph_recording_enabled() -> bool {
+    RECORDING_ENABLED.load(Ordering::Relaxed)
+}
+
+/// Returns the graphs recorded since the last call, in the order the blocks finished executing.
+pub fn take_recorded_dependency_graphs() -> Vec<BlockDependencyGraph> {
+    std::mem::take(&mut *RECORDED_GRAPHS.lock())
+}
+
+/// Writes the graphs recorded since the last call to `dir`, as `block_<index>.json` and
+/// `block_<index>.dot`. Returns the number of graphs written.
+pub fn export_recorded_dependency_graphs(dir: &Path) -> Result<usize> {
+    let graphs = take_recorded_dependency_graphs();
+    if !graphs.is_empty() {
+        std::fs::create_dir_all(dir)?;
+    }
+    for graph in &graphs {
+        std::fs::write(
+            dir.join(format!("block_{}.json", graph.index)),
+            graph.to_json()?,
+        )?;
+        std::fs::write(
+            dir.join(format!("block_{}.dot", graph.index)),
+            graph.to_dot(),
+        )?;
+    }
+    Ok(graphs.len())
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct KeyRead {
+    pub key: String,
+    /// The transaction and incarnation whose write was read, `None` if the value was read from
+    /// storage (or resolved from deltas).
+    pub version: Option<(TxnIndex, Incarnation)>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct IncarnationRecord {
+    pub incarnation: Incarnation,
+    pub reads: Vec<KeyRead>,
+    pub writes: Vec<String>,
+    pub aborted: bool,
+    /// The transaction whose write invalidated the reads of this incarnation, if known.
+    pub aborted_by: Option<TxnIndex>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct TransactionRecord {
+    pub txn_idx: TxnIndex,
+    pub incarnations: Vec<IncarnationRecord>,
+    /// The transactions the committed incarnation read from, i.e. the incoming edges of the DAG.
+    pub dependencies: Vec<TxnIndex>,
+}
+
+/// The dependency graph of one block executed in parallel.
+#[derive(Clone, Debug, Serialize)]
+pub struct BlockDependencyGraph {
+    /// Index of the block among all the recorded blocks.
+    pub index: usize,
+    /// Number of committed transactions, the block may have been cut short by a gas limit.
+    pub num_committed_txns: usize,
+    pub transactions: Vec<TransactionRecord>,
+}
+
+impl BlockDependencyGraph {
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// Renders the DAG of the committed transactions. Transactions that were re-executed are
+    /// highlighted, and dashed edges point from the transaction that caused an abort to the
+    /// aborted transaction.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::new();
+        // Writing to a `String` can't fail.
+        let _ = writeln!(dot, "digraph block_{} {{", self.index);
+        let _ = writeln!(dot, "  node [shape=box];");
+        for txn in &self.transactions {
+            let num_incarnations = txn.incarnations.len();
+            if num_incarnations > 1 {
+                let _ = writeln!(
+                    dot,
+                    "  {} [label=\"txn {}\\n{} incarnations\", color=red];",
+                    txn.txn_idx, txn.txn_idx, num_incarnations
+                );
+            } else {
+                let _ = writeln!(dot, "  {} [label=\"txn {}\"];", txn.txn_idx, txn.txn_idx);
+            }
+        }
+        for txn in &self.transactions {
+            for dependency in &txn.dependencies {
+                let _ = writeln!(dot, "  {} -> {};", dependency, txn.txn_idx);
+            }
+            let aborted_by = txn
+                .incarnations
+                .iter()
+                .filter_map(|incarnation| incarnation.aborted_by)
+                .collect::<BTreeSet<_>>();
+            for idx in aborted_by {
+                let _ = writeln!(
+                    dot,
+                    "  {} -> {} [style=dashed, color=red];",
+                    idx, txn.txn_idx
+                );
+            }
+        }
+        let _ = writeln!(dot, "}}");
+        dot
+    }
+}
+
+/// Collects the records of one block while it's being executed.
+pub(crate) struct DependencyGraphRecorder {
+    // txn_idx -> records of the incarnations, in order.
+    txns: Vec<Mutex<Vec<IncarnationRecord>>>,
+}
+
+impl DependencyGraphRecorder {
+    pub fn new(num_txns: TxnIndex) -> Self {
+        Self {
+            txns: (0..num_txns).map(|_| Mutex::new(Vec::new())).collect(),
+        }
+    }
+
+    pub fn record_execution<K: Debug, O: TransactionOutput, E: Debug>(
+        &self,
+        txn_idx: TxnIndex,
+        incarnation: Incarnation,
+        reads: &[ReadDescriptor<K>],
+        result: &ExecutionStatus<O, Error<E>>,
+    ) {
+        let reads = reads
+            .iter()
+            .map(|read| KeyRead {
+                key: format!("{:?}", read.path()),
+                version: read.version(),
+            })
+            .collect();
+        let writes = match result {
+            ExecutionStatus::Success(output) | ExecutionStatus::SkipRest(output) => output
+                .get_writes()
+                .into_iter()
+                .map(|(k, _)| format!("{:?}", k))
+                .chain(
+                    output
+                        .get_deltas()
+                        .into_iter()
+                        .map(|(k, _)| format!("{:?}", k)),
+                )
+                .collect(),
+            ExecutionStatus::Abort(_) => vec![],
+        };
+        self.txns[txn_idx as usize].lock().push(IncarnationRecord {
+            incarnation,
+            reads,
+            writes,
+            aborted: false,
+            aborted_by: None,
+        });
+    }
+
+    pub fn record_abort(
+        &self,
+        txn_idx: TxnIndex,
+        incarnation: Incarnation,
+        aborted_by: Option<TxnIndex>,
+    ) {
+        if let Some(record) = self.txns[txn_idx as usize]
+            .lock()
+            .iter_mut()
+            .rev()
+            .find(|record| record.incarnation == incarnation)
+        {
+            record.aborted = true;
+            record.aborted_by = aborted_by;
+        }
+    }
+
+    /// Builds the graph of the block and keeps it to be taken by
+    /// `take_recorded_dependency_graphs`.
+    pub fn finish(self, num_committed_txns: usize) {
+        let transactions = self
+            .txns
+            .into_iter()
+            .take(num_committed_txns)
+            .enumerate()
+            .map(|(txn_idx, incarnations)| {
+                let incarnations = incarnations.into_inner();
+                // The last incarnation of a committed transaction is the committed one.
+                let dependencies = incarnations
+                    .last()
+                    .map(|committed| {
+                        committed
+                            .reads
+                            .iter()
+                            .filter_map(|read| read.version.map(|(idx, _)| idx))
+                            .collect::<BTreeSet<_>>()
+                            .into_iter()
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                TransactionRecord {
+                    txn_idx: txn_idx as TxnIndex,
+                    incarnations,
+                    dependencies,
+                }
+            })
+            .collect();
+
+        RECORDED_GRAPHS.lock().push(BlockDependencyGraph {
+            index: NUM_RECORDED_BLOCKS.fetch_add(1, Ordering::Relaxed),
+            num_committed_txns,
+            transactions,
+        });
+    }
+}
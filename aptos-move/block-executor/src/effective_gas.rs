@@ -0,0 +1,95 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Accounting for the conflict-aware block gas limit. Instead of the total gas of the block, the
+//! limit applies to an estimate of how long the block takes to execute in parallel: transactions
+//! are charged by how much they extend the longest chain of conflicting transactions.
+//!
+//! The estimate only depends on the keys read and written by the committed transactions, so
+//! parallel and sequential execution cut the block at the same transaction.
+
+use aptos_mvhashmap::types::TxnIndex;
+use aptos_types::executable::ModulePath;
+use std::{cmp::max, collections::HashMap, hash::Hash};
+
+/// Parameters of `BlockGasLimitType::ConflictAwareV1`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct ConflictAwareGasLimit {
+    pub effective_block_gas_limit: u64,
+    pub parallelism: u32,
+}
+
+pub(crate) struct EffectiveGasTracker<K> {
+    limit: ConflictAwareGasLimit,
+    // The last committed transaction that wrote (or applied a delta to) each key.
+    last_writers: HashMap<K, TxnIndex>,
+    // txn_idx -> gas along the longest chain of conflicting transactions ending with it.
+    chain_gas: Vec<u64>,
+    critical_path_gas: u64,
+    total_gas: u64,
+}
+
+impl<K: ModulePath + Hash + Eq> EffectiveGasTracker<K> {
+    pub fn new(limit: ConflictAwareGasLimit, num_txns: usize) -> Self {
+        Self {
+            limit,
+            last_writers: HashMap::new(),
+            chain_gas: vec![0; num_txns],
+            critical_path_gas: 0,
+            total_gas: 0,
+        }
+    }
+
+    /// Accounts for a committed transaction, must be called in the commit order. A transaction
+    /// depends on the last earlier transaction that wrote any of the keys it read. Module
+    /// accesses are not taken into account, as the code cache makes their reads differ between
+    /// parallel and sequential execution.
+    pub fn add_committed_txn<'a>(
+        &mut self,
+        txn_idx: TxnIndex,
+        txn_gas: u64,
+        read_keys: impl IntoIterator<Item = &'a K>,
+        written_keys: impl IntoIterator<Item = K>,
+    ) where
+        K: 'a,
+    {
+        let start = read_keys
+            .into_iter()
+            .filter(|key| key.module_path().is_none())
+            .filter_map(|key| self.last_writers.get(key))
+            .map(|dep_idx| self.chain_gas[*dep_idx as usize])
+            .max()
+            .unwrap_or(0);
+        let chain_gas = start + txn_gas;
+        self.chain_gas[txn_idx as usize] = chain_gas;
+        self.critical_path_gas = max(self.critical_path_gas, chain_gas);
+        self.total_gas += txn_gas;
+
+        for key in written_keys {
+            if key.module_path().is_none() {
+                self.last_writers.insert(key, txn_idx);
+            }
+        }
+    }
+
+    /// The estimated cost of executing the committed transactions in parallel: the gas along the
+    /// critical path, but at least the total gas split evenly among `parallelism` workers.
+    pub fn effective_gas(&self) -> u64 {
+        max(
+            self.critical_path_gas,
+            self.total_gas / max(self.limit.parallelism, 1) as u64,
+        )
+    }
+
+    pub fn critical_path_gas(&self) -> u64 {
+        self.critical_path_gas
+    }
+
+    pub fn limit_reached(&self) -> bool {
+        self.effective_gas() >= self.limit.effective_block_gas_limit
+    }
+
+    pub fn limit(&self) -> u64 {
+        self.limit.effective_block_gas_limit
+    }
+}
@@ -8,26 +8,34 @@ use crate::{
         PARALLEL_EXECUTION_SECONDS, RAYON_EXECUTION_SECONDS, TASK_EXECUTE_SECONDS,
         TASK_VALIDATE_SECONDS, VM_INIT_SECONDS, WORK_WITH_TASK_SECONDS,
     },
+    dependency_graph::{is_dependency_graph_recording_enabled, DependencyGraphRecorder},
+    effective_gas::{ConflictAwareGasLimit, EffectiveGasTracker},
     errors::*,
     scheduler::{DependencyStatus, ExecutionTaskType, Scheduler, SchedulerTask, Wave},
     task::{ExecutionStatus, ExecutorTask, Transaction, TransactionOutput},
     txn_commit_hook::TransactionCommitHook,
-    txn_last_input_output::TxnLastInputOutput,
+    txn_last_input_output::{ReadDescriptor, TxnLastInputOutput},
     view::{LatestView, MVHashMapView},
 };
 use aptos_aggregator::delta_change_set::{deserialize, serialize};
-use aptos_logger::{debug, info};
+use aptos_logger::info;
 use aptos_mvhashmap::{
-    types::{MVDataError, MVDataOutput, TxnIndex, Version},
+    types::{MVDataError, MVDataOutput, MVModulesError, MVModulesOutput, TxnIndex, Version},
     unsync_map::UnsyncMap,
     MVHashMap,
 };
 use aptos_state_view::TStateView;
-use aptos_types::{executable::Executable, fee_statement::FeeStatement, write_set::WriteOp};
-use aptos_vm_logging::{clear_speculative_txn_logs, init_speculative_logs};
+use aptos_types::{
+    executable::{Executable, ExecutableDescriptor, ModulePath},
+    fee_statement::FeeStatement,
+    write_set::WriteOp,
+};
+use aptos_vm_logging::clear_speculative_txn_logs;
 use num_cpus;
 use rayon::ThreadPool;
 use std::{
+    collections::HashMap,
+    hash::Hash,
     marker::PhantomData,
     sync::{
         mpsc,
@@ -61,6 +69,59 @@ impl<'a> Drop for CommitGuard<'a> {
     }
 }
 
+/// Tracks the modules in the code cache of a worker thread's executor. The executor may serve
+/// modules from its cache without reading them through the multi-versioned view, so the
+/// corresponding reads are recorded here and added to the read-set of the transaction.
+struct CodeCacheTracker<K> {
+    /// Number of module changes in the multi-versioned map when the cache was last flushed.
+    num_module_changes: u64,
+    /// Reads of modules since the last flush, i.e. the versions that may be in the cache.
+    loaded_modules: HashMap<K, ReadDescriptor<K>>,
+}
+
+impl<K: ModulePath + Clone + Hash + Eq> CodeCacheTracker<K> {
+    fn new() -> Self {
+        Self {
+            num_module_changes: 0,
+            loaded_modules: HashMap::new(),
+        }
+    }
+
+    /// Returns true if the code cache has to be flushed because modules were changed in the
+    /// multi-versioned map since the last flush.
+    fn needs_flush(&self, num_module_changes: u64) -> bool {
+        self.num_module_changes != num_module_changes
+    }
+
+    /// Records that the code cache was flushed when the multi-versioned map had the given
+    /// number of module changes.
+    fn flush(&mut self, num_module_changes: u64) {
+        self.num_module_changes = num_module_changes;
+        self.loaded_modules.clear();
+    }
+
+    /// Records module reads performed through the view, and adds reads for the modules used
+    /// from the cache (cache hits) to the read-set.
+    fn record_module_reads(&mut self, reads: &mut Vec<ReadDescriptor<K>>, cache_hits: Vec<K>) {
+        for read in reads.iter().filter(|read| read.is_module_read()) {
+            // The first read since the flush corresponds to the version loaded into the cache.
+            self.loaded_modules
+                .entry(read.path().clone())
+                .or_insert_with(|| read.clone());
+        }
+        for key in cache_hits {
+            // Modules cached before the first flush (e.g. when warming up the executor) were
+            // loaded directly from storage.
+            let read = self
+                .loaded_modules
+                .get(&key)
+                .cloned()
+                .unwrap_or_else(|| ReadDescriptor::from_storage(key));
+            reads.push(read);
+        }
+    }
+}
+
 #[derive(Debug)]
 enum CommitRole {
     Coordinator(Vec<Sender<TxnIndex>>),
@@ -74,7 +135,11 @@ pub struct BlockExecutor<T, E, S, L, X> {
     concurrency_level: usize,
     executor_thread_pool: Arc<ThreadPool>,
     maybe_block_gas_limit: Option<u64>,
+    // Limit on the effective (conflict-aware) gas of the block, in addition to the plain limit.
+    conflict_aware_gas_limit: Option<ConflictAwareGasLimit>,
     transaction_commit_hook: Option<L>,
+    // Whether to record the dependency graphs of the blocks executed in parallel.
+    record_dependency_graph: bool,
     phantom: PhantomData<(T, E, S, L, X)>,
 }
 
@@ -103,11 +168,27 @@ where
             concurrency_level,
             executor_thread_pool,
             maybe_block_gas_limit,
+            conflict_aware_gas_limit: None,
             transaction_commit_hook,
+            record_dependency_graph: is_dependency_graph_recording_enabled(),
             phantom: PhantomData,
         }
     }
 
+    /// Overrides whether the dependency graphs of the blocks executed in parallel are recorded,
+    /// see `dependency_graph::set_dependency_graph_recording`.
+    pub fn with_dependency_graph_recording(mut self, enable: bool) -> Self {
+        self.record_dependency_graph = enable;
+        self
+    }
+
+    /// Cuts the block once its effective gas, estimated from the conflicts between the committed
+    /// transactions, reaches the given limit. See `effective_gas` for the accounting.
+    pub fn with_conflict_aware_gas_limit(mut self, limit: ConflictAwareGasLimit) -> Self {
+        self.conflict_aware_gas_limit = Some(limit);
+        self
+    }
+
     fn execute(
         &self,
         version: Version,
@@ -116,12 +197,35 @@ where
         versioned_cache: &MVHashMap<T::Key, T::Value, X>,
         scheduler: &Scheduler,
         executor: &E,
+        code_cache_tracker: &mut CodeCacheTracker<T::Key>,
         base_view: &S,
+        dependency_graph_recorder: Option<&DependencyGraphRecorder>,
     ) -> SchedulerTask {
         let _timer = TASK_EXECUTE_SECONDS.start_timer();
         let (idx_to_execute, incarnation) = version;
         let txn = &signature_verified_block[idx_to_execute as usize];
 
+        // Modules cached by the executor may be outdated if they were changed in the versioned
+        // cache since the last flush. Note that a module written concurrently after this check
+        // is caught by the validation, as the cached version is recorded in the read-set.
+        //
+        // A cached module may also be outdated for this transaction without any change since the
+        // last flush, e.g. if it was loaded by a transaction with a higher index. Then, the
+        // validation aborts the transaction, and the cache is flushed before the re-execution,
+        // as it would otherwise use the same outdated module again (and be aborted forever).
+        let num_module_changes = versioned_cache.num_module_changes();
+        if code_cache_tracker.needs_flush(num_module_changes)
+            || (incarnation > 0
+                && Self::has_invalid_module_read(
+                    last_input_output,
+                    idx_to_execute,
+                    versioned_cache,
+                ))
+        {
+            code_cache_tracker.flush(num_module_changes);
+            executor.flush_code_cache();
+        }
+
         let speculative_view = MVHashMapView::new(versioned_cache, scheduler);
 
         // VM execution.
@@ -180,15 +284,13 @@ where
             versioned_cache.delete(&k, idx_to_execute);
         }
 
-        if last_input_output
-            .record(idx_to_execute, speculative_view.take_reads(), result)
-            .is_err()
-        {
-            // When there is module publishing r/w intersection, can early halt BlockSTM to
-            // fallback to sequential execution.
-            scheduler.halt();
-            return SchedulerTask::NoTask;
+        let mut reads = speculative_view.take_reads();
+        code_cache_tracker.record_module_reads(&mut reads, executor.take_code_cache_hits());
+
+        if let Some(recorder) = dependency_graph_recorder {
+            recorder.record_execution(idx_to_execute, incarnation, &reads, &result);
         }
+        last_input_output.record(idx_to_execute, reads, result);
         scheduler.finish_execution(idx_to_execute, incarnation, updates_outside)
     }
 
@@ -199,40 +301,31 @@ where
         last_input_output: &TxnLastInputOutput<T::Key, E::Output, E::Error>,
         versioned_cache: &MVHashMap<T::Key, T::Value, X>,
         scheduler: &Scheduler,
+        dependency_graph_recorder: Option<&DependencyGraphRecorder>,
     ) -> SchedulerTask {
-        use MVDataError::*;
-        use MVDataOutput::*;
-
         let _timer = TASK_VALIDATE_SECONDS.start_timer();
         let (idx_to_validate, incarnation) = version_to_validate;
         let read_set = last_input_output
             .read_set(idx_to_validate)
             .expect("[BlockSTM]: Prior read-set must be recorded");
 
-        let valid = read_set.iter().all(|r| {
-            match versioned_cache.fetch_data(r.path(), idx_to_validate) {
-                Ok(Versioned(version, _)) => r.validate_version(version),
-                Ok(Resolved(value)) => r.validate_resolved(value),
-                // Dependency implies a validation failure, and if the original read were to
-                // observe an unresolved delta, it would set the aggregator base value in the
-                // multi-versioned data-structure, resolve, and record the resolved value.
-                Err(Dependency(_)) | Err(Unresolved(_)) => false,
-                Err(NotFound) => r.validate_storage(),
-                // We successfully validate when read (again) results in a delta application
-                // failure. If the failure is speculative, a later validation will fail due to
-                // a read without this error. However, if the failure is real, passing
-                // validation here allows to avoid infinitely looping and instead panic when
-                // materializing deltas as writes in the final output preparation state. Panic
-                // is also preferable as it allows testing for this scenario.
-                Err(DeltaApplicationFailure) => r.validate_delta_application_failure(),
-            }
-        });
+        let valid = read_set
+            .iter()
+            .all(|r| Self::validate_read(r, idx_to_validate, versioned_cache));
 
         let aborted = !valid && scheduler.try_abort(idx_to_validate, incarnation);
 
         if aborted {
             counters::SPECULATIVE_ABORT_COUNT.inc();
 
+            if let Some(recorder) = dependency_graph_recorder {
+                recorder.record_abort(
+                    idx_to_validate,
+                    incarnation,
+                    Self::abort_cause(&read_set, idx_to_validate, versioned_cache),
+                );
+            }
+
             // Any logs from the aborted execution should be cleared and not reported.
             clear_speculative_txn_logs(idx_to_validate as usize);
 
@@ -248,6 +341,99 @@ where
         }
     }
 
+    fn validate_read(
+        read: &ReadDescriptor<T::Key>,
+        idx_to_validate: TxnIndex,
+        versioned_cache: &MVHashMap<T::Key, T::Value, X>,
+    ) -> bool {
+        use MVDataError::*;
+        use MVDataOutput::*;
+
+        if read.is_module_read() {
+            return Self::validate_module_read(read, idx_to_validate, versioned_cache);
+        }
+
+        match versioned_cache.fetch_data(read.path(), idx_to_validate) {
+            Ok(Versioned(version, _)) => read.validate_version(version),
+            Ok(Resolved(value)) => read.validate_resolved(value),
+            // Dependency implies a validation failure, and if the original read were to
+            // observe an unresolved delta, it would set the aggregator base value in the
+            // multi-versioned data-structure, resolve, and record the resolved value.
+            Err(Dependency(_)) | Err(Unresolved(_)) => false,
+            Err(NotFound) => read.validate_storage(),
+            // We successfully validate when read (again) results in a delta application
+            // failure. If the failure is speculative, a later validation will fail due to
+            // a read without this error. However, if the failure is real, passing
+            // validation here allows to avoid infinitely looping and instead panic when
+            // materializing deltas as writes in the final output preparation state. Panic
+            // is also preferable as it allows testing for this scenario.
+            Err(DeltaApplicationFailure) => read.validate_delta_application_failure(),
+        }
+    }
+
+    /// Returns true if a module read of the previous incarnation of the transaction is invalid.
+    fn has_invalid_module_read(
+        last_input_output: &TxnLastInputOutput<T::Key, E::Output, E::Error>,
+        txn_idx: TxnIndex,
+        versioned_cache: &MVHashMap<T::Key, T::Value, X>,
+    ) -> bool {
+        last_input_output
+            .read_set(txn_idx)
+            .map_or(false, |read_set| {
+                read_set.iter().any(|read| {
+                    read.is_module_read() && !Self::validate_read(read, txn_idx, versioned_cache)
+                })
+            })
+    }
+
+    /// Best-effort attribution of a failed validation to the transaction whose write invalidated
+    /// the first invalid read, only used for recording dependency graphs.
+    fn abort_cause(
+        read_set: &[ReadDescriptor<T::Key>],
+        idx_to_validate: TxnIndex,
+        versioned_cache: &MVHashMap<T::Key, T::Value, X>,
+    ) -> Option<TxnIndex> {
+        use MVDataError::*;
+        use MVDataOutput::*;
+
+        let read = read_set
+            .iter()
+            .find(|r| !Self::validate_read(r, idx_to_validate, versioned_cache))?;
+        if read.is_module_read() {
+            return match versioned_cache.fetch_module(read.path(), idx_to_validate) {
+                Err(MVModulesError::Dependency(txn_idx)) => Some(txn_idx),
+                _ => None,
+            };
+        }
+        match versioned_cache.fetch_data(read.path(), idx_to_validate) {
+            Ok(Versioned((txn_idx, _), _)) | Err(Dependency(txn_idx)) => Some(txn_idx),
+            // The write that was read has been removed by a re-execution of its transaction.
+            Err(NotFound) => read.version().map(|(txn_idx, _)| txn_idx),
+            _ => None,
+        }
+    }
+
+    fn validate_module_read(
+        read: &ReadDescriptor<T::Key>,
+        idx_to_validate: TxnIndex,
+        versioned_cache: &MVHashMap<T::Key, T::Value, X>,
+    ) -> bool {
+        use MVModulesError::*;
+        use MVModulesOutput::*;
+
+        // Unlike data, there are no deltas for modules, and reads from storage remain valid
+        // only as long as no lower transaction has written the module.
+        match versioned_cache.fetch_module(read.path(), idx_to_validate) {
+            Ok(Module((_, hash))) | Ok(Executable((_, ExecutableDescriptor::Published(hash)))) => {
+                read.validate_module(hash)
+            },
+            Ok(Executable((_, ExecutableDescriptor::Storage))) | Err(NotFound) => {
+                read.validate_storage()
+            },
+            Err(Dependency(_)) => false,
+        }
+    }
+
     fn coordinator_commit_hook(
         &self,
         maybe_block_gas_limit: Option<u64>,
@@ -258,6 +444,7 @@ where
         last_input_output: &TxnLastInputOutput<T::Key, E::Output, E::Error>,
         accumulated_fee_statement: &mut FeeStatement,
         txn_fee_statements: &mut Vec<FeeStatement>,
+        effective_gas_tracker: &mut Option<EffectiveGasTracker<T::Key>>,
     ) {
         while let Some(txn_idx) = scheduler.try_commit() {
             // Create a CommitGuard to ensure Coordinator sends the committed txn index to Worker.
@@ -269,6 +456,8 @@ where
             if let Some(fee_statement) = last_input_output.fee_statement(txn_idx) {
                 // For committed txns with Success status, calculate the accumulated gas costs.
                 accumulated_fee_statement.add_fee_statement(&fee_statement);
+                let txn_non_storage_gas =
+                    fee_statement.execution_gas_used() + fee_statement.io_gas_used();
                 txn_fee_statements.push(fee_statement);
 
                 if let Some(per_block_gas_limit) = maybe_block_gas_limit {
@@ -292,6 +481,34 @@ where
                         last_input_output.update_to_skip_rest(txn_idx);
                     }
                 }
+
+                if let Some(tracker) = effective_gas_tracker {
+                    // The committed incarnation's reads tell which earlier txns this one
+                    // conflicted with.
+                    let read_set = last_input_output
+                        .read_set(txn_idx)
+                        .expect("[BlockSTM]: Read set must be recorded for a committed txn");
+                    tracker.add_committed_txn(
+                        txn_idx,
+                        txn_non_storage_gas,
+                        read_set.iter().map(|read| read.path()),
+                        last_input_output.modified_keys(txn_idx),
+                    );
+                    if tracker.limit_reached() && !last_input_output.block_truncated_at_idx(txn_idx)
+                    {
+                        counters::EXCEED_PER_BLOCK_GAS_LIMIT_COUNT
+                            .with_label_values(&[counters::Mode::PARALLEL])
+                            .inc();
+                        info!(
+                            "[BlockSTM]: Parallel execution early halted due to \
+                             effective_gas {} >= EFFECTIVE_BLOCK_GAS_LIMIT {}",
+                            tracker.effective_gas(),
+                            tracker.limit(),
+                        );
+
+                        last_input_output.update_to_skip_rest(txn_idx);
+                    }
+                }
             }
 
             // Committed the last transaction, BlockSTM finishes execution.
@@ -316,6 +533,12 @@ where
                     (txn_idx + 1) as usize,
                 );
                 counters::update_parallel_txn_gas_counters(txn_fee_statements);
+                if let Some(tracker) = effective_gas_tracker {
+                    counters::update_parallel_block_effective_gas_counters(
+                        tracker.effective_gas(),
+                        tracker.critical_path_gas(),
+                    );
+                }
 
                 let accumulated_non_storage_gas = accumulated_fee_statement.execution_gas_used()
                     + accumulated_fee_statement.io_gas_used();
@@ -405,11 +628,13 @@ where
         scheduler: &Scheduler,
         base_view: &S,
         role: CommitRole,
+        dependency_graph_recorder: Option<&DependencyGraphRecorder>,
     ) {
         // Make executor for each task. TODO: fast concurrent executor.
         let init_timer = VM_INIT_SECONDS.start_timer();
         let executor = E::init(*executor_arguments);
         drop(init_timer);
+        let mut code_cache_tracker = CodeCacheTracker::new();
 
         let committing = matches!(role, CommitRole::Coordinator(_));
 
@@ -419,6 +644,10 @@ where
 
         let mut accumulated_fee_statement = FeeStatement::zero();
         let mut txn_fee_statements = Vec::with_capacity(block.len());
+        let mut effective_gas_tracker = self
+            .conflict_aware_gas_limit
+            .filter(|_| committing)
+            .map(|limit| EffectiveGasTracker::new(limit, block.len()));
         loop {
             // Only one thread does try_commit to avoid contention.
             match &role {
@@ -432,6 +661,7 @@ where
                         last_input_output,
                         &mut accumulated_fee_statement,
                         &mut txn_fee_statements,
+                        &mut effective_gas_tracker,
                     );
                 },
                 CommitRole::Worker(rx) => {
@@ -453,6 +683,7 @@ where
                     last_input_output,
                     versioned_cache,
                     scheduler,
+                    dependency_graph_recorder,
                 ),
                 SchedulerTask::ExecutionTask(version_to_execute, ExecutionTaskType::Execution) => {
                     self.execute(
@@ -462,7 +693,9 @@ where
                         versioned_cache,
                         scheduler,
                         &executor,
+                        &mut code_cache_tracker,
                         base_view,
+                        dependency_graph_recorder,
                     )
                 },
                 SchedulerTask::ExecutionTask(_, ExecutionTaskType::Wakeup(condvar)) => {
@@ -516,6 +749,9 @@ where
         let num_txns = signature_verified_block.len() as u32;
         let last_input_output = TxnLastInputOutput::new(num_txns);
         let scheduler = Scheduler::new(num_txns);
+        let dependency_graph_recorder = self
+            .record_dependency_graph
+            .then(|| DependencyGraphRecorder::new(num_txns));
 
         let mut roles: Vec<CommitRole> = vec![];
         let mut senders: Vec<Sender<u32>> = Vec::with_capacity(self.concurrency_level - 1);
@@ -544,6 +780,7 @@ where
                         &scheduler,
                         base_view,
                         role,
+                        dependency_graph_recorder.as_ref(),
                     );
                 });
             }
@@ -554,26 +791,26 @@ where
         // TODO: for large block sizes and many cores, extract outputs in parallel.
         let mut final_results = Vec::with_capacity(num_txns);
 
-        let maybe_err = if last_input_output.module_publishing_may_race() {
-            counters::MODULE_PUBLISHING_FALLBACK_COUNT.inc();
-            Some(Error::ModulePathReadWrite)
-        } else {
-            let mut ret = None;
-            for idx in 0..num_txns {
-                match last_input_output.take_output(idx as TxnIndex) {
-                    ExecutionStatus::Success(t) => final_results.push(t),
-                    ExecutionStatus::SkipRest(t) => {
-                        final_results.push(t);
-                        break;
-                    },
-                    ExecutionStatus::Abort(err) => {
-                        ret = Some(err);
-                        break;
-                    },
-                };
-            }
-            ret
-        };
+        let mut maybe_err = None;
+        let mut num_committed = num_txns;
+        for idx in 0..num_txns {
+            match last_input_output.take_output(idx as TxnIndex) {
+                ExecutionStatus::Success(t) => final_results.push(t),
+                ExecutionStatus::SkipRest(t) => {
+                    final_results.push(t);
+                    num_committed = idx + 1;
+                    break;
+                },
+                ExecutionStatus::Abort(err) => {
+                    maybe_err = Some(err);
+                    num_committed = idx + 1;
+                    break;
+                },
+            };
+        }
+        if let Some(recorder) = dependency_graph_recorder {
+            recorder.finish(num_committed);
+        }
 
         self.executor_thread_pool.spawn(move || {
             // Explicit async drops.
@@ -605,18 +842,43 @@ where
         let mut ret = Vec::with_capacity(num_txns);
 
         let mut accumulated_fee_statement = FeeStatement::zero();
+        let mut effective_gas_tracker = self
+            .conflict_aware_gas_limit
+            .map(|limit| EffectiveGasTracker::new(limit, num_txns));
 
         for (idx, txn) in signature_verified_block.iter().enumerate() {
+            let mut view =
+                LatestView::<T, S, X>::new_btree_view(base_view, &data_map, idx as TxnIndex);
+            if effective_gas_tracker.is_some() {
+                view = view.with_unsync_read_capture();
+            }
+            // With the conflict-aware limit, deltas are materialized after execution, so that the
+            // base value reads don't count as reads of the transaction (parallel execution
+            // doesn't read them either).
             let res = executor.execute_transaction(
-                &LatestView::<T, S, X>::new_btree_view(base_view, &data_map, idx as TxnIndex),
+                &view,
                 txn,
                 idx as TxnIndex,
-                true,
+                effective_gas_tracker.is_none(),
             );
 
             let must_skip = matches!(res, ExecutionStatus::SkipRest(_));
             match res {
                 ExecutionStatus::Success(output) | ExecutionStatus::SkipRest(output) => {
+                    if let Some(tracker) = &mut effective_gas_tracker {
+                        let fee_statement = output.fee_statement();
+                        tracker.add_committed_txn(
+                            idx as TxnIndex,
+                            fee_statement.execution_gas_used() + fee_statement.io_gas_used(),
+                            view.take_unsync_reads().iter(),
+                            output
+                                .get_writes()
+                                .into_iter()
+                                .map(|(k, _)| k)
+                                .chain(output.get_deltas().into_iter().map(|(k, _)| k)),
+                        );
+                        output.materialize_deltas(&view);
+                    }
                     assert_eq!(
                         output.get_deltas().len(),
                         0,
@@ -671,6 +933,22 @@ where
                     break;
                 }
             }
+
+            if let Some(tracker) = &effective_gas_tracker {
+                if tracker.limit_reached() {
+                    counters::EXCEED_PER_BLOCK_GAS_LIMIT_COUNT
+                        .with_label_values(&[counters::Mode::SEQUENTIAL])
+                        .inc();
+                    info!(
+                        "[Execution]: Sequential execution early halted due to \
+                        effective_gas {} >= EFFECTIVE_BLOCK_GAS_LIMIT {}, {} txns committed.",
+                        tracker.effective_gas(),
+                        tracker.limit(),
+                        ret.len()
+                    );
+                    break;
+                }
+            }
         }
 
         if ret.len() == num_txns {
@@ -687,6 +965,12 @@ where
         }
 
         counters::update_sequential_block_gas_counters(&accumulated_fee_statement, ret.len());
+        if let Some(tracker) = &effective_gas_tracker {
+            counters::update_sequential_block_effective_gas_counters(
+                tracker.effective_gas(),
+                tracker.critical_path_gas(),
+            );
+        }
         ret.resize_with(num_txns, E::Output::skip_output);
         Ok(ret)
     }
@@ -697,7 +981,7 @@ where
         signature_verified_block: Vec<T>,
         base_view: &S,
     ) -> Result<Vec<E::Output>, E::Error> {
-        let mut ret = if self.concurrency_level > 1 {
+        let ret = if self.concurrency_level > 1 {
             self.execute_transactions_parallel(
                 executor_arguments,
                 &signature_verified_block,
@@ -710,20 +994,6 @@ where
                 base_view,
             )
         };
-
-        if matches!(ret, Err(Error::ModulePathReadWrite)) {
-            debug!("[Execution]: Module read & written, sequential fallback");
-
-            // All logs from the parallel execution should be cleared and not reported.
-            // Clear by re-initializing the speculative logs.
-            init_speculative_logs(signature_verified_block.len());
-
-            ret = self.execute_transactions_sequential(
-                executor_arguments,
-                &signature_verified_block,
-                base_view,
-            )
-        }
         self.executor_thread_pool.spawn(move || {
             // Explicit async drops.
             drop(signature_verified_block);
@@ -137,6 +137,8 @@ due to the ESTIMATE markers on memory locations, instead of waiting for a
 subsequent incarnation to finish.
 **/
 pub mod counters;
+pub mod dependency_graph;
+pub mod effective_gas;
 pub mod errors;
 pub mod executor;
 #[cfg(any(test, feature = "fuzzing"))]
@@ -3,7 +3,6 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    errors::Error,
     executor::BlockExecutor,
     proptest_types::{
         baseline::BaselineOutput,
@@ -15,7 +14,6 @@ use crate::{
     txn_commit_hook::NoOpTransactionCommitHook,
 };
 use aptos_types::executable::ExecutableTestType;
-use claims::assert_ok;
 use num_cpus;
 use proptest::{
     collection::vec,
@@ -79,11 +77,6 @@ fn run_transactions<K, V>(
         )
         .execute_transactions_parallel((), &transactions, &data_view);
 
-        if module_access.0 && module_access.1 {
-            assert_eq!(output.unwrap_err(), Error::ModulePathReadWrite);
-            continue;
-        }
-
         BaselineOutput::generate(&transactions, maybe_block_gas_limit).assert_output(&output);
     }
 }
@@ -299,10 +292,7 @@ fn dynamic_read_writes_contended_with_block_gas_limit(
     );
 }
 
-fn module_publishing_fallback_with_block_gas_limit(
-    num_txns: usize,
-    maybe_block_gas_limit: Option<u64>,
-) {
+fn module_publishing_with_block_gas_limit(num_txns: usize, maybe_block_gas_limit: Option<u64>) {
     let mut runner = TestRunner::default();
 
     let universe = vec(any::<[u8; 32]>(), 100)
@@ -406,7 +396,6 @@ fn publishing_fixed_params_with_block_gas_limit(
             .unwrap(),
     );
 
-    // Confirm still no intersection
     let output = BlockExecutor::<
         MockTransaction<KeyType<[u8; 32]>, ValueType<[u8; 32]>>,
         MockTask<KeyType<[u8; 32]>, ValueType<[u8; 32]>>,
@@ -420,7 +409,7 @@ fn publishing_fixed_params_with_block_gas_limit(
         None,
     )
     .execute_transactions_parallel((), &transactions, &data_view);
-    assert_ok!(output);
+    BaselineOutput::generate(&transactions, maybe_block_gas_limit).assert_output(&output);
 
     // Adjust the reads of txn indices[2] to contain module read to key 42.
     let r_index = indices[2].index(num_txns);
@@ -449,6 +438,7 @@ fn publishing_fixed_params_with_block_gas_limit(
             .unwrap(),
     );
 
+    let block_gas_limit = Some(max(w_index, r_index) as u64 * MAX_GAS_PER_TXN + 1);
     for _ in 0..200 {
         let output = BlockExecutor::<
             MockTransaction<KeyType<[u8; 32]>, ValueType<[u8; 32]>>,
@@ -459,12 +449,14 @@ fn publishing_fixed_params_with_block_gas_limit(
         >::new(
             num_cpus::get(),
             executor_thread_pool.clone(),
-            Some(max(w_index, r_index) as u64 * MAX_GAS_PER_TXN + 1),
+            // Ensure enough gas limit to commit the module txns (4 is maximum gas per txn)
+            block_gas_limit,
             None,
-        ) // Ensure enough gas limit to commit the module txns (4 is maximum gas per txn)
+        )
         .execute_transactions_parallel((), &transactions, &data_view);
 
-        assert_eq!(output.unwrap_err(), Error::ModulePathReadWrite);
+        // The module read and write now intersect, parallel execution must still succeed.
+        BaselineOutput::generate(&transactions, block_gas_limit).assert_output(&output);
     }
 }
 
@@ -489,8 +481,8 @@ fn dynamic_read_writes_contended() {
 }
 
 #[test]
-fn module_publishing_fallback() {
-    module_publishing_fallback_with_block_gas_limit(3000, None);
+fn module_publishing() {
+    module_publishing_with_block_gas_limit(3000, None);
 }
 
 #[test]
@@ -594,10 +586,10 @@ fn dynamic_read_writes_contended_with_block_gas_limit_test() {
 }
 
 #[test]
-fn module_publishing_fallback_with_block_gas_limit_test() {
-    module_publishing_fallback_with_block_gas_limit(
+fn module_publishing_with_block_gas_limit_test() {
+    module_publishing_with_block_gas_limit(
         3000,
-        // Need to execute at least 2 txns to trigger module publishing fallback
+        // Need to execute at least 2 txns for module reads and writes to intersect.
         Some(rand::thread_rng().gen_range(1, 3000 * MAX_GAS_PER_TXN / 2)),
     );
 }
@@ -7,6 +7,7 @@ use aptos_aggregator::{
     delta_change_set::{delta_add, delta_sub, serialize, DeltaOp},
     transaction::AggregatorValue,
 };
+use aptos_infallible::Mutex;
 use aptos_mvhashmap::types::TxnIndex;
 use aptos_state_view::{StateViewId, TStateView};
 use aptos_types::{
@@ -22,7 +23,7 @@ use once_cell::sync::OnceCell;
 use proptest::{arbitrary::Arbitrary, collection::vec, prelude::*, proptest, sample::Index};
 use proptest_derive::Arbitrary;
 use std::{
-    collections::{hash_map::DefaultHasher, BTreeSet},
+    collections::{hash_map::DefaultHasher, BTreeSet, HashMap},
     convert::TryInto,
     fmt::Debug,
     hash::{Hash, Hasher},
@@ -107,9 +108,8 @@ pub struct KeyType<K: Hash + Clone + Debug + PartialOrd + Ord + Eq>(
     /// Wrapping the types used for testing to add ModulePath trait implementation (below).
     pub K,
     /// The bool field determines for testing purposes, whether the key will be interpreted
-    /// as a module access path. In this case, if a module path is both read and written
-    /// during parallel execution, Error::ModulePathReadWrite must be returned and the
-    /// block execution must fall back to the sequential execution.
+    /// as a module access path. Module paths are stored in the multi-versioned modules
+    /// map, and reads of modules are validated by the hash of the written module.
     pub bool,
 );
 
@@ -505,40 +505,104 @@ where
         txn_idx: TxnIndex,
         _materialize_deltas: bool,
     ) -> ExecutionStatus<Self::Output, Self::Error> {
-        match txn {
-            MockTransaction::Write {
-                incarnation_counter,
-                incarnation_behaviors,
-            } => {
-                // Use incarnation counter value as an index to determine the read-
-                // and write-sets of the execution. Increment incarnation counter to
-                // simulate dynamic behavior when there are multiple possible read-
-                // and write-sets (i.e. each are selected round-robin).
-                let idx = incarnation_counter.fetch_add(1, Ordering::SeqCst);
-
-                let behavior = &incarnation_behaviors[idx % incarnation_behaviors.len()];
-
-                // Reads
-                let mut reads_result = vec![];
-                for k in behavior.reads.iter() {
-                    // TODO: later test errors as well? (by fixing state_view behavior).
-                    match view.get_state_value_bytes(k) {
-                        Ok(v) => reads_result.push(v),
-                        Err(_) => reads_result.push(None),
-                    }
-                }
-                ExecutionStatus::Success(MockOutput(
-                    behavior.writes.clone(),
-                    behavior.deltas.clone(),
-                    reads_result,
-                    OnceCell::new(),
-                    behavior.gas,
-                ))
-            },
-            MockTransaction::SkipRest => ExecutionStatus::SkipRest(MockOutput::skip_output()),
-            MockTransaction::Abort => ExecutionStatus::Abort(txn_idx as usize),
+        // TODO: later test errors as well? (by fixing state_view behavior).
+        execute_mock_transaction(txn, txn_idx, |k| {
+            view.get_state_value_bytes(k).ok().flatten()
+        })
+    }
+}
+
+/// Mock transaction executor that, like the VM, keeps the modules it read in a code cache and
+/// serves later module reads from the cache, without reading them through the view.
+pub(crate) struct CachingMockTask<K, V> {
+    code_cache: Mutex<HashMap<K, Option<Vec<u8>>>>,
+    code_cache_hits: Mutex<Vec<K>>,
+    phantom: PhantomData<V>,
+}
+
+impl<K, V> ExecutorTask for CachingMockTask<K, V>
+where
+    K: PartialOrd + Ord + Send + Sync + Clone + Hash + Eq + ModulePath + Debug + 'static,
+    V: Send + Sync + Debug + Clone + TransactionWrite + 'static,
+{
+    type Argument = ();
+    type Error = usize;
+    type Output = MockOutput<K, V>;
+    type Txn = MockTransaction<K, V>;
+
+    fn init(_argument: Self::Argument) -> Self {
+        Self {
+            code_cache: Mutex::new(HashMap::new()),
+            code_cache_hits: Mutex::new(vec![]),
+            phantom: PhantomData,
         }
     }
+
+    fn execute_transaction(
+        &self,
+        view: &impl TStateView<Key = K>,
+        txn: &Self::Txn,
+        txn_idx: TxnIndex,
+        _materialize_deltas: bool,
+    ) -> ExecutionStatus<Self::Output, Self::Error> {
+        execute_mock_transaction(txn, txn_idx, |k| {
+            if k.module_path().is_none() {
+                return view.get_state_value_bytes(k).ok().flatten();
+            }
+            if let Some(bytes) = self.code_cache.lock().get(k) {
+                self.code_cache_hits.lock().push(k.clone());
+                return bytes.clone();
+            }
+            let bytes = view.get_state_value_bytes(k).ok().flatten();
+            self.code_cache.lock().insert(k.clone(), bytes.clone());
+            bytes
+        })
+    }
+
+    fn flush_code_cache(&self) {
+        self.code_cache.lock().clear();
+    }
+
+    fn take_code_cache_hits(&self) -> Vec<K> {
+        std::mem::take(&mut *self.code_cache_hits.lock())
+    }
+}
+
+fn execute_mock_transaction<K, V>(
+    txn: &MockTransaction<K, V>,
+    txn_idx: TxnIndex,
+    mut read: impl FnMut(&K) -> Option<Vec<u8>>,
+) -> ExecutionStatus<MockOutput<K, V>, usize>
+where
+    K: PartialOrd + Ord + Send + Sync + Clone + Hash + Eq + ModulePath + Debug + 'static,
+    V: Send + Sync + Debug + Clone + TransactionWrite + 'static,
+{
+    match txn {
+        MockTransaction::Write {
+            incarnation_counter,
+            incarnation_behaviors,
+        } => {
+            // Use incarnation counter value as an index to determine the read-
+            // and write-sets of the execution. Increment incarnation counter to
+            // simulate dynamic behavior when there are multiple possible read-
+            // and write-sets (i.e. each are selected round-robin).
+            let idx = incarnation_counter.fetch_add(1, Ordering::SeqCst);
+
+            let behavior = &incarnation_behaviors[idx % incarnation_behaviors.len()];
+
+            // Reads
+            let reads_result = behavior.reads.iter().map(&mut read).collect();
+            ExecutionStatus::Success(MockOutput(
+                behavior.writes.clone(),
+                behavior.deltas.clone(),
+                reads_result,
+                OnceCell::new(),
+                behavior.gas,
+            ))
+        },
+        MockTransaction::SkipRest => ExecutionStatus::SkipRest(MockOutput::skip_output()),
+        MockTransaction::Abort => ExecutionStatus::Abort(txn_idx as usize),
+    }
 }
 
 #[derive(Debug)]
@@ -580,6 +644,13 @@ where
         assert_ok!(self.3.set(delta_writes));
     }
 
+    fn materialize_deltas(&self, _view: &impl TStateView<Key = K>) {
+        assert!(
+            self.1.is_empty(),
+            "Mock deltas can only be materialized in parallel execution"
+        );
+    }
+
     fn fee_statement(&self) -> FeeStatement {
         // First argument is supposed to be total (not important for the test though).
         // Next two arguments are different kinds of execution gas that are counted
@@ -65,6 +65,18 @@ pub trait ExecutorTask: Sync {
         txn_idx: TxnIndex,
         materialize_deltas: bool,
     ) -> ExecutionStatus<Self::Output, Self::Error>;
+
+    /// Invalidates any code the executor caches across transactions (e.g. the Move-VM loader
+    /// cache). Called by the parallel executor before an execution whenever modules in the
+    /// multi-versioned data-structure may have changed since the cache was populated.
+    fn flush_code_cache(&self) {}
+
+    /// Returns (and clears) the keys of modules that the last execution used from the executor's
+    /// own code cache, without reading them through the provided view. The parallel executor
+    /// adds these modules to the read-set of the transaction, so they get validated.
+    fn take_code_cache_hits(&self) -> Vec<<Self::Txn as Transaction>::Key> {
+        vec![]
+    }
 }
 
 /// Trait for execution result of a single transaction.
@@ -94,6 +106,10 @@ pub trait TransactionOutput: Send + Sync + Debug {
         delta_writes: Vec<(<Self::Txn as Transaction>::Key, WriteOp)>,
     );
 
+    /// Converts the deltas of the output into writes, resolving the base values from `view`.
+    /// Used by sequential execution when the deltas were not materialized during execution.
+    fn materialize_deltas(&self, view: &impl TStateView<Key = <Self::Txn as Transaction>::Key>);
+
     /// Return the fee statement of the transaction.
     fn fee_statement(&self) -> FeeStatement;
 }
@@ -5,23 +5,16 @@ use crate::{
     errors::Error,
     task::{ExecutionStatus, Transaction, TransactionOutput},
 };
-use anyhow::anyhow;
+use aptos_crypto::HashValue;
 use aptos_mvhashmap::types::{Incarnation, TxnIndex, Version};
-use aptos_types::{
-    access_path::AccessPath, executable::ModulePath, fee_statement::FeeStatement,
-    write_set::WriteOp,
-};
+use aptos_types::{executable::ModulePath, fee_statement::FeeStatement, write_set::WriteOp};
 use arc_swap::ArcSwapOption;
 use crossbeam::utils::CachePadded;
-use dashmap::DashSet;
 use std::{
     collections::HashSet,
     fmt::Debug,
     iter::{empty, Iterator},
-    sync::{
-        atomic::{AtomicBool, Ordering},
-        Arc,
-    },
+    sync::Arc,
 };
 
 type TxnInput<K> = Vec<ReadDescriptor<K>>;
@@ -56,6 +49,9 @@ enum ReadKind {
     Storage,
     /// Read triggered a delta application failure.
     DeltaApplicationFailure,
+    /// Read returned a module from the multi-version data-structure, identified by the
+    /// hash of its contents (so re-publishing the same code does not fail validation).
+    Module(HashValue),
 }
 
 #[derive(Clone)]
@@ -94,14 +90,30 @@ impl<K: ModulePath> ReadDescriptor<K> {
         }
     }
 
-    fn module_path(&self) -> Option<AccessPath> {
-        self.access_path.module_path()
+    pub fn from_module(access_path: K, hash: HashValue) -> Self {
+        Self {
+            access_path,
+            kind: ReadKind::Module(hash),
+        }
+    }
+
+    pub fn is_module_read(&self) -> bool {
+        self.access_path.module_path().is_some()
     }
 
     pub fn path(&self) -> &K {
         &self.access_path
     }
 
+    // The version of the write the read returned, if it was read from the multi-version
+    // data-structure.
+    pub(crate) fn version(&self) -> Option<Version> {
+        match self.kind {
+            ReadKind::Version(txn_idx, incarnation) => Some((txn_idx, incarnation)),
+            _ => None,
+        }
+    }
+
     // Does the read descriptor describe a read from MVHashMap w. a specified version.
     pub fn validate_version(&self, version: Version) -> bool {
         let (txn_idx, incarnation) = version;
@@ -122,20 +134,17 @@ impl<K: ModulePath> ReadDescriptor<K> {
     pub fn validate_delta_application_failure(&self) -> bool {
         self.kind == ReadKind::DeltaApplicationFailure
     }
+
+    // Does the read descriptor describe a read of a module with the given hash from MVHashMap.
+    pub fn validate_module(&self, hash: HashValue) -> bool {
+        self.kind == ReadKind::Module(hash)
+    }
 }
 
 pub struct TxnLastInputOutput<K, T: TransactionOutput, E: Debug> {
     inputs: Vec<CachePadded<ArcSwapOption<TxnInput<K>>>>, // txn_idx -> input.
 
     outputs: Vec<CachePadded<ArcSwapOption<TxnOutput<T, E>>>>, // txn_idx -> output.
-
-    // Record all writes and reads to access paths corresponding to modules (code) in any
-    // (speculative) executions. Used to avoid a potential race with module publishing and
-    // Move-VM loader cache - see 'record' function comment for more information.
-    module_writes: DashSet<AccessPath>,
-    module_reads: DashSet<AccessPath>,
-
-    module_read_write_intersection: AtomicBool,
 }
 
 impl<K: ModulePath, T: TransactionOutput, E: Debug + Send + Clone> TxnLastInputOutput<K, T, E> {
@@ -147,78 +156,17 @@ impl<K: ModulePath, T: TransactionOutput, E: Debug + Send + Clone> TxnLastInputO
             outputs: (0..num_txns)
                 .map(|_| CachePadded::new(ArcSwapOption::empty()))
                 .collect(),
-            module_writes: DashSet::new(),
-            module_reads: DashSet::new(),
-            module_read_write_intersection: AtomicBool::new(false),
-        }
-    }
-
-    fn append_and_check(
-        paths: Vec<AccessPath>,
-        set_to_append: &DashSet<AccessPath>,
-        set_to_check: &DashSet<AccessPath>,
-    ) -> bool {
-        for path in paths {
-            // Standard flags, first show, then look.
-            set_to_append.insert(path.clone());
-
-            if set_to_check.contains(&path) {
-                return true;
-            }
         }
-        false
     }
 
-    /// Returns an error if a module path that was read was previously written to, and vice versa.
-    /// Since parallel executor is instantiated per block, any module that is in the Move-VM loader
-    /// cache must previously be read and would be recorded in the 'module_reads' set. Any module
-    /// that is written (published or re-published) goes through transaction output write-set and
-    /// gets recorded in the 'module_writes' set. If these sets have an intersection, it is currently
-    /// possible that Move-VM loader cache loads a module and incorrectly uses it for another
-    /// transaction (e.g. a smaller transaction, or if the speculative execution of the publishing
-    /// transaction later aborts). The intersection is guaranteed to be found because we first
-    /// record the paths then check the other set (flags principle), and in this case we return an
-    /// error that ensures a fallback to a correct sequential execution.
-    /// When the sets do not have an intersection, it is impossible for the race to occur as any
-    /// module in the loader cache may not be published by a transaction in the ongoing block.
     pub(crate) fn record(
         &self,
         txn_idx: TxnIndex,
         input: Vec<ReadDescriptor<K>>,
         output: ExecutionStatus<T, Error<E>>,
-    ) -> anyhow::Result<()> {
-        let read_modules: Vec<AccessPath> =
-            input.iter().filter_map(|desc| desc.module_path()).collect();
-        let written_modules: Vec<AccessPath> = match &output {
-            ExecutionStatus::Success(output) | ExecutionStatus::SkipRest(output) => output
-                .get_writes()
-                .into_iter()
-                .filter_map(|(k, _)| k.module_path())
-                .collect(),
-            ExecutionStatus::Abort(_) => Vec::new(),
-        };
-
-        if !self.module_read_write_intersection.load(Ordering::Relaxed) {
-            // Check if adding new read & write modules leads to intersections.
-            if Self::append_and_check(read_modules, &self.module_reads, &self.module_writes)
-                || Self::append_and_check(written_modules, &self.module_writes, &self.module_reads)
-            {
-                self.module_read_write_intersection
-                    .store(true, Ordering::Release);
-                return Err(anyhow!(
-                    "[BlockSTM]: Detect module r/w intersection, will fallback to sequential execution"
-                ));
-            }
-        }
-
+    ) {
         self.inputs[txn_idx as usize].store(Some(Arc::new(input)));
         self.outputs[txn_idx as usize].store(Some(Arc::new(TxnOutput::from_output_status(output))));
-
-        Ok(())
-    }
-
-    pub(crate) fn module_publishing_may_race(&self) -> bool {
-        self.module_read_write_intersection.load(Ordering::Acquire)
     }
 
     pub(crate) fn read_set(&self, txn_idx: TxnIndex) -> Option<Arc<Vec<ReadDescriptor<K>>>> {
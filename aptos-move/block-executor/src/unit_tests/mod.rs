@@ -3,15 +3,18 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    dependency_graph::take_recorded_dependency_graphs,
+    effective_gas::ConflictAwareGasLimit,
     executor::BlockExecutor,
     proptest_types::{
         baseline::BaselineOutput,
         types::{
-            DeltaDataView, KeyType, MockIncarnation, MockOutput, MockTask, MockTransaction,
-            ValueType,
+            CachingMockTask, DeltaDataView, KeyType, MockIncarnation, MockOutput, MockTask,
+            MockTransaction, ValueType,
         },
     },
     scheduler::{DependencyResult, ExecutionTaskType, Scheduler, SchedulerTask},
+    task::ExecutorTask,
     txn_commit_hook::NoOpTransactionCommitHook,
 };
 use aptos_aggregator::delta_change_set::{delta_add, delta_sub, DeltaOp, DeltaUpdate};
@@ -32,6 +35,20 @@ fn run_and_assert<K, V>(transactions: Vec<MockTransaction<K, V>>)
 where
     K: PartialOrd + Ord + Send + Sync + Clone + Hash + Eq + ModulePath + Debug + 'static,
     V: Send + Sync + Debug + Clone + Eq + TransactionWrite + 'static,
+{
+    run_and_assert_with_task::<K, V, MockTask<K, V>>(transactions)
+}
+
+fn run_and_assert_with_task<K, V, E>(transactions: Vec<MockTransaction<K, V>>)
+where
+    K: PartialOrd + Ord + Send + Sync + Clone + Hash + Eq + ModulePath + Debug + 'static,
+    V: Send + Sync + Debug + Clone + Eq + TransactionWrite + 'static,
+    E: ExecutorTask<
+        Txn = MockTransaction<K, V>,
+        Output = MockOutput<K, V>,
+        Error = usize,
+        Argument = (),
+    >,
 {
     let data_view = DeltaDataView::<K, V> {
         phantom: PhantomData,
@@ -46,7 +63,7 @@ where
 
     let output = BlockExecutor::<
         MockTransaction<K, V>,
-        MockTask<K, V>,
+        E,
         DeltaDataView<K, V>,
         NoOpTransactionCommitHook<MockOutput<K, V>, usize>,
         ExecutableTestType,
@@ -106,6 +123,38 @@ fn delta_counters() {
     run_and_assert(transactions)
 }
 
+#[test]
+fn module_publishing_with_code_cache() {
+    // Every 10th transaction publishes a new version of the module, the others read it. The
+    // executor serves the module reads from its code cache, which must be flushed whenever a
+    // cached version becomes outdated for the executed transaction (also when it was loaded by
+    // a transaction with a higher index), as otherwise the execution would never finish.
+    let module_key = KeyType(random::<[u8; 32]>(), true);
+    let transactions: Vec<_> = (0..1000)
+        .map(|i| {
+            MockTransaction::<KeyType<[u8; 32]>, ValueType<Vec<u8>>>::from_behavior(
+                if i % 10 == 0 {
+                    MockIncarnation {
+                        reads: vec![],
+                        writes: vec![(module_key, random_value(false))],
+                        deltas: vec![],
+                        gas: 1,
+                    }
+                } else {
+                    MockIncarnation {
+                        reads: vec![module_key],
+                        writes: vec![],
+                        deltas: vec![],
+                        gas: 1,
+                    }
+                },
+            )
+        })
+        .collect();
+
+    run_and_assert_with_task::<_, _, CachingMockTask<_, _>>(transactions)
+}
+
 #[test]
 fn delta_chains() {
     let mut transactions = vec![];
@@ -174,6 +223,144 @@ fn cycle_transactions() {
     run_and_assert(transactions)
 }
 
+#[test]
+fn dependency_graph_recording() {
+    let num_txns = 50;
+    let key = KeyType(random::<[u8; 32]>(), false);
+    // Every transaction reads the value written by the previous one.
+    let transactions: Vec<_> = (0..num_txns)
+        .map(|_| {
+            MockTransaction::from_behavior(MockIncarnation {
+                reads: vec![key],
+                writes: vec![(key, random_value(false))],
+                deltas: vec![],
+                gas: 1,
+            })
+        })
+        .collect();
+
+    let data_view = DeltaDataView::<KeyType<[u8; 32]>, ValueType<Vec<u8>>> {
+        phantom: PhantomData,
+    };
+    let executor_thread_pool = Arc::new(
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(num_cpus::get())
+            .build()
+            .unwrap(),
+    );
+    let output = BlockExecutor::<
+        MockTransaction<KeyType<[u8; 32]>, ValueType<Vec<u8>>>,
+        MockTask<KeyType<[u8; 32]>, ValueType<Vec<u8>>>,
+        DeltaDataView<KeyType<[u8; 32]>, ValueType<Vec<u8>>>,
+        NoOpTransactionCommitHook<MockOutput<KeyType<[u8; 32]>, ValueType<Vec<u8>>>, usize>,
+        ExecutableTestType,
+    >::new(num_cpus::get(), executor_thread_pool, None, None)
+    .with_dependency_graph_recording(true)
+    .execute_transactions_parallel((), &transactions, &data_view);
+    BaselineOutput::generate(&transactions, None).assert_output(&output);
+
+    // Other tests don't record, so this is the only graph.
+    let graphs = take_recorded_dependency_graphs();
+    assert_eq!(graphs.len(), 1);
+    let graph = &graphs[0];
+    assert_eq!(graph.num_committed_txns, num_txns);
+    for (idx, txn) in graph.transactions.iter().enumerate() {
+        let expected_dependencies: Vec<TxnIndex> = if idx == 0 {
+            vec![]
+        } else {
+            vec![idx as TxnIndex - 1]
+        };
+        assert_eq!(txn.dependencies, expected_dependencies);
+
+        let (committed, aborted) = txn.incarnations.split_last().unwrap();
+        assert!(!committed.aborted);
+        assert_eq!(committed.writes, vec![format!("{:?}", key)]);
+        assert!(aborted.iter().all(|incarnation| incarnation.aborted));
+    }
+    assert!(graph.to_dot().contains("  0 -> 1;"));
+}
+
+// Returns the number of committed txns in parallel and in sequential execution.
+fn run_with_conflict_aware_gas_limit(
+    transactions: &Vec<MockTransaction<KeyType<[u8; 32]>, ValueType<Vec<u8>>>>,
+    limit: ConflictAwareGasLimit,
+) -> (usize, usize) {
+    let data_view = DeltaDataView::<KeyType<[u8; 32]>, ValueType<Vec<u8>>> {
+        phantom: PhantomData,
+    };
+    let executor_thread_pool = Arc::new(
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(num_cpus::get())
+            .build()
+            .unwrap(),
+    );
+    let executor = |concurrency_level| {
+        BlockExecutor::<
+            MockTransaction<KeyType<[u8; 32]>, ValueType<Vec<u8>>>,
+            MockTask<KeyType<[u8; 32]>, ValueType<Vec<u8>>>,
+            DeltaDataView<KeyType<[u8; 32]>, ValueType<Vec<u8>>>,
+            NoOpTransactionCommitHook<MockOutput<KeyType<[u8; 32]>, ValueType<Vec<u8>>>, usize>,
+            ExecutableTestType,
+        >::new(concurrency_level, executor_thread_pool.clone(), None, None)
+        .with_conflict_aware_gas_limit(limit)
+    };
+    // Every txn writes, so the skipped ones are the outputs without writes.
+    let num_committed = |outputs: Vec<MockOutput<_, _>>| {
+        outputs
+            .iter()
+            .take_while(|output| !output.0.is_empty())
+            .count()
+    };
+
+    let parallel = executor(num_cpus::get())
+        .execute_transactions_parallel((), transactions, &data_view)
+        .unwrap();
+    let sequential = executor(1)
+        .execute_transactions_sequential((), transactions, &data_view)
+        .unwrap();
+    (num_committed(parallel), num_committed(sequential))
+}
+
+#[test]
+fn conflict_aware_gas_limit() {
+    let limit = ConflictAwareGasLimit {
+        effective_block_gas_limit: 150,
+        parallelism: 4,
+    };
+    let hot_key = KeyType(random::<[u8; 32]>(), false);
+
+    // A chain of txns that each read the value written by the previous one: the effective gas
+    // grows by the gas of every txn, so the limit is reached after 15 txns.
+    let chain: Vec<_> = (0..40)
+        .map(|_| {
+            MockTransaction::from_behavior(MockIncarnation {
+                reads: vec![hot_key],
+                writes: vec![(hot_key, random_value(false))],
+                deltas: vec![],
+                gas: 10,
+            })
+        })
+        .collect();
+    assert_eq!(run_with_conflict_aware_gas_limit(&chain, limit), (15, 15));
+
+    // Independent txns only count for a quarter of their gas, so all of them fit.
+    let independent: Vec<_> = (0..40)
+        .map(|_| {
+            let key = KeyType(random::<[u8; 32]>(), false);
+            MockTransaction::from_behavior(MockIncarnation {
+                reads: vec![key],
+                writes: vec![(key, random_value(false))],
+                deltas: vec![],
+                gas: 10,
+            })
+        })
+        .collect();
+    assert_eq!(
+        run_with_conflict_aware_gas_limit(&independent, limit),
+        (40, 40)
+    );
+}
+
 const NUM_BLOCKS: u64 = 10;
 const TXN_PER_BLOCK: u64 = 100;
 
@@ -74,19 +74,67 @@ impl<
         self.captured_reads.take()
     }
 
-    // TODO: Actually fill in the logic to record fetched executables, etc.
-    fn fetch_module(
-        &self,
-        key: &K,
-        txn_idx: TxnIndex,
-    ) -> anyhow::Result<MVModulesOutput<V, X>, MVModulesError> {
-        // Add a fake read from storage to register in reads for now in order
-        // for the read / write path intersection fallback for modules to still work.
-        self.captured_reads
-            .borrow_mut()
-            .push(ReadDescriptor::from_storage(key.clone()));
+    /// Captures a read of a module from the VM execution. On encountering an estimate, waits for
+    /// the dependency to be resolved (like data reads), so the VM never observes an outdated module.
+    fn fetch_module(&self, key: &K, txn_idx: TxnIndex) -> ReadResult<V> {
+        use MVModulesError::*;
+        use MVModulesOutput::*;
 
-        self.versioned_map.fetch_module(key, txn_idx)
+        loop {
+            match self.versioned_map.fetch_module(key, txn_idx) {
+                Ok(Module((v, hash))) => {
+                    self.captured_reads
+                        .borrow_mut()
+                        .push(ReadDescriptor::from_module(key.clone(), hash));
+                    return ReadResult::Value(v);
+                },
+                Ok(Executable(_)) => unreachable!("Versioned executable not implemented"),
+                Err(NotFound) => {
+                    self.captured_reads
+                        .borrow_mut()
+                        .push(ReadDescriptor::from_storage(key.clone()));
+                    return ReadResult::None;
+                },
+                Err(Dependency(dep_idx)) => {
+                    if !self.wait_for_dependency(txn_idx, dep_idx) {
+                        return ReadResult::ExecutionHalted;
+                    }
+                },
+            }
+        }
+    }
+
+    /// Waits until the dependency of `txn_idx` on `dep_idx` is resolved. Returns false if the
+    /// parallel execution was halted in the meantime.
+    fn wait_for_dependency(&self, txn_idx: TxnIndex, dep_idx: TxnIndex) -> bool {
+        // `self.txn_idx` estimated to depend on a write from `dep_idx`.
+        match self.scheduler.wait_for_dependency(txn_idx, dep_idx) {
+            DependencyResult::Dependency(dep_condition) => {
+                let _timer = counters::DEPENDENCY_WAIT_SECONDS.start_timer();
+                // Wait on a condition variable corresponding to the encountered
+                // read dependency. Once the dep_idx finishes re-execution, scheduler
+                // will mark the dependency as resolved, and then the txn_idx will be
+                // scheduled for re-execution, which will re-awaken cvar here.
+                // A deadlock is not possible due to these condition variables:
+                // suppose all threads are waiting on read dependency, and consider
+                // one with lowest txn_idx. It observed a dependency, so some thread
+                // aborted dep_idx. If that abort returned execution task, by
+                // minimality (lower transactions aren't waiting), that thread would
+                // finish execution unblock txn_idx, contradiction. Otherwise,
+                // execution_idx in scheduler was lower at a time when at least the
+                // thread that aborted dep_idx was alive, and again, since lower txns
+                // than txn_idx are not blocked, so the execution of dep_idx will
+                // eventually finish and lead to unblocking txn_idx, contradiction.
+                let (lock, cvar) = &*dep_condition;
+                let mut dep_resolved = lock.lock();
+                while let DependencyStatus::Unresolved = *dep_resolved {
+                    dep_resolved = cvar.wait(dep_resolved).unwrap();
+                }
+                !matches!(*dep_resolved, DependencyStatus::ExecutionHalted)
+            },
+            DependencyResult::ExecutionHalted => false,
+            DependencyResult::Resolved => true,
+        }
     }
 
     fn set_aggregator_base_value(&self, key: &K, value: u128) {
@@ -122,37 +170,8 @@ impl<
                 },
                 Err(Unresolved(_)) => return ReadResult::Unresolved,
                 Err(Dependency(dep_idx)) => {
-                    // `self.txn_idx` estimated to depend on a write from `dep_idx`.
-                    match self.scheduler.wait_for_dependency(txn_idx, dep_idx) {
-                        DependencyResult::Dependency(dep_condition) => {
-                            let _timer = counters::DEPENDENCY_WAIT_SECONDS.start_timer();
-                            // Wait on a condition variable corresponding to the encountered
-                            // read dependency. Once the dep_idx finishes re-execution, scheduler
-                            // will mark the dependency as resolved, and then the txn_idx will be
-                            // scheduled for re-execution, which will re-awaken cvar here.
-                            // A deadlock is not possible due to these condition variables:
-                            // suppose all threads are waiting on read dependency, and consider
-                            // one with lowest txn_idx. It observed a dependency, so some thread
-                            // aborted dep_idx. If that abort returned execution task, by
-                            // minimality (lower transactions aren't waiting), that thread would
-                            // finish execution unblock txn_idx, contradiction. Otherwise,
-                            // execution_idx in scheduler was lower at a time when at least the
-                            // thread that aborted dep_idx was alive, and again, since lower txns
-                            // than txn_idx are not blocked, so the execution of dep_idx will
-                            // eventually finish and lead to unblocking txn_idx, contradiction.
-                            let (lock, cvar) = &*dep_condition;
-                            let mut dep_resolved = lock.lock();
-                            while let DependencyStatus::Unresolved = *dep_resolved {
-                                dep_resolved = cvar.wait(dep_resolved).unwrap();
-                            }
-                            if let DependencyStatus::ExecutionHalted = *dep_resolved {
-                                return ReadResult::ExecutionHalted;
-                            }
-                        },
-                        DependencyResult::ExecutionHalted => {
-                            return ReadResult::ExecutionHalted;
-                        },
-                        DependencyResult::Resolved => continue,
+                    if !self.wait_for_dependency(txn_idx, dep_idx) {
+                        return ReadResult::ExecutionHalted;
                     }
                 },
                 Err(DeltaApplicationFailure) => {
@@ -178,6 +197,9 @@ pub(crate) struct LatestView<'a, T: Transaction, S: TStateView<Key = T::Key>, X:
     base_view: &'a S,
     latest_view: ViewMapKind<'a, T, X>,
     txn_idx: TxnIndex,
+    // Keys read through the view in sequential execution, if requested. Parallel execution
+    // captures the reads in the MVHashMapView instead.
+    captured_unsync_reads: Option<RefCell<Vec<T::Key>>>,
 }
 
 impl<'a, T: Transaction, S: TStateView<Key = T::Key>, X: Executable> LatestView<'a, T, S, X> {
@@ -190,6 +212,7 @@ impl<'a, T: Transaction, S: TStateView<Key = T::Key>, X: Executable> LatestView<
             base_view,
             latest_view: ViewMapKind::MultiVersion(map),
             txn_idx,
+            captured_unsync_reads: None,
         }
     }
 
@@ -202,9 +225,24 @@ impl<'a, T: Transaction, S: TStateView<Key = T::Key>, X: Executable> LatestView<
             base_view,
             latest_view: ViewMapKind::Unsync(map),
             txn_idx,
+            captured_unsync_reads: None,
         }
     }
 
+    /// Makes the view record the keys read in sequential execution, see `take_unsync_reads`.
+    pub(crate) fn with_unsync_read_capture(mut self) -> Self {
+        self.captured_unsync_reads = Some(RefCell::new(Vec::new()));
+        self
+    }
+
+    /// Drains the keys read so far, empty if the reads are not captured.
+    pub(crate) fn take_unsync_reads(&self) -> Vec<T::Key> {
+        self.captured_unsync_reads
+            .as_ref()
+            .map(|reads| reads.take())
+            .unwrap_or_default()
+    }
+
     fn get_base_value(&self, state_key: &T::Key) -> anyhow::Result<Option<StateValue>> {
         let ret = self.base_view.get_state_value(state_key);
 
@@ -222,6 +260,17 @@ impl<'a, T: Transaction, S: TStateView<Key = T::Key>, X: Executable> LatestView<
     }
 }
 
+// ExecutionHalted indicates that the parallel execution is halted. The read should return
+// immediately and log the error. For now we use STORAGE_ERROR as the VM will not log the
+// speculative eror, so no actual error will be logged once the execution is halted and the
+// speculative logging is flushed.
+fn halted_error() -> anyhow::Error {
+    anyhow::Error::new(VMStatus::error(
+        StatusCode::STORAGE_ERROR,
+        Some("Speculative error to halt BlockSTM early.".to_string()),
+    ))
+}
+
 impl<'a, T: Transaction, S: TStateView<Key = T::Key>, X: Executable> TStateView
     for LatestView<'a, T, S, X>
 {
@@ -230,20 +279,13 @@ impl<'a, T: Transaction, S: TStateView<Key = T::Key>, X: Executable> TStateView
     fn get_state_value(&self, state_key: &T::Key) -> anyhow::Result<Option<StateValue>> {
         match self.latest_view {
             ViewMapKind::MultiVersion(map) => match state_key.module_path() {
-                Some(_) => {
-                    use MVModulesError::*;
-                    use MVModulesOutput::*;
-
-                    match map.fetch_module(state_key, self.txn_idx) {
-                        Ok(Executable(_)) => unreachable!("Versioned executable not implemented"),
-                        Ok(Module((v, _))) => Ok(v.as_state_value()),
-                        Err(Dependency(_)) => {
-                            // Return anything (e.g. module does not exist) to avoid waiting,
-                            // because parallel execution will fall back to sequential anyway.
-                            Ok(None)
-                        },
-                        Err(NotFound) => self.base_view.get_state_value(state_key),
-                    }
+                Some(_) => match map.fetch_module(state_key, self.txn_idx) {
+                    ReadResult::Value(v) => Ok(v.as_state_value()),
+                    ReadResult::ExecutionHalted => Err(halted_error()),
+                    ReadResult::None => self.get_base_value(state_key),
+                    ReadResult::U128(_) | ReadResult::Unresolved => {
+                        unreachable!("Module reads do not resolve deltas")
+                    },
                 },
                 None => {
                     let mut mv_value = map.fetch_data(state_key, self.txn_idx);
@@ -265,15 +307,7 @@ impl<'a, T: Transaction, S: TStateView<Key = T::Key>, X: Executable> TStateView
                     match mv_value {
                         ReadResult::Value(v) => Ok(v.as_state_value()),
                         ReadResult::U128(v) => Ok(Some(StateValue::new_legacy(serialize(&v)))),
-                        // ExecutionHalted indicates that the parallel execution is halted.
-                        // The read should return immediately and log the error.
-                        // For now we use STORAGE_ERROR as the VM will not log the speculative eror,
-                        // so no actual error will be logged once the execution is halted and
-                        // the speculative logging is flushed.
-                        ReadResult::ExecutionHalted => Err(anyhow::Error::new(VMStatus::error(
-                            StatusCode::STORAGE_ERROR,
-                            Some("Speculative error to halt BlockSTM early.".to_string()),
-                        ))),
+                        ReadResult::ExecutionHalted => Err(halted_error()),
                         ReadResult::None => self.get_base_value(state_key),
                         ReadResult::Unresolved => unreachable!(
                             "Must be resolved as base value is recorded in the MV data structure"
@@ -281,10 +315,15 @@ impl<'a, T: Transaction, S: TStateView<Key = T::Key>, X: Executable> TStateView
                     }
                 },
             },
-            ViewMapKind::Unsync(map) => map.fetch_data(state_key).map_or_else(
-                || self.get_base_value(state_key),
-                |v| Ok(v.as_state_value()),
-            ),
+            ViewMapKind::Unsync(map) => {
+                if let Some(reads) = &self.captured_unsync_reads {
+                    reads.borrow_mut().push(state_key.clone());
+                }
+                map.fetch_data(state_key).map_or_else(
+                    || self.get_base_value(state_key),
+                    |v| Ok(v.as_state_value()),
+                )
+            },
         }
     }
 
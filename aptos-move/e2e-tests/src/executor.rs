@@ -32,7 +32,8 @@ use aptos_types::{
     block_metadata::BlockMetadata,
     chain_id::ChainId,
     on_chain_config::{
-        Features, OnChainConfig, TimedFeatureOverride, TimedFeatures, ValidatorSet, Version,
+        BlockGasLimitType, Features, OnChainConfig, TimedFeatureOverride, TimedFeatures,
+        ValidatorSet, Version,
     },
     state_store::{state_key::StateKey, state_value::StateValue},
     transaction::{
@@ -420,7 +421,7 @@ impl FakeExecutor {
             txn_block,
             &self.data_store,
             usize::min(4, num_cpus::get()),
-            None,
+            BlockGasLimitType::NoLimit,
             None,
         )
     }
@@ -442,7 +443,11 @@ impl FakeExecutor {
             }
         }
 
-        let output = AptosVM::execute_block(txn_block.clone(), &self.data_store, None);
+        let output = AptosVM::execute_block(
+            txn_block.clone(),
+            &self.data_store,
+            BlockGasLimitType::NoLimit,
+        );
         if !self.no_parallel_exec {
             let parallel_output = self.execute_transaction_block_parallel(txn_block);
             assert_eq!(output, parallel_output);
@@ -143,6 +143,13 @@ impl<K: ModulePath + Hash + Clone + Eq + Debug, V: TransactionWrite, X: Executab
     ) -> anyhow::Result<MVModulesOutput<V, X>, MVModulesError> {
         self.modules.fetch_module(key, txn_idx)
     }
+
+    /// Returns a counter of all changes applied to the multi-versioned modules so far. If the
+    /// value did not change between two calls, no module was written, deleted or marked as an
+    /// estimate in between, and modules cached outside of the data-structure are still up to date.
+    pub fn num_module_changes(&self) -> u64 {
+        self.modules.num_changes()
+    }
 }
 
 impl<K: ModulePath + Hash + Clone + Debug + Eq, V: TransactionWrite, X: Executable> Default
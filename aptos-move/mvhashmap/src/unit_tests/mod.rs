@@ -284,3 +284,42 @@ fn commit_without_entry() {
     // Must panic as there is no delta at provided index.
     let _ = vd.materialize_delta(&ap, 9);
 }
+
+#[test]
+fn versioned_modules_basic() {
+    use MVModulesError::*;
+    use MVModulesOutput::*;
+
+    let vm: VersionedModules<KeyType<Vec<u8>>, Value, ExecutableTestType> = VersionedModules::new();
+    let ap = KeyType(b"/foo/m".to_vec());
+
+    assert_eq!(vm.num_changes(), 0);
+    assert!(matches!(vm.fetch_module(&ap, 5), Err(NotFound)));
+
+    vm.write(ap.clone(), 3, value_for(3, 0));
+    assert_eq!(vm.num_changes(), 1);
+    // Module writes are not visible to the writing or earlier transactions.
+    assert!(matches!(vm.fetch_module(&ap, 3), Err(NotFound)));
+    let hash = match vm.fetch_module(&ap, 5) {
+        Ok(Module((module, hash))) => {
+            assert_eq!(module, arc_value_for(3, 0));
+            hash
+        },
+        _ => unreachable!(),
+    };
+
+    // Re-writing the same module produces the same hash, different module - different hash.
+    vm.write(ap.clone(), 3, value_for(3, 0));
+    assert!(matches!(vm.fetch_module(&ap, 5), Ok(Module((_, h))) if h == hash));
+    vm.write(ap.clone(), 3, value_for(3, 1));
+    assert!(matches!(vm.fetch_module(&ap, 5), Ok(Module((_, h))) if h != hash));
+    assert_eq!(vm.num_changes(), 3);
+
+    vm.mark_estimate(&ap, 3);
+    assert!(matches!(vm.fetch_module(&ap, 5), Err(Dependency(3))));
+    assert_eq!(vm.num_changes(), 4);
+
+    vm.delete(&ap, 3);
+    assert!(matches!(vm.fetch_module(&ap, 5), Err(NotFound)));
+    assert_eq!(vm.num_changes(), 5);
+}
@@ -12,7 +12,10 @@ use dashmap::DashMap;
 use std::{
     collections::{btree_map::BTreeMap, HashMap},
     hash::Hash,
-    sync::Arc,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
 };
 
 /// Every entry in shared multi-version data-structure has an "estimate" flag
@@ -42,6 +45,11 @@ struct VersionedValue<V: TransactionWrite, X: Executable> {
 /// Maps each key (access path) to an internal VersionedValue.
 pub struct VersionedModules<K, V: TransactionWrite, X: Executable> {
     values: DashMap<K, VersionedValue<V, X>>,
+
+    /// Incremented after every change (write, estimate, deletion) to any module entry. Allows
+    /// executor threads with private code caches to cheaply detect that a cached module may have
+    /// become outdated from the perspective of the multi-versioned map.
+    num_changes: AtomicU64,
 }
 
 impl<V: TransactionWrite> Entry<V> {
@@ -104,21 +112,38 @@ impl<K: Hash + Clone + Eq, V: TransactionWrite, X: Executable> VersionedModules<
     pub(crate) fn new() -> Self {
         Self {
             values: DashMap::new(),
+            num_changes: AtomicU64::new(0),
         }
     }
 
+    /// Returns the number of changes applied to module entries so far. The counter is
+    /// incremented after the change is visible in the map.
+    pub(crate) fn num_changes(&self) -> u64 {
+        self.num_changes.load(Ordering::Acquire)
+    }
+
+    fn record_change(&self) {
+        self.num_changes.fetch_add(1, Ordering::AcqRel);
+    }
+
     pub(crate) fn mark_estimate(&self, key: &K, txn_idx: TxnIndex) {
-        let mut v = self.values.get_mut(key).expect("Path must exist");
-        v.versioned_map
-            .get_mut(&txn_idx)
-            .expect("Entry by the txn must exist to mark estimate")
-            .mark_estimate();
+        {
+            let mut v = self.values.get_mut(key).expect("Path must exist");
+            v.versioned_map
+                .get_mut(&txn_idx)
+                .expect("Entry by the txn must exist to mark estimate")
+                .mark_estimate();
+        }
+        self.record_change();
     }
 
     pub(crate) fn write(&self, key: K, txn_idx: TxnIndex, data: V) {
-        let mut v = self.values.entry(key).or_default();
-        v.versioned_map
-            .insert(txn_idx, CachePadded::new(Entry::new_write_from(data)));
+        {
+            let mut v = self.values.entry(key).or_default();
+            v.versioned_map
+                .insert(txn_idx, CachePadded::new(Entry::new_write_from(data)));
+        }
+        self.record_change();
     }
 
     pub(crate) fn store_executable(&self, key: &K, descriptor_hash: HashValue, executable: X) {
@@ -149,10 +174,13 @@ impl<K: Hash + Clone + Eq, V: TransactionWrite, X: Executable> VersionedModules<
 
     pub(crate) fn delete(&self, key: &K, txn_idx: TxnIndex) {
         // TODO: investigate logical deletion.
-        let mut v = self.values.get_mut(key).expect("Path must exist");
-        assert!(
-            v.versioned_map.remove(&txn_idx).is_some(),
-            "Entry must exist to be deleted"
-        );
+        {
+            let mut v = self.values.get_mut(key).expect("Path must exist");
+            assert!(
+                v.versioned_map.remove(&txn_idx).is_some(),
+                "Entry must exist to be deleted"
+            );
+        }
+        self.record_change();
     }
 }
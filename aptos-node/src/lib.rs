@@ -20,7 +20,9 @@ use aptos_api::bootstrap as bootstrap_api;
 use aptos_build_info::build_information;
 use aptos_config::config::{merge_node_config, NodeConfig, PersistableConfig};
 use aptos_framework::ReleaseBundle;
+use aptos_infallible::RwLock;
 use aptos_logger::{prelude::*, telemetry_log_writer::TelemetryLog, Level, LoggerFilterUpdater};
+use aptos_peer_monitoring_service_types::response::MempoolHealthSummary;
 use aptos_state_sync_driver::driver_factory::StateSyncRuntimes;
 use aptos_types::chain_id::ChainId;
 use clap::Parser;
@@ -496,10 +498,14 @@ pub fn setup_environment_and_start_node(
         consensus_reconfig_subscription,
     ) = state_sync::create_event_subscription_service(&node_config, &db_rw);
 
+    // Load any zstd dictionaries used to compress network messages
+    network::register_compression_dictionaries(&node_config)?;
+
     // Set up the networks and gather the application network handles
     let (
         network_runtimes,
         consensus_network_interfaces,
+        consensus_observer_network_interfaces,
         mempool_network_interfaces,
         peer_monitoring_service_network_interfaces,
         storage_service_network_interfaces,
@@ -510,15 +516,19 @@ pub fn setup_environment_and_start_node(
         &mut event_subscription_service,
     );
 
+    // Create the mempool health summary (updated by mempool and served by the peer monitoring service)
+    let mempool_health_summary = Arc::new(RwLock::new(MempoolHealthSummary::default()));
+
     // Start the peer monitoring service
     let peer_monitoring_service_runtime = services::start_peer_monitoring_service(
         &node_config,
         peer_monitoring_service_network_interfaces,
         db_rw.reader.clone(),
+        mempool_health_summary.clone(),
     );
 
     // Start state sync and get the notification endpoints for mempool and consensus
-    let (state_sync_runtimes, mempool_listener, consensus_notifier) =
+    let (state_sync_runtimes, mempool_listener, consensus_notifier, observer_pause_listener) =
         state_sync::start_state_sync_and_get_notification_handles(
             &node_config,
             storage_service_network_interfaces,
@@ -541,25 +551,50 @@ pub fn setup_environment_and_start_node(
             mempool_listener,
             mempool_client_receiver,
             peers_and_metadata,
+            mempool_health_summary,
         );
 
     // Create the consensus runtime (this blocks on state sync first)
-    let consensus_runtime = consensus_network_interfaces.map(|consensus_network_interfaces| {
+    let consensus_runtime = if let Some(consensus_network_interfaces) = consensus_network_interfaces
+    {
         // Wait until state sync has been initialized
         debug!("Waiting until state sync is initialized!");
         state_sync_runtimes.block_until_initialized();
         debug!("State sync initialization complete.");
 
         // Initialize and start consensus
-        services::start_consensus_runtime(
+        Some(services::start_consensus_runtime(
             &mut node_config,
             db_rw,
             consensus_reconfig_subscription,
             consensus_network_interfaces,
             consensus_notifier,
             consensus_to_mempool_sender,
-        )
-    });
+            consensus_observer_network_interfaces,
+        ))
+    } else if node_config.consensus_observer.observer_enabled {
+        // Fullnodes running the observer execute the ordered blocks themselves
+        let consensus_observer_network_interfaces = consensus_observer_network_interfaces
+            .expect("The consensus observer requires network interfaces!");
+
+        // Wait until state sync has been initialized
+        debug!("Waiting until state sync is initialized!");
+        state_sync_runtimes.block_until_initialized();
+        debug!("State sync initialization complete.");
+
+        // Initialize and start the consensus observer
+        Some(services::start_consensus_observer_runtime(
+            &node_config,
+            db_rw,
+            consensus_reconfig_subscription,
+            consensus_observer_network_interfaces,
+            consensus_notifier,
+            observer_pause_listener,
+            consensus_to_mempool_sender,
+        ))
+    } else {
+        None
+    };
 
     Ok(AptosHandle {
         _api_runtime: api_runtime,
@@ -2,17 +2,28 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use aptos_channels::{self, aptos_channel, message_queues::QueueStyle};
+use aptos_compression::{
+    dictionary::{add_decompression_dictionary, set_compression_dictionary, CompressionDictionary},
+    metrics::CompressionClient,
+};
 use aptos_config::{
     config::{NetworkConfig, NodeConfig},
     network_id::NetworkId,
 };
-use aptos_consensus::network_interface::{ConsensusMsg, DIRECT_SEND, RPC};
+use aptos_consensus::{
+    consensus_observer::network_message::ConsensusObserverMessage,
+    network_interface::{
+        ConsensusMsg, DIRECT_SEND, DIRECT_SEND_ZSTD, DIRECT_SEND_ZSTD_DICTIONARY,
+        QUORUM_STORE_DIRECT_SEND, QUORUM_STORE_RPC, RPC, RPC_ZSTD, RPC_ZSTD_DICTIONARY,
+    },
+};
 use aptos_event_notifications::EventSubscriptionService;
-use aptos_logger::debug;
+use aptos_logger::{debug, info};
 use aptos_mempool::network::MempoolSyncMsg;
 use aptos_network::{
     application::{
         interface::{NetworkClient, NetworkServiceEvents},
+        reputation::PeerReputations,
         storage::PeersAndMetadata,
     },
     protocols::network::{
@@ -45,11 +56,30 @@ struct ApplicationNetworkHandle<T> {
     pub network_events: NetworkEvents<T>,
 }
 
-/// TODO: make this configurable (e.g., for compression)
 /// Returns the network application config for the consensus client and service
 pub fn consensus_network_configuration(node_config: &NodeConfig) -> NetworkApplicationConfig {
-    let direct_send_protocols: Vec<ProtocolId> = DIRECT_SEND.into();
-    let rpc_protocols: Vec<ProtocolId> = RPC.into();
+    // The zstd dictionary protocols are only advertised if a dictionary is configured,
+    // and are only used with peers that know our dictionary (see the transport).
+    let zstd_compression_config = &node_config.consensus.zstd_compression;
+    let (direct_send_protocols, rpc_protocols): (Vec<ProtocolId>, Vec<ProtocolId>) =
+        if zstd_compression_config.enabled && zstd_compression_config.has_dictionaries() {
+            (
+                [DIRECT_SEND_ZSTD_DICTIONARY, DIRECT_SEND_ZSTD, DIRECT_SEND].concat(),
+                [RPC_ZSTD_DICTIONARY, RPC_ZSTD, RPC].concat(),
+            )
+        } else if zstd_compression_config.enabled {
+            (
+                [DIRECT_SEND_ZSTD, DIRECT_SEND].concat(),
+                [RPC_ZSTD, RPC].concat(),
+            )
+        } else {
+            (DIRECT_SEND.into(), RPC.into())
+        };
+
+    // The quorum store protocols are the least preferred, as they're only used
+    // for the quorum store messages (see the consensus network client).
+    let direct_send_protocols = [direct_send_protocols, QUORUM_STORE_DIRECT_SEND.into()].concat();
+    let rpc_protocols = [rpc_protocols, QUORUM_STORE_RPC.into()].concat();
 
     let network_client_config =
         NetworkClientConfig::new(direct_send_protocols.clone(), rpc_protocols.clone());
@@ -63,9 +93,46 @@ pub fn consensus_network_configuration(node_config: &NodeConfig) -> NetworkAppli
     NetworkApplicationConfig::new(network_client_config, network_service_config)
 }
 
+/// Returns the network application config for the consensus observer client and server
+pub fn consensus_observer_network_configuration(
+    node_config: &NodeConfig,
+) -> NetworkApplicationConfig {
+    let direct_send_protocols: Vec<ProtocolId> =
+        aptos_consensus::consensus_observer::network_message::DIRECT_SEND.into();
+    let rpc_protocols: Vec<ProtocolId> =
+        aptos_consensus::consensus_observer::network_message::RPC.into();
+    let max_network_channel_size = node_config.consensus_observer.max_network_channel_size as usize;
+
+    let network_client_config =
+        NetworkClientConfig::new(direct_send_protocols.clone(), rpc_protocols.clone());
+    let network_service_config = NetworkServiceConfig::new(
+        direct_send_protocols,
+        rpc_protocols,
+        aptos_channel::Config::new(max_network_channel_size)
+            .queue_style(QueueStyle::FIFO)
+            .counters(&aptos_consensus::counters::PENDING_CONSENSUS_OBSERVER_NETWORK_EVENTS),
+    );
+    NetworkApplicationConfig::new(network_client_config, network_service_config)
+}
+
 /// Returns the network application config for the mempool client and service
 pub fn mempool_network_configuration(node_config: &NodeConfig) -> NetworkApplicationConfig {
-    let direct_send_protocols = vec![ProtocolId::MempoolDirectSend];
+    let zstd_compression_config = &node_config.mempool.zstd_compression;
+    let direct_send_protocols =
+        if zstd_compression_config.enabled && zstd_compression_config.has_dictionaries() {
+            vec![
+                ProtocolId::MempoolDirectSendZstdDictionary,
+                ProtocolId::MempoolDirectSendZstd,
+                ProtocolId::MempoolDirectSend,
+            ]
+        } else if zstd_compression_config.enabled {
+            vec![
+                ProtocolId::MempoolDirectSendZstd,
+                ProtocolId::MempoolDirectSend,
+            ]
+        } else {
+            vec![ProtocolId::MempoolDirectSend]
+        };
     let rpc_protocols = vec![]; // Mempool does not use RPC
 
     let network_client_config =
@@ -148,7 +215,63 @@ fn extract_network_ids(node_config: &NodeConfig) -> Vec<NetworkId> {
 /// Creates the global peers and metadata struct
 pub fn create_peers_and_metadata(node_config: &NodeConfig) -> Arc<PeersAndMetadata> {
     let network_ids = extract_network_ids(node_config);
-    PeersAndMetadata::new(&network_ids)
+    let peer_reputation_configs = extract_network_configs(node_config)
+        .into_iter()
+        .map(|network_config| {
+            (
+                network_config.network_id,
+                network_config.peer_reputation_config,
+            )
+        })
+        .collect();
+    let peer_reputations = PeerReputations::new(peer_reputation_configs, TimeService::real());
+    PeersAndMetadata::new_with_peer_reputations(&network_ids, peer_reputations)
+}
+
+/// Loads the zstd dictionaries configured for consensus and mempool,
+/// and registers them with the compression crate.
+pub fn register_compression_dictionaries(node_config: &NodeConfig) -> anyhow::Result<()> {
+    let zstd_compression_configs = [
+        (
+            CompressionClient::Consensus,
+            &node_config.consensus.zstd_compression,
+        ),
+        (
+            CompressionClient::Mempool,
+            &node_config.mempool.zstd_compression,
+        ),
+    ];
+    for (client, zstd_compression_config) in zstd_compression_configs {
+        if !zstd_compression_config.enabled {
+            continue;
+        }
+
+        // Register the compression dictionary
+        if let Some(dictionary_path) = &zstd_compression_config.dictionary_path {
+            let dictionary = CompressionDictionary::load(dictionary_path)?;
+            info!(
+                "Using zstd dictionary {} ({:?}) to compress {} messages",
+                dictionary.id(),
+                dictionary_path,
+                client.get_label()
+            );
+            set_compression_dictionary(client.clone(), Arc::new(dictionary));
+        }
+
+        // Register any additional decompression dictionaries
+        for dictionary_path in &zstd_compression_config.additional_dictionary_paths {
+            let dictionary = CompressionDictionary::load(dictionary_path)?;
+            info!(
+                "Accepting zstd dictionary {} ({:?}) for {} messages",
+                dictionary.id(),
+                dictionary_path,
+                client.get_label()
+            );
+            add_decompression_dictionary(Arc::new(dictionary));
+        }
+    }
+
+    Ok(())
 }
 
 /// Sets up all networks and returns the appropriate application network interfaces
@@ -160,6 +283,7 @@ pub fn setup_networks_and_get_interfaces(
 ) -> (
     Vec<Runtime>,
     Option<ApplicationNetworkInterfaces<ConsensusMsg>>,
+    Option<ApplicationNetworkInterfaces<ConsensusObserverMessage>>,
     ApplicationNetworkInterfaces<MempoolSyncMsg>,
     ApplicationNetworkInterfaces<PeerMonitoringServiceMessage>,
     ApplicationNetworkInterfaces<StorageServiceMessage>,
@@ -170,6 +294,7 @@ pub fn setup_networks_and_get_interfaces(
     // Create each network and register the application handles
     let mut network_runtimes = vec![];
     let mut consensus_network_handle = None;
+    let mut consensus_observer_network_handles: Option<Vec<_>> = None;
     let mut mempool_network_handles = vec![];
     let mut peer_monitoring_service_network_handles = vec![];
     let mut storage_service_network_handles = vec![];
@@ -206,6 +331,21 @@ pub fn setup_networks_and_get_interfaces(
             }
         }
 
+        // Register the consensus observer (both client and server) with the network
+        let consensus_observer_config = node_config.consensus_observer;
+        if consensus_observer_config.observer_enabled || consensus_observer_config.publisher_enabled
+        {
+            let consensus_observer_network_handle = register_client_and_service_with_network(
+                &mut network_builder,
+                network_id,
+                &network_config,
+                consensus_observer_network_configuration(node_config),
+            );
+            consensus_observer_network_handles
+                .get_or_insert_with(Vec::new)
+                .push(consensus_observer_network_handle);
+        }
+
         // Register mempool (both client and server) with the network
         let mempool_network_handle = register_client_and_service_with_network(
             &mut network_builder,
@@ -246,12 +386,14 @@ pub fn setup_networks_and_get_interfaces(
     // Transform all network handles into application interfaces
     let (
         consensus_interfaces,
+        consensus_observer_interfaces,
         mempool_interfaces,
         peer_monitoring_service_interfaces,
         storage_service_interfaces,
     ) = transform_network_handles_into_interfaces(
         node_config,
         consensus_network_handle,
+        consensus_observer_network_handles,
         mempool_network_handles,
         peer_monitoring_service_network_handles,
         storage_service_network_handles,
@@ -261,6 +403,7 @@ pub fn setup_networks_and_get_interfaces(
     (
         network_runtimes,
         consensus_interfaces,
+        consensus_observer_interfaces,
         mempool_interfaces,
         peer_monitoring_service_interfaces,
         storage_service_interfaces,
@@ -305,6 +448,9 @@ fn register_client_and_service_with_network<
 fn transform_network_handles_into_interfaces(
     node_config: &NodeConfig,
     consensus_network_handle: Option<ApplicationNetworkHandle<ConsensusMsg>>,
+    consensus_observer_network_handles: Option<
+        Vec<ApplicationNetworkHandle<ConsensusObserverMessage>>,
+    >,
     mempool_network_handles: Vec<ApplicationNetworkHandle<MempoolSyncMsg>>,
     peer_monitoring_service_network_handles: Vec<
         ApplicationNetworkHandle<PeerMonitoringServiceMessage>,
@@ -313,6 +459,7 @@ fn transform_network_handles_into_interfaces(
     peers_and_metadata: Arc<PeersAndMetadata>,
 ) -> (
     Option<ApplicationNetworkInterfaces<ConsensusMsg>>,
+    Option<ApplicationNetworkInterfaces<ConsensusObserverMessage>>,
     ApplicationNetworkInterfaces<MempoolSyncMsg>,
     ApplicationNetworkInterfaces<PeerMonitoringServiceMessage>,
     ApplicationNetworkInterfaces<StorageServiceMessage>,
@@ -324,6 +471,14 @@ fn transform_network_handles_into_interfaces(
             peers_and_metadata.clone(),
         )
     });
+    let consensus_observer_interfaces =
+        consensus_observer_network_handles.map(|consensus_observer_network_handles| {
+            create_network_interfaces(
+                consensus_observer_network_handles,
+                consensus_observer_network_configuration(node_config),
+                peers_and_metadata.clone(),
+            )
+        });
     let mempool_interfaces = create_network_interfaces(
         mempool_network_handles,
         mempool_network_configuration(node_config),
@@ -342,6 +497,7 @@ fn transform_network_handles_into_interfaces(
 
     (
         consensus_interfaces,
+        consensus_observer_interfaces,
         mempool_interfaces,
         peer_monitoring_service_interfaces,
         storage_service_interfaces,
@@ -4,10 +4,13 @@
 use crate::{bootstrap_api, indexer, mpsc::Receiver, network::ApplicationNetworkInterfaces};
 use aptos_build_info::build_information;
 use aptos_config::config::NodeConfig;
-use aptos_consensus::network_interface::ConsensusMsg;
-use aptos_consensus_notifications::ConsensusNotifier;
+use aptos_consensus::{
+    consensus_observer::network_message::ConsensusObserverMessage, network_interface::ConsensusMsg,
+};
+use aptos_consensus_notifications::{ConsensusNotifier, ObserverPauseListener};
 use aptos_event_notifications::ReconfigNotificationListener;
 use aptos_indexer_grpc_fullnode::runtime::bootstrap as bootstrap_indexer_grpc;
+use aptos_infallible::RwLock;
 use aptos_logger::{debug, telemetry_log_writer::TelemetryLog, LoggerFilterUpdater};
 use aptos_mempool::{network::MempoolSyncMsg, MempoolClientRequest, QuorumStoreRequest};
 use aptos_mempool_notifications::MempoolNotificationListener;
@@ -16,7 +19,9 @@ use aptos_peer_monitoring_service_server::{
     network::PeerMonitoringServiceNetworkEvents, storage::StorageReader,
     PeerMonitoringServiceServer,
 };
-use aptos_peer_monitoring_service_types::PeerMonitoringServiceMessage;
+use aptos_peer_monitoring_service_types::{
+    response::MempoolHealthSummary, PeerMonitoringServiceMessage,
+};
 use aptos_storage_interface::{DbReader, DbReaderWriter};
 use aptos_time_service::TimeService;
 use aptos_types::chain_id::ChainId;
@@ -83,8 +88,18 @@ pub fn start_consensus_runtime(
     consensus_network_interfaces: ApplicationNetworkInterfaces<ConsensusMsg>,
     consensus_notifier: ConsensusNotifier,
     consensus_to_mempool_sender: Sender<QuorumStoreRequest>,
+    consensus_observer_network_interfaces: Option<
+        ApplicationNetworkInterfaces<ConsensusObserverMessage>,
+    >,
 ) -> Runtime {
     let instant = Instant::now();
+    let consensus_observer_network =
+        consensus_observer_network_interfaces.map(|network_interfaces| {
+            (
+                network_interfaces.network_client,
+                network_interfaces.network_service_events,
+            )
+        });
     let consensus_runtime = aptos_consensus::consensus_provider::start_consensus(
         node_config,
         consensus_network_interfaces.network_client,
@@ -94,11 +109,41 @@ pub fn start_consensus_runtime(
         db_rw,
         consensus_reconfig_subscription
             .expect("Consensus requires a reconfiguration subscription!"),
+        consensus_observer_network,
     );
     debug!("Consensus started in {} ms", instant.elapsed().as_millis());
     consensus_runtime
 }
 
+/// Starts the consensus observer and returns the runtime
+pub fn start_consensus_observer_runtime(
+    node_config: &NodeConfig,
+    db_rw: DbReaderWriter,
+    consensus_reconfig_subscription: Option<ReconfigNotificationListener>,
+    consensus_observer_network_interfaces: ApplicationNetworkInterfaces<ConsensusObserverMessage>,
+    consensus_notifier: ConsensusNotifier,
+    observer_pause_listener: ObserverPauseListener,
+    consensus_to_mempool_sender: Sender<QuorumStoreRequest>,
+) -> Runtime {
+    let instant = Instant::now();
+    let consensus_observer_runtime = aptos_consensus::consensus_provider::start_consensus_observer(
+        node_config,
+        consensus_observer_network_interfaces.network_client,
+        consensus_observer_network_interfaces.network_service_events,
+        Arc::new(consensus_notifier),
+        observer_pause_listener,
+        consensus_to_mempool_sender,
+        db_rw,
+        consensus_reconfig_subscription
+            .expect("The consensus observer requires a reconfiguration subscription!"),
+    );
+    debug!(
+        "Consensus observer started in {} ms",
+        instant.elapsed().as_millis()
+    );
+    consensus_observer_runtime
+}
+
 /// Create the mempool runtime and start mempool
 pub fn start_mempool_runtime_and_get_consensus_sender(
     node_config: &mut NodeConfig,
@@ -108,6 +153,7 @@ pub fn start_mempool_runtime_and_get_consensus_sender(
     mempool_listener: MempoolNotificationListener,
     mempool_client_receiver: Receiver<MempoolClientRequest>,
     peers_and_metadata: Arc<PeersAndMetadata>,
+    mempool_health_summary: Arc<RwLock<MempoolHealthSummary>>,
 ) -> (Runtime, Sender<QuorumStoreRequest>) {
     // Create a communication channel between consensus and mempool
     let (consensus_to_mempool_sender, consensus_to_mempool_receiver) =
@@ -125,6 +171,7 @@ pub fn start_mempool_runtime_and_get_consensus_sender(
         mempool_listener,
         mempool_reconfig_subscription,
         peers_and_metadata,
+        mempool_health_summary,
     );
     debug!("Mempool started in {} ms", instant.elapsed().as_millis());
 
@@ -144,6 +191,7 @@ pub fn start_peer_monitoring_service(
     node_config: &NodeConfig,
     network_interfaces: ApplicationNetworkInterfaces<PeerMonitoringServiceMessage>,
     db_reader: Arc<dyn DbReader>,
+    mempool_health_summary: Arc<RwLock<MempoolHealthSummary>>,
 ) -> Runtime {
     // Get the network client and events
     let network_client = network_interfaces.network_client;
@@ -159,6 +207,7 @@ pub fn start_peer_monitoring_service(
     let peer_monitoring_server = PeerMonitoringServiceServer::new(
         node_config.clone(),
         peer_monitoring_service_runtime.handle().clone(),
+        mempool_health_summary,
         peer_monitoring_network_events,
         network_client.get_peers_and_metadata(),
         StorageReader::new(db_reader),
@@ -3,7 +3,7 @@
 
 use crate::network::ApplicationNetworkInterfaces;
 use aptos_config::config::{NodeConfig, StateSyncConfig, StorageServiceConfig};
-use aptos_consensus_notifications::ConsensusNotifier;
+use aptos_consensus_notifications::{ConsensusNotifier, ObserverPauseListener};
 use aptos_data_client::client::AptosDataClient;
 use aptos_data_streaming_service::{
     streaming_client::{new_streaming_service_client_listener_pair, StreamingServiceClient},
@@ -55,8 +55,11 @@ pub fn create_event_subscription_service(
         .subscribe_to_reconfigurations()
         .expect("Mempool must subscribe to reconfigurations");
 
-    // Create a reconfiguration subscription for consensus (if this is a validator)
-    let consensus_reconfig_subscription = if node_config.base.role.is_validator() {
+    // Create a reconfiguration subscription for consensus (if this is a validator
+    // or a fullnode running the consensus observer).
+    let consensus_reconfig_subscription = if node_config.base.role.is_validator()
+        || node_config.consensus_observer.observer_enabled
+    {
         Some(
             event_subscription_service
                 .subscribe_to_reconfigurations()
@@ -84,6 +87,7 @@ pub fn start_state_sync_and_get_notification_handles(
     StateSyncRuntimes,
     MempoolNotificationListener,
     ConsensusNotifier,
+    ObserverPauseListener,
 )> {
     // Get the network client and events
     let network_client = storage_network_interfaces.network_client;
@@ -96,13 +100,14 @@ pub fn start_state_sync_and_get_notification_handles(
 
     // Start the data streaming service
     let (streaming_service_client, streaming_service_runtime) =
-        setup_data_streaming_service(node_config.state_sync, aptos_data_client.clone())?;
+        setup_data_streaming_service(node_config.state_sync.clone(), aptos_data_client.clone())?;
 
     // Create the chunk executor and persistent storage
     let chunk_executor = Arc::new(ChunkExecutor::<AptosVM>::new(db_rw.clone()));
     let metadata_storage = PersistentMetadataStorage::new(&node_config.storage.dir());
 
-    // Create notification senders and listeners for mempool, consensus and the storage service
+    // Create notification senders and listeners for mempool, consensus, the consensus
+    // observer and the storage service
     let (mempool_notifier, mempool_listener) =
         aptos_mempool_notifications::new_mempool_notifier_listener_pair();
     let (consensus_notifier, consensus_listener) =
@@ -112,6 +117,8 @@ pub fn start_state_sync_and_get_notification_handles(
                 .state_sync_driver
                 .commit_notification_timeout_ms,
         );
+    let (observer_pause_notifier, observer_pause_listener) =
+        aptos_consensus_notifications::new_observer_pause_notifier_listener_pair();
     let (storage_service_notifier, storage_service_listener) =
         aptos_storage_service_notifications::new_storage_service_notifier_listener_pair();
 
@@ -135,6 +142,7 @@ pub fn start_state_sync_and_get_notification_handles(
         storage_service_notifier,
         metadata_storage,
         consensus_listener,
+        observer_pause_notifier,
         event_subscription_service,
         aptos_data_client,
         streaming_service_client,
@@ -149,7 +157,12 @@ pub fn start_state_sync_and_get_notification_handles(
         streaming_service_runtime,
     );
 
-    Ok((state_sync_runtimes, mempool_listener, consensus_notifier))
+    Ok((
+        state_sync_runtimes,
+        mempool_listener,
+        consensus_notifier,
+        observer_pause_listener,
+    ))
 }
 
 /// Sets up the data streaming service runtime
@@ -41,6 +41,127 @@ pub(crate) fn bootstrap_db(
     (aptos_db, db_rw, None)
 }
 
+/// The file (in the storage directory) marking that the backup restore completed
+#[cfg(not(feature = "consensus-only-perf-test"))]
+const BACKUP_RESTORE_COMPLETED_FILE: &str = "backup_restore_completed";
+
+/// Restores the epoch endings, the latest state snapshot and the transactions
+/// from the configured backups if the node bootstraps from backups and the
+/// restore hasn't completed yet. The restored data is verified against the
+/// trusted waypoints, and state sync then syncs the remaining data from peers.
+///
+/// If the node crashed during a previous restore, the restore resumes from the
+/// data already in the database (the restore coordinator skips what's there).
+#[cfg(not(feature = "consensus-only-perf-test"))]
+pub(crate) fn maybe_restore_from_backup(
+    node_config: &NodeConfig,
+    aptos_db: &Arc<AptosDB>,
+) -> anyhow::Result<()> {
+    use aptos_backup_cli::{
+        coordinators::restore::{RestoreCoordinator, RestoreCoordinatorOpt},
+        metadata::cache::MetadataCacheOpt,
+        storage::{
+            command_adapter::{config::CommandAdapterConfig, CommandAdapter},
+            local_fs::LocalFs,
+            zstd_compressed::ZstdCompressed,
+            BackupStorage,
+        },
+        utils::{GlobalRestoreOptions, RestoreRunMode},
+    };
+    use aptos_config::config::BootstrappingMode;
+    use aptos_db::GetRestoreHandler;
+    use aptos_types::transaction::Version;
+    use std::collections::HashMap;
+
+    if node_config.state_sync.state_sync_driver.bootstrapping_mode
+        != BootstrappingMode::RestoreFromBackup
+    {
+        return Ok(());
+    }
+
+    // Only restore once, the database is synced by state sync afterwards
+    let completed_file = node_config
+        .storage
+        .dir()
+        .join(BACKUP_RESTORE_COMPLETED_FILE);
+    if completed_file.exists() {
+        info!("The backup restore already completed, skipping it.");
+        return Ok(());
+    }
+    let restore_handler = aptos_db.get_restore_handler();
+    let next_version = restore_handler.get_next_expected_transaction_version()?;
+    if next_version > 0 {
+        info!(
+            "Resuming the interrupted backup restore (next version: {}).",
+            next_version
+        );
+    }
+
+    // Trust the genesis and the configured waypoints
+    let mut trusted_waypoints = HashMap::new();
+    for waypoint in [
+        node_config.base.waypoint.genesis_waypoint(),
+        node_config.base.waypoint.waypoint(),
+    ] {
+        trusted_waypoints.insert(waypoint.version(), waypoint);
+    }
+
+    let backup_restore_config = node_config.state_sync.backup_restore.clone();
+    let runtime = aptos_runtimes::spawn_named_runtime("backup-restore".into(), None);
+    runtime.block_on(async move {
+        // Open the backup storage. Compressed backup files are detected on read.
+        let storage: Arc<dyn BackupStorage> = match (
+            backup_restore_config.local_fs_dir,
+            backup_restore_config.command_adapter_config,
+        ) {
+            (Some(local_fs_dir), None) => Arc::new(LocalFs::new(local_fs_dir)),
+            (None, Some(config_path)) => Arc::new(CommandAdapter::new(
+                CommandAdapterConfig::load_from_file(&config_path).await?,
+            )),
+            _ => return Err(anyhow!("Exactly one backup storage must be configured!")),
+        };
+        let storage = Arc::new(ZstdCompressed::new(storage, false));
+
+        // Restore the backups (up to the latest version available)
+        info!("Restoring the database from backups...");
+        let instant = Instant::now();
+        let global_opt = GlobalRestoreOptions {
+            target_version: Version::MAX,
+            trusted_waypoints: Arc::new(trusted_waypoints),
+            run_mode: Arc::new(RestoreRunMode::Restore { restore_handler }),
+            concurrent_downloads: backup_restore_config.concurrent_downloads,
+            replay_concurrency_level: backup_restore_config.replay_concurrency_level,
+        };
+        let restore_opt = RestoreCoordinatorOpt {
+            metadata_cache_opt: MetadataCacheOpt::new(None::<&Path>),
+            replay_all: false,
+            ledger_history_start_version: None,
+            skip_epoch_endings: false,
+        };
+        RestoreCoordinator::new(restore_opt, global_opt, storage)
+            .run()
+            .await
+            .map_err(|error| anyhow!("Failed to restore from backups: {}", error))?;
+        info!(
+            "Restored the database from backups in {} ms",
+            instant.elapsed().as_millis()
+        );
+        Ok(())
+    })?;
+
+    fs::write(&completed_file, b"")
+        .map_err(|error| anyhow!("Failed to mark the backup restore as completed: {}", error))
+}
+
+/// In consensus-only mode, the in-memory [FakeAptosDB] is never restored from backups.
+#[cfg(feature = "consensus-only-perf-test")]
+pub(crate) fn maybe_restore_from_backup(
+    _node_config: &NodeConfig,
+    _aptos_db: &Arc<aptos_db::fake_aptosdb::FakeAptosDB>,
+) -> anyhow::Result<()> {
+    Ok(())
+}
+
 /// Creates a RocksDb checkpoint for the consensus_db, state_sync_db,
 /// ledger_db and state_merkle_db and saves it to the checkpoint_path.
 /// Also, changes the working directory to run the node on the new path,
@@ -109,6 +230,9 @@ pub fn initialize_database_and_checkpoints(
     let (aptos_db, db_rw, backup_service) =
         bootstrap_db(aptos_db, node_config.storage.backup_service_address);
 
+    // If required, restore the database from backups (before applying genesis)
+    maybe_restore_from_backup(node_config, &aptos_db)?;
+
     // TODO: handle non-genesis waypoints for state sync!
     // If there's a genesis txn and waypoint, commit it if the result matches.
     let genesis_waypoint = node_config.base.waypoint.genesis_waypoint();
@@ -1,7 +1,7 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{create_single_node_test_config, network};
+use crate::{create_single_node_test_config, network, storage};
 use aptos_config::config::{NodeConfig, WaypointConfig};
 use aptos_event_notifications::EventSubscriptionService;
 use aptos_infallible::RwLock;
@@ -46,6 +46,115 @@ fn test_mutual_authentication_validators() {
     );
 }
 
+#[cfg(not(feature = "consensus-only-perf-test"))]
+#[test]
+fn test_restore_from_local_fs_backup() {
+    use aptos_backup_cli::{
+        backup_types::{
+            epoch_ending::backup::{EpochEndingBackupController, EpochEndingBackupOpt},
+            state_snapshot::backup::{StateSnapshotBackupController, StateSnapshotBackupOpt},
+            transaction::backup::{TransactionBackupController, TransactionBackupOpt},
+        },
+        storage::{local_fs::LocalFs, BackupStorage},
+        utils::{
+            backup_service_client::BackupServiceClient, test_utils::start_local_backup_service,
+            GlobalBackupOpt,
+        },
+    };
+    use aptos_config::config::BootstrappingMode;
+    use aptos_db::{AptosDB, GetRestoreHandler};
+    use aptos_executor_test_helpers::integration_test_impl::test_execution_with_storage_impl;
+
+    // Create a database with a few epochs and back it up to the local file system
+    let source_db = test_execution_with_storage_impl();
+    let latest_version = source_db.get_latest_version().unwrap();
+    let latest_epoch = source_db.get_latest_epoch_state().unwrap().epoch;
+    let genesis_ledger_info = source_db
+        .get_epoch_ending_ledger_infos(0, 1)
+        .unwrap()
+        .ledger_info_with_sigs
+        .remove(0);
+
+    let backup_dir = TempPath::new();
+    backup_dir.create_as_dir().unwrap();
+    let backup_storage: Arc<dyn BackupStorage> =
+        Arc::new(LocalFs::new(backup_dir.path().to_path_buf()));
+    let (runtime, port) = start_local_backup_service(source_db.clone());
+    let client = Arc::new(BackupServiceClient::new(format!(
+        "http://localhost:{}",
+        port
+    )));
+    let global_backup_opt = GlobalBackupOpt {
+        max_chunk_size: 2048,
+    };
+    runtime
+        .block_on(
+            EpochEndingBackupController::new(
+                EpochEndingBackupOpt {
+                    start_epoch: 0,
+                    end_epoch: latest_epoch,
+                },
+                global_backup_opt.clone(),
+                client.clone(),
+                backup_storage.clone(),
+            )
+            .run(),
+        )
+        .unwrap();
+    runtime
+        .block_on(
+            StateSnapshotBackupController::new(
+                StateSnapshotBackupOpt {
+                    epoch: latest_epoch - 1,
+                },
+                global_backup_opt.clone(),
+                client.clone(),
+                backup_storage.clone(),
+            )
+            .run(),
+        )
+        .unwrap();
+    runtime
+        .block_on(
+            TransactionBackupController::new(
+                TransactionBackupOpt {
+                    start_version: 0,
+                    num_transactions: latest_version as usize + 1,
+                },
+                global_backup_opt,
+                client,
+                backup_storage,
+            )
+            .run(),
+        )
+        .unwrap();
+
+    // Create a node config that restores from the backups into an empty database
+    let temp_path = TempPath::new();
+    let mut node_config = NodeConfig::default();
+    node_config.set_data_dir(temp_path.path().to_path_buf());
+    node_config.base.waypoint = WaypointConfig::FromConfig(
+        Waypoint::new_epoch_boundary(genesis_ledger_info.ledger_info()).unwrap(),
+    );
+    let state_sync_config = &mut node_config.state_sync;
+    state_sync_config.state_sync_driver.bootstrapping_mode = BootstrappingMode::RestoreFromBackup;
+    state_sync_config.backup_restore.local_fs_dir = Some(backup_dir.path().to_path_buf());
+    let aptos_db = Arc::new(AptosDB::new_for_test(node_config.storage.dir()));
+
+    // Restore the backups and verify that state sync takes over after the latest version
+    storage::maybe_restore_from_backup(&node_config, &aptos_db).unwrap();
+    let next_version = aptos_db
+        .get_restore_handler()
+        .get_next_expected_transaction_version()
+        .unwrap();
+    assert_eq!(next_version, latest_version + 1);
+    assert_eq!(aptos_db.get_latest_version().unwrap(), latest_version);
+
+    // Verify that the completed restore is not run again (the backups are gone)
+    fs::remove_dir_all(backup_dir.path()).unwrap();
+    storage::maybe_restore_from_backup(&node_config, &aptos_db).unwrap();
+}
+
 #[cfg(feature = "check-vm-features")]
 #[test]
 fn test_aptos_vm_does_not_have_test_natives() {
@@ -3,6 +3,7 @@
 
 use anyhow::anyhow;
 use aptos_config::config::NodeConfig;
+use aptos_executor_service::remote_executor_client;
 use aptos_state_view::account_with_state_view::AsAccountWithStateView;
 use aptos_storage_interface::{state_view::LatestDbStateCheckpointView, DbReaderWriter};
 use aptos_types::{
@@ -53,6 +54,12 @@ pub fn set_aptos_vm_configurations(node_config: &NodeConfig) {
     AptosVM::set_num_proof_reading_threads_once(
         node_config.execution.num_proof_reading_threads as usize,
     );
+    AptosVM::set_speculative_execution_threads_once(
+        node_config.execution.speculative_execution_threads as usize,
+    );
+    AptosVM::set_speculative_execution_cache_size_once(
+        node_config.execution.speculative_execution_cache_size,
+    );
 
     if node_config
         .execution
@@ -60,4 +67,10 @@ pub fn set_aptos_vm_configurations(node_config: &NodeConfig) {
     {
         AptosVM::set_processed_transactions_detailed_counters();
     }
+
+    // Execute the shards of sharded blocks on the remote executor services (one per shard)
+    if let Some(remote_execution_config) = &node_config.execution.remote_execution {
+        AptosVM::set_num_shards_once(remote_execution_config.remote_executor_addresses.len());
+        remote_executor_client::set_remote_execution_config_once(remote_execution_config.clone());
+    }
 }
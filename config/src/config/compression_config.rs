@@ -0,0 +1,35 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// Configuration for compressing application messages with zstd. When enabled,
+/// the zstd protocols are advertised during the handshake (and preferred over
+/// the lz4 protocols), so peers that don't support zstd are unaffected. If any
+/// dictionary is configured, the zstd dictionary protocols are also advertised.
+/// Messages are only compressed with the dictionary if the peer has advertised
+/// it after the handshake, otherwise they are compressed without a dictionary.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ZstdCompressionConfig {
+    pub enabled: bool,
+    // The pre-trained dictionary used to compress outbound messages (if any)
+    pub dictionary_path: Option<PathBuf>,
+    // Additional dictionaries accepted for inbound messages (e.g., while rotating dictionaries)
+    pub additional_dictionary_paths: Vec<PathBuf>,
+}
+
+impl ZstdCompressionConfig {
+    /// Returns all dictionary paths, starting with the compression dictionary
+    pub fn all_dictionary_paths(&self) -> impl Iterator<Item = &PathBuf> {
+        self.dictionary_path
+            .iter()
+            .chain(self.additional_dictionary_paths.iter())
+    }
+
+    /// Returns true iff any dictionary is configured
+    pub fn has_dictionaries(&self) -> bool {
+        self.all_dictionary_paths().next().is_some()
+    }
+}
@@ -4,9 +4,10 @@
 use crate::config::{
     node_config_loader::NodeType,
     utils::{are_failpoints_enabled, get_config_name},
-    ApiConfig, BaseConfig, ConsensusConfig, Error, ExecutionConfig, IndexerConfig,
-    IndexerGrpcConfig, InspectionServiceConfig, LoggerConfig, MempoolConfig, NodeConfig,
-    PeerMonitoringServiceConfig, StateSyncConfig, StorageConfig,
+    ApiConfig, BaseConfig, ConsensusConfig, ConsensusObserverConfig, DiscoveryMethod, Error,
+    ExecutionConfig, IndexerConfig, IndexerGrpcConfig, InspectionServiceConfig, LoggerConfig,
+    MempoolConfig, NetworkConfig, NodeConfig, PeerMonitoringServiceConfig, StateSyncConfig,
+    StorageConfig,
 };
 use aptos_types::chain_id::ChainId;
 use std::collections::HashSet;
@@ -45,6 +46,7 @@ impl ConfigSanitizer for NodeConfig {
         ApiConfig::sanitize(node_config, node_type, chain_id)?;
         BaseConfig::sanitize(node_config, node_type, chain_id)?;
         ConsensusConfig::sanitize(node_config, node_type, chain_id)?;
+        ConsensusObserverConfig::sanitize(node_config, node_type, chain_id)?;
         ExecutionConfig::sanitize(node_config, node_type, chain_id)?;
         sanitize_failpoints_config(node_config, node_type, chain_id)?;
         sanitize_fullnode_network_configs(node_config, node_type, chain_id)?;
@@ -139,6 +141,21 @@ fn sanitize_fullnode_network_configs(
             ));
         }
 
+        // Verify that peer exchange discovery is only used on the public network
+        if uses_peer_exchange_discovery(fullnode_network_config) && !network_id.is_public_network()
+        {
+            return Err(Error::ConfigSanitizerFailed(
+                sanitizer_name,
+                format!(
+                    "Peer exchange discovery is only supported on the public network! Found: {}",
+                    network_id
+                ),
+            ));
+        }
+
+        // Verify that the traffic capture can rotate its files
+        sanitize_traffic_capture_config(&sanitizer_name, fullnode_network_config)?;
+
         // Prepare the network id
         fullnode_network_config.set_listen_address_and_prepare_identity()?;
     }
@@ -189,6 +206,17 @@ fn sanitize_validator_network_config(
             ));
         }
 
+        // Verify that peer exchange discovery isn't used (the validator set is on-chain)
+        if uses_peer_exchange_discovery(validator_network_config) {
+            return Err(Error::ConfigSanitizerFailed(
+                sanitizer_name,
+                "Peer exchange discovery is not supported on the validator network!".into(),
+            ));
+        }
+
+        // Verify that the traffic capture can rotate its files
+        sanitize_traffic_capture_config(&sanitizer_name, validator_network_config)?;
+
         // Prepare the network id
         validator_network_config.set_listen_address_and_prepare_identity()?;
     }
@@ -196,10 +224,43 @@ fn sanitize_validator_network_config(
     Ok(())
 }
 
+/// Returns true iff the network config uses peer exchange discovery
+fn uses_peer_exchange_discovery(network_config: &NetworkConfig) -> bool {
+    std::iter::once(&network_config.discovery_method)
+        .chain(network_config.discovery_methods.iter())
+        .any(|discovery_method| matches!(discovery_method, DiscoveryMethod::PeerExchange(_)))
+}
+
+/// Verifies that the traffic capture (if enabled) has non-zero file and queue limits
+fn sanitize_traffic_capture_config(
+    sanitizer_name: &str,
+    network_config: &NetworkConfig,
+) -> Result<(), Error> {
+    let capture_config = &network_config.traffic_capture_config;
+    if capture_config.enabled
+        && (capture_config.max_file_size_bytes == 0
+            || capture_config.max_num_files == 0
+            || capture_config.max_pending_messages == 0)
+    {
+        return Err(Error::ConfigSanitizerFailed(
+            sanitizer_name.to_owned(),
+            format!(
+                "The traffic capture requires a non-zero max_file_size_bytes, max_num_files and \
+                max_pending_messages! Found for network: {}",
+                network_config.network_id
+            ),
+        ));
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{config::NetworkConfig, network_id::NetworkId};
+    use crate::{
+        config::{NetworkConfig, TrafficCaptureConfig},
+        network_id::NetworkId,
+    };
 
     #[test]
     fn test_sanitize_missing_pfn_network_configs() {
@@ -285,6 +346,54 @@ mod tests {
         assert!(matches!(error, Error::ConfigSanitizerFailed(_, _)));
     }
 
+    #[test]
+    fn test_sanitize_vfn_network_peer_exchange() {
+        // Create a node config with peer exchange discovery on the VFN network
+        let mut node_config = NodeConfig {
+            full_node_networks: vec![NetworkConfig {
+                network_id: NetworkId::Vfn,
+                discovery_method: DiscoveryMethod::PeerExchange(Default::default()),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        // Sanitize the config and verify that it fails
+        let error = sanitize_fullnode_network_configs(
+            &mut node_config,
+            NodeType::ValidatorFullnode,
+            ChainId::testnet(),
+        )
+        .unwrap_err();
+        assert!(matches!(error, Error::ConfigSanitizerFailed(_, _)));
+    }
+
+    #[test]
+    fn test_sanitize_traffic_capture_without_files() {
+        // Create a node config with a traffic capture that can't keep any files
+        let mut node_config = NodeConfig {
+            full_node_networks: vec![NetworkConfig {
+                network_id: NetworkId::Public,
+                traffic_capture_config: TrafficCaptureConfig {
+                    enabled: true,
+                    max_num_files: 0,
+                    ..Default::default()
+                },
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        // Sanitize the config and verify that it fails
+        let error = sanitize_fullnode_network_configs(
+            &mut node_config,
+            NodeType::PublicFullnode,
+            ChainId::testnet(),
+        )
+        .unwrap_err();
+        assert!(matches!(error, Error::ConfigSanitizerFailed(_, _)));
+    }
+
     #[test]
     fn test_sanitize_missing_validator_network_config() {
         // Create a node config with an empty validator network config
@@ -4,7 +4,7 @@
 
 use crate::config::{
     config_sanitizer::ConfigSanitizer, node_config_loader::NodeType, Error, NodeConfig,
-    QuorumStoreConfig, SafetyRulesConfig,
+    QuorumStoreConfig, SafetyRulesConfig, ZstdCompressionConfig,
 };
 use aptos_types::chain_id::ChainId;
 use cfg_if::cfg_if;
@@ -65,6 +65,9 @@ pub struct ConsensusConfig {
     // must match one of the CHAIN_HEALTH_WINDOW_SIZES values.
     pub window_for_chain_health: usize,
     pub chain_health_backoff: Vec<ChainHealthBackoffValues>,
+    pub adaptive_block_size: AdaptiveBlockSizeConfig,
+    pub message_recorder: MessageRecorderConfig,
+    pub zstd_compression: ZstdCompressionConfig,
 }
 
 #[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
@@ -90,6 +93,89 @@ pub struct ChainHealthBackoffValues {
     pub backoff_proposal_delay_ms: u64,
 }
 
+/// Configuration for adapting the proposed block size to the observed execution and commit
+/// latency of the local pipeline. Limits computed here are combined (by taking the minimum)
+/// with the static block limits, pipeline backpressure and chain health backoff.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AdaptiveBlockSizeConfig {
+    pub enabled: bool,
+    // Execution time per block we aim for. Used together with the observed per-transaction
+    // execution time to bound the number of transactions in a block.
+    pub target_block_execution_latency_ms: u64,
+    // Latency from block timestamp to commit we aim for. Above it the block size is reduced
+    // multiplicatively, below it is grown additively.
+    pub target_commit_latency_ms: u64,
+    // Lower bounds, so that batches from quorum store and large transactions still fit.
+    pub min_block_txns: u64,
+    pub min_block_bytes: u64,
+    pub additive_increase_txns: u64,
+    pub multiplicative_decrease_factor: f64,
+    // Weight of the newest observation in the exponentially weighted moving averages.
+    pub ewma_alpha: f64,
+}
+
+impl Default for AdaptiveBlockSizeConfig {
+    fn default() -> AdaptiveBlockSizeConfig {
+        AdaptiveBlockSizeConfig {
+            enabled: false,
+            target_block_execution_latency_ms: 300,
+            target_commit_latency_ms: 1500,
+            min_block_txns: 250,
+            // stop reducing size, so 1MB transactions can still go through
+            min_block_bytes: 1024 * 1024,
+            additive_increase_txns: 250,
+            multiplicative_decrease_factor: 0.5,
+            ewma_alpha: 0.2,
+        }
+    }
+}
+
+/// Configuration for recording all consensus messages, round timeouts and execution results
+/// to disk, so that the decisions of a node can be replayed offline.
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MessageRecorderConfig {
+    pub enabled: bool,
+    // Directory for the recording files (relative paths are resolved against the data dir)
+    pub recording_dir: PathBuf,
+    // Once a file reaches this size, a new file is started
+    pub max_file_size_bytes: u64,
+    // The oldest files are deleted once there are more than this many
+    pub max_num_files: usize,
+    // Events are dropped (and counted) once this many are waiting to be written
+    pub max_pending_entries: usize,
+    #[serde(skip)]
+    data_dir: PathBuf,
+}
+
+impl Default for MessageRecorderConfig {
+    fn default() -> MessageRecorderConfig {
+        MessageRecorderConfig {
+            enabled: false,
+            recording_dir: PathBuf::from("consensus_recording"),
+            max_file_size_bytes: 64 * 1024 * 1024, // 64 MB
+            max_num_files: 16,
+            max_pending_entries: 10_000,
+            data_dir: PathBuf::from("/opt/aptos/data"),
+        }
+    }
+}
+
+impl MessageRecorderConfig {
+    pub fn recording_dir(&self) -> PathBuf {
+        if self.recording_dir.is_relative() {
+            self.data_dir.join(&self.recording_dir)
+        } else {
+            self.recording_dir.clone()
+        }
+    }
+
+    pub fn set_data_dir(&mut self, data_dir: PathBuf) {
+        self.data_dir = data_dir;
+    }
+}
+
 impl Default for ConsensusConfig {
     fn default() -> ConsensusConfig {
         ConsensusConfig {
@@ -205,12 +291,16 @@ impl Default for ConsensusConfig {
                     backoff_proposal_delay_ms: 300,
                 },
             ],
+            adaptive_block_size: AdaptiveBlockSizeConfig::default(),
+            message_recorder: MessageRecorderConfig::default(),
+            zstd_compression: ZstdCompressionConfig::default(),
         }
     }
 }
 
 impl ConsensusConfig {
     pub fn set_data_dir(&mut self, data_dir: PathBuf) {
+        self.message_recorder.set_data_dir(data_dir.clone());
         self.safety_rules.set_data_dir(data_dir);
     }
 
@@ -336,6 +426,18 @@ impl ConsensusConfig {
                 ),
             ));
         }
+        if config.adaptive_block_size.enabled {
+            recv_batch_send_block_pairs.push((
+                config.quorum_store.receiver_max_batch_txns as u64,
+                config.adaptive_block_size.min_block_txns,
+                "adaptive block size: txns".to_string(),
+            ));
+            recv_batch_send_block_pairs.push((
+                config.quorum_store.receiver_max_batch_bytes as u64,
+                config.adaptive_block_size.min_block_bytes,
+                "adaptive block size: bytes".to_string(),
+            ));
+        }
 
         for (batch, block, label) in &recv_batch_send_block_pairs {
             if *batch > *block {
@@ -347,6 +449,57 @@ impl ConsensusConfig {
         }
         Ok(())
     }
+
+    fn sanitize_adaptive_block_size(
+        sanitizer_name: &str,
+        config: &ConsensusConfig,
+    ) -> Result<(), Error> {
+        let adaptive_config = &config.adaptive_block_size;
+        if !adaptive_config.enabled {
+            return Ok(());
+        }
+        if !(adaptive_config.multiplicative_decrease_factor > 0.0
+            && adaptive_config.multiplicative_decrease_factor < 1.0)
+        {
+            return Err(Error::ConfigSanitizerFailed(
+                sanitizer_name.to_owned(),
+                format!(
+                    "Adaptive block size multiplicative_decrease_factor must be in (0, 1), got {}",
+                    adaptive_config.multiplicative_decrease_factor
+                ),
+            ));
+        }
+        if !(adaptive_config.ewma_alpha > 0.0 && adaptive_config.ewma_alpha <= 1.0) {
+            return Err(Error::ConfigSanitizerFailed(
+                sanitizer_name.to_owned(),
+                format!(
+                    "Adaptive block size ewma_alpha must be in (0, 1], got {}",
+                    adaptive_config.ewma_alpha
+                ),
+            ));
+        }
+        Ok(())
+    }
+
+    fn sanitize_message_recorder(
+        sanitizer_name: &str,
+        config: &ConsensusConfig,
+    ) -> Result<(), Error> {
+        let recorder_config = &config.message_recorder;
+        if recorder_config.enabled
+            && (recorder_config.max_file_size_bytes == 0
+                || recorder_config.max_num_files == 0
+                || recorder_config.max_pending_entries == 0)
+        {
+            return Err(Error::ConfigSanitizerFailed(
+                sanitizer_name.to_owned(),
+                "The message recorder requires a non-zero max_file_size_bytes, max_num_files \
+                and max_pending_entries!"
+                    .to_string(),
+            ));
+        }
+        Ok(())
+    }
 }
 
 impl ConfigSanitizer for ConsensusConfig {
@@ -373,6 +526,10 @@ impl ConfigSanitizer for ConsensusConfig {
         Self::sanitize_send_recv_block_limits(&sanitizer_name, &node_config.consensus)?;
         // Quorum store batches must be <= consensus blocks
         Self::sanitize_batch_block_limits(&sanitizer_name, &node_config.consensus)?;
+        // The adaptive block size controller parameters must be within range
+        Self::sanitize_adaptive_block_size(&sanitizer_name, &node_config.consensus)?;
+        // The message recorder must be able to rotate its files
+        Self::sanitize_message_recorder(&sanitizer_name, &node_config.consensus)?;
 
         Ok(())
     }
@@ -400,4 +557,86 @@ mod test {
 
         serde_yaml::from_str::<ConsensusConfig>(&s).unwrap();
     }
+
+    #[test]
+    fn test_sanitize_adaptive_block_size() {
+        let mut node_config = NodeConfig {
+            consensus: ConsensusConfig {
+                adaptive_block_size: AdaptiveBlockSizeConfig {
+                    enabled: true,
+                    multiplicative_decrease_factor: 1.5,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let error = ConsensusConfig::sanitize(
+            &mut node_config,
+            NodeType::ValidatorFullnode,
+            ChainId::testnet(),
+        )
+        .unwrap_err();
+        assert!(matches!(error, Error::ConfigSanitizerFailed(_, _)));
+
+        node_config.consensus.adaptive_block_size = AdaptiveBlockSizeConfig {
+            enabled: true,
+            min_block_txns: 10,
+            ..Default::default()
+        };
+        let error = ConsensusConfig::sanitize(
+            &mut node_config,
+            NodeType::ValidatorFullnode,
+            ChainId::testnet(),
+        )
+        .unwrap_err();
+        assert!(matches!(error, Error::ConfigSanitizerFailed(_, _)));
+
+        node_config.consensus.adaptive_block_size = AdaptiveBlockSizeConfig {
+            enabled: true,
+            ..Default::default()
+        };
+        ConsensusConfig::sanitize(
+            &mut node_config,
+            NodeType::ValidatorFullnode,
+            ChainId::testnet(),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_sanitize_message_recorder() {
+        let mut node_config = NodeConfig {
+            consensus: ConsensusConfig {
+                message_recorder: MessageRecorderConfig {
+                    enabled: true,
+                    max_num_files: 0,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let error =
+            ConsensusConfig::sanitize(&mut node_config, NodeType::Validator, ChainId::testnet())
+                .unwrap_err();
+        assert!(matches!(error, Error::ConfigSanitizerFailed(_, _)));
+
+        node_config.consensus.message_recorder = MessageRecorderConfig {
+            enabled: true,
+            max_pending_entries: 0,
+            ..Default::default()
+        };
+        let error =
+            ConsensusConfig::sanitize(&mut node_config, NodeType::Validator, ChainId::testnet())
+                .unwrap_err();
+        assert!(matches!(error, Error::ConfigSanitizerFailed(_, _)));
+
+        node_config.consensus.message_recorder = MessageRecorderConfig {
+            enabled: true,
+            ..Default::default()
+        };
+        ConsensusConfig::sanitize(&mut node_config, NodeType::Validator, ChainId::testnet())
+            .unwrap();
+    }
 }
@@ -0,0 +1,91 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::config::{
+    config_sanitizer::ConfigSanitizer, node_config_loader::NodeType, Error, NodeConfig,
+};
+use aptos_types::chain_id::ChainId;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ConsensusObserverConfig {
+    pub observer_enabled: bool, // Whether or not to execute the blocks forwarded by a publisher
+    pub publisher_enabled: bool, // Whether or not to forward blocks to subscribed observers
+    pub max_network_channel_size: u64, // Max num of pending network messages
+    pub max_num_pending_blocks: u64, // Max num of ordered blocks waiting for their payloads
+    pub max_num_subscribers: u64, // Max num of observers subscribed to the publisher
+    pub max_subscription_timeout_ms: u64, // Max time (ms) without messages before resubscribing
+    pub network_request_timeout_ms: u64, // The timeout (ms) for each subscription request
+    pub progress_check_interval_ms: u64, // The interval (ms) between subscription checks
+}
+
+impl Default for ConsensusObserverConfig {
+    fn default() -> Self {
+        Self {
+            observer_enabled: false,             // Disabled by default
+            publisher_enabled: false,            // Disabled by default
+            max_network_channel_size: 1000,      // 1000 messages
+            max_num_pending_blocks: 100,         // 100 blocks
+            max_num_subscribers: 20,             // 20 observers
+            max_subscription_timeout_ms: 10_000, // 10 seconds
+            network_request_timeout_ms: 5_000,   // 5 seconds
+            progress_check_interval_ms: 1_000,   // 1 second
+        }
+    }
+}
+
+impl ConfigSanitizer for ConsensusObserverConfig {
+    fn sanitize(
+        node_config: &mut NodeConfig,
+        node_type: NodeType,
+        _chain_id: ChainId,
+    ) -> Result<(), Error> {
+        let sanitizer_name = Self::get_sanitizer_name();
+        let consensus_observer_config = &node_config.consensus_observer;
+
+        // Verify that validators do not run the observer (they participate in consensus)
+        if node_type.is_validator() && consensus_observer_config.observer_enabled {
+            return Err(Error::ConfigSanitizerFailed(
+                sanitizer_name,
+                "Validators cannot enable the consensus observer!".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sanitize_observer_on_validator() {
+        // Create a node config with the observer enabled
+        let mut node_config = NodeConfig {
+            consensus_observer: ConsensusObserverConfig {
+                observer_enabled: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        // Verify that the config fails sanitization for validators
+        let error = ConsensusObserverConfig::sanitize(
+            &mut node_config,
+            NodeType::Validator,
+            ChainId::testnet(),
+        )
+        .unwrap_err();
+        assert!(matches!(error, Error::ConfigSanitizerFailed(_, _)));
+
+        // Verify that the config passes sanitization for fullnodes
+        ConsensusObserverConfig::sanitize(
+            &mut node_config,
+            NodeType::PublicFullnode,
+            ChainId::testnet(),
+        )
+        .unwrap();
+    }
+}
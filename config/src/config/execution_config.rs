@@ -11,10 +11,14 @@ use serde::{Deserialize, Serialize};
 use std::{
     fs::File,
     io::{Read, Write},
+    net::SocketAddr,
     path::PathBuf,
 };
 
 const GENESIS_DEFAULT: &str = "genesis.blob";
+const REMOTE_EXECUTION_HEARTBEAT_INTERVAL_MS: u64 = 1_000;
+const REMOTE_EXECUTION_HEARTBEAT_TIMEOUT_MS: u64 = 30_000;
+const REMOTE_EXECUTION_TIMEOUT_MS: u64 = 120_000;
 
 #[derive(Clone, Deserialize, PartialEq, Eq, Serialize)]
 #[serde(default, deny_unknown_fields)]
@@ -34,6 +38,57 @@ pub struct ExecutionConfig {
     pub paranoid_hot_potato_verification: bool,
     /// Enables enhanced metrics around processed transactions
     pub processed_transactions_detailed_counters: bool,
+    /// Number of threads to speculatively pre-execute proposed blocks, 0 disables it
+    pub speculative_execution_threads: u16,
+    /// Max number of speculatively executed transactions to keep the outputs of
+    pub speculative_execution_cache_size: usize,
+    /// Executes the shards of sharded blocks on remote executor services, if specified
+    pub remote_execution: Option<RemoteExecutionConfig>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct RemoteExecutionConfig {
+    /// Address the coordinator listens on for the results of the remote executor services
+    pub coordinator_address: SocketAddr,
+    /// Addresses of the remote executor services (one per shard, in shard id order)
+    pub remote_executor_addresses: Vec<SocketAddr>,
+    /// Interval (ms) between the heartbeats sent to each remote executor service
+    #[serde(default = "default_heartbeat_interval_ms")]
+    pub heartbeat_interval_ms: u64,
+    /// Time (ms) without a heartbeat response after which a remote executor service is dead
+    #[serde(default = "default_heartbeat_timeout_ms")]
+    pub heartbeat_timeout_ms: u64,
+    /// Max time (ms) to wait for the results of a block, before executing it locally instead
+    #[serde(default = "default_execution_timeout_ms")]
+    pub execution_timeout_ms: u64,
+}
+
+impl RemoteExecutionConfig {
+    pub fn new(
+        coordinator_address: SocketAddr,
+        remote_executor_addresses: Vec<SocketAddr>,
+    ) -> Self {
+        Self {
+            coordinator_address,
+            remote_executor_addresses,
+            heartbeat_interval_ms: default_heartbeat_interval_ms(),
+            heartbeat_timeout_ms: default_heartbeat_timeout_ms(),
+            execution_timeout_ms: default_execution_timeout_ms(),
+        }
+    }
+}
+
+fn default_heartbeat_interval_ms() -> u64 {
+    REMOTE_EXECUTION_HEARTBEAT_INTERVAL_MS
+}
+
+fn default_heartbeat_timeout_ms() -> u64 {
+    REMOTE_EXECUTION_HEARTBEAT_TIMEOUT_MS
+}
+
+fn default_execution_timeout_ms() -> u64 {
+    REMOTE_EXECUTION_TIMEOUT_MS
 }
 
 impl std::fmt::Debug for ExecutionConfig {
@@ -63,6 +118,9 @@ impl Default for ExecutionConfig {
             paranoid_type_verification: true,
             paranoid_hot_potato_verification: true,
             processed_transactions_detailed_counters: false,
+            speculative_execution_threads: 0,
+            speculative_execution_cache_size: 10_000,
+            remote_execution: None,
         }
     }
 }
@@ -150,6 +208,24 @@ impl ConfigSanitizer for ExecutionConfig {
             }
         }
 
+        // If remote execution is enabled, ensure there are shards and they can be detected as dead
+        if let Some(remote_execution_config) = &execution_config.remote_execution {
+            if remote_execution_config.remote_executor_addresses.is_empty() {
+                return Err(Error::ConfigSanitizerFailed(
+                    sanitizer_name,
+                    "remote_executor_addresses must not be empty for remote execution!".into(),
+                ));
+            }
+            if remote_execution_config.heartbeat_timeout_ms
+                <= remote_execution_config.heartbeat_interval_ms
+            {
+                return Err(Error::ConfigSanitizerFailed(
+                    sanitizer_name,
+                    "heartbeat_timeout_ms must be larger than heartbeat_interval_ms!".into(),
+                ));
+            }
+        }
+
         Ok(())
     }
 }
@@ -218,6 +294,45 @@ mod test {
         assert!(matches!(error, Error::ConfigSanitizerFailed(_, _)));
     }
 
+    #[test]
+    fn test_sanitize_remote_execution() {
+        // Create a node config with remote execution but no remote executor addresses
+        let coordinator_address = SocketAddr::from(([127, 0, 0, 1], 52200));
+        let mut node_config = NodeConfig {
+            execution: ExecutionConfig {
+                remote_execution: Some(RemoteExecutionConfig::new(coordinator_address, vec![])),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        // Sanitize the config and verify that it fails
+        let error =
+            ExecutionConfig::sanitize(&mut node_config, NodeType::Validator, ChainId::testnet())
+                .unwrap_err();
+        assert!(matches!(error, Error::ConfigSanitizerFailed(_, _)));
+
+        // Add a remote executor address, but make the heartbeat timeout too short
+        let mut remote_execution_config = RemoteExecutionConfig::new(coordinator_address, vec![
+            SocketAddr::from(([127, 0, 0, 1], 52201)),
+        ]);
+        remote_execution_config.heartbeat_timeout_ms =
+            remote_execution_config.heartbeat_interval_ms;
+        node_config.execution.remote_execution = Some(remote_execution_config.clone());
+
+        // Sanitize the config and verify that it fails
+        let error =
+            ExecutionConfig::sanitize(&mut node_config, NodeType::Validator, ChainId::testnet())
+                .unwrap_err();
+        assert!(matches!(error, Error::ConfigSanitizerFailed(_, _)));
+
+        // Use the default heartbeat timeout and verify that the config is now valid
+        remote_execution_config.heartbeat_timeout_ms = default_heartbeat_timeout_ms();
+        node_config.execution.remote_execution = Some(remote_execution_config);
+        ExecutionConfig::sanitize(&mut node_config, NodeType::Validator, ChainId::testnet())
+            .unwrap();
+    }
+
     #[test]
     fn test_no_genesis() {
         let (mut config, path) = generate_config();
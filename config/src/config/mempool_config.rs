@@ -4,7 +4,8 @@
 
 use crate::config::{
     config_optimizer::ConfigOptimizer, config_sanitizer::ConfigSanitizer,
-    node_config_loader::NodeType, Error, NodeConfig, MAX_APPLICATION_MESSAGE_SIZE,
+    node_config_loader::NodeType, Error, NodeConfig, ZstdCompressionConfig,
+    MAX_APPLICATION_MESSAGE_SIZE,
 };
 use aptos_global_constants::DEFAULT_BUCKETS;
 use aptos_types::chain_id::ChainId;
@@ -26,6 +27,14 @@ pub struct MempoolConfig {
     pub max_broadcasts_per_peer: usize,
     /// Maximum number of inbound network messages to the Mempool application
     pub max_network_channel_size: usize,
+    /// The interval (ms) at which the Mempool health summary (served to peers) is refreshed
+    pub mempool_health_update_interval_ms: u64,
+    /// Maximum number of transactions an upstream peer may report holding in its Mempool
+    /// before it is deprioritized for broadcasts (as measured by the peer monitoring service).
+    pub max_upstream_mempool_transactions: u64,
+    /// Maximum sync lag (in seconds) an upstream peer may report before it is deprioritized
+    /// for broadcasts (as measured by the peer monitoring service).
+    pub max_upstream_sync_lag_secs: u64,
     /// The interval to take a snapshot of the mempool to logs, only used when trace logging is enabled
     pub mempool_snapshot_interval_secs: u64,
     /// The maximum amount of time to wait for an ACK of Mempool submission to an upstream node.
@@ -53,6 +62,8 @@ pub struct MempoolConfig {
     pub broadcast_buckets: Vec<u64>,
     pub eager_expire_threshold_ms: Option<u64>,
     pub eager_expire_time_ms: u64,
+    /// Configuration for compressing mempool broadcasts with zstd.
+    pub zstd_compression: ZstdCompressionConfig,
 }
 
 impl Default for MempoolConfig {
@@ -66,6 +77,9 @@ impl Default for MempoolConfig {
             shared_mempool_max_concurrent_inbound_syncs: 4,
             max_broadcasts_per_peer: 2,
             max_network_channel_size: 1024,
+            mempool_health_update_interval_ms: 1_000,
+            max_upstream_mempool_transactions: 1_800_000,
+            max_upstream_sync_lag_secs: 30,
             mempool_snapshot_interval_secs: 180,
             capacity: 2_000_000,
             capacity_bytes: 2 * 1024 * 1024 * 1024,
@@ -77,6 +91,7 @@ impl Default for MempoolConfig {
             broadcast_buckets: DEFAULT_BUCKETS.to_vec(),
             eager_expire_threshold_ms: Some(10_000),
             eager_expire_time_ms: 3_000,
+            zstd_compression: ZstdCompressionConfig::default(),
         }
     }
 }
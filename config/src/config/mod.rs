@@ -5,9 +5,11 @@
 // All modules should be declared below
 mod api_config;
 mod base_config;
+mod compression_config;
 mod config_optimizer;
 mod config_sanitizer;
 mod consensus_config;
+mod consensus_observer_config;
 mod error;
 mod execution_config;
 mod gas_estimation_config;
@@ -32,7 +34,9 @@ mod utils;
 // All public usage statements should be declared below
 pub use api_config::*;
 pub use base_config::*;
+pub use compression_config::*;
 pub use consensus_config::*;
+pub use consensus_observer_config::*;
 pub use error::*;
 pub use execution_config::*;
 pub use gas_estimation_config::*;
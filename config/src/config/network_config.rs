@@ -53,6 +53,22 @@ pub const MAX_MESSAGE_SIZE: usize = 64 * 1024 * 1024; /* 64 MiB */
 pub const CONNECTION_BACKOFF_BASE: u64 = 2;
 pub const IP_BYTE_BUCKET_RATE: usize = 102400 /* 100 KiB */;
 pub const IP_BYTE_BUCKET_SIZE: usize = IP_BYTE_BUCKET_RATE;
+pub const CONSENSUS_TRAFFIC_WEIGHT: u32 = 16;
+pub const QUORUM_STORE_TRAFFIC_WEIGHT: u32 = 8;
+pub const MEMPOOL_TRAFFIC_WEIGHT: u32 = 4;
+pub const STATE_SYNC_TRAFFIC_WEIGHT: u32 = 2;
+pub const PEER_MONITORING_TRAFFIC_WEIGHT: u32 = 1;
+pub const PEER_BAN_THRESHOLD: f64 = -100.0;
+pub const PEER_SCORE_HALF_LIFE_SECS: u64 = 300; /* 5 minutes */
+pub const PEER_BAN_DURATION_SECS: u64 = 600; /* 10 minutes */
+pub const PEER_EXCHANGE_INTERVAL_SECS: u64 = 60;
+pub const PEER_EXCHANGE_RECORD_TTL_SECS: u64 = 3600; /* 1 hour */
+pub const MAX_PEER_EXCHANGE_RECORDS_PER_MESSAGE: usize = 32;
+pub const MAX_PEER_EXCHANGE_DISCOVERED_PEERS: usize = 100;
+pub const MAX_PEER_EXCHANGE_PEERS_PER_SUBNET: usize = 2;
+pub const TRAFFIC_CAPTURE_MAX_FILE_SIZE_BYTES: u64 = 64 * 1024 * 1024; /* 64 MiB */
+pub const TRAFFIC_CAPTURE_MAX_NUM_FILES: usize = 16;
+pub const MAX_PENDING_CAPTURED_MESSAGES: usize = 10_000;
 pub const INBOUND_TCP_RX_BUFFER_SIZE: u32 = 3 * 1024 * 1024; // 3MB ~6MB/s with 500ms latency
 pub const INBOUND_TCP_TX_BUFFER_SIZE: u32 = 512 * 1024; // 1MB use a bigger spoon
 pub const OUTBOUND_TCP_RX_BUFFER_SIZE: u32 = 3 * 1024 * 1024; // 3MB ~6MB/s with 500ms latency
@@ -80,7 +96,8 @@ pub struct NetworkConfig {
     /// Identity of this network
     pub identity: Identity,
     // TODO: Add support for multiple listen/advertised addresses in config.
-    /// The address that this node is listening on for new connections.
+    /// The address that this node is listening on for new connections. Use a
+    /// `/udp/<port>/quic` address instead of `/tcp/<port>` to select the QUIC transport.
     pub listen_address: NetworkAddress,
     /// Select this to enforce that both peers should authenticate each other, otherwise
     /// authentication only occurs for outgoing connections.
@@ -117,6 +134,12 @@ pub struct NetworkConfig {
     pub inbound_rate_limit_config: Option<RateLimitConfig>,
     /// Outbound rate limiting configuration, if not specified, no rate limiting
     pub outbound_rate_limit_config: Option<RateLimitConfig>,
+    /// Scheduling of the outbound messages to each peer between the classes of traffic
+    pub outbound_qos_config: OutboundQosConfig,
+    /// Scoring of the peers by the applications, and banning of the misbehaving ones
+    pub peer_reputation_config: PeerReputationConfig,
+    /// Capturing of the messages sent and received on this network, for offline inspection
+    pub traffic_capture_config: TrafficCaptureConfig,
     /// The maximum size of an inbound or outbound message (it may be divided into multiple frame)
     pub max_message_size: usize,
     /// The maximum number of parallel message deserialization tasks that can run (per application)
@@ -156,6 +179,9 @@ impl NetworkConfig {
             max_inbound_connections: MAX_INBOUND_CONNECTIONS,
             inbound_rate_limit_config: None,
             outbound_rate_limit_config: None,
+            outbound_qos_config: OutboundQosConfig::default(),
+            peer_reputation_config: PeerReputationConfig::default(),
+            traffic_capture_config: TrafficCaptureConfig::default(),
             max_message_size: MAX_MESSAGE_SIZE,
             inbound_rx_buffer_size_bytes: Some(INBOUND_TCP_RX_BUFFER_SIZE),
             inbound_tx_buffer_size_bytes: Some(INBOUND_TCP_TX_BUFFER_SIZE),
@@ -344,6 +370,7 @@ pub enum DiscoveryMethod {
     Onchain,
     File(FileDiscovery),
     Rest(RestDiscovery),
+    PeerExchange(PeerExchangeDiscovery),
     None,
 }
 
@@ -361,6 +388,41 @@ pub struct RestDiscovery {
     pub interval_secs: u64,
 }
 
+/// Discovers peers by periodically exchanging address records with connected peers.
+/// Only meant for the public network, where peer ids are derived from the network
+/// keys (which allows the addresses in the records to be checked against the peer ids).
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PeerExchangeDiscovery {
+    /// Interval between two exchanges (each with a single random peer)
+    pub interval_secs: u64,
+    /// The publicly reachable addresses of this node to advertise to other peers. If
+    /// empty, the node only relays the records of others.
+    pub advertised_addresses: Vec<NetworkAddress>,
+    /// Records older than this are no longer relayed nor dialed
+    pub record_ttl_secs: u64,
+    /// Maximum number of records to send (or accept) in a single message
+    pub max_records_per_message: usize,
+    /// Maximum number of discovered peers handed to the connectivity manager
+    pub max_discovered_peers: usize,
+    /// Maximum number of discovered peers sharing a subnet (IPv4 /24 or IPv6 /48),
+    /// so that a single operator can't eclipse the node
+    pub max_peers_per_subnet: usize,
+}
+
+impl Default for PeerExchangeDiscovery {
+    fn default() -> Self {
+        Self {
+            interval_secs: PEER_EXCHANGE_INTERVAL_SECS,
+            advertised_addresses: Vec::new(),
+            record_ttl_secs: PEER_EXCHANGE_RECORD_TTL_SECS,
+            max_records_per_message: MAX_PEER_EXCHANGE_RECORDS_PER_MESSAGE,
+            max_discovered_peers: MAX_PEER_EXCHANGE_DISCOVERED_PEERS,
+            max_peers_per_subnet: MAX_PEER_EXCHANGE_PEERS_PER_SUBNET,
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
 #[serde(deny_unknown_fields)]
 pub struct RateLimitConfig {
@@ -385,6 +447,116 @@ impl Default for RateLimitConfig {
     }
 }
 
+/// The outbound messages to a peer are queued per class of traffic, and the classes share the
+/// connection in proportion to their weights (so that e.g., a burst of state sync responses can't
+/// delay consensus votes). Each class may also be limited to a number of bytes/s per peer.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct OutboundQosConfig {
+    /// Allow for disabling the scheduling, in which case messages are sent in FIFO order
+    pub enabled: bool,
+    /// Consensus messages (e.g., proposals and votes)
+    pub consensus: TrafficClassConfig,
+    /// Quorum store batches, signed batch infos and proofs of store
+    pub quorum_store: TrafficClassConfig,
+    /// Mempool transaction broadcasts
+    pub mempool: TrafficClassConfig,
+    /// Storage service requests and responses
+    pub state_sync: TrafficClassConfig,
+    /// Peer monitoring, health checker and discovery messages
+    pub peer_monitoring: TrafficClassConfig,
+}
+
+impl Default for OutboundQosConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            consensus: TrafficClassConfig::new(CONSENSUS_TRAFFIC_WEIGHT),
+            quorum_store: TrafficClassConfig::new(QUORUM_STORE_TRAFFIC_WEIGHT),
+            mempool: TrafficClassConfig::new(MEMPOOL_TRAFFIC_WEIGHT),
+            state_sync: TrafficClassConfig::new(STATE_SYNC_TRAFFIC_WEIGHT),
+            peer_monitoring: TrafficClassConfig::new(PEER_MONITORING_TRAFFIC_WEIGHT),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct TrafficClassConfig {
+    /// Share of the outbound bandwidth, relative to the weights of the other classes
+    pub weight: u32,
+    /// Maximum number of bytes/s to a peer, if not specified, no rate limiting
+    pub byte_rate_limit: Option<usize>,
+}
+
+impl TrafficClassConfig {
+    pub fn new(weight: u32) -> Self {
+        Self {
+            weight,
+            byte_rate_limit: None,
+        }
+    }
+}
+
+/// The applications report the good and bad behaviour of peers to a shared reputation service,
+/// where scores decay back to zero over time. Peers whose score drops below the threshold are
+/// disconnected and refused for a while.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PeerReputationConfig {
+    /// Allow for disabling the bans, in which case scores are only tracked
+    pub enable_bans: bool,
+    /// Peers are banned once their score drops below this threshold
+    pub ban_threshold: f64,
+    /// Time (secs) for a score to decay halfway back to zero
+    pub score_half_life_secs: u64,
+    /// The min time (secs) to ban a peer for (doubles for each subsequent ban)
+    pub ban_duration_secs: u64,
+}
+
+impl Default for PeerReputationConfig {
+    fn default() -> Self {
+        Self {
+            enable_bans: true,
+            ban_threshold: PEER_BAN_THRESHOLD,
+            score_half_life_secs: PEER_SCORE_HALF_LIFE_SECS,
+            ban_duration_secs: PEER_BAN_DURATION_SECS,
+        }
+    }
+}
+
+/// The metadata (and optionally the payloads) of all messages sent and received on the network
+/// can be captured to rotating files, which can be decoded and filtered offline.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TrafficCaptureConfig {
+    /// Allow for enabling the capture (it is disabled by default)
+    pub enabled: bool,
+    /// Directory for the capture files (shared by all networks, the files are named by network)
+    pub capture_dir: PathBuf,
+    /// Whether to capture the (still encoded) message payloads, and not only the metadata
+    pub include_payloads: bool,
+    /// Once a file reaches this size, a new file is started
+    pub max_file_size_bytes: u64,
+    /// The oldest files (of the network) are deleted once there are more than this many
+    pub max_num_files: usize,
+    /// Messages are dropped from the capture (and not delayed) when the writer falls this far behind
+    pub max_pending_messages: usize,
+}
+
+impl Default for TrafficCaptureConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            capture_dir: PathBuf::from("/opt/aptos/data/network_capture"),
+            include_payloads: false,
+            max_file_size_bytes: TRAFFIC_CAPTURE_MAX_FILE_SIZE_BYTES,
+            max_num_files: TRAFFIC_CAPTURE_MAX_NUM_FILES,
+            max_pending_messages: MAX_PENDING_CAPTURED_MESSAGES,
+        }
+    }
+}
+
 pub type PeerSet = HashMap<PeerId, Peer>;
 
 // TODO: Combine with RoleType?
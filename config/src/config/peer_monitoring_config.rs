@@ -20,6 +20,7 @@ pub struct PeerMonitoringServiceConfig {
     pub max_request_jitter_ms: u64, // Max amount of jitter (ms) that a request will be delayed for
     pub metadata_update_interval_ms: u64, // The interval (ms) between metadata updates
     pub network_monitoring: NetworkMonitoringConfig,
+    pub node_health_monitoring: NodeHealthMonitoringConfig,
     pub node_monitoring: NodeMonitoringConfig,
     pub peer_monitor_interval_usec: u64, // The interval (usec) between peer monitor executions
     pub performance_monitoring: PerformanceMonitoringConfig,
@@ -36,6 +37,7 @@ impl Default for PeerMonitoringServiceConfig {
             max_request_jitter_ms: 1000,        // Monitoring requests are very infrequent
             metadata_update_interval_ms: 5000,  // 5 seconds
             network_monitoring: NetworkMonitoringConfig::default(),
+            node_health_monitoring: NodeHealthMonitoringConfig::default(),
             node_monitoring: NodeMonitoringConfig::default(),
             peer_monitor_interval_usec: 1_000_000, // 1 second
             performance_monitoring: PerformanceMonitoringConfig::default(),
@@ -125,6 +127,22 @@ impl Default for NodeMonitoringConfig {
     }
 }
 
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct NodeHealthMonitoringConfig {
+    pub node_health_request_interval_ms: u64, // The interval (ms) between node health requests
+    pub node_health_request_timeout_ms: u64,  // The timeout (ms) for each node health request
+}
+
+impl Default for NodeHealthMonitoringConfig {
+    fn default() -> Self {
+        Self {
+            node_health_request_interval_ms: 15_000, // 15 seconds
+            node_health_request_timeout_ms: 10_000,  // 10 seconds
+        }
+    }
+}
+
 impl ConfigSanitizer for PeerMonitoringServiceConfig {
     fn sanitize(
         node_config: &mut NodeConfig,
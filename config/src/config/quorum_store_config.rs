@@ -65,6 +65,8 @@ pub struct QuorumStoreConfig {
     pub memory_quota: usize,
     pub db_quota: usize,
     pub batch_quota: usize,
+    /// Interval at which expired batches and proofs are deleted from the quorum store db.
+    pub db_compaction_interval_ms: u64,
     pub mempool_txn_pull_max_bytes: u64,
     pub back_pressure: QuorumStoreBackPressureConfig,
     pub num_workers_for_remote_batches: usize,
@@ -97,6 +99,7 @@ impl Default for QuorumStoreConfig {
             memory_quota: 120_000_000,
             db_quota: 300_000_000,
             batch_quota: 300_000,
+            db_compaction_interval_ms: 60_000,
             mempool_txn_pull_max_bytes: 4 * 1024 * 1024,
             back_pressure: QuorumStoreBackPressureConfig::default(),
             // number of batch coordinators to handle QS batch messages, should be >= 1
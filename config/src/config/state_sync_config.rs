@@ -9,6 +9,7 @@ use crate::config::{
 use aptos_types::chain_id::ChainId;
 use serde::{Deserialize, Serialize};
 use serde_yaml::Value;
+use std::path::PathBuf;
 
 // The maximum message size per state sync message
 const MAX_MESSAGE_SIZE: usize = 4 * 1024 * 1024; /* 4 MiB */
@@ -23,9 +24,14 @@ const MAX_TRANSACTION_OUTPUT_CHUNK_SIZE: u64 = 1000;
 const MAX_CONCURRENT_REQUESTS: u64 = 6;
 const MAX_CONCURRENT_STATE_REQUESTS: u64 = 6;
 
-#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq, Serialize)]
+// The default concurrency used when restoring from backups
+const BACKUP_CONCURRENT_DOWNLOADS: usize = 16;
+const BACKUP_REPLAY_CONCURRENCY_LEVEL: usize = 8;
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq, Serialize)]
 #[serde(default, deny_unknown_fields)]
 pub struct StateSyncConfig {
+    pub backup_restore: BackupRestoreConfig,
     pub data_streaming_service: DataStreamingServiceConfig,
     pub aptos_data_client: AptosDataClientConfig,
     pub state_sync_driver: StateSyncDriverConfig,
@@ -44,6 +50,9 @@ pub enum BootstrappingMode {
     ExecuteTransactionsFromGenesis,
     /// Executes transactions or applies outputs from genesis (whichever is faster)
     ExecuteOrApplyFromGenesis,
+    /// Restores the epoch endings, states and transactions from backups (see
+    /// `BackupRestoreConfig`) and then syncs the remaining data from genesis
+    RestoreFromBackup,
 }
 
 impl BootstrappingMode {
@@ -57,6 +66,33 @@ impl BootstrappingMode {
                 "execute_transactions_from_genesis"
             },
             BootstrappingMode::ExecuteOrApplyFromGenesis => "execute_or_apply_from_genesis",
+            BootstrappingMode::RestoreFromBackup => "restore_from_backup",
+        }
+    }
+}
+
+/// The backups to restore from when bootstrapping with `BootstrappingMode::RestoreFromBackup`.
+/// Exactly one backup storage must be specified.
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BackupRestoreConfig {
+    /// The local directory holding the backups
+    pub local_fs_dir: Option<PathBuf>,
+    /// The command adapter config used to access the backups (e.g., in an object store)
+    pub command_adapter_config: Option<PathBuf>,
+    /// The maximum number of backup files to download concurrently
+    pub concurrent_downloads: usize,
+    /// The number of transactions to replay concurrently
+    pub replay_concurrency_level: usize,
+}
+
+impl Default for BackupRestoreConfig {
+    fn default() -> Self {
+        Self {
+            local_fs_dir: None,
+            command_adapter_config: None,
+            concurrent_downloads: BACKUP_CONCURRENT_DOWNLOADS,
+            replay_concurrency_level: BACKUP_REPLAY_CONCURRENCY_LEVEL,
         }
     }
 }
@@ -103,6 +139,9 @@ pub struct StateSyncDriverConfig {
     pub progress_check_interval_ms: u64,
     /// The maximum time (secs) to wait for connections from peers before auto-bootstrapping
     pub max_connection_deadline_secs: u64,
+    /// The maximum time (ms) without consensus observer commits before the observer is
+    /// paused and continuous syncing resumes
+    pub max_consensus_observer_silence_ms: u64,
     /// The maximum number of notifications to process per driver loop
     pub max_consecutive_stream_notifications: u64,
     /// The maximum number of stream timeouts allowed before termination
@@ -129,6 +168,7 @@ impl Default for StateSyncDriverConfig {
             fallback_to_output_syncing_secs: 180, // 3 minutes
             progress_check_interval_ms: 100,
             max_connection_deadline_secs: 10,
+            max_consensus_observer_silence_ms: 10_000, // 10 seconds
             max_consecutive_stream_notifications: 10,
             max_num_stream_timeouts: 12,
             max_pending_data_chunks: 100,
@@ -215,6 +255,12 @@ pub struct DataStreamingServiceConfig {
     /// memory. Once the number grows beyond this value, garbage collection occurs.
     pub max_notification_id_mappings: u64,
 
+    /// Maximum number of state value chunks that can be sent along a state
+    /// value stream ahead of (i.e., out of order with) the next missing chunk.
+    /// This allows chunks to be fetched concurrently from different peers
+    /// without stalling on the slowest response. If 0, chunks are sent in order.
+    pub max_out_of_order_state_chunks: u64,
+
     /// The interval (milliseconds) at which to check the progress of each stream.
     pub progress_check_interval_ms: u64,
 }
@@ -228,6 +274,7 @@ impl Default for DataStreamingServiceConfig {
             max_data_stream_channel_sizes: 300,
             max_request_retry: 5,
             max_notification_id_mappings: 300,
+            max_out_of_order_state_chunks: 30,
             progress_check_interval_ms: 100,
         }
     }
@@ -236,6 +283,11 @@ impl Default for DataStreamingServiceConfig {
 #[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq, Serialize)]
 #[serde(default, deny_unknown_fields)]
 pub struct AptosDataClientConfig {
+    /// Whether or not to favor low-latency and high-throughput peers when selecting
+    /// peers for requests (instead of selecting peers at random)
+    pub enable_latency_aware_peer_selection: bool,
+    /// Whether or not to hedge slow optimistic fetches (by sending the request to another peer)
+    pub enable_optimistic_fetch_hedging: bool,
     /// The interval (milliseconds) at which to refresh the latency monitor
     pub latency_monitor_loop_interval_ms: u64,
     /// Maximum number of epoch ending ledger infos per chunk
@@ -246,6 +298,9 @@ pub struct AptosDataClientConfig {
     pub max_num_in_flight_regular_polls: u64,
     /// Maximum number of output reductions before transactions are returned
     pub max_num_output_reductions: u64,
+    /// Maximum sync lag (in seconds) reported by a peer before it is avoided
+    /// for requests (if other peers are available).
+    pub max_peer_sync_lag_secs: u64,
     /// Maximum timeout (in ms) when waiting for a response (after exponential increases)
     pub max_response_timeout_ms: u64,
     /// Maximum number of state keys and values per chunk
@@ -254,6 +309,8 @@ pub struct AptosDataClientConfig {
     pub max_transaction_chunk_size: u64,
     /// Maximum number of transaction outputs per chunk
     pub max_transaction_output_chunk_size: u64,
+    /// The percentile of recent optimistic fetch latencies after which fetches are hedged
+    pub optimistic_fetch_hedging_percentile: u64,
     /// First timeout (in ms) when waiting for a response
     pub response_timeout_ms: u64,
     /// Timeout (in ms) when waiting for a subscription response
@@ -267,15 +324,19 @@ pub struct AptosDataClientConfig {
 impl Default for AptosDataClientConfig {
     fn default() -> Self {
         Self {
+            enable_latency_aware_peer_selection: true,
+            enable_optimistic_fetch_hedging: true,
             latency_monitor_loop_interval_ms: 50, // 50 milliseconds
             max_epoch_chunk_size: MAX_EPOCH_CHUNK_SIZE,
             max_num_in_flight_priority_polls: 10,
             max_num_in_flight_regular_polls: 10,
             max_num_output_reductions: 0,
+            max_peer_sync_lag_secs: 30,     // 30 seconds
             max_response_timeout_ms: 60000, // 60 seconds
             max_state_chunk_size: MAX_STATE_CHUNK_SIZE,
             max_transaction_chunk_size: MAX_TRANSACTION_CHUNK_SIZE,
             max_transaction_output_chunk_size: MAX_TRANSACTION_OUTPUT_CHUNK_SIZE,
+            optimistic_fetch_hedging_percentile: 95,
             response_timeout_ms: 10000,    // 10 seconds
             subscription_timeout_ms: 5000, // 5 seconds
             summary_poll_loop_interval_ms: 200,
@@ -286,10 +347,52 @@ impl Default for AptosDataClientConfig {
 
 impl ConfigSanitizer for StateSyncConfig {
     fn sanitize(
-        _node_config: &mut NodeConfig,
+        node_config: &mut NodeConfig,
         _node_type: NodeType,
         _chain_id: ChainId,
     ) -> Result<(), Error> {
+        let sanitizer_name = Self::get_sanitizer_name();
+        let state_sync_config = &node_config.state_sync;
+
+        // Verify that the hedging percentile is valid
+        let data_client_config = &state_sync_config.aptos_data_client;
+        if data_client_config.enable_optimistic_fetch_hedging
+            && !(1..=100).contains(&data_client_config.optimistic_fetch_hedging_percentile)
+        {
+            return Err(Error::ConfigSanitizerFailed(
+                sanitizer_name,
+                "The optimistic fetch hedging percentile must be between 1 and 100!".into(),
+            ));
+        }
+
+        // The backup config is only used when restoring from backups
+        if state_sync_config.state_sync_driver.bootstrapping_mode
+            != BootstrappingMode::RestoreFromBackup
+        {
+            return Ok(());
+        }
+
+        // Verify that exactly one backup storage is specified
+        let backup_restore_config = &state_sync_config.backup_restore;
+        if backup_restore_config.local_fs_dir.is_some()
+            == backup_restore_config.command_adapter_config.is_some()
+        {
+            return Err(Error::ConfigSanitizerFailed(
+                sanitizer_name,
+                "Exactly one backup storage must be set when restoring from backups!".into(),
+            ));
+        }
+
+        // Verify that the restore concurrency is valid
+        if backup_restore_config.concurrent_downloads == 0
+            || backup_restore_config.replay_concurrency_level == 0
+        {
+            return Err(Error::ConfigSanitizerFailed(
+                sanitizer_name,
+                "The backup restore concurrency must be greater than 0!".into(),
+            ));
+        }
+
         Ok(())
     }
 }
@@ -573,6 +676,71 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_sanitize_restore_from_backup() {
+        // Create a node config that restores from backups, without a backup storage
+        let mut node_config = NodeConfig {
+            state_sync: StateSyncConfig {
+                state_sync_driver: StateSyncDriverConfig {
+                    bootstrapping_mode: BootstrappingMode::RestoreFromBackup,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        // Verify that sanitization fails because no backup storage is specified
+        let error =
+            StateSyncConfig::sanitize(&mut node_config, NodeType::PublicFullnode, ChainId::test())
+                .unwrap_err();
+        assert!(matches!(error, Error::ConfigSanitizerFailed(_, _)));
+
+        // Verify that sanitization fails if both backup storages are specified
+        let backup_restore_config = &mut node_config.state_sync.backup_restore;
+        backup_restore_config.local_fs_dir = Some(PathBuf::from("/opt/aptos/backups"));
+        backup_restore_config.command_adapter_config = Some(PathBuf::from("/opt/aptos/s3.yaml"));
+        let error =
+            StateSyncConfig::sanitize(&mut node_config, NodeType::PublicFullnode, ChainId::test())
+                .unwrap_err();
+        assert!(matches!(error, Error::ConfigSanitizerFailed(_, _)));
+
+        // Verify that sanitization succeeds with a single backup storage
+        node_config.state_sync.backup_restore.command_adapter_config = None;
+        StateSyncConfig::sanitize(&mut node_config, NodeType::PublicFullnode, ChainId::test())
+            .unwrap();
+    }
+
+    #[test]
+    fn test_sanitize_hedging_percentile() {
+        // Create a node config with an invalid hedging percentile
+        let mut node_config = NodeConfig {
+            state_sync: StateSyncConfig {
+                aptos_data_client: AptosDataClientConfig {
+                    enable_optimistic_fetch_hedging: true,
+                    optimistic_fetch_hedging_percentile: 101,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        // Verify that sanitization fails
+        let error =
+            StateSyncConfig::sanitize(&mut node_config, NodeType::PublicFullnode, ChainId::test())
+                .unwrap_err();
+        assert!(matches!(error, Error::ConfigSanitizerFailed(_, _)));
+
+        // Disable hedging and verify that sanitization succeeds
+        node_config
+            .state_sync
+            .aptos_data_client
+            .enable_optimistic_fetch_hedging = false;
+        StateSyncConfig::sanitize(&mut node_config, NodeType::PublicFullnode, ChainId::test())
+            .unwrap();
+    }
+
     /// Creates and returns a node config with the syncing modes set to execution
     fn create_execution_mode_config() -> NodeConfig {
         NodeConfig {
@@ -16,6 +16,7 @@ use aptos_types::{
     block_metadata::BlockMetadata,
     epoch_state::EpochState,
     ledger_info::LedgerInfo,
+    on_chain_config::BlockGasLimitType,
     transaction::{SignedTransaction, Transaction, Version},
     validator_signer::ValidatorSigner,
     validator_verifier::ValidatorVerifier,
@@ -346,9 +347,9 @@ impl Block {
         &self,
         validators: &[AccountAddress],
         txns: Vec<SignedTransaction>,
-        block_gas_limit: Option<u64>,
+        block_gas_limit_type: BlockGasLimitType,
     ) -> Vec<Transaction> {
-        if block_gas_limit.is_some() {
+        if block_gas_limit_type.is_limited() {
             // After the per-block gas limit change, StateCheckpoint txn
             // is inserted after block execution
             once(Transaction::BlockMetadata(
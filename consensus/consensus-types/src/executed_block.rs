@@ -14,6 +14,7 @@ use aptos_types::{
     account_address::AccountAddress,
     block_info::BlockInfo,
     contract_event::ContractEvent,
+    on_chain_config::BlockGasLimitType,
     transaction::{SignedTransaction, Transaction, TransactionStatus},
 };
 use std::fmt::{Debug, Display, Formatter};
@@ -108,7 +109,7 @@ impl ExecutedBlock {
         &self,
         validators: &[AccountAddress],
         txns: Vec<SignedTransaction>,
-        block_gas_limit: Option<u64>,
+        block_gas_limit_type: BlockGasLimitType,
     ) -> Vec<Transaction> {
         // reconfiguration suffix don't execute
 
@@ -118,8 +119,8 @@ impl ExecutedBlock {
 
         let mut txns_with_state_checkpoint =
             self.block
-                .transactions_to_execute(validators, txns, block_gas_limit);
-        if block_gas_limit.is_some() && !self.state_compute_result.has_reconfiguration() {
+                .transactions_to_execute(validators, txns, block_gas_limit_type);
+        if block_gas_limit_type.is_limited() && !self.state_compute_result.has_reconfiguration() {
             // After the per-block gas limit change,
             // insert state checkpoint at the position
             // 1) after last txn if there is no Retry
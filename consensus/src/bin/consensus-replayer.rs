@@ -0,0 +1,76 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{bail, Context, Result};
+use aptos_config::config::{IdentityBlob, NodeConfig};
+use aptos_consensus::{read_recording, replay_entries};
+use aptos_types::validator_signer::ValidatorSigner;
+use clap::Parser;
+use std::path::PathBuf;
+
+#[derive(Parser)]
+#[clap(
+    name = "consensus-replayer",
+    about = "Replay a consensus message recording and compare the decisions taken by the replay \
+    with the recorded ones."
+)]
+struct Opt {
+    /// The directory holding the recording files (see `consensus.message_recorder`)
+    #[clap(value_parser)]
+    recording_dir: PathBuf,
+
+    /// The identity file of the validator that produced the recording. The consensus
+    /// key is required to sign proposals and votes, but it is never recorded.
+    #[clap(long, value_parser)]
+    identity_file: PathBuf,
+
+    /// The node config the recording was produced with (the default consensus config
+    /// is used otherwise)
+    #[clap(long, value_parser)]
+    node_config: Option<PathBuf>,
+}
+
+fn main() -> Result<()> {
+    let opt = Opt::parse();
+
+    let identity = IdentityBlob::from_file(&opt.identity_file)
+        .with_context(|| format!("Failed to read the identity file: {:?}", opt.identity_file))?;
+    let author = identity
+        .account_address
+        .context("The identity file does not contain an account address!")?;
+    let consensus_private_key = identity
+        .consensus_private_key
+        .context("The identity file does not contain a consensus key!")?;
+    let signer = ValidatorSigner::new(author, consensus_private_key);
+
+    let consensus_config = match &opt.node_config {
+        Some(path) => {
+            NodeConfig::load_from_path(path)
+                .with_context(|| format!("Failed to load the node config: {:?}", path))?
+                .consensus
+        },
+        None => Default::default(),
+    };
+
+    let entries = read_recording(&opt.recording_dir)?;
+    println!("Replaying {} recorded entries", entries.len());
+    let report = replay_entries(entries, signer, consensus_config)?;
+    println!(
+        "Recorded decisions: {}, replayed decisions: {}",
+        report.expected().len(),
+        report.actual().len()
+    );
+
+    match report.first_divergence() {
+        None => {
+            println!("The replay reproduced all recorded decisions");
+            Ok(())
+        },
+        Some(index) => bail!(
+            "The replay diverged at decision {}! Recorded: {:?}, replayed: {:?}",
+            index,
+            report.expected().get(index),
+            report.actual().get(index)
+        ),
+    }
+}
@@ -0,0 +1,359 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+use crate::{
+    counters::{NUM_CONFLICTING_TXNS_IN_BLOCK, TXN_SHUFFLE_SECONDS},
+    transaction_shuffler::TransactionShuffler,
+};
+use aptos_types::transaction::{
+    analyzed_transaction::{AnalyzedTransaction, StorageLocation},
+    SignedTransaction, TransactionPayload,
+};
+use move_core_types::{account_address::AccountAddress, language_storage::ModuleId};
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// How many remaining transactions (as a multiple of the conflict window size) are considered as
+/// candidates when looking for a non-conflicting transaction.
+const LOOKAHEAD_FACTOR: usize = 4;
+
+/// A key on which two transactions may conflict during parallel execution.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+enum ConflictKey {
+    // All the transactions from the same sender conflict on the sender's account and coin store.
+    Sender(AccountAddress),
+    // A specific storage location the transaction is known to write, derived in the same way as
+    // the read/write hints the block partitioner uses.
+    Location(StorageLocation),
+    // Coarse hint for entry functions we can't analyze precisely: transactions calling into the
+    // same non-framework module are likely to contend on the same shared resources.
+    Module(ModuleId),
+}
+
+/// Returns the estimated set of keys the transaction conflicts on. This is an approximation:
+/// missing a key only results in less effective shuffling, never in incorrect execution.
+fn conflict_keys(txn: &SignedTransaction) -> Vec<ConflictKey> {
+    let mut keys = vec![ConflictKey::Sender(txn.sender())];
+    if let TransactionPayload::EntryFunction(func) = txn.payload() {
+        let receiver = || {
+            func.args()
+                .get(0)
+                .and_then(|arg| bcs::from_bytes::<AccountAddress>(arg).ok())
+        };
+        match (
+            *func.module().address(),
+            func.module().name().as_str(),
+            func.function().as_str(),
+        ) {
+            (AccountAddress::ONE, "coin", "transfer")
+            | (AccountAddress::ONE, "aptos_account", "transfer") => {
+                if let Some(receiver) = receiver() {
+                    keys.push(ConflictKey::Location(
+                        AnalyzedTransaction::coin_store_location(receiver),
+                    ));
+                }
+            },
+            (AccountAddress::ONE, "aptos_account", "create_account") => {
+                if let Some(receiver) = receiver() {
+                    keys.push(ConflictKey::Location(
+                        AnalyzedTransaction::account_resource_location(receiver),
+                    ));
+                }
+            },
+            (AccountAddress::ONE, _, _) => {},
+            _ => keys.push(ConflictKey::Module(func.module().clone())),
+        }
+    }
+    keys
+}
+
+/// An implementation of transaction shuffler, which generalizes the `SenderAwareShuffler` from
+/// senders to the estimated read/write sets of the transactions. It maintains the set of conflict
+/// keys of the last `conflict_window_size` transactions added to the block, and when selecting
+/// the next transaction, it picks the first one (in the original order) that doesn't conflict
+/// with any transaction in the window. If there is none, it preserves the order and adds the
+/// first remaining transaction.
+///
+/// To bound the shuffling time, only the next `LOOKAHEAD_FACTOR * conflict_window_size` remaining
+/// transactions are considered as candidates at every step, so the algorithm is
+/// O(n * conflict_window_size).
+///
+/// The relative ordering of transactions from the same sender is always preserved, because a
+/// transaction is never selected ahead of an earlier remaining transaction from the same sender.
+pub struct ConflictAwareShuffler {
+    conflict_window_size: usize,
+}
+
+impl ConflictAwareShuffler {
+    pub fn new(conflict_window_size: usize) -> Self {
+        Self {
+            conflict_window_size,
+        }
+    }
+}
+
+impl TransactionShuffler for ConflictAwareShuffler {
+    fn shuffle(&self, txns: Vec<SignedTransaction>) -> Vec<SignedTransaction> {
+        let _timer = TXN_SHUFFLE_SECONDS.start_timer();
+
+        // Early return for performance reason if there are no transactions to shuffle, and
+        // handle the corner case of conflict window being 0, in which case we don't do any shuffling
+        if txns.is_empty() || self.conflict_window_size == 0 {
+            return txns;
+        }
+
+        let keys: Vec<_> = txns.iter().map(conflict_keys).collect();
+        let mut window = ConflictWindow::new(self.conflict_window_size);
+        let mut remaining: VecDeque<usize> = (0..txns.len()).collect();
+        let mut order = Vec::with_capacity(txns.len());
+        let mut num_conflicting = 0;
+
+        while !remaining.is_empty() {
+            let mut blocked_senders = HashSet::new();
+            let candidate = remaining
+                .iter()
+                .take(self.conflict_window_size * LOOKAHEAD_FACTOR)
+                .position(|idx| {
+                    let sender = txns[*idx].sender();
+                    if blocked_senders.contains(&sender) {
+                        return false;
+                    }
+                    if window.has_conflict(&keys[*idx]) {
+                        // Later transactions from the same sender can't jump ahead of this one
+                        blocked_senders.insert(sender);
+                        return false;
+                    }
+                    true
+                });
+            let position = candidate.unwrap_or_else(|| {
+                num_conflicting += 1;
+                0
+            });
+            let idx = remaining
+                .remove(position)
+                .expect("Position must be in range");
+            window.add(idx, &keys);
+            order.push(idx);
+        }
+        NUM_CONFLICTING_TXNS_IN_BLOCK.set(num_conflicting as f64);
+
+        let mut txns: Vec<_> = txns.into_iter().map(Some).collect();
+        order
+            .into_iter()
+            .map(|idx| txns[idx].take().expect("Transaction must be added once"))
+            .collect()
+    }
+}
+
+/// Sliding window over the conflict keys of the last `window_size` transactions added to the
+/// block.
+struct ConflictWindow<'a> {
+    window_size: usize,
+    txns_in_window: VecDeque<usize>,
+    keys_in_window: HashMap<&'a ConflictKey, usize>,
+}
+
+impl<'a> ConflictWindow<'a> {
+    fn new(window_size: usize) -> Self {
+        Self {
+            window_size,
+            txns_in_window: VecDeque::with_capacity(window_size + 1),
+            keys_in_window: HashMap::new(),
+        }
+    }
+
+    fn has_conflict(&self, keys: &[ConflictKey]) -> bool {
+        keys.iter().any(|key| self.keys_in_window.contains_key(key))
+    }
+
+    fn add(&mut self, idx: usize, keys: &'a [Vec<ConflictKey>]) {
+        for key in &keys[idx] {
+            *self.keys_in_window.entry(key).or_insert(0) += 1;
+        }
+        self.txns_in_window.push_back(idx);
+        if self.txns_in_window.len() > self.window_size {
+            let dropped = self.txns_in_window.pop_front().unwrap();
+            for key in &keys[dropped] {
+                if let Some(count) = self.keys_in_window.get_mut(key) {
+                    *count -= 1;
+                    if *count == 0 {
+                        self.keys_in_window.remove(key);
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use crate::{
+        conflict_aware_shuffler::ConflictAwareShuffler, transaction_shuffler::TransactionShuffler,
+    };
+    use aptos_crypto::{ed25519::Ed25519PrivateKey, PrivateKey, SigningKey, Uniform};
+    use aptos_types::{
+        chain_id::ChainId,
+        transaction::{
+            EntryFunction, RawTransaction, Script, SignedTransaction, TransactionPayload,
+        },
+    };
+    use move_core_types::{
+        account_address::AccountAddress, identifier::Identifier, language_storage::ModuleId,
+    };
+    use std::collections::HashMap;
+
+    pub(crate) fn create_signed_transactions(
+        payload: TransactionPayload,
+        gas_unit_prices: &[u64],
+    ) -> Vec<SignedTransaction> {
+        let private_key = Ed25519PrivateKey::generate_for_testing();
+        let public_key = private_key.public_key();
+        let sender = AccountAddress::random();
+
+        gas_unit_prices
+            .iter()
+            .enumerate()
+            .map(|(i, gas_unit_price)| {
+                let raw_transaction = RawTransaction::new(
+                    sender,
+                    i as u64,
+                    payload.clone(),
+                    0,
+                    *gas_unit_price,
+                    0,
+                    ChainId::new(10),
+                );
+                SignedTransaction::new(
+                    raw_transaction.clone(),
+                    public_key.clone(),
+                    private_key.sign(&raw_transaction).unwrap(),
+                )
+            })
+            .collect()
+    }
+
+    fn script_payload() -> TransactionPayload {
+        TransactionPayload::Script(Script::new(vec![], vec![], vec![]))
+    }
+
+    fn transfer_payload(receiver: AccountAddress) -> TransactionPayload {
+        TransactionPayload::EntryFunction(EntryFunction::new(
+            ModuleId::new(AccountAddress::ONE, Identifier::new("coin").unwrap()),
+            Identifier::new("transfer").unwrap(),
+            vec![],
+            vec![
+                bcs::to_bytes(&receiver).unwrap(),
+                bcs::to_bytes(&1u64).unwrap(),
+            ],
+        ))
+    }
+
+    fn module_payload(module_address: AccountAddress) -> TransactionPayload {
+        TransactionPayload::EntryFunction(EntryFunction::new(
+            ModuleId::new(module_address, Identifier::new("dex").unwrap()),
+            Identifier::new("swap").unwrap(),
+            vec![],
+            vec![],
+        ))
+    }
+
+    #[test]
+    fn test_unique_senders_without_hints() {
+        let txns: Vec<_> = (0..50)
+            .flat_map(|_| create_signed_transactions(script_payload(), &[0]))
+            .collect();
+        let optimized_txns = ConflictAwareShuffler::new(10).shuffle(txns.clone());
+        // Assert that the ordering is unchanged if there are no conflicts
+        assert_eq!(txns, optimized_txns);
+    }
+
+    #[test]
+    fn test_zero_conflict_window() {
+        let receiver = AccountAddress::random();
+        let txns: Vec<_> = (0..50)
+            .flat_map(|_| create_signed_transactions(transfer_payload(receiver), &[0, 0]))
+            .collect();
+        let optimized_txns = ConflictAwareShuffler::new(0).shuffle(txns.clone());
+        assert_eq!(txns, optimized_txns);
+    }
+
+    #[test]
+    // S1 -> R, S2 -> R, S3 -> X, S4 -> Y
+    // with conflict_window_size=1, transfers to the same receiver should be separated:
+    // S1 -> R, S3 -> X, S2 -> R, S4 -> Y
+    fn test_same_receiver_shuffling() {
+        let receiver = AccountAddress::random();
+        let txn1 = create_signed_transactions(transfer_payload(receiver), &[0]);
+        let txn2 = create_signed_transactions(transfer_payload(receiver), &[0]);
+        let txn3 = create_signed_transactions(transfer_payload(AccountAddress::random()), &[0]);
+        let txn4 = create_signed_transactions(transfer_payload(AccountAddress::random()), &[0]);
+        let orig_txns: Vec<_> = [&txn1, &txn2, &txn3, &txn4]
+            .into_iter()
+            .flatten()
+            .cloned()
+            .collect();
+        let optimized_txns = ConflictAwareShuffler::new(1).shuffle(orig_txns);
+        assert_eq!(
+            optimized_txns,
+            vec![
+                txn1[0].clone(),
+                txn3[0].clone(),
+                txn2[0].clone(),
+                txn4[0].clone()
+            ]
+        );
+    }
+
+    #[test]
+    // S1 -> M, S2 -> M, S3 -> 0x1, S4 -> N
+    // with conflict_window_size=1, calls into the same non-framework module should be separated:
+    // S1 -> M, S3 -> 0x1, S2 -> M, S4 -> N
+    fn test_same_module_shuffling() {
+        let module_address = AccountAddress::random();
+        let txn1 = create_signed_transactions(module_payload(module_address), &[0]);
+        let txn2 = create_signed_transactions(module_payload(module_address), &[0]);
+        let txn3 = create_signed_transactions(module_payload(AccountAddress::ONE), &[0]);
+        let txn4 = create_signed_transactions(module_payload(AccountAddress::random()), &[0]);
+        let orig_txns: Vec<_> = [&txn1, &txn2, &txn3, &txn4]
+            .into_iter()
+            .flatten()
+            .cloned()
+            .collect();
+        let optimized_txns = ConflictAwareShuffler::new(1).shuffle(orig_txns);
+        assert_eq!(
+            optimized_txns,
+            vec![
+                txn1[0].clone(),
+                txn3[0].clone(),
+                txn2[0].clone(),
+                txn4[0].clone()
+            ]
+        );
+    }
+
+    #[test]
+    fn test_same_sender_relative_order() {
+        let receiver = AccountAddress::random();
+        let mut orig_txns = Vec::new();
+        let mut orig_txns_by_sender = HashMap::new();
+        for i in 0..100 {
+            let payload = if i % 2 == 0 {
+                transfer_payload(receiver)
+            } else {
+                module_payload(AccountAddress::random())
+            };
+            let sender_txns = create_signed_transactions(payload, &vec![0; i % 7 + 1]);
+            orig_txns_by_sender.insert(sender_txns[0].sender(), sender_txns.clone());
+            orig_txns.extend(sender_txns);
+        }
+        let optimized_txns = ConflictAwareShuffler::new(32).shuffle(orig_txns.clone());
+        assert_eq!(orig_txns.len(), optimized_txns.len());
+
+        let mut optimized_txns_by_sender = HashMap::new();
+        for txn in optimized_txns {
+            optimized_txns_by_sender
+                .entry(txn.sender())
+                .or_insert_with(Vec::new)
+                .push(txn);
+        }
+        assert_eq!(orig_txns_by_sender, optimized_txns_by_sender);
+    }
+}
@@ -0,0 +1,12 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Consensus observer: validators (and VFNs) publish ordered blocks, block payloads and
+//! commit decisions to subscribed fullnodes, which execute the blocks locally instead of
+//! waiting for state sync.
+
+pub(crate) mod network_handler;
+pub mod network_message;
+pub(crate) mod observer;
+pub(crate) mod payload_store;
+pub(crate) mod publisher;
@@ -0,0 +1,154 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::consensus_observer::{
+    network_message::{
+        ConsensusObserverDirectSend, ConsensusObserverMessage, ConsensusObserverResponse,
+    },
+    publisher::ConsensusPublisher,
+};
+use aptos_channels::aptos_channel;
+use aptos_config::network_id::PeerNetworkId;
+use aptos_logger::prelude::*;
+use aptos_network::{
+    application::interface::NetworkServiceEvents,
+    protocols::network::{Event, RpcError},
+    ProtocolId,
+};
+use bytes::Bytes;
+use futures::{
+    channel::oneshot,
+    stream::{select_all, BoxStream, StreamExt},
+};
+use std::sync::Arc;
+
+/// A channel for fulfilling a pending consensus observer RPC request
+pub struct ResponseSender {
+    protocol_id: ProtocolId,
+    response_tx: oneshot::Sender<Result<Bytes, RpcError>>,
+}
+
+impl ResponseSender {
+    pub fn new(
+        protocol_id: ProtocolId,
+        response_tx: oneshot::Sender<Result<Bytes, RpcError>>,
+    ) -> Self {
+        Self {
+            protocol_id,
+            response_tx,
+        }
+    }
+
+    pub fn send(self, response: ConsensusObserverResponse) {
+        let message = ConsensusObserverMessage::Response(response);
+        let result = self
+            .protocol_id
+            .to_bytes(&message)
+            .map(Bytes::from)
+            .map_err(RpcError::Error);
+        let _ = self.response_tx.send(result);
+    }
+}
+
+/// ConsensusObserverNetworkHandler receives the consensus observer events from all
+/// networks: subscription requests are handled by the publisher (if enabled) and
+/// consensus updates are forwarded to the observer (if enabled).
+pub struct ConsensusObserverNetworkHandler {
+    network_events: BoxStream<'static, (PeerNetworkId, Event<ConsensusObserverMessage>)>,
+    observer_message_tx:
+        Option<aptos_channel::Sender<(), (PeerNetworkId, ConsensusObserverDirectSend)>>,
+    consensus_publisher: Option<Arc<ConsensusPublisher>>,
+}
+
+impl ConsensusObserverNetworkHandler {
+    pub fn new(
+        network_service_events: NetworkServiceEvents<ConsensusObserverMessage>,
+        observer_message_tx: Option<
+            aptos_channel::Sender<(), (PeerNetworkId, ConsensusObserverDirectSend)>,
+        >,
+        consensus_publisher: Option<Arc<ConsensusPublisher>>,
+    ) -> Self {
+        // Transform the event streams to also include the peer network ID
+        let network_events: Vec<_> = network_service_events
+            .into_network_and_events()
+            .into_iter()
+            .map(|(network_id, events)| {
+                events.map(move |event| {
+                    let peer_id = match &event {
+                        Event::Message(peer_id, _) | Event::RpcRequest(peer_id, _, _, _) => {
+                            *peer_id
+                        },
+                        Event::NewPeer(metadata) | Event::LostPeer(metadata) => {
+                            metadata.remote_peer_id
+                        },
+                    };
+                    (PeerNetworkId::new(network_id, peer_id), event)
+                })
+            })
+            .collect();
+
+        Self {
+            network_events: select_all(network_events).boxed(),
+            observer_message_tx,
+            consensus_publisher,
+        }
+    }
+
+    pub async fn start(mut self) {
+        info!("Consensus observer network handler starts.");
+        while let Some((peer_network_id, event)) = self.network_events.next().await {
+            match event {
+                Event::Message(_, ConsensusObserverMessage::DirectSend(message)) => {
+                    self.handle_direct_send(peer_network_id, message)
+                },
+                Event::RpcRequest(
+                    _,
+                    ConsensusObserverMessage::Request(request),
+                    protocol_id,
+                    response_tx,
+                ) => match &self.consensus_publisher {
+                    Some(consensus_publisher) => consensus_publisher.handle_subscription_request(
+                        peer_network_id,
+                        request,
+                        ResponseSender::new(protocol_id, response_tx),
+                    ),
+                    None => warn!(
+                        "Received a {} request from {}, but the publisher is disabled!",
+                        request.get_label(),
+                        peer_network_id
+                    ),
+                },
+                Event::Message(_, message) | Event::RpcRequest(_, message, _, _) => {
+                    warn!(
+                        "Received unexpected consensus observer message from {}: {:?}",
+                        peer_network_id, message
+                    );
+                },
+                Event::NewPeer(_) | Event::LostPeer(_) => {}, // Connection events are ignored
+            }
+        }
+        info!("Consensus observer network handler stops.");
+    }
+
+    fn handle_direct_send(
+        &self,
+        peer_network_id: PeerNetworkId,
+        message: ConsensusObserverDirectSend,
+    ) {
+        match &self.observer_message_tx {
+            Some(observer_message_tx) => {
+                if let Err(error) = observer_message_tx.push((), (peer_network_id, message)) {
+                    warn!(
+                        "Failed to forward the message to the consensus observer: {:?}",
+                        error
+                    );
+                }
+            },
+            None => debug!(
+                "Dropping {} from {}, the consensus observer is disabled!",
+                message.get_label(),
+                peer_network_id
+            ),
+        }
+    }
+}
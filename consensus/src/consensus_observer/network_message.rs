@@ -0,0 +1,138 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Network messages exchanged between consensus publishers and observers.
+
+use aptos_consensus_types::{block::Block, common::Round};
+use aptos_crypto::HashValue;
+use aptos_network::ProtocolId;
+use aptos_types::{ledger_info::LedgerInfoWithSignatures, transaction::SignedTransaction};
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+
+/// Supported protocols for consensus observer direct sends, in order of preference
+pub const DIRECT_SEND: &[ProtocolId] = &[ProtocolId::ConsensusObserver];
+
+/// Supported protocols for consensus observer RPCs, in order of preference
+pub const RPC: &[ProtocolId] = &[ProtocolId::ConsensusObserverRpc];
+
+/// Network type for the consensus observer and publisher
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub enum ConsensusObserverMessage {
+    /// RPC request sent by an observer to a publisher
+    Request(ConsensusObserverRequest),
+    /// RPC response sent by a publisher to an observer
+    Response(ConsensusObserverResponse),
+    /// Consensus update pushed by a publisher to its subscribers
+    DirectSend(ConsensusObserverDirectSend),
+}
+
+/// Requests sent by observers to manage their subscriptions
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum ConsensusObserverRequest {
+    /// Start receiving consensus updates from the publisher
+    Subscribe,
+    /// Stop receiving consensus updates from the publisher
+    Unsubscribe,
+}
+
+impl ConsensusObserverRequest {
+    /// Returns the label of the request, used for logging and metrics
+    pub fn get_label(&self) -> &'static str {
+        match self {
+            ConsensusObserverRequest::Subscribe => "subscribe",
+            ConsensusObserverRequest::Unsubscribe => "unsubscribe",
+        }
+    }
+}
+
+/// Responses sent by publishers to acknowledge subscription requests
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum ConsensusObserverResponse {
+    /// The observer is now subscribed
+    SubscribeAck,
+    /// The observer is no longer subscribed
+    UnsubscribeAck,
+    /// The subscription was rejected (e.g., the publisher has too many subscribers)
+    SubscribeRejected,
+}
+
+/// Consensus updates pushed by publishers to their subscribers
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub enum ConsensusObserverDirectSend {
+    /// A chain of blocks ordered by consensus
+    OrderedBlock(OrderedBlock),
+    /// The commit decision for previously ordered blocks
+    CommitDecision(CommitDecision),
+    /// The transactions of a block (e.g., fetched from quorum store batches)
+    BlockPayload(BlockPayload),
+}
+
+impl ConsensusObserverDirectSend {
+    /// Returns the label of the message, used for logging and metrics
+    pub fn get_label(&self) -> &'static str {
+        match self {
+            ConsensusObserverDirectSend::OrderedBlock(_) => "ordered_block",
+            ConsensusObserverDirectSend::CommitDecision(_) => "commit_decision",
+            ConsensusObserverDirectSend::BlockPayload(_) => "block_payload",
+        }
+    }
+}
+
+impl Display for ConsensusObserverDirectSend {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConsensusObserverDirectSend::OrderedBlock(ordered_block) => {
+                write!(
+                    f,
+                    "OrderedBlock: {}",
+                    ordered_block.ordered_proof.commit_info()
+                )
+            },
+            ConsensusObserverDirectSend::CommitDecision(commit_decision) => {
+                write!(
+                    f,
+                    "CommitDecision: {}",
+                    commit_decision.commit_proof.commit_info()
+                )
+            },
+            ConsensusObserverDirectSend::BlockPayload(block_payload) => write!(
+                f,
+                "BlockPayload: epoch {}, round {}, id {}, {} txns",
+                block_payload.epoch,
+                block_payload.round,
+                block_payload.block_id,
+                block_payload.transactions.len()
+            ),
+        }
+    }
+}
+
+/// A chain of ordered blocks, together with the proof that orders the last block
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct OrderedBlock {
+    /// The ordered blocks, each block is the parent of the next one
+    pub blocks: Vec<Block>,
+    /// The ledger info (signed by the validators) that orders the last block
+    pub ordered_proof: LedgerInfoWithSignatures,
+}
+
+/// The commit decision for ordered blocks, carrying the signed execution result
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct CommitDecision {
+    /// The ledger info (signed by the validators) that commits the blocks
+    pub commit_proof: LedgerInfoWithSignatures,
+}
+
+/// The transactions of a single block, as executed by the publisher
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct BlockPayload {
+    /// The epoch of the block
+    pub epoch: u64,
+    /// The round of the block
+    pub round: Round,
+    /// The id of the block
+    pub block_id: HashValue,
+    /// The transactions of the block, before deduplication and shuffling
+    pub transactions: Vec<SignedTransaction>,
+}
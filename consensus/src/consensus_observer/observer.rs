@@ -0,0 +1,976 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    consensus_observer::{
+        network_message::{
+            BlockPayload, CommitDecision, ConsensusObserverDirectSend, ConsensusObserverMessage,
+            ConsensusObserverRequest, ConsensusObserverResponse, OrderedBlock,
+        },
+        payload_store::{verify_block_payload, BlockPayloadStore},
+        publisher::ConsensusPublisher,
+    },
+    counters,
+    experimental::{
+        buffer_manager::{OrderedBlocks, ResetRequest},
+        decoupled_execution_utils::prepare_phases_and_buffer_manager,
+    },
+    liveness::block_size_controller::AdaptiveBlockSizeController,
+    network::NetworkSender,
+    network_interface::ConsensusNetworkClient,
+    payload_manager::PayloadManager,
+    round_manager::VerifiedEvent,
+    state_replication::StateComputer,
+    transaction_deduper::create_transaction_deduper,
+    transaction_shuffler::create_transaction_shuffler,
+};
+use aptos_channels::{aptos_channel, message_queues::QueueStyle};
+use aptos_config::{config::ConsensusObserverConfig, network_id::PeerNetworkId};
+use aptos_consensus_notifications::{ObserverPauseListener, ObserverPauseNotification};
+use aptos_consensus_types::{block::Block, common::Round, executed_block::ExecutedBlock};
+use aptos_crypto::HashValue;
+use aptos_event_notifications::ReconfigNotificationListener;
+use aptos_executor_types::StateComputeResult;
+use aptos_infallible::Mutex;
+use aptos_logger::prelude::*;
+use aptos_network::application::interface::{NetworkClient, NetworkClientInterface};
+use aptos_storage_interface::DbReader;
+use aptos_types::{
+    account_address::AccountAddress,
+    block_info::BlockInfo,
+    epoch_state::EpochState,
+    ledger_info::LedgerInfoWithSignatures,
+    on_chain_config::{OnChainConfigPayload, OnChainExecutionConfig, ValidatorSet},
+};
+use futures::{
+    channel::{
+        mpsc::{unbounded, UnboundedSender},
+        oneshot,
+    },
+    SinkExt, StreamExt,
+};
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+/// The subscription of the observer to a single publisher
+struct ObserverSubscription {
+    peer_network_id: PeerNetworkId,
+    last_message_time: Instant,
+}
+
+/// ConsensusObserver runs on fullnodes. It subscribes to a consensus publisher, verifies the
+/// ordered blocks and commit decisions it receives and executes them locally via the
+/// BufferManager pipeline (without signing). If the observer falls behind (e.g., it missed
+/// messages), it falls back to state sync to catch up with the latest commit decision.
+///
+/// The observer and state sync never write to storage at the same time: the observer starts
+/// paused (and is paused by state sync whenever it stops committing). While paused, it doesn't
+/// execute any blocks, and it only resumes once it has synced to a commit decision.
+pub struct ConsensusObserver {
+    consensus_observer_config: ConsensusObserverConfig,
+    network_client: NetworkClient<ConsensusObserverMessage>,
+    active_subscription: Option<ObserverSubscription>,
+
+    // The latest committed (or synced) ledger info
+    root: Arc<Mutex<LedgerInfoWithSignatures>>,
+    // The last block info sent to the execution pipeline (None after a reset)
+    last_ordered_block: Option<BlockInfo>,
+    // The ordered blocks that are not yet sent to the execution pipeline, keyed by
+    // the epoch and round of their ordered proof
+    pending_ordered_blocks: BTreeMap<(u64, Round), OrderedBlock>,
+    // Whether execution is paused (i.e., state sync may be writing to storage)
+    paused_for_state_sync: bool,
+    block_payload_store: Arc<BlockPayloadStore>,
+
+    execution_proxy: Arc<dyn StateComputer>,
+    epoch_state: Option<Arc<EpochState>>,
+    // channels to the buffer manager of the current epoch
+    block_tx: Option<UnboundedSender<OrderedBlocks>>,
+    commit_msg_tx: Option<aptos_channel::Sender<AccountAddress, VerifiedEvent>>,
+    reset_tx: Option<UnboundedSender<ResetRequest>>,
+
+    // Republishes the observed blocks to downstream observers (e.g., on VFNs)
+    consensus_publisher: Option<Arc<ConsensusPublisher>>,
+}
+
+impl ConsensusObserver {
+    pub fn new(
+        consensus_observer_config: ConsensusObserverConfig,
+        network_client: NetworkClient<ConsensusObserverMessage>,
+        aptos_db: Arc<dyn DbReader>,
+        execution_proxy: Arc<dyn StateComputer>,
+        consensus_publisher: Option<Arc<ConsensusPublisher>>,
+    ) -> Self {
+        let root = aptos_db
+            .get_latest_ledger_info()
+            .expect("Failed to read the latest ledger info!");
+        let block_payload_store = Arc::new(BlockPayloadStore::new(
+            consensus_observer_config.max_num_pending_blocks as usize,
+        ));
+
+        Self {
+            consensus_observer_config,
+            network_client,
+            active_subscription: None,
+            root: Arc::new(Mutex::new(root)),
+            last_ordered_block: None,
+            pending_ordered_blocks: BTreeMap::new(),
+            paused_for_state_sync: true,
+            block_payload_store,
+            execution_proxy,
+            epoch_state: None,
+            block_tx: None,
+            commit_msg_tx: None,
+            reset_tx: None,
+            consensus_publisher,
+        }
+    }
+
+    fn get_root(&self) -> LedgerInfoWithSignatures {
+        self.root.lock().clone()
+    }
+
+    /// Returns the id of the block the next ordered block must extend
+    fn get_last_ordered_block_id(&self) -> HashValue {
+        match &self.last_ordered_block {
+            Some(block_info) => block_info.id(),
+            None => {
+                let root = self.get_root();
+                if root.ledger_info().ends_epoch() {
+                    Block::make_genesis_block_from_ledger_info(root.ledger_info()).id()
+                } else {
+                    root.commit_info().id()
+                }
+            },
+        }
+    }
+
+    /// Returns the epoch and round of the last block sent to the execution pipeline
+    fn get_last_ordered_epoch_and_round(&self) -> (u64, Round) {
+        let block_info = match &self.last_ordered_block {
+            Some(block_info) => block_info.clone(),
+            None => self.get_root().commit_info().clone(),
+        };
+        (block_info.epoch(), block_info.round())
+    }
+
+    /// Checks the health of the active subscription and subscribes
+    /// to a new publisher if there is none.
+    async fn check_progress(&mut self) {
+        if let Some(subscription) = &self.active_subscription {
+            let peer_network_id = subscription.peer_network_id;
+            let connected_peers = self
+                .network_client
+                .get_available_peers()
+                .unwrap_or_default();
+            let max_subscription_timeout =
+                Duration::from_millis(self.consensus_observer_config.max_subscription_timeout_ms);
+            let reason = if !connected_peers.contains(&peer_network_id) {
+                Some("disconnected")
+            } else if subscription.last_message_time.elapsed() > max_subscription_timeout {
+                Some("timeout")
+            } else {
+                None
+            };
+
+            if let Some(reason) = reason {
+                warn!(
+                    "Terminating the subscription to {} (reason: {})",
+                    peer_network_id, reason
+                );
+                counters::CONSENSUS_OBSERVER_SUBSCRIPTION_CHANGES
+                    .with_label_values(&[reason])
+                    .inc();
+                self.active_subscription = None;
+                if reason == "timeout" {
+                    self.send_subscription_request(
+                        peer_network_id,
+                        ConsensusObserverRequest::Unsubscribe,
+                    )
+                    .await;
+                }
+            }
+        }
+
+        if self.active_subscription.is_none() {
+            self.subscribe_to_publisher().await;
+        }
+    }
+
+    /// Returns the connected peers that support the consensus observer, ordered by
+    /// their distance from the validators (as reported by the peer monitoring service).
+    fn sort_peers_for_subscription(&self) -> Vec<PeerNetworkId> {
+        let available_peers = self
+            .network_client
+            .get_available_peers()
+            .unwrap_or_default();
+        let peers_and_metadata = self
+            .network_client
+            .get_peers_and_metadata()
+            .get_connected_peers_and_metadata()
+            .unwrap_or_default();
+
+        let mut peers_and_distances: Vec<_> = available_peers
+            .into_iter()
+            .map(|peer_network_id| {
+                let distance_from_validators = peers_and_metadata
+                    .get(&peer_network_id)
+                    .and_then(|peer_metadata| {
+                        peer_metadata
+                            .get_peer_monitoring_metadata()
+                            .latest_network_info_response
+                    })
+                    .map_or(u64::MAX, |response| response.distance_from_validators);
+                (peer_network_id, distance_from_validators)
+            })
+            .collect();
+        peers_and_distances.sort_by_key(|(_, distance_from_validators)| *distance_from_validators);
+        peers_and_distances
+            .into_iter()
+            .map(|(peer_network_id, _)| peer_network_id)
+            .collect()
+    }
+
+    /// Sends the given subscription request and returns true iff it was acknowledged
+    async fn send_subscription_request(
+        &self,
+        peer_network_id: PeerNetworkId,
+        request: ConsensusObserverRequest,
+    ) -> bool {
+        let expected_response = match request {
+            ConsensusObserverRequest::Subscribe => ConsensusObserverResponse::SubscribeAck,
+            ConsensusObserverRequest::Unsubscribe => ConsensusObserverResponse::UnsubscribeAck,
+        };
+        let request_label = request.get_label();
+        let response = self
+            .network_client
+            .send_to_peer_rpc(
+                ConsensusObserverMessage::Request(request),
+                Duration::from_millis(self.consensus_observer_config.network_request_timeout_ms),
+                peer_network_id,
+            )
+            .await;
+        match response {
+            Ok(ConsensusObserverMessage::Response(response)) if response == expected_response => {
+                true
+            },
+            Ok(ConsensusObserverMessage::Response(
+                ConsensusObserverResponse::SubscribeRejected,
+            )) => {
+                info!("The subscription was rejected by {}", peer_network_id);
+                false
+            },
+            Ok(message) => {
+                warn!(
+                    "Unexpected response to the {} request from {}: {:?}",
+                    request_label, peer_network_id, message
+                );
+                false
+            },
+            Err(error) => {
+                warn!(
+                    "Failed to send the {} request to {}: {:?}",
+                    request_label, peer_network_id, error
+                );
+                false
+            },
+        }
+    }
+
+    /// Subscribes to the first publisher (in order of preference) that accepts the subscription
+    async fn subscribe_to_publisher(&mut self) {
+        for peer_network_id in self.sort_peers_for_subscription() {
+            if self
+                .send_subscription_request(peer_network_id, ConsensusObserverRequest::Subscribe)
+                .await
+            {
+                info!("Subscribed to consensus publisher {}", peer_network_id);
+                counters::CONSENSUS_OBSERVER_SUBSCRIPTION_CHANGES
+                    .with_label_values(&["subscribed"])
+                    .inc();
+                self.active_subscription = Some(ObserverSubscription {
+                    peer_network_id,
+                    last_message_time: Instant::now(),
+                });
+                return;
+            }
+        }
+    }
+
+    /// Processes a consensus update pushed by a publisher
+    async fn process_network_message(
+        &mut self,
+        peer_network_id: PeerNetworkId,
+        message: ConsensusObserverDirectSend,
+    ) {
+        // Only process the messages sent by the active subscription
+        match &mut self.active_subscription {
+            Some(subscription) if subscription.peer_network_id == peer_network_id => {
+                subscription.last_message_time = Instant::now();
+            },
+            _ => {
+                update_received_message_counter(&message, "unexpected_peer");
+                return;
+            },
+        }
+
+        debug!("Received {} from {}", message, peer_network_id);
+        match message {
+            ConsensusObserverDirectSend::OrderedBlock(ordered_block) => {
+                self.process_ordered_block(ordered_block).await
+            },
+            ConsensusObserverDirectSend::CommitDecision(commit_decision) => {
+                self.process_commit_decision(commit_decision).await
+            },
+            ConsensusObserverDirectSend::BlockPayload(block_payload) => {
+                self.process_block_payload(block_payload).await
+            },
+        }
+    }
+
+    async fn process_ordered_block(&mut self, ordered_block: OrderedBlock) {
+        let message = ConsensusObserverDirectSend::OrderedBlock(ordered_block.clone());
+        let epoch_state = match &self.epoch_state {
+            Some(epoch_state) => epoch_state.clone(),
+            None => return update_received_message_counter(&message, "no_epoch"),
+        };
+
+        // Drop the blocks of other epochs and the blocks that are already ordered
+        let commit_info = ordered_block.ordered_proof.commit_info();
+        if commit_info.epoch() != epoch_state.epoch {
+            return update_received_message_counter(&message, "wrong_epoch");
+        }
+        if (commit_info.epoch(), commit_info.round()) <= self.get_last_ordered_epoch_and_round()
+            || self
+                .pending_ordered_blocks
+                .contains_key(&(commit_info.epoch(), commit_info.round()))
+        {
+            return update_received_message_counter(&message, "duplicate");
+        }
+
+        if let Err(error) = verify_ordered_block(&ordered_block, &epoch_state) {
+            warn!("Received an invalid ordered block: {:?}", error);
+            return update_received_message_counter(&message, "invalid");
+        }
+
+        if self.pending_ordered_blocks.len()
+            >= self.consensus_observer_config.max_num_pending_blocks as usize
+        {
+            warn!(
+                "Dropping ordered block {}: too many pending blocks",
+                commit_info
+            );
+            return update_received_message_counter(&message, "too_many_pending_blocks");
+        }
+
+        update_received_message_counter(&message, "success");
+        self.pending_ordered_blocks
+            .insert((commit_info.epoch(), commit_info.round()), ordered_block);
+        self.process_pending_blocks();
+    }
+
+    async fn process_block_payload(&mut self, block_payload: BlockPayload) {
+        let message = ConsensusObserverDirectSend::BlockPayload(block_payload.clone());
+        let current_epoch = self
+            .epoch_state
+            .as_ref()
+            .map_or(0, |epoch_state| epoch_state.epoch);
+        if block_payload.epoch < current_epoch
+            || (block_payload.epoch, block_payload.round) <= self.get_last_ordered_epoch_and_round()
+        {
+            return update_received_message_counter(&message, "duplicate");
+        }
+
+        // If the ordered block is already known, verify the payload before storing it.
+        // Otherwise, the payload is verified once the ordered block is received.
+        let block = self
+            .pending_ordered_blocks
+            .values()
+            .flat_map(|ordered_block| ordered_block.blocks.iter())
+            .find(|block| {
+                (block.epoch(), block.round()) == (block_payload.epoch, block_payload.round)
+            });
+        let verified = match block {
+            Some(block) => {
+                if let Err(error) = verify_block_payload(&block_payload, block) {
+                    warn!("Received an invalid block payload: {:?}", error);
+                    return update_received_message_counter(&message, "invalid");
+                }
+                true
+            },
+            None => false,
+        };
+
+        if !self
+            .block_payload_store
+            .insert_block_payload(block_payload, verified)
+        {
+            return update_received_message_counter(&message, "too_many_pending_blocks");
+        }
+        update_received_message_counter(&message, "success");
+        self.process_pending_blocks();
+    }
+
+    async fn process_commit_decision(&mut self, commit_decision: CommitDecision) {
+        let message = ConsensusObserverDirectSend::CommitDecision(commit_decision.clone());
+        let commit_proof = commit_decision.commit_proof;
+        let epoch_state = match &self.epoch_state {
+            Some(epoch_state) => epoch_state.clone(),
+            None => return update_received_message_counter(&message, "no_epoch"),
+        };
+
+        let commit_info = commit_proof.commit_info();
+        let root = self.get_root();
+        if (commit_info.epoch(), commit_info.round())
+            <= (root.commit_info().epoch(), root.commit_info().round())
+        {
+            return update_received_message_counter(&message, "duplicate");
+        }
+
+        // The commit decisions of future epochs can't be verified with the current validator
+        // set, so we rely on state sync (which verifies the epoch changes) to catch up.
+        if commit_info.epoch() > epoch_state.epoch {
+            update_received_message_counter(&message, "future_epoch");
+            return self.sync_to_commit(commit_proof).await;
+        }
+
+        if let Err(error) = commit_proof.verify_signatures(&epoch_state.verifier) {
+            warn!("Received an invalid commit decision: {:?}", error);
+            return update_received_message_counter(&message, "invalid");
+        }
+        update_received_message_counter(&message, "success");
+
+        // If the blocks were already sent to the pipeline, forward the commit decision
+        // to the buffer manager. Otherwise, we missed some blocks and need to sync.
+        if (commit_info.epoch(), commit_info.round()) <= self.get_last_ordered_epoch_and_round()
+            && self.last_ordered_block.is_some()
+        {
+            if let Some(commit_msg_tx) = &self.commit_msg_tx {
+                if let Err(error) = commit_msg_tx.push(
+                    AccountAddress::ZERO,
+                    VerifiedEvent::CommitDecision(Box::new(commit_proof)),
+                ) {
+                    warn!("Failed to forward the commit decision: {:?}", error);
+                }
+            }
+        } else {
+            self.sync_to_commit(commit_proof).await;
+        }
+    }
+
+    /// Sends the pending ordered blocks to the execution pipeline, in order,
+    /// as long as they extend the last ordered block and their payloads exist.
+    fn process_pending_blocks(&mut self) {
+        let last_ordered_epoch_and_round = self.get_last_ordered_epoch_and_round();
+        self.pending_ordered_blocks = self.pending_ordered_blocks.split_off(&(
+            last_ordered_epoch_and_round.0,
+            last_ordered_epoch_and_round.1 + 1,
+        ));
+
+        while let Some(key) = self.pending_ordered_blocks.keys().next().cloned() {
+            let ordered_block = &self.pending_ordered_blocks[&key];
+            if self.paused_for_state_sync
+                || ordered_block.blocks[0].parent_id() != self.get_last_ordered_block_id()
+                || !self
+                    .block_payload_store
+                    .all_payloads_exist(&ordered_block.blocks)
+            {
+                break;
+            }
+            let ordered_block = self
+                .pending_ordered_blocks
+                .remove(&key)
+                .expect("Pending ordered block must exist!");
+            self.forward_ordered_block(ordered_block);
+        }
+        counters::CONSENSUS_OBSERVER_NUM_PENDING_BLOCKS
+            .set(self.pending_ordered_blocks.len() as i64);
+    }
+
+    fn forward_ordered_block(&mut self, ordered_block: OrderedBlock) {
+        let OrderedBlock {
+            blocks,
+            ordered_proof,
+        } = ordered_block;
+        info!(
+            "Sending ordered block {} to the execution pipeline",
+            ordered_proof.commit_info()
+        );
+        self.last_ordered_block = Some(ordered_proof.commit_info().clone());
+
+        let root = self.root.clone();
+        let block_payload_store = self.block_payload_store.clone();
+        let ordered_blocks = OrderedBlocks {
+            ordered_blocks: blocks
+                .into_iter()
+                .map(|block| ExecutedBlock::new(block, StateComputeResult::new_dummy()))
+                .collect(),
+            ordered_proof,
+            callback: Box::new(
+                move |_: &[Arc<ExecutedBlock>], commit_proof: LedgerInfoWithSignatures| {
+                    block_payload_store.remove_committed_payloads(
+                        commit_proof.commit_info().epoch(),
+                        commit_proof.commit_info().round(),
+                    );
+                    *root.lock() = commit_proof;
+                },
+            ),
+        };
+        if let Some(block_tx) = &self.block_tx {
+            if block_tx.unbounded_send(ordered_blocks).is_err() {
+                warn!("Failed to send ordered blocks to the buffer manager, maybe epoch ends");
+            }
+        }
+    }
+
+    /// Resets the execution pipeline and falls back to state sync to reach the given commit
+    async fn sync_to_commit(&mut self, commit_proof: LedgerInfoWithSignatures) {
+        info!(
+            "Falling back to state sync to reach commit {}",
+            commit_proof.commit_info()
+        );
+        counters::CONSENSUS_OBSERVER_STATE_SYNC_FALLBACK_COUNT.inc();
+
+        self.reset_pipeline(false).await;
+        if let Err(error) = self.execution_proxy.sync_to(commit_proof.clone()).await {
+            error!("Failed to sync to commit {}: {:?}", commit_proof, error);
+            return;
+        }
+
+        let commit_info = commit_proof.commit_info();
+        self.block_payload_store
+            .remove_committed_payloads(commit_info.epoch(), commit_info.round());
+        *self.root.lock() = commit_proof;
+
+        // Storage is now at the new root, so execution can resume (the executor
+        // was reset by the sync). The pending blocks may also extend the new root.
+        if self.paused_for_state_sync {
+            info!("Resuming execution at commit {}", commit_info);
+            self.paused_for_state_sync = false;
+        }
+        self.process_pending_blocks();
+    }
+
+    /// Pauses execution so that state sync can take over (e.g., because the observer
+    /// stopped committing). Execution only resumes once the observer syncs to a commit
+    /// decision (see `sync_to_commit`), which also refreshes the root and the executor.
+    async fn pause_for_state_sync(&mut self, pause_notification: ObserverPauseNotification) {
+        info!("Pausing execution for state sync");
+        counters::CONSENSUS_OBSERVER_STATE_SYNC_PAUSE_COUNT.inc();
+
+        // Wait for the pipeline to drop its blocks (and finish any ongoing commits)
+        self.reset_pipeline(false).await;
+        self.paused_for_state_sync = true;
+        pause_notification.acknowledge();
+    }
+
+    /// Resets (or stops) the buffer manager and drops the blocks sent to the pipeline
+    async fn reset_pipeline(&mut self, stop: bool) {
+        if let Some(reset_tx) = &self.reset_tx {
+            let (ack_tx, ack_rx) = oneshot::channel();
+            let reset_request = ResetRequest { tx: ack_tx, stop };
+            if reset_tx.clone().send(reset_request).await.is_ok() {
+                let _ = ack_rx.await;
+            }
+        }
+        if stop {
+            self.block_tx = None;
+            self.commit_msg_tx = None;
+            self.reset_tx = None;
+        }
+        self.last_ordered_block = None;
+    }
+
+    async fn end_epoch(&mut self) {
+        self.reset_pipeline(true).await;
+        self.execution_proxy.end_epoch();
+        self.pending_ordered_blocks.clear();
+        self.block_payload_store.clear_all_payloads();
+        self.epoch_state = None;
+    }
+
+    fn start_epoch(&mut self, payload: OnChainConfigPayload) {
+        let validator_set: ValidatorSet = payload
+            .get()
+            .expect("failed to get ValidatorSet from payload");
+        let epoch_state = Arc::new(EpochState {
+            epoch: payload.epoch(),
+            verifier: (&validator_set).into(),
+        });
+        let execution_config = payload
+            .get::<OnChainExecutionConfig>()
+            .unwrap_or_else(|error| {
+                error!("Failed to read on-chain execution config {}", error);
+                OnChainExecutionConfig::default()
+            });
+        info!("Consensus observer starts epoch {}", epoch_state.epoch);
+
+        self.execution_proxy.new_epoch(
+            &epoch_state,
+            Arc::new(PayloadManager::ConsensusObserver(
+                self.block_payload_store.clone(),
+            )),
+            create_transaction_shuffler(execution_config.transaction_shuffler_type()),
+            execution_config.block_gas_limit_type(),
+            create_transaction_deduper(execution_config.transaction_deduper_type()),
+        );
+        self.spawn_execution_pipeline(&epoch_state);
+        self.epoch_state = Some(epoch_state);
+    }
+
+    /// Spawns the execution and persisting phases and a buffer manager without signing phase
+    fn spawn_execution_pipeline(&mut self, epoch_state: &EpochState) {
+        // The buffer manager only sends messages to itself (e.g., epoch changes), which are dropped
+        let (self_sender, mut self_receiver) =
+            aptos_channels::new(1_024, &counters::PENDING_SELF_MESSAGES);
+        let network_client = NetworkClient::new(
+            vec![],
+            vec![],
+            HashMap::new(),
+            self.network_client.get_peers_and_metadata(),
+        );
+        let network_sender = NetworkSender::new(
+            AccountAddress::ZERO,
+            ConsensusNetworkClient::new(network_client),
+            self_sender,
+            epoch_state.verifier.clone(),
+            None,
+        );
+        tokio::spawn(async move { while self_receiver.next().await.is_some() {} });
+
+        let (block_tx, block_rx) = unbounded::<OrderedBlocks>();
+        let (reset_tx, reset_rx) = unbounded::<ResetRequest>();
+        let (commit_msg_tx, commit_msg_rx) = aptos_channel::new::<AccountAddress, VerifiedEvent>(
+            QueueStyle::FIFO,
+            100,
+            Some(&counters::BUFFER_MANAGER_MSGS),
+        );
+
+        let (execution_phase, _, persisting_phase, buffer_manager) =
+            prepare_phases_and_buffer_manager(
+                AccountAddress::ZERO,
+                self.execution_proxy.clone(),
+                None,
+                network_sender,
+                commit_msg_rx,
+                self.execution_proxy.clone(),
+                block_rx,
+                reset_rx,
+                epoch_state.verifier.clone(),
+                Arc::new(AdaptiveBlockSizeController::new_disabled()),
+                self.consensus_publisher.clone(),
+            );
+
+        tokio::spawn(execution_phase.start());
+        tokio::spawn(persisting_phase.start());
+        tokio::spawn(buffer_manager.start());
+
+        self.block_tx = Some(block_tx);
+        self.commit_msg_tx = Some(commit_msg_tx);
+        self.reset_tx = Some(reset_tx);
+    }
+
+    pub async fn start(
+        mut self,
+        mut observer_message_rx: aptos_channel::Receiver<
+            (),
+            (PeerNetworkId, ConsensusObserverDirectSend),
+        >,
+        mut reconfig_events: ReconfigNotificationListener,
+        mut observer_pause_listener: ObserverPauseListener,
+    ) {
+        // Wait for the initial epoch before processing any messages
+        let reconfig_notification = reconfig_events
+            .next()
+            .await
+            .expect("Reconfig sender dropped, unable to start the consensus observer");
+        self.start_epoch(reconfig_notification.on_chain_configs);
+
+        let mut progress_check_interval = tokio::time::interval(Duration::from_millis(
+            self.consensus_observer_config.progress_check_interval_ms,
+        ));
+        info!("Consensus observer starts.");
+        loop {
+            tokio::select! {
+                Some((peer_network_id, message)) = observer_message_rx.next() => {
+                    self.process_network_message(peer_network_id, message).await;
+                },
+                Some(reconfig_notification) = reconfig_events.next() => {
+                    self.end_epoch().await;
+                    self.start_epoch(reconfig_notification.on_chain_configs);
+                },
+                Some(pause_notification) = observer_pause_listener.next() => {
+                    self.pause_for_state_sync(pause_notification).await;
+                },
+                _ = progress_check_interval.tick() => {
+                    self.check_progress().await;
+                },
+            }
+        }
+    }
+}
+
+fn update_received_message_counter(message: &ConsensusObserverDirectSend, result: &str) {
+    counters::CONSENSUS_OBSERVER_RECEIVED_MESSAGES
+        .with_label_values(&[message.get_label(), result])
+        .inc();
+}
+
+/// Verifies that the given blocks form a chain ordered by the (signed) ordered proof
+fn verify_ordered_block(
+    ordered_block: &OrderedBlock,
+    epoch_state: &EpochState,
+) -> anyhow::Result<()> {
+    let OrderedBlock {
+        blocks,
+        ordered_proof,
+    } = ordered_block;
+    let last_block = blocks
+        .last()
+        .ok_or_else(|| anyhow::anyhow!("The ordered block is empty!"))?;
+    anyhow::ensure!(
+        last_block.id() == ordered_proof.commit_info().id(),
+        "The last block {} doesn't match the ordered proof {}",
+        last_block.id(),
+        ordered_proof.commit_info()
+    );
+    for (parent, child) in blocks.iter().zip(blocks.iter().skip(1)) {
+        anyhow::ensure!(
+            parent.is_parent_of(child),
+            "Block {} is not the parent of block {}",
+            parent.id(),
+            child.id()
+        );
+    }
+    anyhow::ensure!(
+        blocks
+            .iter()
+            .all(|block| block.epoch() == epoch_state.epoch),
+        "The ordered blocks are not in epoch {}",
+        epoch_state.epoch
+    );
+    ordered_proof.verify_signatures(&epoch_state.verifier)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::EmptyStateComputer;
+    use aptos_consensus_notifications::new_observer_pause_notifier_listener_pair;
+    use aptos_consensus_types::{
+        block::block_test_utils::{certificate_for_genesis, gen_test_certificate},
+        block_data::BlockData,
+        common::Payload,
+    };
+    use aptos_crypto::hash::CryptoHash;
+    use aptos_network::application::storage::PeersAndMetadata;
+    use aptos_types::{
+        aggregate_signature::AggregateSignature,
+        ledger_info::{generate_ledger_info_with_sig, LedgerInfo},
+        validator_signer::ValidatorSigner,
+        validator_verifier::random_validator_verifier,
+    };
+    use futures::channel::mpsc::UnboundedReceiver;
+
+    fn create_blocks(signers: &[ValidatorSigner], num_blocks: u64) -> Vec<Block> {
+        let mut blocks: Vec<Block> = vec![];
+        let mut quorum_cert = certificate_for_genesis();
+        for round in 1..=num_blocks {
+            let block_data = BlockData::new_proposal(
+                Payload::empty(true),
+                signers[0].author(),
+                vec![],
+                round,
+                round,
+                quorum_cert.clone(),
+            );
+            let block = Block::new_for_testing(block_data.hash(), block_data, None);
+            quorum_cert = gen_test_certificate(
+                signers,
+                block.gen_block_info(HashValue::zero(), 0, None),
+                quorum_cert.certified_block().clone(),
+                None,
+            );
+            blocks.push(block);
+        }
+        blocks
+    }
+
+    #[test]
+    fn test_verify_ordered_block() {
+        let (signers, verifier) = random_validator_verifier(1, None, false);
+        let blocks = create_blocks(&signers, 3);
+        let epoch_state = EpochState {
+            epoch: blocks[0].epoch(),
+            verifier,
+        };
+        let ledger_info = LedgerInfo::new(
+            blocks[2].gen_block_info(HashValue::zero(), 0, None),
+            HashValue::zero(),
+        );
+        let ordered_proof = generate_ledger_info_with_sig(&signers, ledger_info);
+
+        // A valid chain of blocks
+        let ordered_block = OrderedBlock {
+            blocks: blocks.clone(),
+            ordered_proof: ordered_proof.clone(),
+        };
+        verify_ordered_block(&ordered_block, &epoch_state).unwrap();
+
+        // The blocks don't form a chain
+        let ordered_block = OrderedBlock {
+            blocks: vec![blocks[0].clone(), blocks[2].clone()],
+            ordered_proof: ordered_proof.clone(),
+        };
+        assert!(verify_ordered_block(&ordered_block, &epoch_state).is_err());
+
+        // The last block isn't the ordered one
+        let ordered_block = OrderedBlock {
+            blocks: blocks[0..2].to_vec(),
+            ordered_proof: ordered_proof.clone(),
+        };
+        assert!(verify_ordered_block(&ordered_block, &epoch_state).is_err());
+
+        // The ordered proof isn't signed
+        let ordered_block = OrderedBlock {
+            blocks,
+            ordered_proof: LedgerInfoWithSignatures::new(
+                ordered_proof.ledger_info().clone(),
+                AggregateSignature::empty(),
+            ),
+        };
+        assert!(verify_ordered_block(&ordered_block, &epoch_state).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_pause_for_state_sync() {
+        let (signers, verifier) = random_validator_verifier(1, None, false);
+        let blocks = create_blocks(&signers, 3);
+        let epoch_state = Arc::new(EpochState {
+            epoch: blocks[0].epoch(),
+            verifier,
+        });
+
+        // Create an observer (rooted at the parent of the first block) and the block payloads
+        let root = LedgerInfoWithSignatures::new(
+            LedgerInfo::new(
+                blocks[0].quorum_cert().certified_block().clone(),
+                HashValue::zero(),
+            ),
+            AggregateSignature::empty(),
+        );
+        let (mut observer, mut block_rx) = create_observer(root, epoch_state);
+        for block in &blocks {
+            observer.block_payload_store.insert_block_payload(
+                BlockPayload {
+                    epoch: block.epoch(),
+                    round: block.round(),
+                    block_id: block.id(),
+                    transactions: vec![],
+                },
+                true,
+            );
+        }
+
+        // Verify the observer doesn't execute any blocks before it syncs to a commit
+        observer
+            .process_ordered_block(create_ordered_block(&signers, &blocks[0]))
+            .await;
+        assert!(block_rx.try_next().is_err());
+
+        // Sync to the first block and verify the observer executes the next block
+        observer
+            .process_commit_decision(create_commit_decision(&signers, &blocks[0]))
+            .await;
+        assert!(!observer.paused_for_state_sync);
+        observer
+            .process_ordered_block(create_ordered_block(&signers, &blocks[1]))
+            .await;
+        let ordered_blocks = block_rx.try_next().unwrap().unwrap();
+        assert_eq!(ordered_blocks.ordered_blocks[0].id(), blocks[1].id());
+
+        // Pause the observer (e.g., because it's idle) and verify the pause is acknowledged
+        let (observer_pause_notifier, mut observer_pause_listener) =
+            new_observer_pause_notifier_listener_pair();
+        let pause_ack = observer_pause_notifier.pause_observer().unwrap();
+        observer
+            .pause_for_state_sync(observer_pause_listener.next().await.unwrap())
+            .await;
+        pause_ack.await.unwrap();
+        assert!(observer.paused_for_state_sync);
+
+        // Verify the observer doesn't execute any blocks while state sync is running
+        observer
+            .process_ordered_block(create_ordered_block(&signers, &blocks[2]))
+            .await;
+        assert!(block_rx.try_next().is_err());
+
+        // Sync to the second block and verify the observer resumes execution
+        observer
+            .process_commit_decision(create_commit_decision(&signers, &blocks[1]))
+            .await;
+        assert!(!observer.paused_for_state_sync);
+        assert_eq!(observer.get_root().commit_info().id(), blocks[1].id());
+        let ordered_blocks = block_rx.try_next().unwrap().unwrap();
+        assert_eq!(ordered_blocks.ordered_blocks[0].id(), blocks[2].id());
+    }
+
+    /// Creates a (paused) observer in the given epoch, along with the receiver
+    /// for the blocks sent to the execution pipeline.
+    fn create_observer(
+        root: LedgerInfoWithSignatures,
+        epoch_state: Arc<EpochState>,
+    ) -> (ConsensusObserver, UnboundedReceiver<OrderedBlocks>) {
+        let consensus_observer_config = ConsensusObserverConfig::default();
+        let network_client =
+            NetworkClient::new(vec![], vec![], HashMap::new(), PeersAndMetadata::new(&[]));
+        let (block_tx, block_rx) = unbounded();
+        let observer = ConsensusObserver {
+            consensus_observer_config,
+            network_client,
+            active_subscription: None,
+            root: Arc::new(Mutex::new(root)),
+            last_ordered_block: None,
+            pending_ordered_blocks: BTreeMap::new(),
+            paused_for_state_sync: true,
+            block_payload_store: Arc::new(BlockPayloadStore::new(
+                consensus_observer_config.max_num_pending_blocks as usize,
+            )),
+            execution_proxy: Arc::new(EmptyStateComputer),
+            epoch_state: Some(epoch_state),
+            block_tx: Some(block_tx),
+            commit_msg_tx: None,
+            reset_tx: None,
+            consensus_publisher: None,
+        };
+        (observer, block_rx)
+    }
+
+    fn create_ordered_block(signers: &[ValidatorSigner], block: &Block) -> OrderedBlock {
+        OrderedBlock {
+            blocks: vec![block.clone()],
+            ordered_proof: create_ledger_info_with_sig(signers, block),
+        }
+    }
+
+    fn create_commit_decision(signers: &[ValidatorSigner], block: &Block) -> CommitDecision {
+        CommitDecision {
+            commit_proof: create_ledger_info_with_sig(signers, block),
+        }
+    }
+
+    fn create_ledger_info_with_sig(
+        signers: &[ValidatorSigner],
+        block: &Block,
+    ) -> LedgerInfoWithSignatures {
+        let ledger_info = LedgerInfo::new(
+            block.gen_block_info(HashValue::zero(), 0, None),
+            HashValue::zero(),
+        );
+        generate_ledger_info_with_sig(signers, ledger_info)
+    }
+}
@@ -0,0 +1,337 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{consensus_observer::network_message::BlockPayload, quorum_store::types::BatchPayload};
+use anyhow::{anyhow, ensure};
+use aptos_consensus_types::{
+    block::Block,
+    common::{Payload, Round},
+};
+use aptos_crypto::hash::CryptoHash;
+use aptos_executor_types::Error;
+use aptos_infallible::Mutex;
+use aptos_logger::prelude::*;
+use aptos_types::transaction::SignedTransaction;
+use std::collections::BTreeMap;
+
+/// The status of a block payload in the store. Payloads may arrive before the
+/// ordered blocks they belong to, in which case they can only be verified (against
+/// the batch digests of the block) once the block is known.
+enum BlockPayloadStatus {
+    Unverified(BlockPayload),
+    Verified(BlockPayload),
+}
+
+impl BlockPayloadStatus {
+    fn into_block_payload(self) -> BlockPayload {
+        match self {
+            BlockPayloadStatus::Unverified(block_payload)
+            | BlockPayloadStatus::Verified(block_payload) => block_payload,
+        }
+    }
+}
+
+/// BlockPayloadStore holds the block payloads received by the consensus observer
+/// (keyed by epoch and round) until the corresponding blocks are committed. It is
+/// shared with the execution pipeline via `PayloadManager::ConsensusObserver`.
+pub struct BlockPayloadStore {
+    max_num_pending_blocks: usize,
+    block_payloads: Mutex<BTreeMap<(u64, Round), BlockPayloadStatus>>,
+}
+
+impl BlockPayloadStore {
+    pub fn new(max_num_pending_blocks: usize) -> Self {
+        Self {
+            max_num_pending_blocks,
+            block_payloads: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    /// Inserts the given payload into the store. The payload must already be
+    /// verified (see `verify_block_payload`) iff `verified` is true. Returns
+    /// false if the store is already full and the payload was dropped.
+    pub fn insert_block_payload(&self, block_payload: BlockPayload, verified: bool) -> bool {
+        let mut block_payloads = self.block_payloads.lock();
+        if block_payloads.len() >= self.max_num_pending_blocks {
+            warn!(
+                "Dropping block payload for epoch {}, round {}: too many pending payloads",
+                block_payload.epoch, block_payload.round
+            );
+            return false;
+        }
+        let key = (block_payload.epoch, block_payload.round);
+        let block_payload = if verified {
+            BlockPayloadStatus::Verified(block_payload)
+        } else {
+            BlockPayloadStatus::Unverified(block_payload)
+        };
+        block_payloads.insert(key, block_payload);
+        true
+    }
+
+    /// Returns true iff the (verified) transactions of all the given blocks are
+    /// available. The given blocks must already be verified. Any unverified payloads
+    /// of the blocks are verified, and the payloads that fail verification are removed.
+    pub fn all_payloads_exist(&self, blocks: &[Block]) -> bool {
+        let mut block_payloads = self.block_payloads.lock();
+        blocks.iter().all(|block| match block.payload() {
+            Some(Payload::InQuorumStore(_)) => {
+                let key = (block.epoch(), block.round());
+                let verification_result = match block_payloads.get(&key) {
+                    Some(BlockPayloadStatus::Verified(block_payload)) => {
+                        return block_payload.block_id == block.id();
+                    },
+                    Some(BlockPayloadStatus::Unverified(block_payload)) => {
+                        verify_block_payload(block_payload, block)
+                    },
+                    None => return false,
+                };
+
+                // Mark the payload as verified, or drop it if it's invalid
+                let block_payload = block_payloads
+                    .remove(&key)
+                    .expect("The block payload must exist!");
+                match verification_result {
+                    Ok(()) => {
+                        let block_payload = block_payload.into_block_payload();
+                        block_payloads.insert(key, BlockPayloadStatus::Verified(block_payload));
+                        true
+                    },
+                    Err(error) => {
+                        warn!(
+                            "Dropping invalid block payload for epoch {}, round {}: {:?}",
+                            key.0, key.1, error
+                        );
+                        false
+                    },
+                }
+            },
+            // The transactions are part of the block itself
+            Some(Payload::DirectMempool(_)) | None => true,
+        })
+    }
+
+    /// Returns the transactions of the given block
+    pub fn get_transactions(&self, block: &Block) -> Result<Vec<SignedTransaction>, Error> {
+        match block.payload() {
+            None => Ok(Vec::new()),
+            Some(Payload::DirectMempool(txns)) => Ok(txns.clone()),
+            Some(Payload::InQuorumStore(_)) => {
+                // The batches are not available to observers, so we rely on the
+                // transactions forwarded by the publisher (once they are verified).
+                match self
+                    .block_payloads
+                    .lock()
+                    .get(&(block.epoch(), block.round()))
+                {
+                    Some(BlockPayloadStatus::Verified(block_payload))
+                        if block_payload.block_id == block.id() =>
+                    {
+                        Ok(block_payload.transactions.clone())
+                    },
+                    _ => Err(Error::DataNotFound(block.id())),
+                }
+            },
+        }
+    }
+
+    /// Removes all the payloads up to (and including) the given epoch and round
+    pub fn remove_committed_payloads(&self, epoch: u64, round: Round) {
+        let mut block_payloads = self.block_payloads.lock();
+        *block_payloads = block_payloads.split_off(&(epoch, round + 1));
+    }
+
+    /// Removes all the payloads from the store
+    pub fn clear_all_payloads(&self) {
+        self.block_payloads.lock().clear();
+    }
+}
+
+/// Verifies the given payload against the (verified) block, i.e., that the payload
+/// belongs to the block, and that its transactions match the digests of the batches
+/// (and proofs) in the block. The publisher sends the transactions of each batch in
+/// the order of the proofs in the block.
+pub fn verify_block_payload(block_payload: &BlockPayload, block: &Block) -> anyhow::Result<()> {
+    ensure!(
+        block_payload.block_id == block.id(),
+        "The payload block id {} doesn't match the block {}",
+        block_payload.block_id,
+        block.id()
+    );
+
+    let proof_with_data = match block.payload() {
+        Some(Payload::InQuorumStore(proof_with_data)) => proof_with_data,
+        payload => {
+            return Err(anyhow!(
+                "Expected a quorum store payload for block {}, found: {:?}",
+                block.id(),
+                payload
+            ))
+        },
+    };
+
+    let mut transactions = block_payload.transactions.iter();
+    for proof in &proof_with_data.proofs {
+        let batch_transactions: Vec<SignedTransaction> = transactions
+            .by_ref()
+            .take(proof.num_txns() as usize)
+            .cloned()
+            .collect();
+        ensure!(
+            batch_transactions.len() as u64 == proof.num_txns(),
+            "The payload is missing transactions of batch {}",
+            proof.digest()
+        );
+        let batch_payload = BatchPayload::new(proof.author(), batch_transactions);
+        ensure!(
+            batch_payload.hash() == *proof.digest(),
+            "The payload transactions don't match the digest of batch {}",
+            proof.digest()
+        );
+    }
+    ensure!(
+        transactions.next().is_none(),
+        "The payload has more transactions than the batches of block {}",
+        block.id()
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::quorum_store::{tests::utils::create_vec_signed_transactions, types::Batch};
+    use aptos_consensus_types::{
+        block::block_test_utils::certificate_for_genesis,
+        block_data::BlockData,
+        common::ProofWithData,
+        proof_of_store::{BatchId, ProofOfStore},
+    };
+    use aptos_types::{account_address::AccountAddress, aggregate_signature::AggregateSignature};
+
+    fn create_block(round: Round, payload: Payload) -> Block {
+        let block_data = BlockData::new_proposal(
+            payload,
+            AccountAddress::random(),
+            vec![],
+            round,
+            round,
+            certificate_for_genesis(),
+        );
+        Block::new_for_testing(block_data.hash(), block_data, None)
+    }
+
+    fn create_block_payload(block: &Block) -> BlockPayload {
+        BlockPayload {
+            epoch: block.epoch(),
+            round: block.round(),
+            block_id: block.id(),
+            transactions: vec![],
+        }
+    }
+
+    #[test]
+    fn test_all_payloads_exist() {
+        let store = BlockPayloadStore::new(10);
+        let direct_mempool_block = create_block(1, Payload::empty(false));
+        let quorum_store_block = create_block(2, Payload::empty(true));
+        let blocks = vec![direct_mempool_block, quorum_store_block.clone()];
+
+        // Quorum store blocks require the payload from the publisher
+        assert!(!store.all_payloads_exist(&blocks));
+        assert!(store.get_transactions(&quorum_store_block).is_err());
+
+        // A payload for a different block at the same round doesn't count
+        let mut block_payload = create_block_payload(&quorum_store_block);
+        block_payload.block_id = create_block(2, Payload::empty(true)).id();
+        assert!(store.insert_block_payload(block_payload, false));
+        assert!(!store.all_payloads_exist(&blocks));
+
+        assert!(store.insert_block_payload(create_block_payload(&quorum_store_block), false));
+        assert!(store.all_payloads_exist(&blocks));
+        assert!(store.get_transactions(&quorum_store_block).is_ok());
+    }
+
+    #[test]
+    fn test_remove_committed_payloads() {
+        let store = BlockPayloadStore::new(3);
+        let blocks: Vec<_> = (1..=4)
+            .map(|round| create_block(round, Payload::empty(true)))
+            .collect();
+        for block in &blocks[0..3] {
+            assert!(store.insert_block_payload(create_block_payload(block), true));
+        }
+
+        // The store is full
+        assert!(!store.insert_block_payload(create_block_payload(&blocks[3]), true));
+
+        // Remove the payloads up to round 2
+        store.remove_committed_payloads(blocks[1].epoch(), 2);
+        assert!(!store.all_payloads_exist(&blocks[0..2]));
+        assert!(store.all_payloads_exist(&blocks[2..3]));
+        assert!(store.insert_block_payload(create_block_payload(&blocks[3]), true));
+
+        store.clear_all_payloads();
+        assert!(!store.all_payloads_exist(&blocks[2..3]));
+    }
+
+    #[test]
+    fn test_verify_block_payload() {
+        // Create a block with two batches
+        let batches: Vec<_> = (0..2)
+            .map(|batch_id| {
+                Batch::new(
+                    BatchId::new_for_test(batch_id),
+                    create_vec_signed_transactions(3),
+                    1,
+                    0,
+                    AccountAddress::random(),
+                    0,
+                )
+            })
+            .collect();
+        let proofs = batches
+            .iter()
+            .map(|batch| ProofOfStore::new(batch.batch_info().clone(), AggregateSignature::empty()))
+            .collect();
+        let block = create_block(1, Payload::InQuorumStore(ProofWithData::new(proofs)));
+        let transactions: Vec<_> = batches
+            .into_iter()
+            .flat_map(|batch| batch.into_transactions())
+            .collect();
+
+        // The payload with the transactions of all batches is valid
+        let mut block_payload = create_block_payload(&block);
+        block_payload.transactions = transactions.clone();
+        assert!(verify_block_payload(&block_payload, &block).is_ok());
+
+        // Payloads with missing, extra, reordered or different transactions are invalid
+        let mut invalid_transactions = vec![
+            transactions[..5].to_vec(),
+            [transactions.clone(), create_vec_signed_transactions(1)].concat(),
+            [transactions[3..].to_vec(), transactions[..3].to_vec()].concat(),
+            transactions.clone(),
+        ];
+        invalid_transactions[3][4] = create_vec_signed_transactions(1).remove(0);
+        for transactions in invalid_transactions {
+            let mut invalid_block_payload = create_block_payload(&block);
+            invalid_block_payload.transactions = transactions;
+            assert!(verify_block_payload(&invalid_block_payload, &block).is_err());
+
+            // Unverified invalid payloads are dropped by the store
+            let store = BlockPayloadStore::new(10);
+            assert!(store.insert_block_payload(invalid_block_payload, false));
+            assert!(!store.all_payloads_exist(&[block.clone()]));
+            assert!(store.get_transactions(&block).is_err());
+            assert!(store.insert_block_payload(block_payload.clone(), false));
+            assert!(store.all_payloads_exist(&[block.clone()]));
+        }
+
+        // Unverified payloads are only returned once they are verified
+        let store = BlockPayloadStore::new(10);
+        assert!(store.insert_block_payload(block_payload, false));
+        assert!(store.get_transactions(&block).is_err());
+        assert!(store.all_payloads_exist(&[block.clone()]));
+        assert_eq!(store.get_transactions(&block).unwrap(), transactions);
+    }
+}
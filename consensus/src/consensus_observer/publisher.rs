@@ -0,0 +1,233 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    consensus_observer::{
+        network_handler::ResponseSender,
+        network_message::{
+            ConsensusObserverDirectSend, ConsensusObserverMessage, ConsensusObserverRequest,
+            ConsensusObserverResponse,
+        },
+    },
+    counters,
+};
+use aptos_config::{config::ConsensusObserverConfig, network_id::PeerNetworkId};
+use aptos_infallible::RwLock;
+use aptos_logger::prelude::*;
+use aptos_network::application::interface::{NetworkClient, NetworkClientInterface};
+use std::{collections::HashSet, sync::Arc, time::Duration};
+
+/// ConsensusPublisher forwards the consensus updates of this node (ordered blocks, block
+/// payloads and commit decisions) to all the consensus observers subscribed to it.
+pub struct ConsensusPublisher {
+    consensus_observer_config: ConsensusObserverConfig,
+    network_client: NetworkClient<ConsensusObserverMessage>,
+    active_subscribers: RwLock<HashSet<PeerNetworkId>>,
+}
+
+impl ConsensusPublisher {
+    pub fn new(
+        consensus_observer_config: ConsensusObserverConfig,
+        network_client: NetworkClient<ConsensusObserverMessage>,
+    ) -> Self {
+        Self {
+            consensus_observer_config,
+            network_client,
+            active_subscribers: RwLock::new(HashSet::new()),
+        }
+    }
+
+    /// Handles a subscription request from the given peer
+    pub fn handle_subscription_request(
+        &self,
+        peer_network_id: PeerNetworkId,
+        request: ConsensusObserverRequest,
+        response_sender: ResponseSender,
+    ) {
+        let response = match request {
+            ConsensusObserverRequest::Subscribe => {
+                // Reject new subscribers if there are already too many
+                let mut active_subscribers = self.active_subscribers.write();
+                let max_num_subscribers = self.consensus_observer_config.max_num_subscribers;
+                if !active_subscribers.contains(&peer_network_id)
+                    && active_subscribers.len() as u64 >= max_num_subscribers
+                {
+                    warn!(
+                        "Rejecting the subscription from {}: too many consensus observers ({})",
+                        peer_network_id, max_num_subscribers
+                    );
+                    ConsensusObserverResponse::SubscribeRejected
+                } else {
+                    info!(
+                        "New consensus observer subscription from {}",
+                        peer_network_id
+                    );
+                    active_subscribers.insert(peer_network_id);
+                    ConsensusObserverResponse::SubscribeAck
+                }
+            },
+            ConsensusObserverRequest::Unsubscribe => {
+                info!("Consensus observer {} unsubscribed", peer_network_id);
+                self.active_subscribers.write().remove(&peer_network_id);
+                ConsensusObserverResponse::UnsubscribeAck
+            },
+        };
+        counters::CONSENSUS_PUBLISHER_NUM_SUBSCRIBERS
+            .set(self.active_subscribers.read().len() as i64);
+        response_sender.send(response);
+    }
+
+    /// Returns the peers that are currently subscribed
+    pub fn get_active_subscribers(&self) -> HashSet<PeerNetworkId> {
+        self.active_subscribers.read().clone()
+    }
+
+    /// Sends the given message to all active subscribers
+    pub fn publish_message(&self, message: ConsensusObserverDirectSend) {
+        let active_subscribers: Vec<_> = self.get_active_subscribers().into_iter().collect();
+        if active_subscribers.is_empty() {
+            return;
+        }
+
+        counters::CONSENSUS_PUBLISHER_SENT_MESSAGES
+            .with_label_values(&[message.get_label()])
+            .inc();
+        if let Err(error) = self.network_client.send_to_peers(
+            ConsensusObserverMessage::DirectSend(message),
+            &active_subscribers,
+        ) {
+            warn!("Failed to publish consensus observer message: {:?}", error);
+        }
+    }
+
+    /// Removes the subscribers that are no longer connected
+    fn garbage_collect_subscriptions(&self) {
+        let connected_peers: HashSet<_> = match self.network_client.get_available_peers() {
+            Ok(connected_peers) => connected_peers.into_iter().collect(),
+            Err(error) => {
+                warn!("Failed to get the connected peers: {:?}", error);
+                return;
+            },
+        };
+
+        let mut active_subscribers = self.active_subscribers.write();
+        active_subscribers.retain(|peer_network_id| {
+            let connected = connected_peers.contains(peer_network_id);
+            if !connected {
+                info!(
+                    "Removing consensus observer {} as it is disconnected",
+                    peer_network_id
+                );
+            }
+            connected
+        });
+        counters::CONSENSUS_PUBLISHER_NUM_SUBSCRIBERS.set(active_subscribers.len() as i64);
+    }
+
+    /// Periodically removes the subscriptions of disconnected peers
+    pub async fn start(self: Arc<Self>, garbage_collection_interval: Duration) {
+        let mut interval = tokio::time::interval(garbage_collection_interval);
+        loop {
+            interval.tick().await;
+            self.garbage_collect_subscriptions();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_config::network_id::NetworkId;
+    use aptos_network::{application::storage::PeersAndMetadata, ProtocolId};
+    use aptos_types::PeerId;
+    use futures::channel::oneshot;
+    use std::collections::HashMap;
+
+    /// Sends the given request to the publisher and returns the response
+    fn send_request(
+        consensus_publisher: &ConsensusPublisher,
+        peer_network_id: PeerNetworkId,
+        request: ConsensusObserverRequest,
+    ) -> ConsensusObserverResponse {
+        let (response_tx, mut response_rx) = oneshot::channel();
+        let response_sender = ResponseSender::new(ProtocolId::ConsensusObserverRpc, response_tx);
+        consensus_publisher.handle_subscription_request(peer_network_id, request, response_sender);
+
+        let response_bytes = response_rx.try_recv().unwrap().unwrap().unwrap();
+        match ProtocolId::ConsensusObserverRpc
+            .from_bytes(&response_bytes)
+            .unwrap()
+        {
+            ConsensusObserverMessage::Response(response) => response,
+            message => panic!("Unexpected message: {:?}", message),
+        }
+    }
+
+    #[test]
+    fn test_max_num_subscribers() {
+        // Create a publisher that accepts at most 2 subscribers
+        let consensus_observer_config = ConsensusObserverConfig {
+            max_num_subscribers: 2,
+            ..Default::default()
+        };
+        let network_client =
+            NetworkClient::new(vec![], vec![], HashMap::new(), PeersAndMetadata::new(&[]));
+        let consensus_publisher =
+            ConsensusPublisher::new(consensus_observer_config, network_client);
+
+        // Subscribe the first two peers
+        let peers: Vec<_> = (0..3)
+            .map(|_| PeerNetworkId::new(NetworkId::Public, PeerId::random()))
+            .collect();
+        for peer_network_id in &peers[0..2] {
+            assert_eq!(
+                send_request(
+                    &consensus_publisher,
+                    *peer_network_id,
+                    ConsensusObserverRequest::Subscribe
+                ),
+                ConsensusObserverResponse::SubscribeAck
+            );
+        }
+
+        // Verify the third peer is rejected, but existing subscribers can resubscribe
+        assert_eq!(
+            send_request(
+                &consensus_publisher,
+                peers[2],
+                ConsensusObserverRequest::Subscribe
+            ),
+            ConsensusObserverResponse::SubscribeRejected
+        );
+        assert_eq!(
+            send_request(
+                &consensus_publisher,
+                peers[0],
+                ConsensusObserverRequest::Subscribe
+            ),
+            ConsensusObserverResponse::SubscribeAck
+        );
+        assert_eq!(
+            consensus_publisher.get_active_subscribers(),
+            peers[0..2].iter().cloned().collect()
+        );
+
+        // Verify the third peer can subscribe once another peer unsubscribes
+        assert_eq!(
+            send_request(
+                &consensus_publisher,
+                peers[1],
+                ConsensusObserverRequest::Unsubscribe
+            ),
+            ConsensusObserverResponse::UnsubscribeAck
+        );
+        assert_eq!(
+            send_request(
+                &consensus_publisher,
+                peers[2],
+                ConsensusObserverRequest::Subscribe
+            ),
+            ConsensusObserverResponse::SubscribeAck
+        );
+    }
+}
@@ -3,19 +3,28 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    consensus_observer::{
+        network_handler::ConsensusObserverNetworkHandler,
+        network_message::{ConsensusObserverDirectSend, ConsensusObserverMessage},
+        observer::ConsensusObserver,
+        publisher::ConsensusPublisher,
+    },
     counters,
     epoch_manager::EpochManager,
+    message_recorder::{ConsensusRecorder, RecordingStateComputer},
     network::NetworkTask,
     network_interface::{ConsensusMsg, ConsensusNetworkClient},
     persistent_liveness_storage::StorageWriteProxy,
     quorum_store::quorum_store_db::QuorumStoreDB,
     state_computer::ExecutionProxy,
+    state_replication::StateComputer,
     txn_notifier::MempoolNotifier,
     util::time_service::ClockTimeService,
 };
 use aptos_bounded_executor::BoundedExecutor;
-use aptos_config::config::NodeConfig;
-use aptos_consensus_notifications::ConsensusNotificationSender;
+use aptos_channels::{aptos_channel, message_queues::QueueStyle};
+use aptos_config::{config::NodeConfig, network_id::PeerNetworkId};
+use aptos_consensus_notifications::{ConsensusNotificationSender, ObserverPauseListener};
 use aptos_event_notifications::ReconfigNotificationListener;
 use aptos_executor::block_executor::BlockExecutor;
 use aptos_logger::prelude::*;
@@ -24,7 +33,7 @@ use aptos_network::application::interface::{NetworkClient, NetworkServiceEvents}
 use aptos_storage_interface::DbReaderWriter;
 use aptos_vm::AptosVM;
 use futures::channel::mpsc;
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 use tokio::runtime::Runtime;
 
 /// Helper function to start consensus based on configuration and return the runtime
@@ -36,8 +45,23 @@ pub fn start_consensus(
     consensus_to_mempool_sender: mpsc::Sender<QuorumStoreRequest>,
     aptos_db: DbReaderWriter,
     reconfig_events: ReconfigNotificationListener,
+    consensus_observer_network: Option<(
+        NetworkClient<ConsensusObserverMessage>,
+        NetworkServiceEvents<ConsensusObserverMessage>,
+    )>,
 ) -> Runtime {
     let runtime = aptos_runtimes::spawn_named_runtime("consensus".into(), None);
+    let consensus_publisher = consensus_observer_network.and_then(
+        |(observer_network_client, observer_network_service_events)| {
+            start_consensus_observer_network(
+                node_config,
+                observer_network_client,
+                observer_network_service_events,
+                &runtime,
+            )
+            .0
+        },
+    );
     let storage = Arc::new(StorageWriteProxy::new(node_config, aptos_db.reader.clone()));
     let quorum_store_db = Arc::new(QuorumStoreDB::new(node_config.storage.dir()));
 
@@ -46,12 +70,20 @@ pub fn start_consensus(
         node_config.consensus.mempool_executed_txn_timeout_ms,
     ));
 
-    let state_computer = Arc::new(ExecutionProxy::new(
+    let recorder = create_consensus_recorder(node_config);
+    let mut state_computer: Arc<dyn StateComputer> = Arc::new(ExecutionProxy::new(
         Arc::new(BlockExecutor::<AptosVM>::new(aptos_db)),
         txn_notifier,
         state_sync_notifier,
         runtime.handle(),
+        consensus_publisher.clone(),
     ));
+    if let Some(recorder) = &recorder {
+        state_computer = Arc::new(RecordingStateComputer::new(
+            state_computer,
+            recorder.clone(),
+        ));
+    }
 
     let time_service = Arc::new(ClockTimeService::new(runtime.handle().clone()));
 
@@ -73,9 +105,12 @@ pub fn start_consensus(
         quorum_store_db,
         reconfig_events,
         bounded_executor,
+        consensus_publisher,
+        recorder.clone(),
     );
 
-    let (network_task, network_receiver) = NetworkTask::new(network_service_events, self_receiver);
+    let (network_task, network_receiver) =
+        NetworkTask::new(network_service_events, self_receiver, recorder);
 
     runtime.spawn(network_task.start());
     runtime.spawn(epoch_mgr.start(timeout_receiver, network_receiver));
@@ -83,3 +118,120 @@ pub fn start_consensus(
     debug!("Consensus started.");
     runtime
 }
+
+/// Helper function to start the consensus observer (on fullnodes) and return the runtime
+pub fn start_consensus_observer(
+    node_config: &NodeConfig,
+    network_client: NetworkClient<ConsensusObserverMessage>,
+    network_service_events: NetworkServiceEvents<ConsensusObserverMessage>,
+    state_sync_notifier: Arc<dyn ConsensusNotificationSender>,
+    observer_pause_listener: ObserverPauseListener,
+    consensus_to_mempool_sender: mpsc::Sender<QuorumStoreRequest>,
+    aptos_db: DbReaderWriter,
+    reconfig_events: ReconfigNotificationListener,
+) -> Runtime {
+    let runtime = aptos_runtimes::spawn_named_runtime("observer".into(), None);
+    let (consensus_publisher, observer_message_rx) = start_consensus_observer_network(
+        node_config,
+        network_client.clone(),
+        network_service_events,
+        &runtime,
+    );
+
+    let txn_notifier = Arc::new(MempoolNotifier::new(
+        consensus_to_mempool_sender,
+        node_config.consensus.mempool_executed_txn_timeout_ms,
+    ));
+    let execution_proxy = Arc::new(ExecutionProxy::new(
+        Arc::new(BlockExecutor::<AptosVM>::new(aptos_db.clone())),
+        txn_notifier,
+        state_sync_notifier,
+        runtime.handle(),
+        consensus_publisher.clone(),
+    ));
+
+    let consensus_observer = ConsensusObserver::new(
+        node_config.consensus_observer,
+        network_client,
+        aptos_db.reader,
+        execution_proxy,
+        consensus_publisher,
+    );
+    runtime.spawn(consensus_observer.start(
+        observer_message_rx.expect("The consensus observer must be enabled!"),
+        reconfig_events,
+        observer_pause_listener,
+    ));
+
+    debug!("Consensus observer started.");
+    runtime
+}
+
+/// Creates the consensus message recorder (if enabled). Failing to create the recorder
+/// is not fatal: consensus simply runs without it.
+fn create_consensus_recorder(node_config: &NodeConfig) -> Option<Arc<ConsensusRecorder>> {
+    let recorder_config = &node_config.consensus.message_recorder;
+    if !recorder_config.enabled {
+        return None;
+    }
+    match ConsensusRecorder::new(recorder_config) {
+        Ok(recorder) => {
+            info!(
+                "Recording consensus messages to: {:?}",
+                recorder_config.recording_dir()
+            );
+            Some(Arc::new(recorder))
+        },
+        Err(error) => {
+            error!(error = ?error, "Failed to start the consensus message recorder!");
+            None
+        },
+    }
+}
+
+/// Spawns the consensus observer network handler, together with the consensus publisher (if
+/// enabled). Returns the publisher and the receiver of the observer messages (if enabled).
+fn start_consensus_observer_network(
+    node_config: &NodeConfig,
+    network_client: NetworkClient<ConsensusObserverMessage>,
+    network_service_events: NetworkServiceEvents<ConsensusObserverMessage>,
+    runtime: &Runtime,
+) -> (
+    Option<Arc<ConsensusPublisher>>,
+    Option<aptos_channel::Receiver<(), (PeerNetworkId, ConsensusObserverDirectSend)>>,
+) {
+    let consensus_observer_config = node_config.consensus_observer;
+
+    let consensus_publisher = if consensus_observer_config.publisher_enabled {
+        let consensus_publisher = Arc::new(ConsensusPublisher::new(
+            consensus_observer_config,
+            network_client,
+        ));
+        runtime.spawn(consensus_publisher.clone().start(Duration::from_millis(
+            consensus_observer_config.progress_check_interval_ms,
+        )));
+        Some(consensus_publisher)
+    } else {
+        None
+    };
+
+    let (observer_message_tx, observer_message_rx) = if consensus_observer_config.observer_enabled {
+        let (observer_message_tx, observer_message_rx) = aptos_channel::new(
+            QueueStyle::FIFO,
+            consensus_observer_config.max_network_channel_size as usize,
+            None,
+        );
+        (Some(observer_message_tx), Some(observer_message_rx))
+    } else {
+        (None, None)
+    };
+
+    let network_handler = ConsensusObserverNetworkHandler::new(
+        network_service_events,
+        observer_message_tx,
+        consensus_publisher.clone(),
+    );
+    runtime.spawn(network_handler.start());
+
+    (consensus_publisher, observer_message_rx)
+}
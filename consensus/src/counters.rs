@@ -116,6 +116,15 @@ pub static NUM_SENDERS_IN_BLOCK: Lazy<Gauge> = Lazy::new(|| {
     register_gauge!("num_senders_in_block", "Total number of senders in a block").unwrap()
 });
 
+/// Number of transactions the shuffler couldn't separate from a conflicting transaction
+pub static NUM_CONFLICTING_TXNS_IN_BLOCK: Lazy<Gauge> = Lazy::new(|| {
+    register_gauge!(
+        "aptos_consensus_num_conflicting_txns_in_block",
+        "Number of transactions in a block the shuffler couldn't separate from a conflicting transaction"
+    )
+    .unwrap()
+});
+
 /// Transaction shuffling call latency
 pub static TXN_SHUFFLE_SECONDS: Lazy<Histogram> = Lazy::new(|| {
     register_histogram!(
@@ -291,6 +300,42 @@ pub static PROPOSER_PENDING_BLOCKS_FILL_FRACTION: Lazy<Gauge> = Lazy::new(|| {
     .unwrap()
 });
 
+/// Max number of txns in a proposed block, as computed by the adaptive block size controller
+pub static ADAPTIVE_BLOCK_SIZE_MAX_BLOCK_TXNS: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!(
+        "aptos_consensus_adaptive_block_size_max_block_txns",
+        "Max number of txns in a proposed block, as computed by the adaptive block size controller",
+    )
+    .unwrap()
+});
+
+/// Max number of bytes in a proposed block, as computed by the adaptive block size controller
+pub static ADAPTIVE_BLOCK_SIZE_MAX_BLOCK_BYTES: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!(
+        "aptos_consensus_adaptive_block_size_max_block_bytes",
+        "Max number of bytes in a proposed block, as computed by the adaptive block size controller",
+    )
+    .unwrap()
+});
+
+/// Moving average of the execution time per txn, as observed by the adaptive block size controller
+pub static ADAPTIVE_BLOCK_SIZE_EXECUTION_LATENCY_PER_TXN: Lazy<Gauge> = Lazy::new(|| {
+    register_gauge!(
+        "aptos_consensus_adaptive_block_size_execution_latency_per_txn",
+        "Moving average of the execution time (in seconds) per txn, used for adapting block size",
+    )
+    .unwrap()
+});
+
+/// Moving average of the block commit latency, as observed by the adaptive block size controller
+pub static ADAPTIVE_BLOCK_SIZE_COMMIT_LATENCY: Lazy<Gauge> = Lazy::new(|| {
+    register_gauge!(
+        "aptos_consensus_adaptive_block_size_commit_latency",
+        "Moving average of the latency (in seconds) from block timestamp to commit, used for adapting block size",
+    )
+    .unwrap()
+});
+
 /// Next set of counters are computed at leader election time, with some delay.
 
 /// Current voting power fraction that participated in consensus
@@ -792,3 +837,107 @@ pub static BATCH_WAIT_DURATION: Lazy<DurationHistogram> = Lazy::new(|| {
         .unwrap(),
     )
 });
+
+/// Counters(queued,dequeued,dropped) related to consensus observer network events
+pub static PENDING_CONSENSUS_OBSERVER_NETWORK_EVENTS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_consensus_observer_pending_network_events",
+        "Counters(queued,dequeued,dropped) related to consensus observer network events",
+        &["state"]
+    )
+    .unwrap()
+});
+
+/// Count of the messages published to consensus observers, by message type
+pub static CONSENSUS_PUBLISHER_SENT_MESSAGES: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_consensus_publisher_sent_messages",
+        "Count of the messages published to consensus observers, by message type",
+        &["message_type"]
+    )
+    .unwrap()
+});
+
+/// Number of consensus observers currently subscribed to this node
+pub static CONSENSUS_PUBLISHER_NUM_SUBSCRIBERS: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!(
+        "aptos_consensus_publisher_num_subscribers",
+        "Number of consensus observers currently subscribed to this node"
+    )
+    .unwrap()
+});
+
+/// Count of the messages received by the consensus observer, by message type and result
+pub static CONSENSUS_OBSERVER_RECEIVED_MESSAGES: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_consensus_observer_received_messages",
+        "Count of the messages received by the consensus observer, by message type and result",
+        &["message_type", "result"]
+    )
+    .unwrap()
+});
+
+/// Count of the subscription changes made by the consensus observer, by reason
+pub static CONSENSUS_OBSERVER_SUBSCRIPTION_CHANGES: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_consensus_observer_subscription_changes",
+        "Count of the subscription changes made by the consensus observer, by reason",
+        &["reason"]
+    )
+    .unwrap()
+});
+
+/// Count of the times the consensus observer fell back to state sync
+pub static CONSENSUS_OBSERVER_STATE_SYNC_FALLBACK_COUNT: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "aptos_consensus_observer_state_sync_fallback_count",
+        "Count of the times the consensus observer fell back to state sync"
+    )
+    .unwrap()
+});
+
+/// Count of the times state sync paused the consensus observer (e.g., because it stopped committing)
+pub static CONSENSUS_OBSERVER_STATE_SYNC_PAUSE_COUNT: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "aptos_consensus_observer_state_sync_pause_count",
+        "Count of the times state sync paused the consensus observer"
+    )
+    .unwrap()
+});
+
+/// Number of ordered blocks the consensus observer holds while waiting for their payloads
+pub static CONSENSUS_OBSERVER_NUM_PENDING_BLOCKS: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!(
+        "aptos_consensus_observer_num_pending_blocks",
+        "Number of ordered blocks the consensus observer holds while waiting for their payloads"
+    )
+    .unwrap()
+});
+
+/// Count of the events recorded by the consensus message recorder, by event type
+pub static CONSENSUS_RECORDED_EVENTS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_consensus_recorded_events",
+        "Count of the events recorded by the consensus message recorder, by event type",
+        &["event_type"]
+    )
+    .unwrap()
+});
+
+/// Count of the events dropped by the consensus message recorder (because the writer fell behind)
+pub static CONSENSUS_RECORDER_DROPPED_EVENTS: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "aptos_consensus_recorder_dropped_events",
+        "Count of the events dropped by the consensus message recorder"
+    )
+    .unwrap()
+});
+
+/// Count of the events the consensus message recorder failed to write
+pub static CONSENSUS_RECORDER_ERRORS: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "aptos_consensus_recorder_errors",
+        "Count of the events the consensus message recorder failed to write"
+    )
+    .unwrap()
+});
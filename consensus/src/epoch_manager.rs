@@ -7,6 +7,7 @@ use crate::{
         tracing::{observe_block, BlockStage},
         BlockStore,
     },
+    consensus_observer::publisher::ConsensusPublisher,
     counters,
     error::{error_kind, DbError},
     experimental::{
@@ -15,6 +16,7 @@ use crate::{
         ordering_state_computer::OrderingStateComputer,
     },
     liveness::{
+        block_size_controller::AdaptiveBlockSizeController,
         cached_proposer_election::CachedProposerElection,
         leader_reputation::{
             extract_epoch_to_proposers, AptosDBBackend, LeaderReputation,
@@ -29,6 +31,7 @@ use crate::{
         round_state::{ExponentialTimeInterval, RoundState},
     },
     logging::{LogEvent, LogSchema},
+    message_recorder::{ConsensusRecorder, RecordedEvent, RoundManagerSnapshot},
     metrics_safety_rules::MetricsSafetyRules,
     monitor,
     network::{
@@ -63,7 +66,7 @@ use aptos_infallible::{duration_since_epoch, Mutex};
 use aptos_logger::prelude::*;
 use aptos_mempool::QuorumStoreRequest;
 use aptos_network::{application::interface::NetworkClient, protocols::network::Event};
-use aptos_safety_rules::SafetyRulesManager;
+use aptos_safety_rules::{SafetyRulesManager, TSafetyRules};
 use aptos_types::{
     account_address::AccountAddress,
     epoch_change::EpochChangeProof,
@@ -140,6 +143,10 @@ pub struct EpochManager {
     bounded_executor: BoundedExecutor,
     // recovery_mode is set to true when the recovery manager is spawned
     recovery_mode: bool,
+    // forwards the ordered and committed blocks to consensus observers (if enabled)
+    consensus_publisher: Option<Arc<ConsensusPublisher>>,
+    // records the round manager inputs and outputs (if the message recorder is enabled)
+    recorder: Option<Arc<ConsensusRecorder>>,
 }
 
 impl EpochManager {
@@ -155,6 +162,8 @@ impl EpochManager {
         quorum_store_storage: Arc<dyn QuorumStoreStorage>,
         reconfig_events: ReconfigNotificationListener,
         bounded_executor: BoundedExecutor,
+        consensus_publisher: Option<Arc<ConsensusPublisher>>,
+        recorder: Option<Arc<ConsensusRecorder>>,
     ) -> Self {
         let author = node_config.validator_network.as_ref().unwrap().peer_id();
         let config = node_config.consensus.clone();
@@ -186,6 +195,8 @@ impl EpochManager {
             batch_retrieval_tx: None,
             bounded_executor,
             recovery_mode: false,
+            consensus_publisher,
+            recorder,
         }
     }
 
@@ -485,12 +496,14 @@ impl EpochManager {
         &mut self,
         safety_rules_container: Arc<Mutex<MetricsSafetyRules>>,
         verifier: ValidatorVerifier,
+        block_size_controller: Arc<AdaptiveBlockSizeController>,
     ) -> OrderingStateComputer {
         let network_sender = NetworkSender::new(
             self.author,
             self.network_sender.clone(),
             self.self_sender.clone(),
             verifier.clone(),
+            self.recorder.clone(),
         );
 
         let (block_tx, block_rx) = unbounded::<OrderedBlocks>();
@@ -509,17 +522,21 @@ impl EpochManager {
             prepare_phases_and_buffer_manager(
                 self.author,
                 self.commit_state_computer.clone(),
-                safety_rules_container,
+                Some(safety_rules_container),
                 network_sender,
                 commit_msg_rx,
                 self.commit_state_computer.clone(),
                 block_rx,
                 reset_rx,
                 verifier,
+                block_size_controller,
+                self.consensus_publisher.clone(),
             );
 
         tokio::spawn(execution_phase.start());
-        tokio::spawn(signing_phase.start());
+        if let Some(signing_phase) = signing_phase {
+            tokio::spawn(signing_phase.start());
+        }
         tokio::spawn(persisting_phase.start());
         tokio::spawn(buffer_manager.start());
 
@@ -580,6 +597,7 @@ impl EpochManager {
             self.network_sender.clone(),
             self.self_sender.clone(),
             epoch_state.verifier.clone(),
+            self.recorder.clone(),
         );
         let (recovery_manager_tx, recovery_manager_rx) = aptos_channel::new(
             QueueStyle::LIFO,
@@ -633,6 +651,23 @@ impl EpochManager {
         let round_state =
             self.create_round_state(self.time_service.clone(), self.timeout_sender.clone());
 
+        if let Some(recorder) = &self.recorder {
+            let safety_data = safety_rules
+                .consensus_state()
+                .ok()
+                .map(|mut consensus_state| consensus_state.safety_data());
+            recorder.record(RecordedEvent::RoundManagerStarted(Box::new(
+                RoundManagerSnapshot::new(
+                    epoch_state.clone(),
+                    onchain_consensus_config.clone(),
+                    self.quorum_store_enabled,
+                    safety_data,
+                    &self.storage.recover_from_ledger(),
+                    &recovery_data,
+                ),
+            )));
+        }
+
         info!(epoch = epoch, "Create ProposerElection");
         let proposer_election =
             self.create_proposer_election(&epoch_state, &onchain_consensus_config);
@@ -641,11 +676,19 @@ impl EpochManager {
             self.network_sender.clone(),
             self.self_sender.clone(),
             epoch_state.verifier.clone(),
+            self.recorder.clone(),
         );
         let chain_health_backoff_config =
             ChainHealthBackoffConfig::new(self.config.chain_health_backoff.clone());
         let pipeline_backpressure_config =
             PipelineBackpressureConfig::new(self.config.pipeline_backpressure.clone());
+        let block_size_controller = Arc::new(AdaptiveBlockSizeController::new(
+            self.config.adaptive_block_size.clone(),
+            self.config
+                .max_sending_block_txns(self.quorum_store_enabled),
+            self.config
+                .max_sending_block_bytes(self.quorum_store_enabled),
+        ));
 
         let safety_rules_container = Arc::new(Mutex::new(safety_rules));
 
@@ -681,7 +724,7 @@ impl EpochManager {
         let (payload_manager, quorum_store_msg_tx) = quorum_store_builder.init_payload_manager();
         let transaction_shuffler =
             create_transaction_shuffler(onchain_execution_config.transaction_shuffler_type());
-        let block_gas_limit = onchain_execution_config.block_gas_limit();
+        let block_gas_limit_type = onchain_execution_config.block_gas_limit_type();
         let transaction_deduper =
             create_transaction_deduper(onchain_execution_config.transaction_deduper_type());
         self.quorum_store_msg_tx = quorum_store_msg_tx;
@@ -696,13 +739,14 @@ impl EpochManager {
             &epoch_state,
             payload_manager.clone(),
             transaction_shuffler,
-            block_gas_limit,
+            block_gas_limit_type,
             transaction_deduper,
         );
         let state_computer = if onchain_consensus_config.decoupled_execution() {
             Arc::new(self.spawn_decoupled_execution(
                 safety_rules_container.clone(),
                 epoch_state.verifier.clone(),
+                block_size_controller.clone(),
             ))
         } else {
             self.commit_state_computer.clone()
@@ -742,6 +786,7 @@ impl EpochManager {
             onchain_consensus_config.max_failed_authors_to_store(),
             pipeline_backpressure_config,
             chain_health_backoff_config,
+            block_size_controller,
             self.quorum_store_enabled,
         );
 
@@ -1082,6 +1127,9 @@ impl EpochManager {
     }
 
     fn process_local_timeout(&mut self, round: u64) {
+        if let Some(recorder) = &self.recorder {
+            recorder.record(RecordedEvent::LocalTimeout { round });
+        }
         let peer_id = self.author;
         let event = VerifiedEvent::LocalTimeout(round);
         let sender = self
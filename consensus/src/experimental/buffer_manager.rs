@@ -4,6 +4,10 @@
 
 use crate::{
     block_storage::tracing::{observe_block, BlockStage},
+    consensus_observer::{
+        network_message::{CommitDecision, ConsensusObserverDirectSend, OrderedBlock},
+        publisher::ConsensusPublisher,
+    },
     counters,
     experimental::{
         buffer::{Buffer, Cursor},
@@ -13,6 +17,7 @@ use crate::{
         pipeline_phase::CountedRequest,
         signing_phase::{SigningRequest, SigningResponse},
     },
+    liveness::block_size_controller::AdaptiveBlockSizeController,
     monitor,
     network::NetworkSender,
     round_manager::VerifiedEvent,
@@ -20,6 +25,7 @@ use crate::{
 };
 use aptos_consensus_types::{common::Author, executed_block::ExecutedBlock};
 use aptos_crypto::HashValue;
+use aptos_infallible::duration_since_epoch;
 use aptos_logger::prelude::*;
 use aptos_types::{
     account_address::AccountAddress, epoch_change::EpochChangeProof,
@@ -79,7 +85,8 @@ pub struct BufferManager {
     execution_phase_rx: Receiver<ExecutionResponse>,
 
     signing_root: BufferItemRootType,
-    signing_phase_tx: Sender<CountedRequest<SigningRequest>>,
+    // None for consensus observers: they don't sign commit votes and wait for the commit decision
+    signing_phase_tx: Option<Sender<CountedRequest<SigningRequest>>>,
     signing_phase_rx: Receiver<SigningResponse>,
 
     commit_msg_tx: NetworkSender,
@@ -104,6 +111,12 @@ pub struct BufferManager {
     // being updated on-chain.
     end_epoch_timestamp: OnceCell<u64>,
     previous_commit_time: Instant,
+
+    // Receives the commit latency of every aggregated block, to adapt future proposals
+    block_size_controller: Arc<AdaptiveBlockSizeController>,
+
+    // Forwards the ordered blocks and commit decisions to the subscribed consensus observers
+    consensus_publisher: Option<Arc<ConsensusPublisher>>,
 }
 
 impl BufferManager {
@@ -111,7 +124,7 @@ impl BufferManager {
         author: Author,
         execution_phase_tx: Sender<CountedRequest<ExecutionRequest>>,
         execution_phase_rx: Receiver<ExecutionResponse>,
-        signing_phase_tx: Sender<CountedRequest<SigningRequest>>,
+        signing_phase_tx: Option<Sender<CountedRequest<SigningRequest>>>,
         signing_phase_rx: Receiver<SigningResponse>,
         commit_msg_tx: NetworkSender,
         commit_msg_rx: aptos_channels::aptos_channel::Receiver<AccountAddress, VerifiedEvent>,
@@ -120,6 +133,8 @@ impl BufferManager {
         reset_rx: UnboundedReceiver<ResetRequest>,
         verifier: ValidatorVerifier,
         ongoing_tasks: Arc<AtomicU64>,
+        block_size_controller: Arc<AdaptiveBlockSizeController>,
+        consensus_publisher: Option<Arc<ConsensusPublisher>>,
     ) -> Self {
         let buffer = Buffer::<BufferItem>::new();
 
@@ -149,6 +164,8 @@ impl BufferManager {
             ongoing_tasks,
             end_epoch_timestamp: OnceCell::new(),
             previous_commit_time: Instant::now(),
+            block_size_controller,
+            consensus_publisher,
         }
     }
 
@@ -185,6 +202,14 @@ impl BufferManager {
             ordered_proof.commit_info(),
             self.buffer.len() + 1,
         );
+        if let Some(consensus_publisher) = &self.consensus_publisher {
+            consensus_publisher.publish_message(ConsensusObserverDirectSend::OrderedBlock(
+                OrderedBlock {
+                    blocks: ordered_blocks.iter().map(|b| b.block().clone()).collect(),
+                    ordered_proof: ordered_proof.clone(),
+                },
+            ));
+        }
         let item = BufferItem::new_ordered(ordered_blocks, ordered_proof, callback);
         self.buffer.push_back(item);
     }
@@ -220,6 +245,10 @@ impl BufferManager {
     /// Set the signing root to the first not signed item (Executed) and send execution request
     /// Set to None if not exist
     async fn advance_signing_root(&mut self) {
+        let signing_phase_tx = match &self.signing_phase_tx {
+            Some(signing_phase_tx) => signing_phase_tx.clone(),
+            None => return,
+        };
         let cursor = self.signing_root;
         self.signing_root = self
             .buffer
@@ -238,10 +267,9 @@ impl BufferManager {
                 commit_ledger_info: executed_item.partial_commit_proof.ledger_info().clone(),
             });
             if cursor == self.signing_root {
-                let sender = self.signing_phase_tx.clone();
-                Self::spawn_retry_request(sender, request, Duration::from_millis(100));
+                Self::spawn_retry_request(signing_phase_tx, request, Duration::from_millis(100));
             } else {
-                self.signing_phase_tx
+                signing_phase_tx
                     .send(request)
                     .await
                     .expect("Failed to send signing request");
@@ -271,6 +299,18 @@ impl BufferManager {
                 let aggregated_item = item.unwrap_aggregated();
                 let block = aggregated_item.executed_blocks.last().unwrap().block();
                 observe_block(block.timestamp_usecs(), BlockStage::COMMIT_CERTIFIED);
+                if let Some(commit_latency) = duration_since_epoch()
+                    .checked_sub(Duration::from_micros(block.timestamp_usecs()))
+                {
+                    self.block_size_controller.observe_commit(commit_latency);
+                }
+                if let Some(consensus_publisher) = &self.consensus_publisher {
+                    consensus_publisher.publish_message(
+                        ConsensusObserverDirectSend::CommitDecision(CommitDecision {
+                            commit_proof: aggregated_item.commit_proof.clone(),
+                        }),
+                    );
+                }
                 // if we're the proposer for the block, we're responsible to broadcast the commit decision.
                 if block.author() == Some(self.author) {
                     self.commit_msg_tx
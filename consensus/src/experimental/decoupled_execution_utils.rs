@@ -3,6 +3,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    consensus_observer::publisher::ConsensusPublisher,
     experimental::{
         buffer_manager::{create_channel, BufferManager, OrderedBlocks, ResetRequest},
         execution_phase::{ExecutionPhase, ExecutionRequest, ExecutionResponse},
@@ -10,6 +11,7 @@ use crate::{
         pipeline_phase::{CountedRequest, PipelinePhase},
         signing_phase::{SigningPhase, SigningRequest, SigningResponse},
     },
+    liveness::block_size_controller::AdaptiveBlockSizeController,
     metrics_safety_rules::MetricsSafetyRules,
     network::NetworkSender,
     round_manager::VerifiedEvent,
@@ -23,19 +25,22 @@ use futures::channel::mpsc::UnboundedReceiver;
 use std::sync::{atomic::AtomicU64, Arc};
 
 /// build channels and return phases and buffer manager
+/// the signing phase is only created if safety rules are given (i.e., not for consensus observers)
 pub fn prepare_phases_and_buffer_manager(
     author: Author,
     execution_proxy: Arc<dyn StateComputer>,
-    safety_rules: Arc<Mutex<MetricsSafetyRules>>,
+    safety_rules: Option<Arc<Mutex<MetricsSafetyRules>>>,
     commit_msg_tx: NetworkSender,
     commit_msg_rx: Receiver<AccountAddress, VerifiedEvent>,
     persisting_proxy: Arc<dyn StateComputer>,
     block_rx: UnboundedReceiver<OrderedBlocks>,
     sync_rx: UnboundedReceiver<ResetRequest>,
     verifier: ValidatorVerifier,
+    block_size_controller: Arc<AdaptiveBlockSizeController>,
+    consensus_publisher: Option<Arc<ConsensusPublisher>>,
 ) -> (
     PipelinePhase<ExecutionPhase>,
-    PipelinePhase<SigningPhase>,
+    Option<PipelinePhase<SigningPhase>>,
     PipelinePhase<PersistingPhase>,
     BufferManager,
 ) {
@@ -47,7 +52,8 @@ pub fn prepare_phases_and_buffer_manager(
 
     let ongoing_tasks = Arc::new(AtomicU64::new(0));
 
-    let execution_phase_processor = ExecutionPhase::new(execution_proxy);
+    let execution_phase_processor =
+        ExecutionPhase::new(execution_proxy, block_size_controller.clone());
     let execution_phase = PipelinePhase::new(
         execution_phase_request_rx,
         Some(execution_phase_response_tx),
@@ -60,12 +66,14 @@ pub fn prepare_phases_and_buffer_manager(
     let (signing_phase_response_tx, signing_phase_response_rx) =
         create_channel::<SigningResponse>();
 
-    let signing_phase_processor = SigningPhase::new(safety_rules);
-    let signing_phase = PipelinePhase::new(
-        signing_phase_request_rx,
-        Some(signing_phase_response_tx),
-        Box::new(signing_phase_processor),
-    );
+    let signing_phase = safety_rules.map(|safety_rules| {
+        PipelinePhase::new(
+            signing_phase_request_rx,
+            Some(signing_phase_response_tx),
+            Box::new(SigningPhase::new(safety_rules)),
+        )
+    });
+    let signing_phase_request_tx = signing_phase.as_ref().map(|_| signing_phase_request_tx);
 
     // Persisting Phase
     let (persisting_phase_request_tx, persisting_phase_request_rx) =
@@ -95,6 +103,8 @@ pub fn prepare_phases_and_buffer_manager(
             sync_rx,
             verifier,
             ongoing_tasks,
+            block_size_controller,
+            consensus_publisher,
         ),
     )
 }
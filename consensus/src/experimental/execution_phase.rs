@@ -2,7 +2,10 @@
 // Parts of the project are originally copyright © Meta Platforms, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{experimental::pipeline_phase::StatelessPipeline, state_replication::StateComputer};
+use crate::{
+    experimental::pipeline_phase::StatelessPipeline,
+    liveness::block_size_controller::AdaptiveBlockSizeController, state_replication::StateComputer,
+};
 use anyhow::Result;
 use aptos_consensus_types::executed_block::ExecutedBlock;
 use aptos_crypto::HashValue;
@@ -11,6 +14,7 @@ use async_trait::async_trait;
 use std::{
     fmt::{Debug, Display, Formatter},
     sync::Arc,
+    time::Instant,
 };
 
 /// [ This class is used when consensus.decoupled = true ]
@@ -42,11 +46,18 @@ pub struct ExecutionResponse {
 
 pub struct ExecutionPhase {
     execution_proxy: Arc<dyn StateComputer>,
+    block_size_controller: Arc<AdaptiveBlockSizeController>,
 }
 
 impl ExecutionPhase {
-    pub fn new(execution_proxy: Arc<dyn StateComputer>) -> Self {
-        Self { execution_proxy }
+    pub fn new(
+        execution_proxy: Arc<dyn StateComputer>,
+        block_size_controller: Arc<AdaptiveBlockSizeController>,
+    ) -> Self {
+        Self {
+            execution_proxy,
+            block_size_controller,
+        }
     }
 }
 
@@ -70,8 +81,13 @@ impl StatelessPipeline for ExecutionPhase {
         let mut result = vec![];
 
         for b in ordered_blocks {
+            let start_time = Instant::now();
             match self.execution_proxy.compute(b.block(), b.parent_id()).await {
                 Ok(compute_result) => {
+                    self.block_size_controller.observe_execution(
+                        compute_result.compute_status().len(),
+                        start_time.elapsed(),
+                    );
                     result.push(ExecutedBlock::new(b.block().clone(), compute_result));
                 },
                 Err(e) => {
@@ -18,7 +18,10 @@ use aptos_consensus_types::{block::Block, executed_block::ExecutedBlock};
 use aptos_crypto::HashValue;
 use aptos_executor_types::{Error as ExecutionError, StateComputeResult};
 use aptos_logger::prelude::*;
-use aptos_types::{epoch_state::EpochState, ledger_info::LedgerInfoWithSignatures};
+use aptos_types::{
+    epoch_state::EpochState, ledger_info::LedgerInfoWithSignatures,
+    on_chain_config::BlockGasLimitType,
+};
 use fail::fail_point;
 use futures::{
     channel::{mpsc::UnboundedSender, oneshot},
@@ -56,10 +59,13 @@ impl StateComputer for OrderingStateComputer {
     async fn compute(
         &self,
         // The block to be executed.
-        _block: &Block,
+        block: &Block,
         // The parent block id.
-        _parent_block_id: HashValue,
+        parent_block_id: HashValue,
     ) -> Result<StateComputeResult, ExecutionError> {
+        // The block is only executed once ordered, in the meantime its transactions can be
+        // executed speculatively.
+        self.state_computer_for_sync.pre_execute(block, parent_block_id);
         // Return dummy block and bypass the execution phase.
         // This will break the e2e smoke test (for now because
         // no one is actually handling the next phase) if the
@@ -126,7 +132,7 @@ impl StateComputer for OrderingStateComputer {
         _: &EpochState,
         _payload_manager: Arc<PayloadManager>,
         _: Arc<dyn TransactionShuffler>,
-        _: Option<u64>,
+        _: BlockGasLimitType,
         _: Arc<dyn TransactionDeduper>,
     ) {
     }
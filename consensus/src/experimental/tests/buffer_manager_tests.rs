@@ -15,6 +15,7 @@ use crate::{
         signing_phase::SigningPhase,
         tests::test_utils::prepare_executed_blocks_with_ledger_info,
     },
+    liveness::block_size_controller::AdaptiveBlockSizeController,
     metrics_safety_rules::MetricsSafetyRules,
     network::NetworkSender,
     network_interface::{ConsensusMsg, ConsensusNetworkClient, DIRECT_SEND, RPC},
@@ -114,6 +115,7 @@ pub fn prepare_buffer_manager() -> (
         consensus_network_client,
         self_loop_tx,
         validators.clone(),
+        None,
     );
 
     let (msg_tx, msg_rx) =
@@ -142,13 +144,15 @@ pub fn prepare_buffer_manager() -> (
     ) = prepare_phases_and_buffer_manager(
         author,
         mocked_execution_proxy,
-        Arc::new(Mutex::new(safety_rules)),
+        Some(Arc::new(Mutex::new(safety_rules))),
         network,
         msg_rx,
         persisting_proxy,
         block_rx,
         buffer_reset_rx,
         validators.clone(),
+        Arc::new(AdaptiveBlockSizeController::new_disabled()),
+        None,
     );
 
     (
@@ -158,7 +162,7 @@ pub fn prepare_buffer_manager() -> (
         msg_tx,       // channel to pass commit messages into the buffer manager
         self_loop_rx, // channel to receive message from the buffer manager itself
         execution_phase_pipeline,
-        signing_phase_pipeline,
+        signing_phase_pipeline.unwrap(),
         persisting_phase_pipeline,
         hash_val,
         signers,
@@ -9,6 +9,7 @@ use crate::{
         pipeline_phase::{CountedRequest, PipelinePhase},
         tests::phase_tester::PhaseTester,
     },
+    liveness::block_size_controller::AdaptiveBlockSizeController,
     test_utils::{consensus_runtime, RandomComputeResultStateComputer},
 };
 use aptos_consensus_types::{
@@ -25,7 +26,10 @@ use std::sync::Arc;
 pub fn prepare_execution_phase() -> (HashValue, ExecutionPhase) {
     let execution_proxy = Arc::new(RandomComputeResultStateComputer::new());
     let random_hash_value = execution_proxy.get_root_hash();
-    let execution_phase = ExecutionPhase::new(execution_proxy);
+    let execution_phase = ExecutionPhase::new(
+        execution_proxy,
+        Arc::new(AdaptiveBlockSizeController::new_disabled()),
+    );
     (random_hash_value, execution_phase)
 }
 
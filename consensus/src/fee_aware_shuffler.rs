@@ -0,0 +1,128 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+use crate::{
+    conflict_aware_shuffler::ConflictAwareShuffler, transaction_shuffler::TransactionShuffler,
+};
+use aptos_types::transaction::SignedTransaction;
+use std::{cmp::Reverse, collections::HashMap};
+
+/// An implementation of transaction shuffler, which prioritizes transactions with higher gas unit
+/// price, and then spreads apart conflicting transactions via the `ConflictAwareShuffler`.
+///
+/// To keep ordering fair to transactions that were pulled into the block earlier, transactions are
+/// only reordered by gas unit price within consecutive windows of `fairness_window_size`
+/// transactions, i.e. no transaction is moved by more than `fairness_window_size - 1` positions
+/// before conflict shuffling.
+///
+/// The relative ordering of transactions from the same sender is preserved: within a window, a
+/// transaction is prioritized by the lowest gas unit price among itself and the earlier
+/// transactions from the same sender, and the sort is stable.
+pub struct FeeAwareShuffler {
+    fairness_window_size: usize,
+    conflict_aware_shuffler: ConflictAwareShuffler,
+}
+
+impl FeeAwareShuffler {
+    pub fn new(fairness_window_size: usize, conflict_window_size: usize) -> Self {
+        Self {
+            fairness_window_size,
+            conflict_aware_shuffler: ConflictAwareShuffler::new(conflict_window_size),
+        }
+    }
+
+    fn order_by_gas_unit_price(&self, txns: Vec<SignedTransaction>) -> Vec<SignedTransaction> {
+        if self.fairness_window_size <= 1 {
+            return txns;
+        }
+
+        let mut ordered_txns = Vec::with_capacity(txns.len());
+        let mut txns = txns.into_iter().peekable();
+        while txns.peek().is_some() {
+            let mut lowest_price_by_sender = HashMap::new();
+            let mut window: Vec<_> = txns
+                .by_ref()
+                .take(self.fairness_window_size)
+                .map(|txn| {
+                    let price = lowest_price_by_sender
+                        .entry(txn.sender())
+                        .or_insert(u64::MAX);
+                    *price = (*price).min(txn.gas_unit_price());
+                    (*price, txn)
+                })
+                .collect();
+            window.sort_by_key(|(price, _)| Reverse(*price));
+            ordered_txns.extend(window.into_iter().map(|(_, txn)| txn));
+        }
+        ordered_txns
+    }
+}
+
+impl TransactionShuffler for FeeAwareShuffler {
+    fn shuffle(&self, txns: Vec<SignedTransaction>) -> Vec<SignedTransaction> {
+        let txns = self.order_by_gas_unit_price(txns);
+        self.conflict_aware_shuffler.shuffle(txns)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        conflict_aware_shuffler::tests::create_signed_transactions,
+        fee_aware_shuffler::FeeAwareShuffler, transaction_shuffler::TransactionShuffler,
+    };
+    use aptos_types::transaction::{Script, SignedTransaction, TransactionPayload};
+
+    fn script_payload() -> TransactionPayload {
+        TransactionPayload::Script(Script::new(vec![], vec![], vec![]))
+    }
+
+    fn gas_unit_prices(txns: &[SignedTransaction]) -> Vec<u64> {
+        txns.iter().map(|txn| txn.gas_unit_price()).collect()
+    }
+
+    #[test]
+    fn test_order_within_fairness_window() {
+        let txns: Vec<_> = [100, 300, 200, 500, 400, 600, 50]
+            .into_iter()
+            .flat_map(|price| create_signed_transactions(script_payload(), &[price]))
+            .collect();
+        let optimized_txns = FeeAwareShuffler::new(3, 0).shuffle(txns);
+        assert_eq!(
+            gas_unit_prices(&optimized_txns),
+            vec![300, 200, 100, 600, 500, 400, 50]
+        );
+    }
+
+    #[test]
+    fn test_disabled_fairness_window() {
+        let txns: Vec<_> = [100, 300, 200]
+            .into_iter()
+            .flat_map(|price| create_signed_transactions(script_payload(), &[price]))
+            .collect();
+        let optimized_txns = FeeAwareShuffler::new(1, 0).shuffle(txns.clone());
+        assert_eq!(txns, optimized_txns);
+    }
+
+    #[test]
+    // S1_1 (100), S1_2 (500), S2_1 (300)
+    // S1_2 can't be ordered ahead of S1_1, so it is prioritized with price 100:
+    // S2_1, S1_1, S1_2
+    fn test_same_sender_relative_order() {
+        let sender1_txns = create_signed_transactions(script_payload(), &[100, 500]);
+        let sender2_txns = create_signed_transactions(script_payload(), &[300]);
+        let orig_txns: Vec<_> = sender1_txns
+            .iter()
+            .chain(sender2_txns.iter())
+            .cloned()
+            .collect();
+        let optimized_txns = FeeAwareShuffler::new(10, 0).shuffle(orig_txns);
+        assert_eq!(
+            optimized_txns,
+            vec![
+                sender2_txns[0].clone(),
+                sender1_txns[0].clone(),
+                sender1_txns[1].clone(),
+            ]
+        );
+    }
+}
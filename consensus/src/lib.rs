@@ -23,6 +23,9 @@ mod error;
 mod experimental;
 mod liveness;
 mod logging;
+mod message_recorder;
+#[cfg(any(test, feature = "fuzzing"))]
+mod message_replayer;
 mod metrics_safety_rules;
 mod network;
 #[cfg(test)]
@@ -42,10 +45,14 @@ mod twins;
 mod txn_notifier;
 mod util;
 
+mod conflict_aware_shuffler;
+/// Consensus observer: forwards consensus updates to (and executes them on) fullnodes
+pub mod consensus_observer;
 /// AptosBFT implementation
 pub mod consensus_provider;
 /// Required by the telemetry service
 pub mod counters;
+mod fee_aware_shuffler;
 /// AptosNet interface.
 pub mod network_interface;
 mod payload_manager;
@@ -60,6 +67,10 @@ pub use consensusdb::create_checkpoint;
 pub use consensusdb::CONSENSUS_DB_NAME;
 pub use quorum_store::quorum_store_db::QUORUM_STORE_DB_NAME;
 #[cfg(feature = "fuzzing")]
+pub use message_recorder::read_recording;
+#[cfg(feature = "fuzzing")]
+pub use message_replayer::{replay_entries, ConsensusDecision, ReplayReport};
+#[cfg(feature = "fuzzing")]
 pub use round_manager::round_manager_fuzzing;
 
 struct IntGaugeGuard {
@@ -0,0 +1,149 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::counters::{
+    ADAPTIVE_BLOCK_SIZE_COMMIT_LATENCY, ADAPTIVE_BLOCK_SIZE_EXECUTION_LATENCY_PER_TXN,
+    ADAPTIVE_BLOCK_SIZE_MAX_BLOCK_BYTES, ADAPTIVE_BLOCK_SIZE_MAX_BLOCK_TXNS,
+};
+use aptos_config::config::AdaptiveBlockSizeConfig;
+use aptos_infallible::Mutex;
+use aptos_logger::{info, sample, sample::SampleRate};
+use std::time::Duration;
+
+struct ControllerState {
+    // Exponentially weighted moving average of the execution time of a single transaction
+    ewma_execution_per_txn_us: Option<f64>,
+    // Exponentially weighted moving average of the latency from block timestamp to commit
+    ewma_commit_latency_ms: Option<f64>,
+    // Block size limit adjusted (AIMD) based on the observed commit latency
+    commit_based_max_txns: u64,
+}
+
+/// AdaptiveBlockSizeController adapts the number of transactions (and bytes) in a proposed block
+/// to the observed execution and commit latency of the local pipeline:
+/// - ExecutionPhase reports how long blocks took to execute, from which the per-transaction
+///   execution time is derived, bounding the block to what fits into the target execution latency.
+/// - BufferManager reports the latency between block timestamp and its commit. If it exceeds the
+///   target, the limit is reduced multiplicatively, otherwise it is grown additively.
+///
+/// The controller is shared between the execution pipeline and the ProposalGenerator, which
+/// combines its limits with the static limits and the other backoffs.
+pub struct AdaptiveBlockSizeController {
+    config: AdaptiveBlockSizeConfig,
+    max_block_txns: u64,
+    max_block_bytes: u64,
+    state: Mutex<ControllerState>,
+}
+
+impl AdaptiveBlockSizeController {
+    pub fn new(config: AdaptiveBlockSizeConfig, max_block_txns: u64, max_block_bytes: u64) -> Self {
+        Self {
+            config,
+            max_block_txns,
+            max_block_bytes,
+            state: Mutex::new(ControllerState {
+                ewma_execution_per_txn_us: None,
+                ewma_commit_latency_ms: None,
+                commit_based_max_txns: max_block_txns,
+            }),
+        }
+    }
+
+    pub fn new_disabled() -> Self {
+        Self::new(AdaptiveBlockSizeConfig::default(), u64::MAX, u64::MAX)
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.config.enabled
+    }
+
+    fn update_ewma(&self, current: Option<f64>, observation: f64) -> f64 {
+        match current {
+            Some(value) => {
+                self.config.ewma_alpha * observation + (1.0 - self.config.ewma_alpha) * value
+            },
+            None => observation,
+        }
+    }
+
+    /// Called once a block with `num_txns` transactions finished execution.
+    pub fn observe_execution(&self, num_txns: usize, execution_time: Duration) {
+        if !self.is_enabled() || num_txns == 0 {
+            return;
+        }
+        let per_txn_us = execution_time.as_micros() as f64 / num_txns as f64;
+        let mut state = self.state.lock();
+        let ewma = self.update_ewma(state.ewma_execution_per_txn_us, per_txn_us);
+        state.ewma_execution_per_txn_us = Some(ewma);
+        ADAPTIVE_BLOCK_SIZE_EXECUTION_LATENCY_PER_TXN.set(ewma / 1_000_000.0);
+    }
+
+    /// Called once a block is committed, with the latency between its timestamp and the commit.
+    pub fn observe_commit(&self, commit_latency: Duration) {
+        if !self.is_enabled() {
+            return;
+        }
+        let mut state = self.state.lock();
+        let ewma = self.update_ewma(
+            state.ewma_commit_latency_ms,
+            commit_latency.as_millis() as f64,
+        );
+        state.ewma_commit_latency_ms = Some(ewma);
+        ADAPTIVE_BLOCK_SIZE_COMMIT_LATENCY.set(ewma / 1000.0);
+
+        state.commit_based_max_txns = if ewma > self.config.target_commit_latency_ms as f64 {
+            (state.commit_based_max_txns as f64 * self.config.multiplicative_decrease_factor) as u64
+        } else {
+            state
+                .commit_based_max_txns
+                .saturating_add(self.config.additive_increase_txns)
+        }
+        .clamp(self.min_block_txns(), self.max_block_txns);
+    }
+
+    fn min_block_txns(&self) -> u64 {
+        self.config.min_block_txns.min(self.max_block_txns)
+    }
+
+    fn min_block_bytes(&self) -> u64 {
+        self.config.min_block_bytes.min(self.max_block_bytes)
+    }
+
+    /// Returns the (max_block_txns, max_block_bytes) the controller currently allows,
+    /// or None if the controller is disabled.
+    pub fn get_limits(&self) -> Option<(u64, u64)> {
+        if !self.is_enabled() {
+            return None;
+        }
+        let state = self.state.lock();
+        let execution_based_max_txns = state
+            .ewma_execution_per_txn_us
+            .filter(|per_txn_us| *per_txn_us > 0.0)
+            .map_or(self.max_block_txns, |per_txn_us| {
+                (self.config.target_block_execution_latency_ms as f64 * 1000.0 / per_txn_us) as u64
+            });
+        let max_block_txns = execution_based_max_txns
+            .min(state.commit_based_max_txns)
+            .clamp(self.min_block_txns(), self.max_block_txns);
+        // Scale bytes proportionally to the reduction in the number of transactions
+        let max_block_bytes = ((self.max_block_bytes as f64 * max_block_txns as f64
+            / self.max_block_txns as f64) as u64)
+            .clamp(self.min_block_bytes(), self.max_block_bytes);
+
+        ADAPTIVE_BLOCK_SIZE_MAX_BLOCK_TXNS.set(max_block_txns as i64);
+        ADAPTIVE_BLOCK_SIZE_MAX_BLOCK_BYTES.set(max_block_bytes as i64);
+        if max_block_txns < self.max_block_txns {
+            sample!(
+                SampleRate::Duration(Duration::from_secs(10)),
+                info!(
+                    "Adaptive block size limits to {} txns and {} bytes (execution per txn: {:?}us, commit latency: {:?}ms)",
+                    max_block_txns,
+                    max_block_bytes,
+                    state.ewma_execution_per_txn_us,
+                    state.ewma_commit_latency_ms,
+                )
+            );
+        }
+        Some((max_block_txns, max_block_bytes))
+    }
+}
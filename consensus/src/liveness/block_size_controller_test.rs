@@ -0,0 +1,104 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::liveness::block_size_controller::AdaptiveBlockSizeController;
+use aptos_config::config::AdaptiveBlockSizeConfig;
+use std::time::Duration;
+
+const MAX_BLOCK_TXNS: u64 = 4000;
+const MAX_BLOCK_BYTES: u64 = 4 * 1024 * 1024;
+
+fn enabled_controller() -> AdaptiveBlockSizeController {
+    AdaptiveBlockSizeController::new(
+        AdaptiveBlockSizeConfig {
+            enabled: true,
+            target_block_execution_latency_ms: 200,
+            target_commit_latency_ms: 1000,
+            min_block_txns: 100,
+            min_block_bytes: 1024 * 1024,
+            additive_increase_txns: 100,
+            multiplicative_decrease_factor: 0.5,
+            ewma_alpha: 1.0,
+        },
+        MAX_BLOCK_TXNS,
+        MAX_BLOCK_BYTES,
+    )
+}
+
+#[test]
+fn test_disabled_controller() {
+    let controller = AdaptiveBlockSizeController::new_disabled();
+    controller.observe_execution(1000, Duration::from_secs(10));
+    controller.observe_commit(Duration::from_secs(10));
+    assert_eq!(controller.get_limits(), None);
+}
+
+#[test]
+fn test_no_observations() {
+    let controller = enabled_controller();
+    assert_eq!(
+        controller.get_limits(),
+        Some((MAX_BLOCK_TXNS, MAX_BLOCK_BYTES))
+    );
+}
+
+#[test]
+fn test_execution_latency_bounds_block_size() {
+    let controller = enabled_controller();
+    // 100us per txn, 200ms target => 2000 txns
+    controller.observe_execution(1000, Duration::from_millis(100));
+    assert_eq!(controller.get_limits(), Some((2000, MAX_BLOCK_BYTES / 2)));
+
+    // Execution got faster, limit is back to max
+    controller.observe_execution(1000, Duration::from_millis(10));
+    assert_eq!(
+        controller.get_limits(),
+        Some((MAX_BLOCK_TXNS, MAX_BLOCK_BYTES))
+    );
+
+    // Very slow execution is bounded by the configured minimum
+    controller.observe_execution(10, Duration::from_secs(10));
+    assert_eq!(controller.get_limits(), Some((100, 1024 * 1024)));
+
+    // Empty blocks are ignored
+    controller.observe_execution(0, Duration::from_secs(10));
+    assert_eq!(controller.get_limits(), Some((100, 1024 * 1024)));
+}
+
+#[test]
+fn test_commit_latency_aimd() {
+    let controller = enabled_controller();
+    controller.observe_commit(Duration::from_millis(2000));
+    assert_eq!(controller.get_limits().unwrap().0, 2000);
+    controller.observe_commit(Duration::from_millis(2000));
+    assert_eq!(controller.get_limits().unwrap().0, 1000);
+
+    controller.observe_commit(Duration::from_millis(500));
+    assert_eq!(controller.get_limits().unwrap().0, 1100);
+    controller.observe_commit(Duration::from_millis(500));
+    assert_eq!(controller.get_limits().unwrap().0, 1200);
+
+    for _ in 0..10 {
+        controller.observe_commit(Duration::from_millis(5000));
+    }
+    assert_eq!(controller.get_limits(), Some((100, 1024 * 1024)));
+
+    for _ in 0..100 {
+        controller.observe_commit(Duration::from_millis(100));
+    }
+    assert_eq!(
+        controller.get_limits(),
+        Some((MAX_BLOCK_TXNS, MAX_BLOCK_BYTES))
+    );
+}
+
+#[test]
+fn test_limits_combine_execution_and_commit() {
+    let controller = enabled_controller();
+    // execution bound is 2000 txns
+    controller.observe_execution(1000, Duration::from_millis(100));
+    // commit bound is 1000 txns
+    controller.observe_commit(Duration::from_millis(2000));
+    controller.observe_commit(Duration::from_millis(2000));
+    assert_eq!(controller.get_limits().unwrap().0, 1000);
+}
@@ -3,7 +3,8 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use super::{
-    proposer_election::ProposerElection, unequivocal_proposer_election::UnequivocalProposerElection,
+    block_size_controller::AdaptiveBlockSizeController, proposer_election::ProposerElection,
+    unequivocal_proposer_election::UnequivocalProposerElection,
 };
 use crate::{
     block_storage::BlockReader,
@@ -164,6 +165,8 @@ pub struct ProposalGenerator {
 
     pipeline_backpressure_config: PipelineBackpressureConfig,
     chain_health_backoff_config: ChainHealthBackoffConfig,
+    // Adapts block limits to the execution and commit latency observed by the pipeline
+    block_size_controller: Arc<AdaptiveBlockSizeController>,
 
     // Last round that a proposal was generated
     last_round_generated: Round,
@@ -182,6 +185,7 @@ impl ProposalGenerator {
         max_failed_authors_to_store: usize,
         pipeline_backpressure_config: PipelineBackpressureConfig,
         chain_health_backoff_config: ChainHealthBackoffConfig,
+        block_size_controller: Arc<AdaptiveBlockSizeController>,
         quorum_store_enabled: bool,
     ) -> Self {
         Self {
@@ -195,6 +199,7 @@ impl ProposalGenerator {
             max_failed_authors_to_store,
             pipeline_backpressure_config,
             chain_health_backoff_config,
+            block_size_controller,
             last_round_generated: 0,
             quorum_store_enabled,
         }
@@ -378,6 +383,12 @@ impl ProposalGenerator {
             PIPELINE_BACKPRESSURE_ON_PROPOSAL_TRIGGERED.observe(0.0);
         };
 
+        let adaptive_limits = self.block_size_controller.get_limits();
+        if let Some((adaptive_max_block_txns, adaptive_max_block_bytes)) = adaptive_limits {
+            values_max_block_txns.push(adaptive_max_block_txns);
+            values_max_block_bytes.push(adaptive_max_block_bytes);
+        }
+
         let max_block_txns = values_max_block_txns.into_iter().min().unwrap();
         let max_block_bytes = values_max_block_bytes.into_iter().min().unwrap();
         let proposal_delay = values_proposal_delay.into_iter().max().unwrap();
@@ -5,6 +5,7 @@
 use crate::{
     block_storage::BlockReader,
     liveness::{
+        block_size_controller::AdaptiveBlockSizeController,
         proposal_generator::{
             ChainHealthBackoffConfig, PipelineBackpressureConfig, ProposalGenerator,
         },
@@ -41,6 +42,7 @@ async fn test_proposal_generation_empty_tree() {
         10,
         PipelineBackpressureConfig::new_no_backoff(),
         ChainHealthBackoffConfig::new_no_backoff(),
+        Arc::new(AdaptiveBlockSizeController::new_disabled()),
         false,
     );
     let mut proposer_election =
@@ -81,6 +83,7 @@ async fn test_proposal_generation_parent() {
         10,
         PipelineBackpressureConfig::new_no_backoff(),
         ChainHealthBackoffConfig::new_no_backoff(),
+        Arc::new(AdaptiveBlockSizeController::new_disabled()),
         false,
     );
     let mut proposer_election = UnequivocalProposerElection::new(Box::new(RotatingProposer::new(
@@ -153,6 +156,7 @@ async fn test_old_proposal_generation() {
         10,
         PipelineBackpressureConfig::new_no_backoff(),
         ChainHealthBackoffConfig::new_no_backoff(),
+        Arc::new(AdaptiveBlockSizeController::new_disabled()),
         false,
     );
     let mut proposer_election = UnequivocalProposerElection::new(Box::new(RotatingProposer::new(
@@ -190,6 +194,7 @@ async fn test_correct_failed_authors() {
         10,
         PipelineBackpressureConfig::new_no_backoff(),
         ChainHealthBackoffConfig::new_no_backoff(),
+        Arc::new(AdaptiveBlockSizeController::new_disabled()),
         false,
     );
     let mut proposer_election = UnequivocalProposerElection::new(Box::new(RotatingProposer::new(
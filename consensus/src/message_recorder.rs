@@ -0,0 +1,550 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    counters,
+    error::StateSyncError,
+    network_interface::ConsensusMsg,
+    payload_manager::PayloadManager,
+    persistent_liveness_storage::{LedgerRecoveryData, RecoveryData},
+    state_replication::{StateComputer, StateComputerCommitCallBackType},
+    transaction_deduper::TransactionDeduper,
+    transaction_shuffler::TransactionShuffler,
+};
+use anyhow::Context;
+use aptos_config::config::MessageRecorderConfig;
+use aptos_consensus_types::{
+    block::Block,
+    common::{Author, Round},
+    executed_block::ExecutedBlock,
+    quorum_cert::QuorumCert,
+    safety_data::SafetyData,
+    timeout_2chain::TwoChainTimeoutCertificate,
+    vote::Vote,
+};
+use aptos_crypto::HashValue;
+use aptos_executor_types::{Error as ExecutionError, StateComputeResult};
+use aptos_infallible::{duration_since_epoch, Mutex};
+use aptos_logger::prelude::*;
+use aptos_network::protocols::network::Event;
+use aptos_types::{
+    epoch_state::EpochState,
+    ledger_info::LedgerInfoWithSignatures,
+    on_chain_config::{BlockGasLimitType, OnChainConsensusConfig},
+};
+use byteorder::{BigEndian, WriteBytesExt};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    fs::File,
+    io::{BufWriter, Write},
+    iter,
+    path::{Path, PathBuf},
+    sync::{
+        mpsc,
+        mpsc::{SyncSender, TrySendError},
+        Arc,
+    },
+    time::Duration,
+};
+
+const RECORDING_FILE_PREFIX: &str = "consensus-recording-";
+const RECORDING_FILE_EXTENSION: &str = "bcs";
+// The maximum number of entries written before the recording file is flushed
+const MAX_ENTRIES_PER_FLUSH: usize = 100;
+
+/// The state the round manager of an epoch was started with. This is everything (apart from
+/// the consensus key) required to rebuild an identical round manager offline.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct RoundManagerSnapshot {
+    pub epoch_state: EpochState,
+    pub onchain_config: OnChainConsensusConfig,
+    pub quorum_store_enabled: bool,
+    pub safety_data: Option<SafetyData>,
+    // The latest ledger info in storage (i.e., the one used to find the root block)
+    pub storage_ledger: LedgerInfoWithSignatures,
+    pub blocks: Vec<Block>,
+    pub quorum_certs: Vec<QuorumCert>,
+    pub last_vote: Option<Vote>,
+    pub highest_2chain_timeout_cert: Option<TwoChainTimeoutCertificate>,
+}
+
+impl RoundManagerSnapshot {
+    pub fn new(
+        epoch_state: EpochState,
+        onchain_config: OnChainConsensusConfig,
+        quorum_store_enabled: bool,
+        safety_data: Option<SafetyData>,
+        ledger_recovery_data: &LedgerRecoveryData,
+        recovery_data: &RecoveryData,
+    ) -> Self {
+        let (blocks, quorum_certs) = recovery_data.blocks_and_quorum_certs();
+        Self {
+            epoch_state,
+            onchain_config,
+            quorum_store_enabled,
+            safety_data,
+            storage_ledger: ledger_recovery_data.storage_ledger().clone(),
+            // The (virtual) genesis block is regenerated from the storage ledger on recovery
+            blocks: blocks
+                .into_iter()
+                .filter(|block| !block.is_genesis_block())
+                .collect(),
+            quorum_certs,
+            last_vote: recovery_data.last_vote(),
+            highest_2chain_timeout_cert: recovery_data.highest_2chain_timeout_certificate(),
+        }
+    }
+}
+
+/// A single input or output of the round manager
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub enum RecordedEvent {
+    /// A new round manager was started (i.e., a new epoch began or the node restarted)
+    RoundManagerStarted(Box<RoundManagerSnapshot>),
+    /// A message (or rpc request) was received from the given peer (or from ourselves)
+    InboundMessage {
+        author: Author,
+        message: ConsensusMsg,
+    },
+    /// A message (or rpc request) was sent to the given recipients
+    OutboundMessage {
+        recipients: Vec<Author>,
+        message: ConsensusMsg,
+    },
+    /// The round timer fired for the given round
+    LocalTimeout { round: Round },
+    /// The state computer executed the given block
+    BlockExecuted {
+        block_id: HashValue,
+        result: StateComputeResult,
+    },
+    /// The state computer finished committing the given blocks
+    BlocksCommitted {
+        block_ids: Vec<HashValue>,
+        ledger_info: LedgerInfoWithSignatures,
+    },
+    /// The state computer synced to the given target
+    SyncedTo {
+        ledger_info: LedgerInfoWithSignatures,
+    },
+}
+
+impl RecordedEvent {
+    pub fn get_label(&self) -> &'static str {
+        match self {
+            RecordedEvent::RoundManagerStarted(_) => "round_manager_started",
+            RecordedEvent::InboundMessage { .. } => "inbound_message",
+            RecordedEvent::OutboundMessage { .. } => "outbound_message",
+            RecordedEvent::LocalTimeout { .. } => "local_timeout",
+            RecordedEvent::BlockExecuted { .. } => "block_executed",
+            RecordedEvent::BlocksCommitted { .. } => "blocks_committed",
+            RecordedEvent::SyncedTo { .. } => "synced_to",
+        }
+    }
+}
+
+/// A recorded event together with the (wall clock) time it was observed at
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct RecordedEntry {
+    pub timestamp_usecs: u64,
+    pub event: RecordedEvent,
+}
+
+/// The consensus recorder hands all events to a dedicated writer thread, so that
+/// the network and consensus tasks never block on disk IO. If the writer falls
+/// behind, new events are dropped (and counted) instead of queueing up in memory.
+pub struct ConsensusRecorder {
+    entry_tx: Mutex<SyncSender<RecordedEntry>>,
+}
+
+impl ConsensusRecorder {
+    /// Creates a recorder that writes to the configured recording directory
+    pub fn new(config: &MessageRecorderConfig) -> anyhow::Result<Self> {
+        let mut writer = RecordingWriter::new(
+            config.recording_dir(),
+            config.max_file_size_bytes,
+            config.max_num_files,
+        )?;
+        let (entry_tx, entry_rx) = mpsc::sync_channel(config.max_pending_entries);
+        std::thread::Builder::new()
+            .name("consensus-recorder".into())
+            .spawn(move || {
+                while let Ok(entry) = entry_rx.recv() {
+                    // Write all entries that are already pending before flushing the file
+                    let pending_entries = entry_rx.try_iter().take(MAX_ENTRIES_PER_FLUSH - 1);
+                    for entry in iter::once(entry).chain(pending_entries) {
+                        if let Err(error) = writer.write_entry(&entry) {
+                            counters::CONSENSUS_RECORDER_ERRORS.inc();
+                            error!(error = ?error, "Failed to write the consensus recording!");
+                        }
+                    }
+                    if let Err(error) = writer.flush() {
+                        counters::CONSENSUS_RECORDER_ERRORS.inc();
+                        error!(error = ?error, "Failed to flush the consensus recording!");
+                    }
+                }
+            })?;
+        Ok(Self {
+            entry_tx: Mutex::new(entry_tx),
+        })
+    }
+
+    /// Creates a recorder that hands all events to the returned receiver
+    #[cfg(any(test, feature = "fuzzing"))]
+    pub fn new_in_memory() -> (Self, mpsc::Receiver<RecordedEntry>) {
+        let max_pending_entries = MessageRecorderConfig::default().max_pending_entries;
+        let (entry_tx, entry_rx) = mpsc::sync_channel(max_pending_entries);
+        (
+            Self {
+                entry_tx: Mutex::new(entry_tx),
+            },
+            entry_rx,
+        )
+    }
+
+    pub fn record(&self, event: RecordedEvent) {
+        counters::CONSENSUS_RECORDED_EVENTS
+            .with_label_values(&[event.get_label()])
+            .inc();
+        let entry = RecordedEntry {
+            timestamp_usecs: duration_since_epoch().as_micros() as u64,
+            event,
+        };
+        match self.entry_tx.lock().try_send(entry) {
+            Ok(()) => {},
+            Err(TrySendError::Full(_)) => {
+                counters::CONSENSUS_RECORDER_DROPPED_EVENTS.inc();
+                sample!(
+                    SampleRate::Duration(Duration::from_secs(10)),
+                    warn!("The consensus recorder is falling behind, dropping events!")
+                );
+            },
+            Err(TrySendError::Disconnected(_)) => {
+                counters::CONSENSUS_RECORDER_ERRORS.inc();
+                warn!("The consensus recorder has stopped, dropping the event!");
+            },
+        }
+    }
+
+    /// Records the given network event (if it carries a consensus message)
+    pub fn record_network_event(&self, event: &Event<ConsensusMsg>) {
+        match event {
+            Event::Message(author, message) | Event::RpcRequest(author, message, _, _) => self
+                .record(RecordedEvent::InboundMessage {
+                    author: *author,
+                    message: message.clone(),
+                }),
+            _ => {}, // Ignore `NewPeer` and `LostPeer` events
+        }
+    }
+}
+
+/// Appends length-prefixed BCS entries to the current recording file, starting a new file
+/// whenever the current one grows too large and deleting the oldest files.
+struct RecordingWriter {
+    recording_dir: PathBuf,
+    max_file_size_bytes: u64,
+    max_num_files: usize,
+    file_index: u64,
+    file_size_bytes: u64,
+    file: BufWriter<File>,
+}
+
+impl RecordingWriter {
+    fn new(
+        recording_dir: PathBuf,
+        max_file_size_bytes: u64,
+        max_num_files: usize,
+    ) -> anyhow::Result<Self> {
+        fs::create_dir_all(&recording_dir).with_context(|| {
+            format!(
+                "Failed to create the recording directory: {:?}",
+                recording_dir
+            )
+        })?;
+
+        // Never overwrite the recordings of a previous run
+        let file_index = list_recording_files(&recording_dir)?
+            .last()
+            .map_or(0, |(index, _)| index + 1);
+        let file = create_recording_file(&recording_dir, file_index)?;
+        Ok(Self {
+            recording_dir,
+            max_file_size_bytes,
+            max_num_files,
+            file_index,
+            file_size_bytes: 0,
+            file,
+        })
+    }
+
+    fn write_entry(&mut self, entry: &RecordedEntry) -> anyhow::Result<()> {
+        let bytes = bcs::to_bytes(entry)?;
+        self.file.write_u32::<BigEndian>(bytes.len() as u32)?;
+        self.file.write_all(&bytes)?;
+
+        self.file_size_bytes += (bytes.len() + 4) as u64;
+        if self.file_size_bytes >= self.max_file_size_bytes {
+            self.rotate()?;
+        }
+        Ok(())
+    }
+
+    /// Flushes the written entries to the current recording file. The writer thread
+    /// flushes after every batch, as the recording is most useful right before a crash.
+    fn flush(&mut self) -> anyhow::Result<()> {
+        Ok(self.file.flush()?)
+    }
+
+    fn rotate(&mut self) -> anyhow::Result<()> {
+        self.flush()?;
+        self.file_index += 1;
+        self.file = create_recording_file(&self.recording_dir, self.file_index)?;
+        self.file_size_bytes = 0;
+
+        let recording_files = list_recording_files(&self.recording_dir)?;
+        let num_files_to_delete = recording_files.len().saturating_sub(self.max_num_files);
+        for (_, path) in recording_files.into_iter().take(num_files_to_delete) {
+            fs::remove_file(&path)
+                .with_context(|| format!("Failed to remove the recording file: {:?}", path))?;
+        }
+        Ok(())
+    }
+}
+
+fn create_recording_file(recording_dir: &Path, file_index: u64) -> anyhow::Result<BufWriter<File>> {
+    let path = recording_dir.join(format!(
+        "{}{:010}.{}",
+        RECORDING_FILE_PREFIX, file_index, RECORDING_FILE_EXTENSION
+    ));
+    let file = File::create(&path)
+        .with_context(|| format!("Failed to create the recording file: {:?}", path))?;
+    Ok(BufWriter::new(file))
+}
+
+/// Returns all recording files in the directory, sorted by their index
+fn list_recording_files(recording_dir: &Path) -> anyhow::Result<Vec<(u64, PathBuf)>> {
+    let mut recording_files = vec![];
+    for dir_entry in fs::read_dir(recording_dir)? {
+        let path = dir_entry?.path();
+        let index = path
+            .file_name()
+            .and_then(|file_name| file_name.to_str())
+            .and_then(|file_name| file_name.strip_prefix(RECORDING_FILE_PREFIX))
+            .and_then(|file_name| file_name.strip_suffix(RECORDING_FILE_EXTENSION))
+            .and_then(|index| index.trim_end_matches('.').parse::<u64>().ok());
+        if let Some(index) = index {
+            recording_files.push((index, path));
+        }
+    }
+    recording_files.sort();
+    Ok(recording_files)
+}
+
+/// Reads all entries (in the order they were recorded) from the recording directory. A
+/// truncated entry at the end of a file (e.g., because the node crashed) is ignored.
+#[cfg(any(test, feature = "fuzzing"))]
+pub fn read_recording(recording_dir: &Path) -> anyhow::Result<Vec<RecordedEntry>> {
+    use byteorder::ReadBytesExt;
+    use std::io::{BufReader, ErrorKind};
+
+    let mut entries = vec![];
+    for (_, path) in list_recording_files(recording_dir)? {
+        let mut reader = BufReader::new(File::open(&path)?);
+        loop {
+            let length = match reader.read_u32::<BigEndian>() {
+                Ok(length) => length as usize,
+                Err(error) if error.kind() == ErrorKind::UnexpectedEof => break,
+                Err(error) => return Err(error.into()),
+            };
+            let mut bytes = vec![0; length];
+            match std::io::Read::read_exact(&mut reader, &mut bytes) {
+                Ok(()) => {},
+                Err(error) if error.kind() == ErrorKind::UnexpectedEof => break,
+                Err(error) => return Err(error.into()),
+            }
+            entries.push(
+                bcs::from_bytes(&bytes)
+                    .with_context(|| format!("Corrupted recording file: {:?}", path))?,
+            );
+        }
+    }
+    anyhow::ensure!(
+        !entries.is_empty(),
+        "No recorded entries found in {:?}",
+        recording_dir
+    );
+    Ok(entries)
+}
+
+/// Wraps a state computer and records all execution results, commits and syncs
+pub struct RecordingStateComputer {
+    inner: Arc<dyn StateComputer>,
+    recorder: Arc<ConsensusRecorder>,
+}
+
+impl RecordingStateComputer {
+    pub fn new(inner: Arc<dyn StateComputer>, recorder: Arc<ConsensusRecorder>) -> Self {
+        Self { inner, recorder }
+    }
+}
+
+#[async_trait::async_trait]
+impl StateComputer for RecordingStateComputer {
+    async fn compute(
+        &self,
+        block: &Block,
+        parent_block_id: HashValue,
+    ) -> Result<StateComputeResult, ExecutionError> {
+        let result = self.inner.compute(block, parent_block_id).await?;
+        self.recorder.record(RecordedEvent::BlockExecuted {
+            block_id: block.id(),
+            result: result.clone(),
+        });
+        Ok(result)
+    }
+
+    fn pre_execute(&self, block: &Block, parent_block_id: HashValue) {
+        self.inner.pre_execute(block, parent_block_id)
+    }
+
+    async fn commit(
+        &self,
+        blocks: &[Arc<ExecutedBlock>],
+        finality_proof: LedgerInfoWithSignatures,
+        callback: StateComputerCommitCallBackType,
+    ) -> Result<(), ExecutionError> {
+        let recorder = self.recorder.clone();
+        let recording_callback: StateComputerCommitCallBackType =
+            Box::new(move |blocks, ledger_info| {
+                recorder.record(RecordedEvent::BlocksCommitted {
+                    block_ids: blocks.iter().map(|block| block.id()).collect(),
+                    ledger_info: ledger_info.clone(),
+                });
+                callback(blocks, ledger_info)
+            });
+        self.inner
+            .commit(blocks, finality_proof, recording_callback)
+            .await
+    }
+
+    async fn sync_to(&self, target: LedgerInfoWithSignatures) -> Result<(), StateSyncError> {
+        self.inner.sync_to(target.clone()).await?;
+        self.recorder.record(RecordedEvent::SyncedTo {
+            ledger_info: target,
+        });
+        Ok(())
+    }
+
+    fn new_epoch(
+        &self,
+        epoch_state: &EpochState,
+        payload_manager: Arc<PayloadManager>,
+        transaction_shuffler: Arc<dyn TransactionShuffler>,
+        block_gas_limit_type: BlockGasLimitType,
+        transaction_deduper: Arc<dyn TransactionDeduper>,
+    ) {
+        self.inner.new_epoch(
+            epoch_state,
+            payload_manager,
+            transaction_shuffler,
+            block_gas_limit_type,
+            transaction_deduper,
+        )
+    }
+
+    fn end_epoch(&self) {
+        self.inner.end_epoch()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_temppath::TempPath;
+
+    fn create_timeout_entry(round: Round) -> RecordedEntry {
+        RecordedEntry {
+            timestamp_usecs: round,
+            event: RecordedEvent::LocalTimeout { round },
+        }
+    }
+
+    #[test]
+    fn test_recording_rotation() {
+        let recording_dir = TempPath::new();
+        let entry_size = bcs::to_bytes(&create_timeout_entry(0)).unwrap().len() as u64 + 4;
+
+        // Create a writer that fits two entries per file and keeps three files
+        let mut writer =
+            RecordingWriter::new(recording_dir.path().to_path_buf(), 2 * entry_size, 3).unwrap();
+        for round in 0..10 {
+            writer.write_entry(&create_timeout_entry(round)).unwrap();
+        }
+
+        // Verify only the newest files were kept (the last one is still empty)
+        let recording_files = list_recording_files(recording_dir.path()).unwrap();
+        let indices: Vec<_> = recording_files.iter().map(|(index, _)| *index).collect();
+        assert_eq!(indices, vec![3, 4, 5]);
+
+        // Verify the remaining entries are read back in order
+        let rounds: Vec<_> = read_recording(recording_dir.path())
+            .unwrap()
+            .into_iter()
+            .map(|entry| match entry.event {
+                RecordedEvent::LocalTimeout { round } => round,
+                event => panic!("Unexpected recorded event: {:?}", event),
+            })
+            .collect();
+        assert_eq!(rounds, vec![6, 7, 8, 9]);
+
+        // Verify a new writer does not overwrite the existing files
+        let writer =
+            RecordingWriter::new(recording_dir.path().to_path_buf(), 2 * entry_size, 3).unwrap();
+        assert_eq!(writer.file_index, 6);
+    }
+
+    #[test]
+    fn test_recording_flush() {
+        let recording_dir = TempPath::new();
+        let mut writer =
+            RecordingWriter::new(recording_dir.path().to_path_buf(), u64::MAX, 3).unwrap();
+
+        // Verify the entries are buffered until the writer is flushed
+        for round in 0..3 {
+            writer.write_entry(&create_timeout_entry(round)).unwrap();
+        }
+        assert!(read_recording(recording_dir.path()).is_err());
+        writer.flush().unwrap();
+        assert_eq!(read_recording(recording_dir.path()).unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_recorder_drops_events_when_full() {
+        let (entry_tx, entry_rx) = mpsc::sync_channel(2);
+        let recorder = ConsensusRecorder {
+            entry_tx: Mutex::new(entry_tx),
+        };
+
+        // Record more events than the channel can hold
+        let num_dropped_events = counters::CONSENSUS_RECORDER_DROPPED_EVENTS.get();
+        for round in 0..5 {
+            recorder.record(RecordedEvent::LocalTimeout { round });
+        }
+
+        // Verify the newest events were dropped (and counted)
+        let rounds: Vec<_> = entry_rx
+            .try_iter()
+            .map(|entry| match entry.event {
+                RecordedEvent::LocalTimeout { round } => round,
+                event => panic!("Unexpected recorded event: {:?}", event),
+            })
+            .collect();
+        assert_eq!(rounds, vec![0, 1]);
+        assert_eq!(
+            counters::CONSENSUS_RECORDER_DROPPED_EVENTS.get() - num_dropped_events,
+            3
+        );
+    }
+}
@@ -0,0 +1,708 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    block_storage::BlockStore,
+    error::StateSyncError,
+    liveness::{
+        block_size_controller::AdaptiveBlockSizeController,
+        proposal_generator::{
+            ChainHealthBackoffConfig, PipelineBackpressureConfig, ProposalGenerator,
+        },
+        round_proposer_election::RoundProposer,
+        round_state::{ExponentialTimeInterval, RoundState},
+    },
+    message_recorder::{ConsensusRecorder, RecordedEntry, RecordedEvent, RoundManagerSnapshot},
+    metrics_safety_rules::MetricsSafetyRules,
+    network::NetworkSender,
+    network_interface::{ConsensusMsg, ConsensusNetworkClient, DIRECT_SEND, RPC},
+    payload_manager::PayloadManager,
+    persistent_liveness_storage::RecoveryData,
+    round_manager::{RoundManager, UnverifiedEvent, VerifiedEvent},
+    state_replication::{StateComputer, StateComputerCommitCallBackType},
+    test_utils::{MockPayloadManager, MockSharedStorage, MockStorage},
+    transaction_deduper::TransactionDeduper,
+    transaction_shuffler::TransactionShuffler,
+    util::{mock_time_service::SimulatedTimeService, time_service::TimeService},
+};
+use anyhow::Context;
+use aptos_channels::{aptos_channel, message_queues::QueueStyle};
+use aptos_config::{config::ConsensusConfig, network_id::NetworkId};
+use aptos_consensus_types::{
+    block::Block,
+    common::{Author, Round},
+    executed_block::ExecutedBlock,
+    safety_data::SafetyData,
+};
+use aptos_crypto::HashValue;
+use aptos_executor_types::{Error as ExecutionError, StateComputeResult};
+use aptos_infallible::Mutex;
+use aptos_logger::prelude::*;
+use aptos_network::{
+    application::{interface::NetworkClient, storage::PeersAndMetadata},
+    peer_manager::{ConnectionRequestSender, PeerManagerRequestSender},
+    protocols::{
+        network,
+        network::{Event, NewNetworkSender},
+    },
+};
+use aptos_safety_rules::{PersistentSafetyStorage, SafetyRules, TSafetyRules};
+use aptos_secure_storage::{InMemoryStorage, Storage};
+use aptos_types::{
+    aggregate_signature::AggregateSignature,
+    block_info::BlockInfo,
+    epoch_change::EpochChangeProof,
+    epoch_state::EpochState,
+    ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
+    on_chain_config::BlockGasLimitType,
+    validator_signer::ValidatorSigner,
+    waypoint::Waypoint,
+};
+use futures::{FutureExt, StreamExt};
+use maplit::hashmap;
+use std::{
+    collections::HashMap,
+    mem::Discriminant,
+    sync::{mpsc, Arc},
+    time::Duration,
+};
+use tokio::runtime::Runtime;
+
+/// A decision taken by the round manager that is visible to the other validators. Proposals
+/// and votes are identified by their parent (and not by their own id) because the replay
+/// cannot reproduce the payloads pulled from the mempool or quorum store.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ConsensusDecision {
+    Proposal {
+        epoch: u64,
+        round: Round,
+        parent_id: HashValue,
+    },
+    Vote {
+        epoch: u64,
+        round: Round,
+        parent_id: HashValue,
+        timeout: bool,
+    },
+}
+
+impl ConsensusDecision {
+    fn from_message(message: &ConsensusMsg) -> Option<Self> {
+        match message {
+            ConsensusMsg::ProposalMsg(proposal_msg) => Some(ConsensusDecision::Proposal {
+                epoch: proposal_msg.epoch(),
+                round: proposal_msg.proposal().round(),
+                parent_id: proposal_msg.proposal().parent_id(),
+            }),
+            ConsensusMsg::VoteMsg(vote_msg) => {
+                let vote_data = vote_msg.vote().vote_data();
+                Some(ConsensusDecision::Vote {
+                    epoch: vote_msg.epoch(),
+                    round: vote_data.proposed().round(),
+                    parent_id: vote_data.parent().id(),
+                    timeout: vote_msg.vote().is_timeout(),
+                })
+            },
+            _ => None,
+        }
+    }
+}
+
+/// The decisions found in the recording, together with the ones taken by the replayed node
+#[derive(Debug, Default)]
+pub struct ReplayReport {
+    expected: Vec<ConsensusDecision>,
+    actual: Vec<ConsensusDecision>,
+}
+
+impl ReplayReport {
+    pub fn expected(&self) -> &[ConsensusDecision] {
+        &self.expected
+    }
+
+    pub fn actual(&self) -> &[ConsensusDecision] {
+        &self.actual
+    }
+
+    /// Returns the index of the first decision that differs between the recording and the
+    /// replay (or None if the replay reproduced the recording exactly).
+    pub fn first_divergence(&self) -> Option<usize> {
+        let num_common_decisions = self
+            .expected
+            .iter()
+            .zip(self.actual.iter())
+            .take_while(|(expected, actual)| expected == actual)
+            .count();
+        if num_common_decisions == self.expected.len() && num_common_decisions == self.actual.len()
+        {
+            None
+        } else {
+            Some(num_common_decisions)
+        }
+    }
+}
+
+/// Feeds the given recorded entries into freshly built round managers (one per recorded
+/// round manager start) and collects the decisions they take. The signer must hold the
+/// consensus key of the validator that produced the recording (the key is never recorded).
+///
+/// The `consensus-replayer` binary replays a recording directory (it requires the `fuzzing`
+/// feature, as the replay relies on the consensus test utilities).
+///
+/// Note: only the round manager is replayed. Rpc responses (e.g., block retrievals) are not
+/// served, and the leader election is reconstructed from the recorded proposals.
+pub fn replay_entries(
+    entries: Vec<RecordedEntry>,
+    signer: ValidatorSigner,
+    config: ConsensusConfig,
+) -> anyhow::Result<ReplayReport> {
+    let runtime = Runtime::new().context("Failed to create the replay runtime")?;
+    runtime.block_on(replay(entries, signer, config))
+}
+
+async fn replay(
+    entries: Vec<RecordedEntry>,
+    signer: ValidatorSigner,
+    config: ConsensusConfig,
+) -> anyhow::Result<ReplayReport> {
+    let first_start = entries
+        .iter()
+        .position(|entry| matches!(entry.event, RecordedEvent::RoundManagerStarted(_)))
+        .context("The recording does not contain a round manager start!")?;
+
+    let proposers = recorded_proposers(&entries);
+    let execution_results = Arc::new(recorded_execution_results(&entries));
+    let time_service = Arc::new(SimulatedTimeService::new());
+
+    let mut report = ReplayReport::default();
+    let mut node: Option<ReplayNode> = None;
+    for entry in entries.into_iter().skip(first_start) {
+        advance_time(&time_service, entry.timestamp_usecs).await;
+        match entry.event {
+            RecordedEvent::RoundManagerStarted(snapshot) => {
+                let new_node = ReplayNode::new(
+                    *snapshot,
+                    signer.clone(),
+                    &proposers,
+                    &config,
+                    time_service.clone(),
+                    execution_results.clone(),
+                )
+                .await?;
+                node = Some(new_node);
+            },
+            RecordedEvent::OutboundMessage { message, .. } => {
+                report
+                    .expected
+                    .extend(ConsensusDecision::from_message(&message));
+            },
+            event => {
+                if let Some(node) = node.as_mut() {
+                    node.process_event(event).await;
+                }
+            },
+        }
+        if let Some(node) = node.as_mut() {
+            node.process_delayed_proposals().await;
+            // The recording already contains the messages we sent to ourselves (in the order
+            // they were originally processed), so the replayed ones are dropped.
+            node.take_self_messages();
+            report.actual.extend(
+                node.take_outbound_messages()
+                    .iter()
+                    .filter_map(ConsensusDecision::from_message),
+            );
+        }
+    }
+    Ok(report)
+}
+
+/// Moves the simulated clock forward to the given (recorded) time
+async fn advance_time(time_service: &SimulatedTimeService, timestamp_usecs: u64) {
+    let target = Duration::from_micros(timestamp_usecs);
+    let now = time_service.get_current_timestamp();
+    if target > now {
+        time_service.sleep(target - now).await;
+    }
+}
+
+/// Returns the proposer of every recorded (epoch, round)
+fn recorded_proposers(entries: &[RecordedEntry]) -> HashMap<(u64, Round), Author> {
+    let mut proposers = HashMap::new();
+    for entry in entries {
+        let message = match &entry.event {
+            RecordedEvent::InboundMessage { message, .. } => message,
+            RecordedEvent::OutboundMessage { message, .. } => message,
+            _ => continue,
+        };
+        if let ConsensusMsg::ProposalMsg(proposal_msg) = message {
+            proposers.insert(
+                (proposal_msg.epoch(), proposal_msg.proposal().round()),
+                proposal_msg.proposer(),
+            );
+        }
+    }
+    proposers
+}
+
+fn recorded_execution_results(entries: &[RecordedEntry]) -> HashMap<HashValue, StateComputeResult> {
+    entries
+        .iter()
+        .filter_map(|entry| match &entry.event {
+            RecordedEvent::BlockExecuted { block_id, result } => Some((*block_id, result.clone())),
+            _ => None,
+        })
+        .collect()
+}
+
+/// A state computer that returns the recorded execution results and commits to mock storage
+struct ReplayStateComputer {
+    storage: Arc<MockStorage>,
+    execution_results: Arc<HashMap<HashValue, StateComputeResult>>,
+}
+
+#[async_trait::async_trait]
+impl StateComputer for ReplayStateComputer {
+    async fn compute(
+        &self,
+        block: &Block,
+        _parent_block_id: HashValue,
+    ) -> Result<StateComputeResult, ExecutionError> {
+        Ok(self
+            .execution_results
+            .get(&block.id())
+            .cloned()
+            .unwrap_or_else(StateComputeResult::new_dummy))
+    }
+
+    async fn commit(
+        &self,
+        blocks: &[Arc<ExecutedBlock>],
+        finality_proof: LedgerInfoWithSignatures,
+        callback: StateComputerCommitCallBackType,
+    ) -> Result<(), ExecutionError> {
+        self.storage
+            .commit_to_storage(finality_proof.ledger_info().clone());
+        callback(blocks, finality_proof);
+        Ok(())
+    }
+
+    async fn sync_to(&self, target: LedgerInfoWithSignatures) -> Result<(), StateSyncError> {
+        self.storage.commit_to_storage(target.ledger_info().clone());
+        Ok(())
+    }
+
+    fn new_epoch(
+        &self,
+        _: &EpochState,
+        _: Arc<PayloadManager>,
+        _: Arc<dyn TransactionShuffler>,
+        _: BlockGasLimitType,
+        _: Arc<dyn TransactionDeduper>,
+    ) {
+    }
+
+    fn end_epoch(&self) {}
+}
+
+/// A round manager rebuilt from a snapshot, together with the channels it talks to
+struct ReplayNode {
+    author: Author,
+    epoch_state: EpochState,
+    quorum_store_enabled: bool,
+    max_num_batches: usize,
+    round_manager: RoundManager,
+    round_manager_rx:
+        aptos_channel::Receiver<(Author, Discriminant<VerifiedEvent>), (Author, VerifiedEvent)>,
+    self_receiver: aptos_channels::Receiver<Event<ConsensusMsg>>,
+    outbound_rx: mpsc::Receiver<RecordedEntry>,
+}
+
+impl ReplayNode {
+    async fn new(
+        snapshot: RoundManagerSnapshot,
+        signer: ValidatorSigner,
+        proposers: &HashMap<(u64, Round), Author>,
+        config: &ConsensusConfig,
+        time_service: Arc<SimulatedTimeService>,
+        execution_results: Arc<HashMap<HashValue, StateComputeResult>>,
+    ) -> anyhow::Result<Self> {
+        let author = signer.author();
+        let epoch_state = snapshot.epoch_state.clone();
+
+        // Rebuild the consensus db
+        let shared_storage = Arc::new(MockSharedStorage::new((&epoch_state.verifier).into()));
+        for block in snapshot.blocks {
+            shared_storage.block.lock().insert(block.id(), block);
+        }
+        for quorum_cert in snapshot.quorum_certs {
+            shared_storage
+                .qc
+                .lock()
+                .insert(quorum_cert.certified_block().id(), quorum_cert);
+        }
+        *shared_storage.last_vote.lock() = snapshot.last_vote;
+        *shared_storage.highest_2chain_timeout_certificate.lock() =
+            snapshot.highest_2chain_timeout_cert;
+        let storage = Arc::new(MockStorage::new_with_ledger_info(
+            shared_storage,
+            snapshot.storage_ledger.ledger_info().clone(),
+        ));
+        let recovery_data: RecoveryData = storage
+            .try_start()
+            .context("Failed to recover the block tree from the snapshot")?;
+        let last_vote = recovery_data.last_vote();
+
+        let safety_rules = create_safety_rules(&signer, &epoch_state, snapshot.safety_data)?;
+
+        let (network_reqs_tx, _) = aptos_channel::new(QueueStyle::FIFO, 8, None);
+        let (connection_reqs_tx, _) = aptos_channel::new(QueueStyle::FIFO, 8, None);
+        let network_sender = network::NetworkSender::new(
+            PeerManagerRequestSender::new(network_reqs_tx),
+            ConnectionRequestSender::new(connection_reqs_tx),
+        );
+        let network_client = NetworkClient::new(
+            DIRECT_SEND.into(),
+            RPC.into(),
+            hashmap! {NetworkId::Validator => network_sender},
+            PeersAndMetadata::new(&[NetworkId::Validator]),
+        );
+        let (self_sender, self_receiver) = aptos_channels::new_test(1_024);
+        let (recorder, outbound_rx) = ConsensusRecorder::new_in_memory();
+        let network = NetworkSender::new(
+            author,
+            ConsensusNetworkClient::new(network_client),
+            self_sender,
+            epoch_state.verifier.clone(),
+            Some(Arc::new(recorder)),
+        );
+
+        let state_computer = Arc::new(ReplayStateComputer {
+            storage: storage.clone(),
+            execution_results,
+        });
+        let block_store = Arc::new(BlockStore::new(
+            storage.clone(),
+            recovery_data,
+            state_computer,
+            config.max_pruned_blocks_in_mem,
+            time_service.clone(),
+            config.vote_back_pressure_limit,
+            Arc::from(PayloadManager::DirectMempool),
+        ));
+
+        // Back pressure depends on the (unrecorded) execution timings, so it is disabled
+        let proposal_generator = ProposalGenerator::new(
+            author,
+            block_store.clone(),
+            Arc::new(MockPayloadManager::new(None)),
+            time_service.clone(),
+            Duration::from_millis(config.quorum_store_poll_time_ms),
+            config.max_sending_block_txns(snapshot.quorum_store_enabled),
+            config.max_sending_block_bytes(snapshot.quorum_store_enabled),
+            snapshot.onchain_config.max_failed_authors_to_store(),
+            PipelineBackpressureConfig::new_no_backoff(),
+            ChainHealthBackoffConfig::new_no_backoff(),
+            Arc::new(AdaptiveBlockSizeController::new_disabled()),
+            snapshot.quorum_store_enabled,
+        );
+
+        // Timeouts are replayed from the recording, so the round timer never fires on its own
+        let time_interval = Box::new(ExponentialTimeInterval::new(
+            Duration::from_millis(config.round_initial_timeout_ms),
+            config.round_timeout_backoff_exponent_base,
+            config.round_timeout_backoff_max_exponent,
+        ));
+        let (timeout_sender, _) = aptos_channels::new_test(1_024);
+        let round_state = RoundState::new(time_interval, time_service, timeout_sender);
+
+        let epoch_proposers = proposers
+            .iter()
+            .filter(|((epoch, _), _)| *epoch == epoch_state.epoch)
+            .map(|((_, round), proposer)| (*round, *proposer))
+            .collect();
+        let default_proposer = epoch_state
+            .verifier
+            .get_ordered_account_addresses_iter()
+            .find(|validator| *validator != author)
+            .unwrap_or(author);
+        let proposer_election = Box::new(RoundProposer::new(epoch_proposers, default_proposer));
+
+        let (round_manager_tx, round_manager_rx) = aptos_channel::new(QueueStyle::LIFO, 1, None);
+        let mut round_manager = RoundManager::new(
+            epoch_state.clone(),
+            block_store,
+            round_state,
+            proposer_election,
+            proposal_generator,
+            Arc::new(Mutex::new(MetricsSafetyRules::new(
+                Box::new(safety_rules),
+                storage.clone(),
+            ))),
+            network,
+            storage,
+            snapshot.onchain_config,
+            round_manager_tx,
+            config.clone(),
+        );
+        round_manager.init(last_vote).await;
+
+        Ok(Self {
+            author,
+            epoch_state,
+            quorum_store_enabled: snapshot.quorum_store_enabled,
+            max_num_batches: config.quorum_store.receiver_max_num_batches,
+            round_manager,
+            round_manager_rx,
+            self_receiver,
+            outbound_rx,
+        })
+    }
+
+    async fn process_event(&mut self, event: RecordedEvent) {
+        let result = match event {
+            RecordedEvent::InboundMessage { author, message } => {
+                self.process_message(author, message).await
+            },
+            RecordedEvent::LocalTimeout { round } => {
+                self.round_manager.process_local_timeout(round).await
+            },
+            // Execution results are served by the state computer and commits happen as part of
+            // processing the messages.
+            _ => Ok(()),
+        };
+        if let Err(error) = result {
+            warn!(error = ?error, "[ConsensusReplayer] Failed to process the recorded event");
+        }
+    }
+
+    async fn process_message(
+        &mut self,
+        author: Author,
+        message: ConsensusMsg,
+    ) -> anyhow::Result<()> {
+        if !matches!(
+            message,
+            ConsensusMsg::ProposalMsg(_) | ConsensusMsg::VoteMsg(_) | ConsensusMsg::SyncInfo(_)
+        ) {
+            return Ok(());
+        }
+        let verified_event = UnverifiedEvent::from(message).verify(
+            author,
+            &self.epoch_state.verifier,
+            self.quorum_store_enabled,
+            author == self.author,
+            self.max_num_batches,
+        )?;
+        match verified_event {
+            VerifiedEvent::ProposalMsg(proposal_msg) => {
+                self.round_manager.process_proposal_msg(*proposal_msg).await
+            },
+            VerifiedEvent::VoteMsg(vote_msg) => {
+                self.round_manager.process_vote_msg(*vote_msg).await
+            },
+            VerifiedEvent::UnverifiedSyncInfo(sync_info) => {
+                self.round_manager
+                    .process_sync_info_msg(*sync_info, author)
+                    .await
+            },
+            _ => Ok(()),
+        }
+    }
+
+    async fn process_delayed_proposals(&mut self) {
+        while let Some(Some((_, event))) = self.round_manager_rx.next().now_or_never() {
+            if let VerifiedEvent::VerifiedProposalMsg(proposal) = event {
+                if let Err(error) = self
+                    .round_manager
+                    .process_delayed_proposal_msg(*proposal)
+                    .await
+                {
+                    warn!(error = ?error, "[ConsensusReplayer] Failed to process delayed proposal");
+                }
+            }
+        }
+    }
+
+    fn take_self_messages(&mut self) -> Vec<Event<ConsensusMsg>> {
+        let mut messages = vec![];
+        while let Some(Some(message)) = self.self_receiver.next().now_or_never() {
+            messages.push(message);
+        }
+        messages
+    }
+
+    fn take_outbound_messages(&mut self) -> Vec<ConsensusMsg> {
+        self.outbound_rx
+            .try_iter()
+            .filter_map(|entry| match entry.event {
+                RecordedEvent::OutboundMessage { message, .. } => Some(message),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+/// Creates safety rules for the given epoch, starting from the recorded safety data
+fn create_safety_rules(
+    signer: &ValidatorSigner,
+    epoch_state: &EpochState,
+    safety_data: Option<SafetyData>,
+) -> anyhow::Result<SafetyRules> {
+    // A ledger info that ends the previous epoch and carries the replayed epoch state
+    let ledger_info = LedgerInfo::new(
+        BlockInfo::new(
+            epoch_state.epoch.saturating_sub(1),
+            0,
+            HashValue::zero(),
+            HashValue::zero(),
+            0,
+            0,
+            Some(epoch_state.clone()),
+        ),
+        HashValue::zero(),
+    );
+    let waypoint = Waypoint::new_epoch_boundary(&ledger_info)?;
+    let mut safety_storage = PersistentSafetyStorage::initialize(
+        Storage::from(InMemoryStorage::new()),
+        signer.author(),
+        signer.private_key().clone(),
+        waypoint,
+        true,
+    );
+    if let Some(safety_data) = safety_data {
+        safety_storage.set_safety_data(safety_data)?;
+    }
+
+    let mut safety_rules = SafetyRules::new(safety_storage);
+    let proof = EpochChangeProof::new(
+        vec![LedgerInfoWithSignatures::new(
+            ledger_info,
+            AggregateSignature::empty(),
+        )],
+        false,
+    );
+    safety_rules.initialize(&proof)?;
+    Ok(safety_rules)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_types::{
+        on_chain_config::OnChainConsensusConfig, validator_verifier::ValidatorVerifier,
+    };
+
+    /// Runs a single validator network for the given number of steps and returns everything
+    /// that would have been recorded.
+    async fn record_session(
+        snapshot: RoundManagerSnapshot,
+        signer: ValidatorSigner,
+        num_steps: u64,
+    ) -> Vec<RecordedEntry> {
+        // Blocks must be proposed after the (genesis) parent, so the clock doesn't start at zero
+        let step_usecs = 1_000_000;
+        let time_service = Arc::new(SimulatedTimeService::new());
+        advance_time(&time_service, step_usecs).await;
+        let mut entries = vec![RecordedEntry {
+            timestamp_usecs: step_usecs,
+            event: RecordedEvent::RoundManagerStarted(Box::new(snapshot.clone())),
+        }];
+        let mut node = ReplayNode::new(
+            snapshot,
+            signer,
+            &HashMap::new(),
+            &ConsensusConfig::default(),
+            time_service.clone(),
+            Arc::new(HashMap::new()),
+        )
+        .await
+        .unwrap();
+
+        let mut pending_messages = vec![];
+        for step in 1..=num_steps {
+            let timestamp_usecs = step * step_usecs;
+            advance_time(&time_service, timestamp_usecs).await;
+            node.process_delayed_proposals().await;
+            record_outbound_messages(&mut node, &mut entries, timestamp_usecs);
+            pending_messages.extend(node.take_self_messages());
+            if pending_messages.is_empty() {
+                break;
+            }
+            if let Event::Message(author, message) = pending_messages.remove(0) {
+                let event = RecordedEvent::InboundMessage { author, message };
+                entries.push(RecordedEntry {
+                    timestamp_usecs,
+                    event: event.clone(),
+                });
+                node.process_event(event).await;
+            }
+        }
+        node.process_delayed_proposals().await;
+        record_outbound_messages(&mut node, &mut entries, (num_steps + 1) * step_usecs);
+        entries
+    }
+
+    fn record_outbound_messages(
+        node: &mut ReplayNode,
+        entries: &mut Vec<RecordedEntry>,
+        timestamp_usecs: u64,
+    ) {
+        for message in node.take_outbound_messages() {
+            entries.push(RecordedEntry {
+                timestamp_usecs,
+                event: RecordedEvent::OutboundMessage {
+                    recipients: vec![node.author],
+                    message,
+                },
+            });
+        }
+    }
+
+    #[test]
+    fn test_replay_reproduces_decisions() {
+        let signer = ValidatorSigner::from_int(1);
+        let validator = ValidatorVerifier::new_single(signer.author(), signer.public_key());
+        let (recovery_data, storage) = MockStorage::start_for_testing((&validator).into());
+        let snapshot = RoundManagerSnapshot::new(
+            EpochState {
+                epoch: 1,
+                verifier: storage.get_validator_set().into(),
+            },
+            OnChainConsensusConfig::default(),
+            false,
+            None,
+            &storage.get_ledger_recovery_data(),
+            &recovery_data,
+        );
+
+        let runtime = Runtime::new().unwrap();
+        let entries = runtime.block_on(record_session(snapshot, signer.clone(), 20));
+        drop(runtime);
+
+        let report = replay_entries(entries, signer, ConsensusConfig::default()).unwrap();
+        assert!(report.expected().len() > 4);
+        assert_eq!(report.actual(), report.expected());
+        assert_eq!(report.first_divergence(), None);
+    }
+
+    #[test]
+    fn test_first_divergence() {
+        let decision = |round| ConsensusDecision::Proposal {
+            epoch: 1,
+            round,
+            parent_id: HashValue::zero(),
+        };
+        let report = ReplayReport {
+            expected: vec![decision(1), decision(2), decision(3)],
+            actual: vec![decision(1), decision(3)],
+        };
+        assert_eq!(report.first_divergence(), Some(1));
+
+        let report = ReplayReport {
+            expected: vec![decision(1), decision(2)],
+            actual: vec![decision(1), decision(2)],
+        };
+        assert_eq!(report.first_divergence(), None);
+    }
+}
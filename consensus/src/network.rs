@@ -7,6 +7,7 @@ use crate::{
     counters,
     dag::DAGNetworkMessage,
     logging::LogEvent,
+    message_recorder::{ConsensusRecorder, RecordedEvent},
     monitor,
     network_interface::{ConsensusMsg, ConsensusNetworkClient},
     quorum_store::types::{Batch, BatchMsg, BatchRequest},
@@ -43,6 +44,7 @@ use futures::{
 use serde::{de::DeserializeOwned, Serialize};
 use std::{
     mem::{discriminant, Discriminant},
+    sync::Arc,
     time::Duration,
 };
 
@@ -148,6 +150,8 @@ pub struct NetworkSender {
     // Note that we do not support self rpc requests as it might cause infinite recursive calls.
     self_sender: aptos_channels::Sender<Event<ConsensusMsg>>,
     validators: ValidatorVerifier,
+    // Records all outgoing messages (if the message recorder is enabled)
+    recorder: Option<Arc<ConsensusRecorder>>,
 }
 
 impl NetworkSender {
@@ -156,12 +160,23 @@ impl NetworkSender {
         consensus_network_client: ConsensusNetworkClient<NetworkClient<ConsensusMsg>>,
         self_sender: aptos_channels::Sender<Event<ConsensusMsg>>,
         validators: ValidatorVerifier,
+        recorder: Option<Arc<ConsensusRecorder>>,
     ) -> Self {
         NetworkSender {
             author,
             consensus_network_client,
             self_sender,
             validators,
+            recorder,
+        }
+    }
+
+    fn record_outbound_message(&self, msg: &ConsensusMsg, recipients: &[Author]) {
+        if let Some(recorder) = &self.recorder {
+            recorder.record(RecordedEvent::OutboundMessage {
+                recipients: recipients.to_vec(),
+                message: msg.clone(),
+            });
         }
     }
 
@@ -182,6 +197,7 @@ impl NetworkSender {
 
         ensure!(from != self.author, "Retrieve block from self");
         let msg = ConsensusMsg::BlockRetrievalRequest(Box::new(retrieval_request.clone()));
+        self.record_outbound_message(&msg, &[from]);
         counters::CONSENSUS_SENT_MSGS
             .with_label_values(&[msg.name()])
             .inc();
@@ -216,6 +232,14 @@ impl NetworkSender {
     /// out.
     async fn broadcast(&mut self, msg: ConsensusMsg) {
         fail_point!("consensus::send::any", |_| ());
+        if self.recorder.is_some() {
+            let recipients: Vec<_> = self
+                .validators
+                .get_ordered_account_addresses_iter()
+                .collect();
+            self.record_outbound_message(&msg, &recipients);
+        }
+
         // Directly send the message to ourself without going through network.
         let self_msg = Event::Message(self.author, msg.clone());
         if let Err(err) = self.self_sender.send(self_msg).await {
@@ -246,6 +270,7 @@ impl NetworkSender {
     /// Tries to send msg to given recipients.
     async fn send(&self, msg: ConsensusMsg, recipients: Vec<Author>) {
         fail_point!("consensus::send::any", |_| ());
+        self.record_outbound_message(&msg, &recipients);
         let network_sender = self.consensus_network_client.clone();
         let mut self_sender = self.self_sender.clone();
         for peer in recipients {
@@ -424,6 +449,8 @@ pub struct NetworkTask {
     >,
     rpc_tx: aptos_channel::Sender<AccountAddress, (AccountAddress, IncomingRpcRequest)>,
     all_events: Box<dyn Stream<Item = Event<ConsensusMsg>> + Send + Unpin>,
+    // Records all incoming messages (if the message recorder is enabled)
+    recorder: Option<Arc<ConsensusRecorder>>,
 }
 
 impl NetworkTask {
@@ -431,6 +458,7 @@ impl NetworkTask {
     pub fn new(
         network_service_events: NetworkServiceEvents<ConsensusMsg>,
         self_receiver: aptos_channels::Receiver<Event<ConsensusMsg>>,
+        recorder: Option<Arc<ConsensusRecorder>>,
     ) -> (NetworkTask, NetworkReceivers) {
         let (consensus_messages_tx, consensus_messages) = aptos_channel::new(
             QueueStyle::FIFO,
@@ -471,6 +499,7 @@ impl NetworkTask {
                 quorum_store_messages_tx,
                 rpc_tx,
                 all_events,
+                recorder,
             },
             NetworkReceivers {
                 consensus_messages,
@@ -499,6 +528,9 @@ impl NetworkTask {
 
     pub async fn start(mut self) {
         while let Some(message) = self.all_events.next().await {
+            if let Some(recorder) = &self.recorder {
+                recorder.record_network_event(&message);
+            }
             monitor!("network_main_loop", match message {
                 Event::Message(peer_id, msg) => {
                     counters::CONSENSUS_RECEIVED_MSGS
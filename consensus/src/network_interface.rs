@@ -91,6 +91,19 @@ impl ConsensusMsg {
             ConsensusMsg::DAGMessage(_) => "DAGMessage",
         }
     }
+
+    /// Returns true iff the message belongs to quorum store (and should
+    /// be sent over the quorum store protocols, if the peer supports them).
+    pub fn is_quorum_store_message(&self) -> bool {
+        matches!(
+            self,
+            ConsensusMsg::BatchMsg(_)
+                | ConsensusMsg::BatchRequestMsg(_)
+                | ConsensusMsg::BatchResponse(_)
+                | ConsensusMsg::SignedBatchInfo(_)
+                | ConsensusMsg::ProofOfStoreMsg(_)
+        )
+    }
 }
 
 /// The interface from Consensus to Networking layer.
@@ -120,6 +133,27 @@ pub const DIRECT_SEND: &[ProtocolId] = &[
     ProtocolId::ConsensusDirectSendJson,
 ];
 
+/// Zstd protocols, preferred over `RPC` when zstd compression is enabled.
+pub const RPC_ZSTD: &[ProtocolId] = &[ProtocolId::ConsensusRpcZstd];
+
+/// Zstd protocols, preferred over `DIRECT_SEND` when zstd compression is enabled.
+pub const DIRECT_SEND_ZSTD: &[ProtocolId] = &[ProtocolId::ConsensusDirectSendZstd];
+
+/// Zstd dictionary protocols, preferred over `RPC_ZSTD` when a zstd dictionary is configured.
+pub const RPC_ZSTD_DICTIONARY: &[ProtocolId] = &[ProtocolId::ConsensusRpcZstdDictionary];
+
+/// Zstd dictionary protocols, preferred over `DIRECT_SEND_ZSTD` when a zstd dictionary is configured.
+pub const DIRECT_SEND_ZSTD_DICTIONARY: &[ProtocolId] =
+    &[ProtocolId::ConsensusDirectSendZstdDictionary];
+
+/// Quorum store protocols, preferred for quorum store messages (so that the
+/// network can schedule them separately from the other consensus messages).
+pub const QUORUM_STORE_RPC: &[ProtocolId] = &[ProtocolId::QuorumStoreRpcCompressed];
+
+/// Quorum store protocols, preferred for quorum store messages (so that the
+/// network can schedule them separately from the other consensus messages).
+pub const QUORUM_STORE_DIRECT_SEND: &[ProtocolId] = &[ProtocolId::QuorumStoreDirectSendCompressed];
+
 impl<NetworkClient: NetworkClientInterface<ConsensusMsg>> ConsensusNetworkClient<NetworkClient> {
     /// Returns a new consensus network client
     pub fn new(network_client: NetworkClient) -> Self {
@@ -129,7 +163,12 @@ impl<NetworkClient: NetworkClientInterface<ConsensusMsg>> ConsensusNetworkClient
     /// Send a single message to the destination peer
     pub fn send_to(&self, peer: PeerId, message: ConsensusMsg) -> Result<(), Error> {
         let peer_network_id = self.get_peer_network_id_for_peer(peer);
-        self.network_client.send_to_peer(message, peer_network_id)
+        let preferred_protocols = Self::get_preferred_protocols(&message, QUORUM_STORE_DIRECT_SEND);
+        self.network_client.send_to_peer_with_preferred_protocols(
+            message,
+            peer_network_id,
+            preferred_protocols,
+        )
     }
 
     /// Send a single message to the destination peers
@@ -141,8 +180,12 @@ impl<NetworkClient: NetworkClientInterface<ConsensusMsg>> ConsensusNetworkClient
         let peer_network_ids: Vec<PeerNetworkId> = peers
             .map(|peer| self.get_peer_network_id_for_peer(peer))
             .collect();
-        self.network_client
-            .send_to_peers(message, &peer_network_ids)
+        let preferred_protocols = Self::get_preferred_protocols(&message, QUORUM_STORE_DIRECT_SEND);
+        self.network_client.send_to_peers_with_preferred_protocols(
+            message,
+            &peer_network_ids,
+            preferred_protocols,
+        )
     }
 
     /// Send a RPC to the destination peer
@@ -153,11 +196,29 @@ impl<NetworkClient: NetworkClientInterface<ConsensusMsg>> ConsensusNetworkClient
         rpc_timeout: Duration,
     ) -> Result<ConsensusMsg, Error> {
         let peer_network_id = self.get_peer_network_id_for_peer(peer);
+        let preferred_protocols = Self::get_preferred_protocols(&message, QUORUM_STORE_RPC);
         self.network_client
-            .send_to_peer_rpc(message, rpc_timeout, peer_network_id)
+            .send_to_peer_rpc_with_preferred_protocols(
+                message,
+                rpc_timeout,
+                peer_network_id,
+                preferred_protocols,
+            )
             .await
     }
 
+    /// Returns the protocols to prefer over the client protocols for the given message
+    fn get_preferred_protocols(
+        message: &ConsensusMsg,
+        quorum_store_protocols: &'static [ProtocolId],
+    ) -> &'static [ProtocolId] {
+        if message.is_quorum_store_message() {
+            quorum_store_protocols
+        } else {
+            &[]
+        }
+    }
+
     // TODO: we shouldn't need to expose this. Migrate the code to handle
     // peer and network ids.
     fn get_peer_network_id_for_peer(&self, peer: PeerId) -> PeerNetworkId {
@@ -617,12 +617,13 @@ mod tests {
                 consensus_network_client,
                 self_sender,
                 validator_verifier.clone(),
+                None,
             );
 
             let network_events = NetworkEvents::new(consensus_rx, conn_status_rx, None);
             let network_service_events =
                 NetworkServiceEvents::new(hashmap! {NetworkId::Validator => network_events});
-            let (task, receiver) = NetworkTask::new(network_service_events, self_receiver);
+            let (task, receiver) = NetworkTask::new(network_service_events, self_receiver, None);
 
             receivers.push(receiver);
             runtime.handle().spawn(task.start());
@@ -730,12 +731,13 @@ mod tests {
                 consensus_network_client.clone(),
                 self_sender,
                 validator_verifier.clone(),
+                None,
             );
 
             let network_events = NetworkEvents::new(consensus_rx, conn_status_rx, None);
             let network_service_events =
                 NetworkServiceEvents::new(hashmap! {NetworkId::Validator => network_events});
-            let (task, receiver) = NetworkTask::new(network_service_events, self_receiver);
+            let (task, receiver) = NetworkTask::new(network_service_events, self_receiver, None);
 
             senders.push(consensus_network_client);
             receivers.push(receiver);
@@ -810,7 +812,7 @@ mod tests {
         let (self_sender, self_receiver) = aptos_channels::new_test(8);
 
         let (network_task, mut network_receivers) =
-            NetworkTask::new(network_service_events, self_receiver);
+            NetworkTask::new(network_service_events, self_receiver, None);
 
         let peer_id = PeerId::random();
         let protocol_id = ProtocolId::ConsensusDirectSendBcs;
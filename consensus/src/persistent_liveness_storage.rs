@@ -72,6 +72,10 @@ impl LedgerRecoveryData {
         LedgerRecoveryData { storage_ledger }
     }
 
+    pub fn storage_ledger(&self) -> &LedgerInfoWithSignatures {
+        &self.storage_ledger
+    }
+
     pub fn committed_round(&self) -> Round {
         self.storage_ledger.commit_info().round()
     }
@@ -255,6 +259,16 @@ impl RecoveryData {
         self.last_vote.clone()
     }
 
+    /// Returns all blocks and quorum certs (including the root ones) held by the recovery data
+    pub fn blocks_and_quorum_certs(&self) -> (Vec<Block>, Vec<QuorumCert>) {
+        let RootInfo(root_block, root_quorum_cert, root_ordered_cert, _) = &self.root;
+        let mut blocks = vec![root_block.as_ref().clone()];
+        blocks.extend(self.blocks.iter().cloned());
+        let mut quorum_certs = vec![root_quorum_cert.clone(), root_ordered_cert.clone()];
+        quorum_certs.extend(self.quorum_certs.iter().cloned());
+        (blocks, quorum_certs)
+    }
+
     pub fn take(self) -> (RootInfo, RootMetadata, Vec<Block>, Vec<QuorumCert>) {
         (
             self.root,
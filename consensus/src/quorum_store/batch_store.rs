@@ -175,6 +175,7 @@ impl<T: QuorumStoreSender + Clone + Send + Sync + 'static> BatchStore<T> {
                 batch_store
                     .insert_to_cache(value)
                     .expect("Storage limit exceeded upon BatchReader construction");
+                counters::RECOVERED_BATCHES_COUNT.inc();
             }
         }
         trace!(
@@ -358,7 +359,7 @@ impl<T: QuorumStoreSender + Clone + Send + Sync + 'static> BatchStore<T> {
         }
     }
 
-    fn last_certified_time(&self) -> u64 {
+    pub(crate) fn last_certified_time(&self) -> u64 {
         self.last_certified_time.load(Ordering::Relaxed)
     }
 
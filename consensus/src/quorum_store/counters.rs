@@ -578,3 +578,41 @@ pub static BATCH_RECEIVED_REPLIES_VOTING_POWER: Lazy<Histogram> = Lazy::new(|| {
     )
     .unwrap()
 });
+
+/// Number of batches reloaded from the quorum store db on startup.
+pub static RECOVERED_BATCHES_COUNT: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "quorum_store_recovered_batches_count",
+        "Number of batches reloaded from the quorum store db on startup."
+    )
+    .unwrap()
+});
+
+/// Number of proofs reloaded from the quorum store db on startup.
+pub static RECOVERED_PROOFS_COUNT: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "quorum_store_recovered_proofs_count",
+        "Number of proofs reloaded from the quorum store db on startup."
+    )
+    .unwrap()
+});
+
+/// Number of expired batches and proofs deleted by the periodic db compaction.
+pub static DB_COMPACTION_DELETED_ENTRIES_COUNT: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "quorum_store_db_compaction_deleted_entries_count",
+        "Number of expired batches and proofs deleted by the periodic db compaction."
+    )
+    .unwrap()
+});
+
+/// Duration of each quorum store db compaction.
+pub static DB_COMPACTION_DURATION: Lazy<DurationHistogram> = Lazy::new(|| {
+    DurationHistogram::new(
+        register_histogram!(
+            "quorum_store_db_compaction_duration",
+            "Duration of each quorum store db compaction."
+        )
+        .unwrap(),
+    )
+});
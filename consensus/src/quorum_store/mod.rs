@@ -20,4 +20,4 @@ pub(crate) mod utils;
 
 mod schema;
 #[cfg(test)]
-mod tests;
+pub(crate) mod tests;
@@ -3,18 +3,26 @@
 
 use crate::{
     monitor,
-    quorum_store::{batch_generator::BackPressure, counters, utils::ProofQueue},
+    quorum_store::{
+        batch_generator::BackPressure, counters, quorum_store_db::QuorumStoreStorage,
+        utils::ProofQueue,
+    },
 };
 use aptos_consensus_types::{
     common::{Payload, PayloadFilter, ProofWithData},
     proof_of_store::{BatchInfo, ProofOfStore, ProofOfStoreMsg},
     request_response::{GetPayloadCommand, GetPayloadResponse},
 };
+use aptos_crypto::HashValue;
 use aptos_logger::prelude::*;
 use aptos_types::PeerId;
 use futures::StreamExt;
 use futures_channel::mpsc::Receiver;
-use std::collections::HashSet;
+use std::{
+    collections::HashSet,
+    sync::{mpsc, Arc},
+    time::Duration,
+};
 
 #[derive(Debug)]
 pub enum ProofManagerCommand {
@@ -23,12 +31,65 @@ pub enum ProofManagerCommand {
     Shutdown(tokio::sync::oneshot::Sender<()>),
 }
 
+enum ProofDbCommand {
+    SaveProofs(Vec<ProofOfStore>),
+    DeleteProofs(Vec<HashValue>),
+    #[cfg(test)]
+    Flush(mpsc::Sender<()>),
+}
+
+/// Persists and deletes proofs on a dedicated thread, so that the proof manager event loop
+/// never blocks on the db. Commands are applied in order, so a proof that is committed right
+/// after it was received is never persisted after its deletion.
+struct ProofDbWriter {
+    command_tx: mpsc::Sender<ProofDbCommand>,
+}
+
+impl ProofDbWriter {
+    fn new(db: Arc<dyn QuorumStoreStorage>) -> Self {
+        let (command_tx, command_rx) = mpsc::channel();
+        std::thread::Builder::new()
+            .name("qs-proof-db-writer".into())
+            .spawn(move || {
+                while let Ok(command) = command_rx.recv() {
+                    match command {
+                        ProofDbCommand::SaveProofs(proofs) => {
+                            if let Err(e) = db.save_proofs(&proofs) {
+                                warn!("QS: failed to persist proofs: {:?}", e);
+                            }
+                        },
+                        ProofDbCommand::DeleteProofs(digests) => {
+                            if let Err(e) = db.delete_proofs(digests) {
+                                warn!("QS: failed to delete committed proofs from db: {:?}", e);
+                            }
+                        },
+                        #[cfg(test)]
+                        ProofDbCommand::Flush(ack_tx) => {
+                            let _ = ack_tx.send(());
+                        },
+                    }
+                }
+            })
+            .expect("Failed to spawn the proof db writer thread");
+        Self { command_tx }
+    }
+
+    fn send(&self, command: ProofDbCommand) {
+        if self.command_tx.send(command).is_err() {
+            warn!("QS: the proof db writer has stopped");
+        }
+    }
+}
+
 pub struct ProofManager {
     proofs_for_consensus: ProofQueue,
     back_pressure_total_txn_limit: u64,
     remaining_total_txn_num: u64,
     back_pressure_total_proof_limit: u64,
     remaining_total_proof_num: u64,
+    latest_block_timestamp: u64,
+    db: Arc<dyn QuorumStoreStorage>,
+    db_writer: ProofDbWriter,
 }
 
 impl ProofManager {
@@ -36,6 +97,7 @@ impl ProofManager {
         my_peer_id: PeerId,
         back_pressure_total_txn_limit: u64,
         back_pressure_total_proof_limit: u64,
+        db: Arc<dyn QuorumStoreStorage>,
     ) -> Self {
         Self {
             proofs_for_consensus: ProofQueue::new(my_peer_id),
@@ -43,10 +105,49 @@ impl ProofManager {
             remaining_total_txn_num: 0,
             back_pressure_total_proof_limit,
             remaining_total_proof_num: 0,
+            latest_block_timestamp: 0,
+            db_writer: ProofDbWriter::new(db.clone()),
+            db,
+        }
+    }
+
+    /// Reloads the proofs persisted before a restart, so that they can be proposed right away
+    /// instead of waiting for the batches to be certified again. Proofs from other epochs and
+    /// proofs that expired by `last_committed_timestamp` are deleted from the db.
+    pub(crate) fn recover_proofs(&mut self, epoch: u64, last_committed_timestamp: u64) {
+        self.latest_block_timestamp = last_committed_timestamp;
+        let proofs = self
+            .db
+            .get_all_proofs()
+            .expect("failed to read proofs from db");
+        let (valid_proofs, stale_proofs): (Vec<_>, Vec<_>) =
+            proofs.into_values().partition(|proof| {
+                proof.epoch() == epoch && proof.expiration() > last_committed_timestamp
+            });
+        info!(
+            "QS: recovered {} proofs from db, deleting {} stale proofs",
+            valid_proofs.len(),
+            stale_proofs.len()
+        );
+        counters::RECOVERED_PROOFS_COUNT.inc_by(valid_proofs.len() as u64);
+        if let Err(e) = self
+            .db
+            .delete_proofs(stale_proofs.iter().map(|proof| *proof.digest()).collect())
+        {
+            warn!("QS: failed to delete stale proofs from db: {:?}", e);
+        }
+
+        for proof in valid_proofs {
+            self.proofs_for_consensus.push(proof);
         }
+        self.proofs_for_consensus
+            .handle_updated_block_timestamp(last_committed_timestamp);
+        (self.remaining_total_txn_num, self.remaining_total_proof_num) =
+            self.proofs_for_consensus.remaining_txns_and_proofs();
     }
 
     pub(crate) fn receive_proofs(&mut self, proofs: Vec<ProofOfStore>) {
+        self.db_writer.send(ProofDbCommand::SaveProofs(proofs.clone()));
         for proof in proofs.into_iter() {
             self.proofs_for_consensus.push(proof);
         }
@@ -64,6 +165,10 @@ impl ProofManager {
             block_timestamp
         );
 
+        self.latest_block_timestamp = self.latest_block_timestamp.max(block_timestamp);
+        self.db_writer.send(ProofDbCommand::DeleteProofs(
+            batches.iter().map(|batch| *batch.digest()).collect(),
+        ));
         self.proofs_for_consensus.mark_committed(batches);
         self.proofs_for_consensus
             .handle_updated_block_timestamp(block_timestamp);
@@ -123,21 +228,48 @@ impl ProofManager {
         }
     }
 
+    /// Waits until all proofs persisted or deleted so far have been written to the db
+    #[cfg(test)]
+    pub(crate) fn flush_db_writes(&self) {
+        let (ack_tx, ack_rx) = mpsc::channel();
+        self.db_writer.send(ProofDbCommand::Flush(ack_tx));
+        ack_rx.recv().expect("The proof db writer has stopped");
+    }
+
+    /// Deletes the batches and proofs that expired by the latest committed block timestamp
+    /// from the db, off the event loop.
+    fn compact_db(&self) {
+        let db = self.db.clone();
+        let expiration_cutoff = self.latest_block_timestamp;
+        tokio::task::spawn_blocking(move || {
+            if let Err(e) = db.compact(expiration_cutoff) {
+                warn!("QS: failed to compact db: {:?}", e);
+            }
+        });
+    }
+
     pub async fn start(
         mut self,
         back_pressure_tx: tokio::sync::mpsc::Sender<BackPressure>,
         mut proposal_rx: Receiver<GetPayloadCommand>,
         mut proof_rx: tokio::sync::mpsc::Receiver<ProofManagerCommand>,
+        db_compaction_interval: Duration,
     ) {
         let mut back_pressure = BackPressure {
             txn_count: false,
             proof_count: false,
         };
+        let mut compaction_interval = tokio::time::interval(db_compaction_interval);
+        // The first tick completes immediately, skip it as recovery already cleaned up the db.
+        compaction_interval.tick().await;
 
         loop {
             let _timer = counters::PROOF_MANAGER_MAIN_LOOP.start_timer();
 
             tokio::select! {
+                    _ = compaction_interval.tick() => monitor!("proof_manager_compact_db", {
+                        self.compact_db();
+                    }),
                     Some(msg) = proposal_rx.next() => monitor!("proof_manager_handle_proposal", {
                         self.handle_proposal_request(msg);
 
@@ -334,13 +334,18 @@ impl InnerBuilder {
         );
 
         let proof_manager_cmd_rx = self.proof_manager_cmd_rx.take().unwrap();
-        let proof_manager = ProofManager::new(
+        let mut proof_manager = ProofManager::new(
             self.author,
             self.config.back_pressure.backlog_txn_limit_count,
             self.config
                 .back_pressure
                 .backlog_per_validator_batch_limit_count
                 * self.num_validators,
+            self.quorum_store_storage.clone(),
+        );
+        proof_manager.recover_proofs(
+            self.epoch,
+            self.batch_store.as_ref().unwrap().last_certified_time(),
         );
         spawn_named!(
             "proof_manager",
@@ -348,6 +353,7 @@ impl InnerBuilder {
                 self.back_pressure_tx.clone(),
                 self.consensus_to_quorum_store_receiver,
                 proof_manager_cmd_rx,
+                Duration::from_millis(self.config.db_compaction_interval_ms),
             )
         );
 
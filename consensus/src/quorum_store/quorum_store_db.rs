@@ -4,15 +4,21 @@
 use crate::{
     error::DbError,
     quorum_store::{
-        schema::{BatchIdSchema, BatchSchema, BATCH_CF_NAME, BATCH_ID_CF_NAME},
+        counters,
+        schema::{
+            BatchExpirationSchema, BatchIdSchema, BatchSchema, ExpirationKey,
+            ProofOfStoreExpirationSchema, ProofOfStoreSchema, BATCH_CF_NAME,
+            BATCH_EXPIRATION_CF_NAME, BATCH_ID_CF_NAME, PROOF_OF_STORE_CF_NAME,
+            PROOF_OF_STORE_EXPIRATION_CF_NAME,
+        },
         types::PersistedValue,
     },
 };
 use anyhow::Result;
-use aptos_consensus_types::proof_of_store::BatchId;
+use aptos_consensus_types::proof_of_store::{BatchId, ProofOfStore};
 use aptos_crypto::HashValue;
 use aptos_logger::prelude::*;
-use aptos_schemadb::{Options, ReadOptions, SchemaBatch, DB};
+use aptos_schemadb::{schema::Schema, Options, ReadOptions, SchemaBatch, DB};
 use std::{collections::HashMap, path::Path, time::Instant};
 
 pub(crate) trait QuorumStoreStorage: Sync + Send {
@@ -29,6 +35,17 @@ pub(crate) trait QuorumStoreStorage: Sync + Send {
     fn clean_and_get_batch_id(&self, current_epoch: u64) -> Result<Option<BatchId>, DbError>;
 
     fn save_batch_id(&self, epoch: u64, batch_id: BatchId) -> Result<(), DbError>;
+
+    fn save_proofs(&self, proofs: &[ProofOfStore]) -> Result<(), DbError>;
+
+    fn delete_proofs(&self, digests: Vec<HashValue>) -> Result<(), DbError>;
+
+    fn get_all_proofs(&self) -> Result<HashMap<HashValue, ProofOfStore>>;
+
+    /// Deletes all batches and proofs that expired by `expiration_cutoff` (as found in the
+    /// expiration indices) and compacts the column families that had entries deleted.
+    /// Returns the number of deleted entries.
+    fn compact(&self, expiration_cutoff: u64) -> Result<usize, DbError>;
 }
 
 /// The name of the quorum store db file
@@ -40,7 +57,13 @@ pub struct QuorumStoreDB {
 
 impl QuorumStoreDB {
     pub(crate) fn new<P: AsRef<Path> + Clone>(db_root_path: P) -> Self {
-        let column_families = vec![BATCH_CF_NAME, BATCH_ID_CF_NAME];
+        let column_families = vec![
+            BATCH_CF_NAME,
+            BATCH_ID_CF_NAME,
+            PROOF_OF_STORE_CF_NAME,
+            BATCH_EXPIRATION_CF_NAME,
+            PROOF_OF_STORE_EXPIRATION_CF_NAME,
+        ];
 
         // TODO: this fails twins tests because it assumes a unique path per process
         let path = db_root_path.as_ref().join(QUORUM_STORE_DB_NAME);
@@ -59,6 +82,25 @@ impl QuorumStoreDB {
 
         Self { db }
     }
+
+    /// Returns the keys of the given expiration index that expired by `expiration_cutoff`.
+    /// The index is ordered by expiration, so only the expired prefix is read.
+    fn get_expired_keys<S: Schema<Key = ExpirationKey>>(
+        &self,
+        expiration_cutoff: u64,
+    ) -> Result<Vec<ExpirationKey>> {
+        let mut iter = self.db.iter::<S>(ReadOptions::default())?;
+        iter.seek_to_first();
+        let mut expired_keys = vec![];
+        for result in iter {
+            let (expiration_key, _) = result?;
+            if expiration_key.0 > expiration_cutoff {
+                break;
+            }
+            expired_keys.push(expiration_key);
+        }
+        Ok(expired_keys)
+    }
 }
 
 impl QuorumStoreStorage for QuorumStoreDB {
@@ -84,7 +126,10 @@ impl QuorumStoreStorage for QuorumStoreDB {
             batch.digest(),
             batch.expiration()
         );
-        Ok(self.db.put::<BatchSchema>(batch.digest(), &batch)?)
+        let schema_batch = SchemaBatch::new();
+        schema_batch.put::<BatchSchema>(batch.digest(), &batch)?;
+        schema_batch.put::<BatchExpirationSchema>(&(batch.expiration(), *batch.digest()), &())?;
+        Ok(self.db.write_schemas(schema_batch)?)
     }
 
     fn get_batch(&self, digest: &HashValue) -> Result<Option<PersistedValue>, DbError> {
@@ -118,6 +163,80 @@ impl QuorumStoreStorage for QuorumStoreDB {
     fn save_batch_id(&self, epoch: u64, batch_id: BatchId) -> Result<(), DbError> {
         Ok(self.db.put::<BatchIdSchema>(&epoch, &batch_id)?)
     }
+
+    fn save_proofs(&self, proofs: &[ProofOfStore]) -> Result<(), DbError> {
+        let batch = SchemaBatch::new();
+        for proof in proofs {
+            trace!(
+                "QS: db persists proof digest {} expiration {}",
+                proof.digest(),
+                proof.expiration()
+            );
+            batch.put::<ProofOfStoreSchema>(proof.digest(), proof)?;
+            batch
+                .put::<ProofOfStoreExpirationSchema>(&(proof.expiration(), *proof.digest()), &())?;
+        }
+        self.db.write_schemas(batch)?;
+        Ok(())
+    }
+
+    fn delete_proofs(&self, digests: Vec<HashValue>) -> Result<(), DbError> {
+        let batch = SchemaBatch::new();
+        for digest in digests.iter() {
+            trace!("QS: db delete proof digest {}", digest);
+            batch.delete::<ProofOfStoreSchema>(digest)?;
+        }
+        self.db.write_schemas(batch)?;
+        Ok(())
+    }
+
+    fn get_all_proofs(&self) -> Result<HashMap<HashValue, ProofOfStore>> {
+        let mut iter = self.db.iter::<ProofOfStoreSchema>(ReadOptions::default())?;
+        iter.seek_to_first();
+        iter.collect::<Result<HashMap<HashValue, ProofOfStore>>>()
+    }
+
+    fn compact(&self, expiration_cutoff: u64) -> Result<usize, DbError> {
+        let instant = Instant::now();
+        let expired_batches = self.get_expired_keys::<BatchExpirationSchema>(expiration_cutoff)?;
+        let expired_proofs =
+            self.get_expired_keys::<ProofOfStoreExpirationSchema>(expiration_cutoff)?;
+        let num_deleted = expired_batches.len() + expired_proofs.len();
+        if num_deleted == 0 {
+            return Ok(0);
+        }
+
+        let batch = SchemaBatch::new();
+        for expiration_key in expired_batches.iter() {
+            batch.delete::<BatchSchema>(&expiration_key.1)?;
+            batch.delete::<BatchExpirationSchema>(expiration_key)?;
+        }
+        for expiration_key in expired_proofs.iter() {
+            batch.delete::<ProofOfStoreSchema>(&expiration_key.1)?;
+            batch.delete::<ProofOfStoreExpirationSchema>(expiration_key)?;
+        }
+        self.db.write_schemas(batch)?;
+
+        // Only compact the column families that had entries deleted
+        if !expired_batches.is_empty() {
+            self.db.compact_cf(BATCH_CF_NAME)?;
+            self.db.compact_cf(BATCH_EXPIRATION_CF_NAME)?;
+        }
+        if !expired_proofs.is_empty() {
+            self.db.compact_cf(PROOF_OF_STORE_CF_NAME)?;
+            self.db.compact_cf(PROOF_OF_STORE_EXPIRATION_CF_NAME)?;
+        }
+
+        counters::DB_COMPACTION_DELETED_ENTRIES_COUNT.inc_by(num_deleted as u64);
+        counters::DB_COMPACTION_DURATION.observe_duration(instant.elapsed());
+        debug!(
+            "QS: compacted db, deleted {} expired batches and {} expired proofs in {} ms",
+            expired_batches.len(),
+            expired_proofs.len(),
+            instant.elapsed().as_millis()
+        );
+        Ok(num_deleted)
+    }
 }
 
 pub(crate) struct MockQuorumStoreDB {}
@@ -157,4 +276,20 @@ impl QuorumStoreStorage for MockQuorumStoreDB {
     fn save_batch_id(&self, _: u64, _: BatchId) -> Result<(), DbError> {
         Ok(())
     }
+
+    fn save_proofs(&self, _: &[ProofOfStore]) -> Result<(), DbError> {
+        Ok(())
+    }
+
+    fn delete_proofs(&self, _: Vec<HashValue>) -> Result<(), DbError> {
+        Ok(())
+    }
+
+    fn get_all_proofs(&self) -> Result<HashMap<HashValue, ProofOfStore>> {
+        Ok(HashMap::new())
+    }
+
+    fn compact(&self, _: u64) -> Result<usize, DbError> {
+        Ok(0)
+    }
 }
@@ -2,8 +2,8 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::quorum_store::types::PersistedValue;
-use anyhow::Result;
-use aptos_consensus_types::proof_of_store::BatchId;
+use anyhow::{ensure, Result};
+use aptos_consensus_types::proof_of_store::{BatchId, ProofOfStore};
 use aptos_crypto::HashValue;
 use aptos_schemadb::{
     schema::{KeyCodec, Schema, ValueCodec},
@@ -12,6 +12,13 @@ use aptos_schemadb::{
 
 pub(crate) const BATCH_CF_NAME: ColumnFamilyName = "batch";
 pub(crate) const BATCH_ID_CF_NAME: ColumnFamilyName = "batch_ID";
+pub(crate) const PROOF_OF_STORE_CF_NAME: ColumnFamilyName = "proof_of_store";
+pub(crate) const BATCH_EXPIRATION_CF_NAME: ColumnFamilyName = "batch_expiration";
+pub(crate) const PROOF_OF_STORE_EXPIRATION_CF_NAME: ColumnFamilyName = "proof_of_store_expiration";
+
+/// The key of the expiration indices: the expiration (encoded in big endian, so that the
+/// entries are ordered by expiration) followed by the digest of the batch or proof.
+pub(crate) type ExpirationKey = (u64, HashValue);
 
 #[derive(Debug)]
 pub(crate) struct BatchSchema;
@@ -72,3 +79,118 @@ impl ValueCodec<BatchIdSchema> for BatchId {
         Ok(bcs::from_bytes(data)?)
     }
 }
+
+#[derive(Debug)]
+pub(crate) struct ProofOfStoreSchema;
+
+impl Schema for ProofOfStoreSchema {
+    type Key = HashValue;
+    type Value = ProofOfStore;
+
+    const COLUMN_FAMILY_NAME: aptos_schemadb::ColumnFamilyName = PROOF_OF_STORE_CF_NAME;
+}
+
+impl KeyCodec<ProofOfStoreSchema> for HashValue {
+    fn encode_key(&self) -> Result<Vec<u8>> {
+        Ok(self.to_vec())
+    }
+
+    fn decode_key(data: &[u8]) -> Result<Self> {
+        Ok(HashValue::from_slice(data)?)
+    }
+}
+
+impl ValueCodec<ProofOfStoreSchema> for ProofOfStore {
+    fn encode_value(&self) -> Result<Vec<u8>> {
+        Ok(bcs::to_bytes(&self)?)
+    }
+
+    fn decode_value(data: &[u8]) -> Result<Self> {
+        Ok(bcs::from_bytes(data)?)
+    }
+}
+
+/// An index of the batches by expiration, so that expired batches can be found without
+/// reading all batches. Entries of batches deleted by digest are left in the index and are
+/// removed once they expire.
+#[derive(Debug)]
+pub(crate) struct BatchExpirationSchema;
+
+impl Schema for BatchExpirationSchema {
+    type Key = ExpirationKey;
+    type Value = ();
+
+    const COLUMN_FAMILY_NAME: aptos_schemadb::ColumnFamilyName = BATCH_EXPIRATION_CF_NAME;
+}
+
+impl KeyCodec<BatchExpirationSchema> for ExpirationKey {
+    fn encode_key(&self) -> Result<Vec<u8>> {
+        Ok(encode_expiration_key(self))
+    }
+
+    fn decode_key(data: &[u8]) -> Result<Self> {
+        decode_expiration_key(data)
+    }
+}
+
+impl ValueCodec<BatchExpirationSchema> for () {
+    fn encode_value(&self) -> Result<Vec<u8>> {
+        Ok(Vec::new())
+    }
+
+    fn decode_value(_data: &[u8]) -> Result<Self> {
+        Ok(())
+    }
+}
+
+/// An index of the proofs by expiration (see `BatchExpirationSchema`)
+#[derive(Debug)]
+pub(crate) struct ProofOfStoreExpirationSchema;
+
+impl Schema for ProofOfStoreExpirationSchema {
+    type Key = ExpirationKey;
+    type Value = ();
+
+    const COLUMN_FAMILY_NAME: aptos_schemadb::ColumnFamilyName = PROOF_OF_STORE_EXPIRATION_CF_NAME;
+}
+
+impl KeyCodec<ProofOfStoreExpirationSchema> for ExpirationKey {
+    fn encode_key(&self) -> Result<Vec<u8>> {
+        Ok(encode_expiration_key(self))
+    }
+
+    fn decode_key(data: &[u8]) -> Result<Self> {
+        decode_expiration_key(data)
+    }
+}
+
+impl ValueCodec<ProofOfStoreExpirationSchema> for () {
+    fn encode_value(&self) -> Result<Vec<u8>> {
+        Ok(Vec::new())
+    }
+
+    fn decode_value(_data: &[u8]) -> Result<Self> {
+        Ok(())
+    }
+}
+
+fn encode_expiration_key((expiration, digest): &ExpirationKey) -> Vec<u8> {
+    let mut encoded_key = expiration.to_be_bytes().to_vec();
+    encoded_key.extend_from_slice(digest.as_ref());
+    encoded_key
+}
+
+fn decode_expiration_key(data: &[u8]) -> Result<ExpirationKey> {
+    const EXPIRATION_SIZE: usize = std::mem::size_of::<u64>();
+
+    ensure!(
+        data.len() == EXPIRATION_SIZE + HashValue::LENGTH,
+        "Unexpected expiration key length: {}",
+        data.len()
+    );
+    let (expiration, digest) = data.split_at(EXPIRATION_SIZE);
+    Ok((
+        u64::from_be_bytes(expiration.try_into()?),
+        HashValue::from_slice(digest)?,
+    ))
+}
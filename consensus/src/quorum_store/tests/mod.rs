@@ -8,4 +8,4 @@ mod proof_coordinator_test;
 mod proof_manager_test;
 mod quorum_store_db_test;
 mod types_test;
-mod utils;
+pub(crate) mod utils;
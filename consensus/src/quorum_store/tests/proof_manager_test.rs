@@ -1,19 +1,23 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::quorum_store::proof_manager::ProofManager;
+use crate::quorum_store::{
+    proof_manager::ProofManager,
+    quorum_store_db::{MockQuorumStoreDB, QuorumStoreDB, QuorumStoreStorage},
+};
 use aptos_consensus_types::{
     common::{Payload, PayloadFilter},
     proof_of_store::{BatchId, BatchInfo, ProofOfStore},
     request_response::{GetPayloadCommand, GetPayloadResponse},
 };
 use aptos_crypto::HashValue;
+use aptos_temppath::TempPath;
 use aptos_types::{aggregate_signature::AggregateSignature, PeerId};
 use futures::channel::oneshot;
-use std::collections::HashSet;
+use std::{collections::HashSet, sync::Arc};
 
 fn create_proof_manager() -> ProofManager {
-    ProofManager::new(PeerId::random(), 10, 10)
+    ProofManager::new(PeerId::random(), 10, 10, Arc::new(MockQuorumStoreDB::new()))
 }
 
 fn create_proof(author: PeerId, expiration: u64, batch_sequence: u64) -> ProofOfStore {
@@ -108,6 +112,40 @@ async fn test_batch_commit() {
     get_proposal_and_assert(&mut proof_manager, 100, &[], &vec![proof0]).await;
 }
 
+#[tokio::test]
+async fn test_recover_proofs() {
+    let tmp_dir = TempPath::new();
+    let db = Arc::new(QuorumStoreDB::new(&tmp_dir));
+    let mut proof_manager = ProofManager::new(PeerId::random(), 10, 10, db.clone());
+
+    let expired_proof = create_proof(PeerId::random(), 10, 1);
+    let valid_proof = create_proof(PeerId::random(), 30, 2);
+    let committed_proof = create_proof(PeerId::random(), 30, 3);
+    proof_manager.receive_proofs(vec![
+        expired_proof.clone(),
+        valid_proof.clone(),
+        committed_proof.clone(),
+    ]);
+    proof_manager.handle_commit_notification(1, vec![committed_proof.info().clone()]);
+    proof_manager.flush_db_writes();
+    assert_eq!(db.get_all_proofs().unwrap().len(), 2);
+
+    // Restart with a committed timestamp past the expiration of the first proof
+    let mut proof_manager = ProofManager::new(PeerId::random(), 10, 10, db.clone());
+    proof_manager.recover_proofs(0, 15);
+    get_proposal_and_assert(&mut proof_manager, 100, &[], &[valid_proof.clone()]).await;
+
+    let persisted_proofs = db.get_all_proofs().unwrap();
+    assert_eq!(persisted_proofs.len(), 1);
+    assert!(persisted_proofs.contains_key(valid_proof.digest()));
+
+    // Proofs from a previous epoch are not recovered
+    let mut proof_manager = ProofManager::new(PeerId::random(), 10, 10, db.clone());
+    proof_manager.recover_proofs(1, 15);
+    get_proposal_and_assert(&mut proof_manager, 100, &[], &[]).await;
+    assert!(db.get_all_proofs().unwrap().is_empty());
+}
+
 #[tokio::test]
 async fn test_proposal_priority() {
     let mut proof_manager = create_proof_manager();
@@ -6,9 +6,10 @@ use crate::quorum_store::{
     tests::utils::create_vec_signed_transactions,
     types::{Batch, PersistedValue},
 };
-use aptos_consensus_types::proof_of_store::BatchId;
+use aptos_consensus_types::proof_of_store::{BatchId, BatchInfo, ProofOfStore};
+use aptos_crypto::HashValue;
 use aptos_temppath::TempPath;
-use aptos_types::account_address::AccountAddress;
+use aptos_types::{account_address::AccountAddress, aggregate_signature::AggregateSignature};
 use claims::assert_ok;
 
 #[test]
@@ -82,3 +83,77 @@ fn test_db_for_batch_id() {
         BatchId::new_for_test(2)
     );
 }
+
+fn create_proof(expiration: u64) -> ProofOfStore {
+    ProofOfStore::new(
+        BatchInfo::new(
+            AccountAddress::random(),
+            BatchId::new_for_test(1),
+            1,
+            expiration,
+            HashValue::random(),
+            1,
+            1,
+            0,
+        ),
+        AggregateSignature::empty(),
+    )
+}
+
+#[test]
+fn test_db_for_proofs() {
+    let tmp_dir = TempPath::new();
+    let db = QuorumStoreDB::new(&tmp_dir);
+
+    let proofs: Vec<_> = (0..3).map(|_| create_proof(20)).collect();
+    assert_ok!(db.save_proofs(&proofs));
+    assert_ok!(db.delete_proofs(vec![*proofs[2].digest()]));
+
+    let all_proofs = db.get_all_proofs().expect("could not read from db");
+    assert_eq!(all_proofs.len(), 2);
+    assert_eq!(all_proofs.get(proofs[0].digest()), Some(&proofs[0]));
+    assert_eq!(all_proofs.get(proofs[1].digest()), Some(&proofs[1]));
+}
+
+#[test]
+fn test_db_compaction() {
+    let tmp_dir = TempPath::new();
+    let db = QuorumStoreDB::new(&tmp_dir);
+
+    let source = AccountAddress::random();
+    let expired_batch: PersistedValue = Batch::new(
+        BatchId::new_for_test(1),
+        create_vec_signed_transactions(10),
+        1,
+        10,
+        source,
+        0,
+    )
+    .into();
+    let valid_batch: PersistedValue = Batch::new(
+        BatchId::new_for_test(2),
+        create_vec_signed_transactions(10),
+        1,
+        30,
+        source,
+        0,
+    )
+    .into();
+    assert_ok!(db.save_batch(expired_batch.clone()));
+    assert_ok!(db.save_batch(valid_batch.clone()));
+
+    let expired_proof = create_proof(10);
+    let valid_proof = create_proof(30);
+    assert_ok!(db.save_proofs(&[expired_proof.clone(), valid_proof.clone()]));
+
+    assert_eq!(db.compact(20).expect("could not compact db"), 2);
+
+    let all_batches = db.get_all_batches().expect("could not read from db");
+    assert_eq!(all_batches.len(), 1);
+    assert!(all_batches.contains_key(valid_batch.digest()));
+    let all_proofs = db.get_all_proofs().expect("could not read from db");
+    assert_eq!(all_proofs.len(), 1);
+    assert!(all_proofs.contains_key(valid_proof.digest()));
+
+    assert_eq!(db.compact(20).expect("could not compact db"), 0);
+}
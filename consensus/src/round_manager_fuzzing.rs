@@ -5,6 +5,7 @@
 use crate::{
     block_storage::BlockStore,
     liveness::{
+        block_size_controller::AdaptiveBlockSizeController,
         proposal_generator::{
             ChainHealthBackoffConfig, PipelineBackpressureConfig, ProposalGenerator,
         },
@@ -148,6 +149,7 @@ fn create_node_for_fuzzing() -> RoundManager {
         consensus_network_client,
         self_sender,
         epoch_state.verifier.clone(),
+        None,
     );
 
     // TODO: mock
@@ -169,6 +171,7 @@ fn create_node_for_fuzzing() -> RoundManager {
         10,
         PipelineBackpressureConfig::new_no_backoff(),
         ChainHealthBackoffConfig::new_no_backoff(),
+        Arc::new(AdaptiveBlockSizeController::new_disabled()),
         false,
     );
 
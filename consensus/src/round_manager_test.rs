@@ -6,6 +6,7 @@ use crate::{
     block_storage::{BlockReader, BlockStore},
     experimental::buffer_manager::OrderedBlocks,
     liveness::{
+        block_size_controller::AdaptiveBlockSizeController,
         proposal_generator::{
             ChainHealthBackoffConfig, PipelineBackpressureConfig, ProposalGenerator,
         },
@@ -216,7 +217,13 @@ impl NodeSetup {
         playground.add_node(twin_id, consensus_tx, network_reqs_rx, conn_mgr_reqs_rx);
 
         let (self_sender, self_receiver) = aptos_channels::new_test(1000);
-        let network = NetworkSender::new(author, consensus_network_client, self_sender, validators);
+        let network = NetworkSender::new(
+            author,
+            consensus_network_client,
+            self_sender,
+            validators,
+            None,
+        );
 
         let all_network_events = Box::new(select(network_events, self_receiver));
 
@@ -252,6 +259,7 @@ impl NodeSetup {
             10,
             PipelineBackpressureConfig::new_no_backoff(),
             ChainHealthBackoffConfig::new_no_backoff(),
+            Arc::new(AdaptiveBlockSizeController::new_disabled()),
             false,
         );
 
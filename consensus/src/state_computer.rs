@@ -4,6 +4,10 @@
 
 use crate::{
     block_storage::tracing::{observe_block, BlockStage},
+    consensus_observer::{
+        network_message::{BlockPayload, ConsensusObserverDirectSend},
+        publisher::ConsensusPublisher,
+    },
     counters,
     error::StateSyncError,
     monitor,
@@ -22,8 +26,10 @@ use aptos_infallible::Mutex;
 use aptos_logger::prelude::*;
 use aptos_types::{
     account_address::AccountAddress, contract_event::ContractEvent, epoch_state::EpochState,
-    ledger_info::LedgerInfoWithSignatures, transaction::Transaction,
+    ledger_info::LedgerInfoWithSignatures, on_chain_config::BlockGasLimitType,
+    transaction::Transaction,
 };
+use aptos_vm::speculative_execution::is_speculative_execution_enabled;
 use fail::fail_point;
 use futures::{SinkExt, StreamExt};
 use std::{boxed::Box, sync::Arc};
@@ -58,8 +64,9 @@ pub struct ExecutionProxy {
     write_mutex: AsyncMutex<LogicalTime>,
     payload_manager: Mutex<Option<Arc<PayloadManager>>>,
     transaction_shuffler: Mutex<Option<Arc<dyn TransactionShuffler>>>,
-    maybe_block_gas_limit: Mutex<Option<u64>>,
+    block_gas_limit_type: Mutex<BlockGasLimitType>,
     transaction_deduper: Mutex<Option<Arc<dyn TransactionDeduper>>>,
+    consensus_publisher: Option<Arc<ConsensusPublisher>>,
 }
 
 impl ExecutionProxy {
@@ -68,6 +75,7 @@ impl ExecutionProxy {
         txn_notifier: Arc<dyn TxnNotifier>,
         state_sync_notifier: Arc<dyn ConsensusNotificationSender>,
         handle: &tokio::runtime::Handle,
+        consensus_publisher: Option<Arc<ConsensusPublisher>>,
     ) -> Self {
         let (tx, mut rx) =
             aptos_channels::new::<NotificationType>(10, &counters::PENDING_STATE_SYNC_NOTIFICATION);
@@ -93,8 +101,9 @@ impl ExecutionProxy {
             write_mutex: AsyncMutex::new(LogicalTime::new(0, 0)),
             payload_manager: Mutex::new(None),
             transaction_shuffler: Mutex::new(None),
-            maybe_block_gas_limit: Mutex::new(None),
+            block_gas_limit_type: Mutex::new(BlockGasLimitType::NoLimit),
             transaction_deduper: Mutex::new(None),
+            consensus_publisher,
         }
     }
 }
@@ -126,10 +135,22 @@ impl StateComputer for ExecutionProxy {
         let txn_shuffler = self.transaction_shuffler.lock().as_ref().unwrap().clone();
         let txns = payload_manager.get_transactions(block).await?;
 
+        // Forward the transactions to consensus observers, as they can't fetch quorum store batches
+        if let Some(consensus_publisher) = &self.consensus_publisher {
+            consensus_publisher.publish_message(ConsensusObserverDirectSend::BlockPayload(
+                BlockPayload {
+                    epoch: block.epoch(),
+                    round: block.round(),
+                    block_id,
+                    transactions: txns.clone(),
+                },
+            ));
+        }
+
         let deduped_txns = txn_deduper.dedup(txns);
         let shuffled_txns = txn_shuffler.shuffle(deduped_txns);
 
-        let block_gas_limit = *self.maybe_block_gas_limit.lock();
+        let block_gas_limit_type = *self.block_gas_limit_type.lock();
 
         // TODO: figure out error handling for the prologue txn
         let executor = self.executor.clone();
@@ -137,7 +158,7 @@ impl StateComputer for ExecutionProxy {
         let transactions_to_execute = block.transactions_to_execute(
             &self.validators.lock(),
             shuffled_txns.clone(),
-            block_gas_limit,
+            block_gas_limit_type,
         );
 
         let compute_result = monitor!(
@@ -146,7 +167,7 @@ impl StateComputer for ExecutionProxy {
                 executor.execute_block(
                     (block_id, transactions_to_execute).into(),
                     parent_block_id,
-                    block_gas_limit,
+                    block_gas_limit_type,
                 )
             })
             .await
@@ -168,6 +189,38 @@ impl StateComputer for ExecutionProxy {
         Ok(compute_result)
     }
 
+    fn pre_execute(&self, block: &Block, parent_block_id: HashValue) {
+        if !is_speculative_execution_enabled() {
+            return;
+        }
+        let payload_manager = match self.payload_manager.lock().as_ref() {
+            Some(payload_manager) => payload_manager.clone(),
+            None => return,
+        };
+        let txn_deduper = self.transaction_deduper.lock().as_ref().unwrap().clone();
+        let txn_shuffler = self.transaction_shuffler.lock().as_ref().unwrap().clone();
+        let block_gas_limit_type = *self.block_gas_limit_type.lock();
+        let validators = self.validators.lock().clone();
+        let executor = self.executor.clone();
+        let block = block.clone();
+
+        tokio::spawn(async move {
+            let txns = match payload_manager.get_transactions(&block).await {
+                Ok(txns) => txns,
+                Err(e) => {
+                    debug!(error = ?e, "Failed to get transactions to pre-execute");
+                    return;
+                },
+            };
+            let shuffled_txns = txn_shuffler.shuffle(txn_deduper.dedup(txns));
+            let transactions_to_execute =
+                block.transactions_to_execute(&validators, shuffled_txns, block_gas_limit_type);
+            tokio::task::spawn_blocking(move || {
+                executor.pre_execute_block(transactions_to_execute, parent_block_id)
+            });
+        });
+    }
+
     /// Send a successful commit. A future is fulfilled when the state is finalized.
     async fn commit(
         &self,
@@ -191,7 +244,7 @@ impl StateComputer for ExecutionProxy {
         let txn_deduper = self.transaction_deduper.lock().as_ref().unwrap().clone();
         let txn_shuffler = self.transaction_shuffler.lock().as_ref().unwrap().clone();
 
-        let block_gas_limit = *self.maybe_block_gas_limit.lock();
+        let block_gas_limit_type = *self.block_gas_limit_type.lock();
 
         for block in blocks {
             block_ids.push(block.id());
@@ -207,7 +260,7 @@ impl StateComputer for ExecutionProxy {
             txns.extend(block.transactions_to_commit(
                 &self.validators.lock(),
                 shuffled_txns,
-                block_gas_limit,
+                block_gas_limit_type,
             ));
             reconfig_events.extend(block.reconfig_event());
         }
@@ -301,7 +354,7 @@ impl StateComputer for ExecutionProxy {
         epoch_state: &EpochState,
         payload_manager: Arc<PayloadManager>,
         transaction_shuffler: Arc<dyn TransactionShuffler>,
-        block_gas_limit: Option<u64>,
+        block_gas_limit_type: BlockGasLimitType,
         transaction_deduper: Arc<dyn TransactionDeduper>,
     ) {
         *self.validators.lock() = epoch_state
@@ -312,7 +365,7 @@ impl StateComputer for ExecutionProxy {
         self.transaction_shuffler
             .lock()
             .replace(transaction_shuffler);
-        *self.maybe_block_gas_limit.lock() = block_gas_limit;
+        *self.block_gas_limit_type.lock() = block_gas_limit_type;
         self.transaction_deduper.lock().replace(transaction_deduper);
     }
 
@@ -357,7 +410,7 @@ async fn test_commit_sync_race() {
             &self,
             _block: ExecutableBlock,
             _parent_block_id: HashValue,
-            _maybe_block_gas_limit: Option<u64>,
+            _block_gas_limit_type: BlockGasLimitType,
         ) -> Result<StateComputeResult, ExecutionError> {
             Ok(StateComputeResult::new_dummy())
         }
@@ -433,12 +486,13 @@ async fn test_commit_sync_race() {
         recorded_commit.clone(),
         recorded_commit.clone(),
         &tokio::runtime::Handle::current(),
+        None,
     );
     executor.new_epoch(
         &EpochState::empty(),
         Arc::new(PayloadManager::DirectMempool),
         create_transaction_shuffler(TransactionShufflerType::NoShuffling),
-        None,
+        BlockGasLimitType::NoLimit,
         create_transaction_deduper(TransactionDeduperType::NoDedup),
     );
     executor
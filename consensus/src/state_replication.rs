@@ -16,7 +16,10 @@ use aptos_consensus_types::{
 };
 use aptos_crypto::HashValue;
 use aptos_executor_types::{Error as ExecutionError, StateComputeResult};
-use aptos_types::{epoch_state::EpochState, ledger_info::LedgerInfoWithSignatures};
+use aptos_types::{
+    epoch_state::EpochState, ledger_info::LedgerInfoWithSignatures,
+    on_chain_config::BlockGasLimitType,
+};
 use futures::future::BoxFuture;
 use std::{sync::Arc, time::Duration};
 
@@ -58,6 +61,11 @@ pub trait StateComputer: Send + Sync {
         parent_block_id: HashValue,
     ) -> Result<StateComputeResult, ExecutionError>;
 
+    /// Starts speculatively executing the transactions of a block that is not ordered yet, in the
+    /// background, so that its execution can reuse the results. Best effort, no-op by default, so
+    /// state computers wrapping another one have to forward it.
+    fn pre_execute(&self, _block: &Block, _parent_block_id: HashValue) {}
+
     /// Send a successful commit. A future is fulfilled when the state is finalized.
     async fn commit(
         &self,
@@ -78,7 +86,7 @@ pub trait StateComputer: Send + Sync {
         epoch_state: &EpochState,
         payload_manager: Arc<PayloadManager>,
         transaction_shuffler: Arc<dyn TransactionShuffler>,
-        block_gas_limit: Option<u64>,
+        block_gas_limit_type: BlockGasLimitType,
         transaction_deduper: Arc<dyn TransactionDeduper>,
     );
 
@@ -1,12 +1,17 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::sender_aware_shuffler::SenderAwareShuffler;
+use crate::{
+    conflict_aware_shuffler::ConflictAwareShuffler, fee_aware_shuffler::FeeAwareShuffler,
+    sender_aware_shuffler::SenderAwareShuffler,
+};
 use aptos_logger::info;
 use aptos_types::{
     on_chain_config::{
         TransactionShufflerType,
-        TransactionShufflerType::{DeprecatedSenderAwareV1, NoShuffling, SenderAwareV2},
+        TransactionShufflerType::{
+            ConflictAwareV1, DeprecatedSenderAwareV1, FeeAwareV1, NoShuffling, SenderAwareV2,
+        },
     },
     transaction::SignedTransaction,
 };
@@ -45,5 +50,25 @@ pub fn create_transaction_shuffler(
             );
             Arc::new(SenderAwareShuffler::new(confict_window_size as usize))
         },
+        ConflictAwareV1(conflict_window_size) => {
+            info!(
+                "Using conflict aware transaction shuffling with conflict window size {}",
+                conflict_window_size
+            );
+            Arc::new(ConflictAwareShuffler::new(conflict_window_size as usize))
+        },
+        FeeAwareV1 {
+            fairness_window_size,
+            conflict_window_size,
+        } => {
+            info!(
+                "Using fee aware transaction shuffling with fairness window size {} and conflict window size {}",
+                fairness_window_size, conflict_window_size
+            );
+            Arc::new(FeeAwareShuffler::new(
+                fairness_window_size as usize,
+                conflict_window_size as usize,
+            ))
+        },
     }
 }
@@ -0,0 +1,215 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{metrics::CompressionClient, CompressionError};
+use aptos_infallible::RwLock;
+use once_cell::sync::Lazy;
+use std::{collections::HashMap, fmt, path::Path, sync::Arc};
+use zstd::dict::{DecoderDictionary, EncoderDictionary};
+
+/// The magic number that prefixes all (non-raw) zstd dictionaries.
+/// See: <https://github.com/facebook/zstd/blob/dev/doc/zstd_compression_format.md#dictionary-format>
+const ZSTD_DICTIONARY_MAGIC: u32 = 0xEC30_A437;
+
+/// The magic number that prefixes all zstd frames.
+/// See: <https://github.com/facebook/zstd/blob/dev/doc/zstd_compression_format.md#zstandard-frames>
+const ZSTD_FRAME_MAGIC: u32 = 0xFD2F_B528;
+
+/// The registry of dictionaries known to this process. Compression
+/// dictionaries are selected per client, while decompression dictionaries
+/// are selected by the dictionary ID embedded in each zstd frame. This
+/// allows a dictionary to be rotated without breaking peers (or backups)
+/// that still use the previous one.
+static DICTIONARIES: Lazy<RwLock<DictionaryRegistry>> =
+    Lazy::new(|| RwLock::new(DictionaryRegistry::default()));
+
+#[derive(Default)]
+struct DictionaryRegistry {
+    compression_dictionaries: HashMap<&'static str, Arc<CompressionDictionary>>,
+    decompression_dictionaries: HashMap<u32, Arc<CompressionDictionary>>,
+}
+
+/// A pre-trained zstd dictionary. Dictionaries substantially improve the
+/// compression ratio of small, similarly structured payloads (e.g., BCS
+/// encoded transactions and votes).
+pub struct CompressionDictionary {
+    id: u32,
+    bytes: Vec<u8>,
+    encoder_dictionary: EncoderDictionary<'static>,
+    decoder_dictionary: DecoderDictionary<'static>,
+}
+
+impl CompressionDictionary {
+    /// Creates a dictionary from the given (trained) dictionary bytes.
+    /// Raw content dictionaries are not supported, as they carry no
+    /// dictionary ID for peers to identify them by.
+    pub fn new(bytes: Vec<u8>) -> Result<Self, CompressionError> {
+        let id = match read_u32_le(&bytes, 0) {
+            Some(ZSTD_DICTIONARY_MAGIC) => read_u32_le(&bytes, 4).unwrap_or(0),
+            _ => {
+                return Err(CompressionError(
+                    "The dictionary is missing the zstd dictionary magic number!".into(),
+                ))
+            },
+        };
+        if id == 0 {
+            return Err(CompressionError(
+                "The dictionary must have a non-zero dictionary ID!".into(),
+            ));
+        }
+
+        let encoder_dictionary = EncoderDictionary::copy(&bytes, crate::ZSTD_COMPRESSION_LEVEL);
+        let decoder_dictionary = DecoderDictionary::copy(&bytes);
+        Ok(Self {
+            id,
+            bytes,
+            encoder_dictionary,
+            decoder_dictionary,
+        })
+    }
+
+    /// Loads a dictionary from the given file
+    pub fn load(path: &Path) -> Result<Self, CompressionError> {
+        let bytes = std::fs::read(path).map_err(|error| {
+            CompressionError(format!(
+                "Failed to read the dictionary file {:?}: {}",
+                path, error
+            ))
+        })?;
+        Self::new(bytes)
+    }
+
+    /// Trains a new dictionary (of at most `max_size` bytes) from the given samples
+    pub fn train<S: AsRef<[u8]>>(samples: &[S], max_size: usize) -> Result<Self, CompressionError> {
+        let bytes = zstd::dict::from_samples(samples, max_size).map_err(|error| {
+            CompressionError(format!("Failed to train the dictionary: {}", error))
+        })?;
+        Self::new(bytes)
+    }
+
+    /// Returns the ID of the dictionary
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+
+    /// Returns the raw dictionary bytes (e.g., to write them to a file)
+    pub fn bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    pub(crate) fn encoder_dictionary(&self) -> &EncoderDictionary<'static> {
+        &self.encoder_dictionary
+    }
+
+    pub(crate) fn decoder_dictionary(&self) -> &DecoderDictionary<'static> {
+        &self.decoder_dictionary
+    }
+}
+
+impl fmt::Debug for CompressionDictionary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CompressionDictionary")
+            .field("id", &self.id)
+            .field("size", &self.bytes.len())
+            .finish()
+    }
+}
+
+/// Sets the dictionary used by the given client to compress data. The
+/// dictionary is also made available for decompression.
+pub fn set_compression_dictionary(
+    client: CompressionClient,
+    dictionary: Arc<CompressionDictionary>,
+) {
+    let mut registry = DICTIONARIES.write();
+    registry
+        .decompression_dictionaries
+        .insert(dictionary.id(), dictionary.clone());
+    registry
+        .compression_dictionaries
+        .insert(client.get_label(), dictionary);
+}
+
+/// Makes the given dictionary available for decompression (only)
+pub fn add_decompression_dictionary(dictionary: Arc<CompressionDictionary>) {
+    DICTIONARIES
+        .write()
+        .decompression_dictionaries
+        .insert(dictionary.id(), dictionary);
+}
+
+/// Returns the ID of the dictionary used by the given client to compress data (if any)
+pub fn get_compression_dictionary_id(client: &CompressionClient) -> Option<u32> {
+    get_compression_dictionary(client).map(|dictionary| dictionary.id())
+}
+
+/// Returns the IDs of all dictionaries available for decompression (sorted)
+pub fn get_decompression_dictionary_ids() -> Vec<u32> {
+    let mut dictionary_ids: Vec<u32> = DICTIONARIES
+        .read()
+        .decompression_dictionaries
+        .keys()
+        .copied()
+        .collect();
+    dictionary_ids.sort_unstable();
+    dictionary_ids
+}
+
+/// Returns the dictionary used by the given client to compress data (if any)
+pub(crate) fn get_compression_dictionary(
+    client: &CompressionClient,
+) -> Option<Arc<CompressionDictionary>> {
+    DICTIONARIES
+        .read()
+        .compression_dictionaries
+        .get(client.get_label())
+        .cloned()
+}
+
+/// Returns the decompression dictionary with the given ID (if any)
+pub(crate) fn get_decompression_dictionary(id: u32) -> Option<Arc<CompressionDictionary>> {
+    DICTIONARIES
+        .read()
+        .decompression_dictionaries
+        .get(&id)
+        .cloned()
+}
+
+/// Returns true iff the given data starts with a zstd frame
+pub fn is_zstd_frame(data: &[u8]) -> bool {
+    read_u32_le(data, 0) == Some(ZSTD_FRAME_MAGIC)
+}
+
+/// Returns the dictionary ID of the given zstd frame (or 0 if the frame does
+/// not specify a dictionary). Returns None if the frame header is malformed.
+pub(crate) fn get_frame_dictionary_id(data: &[u8]) -> Option<u32> {
+    if !is_zstd_frame(data) {
+        return None;
+    }
+
+    // Parse the frame header descriptor
+    let descriptor = *data.get(4)?;
+    let single_segment = descriptor & 0x20 != 0;
+    let dictionary_id_size = match descriptor & 0x03 {
+        0 => 0,
+        1 => 1,
+        2 => 2,
+        _ => 4,
+    };
+
+    // The dictionary ID follows the window descriptor (if any)
+    let offset = if single_segment { 5 } else { 6 };
+    let id_bytes = data.get(offset..offset + dictionary_id_size)?;
+    Some(
+        id_bytes
+            .iter()
+            .rev()
+            .fold(0u32, |id, byte| (id << 8) | *byte as u32),
+    )
+}
+
+/// Reads a little-endian u32 at the given offset
+fn read_u32_le(data: &[u8], offset: usize) -> Option<u32> {
+    let bytes = data.get(offset..offset + 4)?;
+    Some(u32::from_le_bytes(bytes.try_into().ok()?))
+}
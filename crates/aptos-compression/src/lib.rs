@@ -7,8 +7,12 @@ use crate::metrics::{
     RAW_BYTES,
 };
 use aptos_logger::prelude::*;
+use dictionary::CompressionDictionary;
 use lz4::block::CompressionMode;
-use std::io::{Error, ErrorKind};
+use std::{
+    io::{Error, ErrorKind, Read},
+    sync::Arc,
+};
 use thiserror::Error;
 
 /// This crate provides a simple library interface for data compression.
@@ -17,9 +21,15 @@ use thiserror::Error;
 /// Internally, it uses LZ4 in fast mode to compress the data.
 /// See <https://github.com/10xGenomics/lz4-rs> for more information.
 ///
+/// The crate also offers a zstd codec (see `zstd_compress`,
+/// `zstd_compress_with_dictionary` and `zstd_decompress`) that can
+/// use pre-trained dictionaries to improve the compression ratio of
+/// small payloads. Dictionaries are managed by the `dictionary` module.
+///
 /// Note: the crate also exposes some basic compression metrics
 /// that can be used to track the cumulative compression ratio
 /// and compression/decompression durations during the runtime.
+pub mod dictionary;
 pub mod metrics;
 #[cfg(test)]
 mod tests;
@@ -28,6 +38,10 @@ mod tests;
 /// This was determined anecdotally.
 const ACCELERATION_PARAMETER: i32 = 1;
 
+/// The zstd compression level to use. This is the zstd default,
+/// which offers a good trade-off between speed and ratio.
+const ZSTD_COMPRESSION_LEVEL: i32 = 3;
+
 /// A useful wrapper for representing compressed data
 pub type CompressedData = Vec<u8>;
 
@@ -139,6 +153,177 @@ pub fn decompress(
     Ok(raw_data)
 }
 
+/// Compresses the raw data stream using zstd (without a dictionary)
+pub fn zstd_compress(
+    raw_data: Vec<u8>,
+    client: CompressionClient,
+    max_bytes: usize,
+) -> Result<CompressedData, CompressionError> {
+    compress_zstd(raw_data, client, max_bytes, None)
+}
+
+/// Compresses the raw data stream using zstd and the dictionary set for the
+/// client (see `dictionary::set_compression_dictionary`), if any. The frame
+/// records the dictionary ID, so the data can only be decompressed by those
+/// that know the dictionary (e.g., peers that have advertised it).
+pub fn zstd_compress_with_dictionary(
+    raw_data: Vec<u8>,
+    client: CompressionClient,
+    max_bytes: usize,
+) -> Result<CompressedData, CompressionError> {
+    let dictionary = dictionary::get_compression_dictionary(&client);
+    compress_zstd(raw_data, client, max_bytes, dictionary)
+}
+
+/// Compresses the raw data stream using zstd and the given dictionary (if any)
+fn compress_zstd(
+    raw_data: Vec<u8>,
+    client: CompressionClient,
+    max_bytes: usize,
+    dictionary: Option<Arc<CompressionDictionary>>,
+) -> Result<CompressedData, CompressionError> {
+    if raw_data.len() > max_bytes {
+        return Err(CompressionError(format!(
+            "Uncompressed size greater than max. size: {}, max: {}",
+            raw_data.len(),
+            max_bytes
+        )));
+    }
+    // Start the compression timer
+    let timer = start_compression_operation_timer(COMPRESS, client.clone());
+
+    // Compress the data (using the dictionary, if any)
+    let compression_result = match dictionary {
+        Some(dictionary) => {
+            zstd::bulk::Compressor::with_prepared_dictionary(dictionary.encoder_dictionary())
+                .and_then(|mut compressor| compressor.compress(&raw_data))
+        },
+        None => zstd::bulk::compress(&raw_data, ZSTD_COMPRESSION_LEVEL),
+    };
+    let compressed_data = match compression_result {
+        Ok(compressed_data) => compressed_data,
+        Err(error) => {
+            increment_compression_error(COMPRESS, client);
+            return Err(CompressionError(format!(
+                "Failed to compress the data: {}",
+                error
+            )));
+        },
+    };
+
+    // Ensure that the compressed data size is not greater than the max bytes limit
+    if compressed_data.len() > max_bytes {
+        return Err(CompressionError(format!(
+            "Compressed size greater than max. size: {}, max: {}",
+            compressed_data.len(),
+            max_bytes
+        )));
+    }
+
+    // Stop the timer and update the metrics
+    let compression_duration = timer.stop_and_record();
+    increment_compression_byte_count(RAW_BYTES, client.clone(), raw_data.len() as u64);
+    increment_compression_byte_count(COMPRESSED_BYTES, client, compressed_data.len() as u64);
+
+    // Log the relative data compression statistics
+    let relative_data_size = calculate_relative_size(&raw_data, &compressed_data);
+    trace!(
+        "Zstd compressed {} bytes to {} bytes ({} %) in {} seconds.",
+        raw_data.len(),
+        compressed_data.len(),
+        relative_data_size,
+        compression_duration
+    );
+
+    Ok(compressed_data)
+}
+
+/// Decompresses the zstd compressed data stream. The dictionary is
+/// selected using the dictionary ID recorded in the frame header, and
+/// decompression fails if the dictionary is unknown or the decompressed
+/// data exceeds the max size.
+pub fn zstd_decompress(
+    compressed_data: &CompressedData,
+    client: CompressionClient,
+    max_size: usize,
+) -> Result<Vec<u8>, CompressionError> {
+    // Start the decompression timer
+    let timer = start_compression_operation_timer(DECOMPRESS, client.clone());
+
+    // Decompress the data
+    let raw_data = match decompress_zstd_frames(compressed_data, max_size) {
+        Ok(raw_data) => raw_data,
+        Err(error) => {
+            increment_compression_error(DECOMPRESS, client);
+            return Err(error);
+        },
+    };
+
+    // Stop the timer and log the relative data compression statistics
+    let decompression_duration = timer.stop_and_record();
+    let relative_data_size = calculate_relative_size(compressed_data, &raw_data);
+    trace!(
+        "Zstd decompressed {} bytes to {} bytes ({} %) in {} seconds.",
+        compressed_data.len(),
+        raw_data.len(),
+        relative_data_size,
+        decompression_duration
+    );
+
+    Ok(raw_data)
+}
+
+/// Decompresses the given zstd frames, failing if the output exceeds the max size.
+/// Note: the frames are streamed so that we never allocate more than the max size
+/// (the content size declared in the frame header cannot be trusted).
+fn decompress_zstd_frames(
+    compressed_data: &[u8],
+    max_size: usize,
+) -> Result<Vec<u8>, CompressionError> {
+    // Identify the dictionary used to compress the data (if any)
+    let dictionary_id = dictionary::get_frame_dictionary_id(compressed_data)
+        .ok_or_else(|| CompressionError("The data is not a valid zstd frame!".into()))?;
+    let dictionary = if dictionary_id == 0 {
+        None
+    } else {
+        Some(
+            dictionary::get_decompression_dictionary(dictionary_id).ok_or_else(|| {
+                CompressionError(format!(
+                    "The data was compressed using an unknown dictionary: {}",
+                    dictionary_id
+                ))
+            })?,
+        )
+    };
+
+    // Create the decoder
+    let decoder_result = match &dictionary {
+        Some(dictionary) => zstd::stream::read::Decoder::with_prepared_dictionary(
+            compressed_data,
+            dictionary.decoder_dictionary(),
+        ),
+        None => zstd::stream::read::Decoder::with_buffer(compressed_data),
+    };
+    let decoder = decoder_result.map_err(|error| {
+        CompressionError(format!("Failed to create the zstd decoder: {}", error))
+    })?;
+
+    // Decompress the data (reading at most one byte past the max size)
+    let mut raw_data = vec![];
+    decoder
+        .take((max_size as u64).saturating_add(1))
+        .read_to_end(&mut raw_data)
+        .map_err(|error| CompressionError(format!("Failed to decompress the data: {}", error)))?;
+    if raw_data.len() > max_size {
+        return Err(CompressionError(format!(
+            "Decompressed size greater than max. size: max: {}",
+            max_size
+        )));
+    }
+
+    Ok(raw_data)
+}
+
 /// Derived from lz4-rs crate, which starts the compressed payload with the original data size as i32
 /// see: https://github.com/10XGenomics/lz4-rs/blob/0abc0a52af1f6010f9a57640b1dc8eb8d2d697aa/src/block/mod.rs#L162
 fn get_decompressed_size(src: &CompressedData, max_size: usize) -> std::io::Result<usize> {
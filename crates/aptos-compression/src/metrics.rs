@@ -17,7 +17,9 @@ pub const RAW_BYTES: &str = "raw_bytes";
 /// each client.
 #[derive(Clone, Debug)]
 pub enum CompressionClient {
+    Backup,
     Consensus,
+    ConsensusObserver,
     Mempool,
     StateSync,
 }
@@ -26,7 +28,9 @@ impl CompressionClient {
     /// Returns a summary label for the request
     pub fn get_label(&self) -> &'static str {
         match self {
+            Self::Backup => "backup",
             Self::Consensus => "consensus",
+            Self::ConsensusObserver => "consensus_observer",
             Self::Mempool => "mempool",
             Self::StateSync => "state_sync",
         }
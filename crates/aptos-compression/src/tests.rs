@@ -1,7 +1,7 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::CompressionClient;
+use crate::{dictionary, dictionary::CompressionDictionary, CompressionClient};
 use aptos_crypto::{ed25519::Ed25519PrivateKey, hash::HashValue, PrivateKey, SigningKey, Uniform};
 use aptos_types::{
     account_address::AccountAddress,
@@ -17,7 +17,7 @@ use aptos_types::{
     write_set::WriteSet,
 };
 use serde::{de::DeserializeOwned, Serialize};
-use std::fmt::Debug;
+use std::{fmt::Debug, sync::Arc};
 
 const MAX_COMPRESSION_SIZE: usize = 64 * 1024 * 1024;
 
@@ -66,6 +66,144 @@ fn test_compression_limits() {
     assert!(maybe_decompressed_bytes.is_err());
 }
 
+#[test]
+fn test_zstd_compression() {
+    // Test epoch ending ledger infos
+    let epoch_ending_ledger_infos = create_epoch_ending_ledger_infos(0, 999);
+    test_zstd_compress_and_decompress(epoch_ending_ledger_infos, CompressionClient::StateSync);
+
+    // Test transactions with proof
+    let transactions_with_proof = create_transaction_list_with_proof(1000, 1999, 1999, true);
+    test_zstd_compress_and_decompress(transactions_with_proof, CompressionClient::StateSync);
+}
+
+#[test]
+fn test_zstd_compression_limits() {
+    let too_small_bytes = 1;
+    let transactions_with_proof = create_transaction_list_with_proof(1000, 1999, 1999, true);
+
+    // Test compression limit
+    let bcs_encoded_bytes = bcs::to_bytes(&transactions_with_proof).unwrap();
+    let maybe_compressed_bytes = crate::zstd_compress(
+        bcs_encoded_bytes,
+        CompressionClient::StateSync,
+        too_small_bytes,
+    );
+    assert!(maybe_compressed_bytes.is_err());
+
+    // Test decompression limit
+    let bcs_encoded_bytes = bcs::to_bytes(&transactions_with_proof).unwrap();
+    let compressed_bytes = crate::zstd_compress(
+        bcs_encoded_bytes.clone(),
+        CompressionClient::StateSync,
+        MAX_COMPRESSION_SIZE,
+    )
+    .unwrap();
+    let maybe_decompressed_bytes = crate::zstd_decompress(
+        &compressed_bytes,
+        CompressionClient::StateSync,
+        bcs_encoded_bytes.len() - 1,
+    );
+    assert!(maybe_decompressed_bytes.is_err());
+
+    // Test that garbage data is rejected
+    let maybe_decompressed_bytes = crate::zstd_decompress(
+        &bcs_encoded_bytes,
+        CompressionClient::StateSync,
+        MAX_COMPRESSION_SIZE,
+    );
+    assert!(maybe_decompressed_bytes.is_err());
+}
+
+#[test]
+fn test_zstd_dictionary_compression() {
+    // Train a dictionary on BCS encoded transactions
+    let samples = create_transaction_samples(0, 2000);
+    let dictionary = CompressionDictionary::train(&samples, 16 * 1024).unwrap();
+    assert_ne!(dictionary.id(), 0);
+
+    // Verify the dictionary can be reloaded from its bytes
+    let reloaded_dictionary = CompressionDictionary::new(dictionary.bytes().to_vec()).unwrap();
+    assert_eq!(reloaded_dictionary.id(), dictionary.id());
+
+    // Compress a small payload without the dictionary
+    let payload = bcs::to_bytes(&create_test_transaction(5000)).unwrap();
+    let compressed_without_dictionary = crate::zstd_compress(
+        payload.clone(),
+        CompressionClient::Mempool,
+        MAX_COMPRESSION_SIZE,
+    )
+    .unwrap();
+
+    // Set the dictionary and compress the payload again (with and without the dictionary)
+    let dictionary_id = dictionary.id();
+    dictionary::set_compression_dictionary(CompressionClient::Mempool, Arc::new(dictionary));
+    assert_eq!(
+        dictionary::get_compression_dictionary_id(&CompressionClient::Mempool),
+        Some(dictionary_id)
+    );
+    assert!(dictionary::get_decompression_dictionary_ids().contains(&dictionary_id));
+    let compressed_with_dictionary = crate::zstd_compress_with_dictionary(
+        payload.clone(),
+        CompressionClient::Mempool,
+        MAX_COMPRESSION_SIZE,
+    )
+    .unwrap();
+    assert!(compressed_with_dictionary.len() < compressed_without_dictionary.len());
+    assert_eq!(
+        crate::zstd_compress(
+            payload.clone(),
+            CompressionClient::Mempool,
+            MAX_COMPRESSION_SIZE,
+        )
+        .unwrap(),
+        compressed_without_dictionary
+    );
+
+    // Verify both payloads can be decompressed
+    for compressed_bytes in [compressed_without_dictionary, compressed_with_dictionary] {
+        let decompressed_bytes = crate::zstd_decompress(
+            &compressed_bytes,
+            CompressionClient::Mempool,
+            MAX_COMPRESSION_SIZE,
+        )
+        .unwrap();
+        assert_eq!(decompressed_bytes, payload);
+    }
+}
+
+#[test]
+fn test_zstd_unknown_dictionary() {
+    // Train a dictionary, but don't register it
+    let samples = create_transaction_samples(0, 2000);
+    let dictionary = CompressionDictionary::train(&samples, 16 * 1024).unwrap();
+
+    // Compress a payload using the dictionary
+    let payload = bcs::to_bytes(&create_test_transaction(5000)).unwrap();
+    let compressed_bytes = zstd::bulk::Compressor::with_dictionary(3, dictionary.bytes())
+        .unwrap()
+        .compress(&payload)
+        .unwrap();
+
+    // Verify decompression fails because the dictionary is unknown
+    let maybe_decompressed_bytes = crate::zstd_decompress(
+        &compressed_bytes,
+        CompressionClient::StateSync,
+        MAX_COMPRESSION_SIZE,
+    );
+    assert!(maybe_decompressed_bytes.is_err());
+
+    // Register the dictionary for decompression and verify decompression succeeds
+    dictionary::add_decompression_dictionary(Arc::new(dictionary));
+    let decompressed_bytes = crate::zstd_decompress(
+        &compressed_bytes,
+        CompressionClient::StateSync,
+        MAX_COMPRESSION_SIZE,
+    )
+    .unwrap();
+    assert_eq!(decompressed_bytes, payload);
+}
+
 /// Ensures that the given object can be compressed and decompressed successfully
 /// when BCS encoded.
 fn test_compress_and_decompress<T: Debug + DeserializeOwned + PartialEq + Serialize>(object: T) {
@@ -87,6 +225,29 @@ fn test_compress_and_decompress<T: Debug + DeserializeOwned + PartialEq + Serial
     assert_eq!(object, decoded_object);
 }
 
+/// Ensures that the given object can be compressed and decompressed successfully
+/// using zstd when BCS encoded.
+fn test_zstd_compress_and_decompress<T: Debug + DeserializeOwned + PartialEq + Serialize>(
+    object: T,
+    client: CompressionClient,
+) {
+    let bcs_encoded_bytes = bcs::to_bytes(&object).unwrap();
+    let compressed_bytes =
+        crate::zstd_compress(bcs_encoded_bytes, client.clone(), MAX_COMPRESSION_SIZE).unwrap();
+    let decompressed_bytes =
+        crate::zstd_decompress(&compressed_bytes, client, MAX_COMPRESSION_SIZE).unwrap();
+    let decoded_object = bcs::from_bytes::<T>(&decompressed_bytes).unwrap();
+
+    assert_eq!(object, decoded_object);
+}
+
+/// Creates BCS encoded test transactions (e.g., for dictionary training)
+fn create_transaction_samples(start_version: u64, end_version: u64) -> Vec<Vec<u8>> {
+    (start_version..end_version)
+        .map(|sequence_number| bcs::to_bytes(&create_test_transaction(sequence_number)).unwrap())
+        .collect()
+}
+
 /// Creates a test epoch change proof
 fn create_epoch_ending_ledger_infos(
     start_epoch: u64,
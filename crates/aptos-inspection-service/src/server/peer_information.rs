@@ -113,5 +113,18 @@ fn get_peer_information(peers_and_metadata: Arc<PeersAndMetadata>) -> String {
         }
     }
 
+    // Display the peers banned for a low reputation
+    peer_information.push("Banned peers:".into());
+    for banned_peer in peers_and_metadata.get_banned_peers() {
+        peer_information.push(format!(
+            "\t- Peer: {}, score: {:.2}, remaining ban duration: {:?}, number of bans: {}",
+            banned_peer.peer_network_id,
+            banned_peer.score,
+            banned_peer.remaining_ban_duration,
+            banned_peer.num_bans
+        ));
+    }
+    peer_information.push("\n".into());
+
     peer_information.join("\n") // Separate each entry with a newline
 }
@@ -13,7 +13,7 @@ use std::{
     convert::TryFrom,
     fs::File,
     io::{BufRead, BufReader},
-    path::Path,
+    path::{Path, PathBuf},
 };
 use url::Url;
 
@@ -158,6 +158,16 @@ pub struct EmitArgs {
     #[clap(long, num_args = 0..)]
     pub transaction_phases: Vec<usize>,
 
+    /// YAML file defining the transaction mix of every phase, and optionally their durations.
+    /// Replaces the transaction type, weights and phases arguments.
+    /// See `aptos_transaction_generator_lib::workload` for the format.
+    #[clap(
+        long,
+        value_parser,
+        conflicts_with_all = ["transaction_type", "transaction_weights", "transaction_phases"]
+    )]
+    pub workload_file: Option<PathBuf>,
+
     #[clap(long)]
     pub gas_price: Option<u64>,
 
@@ -120,6 +120,8 @@ pub struct EmitJobRequest {
     mode: EmitJobMode,
 
     transaction_mix_per_phase: Vec<Vec<(TransactionType, usize)>>,
+    /// Explicit durations of the phases, the phases without one share the rest of the duration.
+    phase_durations: Vec<Option<Duration>>,
 
     max_gas_per_txn: u64,
     gas_price: u64,
@@ -152,6 +154,7 @@ impl Default for EmitJobRequest {
                 mempool_backlog: 3000,
             },
             transaction_mix_per_phase: vec![vec![(TransactionType::default(), 1)]],
+            phase_durations: Vec::new(),
             max_gas_per_txn: aptos_global_constants::MAX_GAS_AMOUNT,
             gas_price: aptos_global_constants::GAS_UNIT_PRICE,
             init_gas_price_multiplier: 10,
@@ -228,6 +231,11 @@ impl EmitJobRequest {
         self
     }
 
+    pub fn phase_durations(mut self, phase_durations: Vec<Option<Duration>>) -> Self {
+        self.phase_durations = phase_durations;
+        self
+    }
+
     pub fn get_num_phases(&self) -> usize {
         self.transaction_mix_per_phase.len()
     }
@@ -706,6 +714,8 @@ impl TxnEmitter {
         print_stats_interval: Option<u64>,
     ) -> Result<TxnStats> {
         let phases = emit_job_request.transaction_mix_per_phase.len();
+        let phase_durations =
+            split_duration_between_phases(duration, phases, &emit_job_request.phase_durations);
 
         let mut job = self
             .start_job(source_account, emit_job_request, phases)
@@ -716,16 +726,15 @@ impl TxnEmitter {
             phases
         );
 
-        let per_phase_duration = duration.checked_div(phases as u32).unwrap();
-        for phase in 0..phases {
+        for (phase, phase_duration) in phase_durations.into_iter().enumerate() {
             if phase > 0 {
                 info!("Starting next phase");
                 job.start_next_phase();
             }
             if let Some(interval_secs) = print_stats_interval {
-                job.periodic_stat(per_phase_duration, interval_secs).await;
+                job.periodic_stat(phase_duration, interval_secs).await;
             } else {
-                time::sleep(per_phase_duration).await;
+                time::sleep(phase_duration).await;
             }
         }
         info!("Ran for {} secs, stopping job...", duration.as_secs());
@@ -975,6 +984,30 @@ async fn get_account_if_exists(
     }
 }
 
+/// Phases with an explicit duration run for it, and the remaining duration is split evenly
+/// between the other phases.
+fn split_duration_between_phases(
+    duration: Duration,
+    phases: usize,
+    phase_durations: &[Option<Duration>],
+) -> Vec<Duration> {
+    let explicit = (0..phases).filter_map(|phase| phase_durations.get(phase).copied().flatten());
+    let num_implicit = (phases - explicit.clone().count()) as u32;
+    let implicit_duration = duration
+        .saturating_sub(explicit.sum())
+        .checked_div(num_implicit)
+        .unwrap_or_default();
+    (0..phases)
+        .map(|phase| {
+            phase_durations
+                .get(phase)
+                .copied()
+                .flatten()
+                .unwrap_or(implicit_duration)
+        })
+        .collect()
+}
+
 pub fn gen_transfer_txn_request(
     sender: &mut LocalAccount,
     receiver: &AccountAddress,
@@ -985,3 +1018,25 @@ pub fn gen_transfer_txn_request(
         txn_factory.payload(aptos_stdlib::aptos_coin_transfer(*receiver, num_coins)),
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_duration_between_phases() {
+        let secs = Duration::from_secs;
+        assert_eq!(
+            split_duration_between_phases(secs(60), 3, &[]),
+            vec![secs(20), secs(20), secs(20)]
+        );
+        assert_eq!(
+            split_duration_between_phases(secs(60), 3, &[Some(secs(30)), None, None]),
+            vec![secs(30), secs(15), secs(15)]
+        );
+        assert_eq!(
+            split_duration_between_phases(secs(60), 2, &[Some(secs(10)), Some(secs(100))]),
+            vec![secs(10), secs(100)]
+        );
+    }
+}
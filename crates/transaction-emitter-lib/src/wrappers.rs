@@ -10,7 +10,7 @@ use crate::{
 use anyhow::{bail, Context, Result};
 use aptos_logger::{error, info};
 use aptos_sdk::transaction_builder::TransactionFactory;
-use aptos_transaction_generator_lib::args::TransactionTypeArg;
+use aptos_transaction_generator_lib::{args::TransactionTypeArg, workload::WorkloadDefinition};
 use rand::{rngs::StdRng, SeedableRng};
 use std::time::{Duration, Instant};
 
@@ -83,17 +83,32 @@ pub async fn emit_transactions_with_cluster(
         StdRng::from_entropy(),
     );
 
-    let transaction_mix_per_phase = TransactionTypeArg::args_to_transaction_mix_per_phase(
-        &args.transaction_type,
-        &args.transaction_weights,
-        &args.transaction_phases,
-        args.module_working_set_size.unwrap_or(1),
-        args.sender_use_account_pool.unwrap_or(false),
-    );
+    let (transaction_mix_per_phase, phase_durations) = match &args.workload_file {
+        Some(workload_file) => {
+            let workload = WorkloadDefinition::load(workload_file)?;
+            let phase_durations = workload
+                .phases
+                .iter()
+                .map(|phase| phase.duration_secs.map(Duration::from_secs))
+                .collect();
+            (workload.transaction_mix_per_phase(), phase_durations)
+        },
+        None => (
+            TransactionTypeArg::args_to_transaction_mix_per_phase(
+                &args.transaction_type,
+                &args.transaction_weights,
+                &args.transaction_phases,
+                args.module_working_set_size.unwrap_or(1),
+                args.sender_use_account_pool.unwrap_or(false),
+            ),
+            Vec::new(),
+        ),
+    };
     let mut emit_job_request =
         EmitJobRequest::new(cluster.all_instances().map(Instance::rest_client).collect())
             .mode(emitter_mode)
             .transaction_mix_per_phase(transaction_mix_per_phase)
+            .phase_durations(phase_durations)
             .txn_expiration_time_secs(args.txn_expiration_time_secs)
             .coordination_delay_between_instances(Duration::from_secs(
                 args.coordination_delay_between_instances.unwrap_or(0),
@@ -32,6 +32,7 @@ mod p2p_transaction_generator;
 pub mod publish_modules;
 mod publishing;
 mod transaction_mix_generator;
+pub mod workload;
 use self::{
     account_generator::AccountGeneratorCreator,
     call_custom_modules::CustomModulesDelegationGeneratorCreator,
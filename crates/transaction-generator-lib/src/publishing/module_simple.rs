@@ -17,6 +17,7 @@ use move_binary_format::{
 };
 use rand::{distributions::Alphanumeric, prelude::StdRng, seq::SliceRandom, Rng};
 use rand_core::RngCore;
+use serde::{Deserialize, Serialize};
 
 //
 // Contains all the code to work on the Simple package
@@ -96,7 +97,7 @@ pub enum MultiSigConfig {
 // List of entry points to expose
 //
 // More info in the Simple.move
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, Deserialize, Serialize)]
 pub enum EntryPoints {
     // 0 args
     /// Empty (NoOp) function
@@ -0,0 +1,282 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Declarative workload definitions, loaded from YAML files, so that the same traffic shape can
+//! be replayed by both the executor benchmark and the transaction emitter. For example:
+//!
+//! ```yaml
+//! module_working_set_size: 10
+//! sender_distribution:
+//!   zipf:
+//!     exponent: 1.1
+//! phases:
+//!   - name: publish
+//!     num_blocks: 5
+//!     mix:
+//!       - transaction_type: PublishPackage
+//!   - name: traffic
+//!     block_size: 5000
+//!     duration_secs: 600
+//!     mix:
+//!       - transaction_type: CoinTransfer
+//!         weight: 7
+//!       - entry_point:
+//!           BytesMakeOrChange:
+//!             data_length: 32
+//!         weight: 2
+//!       - entry_point: TokenV1MintAndStoreNFTParallel
+//! ```
+//!
+//! `transaction_type` takes the predefined workloads of `TransactionTypeArg`, while `entry_point`
+//! calls any of the `EntryPoints` of the published test modules.
+
+use crate::{args::TransactionTypeArg, EntryPoints, TransactionType};
+use anyhow::{bail, Context, Result};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct WorkloadDefinition {
+    /// Number of copies of the modules to publish, under separate accounts.
+    #[serde(default = "default_module_working_set_size")]
+    pub module_working_set_size: usize,
+    /// Whether to use burner accounts for the sender.
+    #[serde(default)]
+    pub sender_use_account_pool: bool,
+    /// How the senders of the transactions are picked from the available accounts.
+    /// Only used by the executor benchmark, the emitter spreads load over its worker accounts.
+    #[serde(default)]
+    pub sender_distribution: AccountDistribution,
+    pub phases: Vec<WorkloadPhase>,
+}
+
+fn default_module_working_set_size() -> usize {
+    1
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct WorkloadPhase {
+    #[serde(default)]
+    pub name: Option<String>,
+    pub mix: Vec<WorkloadMixEntry>,
+    /// Block size of the phase in the executor benchmark, defaults to the benchmark's.
+    #[serde(default)]
+    pub block_size: Option<usize>,
+    /// Number of blocks of the phase in the executor benchmark, defaults to the benchmark's.
+    #[serde(default)]
+    pub num_blocks: Option<usize>,
+    /// Duration of the phase in the transaction emitter, defaults to an even split of the total
+    /// duration.
+    #[serde(default)]
+    pub duration_secs: Option<u64>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct WorkloadMixEntry {
+    #[serde(flatten)]
+    pub transaction: WorkloadTransaction,
+    #[serde(default = "default_weight")]
+    pub weight: usize,
+}
+
+fn default_weight() -> usize {
+    1
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WorkloadTransaction {
+    TransactionType(TransactionTypeArg),
+    EntryPoint(EntryPoints),
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AccountDistribution {
+    /// Every account is equally likely to be picked.
+    #[default]
+    Uniform,
+    /// The account of rank `k` is picked with a probability proportional to `1 / k^exponent`,
+    /// creating hotspots on the first accounts.
+    Zipf { exponent: f64 },
+}
+
+impl AccountDistribution {
+    pub fn sampler(&self, num_accounts: usize) -> AccountSampler {
+        match self {
+            AccountDistribution::Uniform => AccountSampler::Uniform { num_accounts },
+            AccountDistribution::Zipf { exponent } => {
+                let mut total = 0.0;
+                let cumulative_weights = (1..=num_accounts)
+                    .map(|rank| {
+                        total += 1.0 / (rank as f64).powf(*exponent);
+                        total
+                    })
+                    .collect();
+                AccountSampler::Zipf { cumulative_weights }
+            },
+        }
+    }
+}
+
+/// Picks the indices of the senders of a block among `num_accounts` accounts.
+pub enum AccountSampler {
+    Uniform { num_accounts: usize },
+    Zipf { cumulative_weights: Vec<f64> },
+}
+
+impl AccountSampler {
+    /// Uniform sampling returns distinct indices, while Zipf sampling returns hot accounts
+    /// multiple times.
+    pub fn sample_indices(&self, rng: &mut impl Rng, count: usize) -> Vec<usize> {
+        match self {
+            AccountSampler::Uniform { num_accounts } => {
+                rand::seq::index::sample(rng, *num_accounts, count).into_vec()
+            },
+            AccountSampler::Zipf { cumulative_weights } => {
+                let total = *cumulative_weights.last().expect("No accounts to sample");
+                (0..count)
+                    .map(|_| {
+                        let target = rng.gen::<f64>() * total;
+                        cumulative_weights
+                            .partition_point(|weight| *weight <= target)
+                            .min(cumulative_weights.len() - 1)
+                    })
+                    .collect()
+            },
+        }
+    }
+}
+
+impl WorkloadDefinition {
+    pub fn load(path: &Path) -> Result<Self> {
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read workload file {}", path.display()))?;
+        let workload: Self = serde_yaml::from_str(&contents)
+            .with_context(|| format!("Failed to parse workload file {}", path.display()))?;
+        workload.validate()?;
+        Ok(workload)
+    }
+
+    fn validate(&self) -> Result<()> {
+        if self.phases.is_empty() {
+            bail!("Workload has no phases");
+        }
+        for (idx, phase) in self.phases.iter().enumerate() {
+            if phase.mix.is_empty() {
+                bail!("Phase {} has an empty transaction mix", idx);
+            }
+            if phase.mix.iter().all(|entry| entry.weight == 0) {
+                bail!("Phase {} only has zero weights", idx);
+            }
+            if phase.block_size == Some(0) {
+                bail!("Phase {} has a zero block size", idx);
+            }
+        }
+        if let AccountDistribution::Zipf { exponent } = self.sender_distribution {
+            if exponent <= 0.0 {
+                bail!("Zipf exponent must be positive, got {}", exponent);
+            }
+        }
+        Ok(())
+    }
+
+    /// The transaction mix of every phase, in the format of `create_txn_generator_creator`.
+    pub fn transaction_mix_per_phase(&self) -> Vec<Vec<(TransactionType, usize)>> {
+        self.phases
+            .iter()
+            .map(|phase| {
+                phase
+                    .mix
+                    .iter()
+                    .map(|entry| (self.materialize(&entry.transaction), entry.weight))
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn materialize(&self, transaction: &WorkloadTransaction) -> TransactionType {
+        match transaction {
+            WorkloadTransaction::TransactionType(transaction_type) => transaction_type
+                .materialize(self.module_working_set_size, self.sender_use_account_pool),
+            WorkloadTransaction::EntryPoint(entry_point) => TransactionType::CallCustomModules {
+                entry_point: *entry_point,
+                num_modules: self.module_working_set_size,
+                use_account_pool: self.sender_use_account_pool,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn test_parse_workload() {
+        let workload: WorkloadDefinition = serde_yaml::from_str(
+            r#"
+module_working_set_size: 3
+sender_distribution:
+  zipf:
+    exponent: 1.2
+phases:
+  - num_blocks: 2
+    mix:
+      - transaction_type: PublishPackage
+  - block_size: 100
+    mix:
+      - transaction_type: CoinTransfer
+        weight: 3
+      - entry_point:
+          BytesMakeOrChange:
+            data_length: 32
+"#,
+        )
+        .unwrap();
+        workload.validate().unwrap();
+
+        let mix_per_phase = workload.transaction_mix_per_phase();
+        assert_eq!(mix_per_phase.len(), 2);
+        assert!(matches!(mix_per_phase[0][..], [(
+            TransactionType::PublishPackage {
+                use_account_pool: false
+            },
+            1
+        )]));
+        assert!(matches!(mix_per_phase[1][..], [
+            (TransactionType::CoinTransfer { .. }, 3),
+            (
+                TransactionType::CallCustomModules {
+                    entry_point: EntryPoints::BytesMakeOrChange {
+                        data_length: Some(32)
+                    },
+                    num_modules: 3,
+                    ..
+                },
+                1
+            )
+        ]));
+        assert_eq!(workload.phases[1].block_size, Some(100));
+    }
+
+    #[test]
+    fn test_zipf_sampler_prefers_first_accounts() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let sampler = AccountDistribution::Zipf { exponent: 1.5 }.sampler(1000);
+        let samples = sampler.sample_indices(&mut rng, 10000);
+        assert!(samples.iter().all(|idx| *idx < 1000));
+        let first = samples.iter().filter(|idx| **idx == 0).count();
+        let last_half = samples.iter().filter(|idx| **idx >= 500).count();
+        assert!(first > last_half);
+
+        let sampler = AccountDistribution::Uniform.sampler(10);
+        let mut samples = sampler.sample_indices(&mut rng, 10);
+        samples.sort();
+        assert_eq!(samples, (0..10).collect::<Vec<_>>());
+    }
+}
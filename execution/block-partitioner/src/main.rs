@@ -1,14 +1,15 @@
 // Copyright © Aptos Foundation
 
+use anyhow::Result;
 use aptos_block_partitioner::{
-    sharded_block_partitioner::ShardedBlockPartitioner,
+    sharded_block_partitioner::{ShardExecutionStats, ShardedBlockPartitioner},
     test_utils::{create_signed_p2p_transaction, generate_test_account, TestAccount},
 };
-use aptos_types::transaction::analyzed_transaction::AnalyzedTransaction;
-use clap::Parser;
+use aptos_types::transaction::{analyzed_transaction::AnalyzedTransaction, Transaction};
+use clap::{Parser, Subcommand};
 use rand::rngs::OsRng;
 use rayon::iter::{IntoParallelIterator, ParallelIterator};
-use std::{sync::Mutex, time::Instant};
+use std::{collections::BTreeMap, path::PathBuf, sync::Mutex, time::Instant};
 
 #[derive(Debug, Parser)]
 struct Args {
@@ -23,11 +24,51 @@ struct Args {
 
     #[clap(long, default_value_t = 12)]
     pub num_shards: usize,
+
+    #[clap(subcommand)]
+    pub cmd: Option<Cmd>,
 }
 
-fn main() {
-    println!("Starting the block partitioning benchmark");
+#[derive(Debug, Subcommand)]
+enum Cmd {
+    /// Partitions historical blocks, as exported by `aptos-db-tool debug ledger export-blocks`,
+    /// and reports the quality of the partitioning.
+    Replay(ReplayArgs),
+}
+
+#[derive(Debug, Parser)]
+struct ReplayArgs {
+    #[clap(long, value_parser)]
+    pub blocks_file: PathBuf,
+
+    #[clap(long, default_value_t = 12)]
+    pub num_shards: usize,
+
+    #[clap(long, default_value_t = 4)]
+    pub max_partitioning_rounds: usize,
+
+    #[clap(long, default_value_t = 0.95)]
+    pub cross_shard_dep_avoid_threshold: f32,
+
+    /// Use the adaptive partitioner, fed with execution statistics estimated from the previous
+    /// blocks' partitions.
+    #[clap(long)]
+    pub adaptive: bool,
+}
+
+fn main() -> Result<()> {
     let args = Args::parse();
+    match args.cmd {
+        Some(Cmd::Replay(replay_args)) => replay(replay_args),
+        None => {
+            run_benchmark(args);
+            Ok(())
+        },
+    }
+}
+
+fn run_benchmark(args: Args) {
+    println!("Starting the block partitioning benchmark");
     let num_accounts = args.num_accounts;
     println!("Creating {} accounts", num_accounts);
     let accounts: Vec<Mutex<TestAccount>> = (0..num_accounts)
@@ -59,6 +100,145 @@ fn main() {
     }
 }
 
+/// The kind of a transaction the partitioner can't analyze, as reported by the replay.
+fn unsupported_kind(txn: &Transaction) -> &'static str {
+    match txn {
+        Transaction::UserTransaction(_) => "unsupported user transaction",
+        Transaction::GenesisTransaction(_) => "genesis",
+        Transaction::BlockMetadata(_) => "block metadata",
+        Transaction::StateCheckpoint(_) => "state checkpoint",
+    }
+}
+
+#[derive(Default)]
+struct PartitionQuality {
+    num_txns: usize,
+    num_rounds: usize,
+    // Number of transactions in the largest shard over the mean number per shard.
+    shard_imbalance: f64,
+    num_txns_with_cross_shard_deps: usize,
+    num_cross_shard_edges: usize,
+}
+
+fn replay(args: ReplayArgs) -> Result<()> {
+    let blocks: Vec<Vec<Transaction>> = bcs::from_bytes(&std::fs::read(&args.blocks_file)?)?;
+    println!(
+        "Replaying {} blocks on {} shards",
+        blocks.len(),
+        args.num_shards
+    );
+    let partitioner = if args.adaptive {
+        ShardedBlockPartitioner::new_adaptive(args.num_shards)
+    } else {
+        ShardedBlockPartitioner::new(args.num_shards)
+    };
+    // Cumulative execution statistics, estimated from the partitions: each transaction takes the
+    // same time to execute, and each cross-shard edge needs a message.
+    let mut stats = ShardExecutionStats {
+        execution_seconds: vec![0.0; args.num_shards],
+        cross_shard_messages: vec![0; args.num_shards],
+    };
+
+    let mut total = PartitionQuality::default();
+    let mut num_partitioned_blocks = 0;
+    let mut num_skipped_txns = BTreeMap::new();
+    let mut total_partition_time = std::time::Duration::ZERO;
+    for (block_idx, block) in blocks.into_iter().enumerate() {
+        // Only user transactions are partitioned; the partitioner can't analyze the others.
+        let mut txns = vec![];
+        let mut num_block_skipped_txns = 0;
+        for txn in block {
+            if AnalyzedTransaction::is_supported_user_transaction(&txn) {
+                txns.push(AnalyzedTransaction::from(txn));
+            } else {
+                *num_skipped_txns.entry(unsupported_kind(&txn)).or_insert(0) += 1;
+                num_block_skipped_txns += 1;
+            }
+        }
+        if txns.is_empty() {
+            println!(
+                "Block {}: skipped, none of its {} txns can be partitioned",
+                block_idx, num_block_skipped_txns
+            );
+            continue;
+        }
+
+        if args.adaptive {
+            partitioner.record_execution_stats(&stats);
+        }
+        let now = Instant::now();
+        let sub_blocks = partitioner.partition(
+            txns,
+            args.max_partitioning_rounds,
+            args.cross_shard_dep_avoid_threshold,
+        );
+        let partition_time = now.elapsed();
+
+        let mut quality = PartitionQuality::default();
+        let mut max_shard_txns = 0;
+        for sub_blocks_for_shard in &sub_blocks {
+            let shard_id = sub_blocks_for_shard.shard_id;
+            let num_shard_txns = sub_blocks_for_shard.num_txns();
+            quality.num_txns += num_shard_txns;
+            quality.num_rounds = quality
+                .num_rounds
+                .max(sub_blocks_for_shard.num_sub_blocks());
+            max_shard_txns = max_shard_txns.max(num_shard_txns);
+            for txn in sub_blocks_for_shard.iter() {
+                let num_edges = txn.cross_shard_dependencies().num_required_edges();
+                if num_edges > 0 {
+                    quality.num_txns_with_cross_shard_deps += 1;
+                }
+                quality.num_cross_shard_edges += num_edges;
+                stats.cross_shard_messages[shard_id] += num_edges as u64;
+            }
+            stats.execution_seconds[shard_id] += num_shard_txns as f64;
+        }
+        quality.shard_imbalance =
+            max_shard_txns as f64 * args.num_shards as f64 / quality.num_txns as f64;
+        println!(
+            "Block {}: txns: {}, skipped txns: {}, rounds: {}, shard imbalance: {:.2}, cross-shard txns: {:.2}%, cross-shard edges: {}, partition time: {:?}",
+            block_idx,
+            quality.num_txns,
+            num_block_skipped_txns,
+            quality.num_rounds,
+            quality.shard_imbalance,
+            100.0 * quality.num_txns_with_cross_shard_deps as f64 / quality.num_txns as f64,
+            quality.num_cross_shard_edges,
+            partition_time,
+        );
+
+        num_partitioned_blocks += 1;
+        total.num_txns += quality.num_txns;
+        total.num_rounds += quality.num_rounds;
+        total.shard_imbalance += quality.shard_imbalance;
+        total.num_txns_with_cross_shard_deps += quality.num_txns_with_cross_shard_deps;
+        total.num_cross_shard_edges += quality.num_cross_shard_edges;
+        total_partition_time += partition_time;
+    }
+
+    println!(
+        "Skipped {} transactions the partitioner can't analyze",
+        num_skipped_txns.values().sum::<usize>()
+    );
+    for (kind, num_txns) in &num_skipped_txns {
+        println!("  {}: {}", kind, num_txns);
+    }
+    if num_partitioned_blocks > 0 {
+        println!(
+            "Partitioned {} blocks with {} txns, average rounds: {:.2}, average shard imbalance: {:.2}, cross-shard txns: {:.2}%, cross-shard edges per txn: {:.3}, average partition time: {:?}",
+            num_partitioned_blocks,
+            total.num_txns,
+            total.num_rounds as f64 / num_partitioned_blocks as f64,
+            total.shard_imbalance / num_partitioned_blocks as f64,
+            100.0 * total.num_txns_with_cross_shard_deps as f64 / total.num_txns as f64,
+            total.num_cross_shard_edges as f64 / total.num_txns as f64,
+            total_partition_time / num_partitioned_blocks as u32,
+        );
+    }
+    Ok(())
+}
+
 #[test]
 fn verify_tool() {
     use clap::CommandFactory;
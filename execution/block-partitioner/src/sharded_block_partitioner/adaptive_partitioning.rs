@@ -0,0 +1,220 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::sharded_block_partitioner::counters::ADAPTIVE_PARTITIONING_HOT_KEY_THRESHOLD;
+use aptos_types::{
+    block_executor::partitioner::ShardId,
+    state_store::state_key::StateKey,
+    transaction::analyzed_transaction::{AnalyzedTransaction, StorageLocation},
+};
+use std::collections::{HashMap, HashSet};
+
+/// Cross-shard messages per transaction above which hot keys are co-located more aggressively.
+const TARGET_CROSS_SHARD_MSGS_PER_TXN: f64 = 0.1;
+const DEFAULT_HOT_KEY_THRESHOLD: usize = 16;
+const MIN_HOT_KEY_THRESHOLD: usize = 2;
+const MAX_HOT_KEY_THRESHOLD: usize = 1024;
+/// How much a shard may exceed its share of the block to keep the senders of a hot key together.
+const HOT_KEY_LOAD_SLACK: f64 = 0.1;
+/// Weight of the latest observation in the smoothed per-shard throughput.
+const THROUGHPUT_SMOOTHING: f64 = 0.5;
+
+/// Cumulative execution statistics of the executor shards, e.g. as collected by the sharded block
+/// executor from the results of the shards. Both vectors are indexed by shard id.
+#[derive(Clone, Debug, Default)]
+pub struct ShardExecutionStats {
+    /// Total time spent executing sub-blocks.
+    pub execution_seconds: Vec<f64>,
+    /// Total number of cross-shard messages sent.
+    pub cross_shard_messages: Vec<u64>,
+}
+
+/// Decides the initial assignment of transactions to shards, based on feedback from the
+/// execution of previous blocks:
+/// - Shards get a share of the block proportional to the throughput they have shown so far.
+/// - Senders that write the same hot storage location are kept on the same shard, so that they
+///   don't need cross-shard messages. How many writers make a location hot is adjusted based on
+///   the number of cross-shard messages the previous blocks needed.
+pub(crate) struct AdaptivePartitioningState {
+    num_shards: usize,
+    last_stats: Option<ShardExecutionStats>,
+    // Number of transactions assigned to each shard since `last_stats` were recorded.
+    txns_since_last_stats: Vec<usize>,
+    // Smoothed transactions per second of each shard, `None` until observed.
+    throughput: Vec<Option<f64>>,
+    hot_key_threshold: usize,
+}
+
+impl AdaptivePartitioningState {
+    pub fn new(num_shards: usize) -> Self {
+        ADAPTIVE_PARTITIONING_HOT_KEY_THRESHOLD.set(DEFAULT_HOT_KEY_THRESHOLD as i64);
+        Self {
+            num_shards,
+            last_stats: None,
+            txns_since_last_stats: vec![0; num_shards],
+            throughput: vec![None; num_shards],
+            hot_key_threshold: DEFAULT_HOT_KEY_THRESHOLD,
+        }
+    }
+
+    pub fn hot_key_threshold(&self) -> usize {
+        self.hot_key_threshold
+    }
+
+    pub fn record_execution_stats(&mut self, stats: &ShardExecutionStats) {
+        if stats.execution_seconds.len() != self.num_shards
+            || stats.cross_shard_messages.len() != self.num_shards
+        {
+            return;
+        }
+        let last_stats = match self.last_stats.replace(stats.clone()) {
+            Some(last_stats) => last_stats,
+            None => {
+                // Only the stats of the blocks partitioned from now on can be attributed.
+                self.txns_since_last_stats = vec![0; self.num_shards];
+                return;
+            },
+        };
+
+        let mut total_msgs = 0;
+        for shard_id in 0..self.num_shards {
+            let seconds =
+                stats.execution_seconds[shard_id] - last_stats.execution_seconds[shard_id];
+            let txns = self.txns_since_last_stats[shard_id];
+            if txns > 0 && seconds > 0.0 {
+                let observed = txns as f64 / seconds;
+                self.throughput[shard_id] = Some(match self.throughput[shard_id] {
+                    Some(throughput) => {
+                        THROUGHPUT_SMOOTHING * observed + (1.0 - THROUGHPUT_SMOOTHING) * throughput
+                    },
+                    None => observed,
+                });
+            }
+            total_msgs += stats.cross_shard_messages[shard_id]
+                .saturating_sub(last_stats.cross_shard_messages[shard_id]);
+        }
+
+        let total_txns = self.txns_since_last_stats.iter().sum::<usize>();
+        if total_txns > 0 {
+            let msgs_per_txn = total_msgs as f64 / total_txns as f64;
+            if msgs_per_txn > TARGET_CROSS_SHARD_MSGS_PER_TXN {
+                self.hot_key_threshold = (self.hot_key_threshold / 2).max(MIN_HOT_KEY_THRESHOLD);
+            } else if msgs_per_txn < TARGET_CROSS_SHARD_MSGS_PER_TXN / 2.0 {
+                self.hot_key_threshold = (self.hot_key_threshold * 2).min(MAX_HOT_KEY_THRESHOLD);
+            }
+            ADAPTIVE_PARTITIONING_HOT_KEY_THRESHOLD.set(self.hot_key_threshold as i64);
+        }
+        self.txns_since_last_stats = vec![0; self.num_shards];
+    }
+
+    /// The number of transactions each shard should get out of `num_txns`.
+    fn shard_targets(&self, num_txns: usize) -> Vec<f64> {
+        let observed = self.throughput.iter().flatten().collect::<Vec<_>>();
+        // Shards without observations are assumed to be as fast as the average one.
+        let default_throughput = if observed.is_empty() {
+            1.0
+        } else {
+            observed.iter().copied().sum::<f64>() / observed.len() as f64
+        };
+        let weights = self
+            .throughput
+            .iter()
+            .map(|throughput| throughput.unwrap_or(default_throughput))
+            .collect::<Vec<_>>();
+        let total_weight = weights.iter().sum::<f64>();
+        weights
+            .iter()
+            .map(|weight| num_txns as f64 * weight / total_weight)
+            .collect()
+    }
+
+    /// Like `ShardedBlockPartitioner::partition_by_senders`, transactions of the same sender are
+    /// kept together (and in order) on one shard, but the shards are chosen based on the
+    /// feedback.
+    pub fn partition_by_senders(
+        &mut self,
+        txns: Vec<AnalyzedTransaction>,
+    ) -> Vec<Vec<AnalyzedTransaction>> {
+        let targets = self.shard_targets(txns.len());
+        let mut sender_to_txns = HashMap::new();
+        let mut sender_order = Vec::new();
+        for txn in txns {
+            let sender = txn.sender().unwrap();
+            let entry = sender_to_txns.entry(sender).or_insert_with(Vec::new);
+            if entry.is_empty() {
+                sender_order.push(sender);
+            }
+            entry.push(txn);
+        }
+
+        // Count the number of senders writing to each storage location.
+        let mut num_writers: HashMap<&StateKey, usize> = HashMap::new();
+        for txns in sender_to_txns.values() {
+            let written = txns
+                .iter()
+                .flat_map(|txn| txn.write_hints())
+                .filter_map(|location| match location {
+                    StorageLocation::Specific(state_key) => Some(state_key),
+                    _ => None,
+                })
+                .collect::<HashSet<_>>();
+            for state_key in written {
+                *num_writers.entry(state_key).or_default() += 1;
+            }
+        }
+        // The hottest location each sender writes to, if any is hot.
+        let hot_keys = sender_order
+            .iter()
+            .map(|sender| {
+                sender_to_txns[sender]
+                    .iter()
+                    .flat_map(|txn| txn.write_hints())
+                    .filter_map(|location| match location {
+                        StorageLocation::Specific(state_key) => {
+                            Some((num_writers[state_key], state_key.clone()))
+                        },
+                        _ => None,
+                    })
+                    .filter(|(writers, _)| *writers >= self.hot_key_threshold)
+                    .max_by_key(|(writers, _)| *writers)
+                    .map(|(_, state_key)| state_key)
+            })
+            .collect::<Vec<_>>();
+
+        let mut result: Vec<Vec<AnalyzedTransaction>> = vec![Vec::new(); self.num_shards];
+        let mut hot_key_shards: HashMap<StateKey, ShardId> = HashMap::new();
+        let least_loaded_shard = |result: &Vec<Vec<AnalyzedTransaction>>| {
+            (0..self.num_shards)
+                .min_by(|a, b| {
+                    let load_a = result[*a].len() as f64 / targets[*a].max(f64::EPSILON);
+                    let load_b = result[*b].len() as f64 / targets[*b].max(f64::EPSILON);
+                    load_a.total_cmp(&load_b)
+                })
+                .unwrap()
+        };
+        for (sender, hot_key) in sender_order.into_iter().zip(hot_keys) {
+            let txns = sender_to_txns.remove(&sender).unwrap();
+            let shard_id = match hot_key {
+                Some(hot_key) => {
+                    let shard_id = *hot_key_shards
+                        .entry(hot_key)
+                        .or_insert_with(|| least_loaded_shard(&result));
+                    if (result[shard_id].len() + txns.len()) as f64
+                        <= targets[shard_id] * (1.0 + HOT_KEY_LOAD_SLACK)
+                    {
+                        shard_id
+                    } else {
+                        least_loaded_shard(&result)
+                    }
+                },
+                None => least_loaded_shard(&result),
+            };
+            result[shard_id].extend(txns);
+        }
+
+        for (shard_id, txns) in result.iter().enumerate() {
+            self.txns_since_last_stats[shard_id] += txns.len();
+        }
+        result
+    }
+}
@@ -3,7 +3,8 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use aptos_metrics_core::{
-    exponential_buckets, register_histogram, register_int_gauge_vec, Histogram, IntGaugeVec,
+    exponential_buckets, register_histogram, register_int_gauge, register_int_gauge_vec, Histogram,
+    IntGauge, IntGaugeVec,
 };
 use once_cell::sync::Lazy;
 
@@ -26,3 +27,11 @@ pub static BLOCK_PARTITIONING_SECONDS: Lazy<Histogram> = Lazy::new(|| {
     )
     .unwrap()
 });
+
+pub static ADAPTIVE_PARTITIONING_HOT_KEY_THRESHOLD: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!(
+        "aptos_adaptive_partitioning_hot_key_threshold",
+        "Number of senders writing a storage location for the adaptive partitioner to keep them on one shard"
+    )
+    .unwrap()
+});
@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::sharded_block_partitioner::{
+    adaptive_partitioning::AdaptivePartitioningState,
     cross_shard_messages::CrossShardMsg,
     dependency_analysis::WriteSetWithTxnIndex,
     messages::{
@@ -22,11 +23,12 @@ use std::{
     collections::HashMap,
     sync::{
         mpsc::{Receiver, Sender},
-        Arc,
+        Arc, Mutex,
     },
     thread,
 };
 
+mod adaptive_partitioning;
 mod conflict_detector;
 mod counters;
 mod cross_shard_messages;
@@ -35,6 +37,8 @@ mod dependent_edges;
 mod messages;
 mod partitioning_shard;
 
+pub use adaptive_partitioning::ShardExecutionStats;
+
 /// A sharded block partitioner that partitions a block into multiple transaction chunks.
 /// On a high level, the partitioning process is as follows:
 /// ```plaintext
@@ -112,9 +116,19 @@ pub struct ShardedBlockPartitioner {
     control_txs: Vec<Sender<ControlMsg>>,
     result_rxs: Vec<Receiver<PartitioningResp>>,
     shard_threads: Vec<thread::JoinHandle<()>>,
+    // Only set for adaptive partitioners, see `new_adaptive`.
+    adaptive_state: Option<Mutex<AdaptivePartitioningState>>,
 }
 
 impl ShardedBlockPartitioner {
+    /// Creates a partitioner that rebalances hot storage locations and load across shards, based
+    /// on the execution statistics passed to `record_execution_stats` after executing blocks.
+    pub fn new_adaptive(num_shards: usize) -> Self {
+        let mut partitioner = Self::new(num_shards);
+        partitioner.adaptive_state = Some(Mutex::new(AdaptivePartitioningState::new(num_shards)));
+        partitioner
+    }
+
     pub fn new(num_shards: usize) -> Self {
         info!(
             "Creating a new sharded block partitioner with {} shards",
@@ -156,6 +170,18 @@ impl ShardedBlockPartitioner {
             control_txs,
             result_rxs,
             shard_threads: shard_join_handles,
+            adaptive_state: None,
+        }
+    }
+
+    /// Feeds the (cumulative) execution statistics of the shards back into an adaptive
+    /// partitioner, no-op otherwise.
+    pub fn record_execution_stats(&self, stats: &ShardExecutionStats) {
+        if let Some(adaptive_state) = &self.adaptive_state {
+            adaptive_state
+                .lock()
+                .unwrap()
+                .record_execution_stats(stats);
         }
     }
 
@@ -324,7 +350,13 @@ impl ShardedBlockPartitioner {
         }
 
         // First round, we filter all transactions with cross-shard dependencies
-        let mut txns_to_partition = self.partition_by_senders(transactions);
+        let mut txns_to_partition = match &self.adaptive_state {
+            Some(adaptive_state) => adaptive_state
+                .lock()
+                .unwrap()
+                .partition_by_senders(transactions),
+            None => self.partition_by_senders(transactions),
+        };
         let mut frozen_write_set_with_index = Arc::new(Vec::new());
         let mut current_round_start_index = 0;
         let mut frozen_sub_blocks: Vec<SubBlocksForShard<AnalyzedTransaction>> = vec![];
@@ -427,7 +459,7 @@ fn spawn_partitioning_shard(
 #[cfg(test)]
 mod tests {
     use crate::{
-        sharded_block_partitioner::ShardedBlockPartitioner,
+        sharded_block_partitioner::{ShardExecutionStats, ShardedBlockPartitioner},
         test_utils::{
             create_non_conflicting_p2p_transaction, create_signed_p2p_transaction,
             generate_test_account, generate_test_account_for_address, TestAccount,
@@ -847,4 +879,74 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    // Senders paying the same receiver all write its coin store, so the adaptive partitioner keeps
+    // them on one shard instead of spreading them across shards.
+    fn test_adaptive_partitioner_colocates_hot_keys() {
+        let num_shards = 2;
+        let receiver = generate_test_account();
+        let mut hot_txns = Vec::new();
+        for _ in 0..20 {
+            hot_txns.push(
+                create_signed_p2p_transaction(&mut generate_test_account(), vec![&receiver])
+                    .remove(0),
+            );
+        }
+        let mut transactions = Vec::new();
+        for hot_txn in hot_txns.iter() {
+            transactions.push(hot_txn.clone());
+            transactions.push(create_non_conflicting_p2p_transaction());
+        }
+
+        let partitioner = ShardedBlockPartitioner::new_adaptive(num_shards);
+        let partitioned_txns = partitioner.partition(transactions, 2, 0.9);
+        let hot_txn_shards = partitioned_txns
+            .iter()
+            .enumerate()
+            .flat_map(|(shard_id, sub_blocks)| {
+                sub_blocks
+                    .iter()
+                    .filter(|txn| hot_txns.contains(txn.txn()))
+                    .map(move |_| shard_id)
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(hot_txn_shards.len(), hot_txns.len());
+        assert!(hot_txn_shards.iter().all(|shard_id| *shard_id == hot_txn_shards[0]));
+        for sub_blocks in partitioned_txns {
+            for txn in sub_blocks.iter() {
+                assert_eq!(txn.cross_shard_dependencies().num_required_edges(), 0);
+            }
+        }
+    }
+
+    #[test]
+    // A shard that was slower executing the previous block gets fewer transactions.
+    fn test_adaptive_partitioner_rebalances_load() {
+        let num_shards = 2;
+        let num_txns = 100;
+        let partitioner = ShardedBlockPartitioner::new_adaptive(num_shards);
+        partitioner.record_execution_stats(&ShardExecutionStats {
+            execution_seconds: vec![0.0, 0.0],
+            cross_shard_messages: vec![0, 0],
+        });
+        let transactions = (0..num_txns)
+            .map(|_| create_non_conflicting_p2p_transaction())
+            .collect::<Vec<_>>();
+        let partitioned_txns = partitioner.partition(transactions, 2, 0.9);
+        assert_eq!(partitioned_txns[0].num_txns(), num_txns / 2);
+        assert_eq!(partitioned_txns[1].num_txns(), num_txns / 2);
+
+        // Shard 1 took three times as long for the same number of transactions.
+        partitioner.record_execution_stats(&ShardExecutionStats {
+            execution_seconds: vec![1.0, 3.0],
+            cross_shard_messages: vec![0, 0],
+        });
+        let transactions = (0..num_txns)
+            .map(|_| create_non_conflicting_p2p_transaction())
+            .collect::<Vec<_>>();
+        let partitioned_txns = partitioner.partition(transactions, 2, 0.9);
+        assert_eq!(partitioned_txns[0].num_txns(), 75);
+        assert_eq!(partitioned_txns[1].num_txns(), 25);
+    }
 }
@@ -1,8 +1,12 @@
 // Copyright © Aptos Foundation
 
 use crate::pipeline::ExecuteBlockMessage;
-use aptos_block_partitioner::sharded_block_partitioner::ShardedBlockPartitioner;
+use aptos_block_partitioner::sharded_block_partitioner::{
+    ShardExecutionStats, ShardedBlockPartitioner,
+};
 use aptos_crypto::HashValue;
+use aptos_executor::components::chunk_output::sharded_execution_stats;
+use aptos_infallible::Mutex;
 use aptos_logger::info;
 use aptos_types::{
     block_executor::partitioner::{
@@ -11,24 +15,35 @@ use aptos_types::{
     },
     transaction::Transaction,
 };
-use std::time::Instant;
+use std::{sync::Arc, time::Instant};
 
 pub(crate) struct BlockPartitioningStage {
     num_blocks_processed: usize,
+    // The stats of the executor the blocks are executed by, if the partitioning is adaptive
+    maybe_execution_stats: Option<Arc<Mutex<ShardExecutionStats>>>,
     maybe_partitioner: Option<ShardedBlockPartitioner>,
 }
 
 impl BlockPartitioningStage {
-    pub fn new(num_shards: usize) -> Self {
+    pub fn new(num_shards: usize, adaptive: bool) -> Self {
         let maybe_partitioner = if num_shards <= 1 {
             None
+        } else if adaptive {
+            Some(ShardedBlockPartitioner::new_adaptive(num_shards))
         } else {
             let partitioner = ShardedBlockPartitioner::new(num_shards);
             Some(partitioner)
         };
 
+        let maybe_execution_stats = if adaptive && maybe_partitioner.is_some() {
+            Some(sharded_execution_stats())
+        } else {
+            None
+        };
+
         Self {
             num_blocks_processed: 0,
+            maybe_execution_stats,
             maybe_partitioner,
         }
     }
@@ -47,6 +62,9 @@ impl BlockPartitioningStage {
                 let last_txn = txns.pop().unwrap();
                 assert!(matches!(last_txn, Transaction::StateCheckpoint(_)));
                 let analyzed_transactions = txns.into_iter().map(|t| t.into()).collect();
+                if let Some(execution_stats) = &self.maybe_execution_stats {
+                    partitioner.record_execution_stats(&execution_stats.lock().clone());
+                }
                 let mut sub_blocks = partitioner.partition(analyzed_transactions, 4, 0.95);
                 sub_blocks
                     .last_mut()
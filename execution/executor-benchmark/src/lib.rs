@@ -36,7 +36,9 @@ use aptos_metrics_core::Histogram;
 use aptos_sdk::types::LocalAccount;
 use aptos_storage_interface::DbReaderWriter;
 use aptos_transaction_generator_lib::{
-    create_txn_generator_creator, TransactionGeneratorCreator, TransactionType,
+    create_txn_generator_creator,
+    workload::{AccountDistribution, WorkloadDefinition},
+    TransactionGeneratorCreator, TransactionType,
     TransactionType::NonConflictingCoinTransfer,
 };
 use db_reliable_submitter::DbReliableTransactionSubmitter;
@@ -93,12 +95,65 @@ fn create_checkpoint(
     .expect("db checkpoint creation fails.");
 }
 
+/// The transactions to generate with transaction-generator-lib, in phases.
+#[derive(Clone, Debug)]
+pub struct BenchmarkWorkload {
+    pub transaction_mix_per_phase: Vec<Vec<(TransactionType, usize)>>,
+    /// Block size and number of blocks of each phase, `None` to use the ones of the benchmark.
+    pub phase_blocks: Vec<(Option<usize>, Option<usize>)>,
+    pub sender_distribution: AccountDistribution,
+}
+
+impl BenchmarkWorkload {
+    pub fn from_transaction_mix(transaction_mix: Vec<(TransactionType, usize)>) -> Self {
+        Self {
+            transaction_mix_per_phase: vec![transaction_mix],
+            phase_blocks: vec![(None, None)],
+            sender_distribution: AccountDistribution::Uniform,
+        }
+    }
+
+    pub fn from_definition(workload: &WorkloadDefinition) -> Self {
+        Self {
+            transaction_mix_per_phase: workload.transaction_mix_per_phase(),
+            phase_blocks: workload
+                .phases
+                .iter()
+                .map(|phase| (phase.block_size, phase.num_blocks))
+                .collect(),
+            sender_distribution: workload.sender_distribution,
+        }
+    }
+
+    /// (block size, number of blocks) of each phase.
+    fn phases(&self, block_size: usize, num_blocks: usize) -> Vec<(usize, usize)> {
+        self.phase_blocks
+            .iter()
+            .map(|(phase_block_size, phase_num_blocks)| {
+                (
+                    phase_block_size.unwrap_or(block_size),
+                    phase_num_blocks.unwrap_or(num_blocks),
+                )
+            })
+            .collect()
+    }
+
+    fn has_non_conflicting_coin_transfer(&self) -> bool {
+        self.transaction_mix_per_phase
+            .iter()
+            .flatten()
+            .any(|(transaction_type, _)| {
+                matches!(transaction_type, NonConflictingCoinTransfer { .. })
+            })
+    }
+}
+
 /// Runs the benchmark with given parameters.
 #[allow(clippy::too_many_arguments)]
 pub fn run_benchmark<V>(
     block_size: usize,
     num_blocks: usize,
-    transaction_mix: Option<Vec<(TransactionType, usize)>>,
+    workload: Option<BenchmarkWorkload>,
     mut transactions_per_sender: usize,
     num_main_signer_accounts: usize,
     num_additional_dst_pool_accounts: usize,
@@ -128,7 +183,18 @@ pub fn run_benchmark<V>(
     config.storage.rocksdb_configs.skip_index_and_usage = skip_index_and_usage;
 
     let (db, executor) = init_db_and_executor::<V>(&config);
-    let transaction_generator_creator = transaction_mix.clone().map(|transaction_mix| {
+    let phases = match &workload {
+        Some(workload) => workload.phases(block_size, num_blocks),
+        None => vec![(block_size, num_blocks)],
+    };
+    let max_block_size = phases
+        .iter()
+        .map(|(block_size, _)| *block_size)
+        .max()
+        .unwrap();
+    let total_blocks = phases.iter().map(|(_, num_blocks)| *num_blocks).sum();
+    let cur_phase = Arc::new(AtomicUsize::new(0));
+    let transaction_generator_creator = workload.as_ref().map(|workload| {
         let num_existing_accounts = TransactionGenerator::read_meta(&source_dir);
         let num_accounts_to_be_loaded = std::cmp::min(
             num_existing_accounts,
@@ -136,16 +202,14 @@ pub fn run_benchmark<V>(
         );
 
         let mut num_accounts_to_skip = 0;
-        for (transaction_type, _) in &transaction_mix {
-            if let NonConflictingCoinTransfer{..} = transaction_type {
-                // In case of random non-conflicting coin transfer using `P2PTransactionGenerator`,
-                // `3*block_size` addresses is required:
-                // `block_size` number of signers, and 2 groups of burn-n-recycle recipients used alternatively.
-                if num_accounts_to_be_loaded < block_size * 3 {
-                    panic!("Cannot guarantee random non-conflicting coin transfer using `P2PTransactionGenerator`.");
-                }
-                num_accounts_to_skip = block_size;
+        if workload.has_non_conflicting_coin_transfer() {
+            // In case of random non-conflicting coin transfer using `P2PTransactionGenerator`,
+            // `3*block_size` addresses is required:
+            // `block_size` number of signers, and 2 groups of burn-n-recycle recipients used alternatively.
+            if num_accounts_to_be_loaded < max_block_size * 3 {
+                panic!("Cannot guarantee random non-conflicting coin transfer using `P2PTransactionGenerator`.");
             }
+            num_accounts_to_skip = max_block_size;
         }
 
         let accounts_cache =
@@ -154,7 +218,8 @@ pub fn run_benchmark<V>(
             accounts_cache.split(num_main_signer_accounts);
 
         init_workload::<V>(
-            transaction_mix,
+            &workload.transaction_mix_per_phase,
+            cur_phase.clone(),
             main_signer_accounts,
             burner_accounts,
             db.clone(),
@@ -168,29 +233,33 @@ pub fn run_benchmark<V>(
                 allow_aborts: false,
                 num_executor_shards: 1,
                 async_partitioning: false,
+                adaptive_partitioning: false,
+                dependency_graph_dir: None,
             },
         )
     });
 
     let version = db.reader.get_latest_version().unwrap();
 
-    let (pipeline, block_sender) =
-        Pipeline::new(executor, version, pipeline_config.clone(), Some(num_blocks));
+    let (pipeline, block_sender) = Pipeline::new(
+        executor,
+        version,
+        pipeline_config.clone(),
+        Some(total_blocks),
+    );
 
     let mut num_accounts_to_load = num_main_signer_accounts;
-    if let Some(mix) = &transaction_mix {
-        for (transaction_type, _) in mix {
-            if let NonConflictingCoinTransfer { .. } = transaction_type {
-                // In case of non-conflicting coin transfer,
-                // `aptos_executor_benchmark::transaction_generator::TransactionGenerator` needs to hold
-                // at least `block_size` number of accounts, all as signer only.
-                num_accounts_to_load = block_size;
-                if transactions_per_sender > 1 {
-                    warn!(
+    if let Some(workload) = &workload {
+        if workload.has_non_conflicting_coin_transfer() {
+            // In case of non-conflicting coin transfer,
+            // `aptos_executor_benchmark::transaction_generator::TransactionGenerator` needs to hold
+            // at least `block_size` number of accounts, all as signer only.
+            num_accounts_to_load = max_block_size;
+            if transactions_per_sender > 1 {
+                warn!(
                     "Overriding transactions_per_sender to 1 for non_conflicting_txns_per_block workload"
                 );
-                    transactions_per_sender = 1;
-                }
+                transactions_per_sender = 1;
             }
         }
     }
@@ -239,9 +308,10 @@ pub fn run_benchmark<V>(
     let start_vm_time = APTOS_EXECUTOR_VM_EXECUTE_BLOCK_SECONDS.get_sample_sum();
     if let Some(transaction_generator_creator) = transaction_generator_creator {
         generator.run_workload(
-            block_size,
-            num_blocks,
+            &phases,
             transaction_generator_creator,
+            &cur_phase,
+            &workload.as_ref().unwrap().sender_distribution,
             transactions_per_sender,
         );
     } else {
@@ -265,8 +335,8 @@ pub fn run_benchmark<V>(
     );
     info!(
         "Executed workload {}",
-        if let Some(mix) = transaction_mix {
-            format!("{:?} via txn generator", mix)
+        if let Some(workload) = workload {
+            format!("{:?} via txn generator", workload)
         } else {
             "raw transfer".to_string()
         }
@@ -319,7 +389,8 @@ pub fn run_benchmark<V>(
 }
 
 fn init_workload<V>(
-    transaction_mix: Vec<(TransactionType, usize)>,
+    transaction_mix_per_phase: &[Vec<(TransactionType, usize)>],
+    cur_phase: Arc<AtomicUsize>,
     mut main_signer_accounts: Vec<LocalAccount>,
     burner_accounts: Vec<LocalAccount>,
     db: DbReaderWriter,
@@ -340,21 +411,19 @@ where
     let transaction_factory = TransactionGenerator::create_transaction_factory();
 
     let (txn_generator_creator, _address_pool, _account_pool) = runtime.block_on(async {
-        let phase = Arc::new(AtomicUsize::new(0));
-
         let db_gen_init_transaction_executor = DbReliableTransactionSubmitter {
             db: db.clone(),
             block_sender,
         };
 
         create_txn_generator_creator(
-            &[transaction_mix],
+            transaction_mix_per_phase,
             &mut main_signer_accounts,
             burner_accounts,
             &db_gen_init_transaction_executor,
             &transaction_factory,
             &transaction_factory,
-            phase,
+            cur_phase,
         )
         .await
     });
@@ -528,6 +597,8 @@ mod tests {
                 allow_aborts: false,
                 num_executor_shards: 1,
                 async_partitioning: false,
+                adaptive_partitioning: false,
+                dependency_graph_dir: None,
             },
         );
 
@@ -536,7 +607,9 @@ mod tests {
         super::run_benchmark::<E>(
             6, /* block_size */
             5, /* num_blocks */
-            transaction_type.map(|t| vec![(t.materialize(2, false), 1)]),
+            transaction_type.map(|t| {
+                super::BenchmarkWorkload::from_transaction_mix(vec![(t.materialize(2, false), 1)])
+            }),
             2,  /* transactions per sender */
             25, /* num_main_signer_accounts */
             30, /* num_dst_pool_accounts */
@@ -555,6 +628,8 @@ mod tests {
                 allow_aborts: false,
                 num_executor_shards: 1,
                 async_partitioning: false,
+                adaptive_partitioning: false,
+                dependency_graph_dir: None,
             },
         );
     }
@@ -3,17 +3,22 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use aptos_config::config::{
-    EpochSnapshotPrunerConfig, LedgerPrunerConfig, PrunerConfig, StateMerklePrunerConfig,
+    EpochSnapshotPrunerConfig, LedgerPrunerConfig, PrunerConfig, RemoteExecutionConfig,
+    StateMerklePrunerConfig,
 };
 use aptos_executor::block_executor::TransactionBlockExecutor;
-use aptos_executor_benchmark::{native_executor::NativeExecutor, pipeline::PipelineConfig};
+use aptos_executor_benchmark::{
+    native_executor::NativeExecutor, pipeline::PipelineConfig, BenchmarkWorkload,
+};
+use aptos_executor_service::remote_executor_client;
 use aptos_metrics_core::{register_int_gauge, IntGauge};
 use aptos_push_metrics::MetricsPusher;
-use aptos_transaction_generator_lib::args::TransactionTypeArg;
+use aptos_transaction_generator_lib::{args::TransactionTypeArg, workload::WorkloadDefinition};
 use aptos_vm::AptosVM;
 use clap::{Parser, Subcommand};
 use once_cell::sync::Lazy;
 use std::{
+    net::SocketAddr,
     path::PathBuf,
     time::{SystemTime, UNIX_EPOCH},
 };
@@ -96,6 +101,21 @@ pub struct PipelineOpt {
     num_executor_shards: usize,
     #[clap(long)]
     async_partitioning: bool,
+    /// Let the partitioner adapt the assignment of transactions to shards to the execution
+    /// statistics of the previous blocks.
+    #[clap(long)]
+    adaptive_partitioning: bool,
+    /// Record the dependency graphs of the blocks executed in parallel and write them to this
+    /// directory, as JSON and DOT files.
+    #[clap(long, value_parser)]
+    dependency_graph_dir: Option<PathBuf>,
+    /// Run the executor shards on remote executor services at these addresses (one per shard, in
+    /// shard id order) instead of locally.
+    #[clap(long, num_args = 1.., requires = "coordinator_address")]
+    remote_executor_addresses: Option<Vec<SocketAddr>>,
+    /// Address the coordinator listens on for results from the remote executor services.
+    #[clap(long)]
+    coordinator_address: Option<SocketAddr>,
 }
 
 impl PipelineOpt {
@@ -108,6 +128,8 @@ impl PipelineOpt {
             allow_aborts: self.allow_aborts,
             num_executor_shards: self.num_executor_shards,
             async_partitioning: self.async_partitioning,
+            adaptive_partitioning: self.adaptive_partitioning,
+            dependency_graph_dir: self.dependency_graph_dir.clone(),
         }
     }
 }
@@ -205,6 +227,11 @@ enum Command {
         #[clap(long, default_value_t = 1)]
         module_working_set_size: usize,
 
+        /// YAML file defining the workload (transaction mix, sender distribution and phases),
+        /// instead of `--transaction-type`. See `aptos_transaction_generator_lib::workload`.
+        #[clap(long, value_parser, conflicts_with = "transaction_type")]
+        workload_file: Option<PathBuf>,
+
         #[clap(long, value_parser)]
         data_dir: PathBuf,
 
@@ -256,10 +283,15 @@ where
             transaction_type,
             transaction_weights,
             module_working_set_size,
+            workload_file,
             data_dir,
             checkpoint_dir,
         } => {
-            let transaction_mix = if transaction_type.is_empty() {
+            let workload = if let Some(workload_file) = workload_file {
+                let workload = WorkloadDefinition::load(&workload_file)
+                    .unwrap_or_else(|err| panic!("Invalid workload file: {:?}", err));
+                Some(BenchmarkWorkload::from_definition(&workload))
+            } else if transaction_type.is_empty() {
                 None
             } else {
                 let mix_per_phase = TransactionTypeArg::args_to_transaction_mix_per_phase(
@@ -270,13 +302,15 @@ where
                     false,
                 );
                 assert!(mix_per_phase.len() == 1);
-                Some(mix_per_phase[0].clone())
+                Some(BenchmarkWorkload::from_transaction_mix(
+                    mix_per_phase[0].clone(),
+                ))
             };
 
             aptos_executor_benchmark::run_benchmark::<E>(
                 opt.block_size,
                 blocks,
-                transaction_mix,
+                workload,
                 opt.transactions_per_sender,
                 main_signer_accounts,
                 additional_dst_pool_accounts,
@@ -330,6 +364,20 @@ fn main() {
         .expect("Failed to build rayon global thread pool.");
     AptosVM::set_concurrency_level_once(opt.concurrency_level());
     AptosVM::set_num_shards_once(opt.pipeline_opt.num_executor_shards);
+    if let (Some(remote_executor_addresses), Some(coordinator_address)) = (
+        opt.pipeline_opt.remote_executor_addresses.clone(),
+        opt.pipeline_opt.coordinator_address,
+    ) {
+        assert_eq!(
+            remote_executor_addresses.len(),
+            opt.pipeline_opt.num_executor_shards,
+            "An address is needed for every executor shard"
+        );
+        remote_executor_client::set_remote_execution_config_once(RemoteExecutionConfig::new(
+            coordinator_address,
+            remote_executor_addresses,
+        ));
+    }
     NativeExecutor::set_concurrency_level_once(opt.concurrency_level());
 
     if opt.use_native_executor {
@@ -16,6 +16,7 @@ use aptos_types::{
     block_executor::partitioner::ExecutableTransactions,
     contract_event::ContractEvent,
     event::EventKey,
+    on_chain_config::BlockGasLimitType,
     state_store::state_key::StateKey,
     transaction::{ExecutionStatus, Transaction, TransactionOutput, TransactionStatus},
     vm_status::AbortLocation,
@@ -339,7 +340,7 @@ impl TransactionBlockExecutor for NativeExecutor {
     fn execute_transaction_block(
         transactions: ExecutableTransactions,
         state_view: CachedStateView,
-        _maybe_block_gas_limit: Option<u64>,
+        _block_gas_limit_type: BlockGasLimitType,
     ) -> Result<ChunkOutput> {
         let transactions = match transactions {
             ExecutableTransactions::Unsharded(txns) => txns,
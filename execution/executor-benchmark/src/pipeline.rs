@@ -15,6 +15,7 @@ use aptos_types::{
 };
 use std::{
     marker::PhantomData,
+    path::PathBuf,
     sync::{
         mpsc::{self, SyncSender},
         Arc,
@@ -32,6 +33,9 @@ pub struct PipelineConfig {
     pub allow_aborts: bool,
     pub num_executor_shards: usize,
     pub async_partitioning: bool,
+    pub adaptive_partitioning: bool,
+    /// If set, the dependency graphs of the blocks executed in parallel are written here.
+    pub dependency_graph_dir: Option<PathBuf>,
 }
 
 pub struct Pipeline<V> {
@@ -90,7 +94,8 @@ where
 
         let mut join_handles = vec![];
 
-        let mut partitioning_stage = BlockPartitioningStage::new(num_partitioner_shards);
+        let mut partitioning_stage =
+            BlockPartitioningStage::new(num_partitioner_shards, config.adaptive_partitioning);
 
         let mut exe = TransactionExecutor::new(
             executor_1,
@@ -99,6 +104,7 @@ where
             Some(commit_sender),
             config.allow_discards,
             config.allow_aborts,
+            config.dependency_graph_dir.clone(),
         );
 
         if config.async_partitioning {
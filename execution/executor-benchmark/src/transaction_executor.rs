@@ -3,12 +3,19 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::pipeline::CommitBlockMessage;
+use aptos_block_executor::dependency_graph::{
+    export_recorded_dependency_graphs, set_dependency_graph_recording,
+};
 use aptos_crypto::hash::HashValue;
 use aptos_executor::block_executor::{BlockExecutor, TransactionBlockExecutor};
 use aptos_executor_types::BlockExecutorTrait;
 use aptos_logger::info;
-use aptos_types::{block_executor::partitioner::ExecutableBlock, transaction::Version};
+use aptos_types::{
+    block_executor::partitioner::ExecutableBlock, on_chain_config::BlockGasLimitType,
+    transaction::Version,
+};
 use std::{
+    path::PathBuf,
     sync::{mpsc, Arc},
     time::{Duration, Instant},
 };
@@ -23,6 +30,7 @@ pub struct TransactionExecutor<V> {
     commit_sender: Option<mpsc::SyncSender<CommitBlockMessage>>,
     allow_discards: bool,
     allow_aborts: bool,
+    dependency_graph_dir: Option<PathBuf>,
 }
 
 impl<V> TransactionExecutor<V>
@@ -36,7 +44,11 @@ where
         commit_sender: Option<mpsc::SyncSender<CommitBlockMessage>>,
         allow_discards: bool,
         allow_aborts: bool,
+        dependency_graph_dir: Option<PathBuf>,
     ) -> Self {
+        if dependency_graph_dir.is_some() {
+            set_dependency_graph_recording(true);
+        }
         Self {
             num_blocks_processed: 0,
             executor,
@@ -46,6 +58,7 @@ where
             commit_sender,
             allow_discards,
             allow_aborts,
+            dependency_graph_dir,
         }
     }
 
@@ -68,8 +81,20 @@ where
         self.version += num_txns as Version;
         let output = self
             .executor
-            .execute_block(executable_block, self.parent_block_id, None)
+            .execute_block(
+                executable_block,
+                self.parent_block_id,
+                BlockGasLimitType::NoLimit,
+            )
             .unwrap();
+        if let Some(dependency_graph_dir) = &self.dependency_graph_dir {
+            let num_graphs = export_recorded_dependency_graphs(dependency_graph_dir).unwrap();
+            info!(
+                "Exported {} dependency graphs to {}.",
+                num_graphs,
+                dependency_graph_dir.display()
+            );
+        }
 
         assert_eq!(output.compute_status().len(), num_txns);
         let discards = output
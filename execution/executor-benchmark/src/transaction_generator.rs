@@ -7,7 +7,7 @@ use aptos_crypto::{ed25519::Ed25519PrivateKey, HashValue};
 use aptos_sdk::{transaction_builder::TransactionFactory, types::LocalAccount};
 use aptos_state_view::account_with_state_view::AsAccountWithStateView;
 use aptos_storage_interface::{state_view::LatestDbStateCheckpointView, DbReader, DbReaderWriter};
-use aptos_transaction_generator_lib::TransactionGeneratorCreator;
+use aptos_transaction_generator_lib::{workload::AccountDistribution, TransactionGeneratorCreator};
 use aptos_types::{
     account_address::AccountAddress,
     account_config::aptos_test_root_address,
@@ -26,7 +26,10 @@ use std::{
     io::{Read, Write},
     iter::once,
     path::Path,
-    sync::{mpsc, Arc},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc, Arc,
+    },
 };
 
 const META_FILENAME: &str = "metadata.toml";
@@ -274,37 +277,41 @@ impl TransactionGenerator {
         self.gen_transfer_transactions(block_size, num_transfer_blocks, transactions_per_sender);
     }
 
+    /// Generates the blocks of every phase, given as (block size, number of blocks), in order.
+    /// `cur_phase` is updated for the transaction generator to switch to the mix of the phase.
     pub fn run_workload(
         &mut self,
-        block_size: usize,
-        num_blocks: usize,
+        phases: &[(usize, usize)],
         mut transaction_generator_creator: Box<dyn TransactionGeneratorCreator>,
+        cur_phase: &AtomicUsize,
+        sender_distribution: &AccountDistribution,
         transactions_per_sender: usize,
     ) {
         assert!(self.block_sender.is_some());
-        let num_senders_per_block =
-            (block_size + transactions_per_sender - 1) / transactions_per_sender;
         let account_pool_size = self.main_signer_accounts.as_ref().unwrap().accounts.len();
+        let sender_sampler = sender_distribution.sampler(account_pool_size);
         let mut transaction_generator =
             transaction_generator_creator.create_transaction_generator();
-        for _ in 0..num_blocks {
-            let transactions: Vec<_> = rand::seq::index::sample(
-                &mut thread_rng(),
-                account_pool_size,
-                num_senders_per_block,
-            )
-            .into_iter()
-            .flat_map(|idx| {
-                let sender = &mut self.main_signer_accounts.as_mut().unwrap().accounts[idx];
-                transaction_generator.generate_transactions(sender, transactions_per_sender)
-            })
-            .map(Transaction::UserTransaction)
-            .chain(once(Transaction::StateCheckpoint(HashValue::random())))
-            .collect();
-            self.version += transactions.len() as Version;
-
-            if let Some(sender) = &self.block_sender {
-                sender.send(transactions).unwrap();
+        for (phase, (block_size, num_blocks)) in phases.iter().enumerate() {
+            cur_phase.store(phase, Ordering::Relaxed);
+            let num_senders_per_block =
+                (block_size + transactions_per_sender - 1) / transactions_per_sender;
+            for _ in 0..*num_blocks {
+                let transactions: Vec<_> = sender_sampler
+                    .sample_indices(&mut thread_rng(), num_senders_per_block)
+                    .into_iter()
+                    .flat_map(|idx| {
+                        let sender = &mut self.main_signer_accounts.as_mut().unwrap().accounts[idx];
+                        transaction_generator.generate_transactions(sender, transactions_per_sender)
+                    })
+                    .map(Transaction::UserTransaction)
+                    .chain(once(Transaction::StateCheckpoint(HashValue::random())))
+                    .collect();
+                self.version += transactions.len() as Version;
+
+                if let Some(sender) = &self.block_sender {
+                    sender.send(transactions).unwrap();
+                }
             }
         }
     }
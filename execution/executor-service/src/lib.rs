@@ -2,18 +2,22 @@
 // SPDX-License-Identifier: Apache-2.0
 use aptos_state_view::in_memory_state_view::InMemoryStateView;
 use aptos_types::{
-    block_executor::partitioner::SubBlocksForShard,
-    transaction::{analyzed_transaction::AnalyzedTransaction, TransactionOutput},
+    block_executor::partitioner::{ShardId, SubBlocksForShard},
+    on_chain_config::BlockGasLimitType,
+    transaction::analyzed_transaction::AnalyzedTransaction,
     vm_status::VMStatus,
 };
+use aptos_vm::sharded_block_executor::{messages::CrossShardMsg, ShardExecutionResult};
 use serde::{Deserialize, Serialize};
 
 mod error;
+mod metrics;
 pub mod process_executor_service;
 mod remote_cordinator_client;
 mod remote_cross_shard_client;
-mod remote_executor_client;
+pub mod remote_executor_client;
 pub mod remote_executor_service;
+mod shard_health_monitor;
 #[cfg(test)]
 mod test_utils;
 #[cfg(test)]
@@ -23,12 +27,15 @@ mod thread_executor_service;
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct RemoteExecutionResult {
-    pub inner: Result<Vec<Vec<TransactionOutput>>, VMStatus>,
+    /// Id of the block (as assigned by the coordinator) this is the result for. Results of blocks
+    /// the coordinator has already given up on are discarded based on it.
+    pub block_id: u64,
+    pub inner: Result<ShardExecutionResult, VMStatus>,
 }
 
 impl RemoteExecutionResult {
-    pub fn new(inner: Result<Vec<Vec<TransactionOutput>>, VMStatus>) -> Self {
-        Self { inner }
+    pub fn new(block_id: u64, inner: Result<ShardExecutionResult, VMStatus>) -> Self {
+        Self { block_id, inner }
     }
 }
 
@@ -39,6 +46,7 @@ pub enum RemoteExecutionRequest {
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct ExecuteBlockCommand {
+    pub(crate) block_id: u64,
     pub(crate) sub_blocks: SubBlocksForShard<AnalyzedTransaction>,
     // Currently we only support the state view backed by in-memory hashmap, which means that
     // the controller needs to pre-read all the KV pairs from the storage and pass them to the
@@ -47,23 +55,49 @@ pub struct ExecuteBlockCommand {
     // directly from the storage.
     pub(crate) state_view: InMemoryStateView,
     pub(crate) concurrency_level: usize,
-    pub(crate) maybe_block_gas_limit: Option<u64>,
+    pub(crate) block_gas_limit_type: BlockGasLimitType,
 }
 
 impl ExecuteBlockCommand {
     pub fn into(
         self,
     ) -> (
+        u64,
         SubBlocksForShard<AnalyzedTransaction>,
         InMemoryStateView,
         usize,
-        Option<u64>,
+        BlockGasLimitType,
     ) {
         (
+            self.block_id,
             self.sub_blocks,
             self.state_view,
             self.concurrency_level,
-            self.maybe_block_gas_limit,
+            self.block_gas_limit_type,
         )
     }
 }
+
+/// A cross-shard message tagged with the id of the block it belongs to. A shard may still be
+/// executing a block the coordinator has given up on, so its messages must not be mistaken for
+/// the ones of the next block.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct RemoteCrossShardMsg {
+    pub block_id: u64,
+    pub msg: CrossShardMsg,
+}
+
+/// Sent periodically by the coordinator to every shard, on a channel separate from the execution
+/// commands so that a shard busy executing a block still answers it.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct HeartbeatRequest {
+    pub seq: u64,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct HeartbeatResponse {
+    pub seq: u64,
+    pub shard_id: ShardId,
+    /// Id of the block the shard is currently executing, if any.
+    pub executing_block: Option<u64>,
+}
@@ -1,30 +1,67 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use clap::Parser;
+use aptos_executor_service::process_executor_service::ProcessExecutorService;
+use clap::{error::ErrorKind, CommandFactory, Parser};
+use std::net::SocketAddr;
 
 #[derive(Debug, Parser)]
 struct Args {
-    #[clap(long, default_value_t = 8080)]
-    pub server_port: u16,
-
     #[clap(long, default_value_t = 8)]
     pub num_executor_threads: usize,
+
+    #[clap(long)]
+    pub shard_id: usize,
+
+    #[clap(long)]
+    pub num_shards: usize,
+
+    /// Addresses of all the executor shards, in shard id order (including this one).
+    #[clap(long, num_args = 1..)]
+    pub remote_executor_addresses: Vec<SocketAddr>,
+
+    #[clap(long)]
+    pub coordinator_address: SocketAddr,
 }
 
 fn main() {
-    // TODO (skedia): Uncomment this once the executor service is implemented.
-    let _args = Args::parse();
+    let args = Args::parse();
     aptos_logger::Logger::new().init();
 
-    // let server_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), args.server_port);
-    // let executor_service =
-    //     ProcessExecutorService::new(server_addr, 1000, args.num_executor_threads);
-    // executor_service.run();
+    if args.remote_executor_addresses.len() != args.num_shards {
+        Args::command()
+            .error(
+                ErrorKind::WrongNumberOfValues,
+                format!(
+                    "An address is needed for every executor shard: got {} addresses for {} shards",
+                    args.remote_executor_addresses.len(),
+                    args.num_shards
+                ),
+            )
+            .exit();
+    }
+    if args.shard_id >= args.num_shards {
+        Args::command()
+            .error(
+                ErrorKind::ValueValidation,
+                format!(
+                    "The shard id {} is out of range for {} shards",
+                    args.shard_id, args.num_shards
+                ),
+            )
+            .exit();
+    }
+    // Runs until the process is killed.
+    let _executor_service = ProcessExecutorService::new(
+        args.shard_id,
+        args.num_shards,
+        args.num_executor_threads,
+        args.coordinator_address,
+        args.remote_executor_addresses,
+    );
 }
 
 #[test]
 fn verify_tool() {
-    use clap::CommandFactory;
     Args::command().debug_assert()
 }
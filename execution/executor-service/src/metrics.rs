@@ -0,0 +1,45 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_metrics_core::{
+    register_histogram_vec, register_int_counter_vec, register_int_gauge_vec, HistogramVec,
+    IntCounterVec, IntGaugeVec,
+};
+use once_cell::sync::Lazy;
+
+/// Whether a remote executor shard is currently considered available (1) or not (0).
+pub static REMOTE_EXECUTOR_SHARD_AVAILABLE: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register_int_gauge_vec!(
+        "remote_executor_shard_available",
+        "Whether the remote executor shard is healthy and idle",
+        &["shard_id"]
+    )
+    .unwrap()
+});
+
+pub static REMOTE_EXECUTOR_SHARD_FAILURES: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "remote_executor_shard_failures",
+        "Number of times a remote executor shard failed to return the result of a block",
+        &["shard_id", "reason"]
+    )
+    .unwrap()
+});
+
+pub static REMOTE_EXECUTOR_SHARD_EXECUTION_SECONDS: Lazy<HistogramVec> = Lazy::new(|| {
+    register_histogram_vec!(
+        "remote_executor_shard_execution_seconds",
+        "Time between sending a block to a remote executor shard and receiving its result",
+        &["shard_id"]
+    )
+    .unwrap()
+});
+
+pub static REMOTE_EXECUTOR_LOCAL_FALLBACK_BLOCKS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "remote_executor_local_fallback_blocks",
+        "Number of blocks executed by the local fallback instead of the remote shards",
+        &["reason"]
+    )
+    .unwrap()
+});
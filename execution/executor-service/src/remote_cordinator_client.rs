@@ -1,20 +1,26 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 use crate::{RemoteExecutionRequest, RemoteExecutionResult};
+use aptos_logger::error;
 use aptos_secure_net::network_controller::{Message, NetworkController};
 use aptos_state_view::in_memory_state_view::InMemoryStateView;
-use aptos_types::{
-    block_executor::partitioner::ShardId, transaction::TransactionOutput, vm_status::VMStatus,
-};
+use aptos_types::{block_executor::partitioner::ShardId, vm_status::VMStatus};
 use aptos_vm::sharded_block_executor::{
-    coordinator_client::CoordinatorClient, ExecutorShardCommand,
+    coordinator_client::CoordinatorClient, ExecutorShardCommand, ShardExecutionResult,
 };
 use crossbeam_channel::{Receiver, Sender};
-use std::{net::SocketAddr, sync::Arc};
+use std::{
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+};
 
 pub struct RemoteCoordinatorClient {
+    shard_id: ShardId,
     command_rx: Receiver<Message>,
     result_tx: Sender<Message>,
+    // Id of the block being executed, shared with the heartbeat responder so that the coordinator
+    // can tell a busy shard from an idle one.
+    executing_block: Arc<Mutex<Option<u64>>>,
 }
 
 impl RemoteCoordinatorClient {
@@ -30,32 +36,75 @@ impl RemoteCoordinatorClient {
             controller.create_outbound_channel(coordinator_address, execute_result_type);
 
         Self {
+            shard_id,
             command_rx,
             result_tx,
+            executing_block: Arc::new(Mutex::new(None)),
         }
     }
+
+    pub fn executing_block(&self) -> Arc<Mutex<Option<u64>>> {
+        self.executing_block.clone()
+    }
 }
 
 impl CoordinatorClient<InMemoryStateView> for RemoteCoordinatorClient {
     fn receive_execute_command(&self) -> ExecutorShardCommand<InMemoryStateView> {
-        let message = self.command_rx.recv().unwrap();
-        let request: RemoteExecutionRequest = bcs::from_bytes(&message.data).unwrap();
-        match request {
-            RemoteExecutionRequest::ExecuteBlock(command) => {
-                let (sub_blocks, state_view, concurrency, gas_limit) = command.into();
-                ExecutorShardCommand::ExecuteSubBlocks(
-                    Arc::new(state_view),
-                    sub_blocks,
-                    concurrency,
-                    gas_limit,
-                )
-            },
+        loop {
+            let message = match self.command_rx.recv() {
+                Ok(message) => message,
+                // The network controller is gone, nothing will ever be received again.
+                Err(_) => return ExecutorShardCommand::Stop,
+            };
+            let request: RemoteExecutionRequest = match bcs::from_bytes(&message.data) {
+                Ok(request) => request,
+                Err(e) => {
+                    error!("Failed to deserialize execute command: {:?}", e);
+                    continue;
+                },
+            };
+            match request {
+                RemoteExecutionRequest::ExecuteBlock(command) => {
+                    let (block_id, sub_blocks, state_view, concurrency, gas_limit) = command.into();
+                    *self.executing_block.lock().unwrap() = Some(block_id);
+                    return ExecutorShardCommand::ExecuteSubBlocks(
+                        Arc::new(state_view),
+                        sub_blocks,
+                        concurrency,
+                        gas_limit,
+                    );
+                },
+            }
         }
     }
 
-    fn send_execution_result(&self, result: Result<Vec<Vec<TransactionOutput>>, VMStatus>) {
-        let remote_execution_result = RemoteExecutionResult::new(result);
-        let output_message = bcs::to_bytes(&remote_execution_result).unwrap();
-        self.result_tx.send(Message::new(output_message)).unwrap();
+    fn send_execution_result(&self, result: Result<ShardExecutionResult, VMStatus>) {
+        let block_id = match self.executing_block.lock().unwrap().take() {
+            Some(block_id) => block_id,
+            None => {
+                error!(
+                    "Shard {} is dropping an execution result, no block is being executed",
+                    self.shard_id
+                );
+                return;
+            },
+        };
+        let remote_execution_result = RemoteExecutionResult::new(block_id, result);
+        let output_message = match bcs::to_bytes(&remote_execution_result) {
+            Ok(output_message) => output_message,
+            Err(error) => {
+                error!(
+                    "Shard {} failed to serialize the result of block {}: {:?}",
+                    self.shard_id, block_id, error
+                );
+                return;
+            },
+        };
+        if let Err(error) = self.result_tx.send(Message::new(output_message)) {
+            error!(
+                "Shard {} failed to send the result of block {}: {:?}",
+                self.shard_id, block_id, error
+            );
+        }
     }
 }
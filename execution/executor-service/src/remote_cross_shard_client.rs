@@ -1,6 +1,8 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
+use crate::RemoteCrossShardMsg;
 use aptos_block_partitioner::sharded_block_partitioner::MAX_ALLOWED_PARTITIONING_ROUNDS;
+use aptos_logger::{error, trace};
 use aptos_secure_net::network_controller::{Message, NetworkController};
 use aptos_types::block_executor::partitioner::{RoundId, ShardId};
 use aptos_vm::sharded_block_executor::{
@@ -12,15 +14,30 @@ use std::{
     sync::{Arc, Mutex},
 };
 
+/// The receiver of the cross-shard messages of a round, along with the messages that already
+/// arrived for a later block (i.e. from a shard that started the block before this one).
+struct RoundReceiver {
+    rx: Receiver<Message>,
+    early_msgs: Vec<RemoteCrossShardMsg>,
+}
+
 pub struct RemoteCrossShardClient {
     // The senders of cross-shard messages to other shards per round.
     message_txs: Arc<Vec<Vec<Mutex<Sender<Message>>>>>,
     // The receivers of cross shard messages from other shards per round.
-    message_rxs: Arc<Vec<Mutex<Receiver<Message>>>>,
+    message_rxs: Arc<Vec<Mutex<RoundReceiver>>>,
+    // Id of the block being executed, shared with the coordinator client. Messages are tagged
+    // with it, so that messages of a block the coordinator has given up on, which other shards
+    // may still be executing, are not mistaken for messages of the next block.
+    executing_block: Arc<Mutex<Option<u64>>>,
 }
 
 impl RemoteCrossShardClient {
-    pub fn new(controller: &mut NetworkController, shard_addresses: Vec<SocketAddr>) -> Self {
+    pub fn new(
+        controller: &mut NetworkController,
+        shard_addresses: Vec<SocketAddr>,
+        executing_block: Arc<Mutex<Option<u64>>>,
+    ) -> Self {
         let mut message_txs = vec![];
         let mut message_rxs = vec![];
         // Create outbound channels for each shard per round.
@@ -38,27 +55,72 @@ impl RemoteCrossShardClient {
         for round in 0..MAX_ALLOWED_PARTITIONING_ROUNDS {
             let message_type = format!("cross_shard_{}", round);
             let rx = controller.create_inbound_channel(message_type);
-            message_rxs.push(Mutex::new(rx));
+            message_rxs.push(Mutex::new(RoundReceiver {
+                rx,
+                early_msgs: vec![],
+            }));
         }
 
         Self {
             message_txs: Arc::new(message_txs),
             message_rxs: Arc::new(message_rxs),
+            executing_block,
         }
     }
+
+    fn executing_block(&self) -> Option<u64> {
+        *self.executing_block.lock().unwrap()
+    }
 }
 
 impl CrossShardClient for RemoteCrossShardClient {
     fn send_cross_shard_msg(&self, shard_id: ShardId, round: RoundId, msg: CrossShardMsg) {
-        let input_message = bcs::to_bytes(&msg).unwrap();
+        let block_id = match self.executing_block() {
+            Some(block_id) => block_id,
+            None => {
+                error!(
+                    "Dropping cross-shard message to shard {}, no block is being executed",
+                    shard_id
+                );
+                return;
+            },
+        };
+        let input_message = bcs::to_bytes(&RemoteCrossShardMsg { block_id, msg }).unwrap();
         let tx = self.message_txs[shard_id][round].lock().unwrap();
         tx.send(Message::new(input_message)).unwrap();
     }
 
     fn receive_cross_shard_msg(&self, current_round: RoundId) -> CrossShardMsg {
-        let rx = self.message_rxs[current_round].lock().unwrap();
-        let message = rx.recv().unwrap();
-        let msg: CrossShardMsg = bcs::from_bytes(&message.to_bytes()).unwrap();
-        msg
+        let mut receiver = self.message_rxs[current_round].lock().unwrap();
+        let block_id = self
+            .executing_block()
+            .expect("Cross-shard messages are only received while executing a block");
+
+        // Drop the messages of earlier blocks that were received early, and use the first one
+        // of the current block if any.
+        receiver.early_msgs.retain(|msg| msg.block_id >= block_id);
+        if let Some(index) = receiver
+            .early_msgs
+            .iter()
+            .position(|msg| msg.block_id == block_id)
+        {
+            return receiver.early_msgs.remove(index).msg;
+        }
+
+        loop {
+            let message = receiver.rx.recv().unwrap();
+            let msg: RemoteCrossShardMsg = bcs::from_bytes(&message.to_bytes()).unwrap();
+            if msg.block_id == block_id {
+                return msg.msg;
+            } else if msg.block_id > block_id {
+                receiver.early_msgs.push(msg);
+            } else {
+                trace!(
+                    "Discarding cross-shard message of abandoned block {} in round {}",
+                    msg.block_id,
+                    current_round
+                );
+            }
+        }
     }
 }
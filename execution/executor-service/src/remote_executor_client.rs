@@ -1,24 +1,91 @@
 // Copyright © Aptos Foundation
-// Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
-use crate::{ExecuteBlockCommand, RemoteExecutionRequest, RemoteExecutionResult};
-use aptos_logger::trace;
+use crate::{
+    metrics::{
+        REMOTE_EXECUTOR_LOCAL_FALLBACK_BLOCKS, REMOTE_EXECUTOR_SHARD_EXECUTION_SECONDS,
+        REMOTE_EXECUTOR_SHARD_FAILURES,
+    },
+    shard_health_monitor::ShardHealthMonitor,
+    ExecuteBlockCommand, RemoteExecutionRequest, RemoteExecutionResult,
+};
+use aptos_config::config::RemoteExecutionConfig;
+use aptos_logger::{info, trace, warn};
 use aptos_secure_net::network_controller::{Message, NetworkController};
 use aptos_state_view::StateView;
 use aptos_types::{
-    block_executor::partitioner::SubBlocksForShard,
-    transaction::{analyzed_transaction::AnalyzedTransaction, TransactionOutput},
+    block_executor::partitioner::{ShardId, SubBlocksForShard},
+    on_chain_config::BlockGasLimitType,
+    transaction::analyzed_transaction::{AnalyzedTransaction, StorageLocation},
     vm_status::VMStatus,
 };
-use aptos_vm::sharded_block_executor::executor_client::ExecutorClient;
-use crossbeam_channel::{Receiver, Sender};
+use aptos_vm::sharded_block_executor::{
+    executor_client::ExecutorClient,
+    local_executor_shard::{LocalExecutorClient, LocalExecutorService},
+    ShardExecutionResult,
+};
+use crossbeam_channel::{Receiver, RecvTimeoutError, Sender};
+use once_cell::sync::OnceCell;
+use rayon::prelude::*;
 use std::{
     net::SocketAddr,
-    ops::Deref,
-    sync::{Arc, Mutex},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
 };
 
-#[allow(dead_code)]
+static REMOTE_EXECUTION_CONFIG: OnceCell<RemoteExecutionConfig> = OnceCell::new();
+
+pub const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(1);
+pub const HEARTBEAT_TIMEOUT: Duration = Duration::from_secs(30);
+pub const EXECUTION_TIMEOUT: Duration = Duration::from_secs(120);
+// How often the liveness of the shards is re-checked while waiting for their results.
+const RESULT_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Sets the addresses of the coordinator and the remote executor shards (and the timeouts to
+/// detect failed shards with), which enables remote sharded execution in the executor. Only the
+/// first call succeeds.
+pub fn set_remote_execution_config_once(config: RemoteExecutionConfig) {
+    REMOTE_EXECUTION_CONFIG.set(config).ok();
+}
+
+pub fn get_remote_execution_config() -> Option<RemoteExecutionConfig> {
+    REMOTE_EXECUTION_CONFIG.get().cloned()
+}
+
+/// Why the result of a remote shard could not be used.
+#[derive(Debug)]
+struct ShardFailure {
+    shard_id: ShardId,
+    reason: &'static str,
+}
+
+enum ExecutionMode {
+    Remote { dispatched_at: Instant },
+    Local,
+}
+
+/// Everything needed to collect the result of the in-flight block, or to re-execute it locally if
+/// a shard fails.
+struct PendingBlock<S> {
+    block_id: u64,
+    mode: ExecutionMode,
+    state_view: Arc<S>,
+    block: Vec<SubBlocksForShard<AnalyzedTransaction>>,
+    concurrency_level_per_shard: usize,
+    block_gas_limit_type: BlockGasLimitType,
+}
+
+/// Executor client that runs the shards of a block on remote executor services (see
+/// `ExecutorService`), e.g. in other processes or on other machines.
+///
+/// The liveness of the shards is tracked with heartbeats. A block is only sent to the remote
+/// shards when all of them are alive and idle, and otherwise executed by a local fallback. When a
+/// shard dies or times out while executing a block, the whole block is re-executed by the local
+/// fallback: the other shards may be blocked on cross-shard messages from the failed one, so the
+/// sub-blocks of the failed shard cannot be re-dispatched on their own. Results that remote
+/// shards send for abandoned blocks are discarded.
 pub struct RemoteExecutorClient<S: StateView + Sync + Send + 'static> {
     // Channels to send execute block commands to the executor shards.
     command_txs: Arc<Vec<Mutex<Sender<Message>>>>,
@@ -26,15 +93,52 @@ pub struct RemoteExecutorClient<S: StateView + Sync + Send + 'static> {
     result_rxs: Vec<Receiver<Message>>,
     // Thread pool used to pre-fetch the state values for the block in parallel and create an in-memory state view.
     thread_pool: Arc<rayon::ThreadPool>,
-    phantom: std::marker::PhantomData<S>,
+    health_monitor: ShardHealthMonitor,
+    // Created on first use, a coordinator whose shards are healthy never needs it.
+    local_fallback: OnceCell<LocalExecutorClient<S>>,
+    execution_timeout: Duration,
+    next_block_id: AtomicU64,
+    pending_block: Mutex<Option<PendingBlock<S>>>,
 }
 
-#[allow(dead_code)]
 impl<S: StateView + Sync + Send + 'static> RemoteExecutorClient<S> {
     pub fn new(
         remote_shard_addresses: Vec<SocketAddr>,
         controller: &mut NetworkController,
         num_threads: Option<usize>,
+    ) -> Self {
+        Self::new_with_timeouts(
+            remote_shard_addresses,
+            controller,
+            num_threads,
+            HEARTBEAT_INTERVAL,
+            HEARTBEAT_TIMEOUT,
+            EXECUTION_TIMEOUT,
+        )
+    }
+
+    pub fn from_config(
+        config: &RemoteExecutionConfig,
+        controller: &mut NetworkController,
+        num_threads: Option<usize>,
+    ) -> Self {
+        Self::new_with_timeouts(
+            config.remote_executor_addresses.clone(),
+            controller,
+            num_threads,
+            Duration::from_millis(config.heartbeat_interval_ms),
+            Duration::from_millis(config.heartbeat_timeout_ms),
+            Duration::from_millis(config.execution_timeout_ms),
+        )
+    }
+
+    pub fn new_with_timeouts(
+        remote_shard_addresses: Vec<SocketAddr>,
+        controller: &mut NetworkController,
+        num_threads: Option<usize>,
+        heartbeat_interval: Duration,
+        heartbeat_timeout: Duration,
+        execution_timeout: Duration,
     ) -> Self {
         let num_threads = num_threads.unwrap_or_else(num_cpus::get);
         let thread_pool = Arc::new(
@@ -55,12 +159,168 @@ impl<S: StateView + Sync + Send + 'static> RemoteExecutorClient<S> {
                 (command_tx, result_rx)
             })
             .unzip();
+        let health_monitor = ShardHealthMonitor::new(
+            &remote_shard_addresses,
+            controller,
+            heartbeat_interval,
+            heartbeat_timeout,
+        );
         Self {
             command_txs: Arc::new(command_txs),
             result_rxs,
             thread_pool,
-            phantom: std::marker::PhantomData,
+            health_monitor,
+            local_fallback: OnceCell::new(),
+            execution_timeout,
+            next_block_id: AtomicU64::new(0),
+            pending_block: Mutex::new(None),
+        }
+    }
+
+    /// Whether the next block will be sent to the remote shards.
+    pub fn all_shards_available(&self) -> bool {
+        self.health_monitor.all_available()
+    }
+
+    fn local_fallback(&self) -> &LocalExecutorClient<S> {
+        self.local_fallback.get_or_init(|| {
+            info!("Starting local fallback executor shards");
+            LocalExecutorService::setup_local_executor_shards(self.num_shards(), None)
+        })
+    }
+
+    fn execute_block_locally(&self, pending: &PendingBlock<S>, reason: &str) {
+        REMOTE_EXECUTOR_LOCAL_FALLBACK_BLOCKS
+            .with_label_values(&[reason])
+            .inc();
+        self.local_fallback().execute_block(
+            pending.state_view.clone(),
+            pending.block.clone(),
+            pending.concurrency_level_per_shard,
+            pending.block_gas_limit_type,
+        );
+    }
+
+    /// Sends the block to the remote shards. The shards only get an in-memory snapshot of the
+    /// state, so the state values hinted by the partitioner are pre-fetched into the state view
+    /// first.
+    fn dispatch_block(&self, pending: &PendingBlock<S>) -> Result<(), ShardFailure> {
+        let state_view = &pending.state_view;
+        let in_memory_state_view = self.thread_pool.install(|| {
+            pending
+                .block
+                .par_iter()
+                .flat_map_iter(|sub_blocks| sub_blocks.iter())
+                .for_each(|txn| {
+                    let txn = txn.txn();
+                    for location in txn.read_hints().iter().chain(txn.write_hints()) {
+                        if let StorageLocation::Specific(state_key) = location {
+                            // Errors surface again when the shard reads the key.
+                            let _ = state_view.get_state_value(state_key);
+                        }
+                    }
+                });
+            // TODO(skedia): Instead of serializing the entire state view, we should
+            // serialize only the state values needed for the shard.
+            state_view.as_in_memory_state_view()
+        });
+
+        self.thread_pool.install(|| {
+            pending
+                .block
+                .par_iter()
+                .enumerate()
+                .try_for_each(|(shard_id, sub_blocks)| {
+                    let execution_request =
+                        RemoteExecutionRequest::ExecuteBlock(ExecuteBlockCommand {
+                            block_id: pending.block_id,
+                            sub_blocks: sub_blocks.clone(),
+                            state_view: in_memory_state_view.clone(),
+                            concurrency_level: pending.concurrency_level_per_shard,
+                            block_gas_limit_type: pending.block_gas_limit_type,
+                        });
+                    let bytes = bcs::to_bytes(&execution_request).map_err(|_| ShardFailure {
+                        shard_id,
+                        reason: "serialization",
+                    })?;
+                    self.command_txs[shard_id]
+                        .lock()
+                        .unwrap()
+                        .send(Message::new(bytes))
+                        .map_err(|_| ShardFailure {
+                            shard_id,
+                            reason: "send",
+                        })
+                })
+        })
+    }
+
+    /// Waits for the result of the block from every remote shard, giving up on the block if a
+    /// shard stops answering heartbeats or does not return its result in time.
+    fn collect_remote_results(
+        &self,
+        pending: &PendingBlock<S>,
+        dispatched_at: Instant,
+    ) -> Result<Vec<Result<ShardExecutionResult, VMStatus>>, ShardFailure> {
+        let mut results = vec![];
+        for (shard_id, rx) in self.result_rxs.iter().enumerate() {
+            let result = loop {
+                let message = match rx.recv_timeout(RESULT_POLL_INTERVAL) {
+                    Ok(message) => message,
+                    Err(RecvTimeoutError::Timeout) => {
+                        if !self.health_monitor.is_alive(shard_id) {
+                            return Err(ShardFailure {
+                                shard_id,
+                                reason: "heartbeat",
+                            });
+                        }
+                        if dispatched_at.elapsed() > self.execution_timeout {
+                            return Err(ShardFailure {
+                                shard_id,
+                                reason: "timeout",
+                            });
+                        }
+                        continue;
+                    },
+                    Err(RecvTimeoutError::Disconnected) => {
+                        return Err(ShardFailure {
+                            shard_id,
+                            reason: "disconnected",
+                        })
+                    },
+                };
+                let result: RemoteExecutionResult =
+                    bcs::from_bytes(&message.to_bytes()).map_err(|_| ShardFailure {
+                        shard_id,
+                        reason: "deserialization",
+                    })?;
+                if result.block_id == pending.block_id {
+                    break result.inner;
+                }
+                trace!(
+                    "Discarding result of abandoned block {} from shard {}",
+                    result.block_id,
+                    shard_id
+                );
+            };
+            REMOTE_EXECUTOR_SHARD_EXECUTION_SECONDS
+                .with_label_values(&[&shard_id.to_string()])
+                .observe(dispatched_at.elapsed().as_secs_f64());
+            results.push(result);
         }
+        Ok(results)
+    }
+
+    fn handle_shard_failure(&self, pending: &PendingBlock<S>, failure: ShardFailure) {
+        warn!(
+            "Remote executor shard {} failed block {} ({}), re-executing the block locally",
+            failure.shard_id, pending.block_id, failure.reason
+        );
+        REMOTE_EXECUTOR_SHARD_FAILURES
+            .with_label_values(&[&failure.shard_id.to_string(), failure.reason])
+            .inc();
+        self.health_monitor.mark_failed(failure.shard_id);
+        self.execute_block_locally(pending, "shard_failure");
     }
 }
 
@@ -74,41 +334,42 @@ impl<S: StateView + Sync + Send + 'static> ExecutorClient<S> for RemoteExecutorC
         state_view: Arc<S>,
         block: Vec<SubBlocksForShard<AnalyzedTransaction>>,
         concurrency_level_per_shard: usize,
-        maybe_block_gas_limit: Option<u64>,
+        block_gas_limit_type: BlockGasLimitType,
     ) {
-        self.thread_pool.scope(|s| {
-            for (shard_id, sub_blocks) in block.into_iter().enumerate() {
-                let state_view = state_view.clone();
-                let senders = self.command_txs.clone();
-                s.spawn(move |_| {
-                    let execution_request =
-                        RemoteExecutionRequest::ExecuteBlock(ExecuteBlockCommand {
-                            sub_blocks,
-                            // TODO(skedia): Instead of serializing the entire state view, we should
-                            // serialize only the state values needed for the shard.
-                            state_view: S::as_in_memory_state_view(state_view.deref()),
-                            concurrency_level: concurrency_level_per_shard,
-                            maybe_block_gas_limit,
-                        });
-
-                    senders[shard_id]
-                        .lock()
-                        .unwrap()
-                        .send(Message::new(bcs::to_bytes(&execution_request).unwrap()))
-                        .unwrap()
-                });
+        let mut pending = PendingBlock {
+            block_id: self.next_block_id.fetch_add(1, Ordering::Relaxed),
+            mode: ExecutionMode::Local,
+            state_view,
+            block,
+            concurrency_level_per_shard,
+            block_gas_limit_type,
+        };
+        if self.health_monitor.all_available() {
+            let dispatched_at = Instant::now();
+            match self.dispatch_block(&pending) {
+                Ok(()) => pending.mode = ExecutionMode::Remote { dispatched_at },
+                Err(failure) => self.handle_shard_failure(&pending, failure),
             }
-        });
+        } else {
+            self.execute_block_locally(&pending, "shards_unavailable");
+        }
+        *self.pending_block.lock().unwrap() = Some(pending);
     }
 
-    fn get_execution_result(&self) -> Result<Vec<Vec<Vec<TransactionOutput>>>, VMStatus> {
+    fn get_execution_result(&self) -> Result<Vec<ShardExecutionResult>, VMStatus> {
         trace!("RemoteExecutorClient Waiting for results");
-        let mut results = vec![];
-        for rx in self.result_rxs.iter() {
-            let received_bytes = rx.recv().unwrap().to_bytes();
-            let result: RemoteExecutionResult = bcs::from_bytes(&received_bytes).unwrap();
-            results.push(result.inner?);
+        let pending = self
+            .pending_block
+            .lock()
+            .unwrap()
+            .take()
+            .expect("get_execution_result called without a block being executed");
+        if let ExecutionMode::Remote { dispatched_at } = pending.mode {
+            match self.collect_remote_results(&pending, dispatched_at) {
+                Ok(results) => return results.into_iter().collect(),
+                Err(failure) => self.handle_shard_failure(&pending, failure),
+            }
         }
-        Ok(results)
+        self.local_fallback().get_execution_result()
     }
 }
@@ -3,19 +3,29 @@
 
 use crate::{
     remote_cordinator_client::RemoteCoordinatorClient,
-    remote_cross_shard_client::RemoteCrossShardClient,
+    remote_cross_shard_client::RemoteCrossShardClient, HeartbeatRequest, HeartbeatResponse,
 };
-use aptos_secure_net::network_controller::NetworkController;
+use aptos_logger::warn;
+use aptos_secure_net::network_controller::{Message, NetworkController};
 use aptos_state_view::in_memory_state_view::InMemoryStateView;
 use aptos_types::block_executor::partitioner::ShardId;
 use aptos_vm::sharded_block_executor::sharded_executor_service::ShardedExecutorService;
-use std::{net::SocketAddr, sync::Arc};
+use crossbeam_channel::{Receiver, Sender};
+use std::{
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+    thread,
+};
 
 /// A service that provides support for remote execution. Essentially, it reads a request from
 /// the remote executor client and executes the block locally and returns the result.
 pub struct ExecutorService {
+    shard_id: ShardId,
     controller: NetworkController,
     executor_service: Arc<ShardedExecutorService<InMemoryStateView>>,
+    heartbeat_rx: Receiver<Message>,
+    heartbeat_tx: Sender<Message>,
+    executing_block: Arc<Mutex<Option<u64>>>,
 }
 
 impl ExecutorService {
@@ -34,10 +44,18 @@ impl ExecutorService {
             &mut controller,
             coordinator_address,
         ));
+        let executing_block = coordinator_client.executing_block();
         let cross_shard_client = Arc::new(RemoteCrossShardClient::new(
             &mut controller,
             remote_shard_addresses,
+            executing_block.clone(),
         ));
+        let heartbeat_rx =
+            controller.create_inbound_channel(format!("heartbeat_request_{}", shard_id));
+        let heartbeat_tx = controller.create_outbound_channel(
+            coordinator_address,
+            format!("heartbeat_response_{}", shard_id),
+        );
 
         let executor_service = Arc::new(ShardedExecutorService::new(
             shard_id,
@@ -48,13 +66,50 @@ impl ExecutorService {
         ));
 
         Self {
+            shard_id,
             controller,
             executor_service,
+            heartbeat_rx,
+            heartbeat_tx,
+            executing_block,
         }
     }
 
     pub fn start(&mut self) {
         self.controller.start();
+        self.start_heartbeat_responder();
         self.executor_service.start();
     }
+
+    /// Answers the heartbeats of the coordinator from a dedicated thread, so that the coordinator
+    /// can tell a shard that is busy executing a (large) block from one that is gone.
+    fn start_heartbeat_responder(&self) {
+        let shard_id = self.shard_id;
+        let heartbeat_rx = self.heartbeat_rx.clone();
+        let heartbeat_tx = self.heartbeat_tx.clone();
+        let executing_block = self.executing_block.clone();
+        thread::Builder::new()
+            .name(format!("executor-shard-{}-heartbeat", shard_id))
+            .spawn(move || {
+                while let Ok(message) = heartbeat_rx.recv() {
+                    let request: HeartbeatRequest = match bcs::from_bytes(&message.data) {
+                        Ok(request) => request,
+                        Err(e) => {
+                            warn!("Failed to deserialize heartbeat request: {:?}", e);
+                            continue;
+                        },
+                    };
+                    let response = HeartbeatResponse {
+                        seq: request.seq,
+                        shard_id,
+                        executing_block: *executing_block.lock().unwrap(),
+                    };
+                    let message = Message::new(bcs::to_bytes(&response).unwrap());
+                    if heartbeat_tx.send(message).is_err() {
+                        break;
+                    }
+                }
+            })
+            .expect("Failed to spawn heartbeat responder thread");
+    }
 }
@@ -0,0 +1,154 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{metrics::REMOTE_EXECUTOR_SHARD_AVAILABLE, HeartbeatRequest, HeartbeatResponse};
+use aptos_logger::{info, warn};
+use aptos_secure_net::network_controller::{Message, NetworkController};
+use aptos_types::block_executor::partitioner::ShardId;
+use crossbeam_channel::{Receiver, Sender};
+use std::{
+    net::SocketAddr,
+    sync::{Arc, Mutex, Weak},
+    thread,
+    time::{Duration, Instant},
+};
+
+#[derive(Default)]
+struct ShardHealth {
+    last_heartbeat: Option<Instant>,
+    executing_block: Option<u64>,
+    failed_at: Option<Instant>,
+}
+
+impl ShardHealth {
+    fn is_alive(&self, heartbeat_timeout: Duration) -> bool {
+        self.last_heartbeat.map_or(false, |last_heartbeat| {
+            last_heartbeat.elapsed() <= heartbeat_timeout
+        })
+    }
+
+    /// A shard can be given a new block if it is alive and idle. If it failed a block before, it
+    /// additionally needs to have answered a heartbeat since then.
+    fn is_available(&self, heartbeat_timeout: Duration) -> bool {
+        let recovered = match (self.failed_at, self.last_heartbeat) {
+            (None, _) => true,
+            (Some(failed_at), Some(last_heartbeat)) => last_heartbeat > failed_at,
+            (Some(_), None) => false,
+        };
+        recovered && self.executing_block.is_none() && self.is_alive(heartbeat_timeout)
+    }
+}
+
+/// Tracks the liveness of the remote executor shards on the coordinator side. A background thread
+/// sends a heartbeat to every shard each `heartbeat_interval`; a shard that has not answered for
+/// `heartbeat_timeout` is considered dead.
+pub(crate) struct ShardHealthMonitor {
+    shards: Arc<Vec<Mutex<ShardHealth>>>,
+    heartbeat_timeout: Duration,
+}
+
+impl ShardHealthMonitor {
+    pub fn new(
+        remote_shard_addresses: &[SocketAddr],
+        controller: &mut NetworkController,
+        heartbeat_interval: Duration,
+        heartbeat_timeout: Duration,
+    ) -> Self {
+        let (request_txs, response_rxs): (Vec<_>, Vec<_>) = remote_shard_addresses
+            .iter()
+            .enumerate()
+            .map(|(shard_id, address)| {
+                let request_tx = controller
+                    .create_outbound_channel(*address, format!("heartbeat_request_{}", shard_id));
+                let response_rx =
+                    controller.create_inbound_channel(format!("heartbeat_response_{}", shard_id));
+                (request_tx, response_rx)
+            })
+            .unzip();
+        let shards = Arc::new(
+            (0..remote_shard_addresses.len())
+                .map(|_| Mutex::new(ShardHealth::default()))
+                .collect::<Vec<_>>(),
+        );
+
+        let weak_shards = Arc::downgrade(&shards);
+        thread::Builder::new()
+            .name("remote-executor-heartbeat".to_string())
+            .spawn(move || {
+                Self::run_heartbeats(
+                    weak_shards,
+                    request_txs,
+                    response_rxs,
+                    heartbeat_interval,
+                    heartbeat_timeout,
+                )
+            })
+            .expect("Failed to spawn heartbeat thread");
+
+        Self {
+            shards,
+            heartbeat_timeout,
+        }
+    }
+
+    fn run_heartbeats(
+        shards: Weak<Vec<Mutex<ShardHealth>>>,
+        request_txs: Vec<Sender<Message>>,
+        response_rxs: Vec<Receiver<Message>>,
+        heartbeat_interval: Duration,
+        heartbeat_timeout: Duration,
+    ) {
+        for seq in 0.. {
+            // Stop once the monitor (i.e. the executor client) is dropped.
+            let shards = match shards.upgrade() {
+                Some(shards) => shards,
+                None => return,
+            };
+            for (shard_id, shard) in shards.iter().enumerate() {
+                let mut health = shard.lock().unwrap();
+                for message in response_rxs[shard_id].try_iter() {
+                    match bcs::from_bytes::<HeartbeatResponse>(&message.data) {
+                        Ok(response) => {
+                            health.last_heartbeat = Some(Instant::now());
+                            health.executing_block = response.executing_block;
+                        },
+                        Err(e) => warn!("Failed to deserialize heartbeat response: {:?}", e),
+                    }
+                }
+                REMOTE_EXECUTOR_SHARD_AVAILABLE
+                    .with_label_values(&[&shard_id.to_string()])
+                    .set(health.is_available(heartbeat_timeout) as i64);
+
+                let request = bcs::to_bytes(&HeartbeatRequest { seq }).unwrap();
+                if request_txs[shard_id].send(Message::new(request)).is_err() {
+                    // The network controller has shut down.
+                    return;
+                }
+            }
+            drop(shards);
+            thread::sleep(heartbeat_interval);
+        }
+    }
+
+    /// Whether all the shards can be given a new block.
+    pub fn all_available(&self) -> bool {
+        self.shards
+            .iter()
+            .all(|shard| shard.lock().unwrap().is_available(self.heartbeat_timeout))
+    }
+
+    /// Whether the shard has answered heartbeats recently, regardless of whether it is busy.
+    pub fn is_alive(&self, shard_id: ShardId) -> bool {
+        self.shards[shard_id]
+            .lock()
+            .unwrap()
+            .is_alive(self.heartbeat_timeout)
+    }
+
+    /// Marks the shard as failed. It will not be given new blocks before it answers a heartbeat
+    /// again and reports to be idle.
+    pub fn mark_failed(&self, shard_id: ShardId) {
+        info!("Marking remote executor shard {} as failed", shard_id);
+        self.shards[shard_id].lock().unwrap().failed_at = Some(Instant::now());
+    }
+}
@@ -6,6 +6,7 @@ use aptos_language_e2e_tests::{
     executor::FakeExecutor,
 };
 use aptos_types::{
+    on_chain_config::BlockGasLimitType,
     state_store::state_key::StateKeyInner,
     transaction::{analyzed_transaction::AnalyzedTransaction, Transaction, TransactionOutput},
 };
@@ -108,13 +109,13 @@ pub fn test_sharded_block_executor_no_conflict<E: ExecutorClient<FakeDataStore>>
             Arc::new(executor.data_store().clone()),
             partitioned_txns,
             2,
-            None,
+            BlockGasLimitType::NoLimit,
         )
         .unwrap();
     let unsharded_txn_output = AptosVM::execute_block(
         transactions.into_iter().map(|t| t.into_txn()).collect(),
         &executor.data_store(),
-        None,
+        BlockGasLimitType::NoLimit,
     )
     .unwrap();
     compare_txn_outputs(unsharded_txn_output, sharded_txn_output);
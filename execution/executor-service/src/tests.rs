@@ -2,42 +2,59 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    remote_executor_client::RemoteExecutorClient, test_utils,
+    remote_executor_client::{RemoteExecutorClient, EXECUTION_TIMEOUT},
+    test_utils,
     thread_executor_service::ThreadExecutorService,
+    HeartbeatRequest, HeartbeatResponse,
 };
 use aptos_config::utils;
 use aptos_language_e2e_tests::data_store::FakeDataStore;
-use aptos_secure_net::network_controller::NetworkController;
+use aptos_secure_net::network_controller::{Message, NetworkController};
+use aptos_types::block_executor::partitioner::ShardId;
 use aptos_vm::sharded_block_executor::ShardedBlockExecutor;
-use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::{
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    thread,
+    time::{Duration, Instant},
+};
+
+// Much shorter than the defaults, so that the tests don't wait long for the shards to be (or not
+// to be) detected as alive.
+const TEST_HEARTBEAT_INTERVAL: Duration = Duration::from_millis(100);
+const TEST_HEARTBEAT_TIMEOUT: Duration = Duration::from_secs(1);
 
+fn local_address() -> SocketAddr {
+    SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), utils::get_available_port())
+}
+
+/// Creates the remote executor client and starts an executor service thread for every shard that
+/// is not in `missing_shards`.
 pub fn create_thread_remote_executor_shards(
     num_shards: usize,
     num_threads: Option<usize>,
+    missing_shards: &[ShardId],
+    execution_timeout: Duration,
 ) -> (
     NetworkController,
     RemoteExecutorClient<FakeDataStore>,
     Vec<ThreadExecutorService>,
+    SocketAddr,
+    Vec<SocketAddr>,
 ) {
     // First create the coordinator.
-    let listen_port = utils::get_available_port();
-    let coordinator_address = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), listen_port);
+    let coordinator_address = local_address();
     let mut controller = NetworkController::new(
         "remote-executor-coordinator".to_string(),
         coordinator_address,
         5000,
     );
-    let remote_shard_addresses = (0..num_shards)
-        .map(|_| {
-            let listen_port = utils::get_available_port();
-            SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), listen_port)
-        })
-        .collect::<Vec<_>>();
+    let remote_shard_addresses = (0..num_shards).map(|_| local_address()).collect::<Vec<_>>();
 
     let num_threads =
         num_threads.unwrap_or_else(|| (num_cpus::get() as f64 / num_shards as f64).ceil() as usize);
 
     let remote_executor_services = (0..num_shards)
+        .filter(|shard_id| !missing_shards.contains(shard_id))
         .map(|shard_id| {
             ThreadExecutorService::new(
                 shard_id,
@@ -49,17 +66,103 @@ pub fn create_thread_remote_executor_shards(
         })
         .collect::<Vec<_>>();
 
-    let remote_executor_client =
-        RemoteExecutorClient::new(remote_shard_addresses, &mut controller, None);
-    (controller, remote_executor_client, remote_executor_services)
+    let remote_executor_client = RemoteExecutorClient::new_with_timeouts(
+        remote_shard_addresses.clone(),
+        &mut controller,
+        None,
+        TEST_HEARTBEAT_INTERVAL,
+        TEST_HEARTBEAT_TIMEOUT,
+        execution_timeout,
+    );
+    (
+        controller,
+        remote_executor_client,
+        remote_executor_services,
+        coordinator_address,
+        remote_shard_addresses,
+    )
+}
+
+fn wait_for_shards(executor_client: &RemoteExecutorClient<FakeDataStore>) {
+    let start = Instant::now();
+    while !executor_client.all_shards_available() {
+        assert!(
+            start.elapsed() < Duration::from_secs(60),
+            "Remote executor shards did not become available"
+        );
+        thread::sleep(TEST_HEARTBEAT_INTERVAL);
+    }
+}
+
+/// Starts a shard that answers heartbeats as if it was idle, but never executes anything.
+fn start_unresponsive_shard(
+    shard_id: ShardId,
+    self_address: SocketAddr,
+    coordinator_address: SocketAddr,
+) -> NetworkController {
+    let mut controller = NetworkController::new(
+        format!("unresponsive-shard-{}", shard_id),
+        self_address,
+        5000,
+    );
+    let command_rx = controller.create_inbound_channel(format!("execute_command_{}", shard_id));
+    let heartbeat_rx = controller.create_inbound_channel(format!("heartbeat_request_{}", shard_id));
+    let heartbeat_tx = controller.create_outbound_channel(
+        coordinator_address,
+        format!("heartbeat_response_{}", shard_id),
+    );
+    controller.start();
+    thread::spawn(move || {
+        // Keep the command channel open, so that commands are swallowed silently.
+        let _command_rx = command_rx;
+        while let Ok(message) = heartbeat_rx.recv() {
+            let request: HeartbeatRequest = bcs::from_bytes(&message.data).unwrap();
+            let response = HeartbeatResponse {
+                seq: request.seq,
+                shard_id,
+                executing_block: None,
+            };
+            heartbeat_tx
+                .send(Message::new(bcs::to_bytes(&response).unwrap()))
+                .unwrap();
+        }
+    });
+    controller
 }
 
 #[test]
 fn test_sharded_block_executor_no_conflict() {
-    let num_shards = 8;
-    let (mut controller, executor_client, _executor_services) =
-        create_thread_remote_executor_shards(num_shards, Some(2));
+    let (mut controller, executor_client, _executor_services, _, _) =
+        create_thread_remote_executor_shards(8, Some(2), &[], EXECUTION_TIMEOUT);
+    controller.start();
+    wait_for_shards(&executor_client);
+    let sharded_block_executor = ShardedBlockExecutor::new(executor_client);
+    test_utils::test_sharded_block_executor_no_conflict(sharded_block_executor);
+}
+
+#[test]
+fn test_sharded_block_executor_missing_shard() {
+    // Shard 1 is never started, so the block must be executed locally instead
+    let (mut controller, executor_client, _executor_services, _, _) =
+        create_thread_remote_executor_shards(4, Some(2), &[1], EXECUTION_TIMEOUT);
     controller.start();
+    thread::sleep(TEST_HEARTBEAT_TIMEOUT);
+    assert!(!executor_client.all_shards_available());
+
+    let sharded_block_executor = ShardedBlockExecutor::new(executor_client);
+    test_utils::test_sharded_block_executor_no_conflict(sharded_block_executor);
+}
+
+#[test]
+fn test_sharded_block_executor_unresponsive_shard() {
+    // Shard 1 answers heartbeats but never returns a result, so the execution times out and the
+    // block must be executed locally instead
+    let (mut controller, executor_client, _executor_services, coordinator_address, shard_addresses) =
+        create_thread_remote_executor_shards(4, Some(2), &[1], Duration::from_secs(5));
+    let _unresponsive_shard = start_unresponsive_shard(1, shard_addresses[1], coordinator_address);
+    controller.start();
+    wait_for_shards(&executor_client);
+
     let sharded_block_executor = ShardedBlockExecutor::new(executor_client);
     test_utils::test_sharded_block_executor_no_conflict(sharded_block_executor);
 }
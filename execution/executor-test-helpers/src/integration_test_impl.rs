@@ -442,7 +442,7 @@ pub fn test_execution_with_storage_impl() -> Arc<AptosDB> {
     .unwrap();
 
     // With block gas limit, StateCheckpoint txn is inserted to block after execution.
-    let diff = BLOCK_GAS_LIMIT.map(|_| 0).unwrap_or(1);
+    let diff = if BLOCK_GAS_LIMIT.is_limited() { 0 } else { 1 };
 
     let transaction_list_with_proof = db
         .reader
@@ -14,6 +14,7 @@ use aptos_types::{
     contract_event::ContractEvent,
     epoch_state::EpochState,
     ledger_info::LedgerInfoWithSignatures,
+    on_chain_config::BlockGasLimitType,
     proof::{accumulator::InMemoryAccumulator, AccumulatorExtensionProof, SparseMerkleProofExt},
     state_store::{state_key::StateKey, state_value::StateValue},
     transaction::{
@@ -31,6 +32,7 @@ use std::{
     cmp::max,
     collections::{BTreeSet, HashMap},
     fmt::Debug,
+    path::{Path, PathBuf},
     sync::{
         atomic::{AtomicBool, Ordering},
         Arc,
@@ -92,9 +94,14 @@ pub trait BlockExecutorTrait: Send + Sync {
         &self,
         block: ExecutableBlock,
         parent_block_id: HashValue,
-        maybe_block_gas_limit: Option<u64>,
+        block_gas_limit_type: BlockGasLimitType,
     ) -> Result<StateComputeResult, Error>;
 
+    /// Speculatively executes transactions that are likely to be executed soon in a child block of
+    /// `parent_block_id`, so that executing the block can reuse the results. Best effort, no-op by
+    /// default.
+    fn pre_execute_block(&self, _transactions: Vec<Transaction>, _parent_block_id: HashValue) {}
+
     /// Saves eligible blocks to persistent storage.
     /// If we have multiple blocks and not all of them have signatures, we may send them to storage
     /// in a few batches. For example, if we have
@@ -134,6 +141,8 @@ pub enum VerifyExecutionMode {
         txns_to_skip: Arc<BTreeSet<Version>>,
         lazy_quit: bool,
         seen_error: Arc<AtomicBool>,
+        /// Directory to dump the expected and actual outputs of mismatching transactions to.
+        mismatch_dump_dir: Option<PathBuf>,
     },
 }
 
@@ -143,6 +152,7 @@ impl VerifyExecutionMode {
             txns_to_skip: Arc::new(BTreeSet::new()),
             lazy_quit: false,
             seen_error: Arc::new(AtomicBool::new(false)),
+            mismatch_dump_dir: None,
         }
     }
 
@@ -151,6 +161,7 @@ impl VerifyExecutionMode {
             txns_to_skip: Arc::new(txns_to_skip.into_iter().collect()),
             lazy_quit: false,
             seen_error: Arc::new(AtomicBool::new(false)),
+            mismatch_dump_dir: None,
         }
     }
 
@@ -171,6 +182,26 @@ impl VerifyExecutionMode {
         self
     }
 
+    pub fn set_mismatch_dump_dir(mut self, dump_dir: Option<PathBuf>) -> Self {
+        if let Self::Verify {
+            ref mut mismatch_dump_dir,
+            ..
+        } = self
+        {
+            *mismatch_dump_dir = dump_dir
+        }
+        self
+    }
+
+    pub fn mismatch_dump_dir(&self) -> Option<&Path> {
+        match self {
+            VerifyExecutionMode::NoVerify => None,
+            VerifyExecutionMode::Verify {
+                mismatch_dump_dir, ..
+            } => mismatch_dump_dir.as_deref(),
+        }
+    }
+
     pub fn is_lazy_quit(&self) -> bool {
         match self {
             VerifyExecutionMode::NoVerify => false,
@@ -26,9 +26,11 @@ use aptos_storage_interface::{
 use aptos_types::{
     block_executor::partitioner::{ExecutableBlock, ExecutableTransactions},
     ledger_info::LedgerInfoWithSignatures,
+    on_chain_config::BlockGasLimitType,
     state_store::state_value::StateValue,
+    transaction::Transaction,
 };
-use aptos_vm::AptosVM;
+use aptos_vm::{speculative_execution::SpeculativeExecutor, AptosVM};
 use fail::fail_point;
 use std::{marker::PhantomData, sync::Arc};
 
@@ -36,27 +38,70 @@ pub trait TransactionBlockExecutor: Send + Sync {
     fn execute_transaction_block(
         transactions: ExecutableTransactions,
         state_view: CachedStateView,
-        maybe_block_gas_limit: Option<u64>,
+        block_gas_limit_type: BlockGasLimitType,
     ) -> Result<ChunkOutput>;
+
+    /// Executes the block like `execute_transaction_block`, but reuses the outputs of the
+    /// transactions that were pre-executed by `speculative_executor`, if still valid.
+    fn execute_transaction_block_with_speculative_executor(
+        transactions: ExecutableTransactions,
+        state_view: CachedStateView,
+        block_gas_limit_type: BlockGasLimitType,
+        _speculative_executor: &SpeculativeExecutor,
+    ) -> Result<ChunkOutput> {
+        Self::execute_transaction_block(transactions, state_view, block_gas_limit_type)
+    }
+
+    /// Speculatively executes transactions against `state_view` with `speculative_executor`, see
+    /// `BlockExecutorTrait::pre_execute_block`.
+    fn pre_execute_transaction_block(
+        _speculative_executor: &SpeculativeExecutor,
+        _transactions: Vec<Transaction>,
+        _state_view: CachedStateView,
+    ) {
+    }
 }
 
 impl TransactionBlockExecutor for AptosVM {
     fn execute_transaction_block(
         transactions: ExecutableTransactions,
         state_view: CachedStateView,
-        maybe_block_gas_limit: Option<u64>,
+        block_gas_limit_type: BlockGasLimitType,
     ) -> Result<ChunkOutput> {
         ChunkOutput::by_transaction_execution::<AptosVM>(
             transactions,
             state_view,
-            maybe_block_gas_limit,
+            block_gas_limit_type,
+        )
+    }
+
+    fn execute_transaction_block_with_speculative_executor(
+        transactions: ExecutableTransactions,
+        state_view: CachedStateView,
+        block_gas_limit_type: BlockGasLimitType,
+        speculative_executor: &SpeculativeExecutor,
+    ) -> Result<ChunkOutput> {
+        ChunkOutput::by_transaction_execution_with_speculative_executor(
+            transactions,
+            state_view,
+            block_gas_limit_type,
+            speculative_executor,
         )
     }
+
+    fn pre_execute_transaction_block(
+        speculative_executor: &SpeculativeExecutor,
+        transactions: Vec<Transaction>,
+        state_view: CachedStateView,
+    ) {
+        speculative_executor.pre_execute_block(transactions, &state_view)
+    }
 }
 
 pub struct BlockExecutor<V> {
     pub db: DbReaderWriter,
     inner: RwLock<Option<BlockExecutorInner<V>>>,
+    speculative_executor: Option<SpeculativeExecutor>,
 }
 
 impl<V> BlockExecutor<V>
@@ -67,6 +112,7 @@ where
         Self {
             db,
             inner: RwLock::new(None),
+            speculative_executor: SpeculativeExecutor::from_vm_config(),
         }
     }
 
@@ -108,14 +154,43 @@ where
         &self,
         block: ExecutableBlock,
         parent_block_id: HashValue,
-        maybe_block_gas_limit: Option<u64>,
+        block_gas_limit_type: BlockGasLimitType,
     ) -> Result<StateComputeResult, Error> {
         self.maybe_initialize()?;
         self.inner
             .read()
             .as_ref()
             .expect("BlockExecutor is not reset")
-            .execute_block(block, parent_block_id, maybe_block_gas_limit)
+            .execute_block(
+                block,
+                parent_block_id,
+                block_gas_limit_type,
+                self.speculative_executor.as_ref(),
+            )
+    }
+
+    fn pre_execute_block(&self, transactions: Vec<Transaction>, parent_block_id: HashValue) {
+        let speculative_executor = match &self.speculative_executor {
+            Some(speculative_executor) => speculative_executor,
+            None => return,
+        };
+        if self.maybe_initialize().is_err() {
+            return;
+        }
+        // Don't hold the lock while executing.
+        let state_view = self
+            .inner
+            .read()
+            .as_ref()
+            .expect("BlockExecutor is not reset")
+            .pre_execution_state_view(parent_block_id);
+        match state_view {
+            Ok(Some(state_view)) => {
+                V::pre_execute_transaction_block(speculative_executor, transactions, state_view)
+            },
+            Ok(None) => {},
+            Err(err) => warn!("Failed to create state view for pre-execution: {:?}", err),
+        }
     }
 
     fn commit_blocks_ext(
@@ -178,7 +253,8 @@ where
         &self,
         block: ExecutableBlock,
         parent_block_id: HashValue,
-        maybe_block_gas_limit: Option<u64>,
+        block_gas_limit_type: BlockGasLimitType,
+        speculative_executor: Option<&SpeculativeExecutor>,
     ) -> Result<StateComputeResult, Error> {
         let _timer = APTOS_EXECUTOR_EXECUTE_BLOCK_SECONDS.start_timer();
         let ExecutableBlock {
@@ -233,7 +309,21 @@ where
                         "Injected error in vm_execute_block"
                     )))
                 });
-                V::execute_transaction_block(transactions, state_view, maybe_block_gas_limit)?
+                match speculative_executor {
+                    Some(speculative_executor) => {
+                        V::execute_transaction_block_with_speculative_executor(
+                            transactions,
+                            state_view,
+                            block_gas_limit_type,
+                            speculative_executor,
+                        )?
+                    },
+                    None => V::execute_transaction_block(
+                        transactions,
+                        state_view,
+                        block_gas_limit_type,
+                    )?,
+                }
             };
             chunk_output.trace_log_transaction_status();
 
@@ -241,8 +331,12 @@ where
                 .with_label_values(&["apply_to_ledger"])
                 .start_timer();
 
-            let (output, _, _) = chunk_output
-                .apply_to_ledger_for_block(parent_view, maybe_block_gas_limit.map(|_| block_id))?;
+            // A block that can be cut by the gas limit doesn't end with a state checkpoint, one is
+            // appended after the last committed transaction instead.
+            let (output, _, _) = chunk_output.apply_to_ledger_for_block(
+                parent_view,
+                block_gas_limit_type.is_limited().then_some(block_id),
+            )?;
 
             output
         };
@@ -257,6 +351,31 @@ where
         Ok(block.output.as_state_compute_result(parent_accumulator))
     }
 
+    /// The state to pre-execute a child block of `parent_block_id` on: the result of the parent
+    /// if it was executed already, otherwise the latest committed state. `None` if the block will
+    /// not be executed, because it follows a reconfiguration.
+    fn pre_execution_state_view(
+        &self,
+        parent_block_id: HashValue,
+    ) -> Result<Option<CachedStateView>> {
+        let committed_block = self.block_tree.root_block();
+        let parent_block = self
+            .block_tree
+            .get_blocks_opt(&[parent_block_id])?
+            .pop()
+            .expect("Must exist.")
+            .unwrap_or_else(|| committed_block.clone());
+        if parent_block.id != committed_block.id && parent_block.output.has_reconfiguration() {
+            return Ok(None);
+        }
+        let state_view = parent_block.output.result_view.verified_state_view(
+            StateViewId::Miscellaneous,
+            Arc::clone(&self.db.reader),
+            Arc::new(AsyncProofFetcher::new(self.db.reader.clone())),
+        )?;
+        Ok(Some(state_view))
+    }
+
     fn commit_blocks_ext(
         &self,
         block_ids: Vec<HashValue>,
@@ -9,6 +9,7 @@ use crate::{
         apply_chunk_output::{ensure_no_discard, ensure_no_retry},
         chunk_commit_queue::ChunkCommitQueue,
         chunk_output::ChunkOutput,
+        replay_mismatch::ReplayMismatch,
     },
     logging::{LogEntry, LogSchema},
     metrics::{
@@ -31,6 +32,7 @@ use aptos_storage_interface::{
 use aptos_types::{
     contract_event::ContractEvent,
     ledger_info::LedgerInfoWithSignatures,
+    on_chain_config::BlockGasLimitType,
     transaction::{
         Transaction, TransactionInfo, TransactionListWithProof, TransactionOutput,
         TransactionOutputListWithProof, TransactionStatus, Version,
@@ -204,7 +206,11 @@ impl<V: VMExecutor> ChunkExecutorInner<V> {
         let chunk_output = {
             let _timer = APTOS_EXECUTOR_VM_EXECUTE_CHUNK_SECONDS.start_timer();
             // State sync executor shouldn't have block gas limit.
-            ChunkOutput::by_transaction_execution::<V>(transactions.into(), state_view, None)?
+            ChunkOutput::by_transaction_execution::<V>(
+                transactions.into(),
+                state_view,
+                BlockGasLimitType::NoLimit,
+            )?
         };
         let executed_chunk = Self::apply_chunk_output_for_state_sync(
             verified_target_li,
@@ -531,8 +537,11 @@ impl<V: VMExecutor> ChunkExecutorInner<V> {
             .collect::<Vec<Transaction>>();
 
         // State sync executor shouldn't have block gas limit.
-        let chunk_output =
-            ChunkOutput::by_transaction_execution::<V>(txns.into(), state_view, None)?;
+        let chunk_output = ChunkOutput::by_transaction_execution::<V>(
+            txns.into(),
+            state_view,
+            BlockGasLimitType::NoLimit,
+        )?;
         // not `zip_eq`, deliberately
         for (version, txn_out, txn_info, write_set, events) in multizip((
             begin_version..end_version,
@@ -547,6 +556,11 @@ impl<V: VMExecutor> ChunkExecutorInner<V> {
                 Some(write_set),
                 Some(events),
             ) {
+                self.report_replay_mismatch(
+                    latest_view,
+                    ReplayMismatch::new(version, txn_out.clone(), txn_info, write_set, events),
+                    verify_execution_mode,
+                );
                 if verify_execution_mode.is_lazy_quit() {
                     error!("(Not quitting right away.) {}", err);
                     verify_execution_mode.mark_seen_error();
@@ -559,6 +573,36 @@ impl<V: VMExecutor> ChunkExecutorInner<V> {
         Ok(end_version)
     }
 
+    /// Logs what differs in the output of a transaction that failed verification, and dumps both
+    /// outputs if asked to.
+    fn report_replay_mismatch(
+        &self,
+        latest_view: &ExecutedTrees,
+        mismatch: ReplayMismatch,
+        verify_execution_mode: &VerifyExecutionMode,
+    ) {
+        // The transactions of the batch before the mismatch aren't applied, so resources of
+        // modules published by them can't be decoded.
+        let description = match self.state_view(latest_view) {
+            Ok(state_view) => mismatch.describe(&state_view),
+            Err(err) => format!("Failed to create state view to describe mismatch: {}", err),
+        };
+        error!("{}", description);
+        if let Some(dump_dir) = verify_execution_mode.mismatch_dump_dir() {
+            match mismatch.dump(dump_dir, &description) {
+                Ok(()) => info!(
+                    "Dumped outputs of version {} to {}",
+                    mismatch.version,
+                    dump_dir.display()
+                ),
+                Err(err) => error!(
+                    "Failed to dump outputs of version {}: {}",
+                    mismatch.version, err
+                ),
+            }
+        }
+    }
+
     /// Consume `end_version - begin_version` txns from the mutable input arguments
     /// It's guaranteed that there's no known broken versions or epoch endings in the range.
     fn remove_and_apply(
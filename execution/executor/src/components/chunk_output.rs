@@ -6,10 +6,13 @@
 
 use crate::{components::apply_chunk_output::ApplyChunkOutput, metrics};
 use anyhow::Result;
+use aptos_block_partitioner::sharded_block_partitioner::ShardExecutionStats;
 use aptos_crypto::HashValue;
+use aptos_executor_service::remote_executor_client::{self, RemoteExecutorClient};
 use aptos_executor_types::{ExecutedBlock, ExecutedChunk};
 use aptos_infallible::Mutex;
-use aptos_logger::{sample, sample::SampleRate, trace, warn};
+use aptos_logger::{info, sample, sample::SampleRate, trace, warn};
+use aptos_secure_net::network_controller::NetworkController;
 use aptos_storage_interface::{
     cached_state_view::{CachedStateView, StateCache},
     ExecutedTrees,
@@ -17,6 +20,7 @@ use aptos_storage_interface::{
 use aptos_types::{
     account_config::CORE_CODE_ADDRESS,
     block_executor::partitioner::{ExecutableTransactions, SubBlocksForShard},
+    on_chain_config::BlockGasLimitType,
     transaction::{
         analyzed_transaction::AnalyzedTransaction, ExecutionStatus, Transaction, TransactionOutput,
         TransactionStatus,
@@ -27,6 +31,7 @@ use aptos_vm::{
         local_executor_shard::{LocalExecutorClient, LocalExecutorService},
         ShardedBlockExecutor,
     },
+    speculative_execution::SpeculativeExecutor,
     AptosVM, VMExecutor,
 };
 use fail::fail_point;
@@ -41,6 +46,39 @@ pub static SHARDED_BLOCK_EXECUTOR: Lazy<
     Arc::new(Mutex::new(ShardedBlockExecutor::new(client)))
 });
 
+/// Executes the shards on remote executor services instead, if they are configured (see
+/// `remote_executor_client::set_remote_execution_config_once`).
+pub static REMOTE_SHARDED_BLOCK_EXECUTOR: Lazy<
+    Option<
+        Arc<Mutex<ShardedBlockExecutor<CachedStateView, RemoteExecutorClient<CachedStateView>>>>,
+    >,
+> = Lazy::new(|| {
+    remote_executor_client::get_remote_execution_config().map(|config| {
+        info!(
+            "Using remote executor shards {:?}, coordinator listening on {}",
+            config.remote_executor_addresses, config.coordinator_address
+        );
+        let mut controller = NetworkController::new(
+            "remote-executor-coordinator".to_string(),
+            config.coordinator_address,
+            5000,
+        );
+        let client = RemoteExecutorClient::from_config(&config, &mut controller, None);
+        // The controller's threads keep serving the channels after it is dropped.
+        controller.start();
+        Arc::new(Mutex::new(ShardedBlockExecutor::new(client)))
+    })
+});
+
+/// The cumulative execution statistics of the sharded block executor in use, to be fed back into
+/// an adaptive block partitioner.
+pub fn sharded_execution_stats() -> Arc<Mutex<ShardExecutionStats>> {
+    match REMOTE_SHARDED_BLOCK_EXECUTOR.as_ref() {
+        Some(remote_executor) => remote_executor.lock().execution_stats(),
+        None => SHARDED_BLOCK_EXECUTOR.lock().execution_stats(),
+    }
+}
+
 pub struct ChunkOutput {
     /// Input transactions.
     pub transactions: Vec<Transaction>,
@@ -56,31 +94,29 @@ impl ChunkOutput {
     pub fn by_transaction_execution<V: VMExecutor>(
         transactions: ExecutableTransactions,
         state_view: CachedStateView,
-        maybe_block_gas_limit: Option<u64>,
+        block_gas_limit_type: BlockGasLimitType,
     ) -> Result<Self> {
         match transactions {
             ExecutableTransactions::Unsharded(txns) => {
                 Self::by_transaction_execution_unsharded::<V>(
                     txns,
                     state_view,
-                    maybe_block_gas_limit,
+                    block_gas_limit_type,
                 )
             },
-            ExecutableTransactions::Sharded(block) => Self::by_transaction_execution_sharded::<V>(
-                block,
-                state_view,
-                maybe_block_gas_limit,
-            ),
+            ExecutableTransactions::Sharded(block) => {
+                Self::by_transaction_execution_sharded::<V>(block, state_view, block_gas_limit_type)
+            },
         }
     }
 
     fn by_transaction_execution_unsharded<V: VMExecutor>(
         transactions: Vec<Transaction>,
         state_view: CachedStateView,
-        maybe_block_gas_limit: Option<u64>,
+        block_gas_limit_type: BlockGasLimitType,
     ) -> Result<Self> {
         let transaction_outputs =
-            Self::execute_block::<V>(transactions.clone(), &state_view, maybe_block_gas_limit)?;
+            Self::execute_block::<V>(transactions.clone(), &state_view, block_gas_limit_type)?;
 
         // to print txn output for debugging, uncomment:
         // println!("{:?}", transaction_outputs.iter().map(|t| t.status() ).collect::<Vec<_>>());
@@ -94,16 +130,51 @@ impl ChunkOutput {
         })
     }
 
+    /// Executes the transactions like `by_transaction_execution`, but reuses the outputs of the
+    /// transactions that were pre-executed by the speculative executor, if still valid. Sharded
+    /// blocks are executed as usual.
+    pub fn by_transaction_execution_with_speculative_executor(
+        transactions: ExecutableTransactions,
+        state_view: CachedStateView,
+        block_gas_limit_type: BlockGasLimitType,
+        speculative_executor: &SpeculativeExecutor,
+    ) -> Result<Self> {
+        let transactions = match transactions {
+            ExecutableTransactions::Unsharded(txns) => txns,
+            transactions => {
+                return Self::by_transaction_execution::<AptosVM>(
+                    transactions,
+                    state_view,
+                    block_gas_limit_type,
+                )
+            },
+        };
+        let transaction_outputs = AptosVM::execute_block_with_speculative_executor(
+            transactions.clone(),
+            &state_view,
+            block_gas_limit_type,
+            speculative_executor,
+        )?;
+
+        update_counters_for_processed_chunk(&transactions, &transaction_outputs, "executed");
+
+        Ok(Self {
+            transactions,
+            transaction_outputs,
+            state_cache: state_view.into_state_cache(),
+        })
+    }
+
     pub fn by_transaction_execution_sharded<V: VMExecutor>(
         block: Vec<SubBlocksForShard<AnalyzedTransaction>>,
         state_view: CachedStateView,
-        maybe_block_gas_limit: Option<u64>,
+        block_gas_limit_type: BlockGasLimitType,
     ) -> Result<Self> {
         let state_view_arc = Arc::new(state_view);
         let transaction_outputs = Self::execute_block_sharded::<V>(
             block.clone(),
             state_view_arc.clone(),
-            maybe_block_gas_limit,
+            block_gas_limit_type,
         )?;
 
         // TODO(skedia) add logic to emit counters per shard instead of doing it globally.
@@ -187,13 +258,21 @@ impl ChunkOutput {
     fn execute_block_sharded<V: VMExecutor>(
         block: Vec<SubBlocksForShard<AnalyzedTransaction>>,
         state_view: Arc<CachedStateView>,
-        maybe_block_gas_limit: Option<u64>,
+        block_gas_limit_type: BlockGasLimitType,
     ) -> Result<Vec<TransactionOutput>> {
+        if let Some(remote_executor) = REMOTE_SHARDED_BLOCK_EXECUTOR.as_ref() {
+            return Ok(V::execute_block_sharded(
+                remote_executor.lock().deref(),
+                block,
+                state_view,
+                block_gas_limit_type,
+            )?);
+        }
         Ok(V::execute_block_sharded(
             SHARDED_BLOCK_EXECUTOR.lock().deref(),
             block,
             state_view,
-            maybe_block_gas_limit,
+            block_gas_limit_type,
         )?)
     }
 
@@ -203,12 +282,12 @@ impl ChunkOutput {
     fn execute_block<V: VMExecutor>(
         transactions: Vec<Transaction>,
         state_view: &CachedStateView,
-        maybe_block_gas_limit: Option<u64>,
+        block_gas_limit_type: BlockGasLimitType,
     ) -> Result<Vec<TransactionOutput>> {
         Ok(V::execute_block(
             transactions,
             &state_view,
-            maybe_block_gas_limit,
+            block_gas_limit_type,
         )?)
     }
 
@@ -220,7 +299,7 @@ impl ChunkOutput {
     fn execute_block<V: VMExecutor>(
         transactions: Vec<Transaction>,
         state_view: &CachedStateView,
-        maybe_block_gas_limit: Option<u64>,
+        block_gas_limit_type: BlockGasLimitType,
     ) -> Result<Vec<TransactionOutput>> {
         use aptos_state_view::{StateViewId, TStateView};
         use aptos_types::write_set::WriteSet;
@@ -228,7 +307,7 @@ impl ChunkOutput {
         let transaction_outputs = match state_view.id() {
             // this state view ID implies a genesis block in non-test cases.
             StateViewId::Miscellaneous => {
-                V::execute_block(transactions, &state_view, maybe_block_gas_limit)?
+                V::execute_block(transactions, &state_view, block_gas_limit_type)?
             },
             _ => transactions
                 .iter()
@@ -0,0 +1,160 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+#![forbid(unsafe_code)]
+
+use anyhow::Result;
+use aptos_resource_viewer::AptosValueAnnotator;
+use aptos_state_view::StateView;
+use aptos_types::{
+    contract_event::ContractEvent,
+    state_store::state_key::{StateKey, StateKeyInner},
+    transaction::{TransactionInfo, TransactionOutput, TransactionStatus, Version},
+    write_set::{WriteOp, WriteSet},
+};
+use aptos_vm::{data_cache::StorageAdapter, move_vm_ext::MoveResolverExt};
+use std::{collections::BTreeSet, fmt::Write, path::Path};
+
+/// A replayed transaction whose output doesn't match the one committed on chain.
+pub struct ReplayMismatch {
+    pub version: Version,
+    pub expected: TransactionOutput,
+    pub actual: TransactionOutput,
+}
+
+impl ReplayMismatch {
+    pub fn new(
+        version: Version,
+        actual: TransactionOutput,
+        txn_info: &TransactionInfo,
+        expected_write_set: &WriteSet,
+        expected_events: &[ContractEvent],
+    ) -> Self {
+        Self {
+            version,
+            expected: TransactionOutput::new(
+                expected_write_set.clone(),
+                expected_events.to_vec(),
+                txn_info.gas_used(),
+                TransactionStatus::Keep(txn_info.status().clone()),
+            ),
+            actual,
+        }
+    }
+
+    /// Describes the differences in status, gas, write set and events. Resources and events are
+    /// decoded with the modules in `state_view` when possible.
+    pub fn describe(&self, state_view: &impl StateView) -> String {
+        let storage = StorageAdapter::new(state_view);
+        let annotator = AptosValueAnnotator::new(&storage);
+        let mut description = format!("Replay mismatch at version {}:\n", self.version);
+
+        if self.expected.status() != self.actual.status() {
+            writeln!(
+                description,
+                "status: expected {:?}, actual {:?}",
+                self.expected.status(),
+                self.actual.status()
+            )
+            .unwrap();
+        }
+        if self.expected.gas_used() != self.actual.gas_used() {
+            writeln!(
+                description,
+                "gas used: expected {}, actual {}",
+                self.expected.gas_used(),
+                self.actual.gas_used()
+            )
+            .unwrap();
+        }
+
+        let keys: BTreeSet<_> = self
+            .expected
+            .write_set()
+            .iter()
+            .chain(self.actual.write_set().iter())
+            .map(|(key, _)| key)
+            .collect();
+        for key in keys {
+            let expected_op = self.expected.write_set().get(key);
+            let actual_op = self.actual.write_set().get(key);
+            if expected_op != actual_op {
+                writeln!(
+                    description,
+                    "write set {:?}:\n  expected: {}\n  actual: {}",
+                    key,
+                    describe_write_op(&annotator, key, expected_op),
+                    describe_write_op(&annotator, key, actual_op)
+                )
+                .unwrap();
+            }
+        }
+
+        let num_events = self.expected.events().len().max(self.actual.events().len());
+        for idx in 0..num_events {
+            let expected_event = self.expected.events().get(idx);
+            let actual_event = self.actual.events().get(idx);
+            if expected_event != actual_event {
+                writeln!(
+                    description,
+                    "event {}:\n  expected: {}\n  actual: {}",
+                    idx,
+                    describe_event(&annotator, expected_event),
+                    describe_event(&annotator, actual_event)
+                )
+                .unwrap();
+            }
+        }
+        description
+    }
+
+    /// Writes the description and both outputs (as BCS) to `dir`, for triage.
+    pub fn dump(&self, dir: &Path, description: &str) -> Result<()> {
+        std::fs::create_dir_all(dir)?;
+        std::fs::write(dir.join(format!("{}.diff.txt", self.version)), description)?;
+        std::fs::write(
+            dir.join(format!("{}.expected.bcs", self.version)),
+            bcs::to_bytes(&self.expected)?,
+        )?;
+        std::fs::write(
+            dir.join(format!("{}.actual.bcs", self.version)),
+            bcs::to_bytes(&self.actual)?,
+        )?;
+        Ok(())
+    }
+}
+
+fn describe_write_op<R: MoveResolverExt>(
+    annotator: &AptosValueAnnotator<R>,
+    key: &StateKey,
+    op: Option<&WriteOp>,
+) -> String {
+    let op = match op {
+        None => return "not written".to_string(),
+        Some(op) => op,
+    };
+    let bytes = match op.bytes() {
+        None => return "deleted".to_string(),
+        Some(bytes) => bytes,
+    };
+    if let StateKeyInner::AccessPath(access_path) = key.inner() {
+        if let Ok(resource) = annotator.view_access_path(access_path.clone(), bytes) {
+            return resource.to_string();
+        }
+    }
+    // Modules, table items and resources that can't be decoded.
+    format!("0x{}", hex::encode(bytes))
+}
+
+fn describe_event<R: MoveResolverExt>(
+    annotator: &AptosValueAnnotator<R>,
+    event: Option<&ContractEvent>,
+) -> String {
+    match event {
+        None => "none".to_string(),
+        Some(event) => match annotator.view_contract_event(event) {
+            Ok(value) => format!("{} {}", event.type_tag(), value),
+            Err(_) => format!("{:?}", event),
+        },
+    }
+}
@@ -16,6 +16,7 @@ use aptos_storage_interface::{
 use aptos_types::{
     block_executor::partitioner::{ExecutableTransactions, SubBlocksForShard},
     ledger_info::LedgerInfoWithSignatures,
+    on_chain_config::BlockGasLimitType,
     test_helpers::transaction_test_helpers::BLOCK_GAS_LIMIT,
     transaction::{
         analyzed_transaction::AnalyzedTransaction, Transaction, TransactionOutput,
@@ -61,12 +62,12 @@ impl TransactionBlockExecutor for FakeVM {
     fn execute_transaction_block(
         transactions: ExecutableTransactions,
         state_view: CachedStateView,
-        maybe_block_gas_limit: Option<u64>,
+        block_gas_limit_type: BlockGasLimitType,
     ) -> Result<ChunkOutput> {
         ChunkOutput::by_transaction_execution::<FakeVM>(
             transactions,
             state_view,
-            maybe_block_gas_limit,
+            block_gas_limit_type,
         )
     }
 }
@@ -76,7 +77,7 @@ impl VMExecutor for FakeVM {
         _sharded_block_executor: &ShardedBlockExecutor<S, E>,
         _block: Vec<SubBlocksForShard<AnalyzedTransaction>>,
         _state_view: Arc<S>,
-        _maybe_block_gas_limit: Option<u64>,
+        _block_gas_limit_type: BlockGasLimitType,
     ) -> Result<Vec<TransactionOutput>, VMStatus> {
         Ok(Vec::new())
     }
@@ -84,7 +85,7 @@ impl VMExecutor for FakeVM {
     fn execute_block(
         _transactions: Vec<Transaction>,
         _state_view: &impl StateView,
-        _maybe_block_gas_limit: Option<u64>,
+        _block_gas_limit_type: BlockGasLimitType,
     ) -> Result<Vec<TransactionOutput>, VMStatus> {
         Ok(Vec::new())
     }
@@ -19,8 +19,8 @@ use aptos_types::{
     contract_event::ContractEvent,
     event::EventKey,
     on_chain_config::{
-        access_path_for_config, new_epoch_event_key, ConfigurationResource, OnChainConfig,
-        ValidatorSet,
+        access_path_for_config, new_epoch_event_key, BlockGasLimitType, ConfigurationResource,
+        OnChainConfig, ValidatorSet,
     },
     state_store::state_key::StateKey,
     transaction::{
@@ -65,12 +65,12 @@ impl TransactionBlockExecutor for MockVM {
     fn execute_transaction_block(
         transactions: ExecutableTransactions,
         state_view: CachedStateView,
-        maybe_block_gas_limit: Option<u64>,
+        block_gas_limit_type: BlockGasLimitType,
     ) -> Result<ChunkOutput> {
         ChunkOutput::by_transaction_execution::<MockVM>(
             transactions,
             state_view,
-            maybe_block_gas_limit,
+            block_gas_limit_type,
         )
     }
 }
@@ -79,7 +79,7 @@ impl VMExecutor for MockVM {
     fn execute_block(
         transactions: Vec<Transaction>,
         state_view: &impl StateView,
-        _maybe_block_gas_limit: Option<u64>,
+        block_gas_limit_type: BlockGasLimitType,
     ) -> Result<Vec<TransactionOutput>, VMStatus> {
         if state_view.is_genesis() {
             assert_eq!(
@@ -207,6 +207,23 @@ impl VMExecutor for MockVM {
             }
         }
 
+        // The conflict-aware limit is mocked with a unit effective cost per transaction, i.e. the
+        // block is cut once `effective_block_gas_limit` transactions have been executed.
+        if let BlockGasLimitType::ConflictAwareV1 {
+            effective_block_gas_limit,
+            ..
+        } = block_gas_limit_type
+        {
+            for output in outputs.iter_mut().skip(effective_block_gas_limit as usize) {
+                *output = TransactionOutput::new(
+                    WriteSet::default(),
+                    vec![],
+                    0,
+                    TransactionStatus::Retry,
+                );
+            }
+        }
+
         Ok(outputs)
     }
 
@@ -214,7 +231,7 @@ impl VMExecutor for MockVM {
         _sharded_block_executor: &ShardedBlockExecutor<S, E>,
         _block: Vec<SubBlocksForShard<AnalyzedTransaction>>,
         _state_view: Arc<S>,
-        _maybe_block_gas_limit: Option<u64>,
+        _block_gas_limit_type: BlockGasLimitType,
     ) -> std::result::Result<Vec<TransactionOutput>, VMStatus> {
         todo!()
     }
@@ -28,6 +28,7 @@ use aptos_types::{
     block_info::BlockInfo,
     chain_id::ChainId,
     ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
+    on_chain_config::BlockGasLimitType,
     proof::definition::LeafCount,
     state_store::{state_key::StateKey, state_value::StateValue},
     test_helpers::transaction_test_helpers::{block, BLOCK_GAS_LIMIT},
@@ -42,6 +43,7 @@ use proptest::prelude::*;
 use std::{iter::once, sync::Arc};
 
 mod chunk_executor_tests;
+mod replay_mismatch_tests;
 
 fn execute_and_commit_block(
     executor: &TestExecutor,
@@ -224,6 +226,43 @@ fn test_executor_one_block() {
     executor.commit_blocks(vec![block_id], ledger_info).unwrap();
 }
 
+#[test]
+fn test_executor_conflict_aware_block_gas_limit_cut() {
+    let executor = TestExecutor::new();
+    let parent_block_id = executor.committed_block_id();
+    let block_id = gen_block_id(1);
+    let block_gas_limit_type = BlockGasLimitType::ConflictAwareV1 {
+        effective_block_gas_limit: 5,
+        parallelism: 4,
+    };
+
+    // The block doesn't end with a state checkpoint, as it can be cut.
+    let num_user_txns = 10;
+    let txns = (0..num_user_txns)
+        .map(|i| encode_mint_transaction(gen_address(i), 100))
+        .collect::<Vec<_>>();
+    let output = executor
+        .execute_block(
+            (block_id, block(txns, block_gas_limit_type)).into(),
+            parent_block_id,
+            block_gas_limit_type,
+        )
+        .unwrap();
+
+    // The block is cut after 5 transactions, and the state checkpoint is appended after them
+    let mut expected_status = vec![KEEP_STATUS.clone(); 6];
+    expected_status.extend(vec![TransactionStatus::Retry; 5]);
+    assert_eq!(&expected_status, output.compute_status());
+    let version = 6;
+    assert_eq!(output.version(), version);
+
+    // The cut block still commits
+    let ledger_info = gen_ledger_info(version, output.root_hash(), block_id, 1);
+    executor.commit_blocks(vec![block_id], ledger_info).unwrap();
+    assert_eq!(executor.committed_block_id(), block_id);
+    assert_eq!(executor.db.reader.get_latest_version().unwrap(), version);
+}
+
 #[test]
 fn test_executor_multiple_blocks() {
     let executor = TestExecutor::new();
@@ -327,10 +366,13 @@ fn test_executor_execute_same_block_multiple_times() {
     assert_eq!(responses.len(), 1);
 }
 
-fn ledger_version_from_block_size(block_size: usize, maybe_block_gas_limit: Option<u64>) -> usize {
+fn ledger_version_from_block_size(
+    block_size: usize,
+    block_gas_limit_type: BlockGasLimitType,
+) -> usize {
     // With block gas limit, StateCheckpoint txn is inserted to block after execution.
     // So the ledger_info version needs to block_size + 1 with block gas limit.
-    block_size + maybe_block_gas_limit.map(|_| 1).unwrap_or(0)
+    block_size + usize::from(block_gas_limit_type.is_limited())
 }
 
 /// Generates a list of `TransactionListWithProof`s according to the given ranges.
@@ -356,7 +398,7 @@ fn create_transaction_chunks(
         let txn = encode_mint_transaction(gen_address(i), 100);
         txns.push(txn);
     }
-    if BLOCK_GAS_LIMIT.is_none() {
+    if !BLOCK_GAS_LIMIT.is_limited() {
         txns.push(Transaction::StateCheckpoint(HashValue::random()));
     }
     let id = gen_block_id(1);
@@ -643,7 +685,7 @@ impl TestBlock {
         num_user_txns: u64,
         amount: u32,
         id: HashValue,
-        maybe_block_gas_limit: Option<u64>,
+        block_gas_limit_type: BlockGasLimitType,
     ) -> Self {
         let txns = if num_user_txns == 0 {
             Vec::new()
@@ -652,7 +694,7 @@ impl TestBlock {
                 (0..num_user_txns)
                     .map(|index| encode_mint_transaction(gen_address(index), u64::from(amount)))
                     .collect(),
-                maybe_block_gas_limit,
+                block_gas_limit_type,
             )
         };
         TestBlock { txns, id }
@@ -663,7 +705,7 @@ impl TestBlock {
 // the root hash after all transactions are committed.
 fn run_transactions_naive(
     transactions: Vec<Transaction>,
-    maybe_block_gas_limit: Option<u64>,
+    block_gas_limit_type: BlockGasLimitType,
 ) -> HashValue {
     let executor = TestExecutor::new();
     let db = &executor.db;
@@ -679,7 +721,7 @@ fn run_transactions_naive(
                     Arc::new(AsyncProofFetcher::new(db.reader.clone())),
                 )
                 .unwrap(),
-            maybe_block_gas_limit,
+            block_gas_limit_type,
         )
         .unwrap();
         let (executed, _, _) = out.apply_to_ledger(&ledger_view, None).unwrap();
@@ -809,11 +851,11 @@ proptest! {
         let expected_root_hash = run_transactions_naive({
             let mut txns = vec![];
             txns.extend(block_a.txns.iter().cloned());
-            if BLOCK_GAS_LIMIT.is_some() {
+            if BLOCK_GAS_LIMIT.is_limited() {
                 txns.push(Transaction::StateCheckpoint(block_a.id));
             }
             txns.extend(block_b.txns.iter().cloned());
-            if BLOCK_GAS_LIMIT.is_some() {
+            if BLOCK_GAS_LIMIT.is_limited() {
                 txns.push(Transaction::StateCheckpoint(block_b.id));
             }
             txns
@@ -0,0 +1,80 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::components::replay_mismatch::ReplayMismatch;
+use aptos_state_view::in_memory_state_view::InMemoryStateView;
+use aptos_types::{
+    contract_event::ContractEvent,
+    event::EventKey,
+    state_store::state_key::StateKey,
+    transaction::{ExecutionStatus, TransactionOutput, TransactionStatus},
+    write_set::{WriteOp, WriteSetMut},
+};
+use move_core_types::language_storage::TypeTag;
+use std::collections::HashMap;
+
+fn output(
+    write_ops: Vec<(StateKey, WriteOp)>,
+    events: Vec<ContractEvent>,
+    gas_used: u64,
+    status: ExecutionStatus,
+) -> TransactionOutput {
+    TransactionOutput::new(
+        WriteSetMut::new(write_ops).freeze().unwrap(),
+        events,
+        gas_used,
+        TransactionStatus::Keep(status),
+    )
+}
+
+#[test]
+fn test_describe_and_dump() {
+    let unchanged_key = StateKey::raw(b"unchanged".to_vec());
+    let changed_key = StateKey::raw(b"changed".to_vec());
+    let event = ContractEvent::new(EventKey::random(), 0, TypeTag::U64, vec![1]);
+    let mismatch = ReplayMismatch {
+        version: 42,
+        expected: output(
+            vec![
+                (unchanged_key.clone(), WriteOp::Modification(vec![0])),
+                (changed_key.clone(), WriteOp::Modification(vec![0xab, 0xcd])),
+            ],
+            vec![event],
+            100,
+            ExecutionStatus::Success,
+        ),
+        actual: output(
+            vec![
+                (unchanged_key, WriteOp::Modification(vec![0])),
+                (changed_key, WriteOp::Deletion),
+            ],
+            vec![],
+            200,
+            ExecutionStatus::OutOfGas,
+        ),
+    };
+
+    // Nothing in the state view, so values are shown undecoded.
+    let description = mismatch.describe(&InMemoryStateView::new(HashMap::new()));
+    assert!(description.starts_with("Replay mismatch at version 42:\n"));
+    assert!(description.contains("status: expected Keep(Success), actual Keep(OutOfGas)\n"));
+    assert!(description.contains("gas used: expected 100, actual 200\n"));
+    assert!(description.contains("  expected: 0xabcd\n  actual: deleted\n"));
+    assert!(description.contains("event 0:\n"));
+    assert!(description.contains("  actual: none\n"));
+    // Only the entries that differ are described.
+    assert_eq!(description.matches("write set").count(), 1);
+
+    let dir = aptos_temppath::TempPath::new();
+    mismatch.dump(dir.path(), &description).unwrap();
+    assert_eq!(
+        std::fs::read_to_string(dir.path().join("42.diff.txt")).unwrap(),
+        description
+    );
+    let expected: TransactionOutput =
+        bcs::from_bytes(&std::fs::read(dir.path().join("42.expected.bcs")).unwrap()).unwrap();
+    assert_eq!(expected, mismatch.expected);
+    let actual: TransactionOutput =
+        bcs::from_bytes(&std::fs::read(dir.path().join("42.actual.bcs")).unwrap()).unwrap();
+    assert_eq!(actual, mismatch.actual);
+}
@@ -18,6 +18,7 @@ use aptos_config::config::NodeConfig;
 use aptos_consensus_types::common::TransactionInProgress;
 use aptos_crypto::HashValue;
 use aptos_logger::prelude::*;
+use aptos_peer_monitoring_service_types::response::MempoolHealthSummary;
 use aptos_types::{
     account_address::AccountAddress,
     mempool_status::{MempoolStatus, MempoolStatusCode},
@@ -401,6 +402,22 @@ impl Mempool {
         self.transactions.timeline_range(start_end_pairs)
     }
 
+    /// Returns a summary of the mempool health (i.e., the number of
+    /// transactions and the age of the oldest transaction).
+    pub(crate) fn get_health_summary(&self) -> MempoolHealthSummary {
+        let oldest_transaction_age_ms = self
+            .transactions
+            .get_oldest_insertion_time()
+            .and_then(|insertion_time| SystemTime::now().duration_since(insertion_time).ok())
+            .map(|age| age.as_millis() as u64)
+            .unwrap_or(0);
+
+        MempoolHealthSummary {
+            num_transactions: self.transactions.num_transactions() as u64,
+            oldest_transaction_age_ms,
+        }
+    }
+
     pub fn gen_snapshot(&self) -> TxnsLog {
         self.transactions.gen_snapshot()
     }
@@ -667,6 +667,22 @@ impl TransactionStore {
         gc_time
     }
 
+    /// Returns the number of transactions in the store
+    pub(crate) fn num_transactions(&self) -> usize {
+        self.system_ttl_index.size()
+    }
+
+    /// Returns the insertion time of the oldest transaction in the store (if any).
+    /// All transactions share the same system TTL, so the transaction with the
+    /// earliest system expiration time is also the oldest.
+    pub(crate) fn get_oldest_insertion_time(&self) -> Option<SystemTime> {
+        self.system_ttl_index
+            .iter()
+            .next()
+            .and_then(|key| self.get_mempool_txn(&key.address, key.sequence_number))
+            .map(|txn| txn.insertion_info.insertion_time)
+    }
+
     /// Garbage collect old transactions.
     pub(crate) fn gc_by_system_ttl(&mut self, gc_time: Duration) {
         self.gc(gc_time, true);
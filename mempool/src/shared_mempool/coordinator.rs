@@ -20,7 +20,7 @@ use aptos_bounded_executor::BoundedExecutor;
 use aptos_config::network_id::{NetworkId, PeerNetworkId};
 use aptos_consensus_types::common::TransactionSummary;
 use aptos_event_notifications::ReconfigNotificationListener;
-use aptos_infallible::Mutex;
+use aptos_infallible::{Mutex, RwLock};
 use aptos_logger::prelude::*;
 use aptos_mempool_notifications::{MempoolCommitNotification, MempoolNotificationListener};
 use aptos_network::{
@@ -30,6 +30,7 @@ use aptos_network::{
     },
     protocols::network::Event,
 };
+use aptos_peer_monitoring_service_types::response::MempoolHealthSummary;
 use aptos_types::on_chain_config::OnChainConfigPayload;
 use aptos_vm_validator::vm_validator::TransactionValidation;
 use futures::{
@@ -383,6 +384,20 @@ pub(crate) async fn gc_coordinator(mempool: Arc<Mutex<CoreMempool>>, gc_interval
     ));
 }
 
+/// Periodically refreshes the mempool health summary (which is served
+/// to peers by the peer monitoring service).
+pub(crate) async fn health_summary_job(
+    mempool: Arc<Mutex<CoreMempool>>,
+    mempool_health_summary: Arc<RwLock<MempoolHealthSummary>>,
+    update_interval_ms: u64,
+) {
+    let mut interval = IntervalStream::new(interval(Duration::from_millis(update_interval_ms)));
+    while let Some(_interval) = interval.next().await {
+        let health_summary = mempool.lock().get_health_summary();
+        *mempool_health_summary.write() = health_summary;
+    }
+}
+
 /// Periodically logs a snapshot of transactions in core mempool.
 /// In the future we may want an interactive way to directly query mempool's internal state.
 /// For now, we will rely on this periodic snapshot to observe the internal state.
@@ -23,7 +23,10 @@ use aptos_infallible::{Mutex, RwLock};
 use aptos_logger::prelude::*;
 use aptos_netcore::transport::ConnectionOrigin;
 use aptos_network::{
-    application::{error::Error, interface::NetworkClientInterface, metadata::PeerMetadata},
+    application::{
+        error::Error, interface::NetworkClientInterface, metadata::PeerMetadata,
+        reputation::PeerEvent,
+    },
     transport::ConnectionMetadata,
 };
 use aptos_types::{transaction::SignedTransaction, PeerId};
@@ -103,6 +106,13 @@ impl<NetworkClient: NetworkClientInterface<MempoolSyncMsg>> MempoolNetworkInterf
         }
     }
 
+    /// Reports the behaviour of the peer to the shared peer reputations
+    pub fn report_peer_event(&self, peer: PeerNetworkId, event: PeerEvent) {
+        self.network_client
+            .get_peers_and_metadata()
+            .report_peer_event(peer, event);
+    }
+
     /// Returns peers to add (with metadata) and peers to disable
     fn get_upstream_peers_to_add_and_disable(
         &self,
@@ -153,11 +163,12 @@ impl<NetworkClient: NetworkClientInterface<MempoolSyncMsg>> MempoolNetworkInterf
     ) -> (Vec<PeerNetworkId>, Vec<PeerNetworkId>) {
         // Get the upstream peers to add or disable, using a read lock
         let (to_add, to_disable) = self.get_upstream_peers_to_add_and_disable(all_connected_peers);
-        if to_add.is_empty() && to_disable.is_empty() {
-            return (vec![], vec![]);
-        }
         // If there are updates, apply using a write lock
-        self.add_and_disable_upstream_peers(&to_add, &to_disable);
+        if !to_add.is_empty() || !to_disable.is_empty() {
+            self.add_and_disable_upstream_peers(&to_add, &to_disable);
+        }
+
+        // Always update the prioritized peers, as the health of peers may have changed
         self.update_prioritized_peers();
 
         (to_add.iter().map(|(peer, _)| *peer).collect(), to_disable)
@@ -181,15 +192,44 @@ impl<NetworkClient: NetworkClientInterface<MempoolSyncMsg>> MempoolNetworkInterf
         // Order peers by network and by type
         // Origin doesn't matter at this point, only inserted ones into peer_states are upstream
         // Validators will always have the full set
-        let mut prioritized_peers = self.prioritized_peers.lock();
-        let peers: Vec<_> = peers
+        let mut peers: Vec<_> = peers
             .iter()
             .sorted_by(|peer_a, peer_b| self.prioritized_peers_comparator.compare(peer_a, peer_b))
             .map(|(peer, _)| *peer)
             .collect();
+
+        // Move unhealthy peers to the back of the list (the sort is stable, so
+        // the relative ordering amongst healthy and unhealthy peers is preserved).
+        peers.sort_by_cached_key(|peer| !self.is_healthy_upstream_peer(peer));
+
+        let mut prioritized_peers = self.prioritized_peers.lock();
         let _ = std::mem::replace(&mut *prioritized_peers, peers);
     }
 
+    /// Returns true iff the peer is not known to be lagging or overloaded,
+    /// according to the latest node health reported by the peer monitoring service.
+    fn is_healthy_upstream_peer(&self, peer: &PeerNetworkId) -> bool {
+        let node_health_response = self
+            .network_client
+            .get_peers_and_metadata()
+            .get_metadata_for_peer(*peer)
+            .ok()
+            .and_then(|peer_metadata| {
+                peer_metadata
+                    .get_peer_monitoring_metadata()
+                    .latest_node_health_response
+            });
+
+        match node_health_response {
+            Some(node_health_response) => {
+                !node_health_response.is_lagging(self.mempool_config.max_upstream_sync_lag_secs)
+                    && !node_health_response
+                        .is_overloaded(self.mempool_config.max_upstream_mempool_transactions)
+            },
+            None => true, // We have no health information for the peer
+        }
+    }
+
     pub fn is_validator(&self) -> bool {
         self.role.is_validator()
     }
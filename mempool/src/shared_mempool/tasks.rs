@@ -22,7 +22,7 @@ use aptos_crypto::HashValue;
 use aptos_infallible::{Mutex, RwLock};
 use aptos_logger::prelude::*;
 use aptos_metrics_core::HistogramTimer;
-use aptos_network::application::interface::NetworkClientInterface;
+use aptos_network::application::{interface::NetworkClientInterface, reputation::PeerEvent};
 use aptos_storage_interface::state_view::LatestDbStateCheckpointView;
 use aptos_types::{
     mempool_status::{MempoolStatus, MempoolStatusCode},
@@ -170,6 +170,7 @@ pub(crate) async fn process_transaction_broadcast<NetworkClient, TransactionVali
     let _timer = counters::process_txn_submit_latency_timer(peer.network_id());
     let results = process_incoming_transactions(&smp, transactions, timeline_state, false);
     log_txn_process_results(&results, Some(peer));
+    report_broadcast_to_peer_reputation(&smp, peer, &results);
 
     let ack_response = gen_ack_response(request_id, results, &peer);
 
@@ -191,6 +192,27 @@ pub(crate) async fn process_transaction_broadcast<NetworkClient, TransactionVali
     notify_subscribers(SharedMempoolNotification::ACK, &smp.subscribers);
 }
 
+/// Peers only broadcast transactions they have validated themselves, so a transaction
+/// with an invalid signature means the peer is faulty or malicious.
+fn report_broadcast_to_peer_reputation<NetworkClient, TransactionValidator>(
+    smp: &SharedMempool<NetworkClient, TransactionValidator>,
+    peer: PeerNetworkId,
+    results: &[SubmissionStatusBundle],
+) where
+    NetworkClient: NetworkClientInterface<MempoolSyncMsg>,
+    TransactionValidator: TransactionValidation,
+{
+    let has_invalid_signature = results
+        .iter()
+        .any(|(_, (_, vm_status))| *vm_status == Some(DiscardedVMStatus::INVALID_SIGNATURE));
+    let event = if has_invalid_signature {
+        PeerEvent::Malicious
+    } else {
+        PeerEvent::Useful
+    };
+    smp.network_interface.report_peer_event(peer, event);
+}
+
 /// If `MempoolIsFull` on any of the transactions, provide backpressure to the downstream peer.
 fn gen_ack_response(
     request_id: MultiBatchId,
@@ -12,14 +12,14 @@
 //! long as the latter is in its trusted peers set.
 use aptos_config::{
     config::{
-        DiscoveryMethod, NetworkConfig, Peer, PeerRole, PeerSet, RoleType, CONNECTION_BACKOFF_BASE,
-        CONNECTIVITY_CHECK_INTERVAL_MS, MAX_CONCURRENT_NETWORK_REQS, MAX_CONNECTION_DELAY_MS,
-        MAX_FRAME_SIZE, MAX_FULLNODE_OUTBOUND_CONNECTIONS, MAX_INBOUND_CONNECTIONS,
-        NETWORK_CHANNEL_SIZE,
+        DiscoveryMethod, NetworkConfig, OutboundQosConfig, Peer, PeerRole, PeerSet, RoleType,
+        TrafficCaptureConfig, CONNECTION_BACKOFF_BASE, CONNECTIVITY_CHECK_INTERVAL_MS,
+        MAX_CONCURRENT_NETWORK_REQS, MAX_CONNECTION_DELAY_MS, MAX_FRAME_SIZE,
+        MAX_FULLNODE_OUTBOUND_CONNECTIONS, MAX_INBOUND_CONNECTIONS, NETWORK_CHANNEL_SIZE,
     },
     network_id::NetworkContext,
 };
-use aptos_crypto::x25519::PublicKey;
+use aptos_crypto::x25519::{self, PublicKey};
 use aptos_event_notifications::{EventSubscriptionService, ReconfigNotificationListener};
 use aptos_logger::prelude::*;
 use aptos_netcore::transport::tcp::TCPBufferCfg;
@@ -35,12 +35,15 @@ use aptos_network::{
     protocols::{
         health_checker::{self, builder::HealthCheckerBuilder},
         network::{
-            NetworkApplicationConfig, NetworkClientConfig, NetworkServiceConfig, NewNetworkEvents,
-            NewNetworkSender,
+            NetworkApplicationConfig, NetworkClientConfig, NetworkEvents, NetworkSender,
+            NetworkServiceConfig, NewNetworkEvents, NewNetworkSender,
         },
+        wire::capture::TrafficCapture,
     },
 };
-use aptos_network_discovery::DiscoveryChangeListener;
+use aptos_network_discovery::{
+    peer_exchange_network_config, DiscoveryChangeListener, PeerExchangeMsg,
+};
 use aptos_time_service::TimeService;
 use aptos_types::{chain_id::ChainId, network_address::NetworkAddress};
 use std::{clone::Clone, collections::HashSet, sync::Arc, time::Duration};
@@ -83,6 +86,7 @@ impl NetworkBuilder {
         authentication_mode: AuthenticationMode,
         max_frame_size: usize,
         max_message_size: usize,
+        outbound_qos_config: OutboundQosConfig,
         enable_proxy_protocol: bool,
         network_channel_size: usize,
         max_concurrent_network_reqs: usize,
@@ -102,6 +106,7 @@ impl NetworkBuilder {
             max_concurrent_network_reqs,
             max_frame_size,
             max_message_size,
+            outbound_qos_config,
             enable_proxy_protocol,
             inbound_connection_limit,
             tcp_buffer_cfg,
@@ -140,6 +145,7 @@ impl NetworkBuilder {
             authentication_mode,
             MAX_FRAME_SIZE,
             MAX_MESSAGE_SIZE,
+            OutboundQosConfig::default(),
             false, /* Disable proxy protocol */
             NETWORK_CHANNEL_SIZE,
             MAX_CONCURRENT_NETWORK_REQS,
@@ -191,6 +197,7 @@ impl NetworkBuilder {
             authentication_mode,
             config.max_frame_size,
             config.max_message_size,
+            config.outbound_qos_config,
             config.enable_proxy_protocol,
             config.network_channel_size,
             config.max_concurrent_network_reqs,
@@ -203,6 +210,10 @@ impl NetworkBuilder {
             ),
         );
 
+        if config.traffic_capture_config.enabled {
+            network_builder.add_traffic_capture(&config.traffic_capture_config);
+        }
+
         network_builder.add_connection_monitoring(
             config.ping_interval_ms,
             config.ping_timeout_ms,
@@ -237,11 +248,18 @@ impl NetworkBuilder {
             } else {
                 None
             };
+            let identity_key = if let DiscoveryMethod::PeerExchange(_) = discovery_method {
+                Some(config.identity_key())
+            } else {
+                None
+            };
 
             network_builder.add_discovery_change_listener(
                 discovery_method,
                 pubkey,
                 reconfig_listener,
+                identity_key,
+                config.max_parallel_deserialization_tasks,
             );
         }
 
@@ -371,6 +389,8 @@ impl NetworkBuilder {
         discovery_method: &DiscoveryMethod,
         pubkey: PublicKey,
         reconfig_events: Option<ReconfigNotificationListener>,
+        identity_key: Option<x25519::PrivateKey>,
+        max_parallel_deserialization_tasks: Option<usize>,
     ) {
         let conn_mgr_reqs_tx = self
             .conn_mgr_reqs_tx()
@@ -401,6 +421,27 @@ impl NetworkBuilder {
                 Duration::from_secs(rest_discovery.interval_secs),
                 self.time_service.clone(),
             ),
+            DiscoveryMethod::PeerExchange(peer_exchange_discovery) => {
+                let identity_key =
+                    identity_key.expect("An identity key is required for peer exchange!");
+                let (network_sender, network_events): (
+                    NetworkSender<PeerExchangeMsg>,
+                    NetworkEvents<PeerExchangeMsg>,
+                ) = self.add_client_and_service(
+                    &peer_exchange_network_config(),
+                    max_parallel_deserialization_tasks,
+                );
+                DiscoveryChangeListener::peer_exchange(
+                    self.network_context,
+                    conn_mgr_reqs_tx,
+                    peer_exchange_discovery.clone(),
+                    &identity_key,
+                    network_sender,
+                    network_events,
+                    self.peers_and_metadata.clone(),
+                    self.time_service.clone(),
+                )
+            },
             DiscoveryMethod::None => return,
         };
 
@@ -410,6 +451,30 @@ impl NetworkBuilder {
             .push(listener);
     }
 
+    /// Capture the messages exchanged with all peers of the network. Failing to set up the
+    /// capture (e.g., because the capture directory isn't writable) doesn't prevent the node
+    /// from starting.
+    fn add_traffic_capture(&mut self, config: &TrafficCaptureConfig) {
+        match TrafficCapture::new(self.network_context.network_id(), config) {
+            Ok(traffic_capture) => {
+                self.peer_manager_builder
+                    .add_traffic_capture(traffic_capture);
+                info!(
+                    NetworkSchema::new(&self.network_context),
+                    "{} Capturing the network traffic to: {:?}",
+                    self.network_context,
+                    config.capture_dir
+                );
+            },
+            Err(error) => error!(
+                NetworkSchema::new(&self.network_context),
+                "{} Failed to set up the network traffic capture: {:?}",
+                self.network_context,
+                error
+            ),
+        }
+    }
+
     /// Add a HealthChecker to the network.
     fn add_connection_monitoring(
         &mut self,
@@ -0,0 +1,97 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_consensus::{
+    consensus_observer::network_message::ConsensusObserverMessage, network_interface::ConsensusMsg,
+};
+use aptos_mempool::MempoolSyncMsg;
+use aptos_network::{protocols::health_checker::HealthCheckerMsg, ProtocolId};
+use aptos_network_discovery::PeerExchangeMsg;
+use aptos_peer_monitoring_service_types::PeerMonitoringServiceMessage;
+use aptos_storage_service_types::StorageServiceMessage;
+
+/// A captured payload, decoded using the application message type of its protocol
+#[derive(Debug)]
+pub enum DecodedMessage {
+    Consensus(ConsensusMsg),
+    ConsensusObserver(ConsensusObserverMessage),
+    Mempool(MempoolSyncMsg),
+    StorageService(StorageServiceMessage),
+    PeerMonitoring(PeerMonitoringServiceMessage),
+    HealthChecker(HealthCheckerMsg),
+    PeerExchange(PeerExchangeMsg),
+}
+
+impl DecodedMessage {
+    /// Decodes the payload using the message type of the given protocol. Returns None if the
+    /// protocol has no known message type.
+    pub fn decode(protocol_id: ProtocolId, payload: &[u8]) -> Option<anyhow::Result<Self>> {
+        use ProtocolId::*;
+        let message = match protocol_id {
+            ConsensusRpcBcs
+            | ConsensusDirectSendBcs
+            | ConsensusDirectSendJson
+            | ConsensusRpcJson
+            | ConsensusRpcCompressed
+            | ConsensusDirectSendCompressed
+            | ConsensusRpcZstd
+            | ConsensusDirectSendZstd
+            | ConsensusRpcZstdDictionary
+            | ConsensusDirectSendZstdDictionary
+            | QuorumStoreRpcCompressed
+            | QuorumStoreDirectSendCompressed => {
+                protocol_id.from_bytes(payload).map(Self::Consensus)
+            },
+            ConsensusObserver | ConsensusObserverRpc => {
+                protocol_id.from_bytes(payload).map(Self::ConsensusObserver)
+            },
+            MempoolDirectSend
+            | MempoolRpc
+            | MempoolDirectSendZstd
+            | MempoolDirectSendZstdDictionary => protocol_id.from_bytes(payload).map(Self::Mempool),
+            StorageServiceRpc => protocol_id.from_bytes(payload).map(Self::StorageService),
+            PeerMonitoringServiceRpc => protocol_id.from_bytes(payload).map(Self::PeerMonitoring),
+            HealthCheckerRpc => protocol_id.from_bytes(payload).map(Self::HealthChecker),
+            DiscoveryDirectSend => protocol_id.from_bytes(payload).map(Self::PeerExchange),
+            StateSyncDirectSend => return None,
+        };
+        Some(message)
+    }
+
+    /// Returns a short label of the message (e.g., its variant)
+    pub fn get_label(&self) -> String {
+        match self {
+            DecodedMessage::Consensus(message) => message.name().to_string(),
+            DecodedMessage::ConsensusObserver(message) => match message {
+                ConsensusObserverMessage::Request(request) => request.get_label().to_string(),
+                ConsensusObserverMessage::Response(response) => format!("{:?}", response),
+                ConsensusObserverMessage::DirectSend(message) => message.get_label().to_string(),
+            },
+            DecodedMessage::Mempool(message) => match message {
+                MempoolSyncMsg::BroadcastTransactionsRequest { transactions, .. } => {
+                    format!("BroadcastTransactionsRequest ({} txns)", transactions.len())
+                },
+                MempoolSyncMsg::BroadcastTransactionsResponse { retry, backoff, .. } => format!(
+                    "BroadcastTransactionsResponse (retry: {}, backoff: {})",
+                    retry, backoff
+                ),
+            },
+            DecodedMessage::StorageService(message) => match message {
+                StorageServiceMessage::Request(request) => request.get_label(),
+                StorageServiceMessage::Response(Ok(response)) => response.get_label(),
+                StorageServiceMessage::Response(Err(error)) => format!("error: {}", error),
+            },
+            DecodedMessage::PeerMonitoring(message) => match message {
+                PeerMonitoringServiceMessage::Request(request) => request.get_label().to_string(),
+                PeerMonitoringServiceMessage::Response(Ok(response)) => {
+                    response.get_label().to_string()
+                },
+                PeerMonitoringServiceMessage::Response(Err(error)) => format!("error: {}", error),
+            },
+            DecodedMessage::HealthChecker(message) => format!("{:?}", message),
+            DecodedMessage::PeerExchange(PeerExchangeMsg::Records(records)) => {
+                format!("Records ({} peers)", records.len())
+            },
+        }
+    }
+}
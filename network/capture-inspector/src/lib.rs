@@ -0,0 +1,333 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! A tool for inspecting the network traffic captured by a node (see
+//! `aptos_network::protocols::wire::capture`). The captured payloads are decoded using the
+//! application message types of their protocols, and the messages can be filtered to follow
+//! the conversations with specific peers or protocols.
+
+mod decode;
+
+use anyhow::bail;
+use aptos_compression::dictionary::{add_decompression_dictionary, CompressionDictionary};
+use aptos_config::network_id::NetworkId;
+use aptos_network::{
+    protocols::wire::{
+        capture::{read_capture, CaptureDirection, CapturedMessage, CapturedMessageType},
+        messaging::v1::RequestId,
+    },
+    ProtocolId,
+};
+use aptos_types::PeerId;
+use clap::Parser;
+pub use decode::DecodedMessage;
+use std::{collections::HashMap, path::PathBuf, sync::Arc};
+
+/// Decode and filter the network traffic captured by a node
+#[derive(Debug, Parser)]
+#[clap(author, version, about, long_about = None)]
+pub struct Args {
+    /// The capture file, or the capture directory (to read the captures of all networks)
+    #[clap(value_parser)]
+    pub capture_path: PathBuf,
+
+    /// Only show the messages exchanged with this peer
+    #[clap(long)]
+    pub peer_id: Option<PeerId>,
+
+    /// Only show the messages of this protocol (e.g., "ConsensusRpcBcs")
+    #[clap(long, value_parser = parse_protocol_id)]
+    pub protocol: Option<ProtocolId>,
+
+    /// Only show the messages sent or received by the node ("inbound" or "outbound")
+    #[clap(long, value_parser = parse_direction)]
+    pub direction: Option<CaptureDirection>,
+
+    /// Only show the messages of this type (e.g., "rpc_request" or "direct_send")
+    #[clap(long, value_parser = parse_message_type)]
+    pub message_type: Option<CapturedMessageType>,
+
+    /// Only show the messages captured at or after this time (in microseconds since the epoch)
+    #[clap(long)]
+    pub start_time_usecs: Option<u64>,
+
+    /// Only show the messages captured before this time (in microseconds since the epoch)
+    #[clap(long)]
+    pub end_time_usecs: Option<u64>,
+
+    /// The zstd dictionaries used by the node to compress its messages
+    #[clap(long)]
+    pub dictionary: Vec<PathBuf>,
+
+    /// Print the full decoded messages, instead of a summary
+    #[clap(long)]
+    pub verbose: bool,
+}
+
+impl Args {
+    pub fn run(self) -> anyhow::Result<()> {
+        for path in &self.dictionary {
+            add_decompression_dictionary(Arc::new(CompressionDictionary::load(path)?));
+        }
+
+        let messages = read_capture(&self.capture_path)?;
+        for message in inspect_messages(messages, &self) {
+            println!("{}", message.summary());
+            if self.verbose {
+                match &message.decoded_message {
+                    Some(Ok(decoded_message)) => println!("{:#?}", decoded_message),
+                    Some(Err(error)) => println!("Failed to decode the payload: {:?}", error),
+                    None => {},
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn matches(&self, message: &InspectedMessage) -> bool {
+        let captured_message = &message.captured_message;
+        self.peer_id
+            .map_or(true, |peer_id| captured_message.peer_id == peer_id)
+            && self
+                .protocol
+                .map_or(true, |protocol_id| message.protocol_id == Some(protocol_id))
+            && self
+                .direction
+                .map_or(true, |direction| captured_message.direction == direction)
+            && self.message_type.map_or(true, |message_type| {
+                captured_message.message_type == message_type
+            })
+            && self.start_time_usecs.map_or(true, |start_time_usecs| {
+                captured_message.timestamp_usecs >= start_time_usecs
+            })
+            && self.end_time_usecs.map_or(true, |end_time_usecs| {
+                captured_message.timestamp_usecs < end_time_usecs
+            })
+    }
+}
+
+/// A captured message, along with its protocol (which, for rpc responses, is the protocol of
+/// the matching request) and its decoded payload (if it was captured).
+pub struct InspectedMessage {
+    pub captured_message: CapturedMessage,
+    pub protocol_id: Option<ProtocolId>,
+    pub decoded_message: Option<anyhow::Result<DecodedMessage>>,
+}
+
+impl InspectedMessage {
+    /// Returns a single line summary of the message
+    pub fn summary(&self) -> String {
+        let message = &self.captured_message;
+        let arrow = match message.direction {
+            CaptureDirection::Inbound => "<-",
+            CaptureDirection::Outbound => "->",
+        };
+        let mut summary = format!(
+            "{} {} {} {} {} {}",
+            message.timestamp_usecs,
+            message.network_id,
+            arrow,
+            message.peer_id,
+            message.message_type.as_str(),
+            self.protocol_id
+                .map_or("unknown", |protocol_id| protocol_id.as_str()),
+        );
+        if let Some(request_id) = message.request_id {
+            summary += &format!(" (request {})", request_id);
+        }
+        summary += &format!(" {} bytes", message.size_bytes);
+        match &self.decoded_message {
+            Some(Ok(decoded_message)) => summary += &format!(": {}", decoded_message.get_label()),
+            Some(Err(_)) => summary += ": <undecodable>",
+            None => {},
+        }
+        summary
+    }
+}
+
+/// Resolves the protocol of every message and decodes its payload, and returns the messages
+/// matching the given filters (in the order they were captured).
+pub fn inspect_messages(messages: Vec<CapturedMessage>, args: &Args) -> Vec<InspectedMessage> {
+    // The protocols of the pending rpc requests, keyed by the peer, the direction of the
+    // request and its id.
+    let mut pending_requests: HashMap<(NetworkId, PeerId, bool, RequestId), ProtocolId> =
+        HashMap::new();
+
+    let mut inspected_messages = vec![];
+    for message in messages {
+        let is_outbound = message.direction == CaptureDirection::Outbound;
+        let protocol_id = match (message.message_type, message.request_id) {
+            (CapturedMessageType::RpcRequest, Some(request_id)) => {
+                if let Some(protocol_id) = message.protocol_id {
+                    pending_requests.insert(
+                        (message.network_id, message.peer_id, is_outbound, request_id),
+                        protocol_id,
+                    );
+                }
+                message.protocol_id
+            },
+            (CapturedMessageType::RpcResponse, Some(request_id)) => pending_requests.remove(&(
+                message.network_id,
+                message.peer_id,
+                !is_outbound,
+                request_id,
+            )),
+            _ => message.protocol_id,
+        };
+        let decoded_message = match (protocol_id, &message.payload) {
+            (Some(protocol_id), Some(payload)) => DecodedMessage::decode(protocol_id, payload),
+            _ => None,
+        };
+
+        let inspected_message = InspectedMessage {
+            captured_message: message,
+            protocol_id,
+            decoded_message,
+        };
+        if args.matches(&inspected_message) {
+            inspected_messages.push(inspected_message);
+        }
+    }
+    inspected_messages
+}
+
+fn parse_protocol_id(protocol: &str) -> anyhow::Result<ProtocolId> {
+    match ProtocolId::all()
+        .iter()
+        .find(|protocol_id| protocol_id.as_str().eq_ignore_ascii_case(protocol))
+    {
+        Some(protocol_id) => Ok(*protocol_id),
+        None => bail!("Unknown protocol: {}", protocol),
+    }
+}
+
+fn parse_direction(direction: &str) -> anyhow::Result<CaptureDirection> {
+    match direction {
+        "inbound" => Ok(CaptureDirection::Inbound),
+        "outbound" => Ok(CaptureDirection::Outbound),
+        _ => bail!("Unknown direction: {}", direction),
+    }
+}
+
+fn parse_message_type(message_type: &str) -> anyhow::Result<CapturedMessageType> {
+    match [
+        CapturedMessageType::Error,
+        CapturedMessageType::RpcRequest,
+        CapturedMessageType::RpcResponse,
+        CapturedMessageType::DirectSend,
+    ]
+    .into_iter()
+    .find(|captured_message_type| captured_message_type.as_str() == message_type)
+    {
+        Some(message_type) => Ok(message_type),
+        None => bail!("Unknown message type: {}", message_type),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_network::protocols::wire::messaging::v1::{
+        DirectSendMsg, NetworkMessage, RpcRequest, RpcResponse,
+    };
+    use aptos_peer_monitoring_service_types::{
+        request::{LatencyPingRequest, PeerMonitoringServiceRequest},
+        response::{LatencyPingResponse, PeerMonitoringServiceResponse},
+        PeerMonitoringServiceMessage,
+    };
+
+    fn capture(
+        timestamp_usecs: u64,
+        peer_id: PeerId,
+        direction: CaptureDirection,
+        message: NetworkMessage,
+    ) -> CapturedMessage {
+        CapturedMessage::new(
+            timestamp_usecs,
+            NetworkId::Public,
+            peer_id,
+            direction,
+            &message,
+            true,
+        )
+    }
+
+    #[test]
+    fn test_inspect_rpc_conversation() {
+        let protocol_id = ProtocolId::PeerMonitoringServiceRpc;
+        let request = PeerMonitoringServiceMessage::Request(
+            PeerMonitoringServiceRequest::LatencyPing(LatencyPingRequest { ping_counter: 1 }),
+        );
+        let response = PeerMonitoringServiceMessage::Response(Ok(
+            PeerMonitoringServiceResponse::LatencyPing(LatencyPingResponse { ping_counter: 1 }),
+        ));
+
+        // Capture a ping to a peer (and its response), along with a message from another peer
+        let peer_id = PeerId::random();
+        let messages = vec![
+            capture(
+                1,
+                peer_id,
+                CaptureDirection::Outbound,
+                NetworkMessage::RpcRequest(RpcRequest {
+                    protocol_id,
+                    request_id: 7,
+                    priority: 0,
+                    raw_request: protocol_id.to_bytes(&request).unwrap(),
+                }),
+            ),
+            capture(
+                2,
+                PeerId::random(),
+                CaptureDirection::Inbound,
+                NetworkMessage::DirectSendMsg(DirectSendMsg {
+                    protocol_id: ProtocolId::MempoolDirectSend,
+                    priority: 0,
+                    raw_msg: vec![1, 2, 3],
+                }),
+            ),
+            capture(
+                3,
+                peer_id,
+                CaptureDirection::Inbound,
+                NetworkMessage::RpcResponse(RpcResponse {
+                    request_id: 7,
+                    priority: 0,
+                    raw_response: protocol_id.to_bytes(&response).unwrap(),
+                }),
+            ),
+        ];
+
+        // Verify the response is attributed to the protocol of the request, and decoded
+        let args = Args::try_parse_from([
+            "inspector",
+            "capture",
+            "--protocol",
+            "peermonitoringservicerpc",
+        ])
+        .unwrap();
+        let inspected_messages = inspect_messages(messages.clone(), &args);
+        assert_eq!(inspected_messages.len(), 2);
+        for inspected_message in &inspected_messages {
+            assert_eq!(inspected_message.protocol_id, Some(protocol_id));
+            let decoded_message = inspected_message.decoded_message.as_ref().unwrap();
+            assert_eq!(
+                decoded_message.as_ref().unwrap().get_label(),
+                "latency_ping"
+            );
+        }
+
+        // Verify the messages are filtered by direction and time
+        let args =
+            Args::try_parse_from(["inspector", "capture", "--direction", "inbound"]).unwrap();
+        assert_eq!(inspect_messages(messages.clone(), &args).len(), 2);
+        let args =
+            Args::try_parse_from(["inspector", "capture", "--start-time-usecs", "2"]).unwrap();
+        let inspected_messages = inspect_messages(messages, &args);
+        let timestamps: Vec<_> = inspected_messages
+            .iter()
+            .map(|message| message.captured_message.timestamp_usecs)
+            .collect();
+        assert_eq!(timestamps, vec![2, 3]);
+    }
+}
@@ -0,0 +1,9 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_network_capture_inspector::Args;
+use clap::Parser;
+
+fn main() -> anyhow::Result<()> {
+    Args::parse().run()
+}
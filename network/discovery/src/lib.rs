@@ -3,23 +3,29 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    counters::DISCOVERY_COUNTS, file::FileStream, rest::RestStream,
-    validator_set::ValidatorSetStream,
+    counters::DISCOVERY_COUNTS, file::FileStream, peer_exchange::PeerExchangeStream,
+    rest::RestStream, validator_set::ValidatorSetStream,
+};
+use aptos_config::{
+    config::{PeerExchangeDiscovery, PeerSet},
+    network_id::NetworkContext,
 };
-use aptos_config::{config::PeerSet, network_id::NetworkContext};
 use aptos_crypto::x25519;
 use aptos_event_notifications::ReconfigNotificationListener;
 use aptos_logger::prelude::*;
 use aptos_network::{
+    application::storage::PeersAndMetadata,
     connectivity_manager::{ConnectivityRequest, DiscoverySource},
     counters::inc_by_with_context,
     logging::NetworkSchema,
+    protocols::network::{NetworkEvents, NetworkSender},
 };
 use aptos_time_service::TimeService;
 use futures::{Stream, StreamExt};
 use std::{
     path::Path,
     pin::Pin,
+    sync::Arc,
     task::{Context, Poll},
     time::Duration,
 };
@@ -27,9 +33,12 @@ use tokio::runtime::Handle;
 
 mod counters;
 mod file;
+mod peer_exchange;
 mod rest;
 mod validator_set;
 
+pub use peer_exchange::{peer_exchange_network_config, PeerExchangeMsg};
+
 #[derive(Debug)]
 pub enum DiscoveryError {
     IO(std::io::Error),
@@ -49,6 +58,7 @@ enum DiscoveryChangeStream {
     ValidatorSet(ValidatorSetStream),
     File(FileStream),
     Rest(RestStream),
+    PeerExchange(PeerExchangeStream),
 }
 
 impl Stream for DiscoveryChangeStream {
@@ -59,6 +69,7 @@ impl Stream for DiscoveryChangeStream {
             Self::ValidatorSet(stream) => Pin::new(stream).poll_next(cx),
             Self::File(stream) => Pin::new(stream).poll_next(cx),
             Self::Rest(stream) => Pin::new(stream).poll_next(cx),
+            Self::PeerExchange(stream) => Pin::new(stream).poll_next(cx),
         }
     }
 }
@@ -124,6 +135,33 @@ impl DiscoveryChangeListener {
         }
     }
 
+    pub fn peer_exchange(
+        network_context: NetworkContext,
+        update_channel: aptos_channels::Sender<ConnectivityRequest>,
+        config: PeerExchangeDiscovery,
+        identity_key: &x25519::PrivateKey,
+        network_sender: NetworkSender<PeerExchangeMsg>,
+        network_events: NetworkEvents<PeerExchangeMsg>,
+        peers_and_metadata: Arc<PeersAndMetadata>,
+        time_service: TimeService,
+    ) -> Self {
+        let source_stream = DiscoveryChangeStream::PeerExchange(PeerExchangeStream::new(
+            network_context,
+            config,
+            identity_key,
+            network_sender,
+            network_events,
+            peers_and_metadata,
+            time_service,
+        ));
+        DiscoveryChangeListener {
+            discovery_source: DiscoverySource::PeerExchange,
+            network_context,
+            update_channel,
+            source_stream,
+        }
+    }
+
     pub fn start(self, executor: &Handle) {
         spawn_named!("DiscoveryChangeListener", executor, Box::pin(self).run());
     }
@@ -0,0 +1,821 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Peer discovery by gossiping address records (i.e., a peer exchange protocol)
+//!
+//! Each node periodically sends its own address record, along with a random sample of the
+//! records of the peers it's connected to, to a random connected peer. The records are
+//! checked on receipt and the node hands a bounded, randomly sampled subset of them to the
+//! `ConnectivityManager` as another discovery source.
+//!
+//! Records are signed with an ed25519 key derived from the network identity key. However, the
+//! x25519 identity key can't sign, so the public signing key can't be verified against the peer
+//! id, and a relayed record of a peer we never talked to may be signed by anyone. The signature
+//! only binds a record to its sender once the signing key of the peer is pinned, which happens
+//! the first time the peer sends its own record over a (noise authenticated) connection. After
+//! that, relayed records signed with a different key are ignored.
+//!
+//! Unpinned records are safe to dial nevertheless: as peer ids on the public network are derived
+//! from the network keys, a record is only accepted if the noise key in every address matches the
+//! peer id, so a forged address can't be used to impersonate the peer (the noise handshake with
+//! anyone else fails). At worst, it wastes a dial.
+//!
+//! To limit eclipse attacks, the number of records (and discovered peers) per subnet is capped,
+//! discovered peers are sampled randomly, and discovered addresses have the lowest priority in the
+//! `ConnectivityManager`. Peers sending invalid records are reported as malicious to the peer
+//! reputations (and eventually banned).
+
+use crate::{counters::DISCOVERY_COUNTS, DiscoveryError};
+use aptos_channels::{aptos_channel, message_queues::QueueStyle};
+use aptos_config::{
+    config::{Peer, PeerExchangeDiscovery, PeerRole, PeerSet, HANDSHAKE_VERSION},
+    network_id::{NetworkContext, PeerNetworkId},
+};
+use aptos_crypto::{
+    ed25519::{Ed25519PrivateKey, Ed25519PublicKey, Ed25519Signature},
+    x25519, HashValue, PrivateKey, Signature, SigningKey, ValidCryptoMaterial,
+};
+use aptos_crypto_derive::{BCSCryptoHash, CryptoHasher};
+use aptos_logger::prelude::*;
+use aptos_network::{
+    application::{reputation::PeerEvent, storage::PeersAndMetadata},
+    constants::NETWORK_CHANNEL_SIZE,
+    counters::{inc_by_with_context, PENDING_DISCOVERY_NETWORK_EVENTS},
+    logging::NetworkSchema,
+    protocols::network::{
+        Event, NetworkApplicationConfig, NetworkClientConfig, NetworkEvents, NetworkSender,
+        NetworkServiceConfig,
+    },
+    ProtocolId,
+};
+use aptos_time_service::{Interval, TimeService, TimeServiceTrait};
+use aptos_types::{
+    account_address::from_identity_public_key, network_address::NetworkAddress, PeerId,
+};
+use futures::{Stream, StreamExt};
+use rand::{rngs::SmallRng, seq::SliceRandom, Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{HashMap, HashSet},
+    convert::TryFrom,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr},
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+/// Domain separator for deriving the record signing key from the network identity key
+const RECORD_SIGNING_KEY_SALT: &[u8] = b"APTOS::PeerExchange::RecordSigningKey";
+/// The max number of addresses in a single record
+const MAX_ADDRESSES_PER_RECORD: usize = 8;
+/// Records with timestamps further in the future than this are rejected
+const MAX_CLOCK_SKEW: Duration = Duration::from_secs(5 * 60);
+/// The number of records stored for each peer that may be discovered
+const RECORDS_PER_DISCOVERED_PEER: usize = 4;
+
+/// Returns a network application config for the peer exchange client and service
+pub fn peer_exchange_network_config() -> NetworkApplicationConfig {
+    let direct_send_protocols = vec![ProtocolId::DiscoveryDirectSend];
+    let rpc_protocols = vec![]; // Peer exchange doesn't use rpc
+
+    let network_client_config =
+        NetworkClientConfig::new(direct_send_protocols.clone(), rpc_protocols.clone());
+    let network_service_config = NetworkServiceConfig::new(
+        direct_send_protocols,
+        rpc_protocols,
+        aptos_channel::Config::new(NETWORK_CHANNEL_SIZE)
+            .queue_style(QueueStyle::LIFO)
+            .counters(&PENDING_DISCOVERY_NETWORK_EVENTS),
+    );
+    NetworkApplicationConfig::new(network_client_config, network_service_config)
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub enum PeerExchangeMsg {
+    Records(Vec<SignedPeerRecord>),
+}
+
+/// The addresses at which a peer can be reached
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize, CryptoHasher, BCSCryptoHash)]
+pub struct PeerRecord {
+    pub peer_id: PeerId,
+    pub addresses: Vec<NetworkAddress>,
+    pub timestamp_usecs: u64,
+}
+
+/// A `PeerRecord` signed by the peer itself
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct SignedPeerRecord {
+    pub record: PeerRecord,
+    pub public_key: Ed25519PublicKey,
+    pub signature: Ed25519Signature,
+}
+
+impl SignedPeerRecord {
+    pub fn sign(record: PeerRecord, signing_key: &Ed25519PrivateKey) -> Self {
+        let signature = signing_key
+            .sign(&record)
+            .expect("Signing a peer record should never fail!");
+        Self {
+            record,
+            public_key: signing_key.public_key(),
+            signature,
+        }
+    }
+
+    fn peer_id(&self) -> PeerId {
+        self.record.peer_id
+    }
+
+    /// Verifies the signature and addresses of the record. Returns the reason on failure.
+    fn verify(&self) -> Result<(), &'static str> {
+        let addresses = &self.record.addresses;
+        if addresses.is_empty() || addresses.len() > MAX_ADDRESSES_PER_RECORD {
+            return Err("invalid_address_count");
+        }
+        for address in addresses {
+            if !address.is_aptosnet_addr() || subnet(address).is_none() {
+                return Err("invalid_address");
+            }
+            let noise_key = address.find_noise_proto().ok_or("invalid_address")?;
+            if from_identity_public_key(noise_key) != self.record.peer_id {
+                return Err("peer_id_mismatch");
+            }
+        }
+        self.signature
+            .verify(&self.record, &self.public_key)
+            .map_err(|_| "invalid_signature")
+    }
+
+    /// Returns the subnets of all addresses in the record
+    fn subnets(&self) -> HashSet<Subnet> {
+        self.record.addresses.iter().filter_map(subnet).collect()
+    }
+}
+
+/// Derives the key used to sign the records of this node from its network identity key
+pub fn derive_record_signing_key(identity_key: &x25519::PrivateKey) -> Ed25519PrivateKey {
+    let seed =
+        HashValue::sha3_256_of(&[RECORD_SIGNING_KEY_SALT, &identity_key.to_bytes()].concat());
+    Ed25519PrivateKey::try_from(seed.to_vec().as_slice())
+        .expect("Any 32 bytes are a valid ed25519 key!")
+}
+
+/// The subnet of an address: an IPv4 /24, an IPv6 /48 or a DNS name
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+enum Subnet {
+    V4([u8; 3]),
+    V6([u8; 6]),
+    Dns(String),
+}
+
+/// Returns the subnet of the address, or `None` if it can't be dialed from another host on
+/// the public internet (e.g., loopback, private, link-local and documentation addresses).
+fn subnet(address: &NetworkAddress) -> Option<Subnet> {
+    match address.find_ip_addr() {
+        Some(IpAddr::V4(ip)) if is_public_ipv4(&ip) => {
+            let octets = ip.octets();
+            Some(Subnet::V4([octets[0], octets[1], octets[2]]))
+        },
+        Some(IpAddr::V6(ip)) if is_public_ipv6(&ip) => {
+            let mut prefix = [0; 6];
+            prefix.copy_from_slice(&ip.octets()[..6]);
+            Some(Subnet::V6(prefix))
+        },
+        Some(_) => None,
+        None => address
+            .as_slice()
+            .first()
+            .map(|protocol| Subnet::Dns(protocol.to_string())),
+    }
+}
+
+fn is_public_ipv4(ip: &Ipv4Addr) -> bool {
+    !(ip.is_unspecified()
+        || ip.is_multicast()
+        || ip.is_broadcast()
+        || ip.is_loopback()
+        || ip.is_private()
+        || ip.is_link_local()
+        || ip.is_documentation())
+}
+
+fn is_public_ipv6(ip: &Ipv6Addr) -> bool {
+    if let Some(ipv4) = ip.to_ipv4_mapped() {
+        return is_public_ipv4(&ipv4);
+    }
+    let segments = ip.segments();
+    let is_unique_local = segments[0] & 0xfe00 == 0xfc00; // fc00::/7
+    let is_link_local = segments[0] & 0xffc0 == 0xfe80; // fe80::/10
+    let is_documentation = segments[0] == 0x2001 && segments[1] == 0xdb8; // 2001:db8::/32
+    !(ip.is_unspecified()
+        || ip.is_multicast()
+        || ip.is_loopback()
+        || is_unique_local
+        || is_link_local
+        || is_documentation)
+}
+
+/// The outcome of receiving a record
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum RecordOutcome {
+    /// The record was stored
+    Accepted,
+    /// The record is valid, but was not stored (e.g., it's stale or its subnet is full)
+    Ignored(&'static str),
+    /// The record is invalid, which only a faulty or malicious peer would send
+    Invalid(&'static str),
+}
+
+/// The verified records known to the node
+struct PeerRecords {
+    own_peer_id: PeerId,
+    config: PeerExchangeDiscovery,
+    records: HashMap<PeerId, SignedPeerRecord>,
+    pinned_keys: HashMap<PeerId, Ed25519PublicKey>,
+}
+
+impl PeerRecords {
+    fn new(own_peer_id: PeerId, config: PeerExchangeDiscovery) -> Self {
+        Self {
+            own_peer_id,
+            config,
+            records: HashMap::new(),
+            pinned_keys: HashMap::new(),
+        }
+    }
+
+    fn max_records(&self) -> usize {
+        self.config.max_discovered_peers * RECORDS_PER_DISCOVERED_PEER
+    }
+
+    fn is_expired(&self, record: &SignedPeerRecord, now_usecs: u64) -> bool {
+        let ttl_usecs = Duration::from_secs(self.config.record_ttl_secs).as_micros() as u64;
+        record.record.timestamp_usecs.saturating_add(ttl_usecs) < now_usecs
+    }
+
+    /// Verifies and stores a record received from the given sender
+    fn insert<R: Rng>(
+        &mut self,
+        sender: PeerId,
+        record: SignedPeerRecord,
+        now_usecs: u64,
+        rng: &mut R,
+    ) -> RecordOutcome {
+        let peer_id = record.peer_id();
+        if peer_id == self.own_peer_id {
+            return RecordOutcome::Ignored("own_record");
+        }
+        if let Err(reason) = record.verify() {
+            return RecordOutcome::Invalid(reason);
+        }
+        let max_skew_usecs = MAX_CLOCK_SKEW.as_micros() as u64;
+        if record.record.timestamp_usecs > now_usecs.saturating_add(max_skew_usecs) {
+            return RecordOutcome::Invalid("future_timestamp");
+        }
+        if self.is_expired(&record, now_usecs) {
+            return RecordOutcome::Ignored("expired");
+        }
+
+        // Pin the signing key of peers that send their own records, and ignore
+        // relayed records that aren't signed with the pinned key.
+        if sender == peer_id {
+            self.pinned_keys.insert(peer_id, record.public_key.clone());
+        } else if let Some(pinned_key) = self.pinned_keys.get(&peer_id) {
+            if pinned_key != &record.public_key {
+                return RecordOutcome::Ignored("unpinned_key");
+            }
+        }
+
+        // Only replace existing records with newer ones (signed by the same key,
+        // unless the record comes from the peer itself).
+        if let Some(existing_record) = self.records.get(&peer_id) {
+            if sender != peer_id && existing_record.public_key != record.public_key {
+                return RecordOutcome::Ignored("conflicting_key");
+            }
+            if existing_record.record.timestamp_usecs >= record.record.timestamp_usecs {
+                return RecordOutcome::Ignored("stale");
+            }
+        }
+
+        // Cap the number of records per subnet, so that a single operator can't fill the store
+        let subnets = record.subnets();
+        let num_records_in_subnets = self
+            .records
+            .values()
+            .filter(|existing_record| existing_record.peer_id() != peer_id)
+            .filter(|existing_record| !existing_record.subnets().is_disjoint(&subnets))
+            .count();
+        if num_records_in_subnets >= self.config.max_peers_per_subnet {
+            return RecordOutcome::Ignored("subnet_full");
+        }
+
+        // Evict a random record if the store is full
+        if !self.records.contains_key(&peer_id) && self.records.len() >= self.max_records() {
+            let peer_ids: Vec<_> = self.records.keys().copied().collect();
+            if let Some(evicted_peer_id) = peer_ids.choose(rng) {
+                self.records.remove(evicted_peer_id);
+            }
+        }
+        self.records.insert(peer_id, record);
+        RecordOutcome::Accepted
+    }
+
+    /// Removes the expired records
+    fn remove_expired(&mut self, now_usecs: u64) {
+        let expired_peers: Vec<_> = self
+            .records
+            .values()
+            .filter(|record| self.is_expired(record, now_usecs))
+            .map(SignedPeerRecord::peer_id)
+            .collect();
+        for peer_id in expired_peers {
+            self.records.remove(&peer_id);
+        }
+    }
+
+    /// Returns a random sample of the records of the given peers
+    fn sample<R: Rng>(
+        &self,
+        peer_ids: &HashSet<PeerId>,
+        count: usize,
+        rng: &mut R,
+    ) -> Vec<SignedPeerRecord> {
+        let mut records: Vec<_> = peer_ids
+            .iter()
+            .filter_map(|peer_id| self.records.get(peer_id))
+            .cloned()
+            .collect();
+        records.shuffle(rng);
+        records.truncate(count);
+        records
+    }
+
+    /// Selects the peers to hand to the connectivity manager. The previously selected
+    /// peers are kept (if their records still exist), and the rest are sampled randomly.
+    fn select_peers<R: Rng>(
+        &self,
+        previous_peers: &PeerSet,
+        is_banned: impl Fn(&PeerId) -> bool,
+        rng: &mut R,
+    ) -> PeerSet {
+        let mut records: Vec<_> = self
+            .records
+            .values()
+            .filter(|record| !is_banned(&record.peer_id()))
+            .collect();
+        records.shuffle(rng);
+        records.sort_by_key(|record| !previous_peers.contains_key(&record.peer_id()));
+        records
+            .into_iter()
+            .take(self.config.max_discovered_peers)
+            .map(|record| {
+                let peer = Peer::from_addrs(PeerRole::Unknown, record.record.addresses.clone());
+                (record.peer_id(), peer)
+            })
+            .collect()
+    }
+}
+
+/// A discovery stream that exchanges peer records with the connected peers
+pub struct PeerExchangeStream {
+    network_context: NetworkContext,
+    config: PeerExchangeDiscovery,
+    own_addresses: Vec<NetworkAddress>,
+    signing_key: Ed25519PrivateKey,
+    network_sender: NetworkSender<PeerExchangeMsg>,
+    network_events: NetworkEvents<PeerExchangeMsg>,
+    peers_and_metadata: Arc<PeersAndMetadata>,
+    time_service: TimeService,
+    interval: Pin<Box<Interval>>,
+    records: PeerRecords,
+    discovered_peers: PeerSet,
+    rng: SmallRng,
+}
+
+impl PeerExchangeStream {
+    pub(crate) fn new(
+        network_context: NetworkContext,
+        config: PeerExchangeDiscovery,
+        identity_key: &x25519::PrivateKey,
+        network_sender: NetworkSender<PeerExchangeMsg>,
+        network_events: NetworkEvents<PeerExchangeMsg>,
+        peers_and_metadata: Arc<PeersAndMetadata>,
+        time_service: TimeService,
+    ) -> Self {
+        // Append the noise and handshake protocols to the advertised addresses (if missing)
+        let own_addresses = config
+            .advertised_addresses
+            .iter()
+            .cloned()
+            .map(|address| {
+                if address.find_noise_proto().is_some() {
+                    address
+                } else {
+                    address.append_prod_protos(identity_key.public_key(), HANDSHAKE_VERSION)
+                }
+            })
+            .collect();
+        let interval = Box::pin(time_service.interval(Duration::from_secs(config.interval_secs)));
+        PeerExchangeStream {
+            network_context,
+            config: config.clone(),
+            own_addresses,
+            signing_key: derive_record_signing_key(identity_key),
+            network_sender,
+            network_events,
+            peers_and_metadata,
+            time_service,
+            interval,
+            records: PeerRecords::new(network_context.peer_id(), config),
+            discovered_peers: PeerSet::new(),
+            rng: SmallRng::from_entropy(),
+        }
+    }
+
+    fn now_usecs(&self) -> u64 {
+        self.time_service.now_unix_time().as_micros() as u64
+    }
+
+    /// Returns the connected peers (on this network) that support peer exchange
+    fn connected_peers(&self) -> HashSet<PeerId> {
+        self.peers_and_metadata
+            .get_connected_supported_peers(&[ProtocolId::DiscoveryDirectSend])
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|peer| peer.network_id() == self.network_context.network_id())
+            .map(|peer| peer.peer_id())
+            .collect()
+    }
+
+    /// Sends our own record and a sample of the records of our connected peers to a random peer
+    fn send_records(&mut self) {
+        let connected_peers = self.connected_peers();
+        let candidates: Vec<_> = connected_peers.iter().copied().collect();
+        let recipient = match candidates.choose(&mut self.rng) {
+            Some(recipient) => *recipient,
+            None => return, // There's no one to exchange records with
+        };
+
+        let mut records = Vec::new();
+        if !self.own_addresses.is_empty() {
+            let own_record = PeerRecord {
+                peer_id: self.network_context.peer_id(),
+                addresses: self.own_addresses.clone(),
+                timestamp_usecs: self.now_usecs(),
+            };
+            records.push(SignedPeerRecord::sign(own_record, &self.signing_key));
+        }
+        let num_relayed_records = self
+            .config
+            .max_records_per_message
+            .saturating_sub(records.len());
+        records.extend(
+            self.records
+                .sample(&connected_peers, num_relayed_records, &mut self.rng)
+                .into_iter()
+                .filter(|record| record.peer_id() != recipient),
+        );
+        if records.is_empty() {
+            return;
+        }
+
+        let num_records = records.len() as u64;
+        if let Err(error) = self.network_sender.send_to(
+            recipient,
+            ProtocolId::DiscoveryDirectSend,
+            PeerExchangeMsg::Records(records),
+        ) {
+            warn!(
+                NetworkSchema::new(&self.network_context).remote_peer(&recipient),
+                "{} Failed to send peer records: {:?}", self.network_context, error
+            );
+        } else {
+            inc_by_with_context(
+                &DISCOVERY_COUNTS,
+                &self.network_context,
+                "peer_exchange_sent_records",
+                num_records,
+            );
+        }
+    }
+
+    /// Verifies and stores the records received from the given peer
+    fn handle_records(&mut self, sender: PeerId, records: Vec<SignedPeerRecord>) {
+        let now_usecs = self.now_usecs();
+        let sender_network_id = PeerNetworkId::new(self.network_context.network_id(), sender);
+        for record in records
+            .into_iter()
+            .take(self.config.max_records_per_message)
+        {
+            let record_peer_id = record.peer_id();
+            let metric = match self
+                .records
+                .insert(sender, record, now_usecs, &mut self.rng)
+            {
+                RecordOutcome::Accepted => "peer_exchange_accepted",
+                RecordOutcome::Ignored(reason) => {
+                    trace!(
+                        NetworkSchema::new(&self.network_context).remote_peer(&sender),
+                        "{} Ignored record of peer {} ({})",
+                        self.network_context,
+                        record_peer_id,
+                        reason
+                    );
+                    "peer_exchange_ignored"
+                },
+                RecordOutcome::Invalid(reason) => {
+                    warn!(
+                        NetworkSchema::new(&self.network_context).remote_peer(&sender),
+                        "{} Received an invalid record of peer {} ({})",
+                        self.network_context,
+                        record_peer_id,
+                        reason
+                    );
+                    self.peers_and_metadata
+                        .report_peer_event(sender_network_id, PeerEvent::Malicious);
+                    "peer_exchange_invalid"
+                },
+            };
+            inc_by_with_context(&DISCOVERY_COUNTS, &self.network_context, metric, 1);
+        }
+    }
+
+    /// Returns the newly selected peers, if they differ from the previous selection
+    fn update_discovered_peers(&mut self) -> Option<PeerSet> {
+        let network_id = self.network_context.network_id();
+        let peers_and_metadata = self.peers_and_metadata.clone();
+        let discovered_peers = self.records.select_peers(
+            &self.discovered_peers,
+            |peer_id| peers_and_metadata.is_peer_banned(&PeerNetworkId::new(network_id, *peer_id)),
+            &mut self.rng,
+        );
+        if discovered_peers == self.discovered_peers {
+            None
+        } else {
+            self.discovered_peers = discovered_peers.clone();
+            Some(discovered_peers)
+        }
+    }
+}
+
+impl Stream for PeerExchangeStream {
+    type Item = Result<PeerSet, DiscoveryError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        // Handle all the records received so far
+        loop {
+            match self.network_events.poll_next_unpin(cx) {
+                Poll::Ready(Some(Event::Message(sender, PeerExchangeMsg::Records(records)))) => {
+                    self.handle_records(sender, records)
+                },
+                Poll::Ready(Some(_)) => {}, // Connection events and rpcs are ignored
+                Poll::Ready(None) => return Poll::Ready(None),
+                Poll::Pending => break,
+            }
+        }
+
+        // Exchange records with a random peer at each interval
+        while let Poll::Ready(Some(())) = self.interval.as_mut().poll_next(cx) {
+            let now_usecs = self.now_usecs();
+            self.records.remove_expired(now_usecs);
+            self.send_records();
+        }
+
+        match self.update_discovered_peers() {
+            Some(discovered_peers) => Poll::Ready(Some(Ok(discovered_peers))),
+            None => Poll::Pending,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_crypto::Uniform;
+    use rand::rngs::OsRng;
+    use std::str::FromStr;
+
+    const NOW_USECS: u64 = 1_000_000_000_000;
+
+    #[test]
+    fn test_record_verification() {
+        let (identity_key, peer_id) = create_identity();
+        let signing_key = derive_record_signing_key(&identity_key);
+
+        // Verify that a correctly signed record is valid
+        let record = create_record(&identity_key, peer_id, "1.2.3.4", NOW_USECS);
+        assert_eq!(record.verify(), Ok(()));
+
+        // Verify that a record with a tampered address is invalid
+        let mut tampered_record = record.clone();
+        tampered_record.record.addresses = vec![create_address(&identity_key, "5.6.7.8")];
+        assert_eq!(tampered_record.verify(), Err("invalid_signature"));
+
+        // Verify that a record for another peer id is invalid
+        let (_, other_peer_id) = create_identity();
+        let mismatched_record = SignedPeerRecord::sign(
+            PeerRecord {
+                peer_id: other_peer_id,
+                ..record.record.clone()
+            },
+            &signing_key,
+        );
+        assert_eq!(mismatched_record.verify(), Err("peer_id_mismatch"));
+
+        // Verify that a record with an unspecified address is invalid
+        let unspecified_record = create_record(&identity_key, peer_id, "0.0.0.0", NOW_USECS);
+        assert_eq!(unspecified_record.verify(), Err("invalid_address"));
+
+        // Verify that a record with a private address is invalid
+        let private_record = create_record(&identity_key, peer_id, "10.0.0.1", NOW_USECS);
+        assert_eq!(private_record.verify(), Err("invalid_address"));
+    }
+
+    #[test]
+    fn test_subnet_filtering() {
+        // Verify that addresses that can't be dialed from the public internet have no subnet
+        for address in [
+            "/ip4/127.0.0.1/tcp/6182",
+            "/ip4/10.1.2.3/tcp/6182",
+            "/ip4/172.16.0.1/tcp/6182",
+            "/ip4/192.168.1.1/tcp/6182",
+            "/ip4/169.254.1.1/tcp/6182",
+            "/ip4/192.0.2.1/tcp/6182",
+            "/ip4/255.255.255.255/tcp/6182",
+            "/ip6/::1/tcp/6182",
+            "/ip6/fd00::1/tcp/6182",
+            "/ip6/fe80::1/tcp/6182",
+            "/ip6/2001:db8::1/tcp/6182",
+            "/ip6/::ffff:10.0.0.1/tcp/6182",
+        ] {
+            let address = NetworkAddress::from_str(address).unwrap();
+            assert_eq!(subnet(&address), None, "{}", address);
+        }
+
+        // Verify the subnets of public addresses
+        let address = NetworkAddress::from_str("/ip4/1.2.3.4/tcp/6182").unwrap();
+        assert_eq!(subnet(&address), Some(Subnet::V4([1, 2, 3])));
+        let address = NetworkAddress::from_str("/ip6/2600:1f18:1::1/tcp/6182").unwrap();
+        assert_eq!(
+            subnet(&address),
+            Some(Subnet::V6([0x26, 0x00, 0x1f, 0x18, 0x00, 0x01]))
+        );
+        let address = NetworkAddress::from_str("/dns/example.com/tcp/6182").unwrap();
+        assert!(matches!(subnet(&address), Some(Subnet::Dns(_))));
+    }
+
+    #[test]
+    fn test_record_insertion() {
+        let mut peer_records = PeerRecords::new(PeerId::random(), PeerExchangeDiscovery::default());
+        let (identity_key, peer_id) = create_identity();
+        let relayer = PeerId::random();
+
+        // Insert a relayed record and verify it's accepted
+        let record = create_record(&identity_key, peer_id, "1.2.3.4", NOW_USECS);
+        assert_eq!(
+            peer_records.insert(relayer, record.clone(), NOW_USECS, &mut OsRng),
+            RecordOutcome::Accepted
+        );
+
+        // Verify that the same record is ignored as stale
+        assert_eq!(
+            peer_records.insert(relayer, record, NOW_USECS, &mut OsRng),
+            RecordOutcome::Ignored("stale")
+        );
+
+        // Verify that records from the future are invalid
+        let future_usecs = NOW_USECS + 2 * MAX_CLOCK_SKEW.as_micros() as u64;
+        let future_record = create_record(&identity_key, peer_id, "1.2.3.4", future_usecs);
+        assert_eq!(
+            peer_records.insert(relayer, future_record, NOW_USECS, &mut OsRng),
+            RecordOutcome::Invalid("future_timestamp")
+        );
+
+        // Verify that expired records are ignored
+        let (other_identity_key, other_peer_id) = create_identity();
+        let expired_record = create_record(&other_identity_key, other_peer_id, "2.3.4.5", 0);
+        assert_eq!(
+            peer_records.insert(relayer, expired_record, NOW_USECS, &mut OsRng),
+            RecordOutcome::Ignored("expired")
+        );
+
+        // Have the peer send its own record (pinning its key), and verify that relayed
+        // records signed by another key are ignored.
+        let own_record = create_record(&identity_key, peer_id, "1.2.3.4", NOW_USECS + 1);
+        assert_eq!(
+            peer_records.insert(peer_id, own_record.clone(), NOW_USECS, &mut OsRng),
+            RecordOutcome::Accepted
+        );
+        let forged_record = SignedPeerRecord::sign(
+            PeerRecord {
+                timestamp_usecs: NOW_USECS + 2,
+                ..own_record.record
+            },
+            &Ed25519PrivateKey::generate(&mut OsRng),
+        );
+        assert_eq!(
+            peer_records.insert(relayer, forged_record, NOW_USECS, &mut OsRng),
+            RecordOutcome::Ignored("unpinned_key")
+        );
+    }
+
+    #[test]
+    fn test_subnet_limits() {
+        let config = PeerExchangeDiscovery {
+            max_peers_per_subnet: 2,
+            ..Default::default()
+        };
+        let mut peer_records = PeerRecords::new(PeerId::random(), config);
+        let relayer = PeerId::random();
+
+        // Insert records for many peers in the same subnet, and verify only two are accepted
+        let num_accepted = (1..10)
+            .filter(|index| {
+                let (identity_key, peer_id) = create_identity();
+                let ip = format!("1.2.3.{}", index);
+                let record = create_record(&identity_key, peer_id, &ip, NOW_USECS);
+                peer_records.insert(relayer, record, NOW_USECS, &mut OsRng)
+                    == RecordOutcome::Accepted
+            })
+            .count();
+        assert_eq!(num_accepted, 2);
+
+        // Verify that records in another subnet are still accepted
+        let (identity_key, peer_id) = create_identity();
+        let record = create_record(&identity_key, peer_id, "1.2.4.1", NOW_USECS);
+        assert_eq!(
+            peer_records.insert(relayer, record, NOW_USECS, &mut OsRng),
+            RecordOutcome::Accepted
+        );
+    }
+
+    #[test]
+    fn test_peer_selection() {
+        let config = PeerExchangeDiscovery {
+            max_discovered_peers: 5,
+            ..Default::default()
+        };
+        let mut peer_records = PeerRecords::new(PeerId::random(), config);
+        let relayer = PeerId::random();
+
+        // Insert records for peers in different subnets
+        let mut peer_ids = vec![];
+        for index in 0..10 {
+            let (identity_key, peer_id) = create_identity();
+            let ip = format!("1.2.{}.1", index);
+            let record = create_record(&identity_key, peer_id, &ip, NOW_USECS);
+            peer_records.insert(relayer, record, NOW_USECS, &mut OsRng);
+            peer_ids.push(peer_id);
+        }
+
+        // Verify that the number of selected peers is capped, and that banned peers are excluded
+        let banned_peer = peer_ids[0];
+        let selected_peers = peer_records.select_peers(
+            &PeerSet::new(),
+            |peer_id| *peer_id == banned_peer,
+            &mut OsRng,
+        );
+        assert_eq!(selected_peers.len(), 5);
+        assert!(!selected_peers.contains_key(&banned_peer));
+
+        // Verify that the previously selected peers are kept
+        let reselected_peers = peer_records.select_peers(&selected_peers, |_| false, &mut OsRng);
+        assert_eq!(reselected_peers, selected_peers);
+
+        // Verify that the selected peers have no trusted role
+        for peer in selected_peers.values() {
+            assert_eq!(peer.role, PeerRole::Unknown);
+        }
+    }
+
+    /// Creates a random network identity (with a derived peer id)
+    fn create_identity() -> (x25519::PrivateKey, PeerId) {
+        let identity_key = x25519::PrivateKey::generate(&mut OsRng);
+        let peer_id = from_identity_public_key(identity_key.public_key());
+        (identity_key, peer_id)
+    }
+
+    /// Creates an address for the given identity and IP
+    fn create_address(identity_key: &x25519::PrivateKey, ip: &str) -> NetworkAddress {
+        NetworkAddress::from_str(&format!("/ip4/{}/tcp/6182", ip))
+            .unwrap()
+            .append_prod_protos(identity_key.public_key(), HANDSHAKE_VERSION)
+    }
+
+    /// Creates a record signed by the given identity
+    fn create_record(
+        identity_key: &x25519::PrivateKey,
+        peer_id: PeerId,
+        ip: &str,
+        timestamp_usecs: u64,
+    ) -> SignedPeerRecord {
+        let record = PeerRecord {
+            peer_id,
+            addresses: vec![create_address(identity_key, ip)],
+            timestamp_usecs,
+        };
+        SignedPeerRecord::sign(record, &derive_record_signing_key(identity_key))
+    }
+}
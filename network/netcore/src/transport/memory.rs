@@ -2,7 +2,7 @@
 // Parts of the project are originally copyright © Meta Platforms, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::transport::Transport;
+use crate::transport::{Substreams, Transport};
 use aptos_memsocket::{MemoryListener, MemorySocket};
 use aptos_types::{
     network_address::{parse_memory, NetworkAddress, Protocol},
@@ -99,6 +99,8 @@ impl Stream for Listener {
     }
 }
 
+impl Substreams for MemorySocket {}
+
 #[cfg(test)]
 mod test {
     use crate::transport::{memory::MemoryTransport, Transport};
@@ -22,6 +22,7 @@ pub mod boxed;
 #[cfg(any(test, feature = "testing", feature = "fuzzing"))]
 pub mod memory;
 pub mod proxy_protocol;
+pub mod quic;
 pub mod tcp;
 
 /// Origin of how a Connection was established.
@@ -115,6 +116,14 @@ pub trait Transport {
         Self: Sized;
 }
 
+/// Access to the additional streams multiplexed over the connection of a socket. Only the sockets
+/// of the QUIC transport have any, the others keep the default.
+pub trait Substreams {
+    fn substreams(&self) -> Option<quic::QuicStreams> {
+        None
+    }
+}
+
 impl<T: ?Sized> TransportExt for T where T: Transport {}
 
 /// An extension trait for [`Transport`]s that provides a variety of convenient
@@ -3,7 +3,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 //! TCP Transport
-use crate::transport::Transport;
+use crate::transport::{Substreams, Transport};
 use aptos_proxy::Proxy;
 use aptos_types::{
     network_address::{parse_dns_tcp, parse_ip_tcp, parse_tcp, IpFilter, NetworkAddress},
@@ -407,6 +407,8 @@ impl AsyncWrite for TcpSocket {
     }
 }
 
+impl Substreams for TcpSocket {}
+
 #[cfg(test)]
 mod test {
     use super::*;
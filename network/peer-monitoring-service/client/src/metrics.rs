@@ -43,6 +43,21 @@ pub static DISTANCE_FROM_VALIDATORS: Lazy<HistogramVec> = Lazy::new(|| {
     register_histogram_vec!(histogram_opts, &["network_id"]).unwrap()
 });
 
+// Histogram buckets for tracking the node sync lag (seconds)
+const NODE_SYNC_LAG_BUCKETS: &[f64] = &[
+    0.5, 1.0, 2.0, 5.0, 10.0, 20.0, 30.0, 60.0, 120.0, 300.0, 600.0, 1800.0, 3600.0,
+];
+
+/// Counter for tracking the node sync lag
+pub static NODE_SYNC_LAG: Lazy<HistogramVec> = Lazy::new(|| {
+    let histogram_opts = histogram_opts!(
+        "peer_monitoring_client_node_sync_lag",
+        "Counters related to the node sync lag (seconds)",
+        NODE_SYNC_LAG_BUCKETS.to_vec()
+    );
+    register_histogram_vec!(histogram_opts, &["network_id"]).unwrap()
+});
+
 // Histogram buckets for tracking the node uptime (hours)
 const NODE_UPTIME_BUCKETS: &[f64] = &[
     0.5, 1.0, 6.0, 12.0, 24.0, 48.0, 96.0, 192.0, 384.0, 768.0, 1536.0, 3072.0, 6144.0,
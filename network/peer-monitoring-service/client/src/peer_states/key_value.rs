@@ -3,7 +3,8 @@
 
 use crate::{
     peer_states::{
-        latency_info::LatencyInfoState, network_info::NetworkInfoState, node_info::NodeInfoState,
+        latency_info::LatencyInfoState, network_info::NetworkInfoState,
+        node_health_info::NodeHealthInfoState, node_info::NodeInfoState,
         request_tracker::RequestTracker,
     },
     Error,
@@ -30,6 +31,7 @@ use {
 pub enum PeerStateKey {
     LatencyInfo,
     NetworkInfo,
+    NodeHealthInfo,
     NodeInfo,
 
     #[cfg(feature = "network-perf-test")] // Disabled by default
@@ -42,6 +44,7 @@ impl PeerStateKey {
         vec![
             PeerStateKey::LatencyInfo,
             PeerStateKey::NetworkInfo,
+            PeerStateKey::NodeHealthInfo,
             PeerStateKey::NodeInfo,
             #[cfg(feature = "network-perf-test")] // Disabled by default
             PeerStateKey::PerformanceMonitoring,
@@ -53,6 +56,7 @@ impl PeerStateKey {
         match self {
             PeerStateKey::LatencyInfo => "latency_info",
             PeerStateKey::NetworkInfo => "network_info",
+            PeerStateKey::NodeHealthInfo => "node_health_info",
             PeerStateKey::NodeInfo => "node_info",
 
             #[cfg(feature = "network-perf-test")] // Disabled by default
@@ -71,6 +75,9 @@ impl PeerStateKey {
             PeerStateKey::NetworkInfo => {
                 PeerMonitoringServiceRequest::GetNetworkInformation.get_label()
             },
+            PeerStateKey::NodeHealthInfo => {
+                PeerMonitoringServiceRequest::GetNodeHealthInformation.get_label()
+            },
             PeerStateKey::NodeInfo => PeerMonitoringServiceRequest::GetNodeInformation.get_label(),
 
             #[cfg(feature = "network-perf-test")] // Disabled by default
@@ -127,6 +134,7 @@ pub trait StateValueInterface {
 pub enum PeerStateValue {
     LatencyInfoState,
     NetworkInfoState,
+    NodeHealthInfoState,
     NodeInfoState,
 
     #[cfg(feature = "network-perf-test")] // Disabled by default
@@ -146,6 +154,11 @@ impl PeerStateValue {
                 LatencyInfoState::new(latency_monitoring_config, time_service).into()
             },
             PeerStateKey::NetworkInfo => NetworkInfoState::new(node_config, time_service).into(),
+            PeerStateKey::NodeHealthInfo => {
+                let node_health_monitoring_config =
+                    node_config.peer_monitoring_service.node_health_monitoring;
+                NodeHealthInfoState::new(node_health_monitoring_config, time_service).into()
+            },
             PeerStateKey::NodeInfo => {
                 let node_monitoring_config = node_config.peer_monitoring_service.node_monitoring;
                 NodeInfoState::new(node_monitoring_config, time_service).into()
@@ -167,6 +180,9 @@ impl Display for PeerStateValue {
         match self {
             PeerStateValue::LatencyInfoState(state) => write!(f, "LatencyInfoState: {}", state),
             PeerStateValue::NetworkInfoState(state) => write!(f, "NetworkInfoState: {}", state),
+            PeerStateValue::NodeHealthInfoState(state) => {
+                write!(f, "NodeHealthInfoState: {}", state)
+            },
             PeerStateValue::NodeInfoState(state) => write!(f, "NodeInfoState: {}", state),
 
             #[cfg(feature = "network-perf-test")] // Disabled by default
@@ -17,6 +17,7 @@ use tokio::runtime::Handle;
 pub mod key_value;
 pub mod latency_info;
 pub mod network_info;
+pub mod node_health_info;
 pub mod node_info;
 pub mod peer_state;
 mod request_tracker;
@@ -0,0 +1,294 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    metrics,
+    peer_states::{key_value::StateValueInterface, request_tracker::RequestTracker},
+    Error, LogEntry, LogEvent, LogSchema,
+};
+use aptos_config::{config::NodeHealthMonitoringConfig, network_id::PeerNetworkId};
+use aptos_infallible::RwLock;
+use aptos_logger::warn;
+use aptos_network::application::metadata::PeerMetadata;
+use aptos_peer_monitoring_service_types::{
+    request::PeerMonitoringServiceRequest,
+    response::{NodeHealthInformationResponse, PeerMonitoringServiceResponse},
+};
+use aptos_time_service::TimeService;
+use std::{
+    fmt,
+    fmt::{Display, Formatter},
+    sync::Arc,
+};
+
+/// A simple container that holds a single peer's node health info
+#[derive(Clone, Debug)]
+pub struct NodeHealthInfoState {
+    node_health_monitoring_config: NodeHealthMonitoringConfig, // The config for node health monitoring
+    recorded_node_health_response: Option<NodeHealthInformationResponse>, // The last node health response
+    request_tracker: Arc<RwLock<RequestTracker>>, // The request tracker for node health requests
+}
+
+impl NodeHealthInfoState {
+    pub fn new(
+        node_health_monitoring_config: NodeHealthMonitoringConfig,
+        time_service: TimeService,
+    ) -> Self {
+        let request_tracker = RequestTracker::new(
+            node_health_monitoring_config.node_health_request_interval_ms,
+            time_service,
+        );
+
+        Self {
+            node_health_monitoring_config,
+            recorded_node_health_response: None,
+            request_tracker: Arc::new(RwLock::new(request_tracker)),
+        }
+    }
+
+    /// Records the new node health response for the peer
+    pub fn record_node_health_response(
+        &mut self,
+        node_health_response: NodeHealthInformationResponse,
+    ) {
+        // Update the request tracker with a successful response
+        self.request_tracker.write().record_response_success();
+
+        // Save the node health info
+        self.recorded_node_health_response = Some(node_health_response);
+    }
+
+    /// Handles a request failure for the specified peer
+    fn handle_request_failure(&self) {
+        self.request_tracker.write().record_response_failure();
+    }
+
+    /// Returns the latest node health response
+    pub fn get_latest_node_health_response(&self) -> Option<NodeHealthInformationResponse> {
+        self.recorded_node_health_response.clone()
+    }
+}
+
+impl StateValueInterface for NodeHealthInfoState {
+    fn create_monitoring_service_request(&mut self) -> PeerMonitoringServiceRequest {
+        PeerMonitoringServiceRequest::GetNodeHealthInformation
+    }
+
+    fn get_request_timeout_ms(&self) -> u64 {
+        self.node_health_monitoring_config
+            .node_health_request_timeout_ms
+    }
+
+    fn get_request_tracker(&self) -> Arc<RwLock<RequestTracker>> {
+        self.request_tracker.clone()
+    }
+
+    fn handle_monitoring_service_response(
+        &mut self,
+        peer_network_id: &PeerNetworkId,
+        _peer_metadata: PeerMetadata,
+        _monitoring_service_request: PeerMonitoringServiceRequest,
+        monitoring_service_response: PeerMonitoringServiceResponse,
+        _response_time_secs: f64,
+    ) {
+        // Verify the response type is valid
+        let node_health_response = match monitoring_service_response {
+            PeerMonitoringServiceResponse::NodeHealthInformation(node_health_response) => {
+                node_health_response
+            },
+            _ => {
+                warn!(LogSchema::new(LogEntry::NodeHealthInfoRequest)
+                    .event(LogEvent::ResponseError)
+                    .peer(peer_network_id)
+                    .message(
+                        "An unexpected response was received instead of a node health response!"
+                    ));
+                self.handle_request_failure();
+                return;
+            },
+        };
+
+        // Store the new node health response
+        self.record_node_health_response(node_health_response);
+    }
+
+    fn handle_monitoring_service_response_error(
+        &mut self,
+        peer_network_id: &PeerNetworkId,
+        error: Error,
+    ) {
+        // Handle the failure
+        self.handle_request_failure();
+
+        // Log the error
+        warn!(LogSchema::new(LogEntry::NodeHealthInfoRequest)
+            .event(LogEvent::ResponseError)
+            .message("Error encountered when requesting node health information from the peer!")
+            .peer(peer_network_id)
+            .error(&error));
+    }
+
+    fn update_peer_state_metrics(&self, peer_network_id: &PeerNetworkId) {
+        if let Some(node_health_response) = self.get_latest_node_health_response() {
+            // Update the sync lag metric
+            let sync_lag_secs = node_health_response.state_sync_lag_usecs as f64 / 1_000_000.0;
+            metrics::observe_value(&metrics::NODE_SYNC_LAG, peer_network_id, sync_lag_secs);
+        }
+    }
+}
+
+impl Display for NodeHealthInfoState {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "NodeHealthInfoState {{ recorded_node_health_response: {:?} }}",
+            self.recorded_node_health_response
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::peer_states::{
+        key_value::StateValueInterface, node_health_info::NodeHealthInfoState,
+    };
+    use aptos_config::{
+        config::{NodeHealthMonitoringConfig, PeerRole},
+        network_id::PeerNetworkId,
+    };
+    use aptos_netcore::transport::ConnectionOrigin;
+    use aptos_network::{
+        application::metadata::PeerMetadata,
+        protocols::wire::handshake::v1::{MessagingProtocolVersion, ProtocolIdSet},
+        transport::{ConnectionId, ConnectionMetadata},
+    };
+    use aptos_peer_monitoring_service_types::{
+        request::PeerMonitoringServiceRequest,
+        response::{
+            MempoolHealthSummary, NodeHealthInformationResponse, NodeInformationResponse,
+            PeerMonitoringServiceResponse,
+        },
+    };
+    use aptos_time_service::TimeService;
+    use aptos_types::network_address::NetworkAddress;
+    use std::{str::FromStr, time::Duration};
+
+    // Useful test constants
+    const TEST_NETWORK_ADDRESS: &str = "/ip4/127.0.0.1/tcp/8081";
+
+    #[test]
+    fn test_verify_node_health_info_state() {
+        // Create the node health info state
+        let node_health_monitoring_config = NodeHealthMonitoringConfig::default();
+        let time_service = TimeService::mock();
+        let mut node_health_info_state =
+            NodeHealthInfoState::new(node_health_monitoring_config, time_service);
+
+        // Verify the initial node health info state
+        verify_empty_node_health_response(&node_health_info_state);
+
+        // Handle several valid node health responses and verify the state
+        for i in 0..10 {
+            // Create the service response
+            let node_health_response = NodeHealthInformationResponse {
+                consensus_epoch: i,
+                consensus_round: (i + 1) * 10,
+                ledger_prune_window: Some((i + 1) * 1000),
+                mempool_summary: MempoolHealthSummary {
+                    num_transactions: i * 50,
+                    oldest_transaction_age_ms: i * 100,
+                },
+                state_prune_window: None,
+                state_sync_lag_usecs: i * 1_000_000,
+            };
+
+            // Handle the node health response
+            handle_monitoring_service_response(
+                &mut node_health_info_state,
+                PeerMonitoringServiceResponse::NodeHealthInformation(node_health_response.clone()),
+            );
+
+            // Verify the latest node health info state
+            verify_node_health_info_state(&node_health_info_state, node_health_response);
+        }
+    }
+
+    #[test]
+    fn test_unexpected_node_health_response() {
+        // Create the node health info state
+        let node_health_monitoring_config = NodeHealthMonitoringConfig::default();
+        let time_service = TimeService::mock();
+        let mut node_health_info_state =
+            NodeHealthInfoState::new(node_health_monitoring_config, time_service);
+
+        // Handle an unexpected (node info) response
+        let node_information_response = NodeInformationResponse {
+            build_information: aptos_build_info::get_build_information(),
+            highest_synced_epoch: 0,
+            highest_synced_version: 0,
+            ledger_timestamp_usecs: 0,
+            lowest_available_version: 0,
+            uptime: Duration::from_secs(0),
+        };
+        handle_monitoring_service_response(
+            &mut node_health_info_state,
+            PeerMonitoringServiceResponse::NodeInformation(node_information_response),
+        );
+
+        // Verify the node health info state is still empty and the failure was recorded
+        verify_empty_node_health_response(&node_health_info_state);
+        assert_eq!(
+            node_health_info_state
+                .get_request_tracker()
+                .read()
+                .get_num_consecutive_failures(),
+            1
+        );
+    }
+
+    /// Handles a monitoring service response from a peer
+    fn handle_monitoring_service_response(
+        node_health_info_state: &mut NodeHealthInfoState,
+        peer_monitoring_service_response: PeerMonitoringServiceResponse,
+    ) {
+        // Create a new peer metadata entry
+        let peer_network_id = PeerNetworkId::random();
+        let connection_metadata = ConnectionMetadata::new(
+            peer_network_id.peer_id(),
+            ConnectionId::default(),
+            NetworkAddress::from_str(TEST_NETWORK_ADDRESS).unwrap(),
+            ConnectionOrigin::Outbound,
+            MessagingProtocolVersion::V1,
+            ProtocolIdSet::empty(),
+            PeerRole::Validator,
+        );
+        let peer_metadata = PeerMetadata::new(connection_metadata);
+
+        // Handle the response
+        node_health_info_state.handle_monitoring_service_response(
+            &peer_network_id,
+            peer_metadata,
+            PeerMonitoringServiceRequest::GetNodeHealthInformation,
+            peer_monitoring_service_response,
+            0.0,
+        );
+    }
+
+    /// Verifies that there is no latest node health response stored
+    fn verify_empty_node_health_response(node_health_info_state: &NodeHealthInfoState) {
+        assert!(node_health_info_state
+            .get_latest_node_health_response()
+            .is_none());
+    }
+
+    /// Verifies that the latest node health response is valid
+    fn verify_node_health_info_state(
+        node_health_info_state: &NodeHealthInfoState,
+        expected_node_health_response: NodeHealthInformationResponse,
+    ) {
+        let latest_node_health_response = node_health_info_state
+            .get_latest_node_health_response()
+            .unwrap();
+        assert_eq!(latest_node_health_response, expected_node_health_response);
+    }
+}
@@ -7,6 +7,7 @@ use crate::{
         key_value::{PeerStateKey, PeerStateValue, StateValueInterface},
         latency_info::LatencyInfoState,
         network_info::NetworkInfoState,
+        node_health_info::NodeHealthInfoState,
         node_info::NodeInfoState,
         request_tracker::RequestTracker,
     },
@@ -207,6 +208,11 @@ impl PeerState {
         let node_info_response = node_info_state.get_latest_node_info_response();
         peer_monitoring_metadata.latest_node_info_response = node_info_response;
 
+        // Get and store the latest node health response
+        let node_health_info_state = self.get_node_health_info_state()?;
+        let node_health_response = node_health_info_state.get_latest_node_health_response();
+        peer_monitoring_metadata.latest_node_health_response = node_health_response;
+
         Ok(peer_monitoring_metadata)
     }
 
@@ -254,6 +260,23 @@ impl PeerState {
         }
     }
 
+    /// Returns a copy of the node health info state
+    pub(crate) fn get_node_health_info_state(&self) -> Result<NodeHealthInfoState, Error> {
+        let peer_state_value = self
+            .get_peer_state_value(&PeerStateKey::NodeHealthInfo)?
+            .read()
+            .clone();
+        match peer_state_value {
+            PeerStateValue::NodeHealthInfoState(node_health_info_state) => {
+                Ok(node_health_info_state)
+            },
+            peer_state_value => Err(Error::UnexpectedError(format!(
+                "Invalid peer state value found! Expected node_health_info_state but got: {:?}",
+                peer_state_value
+            ))),
+        }
+    }
+
     /// Returns a copy of the node info state
     pub(crate) fn get_node_info_state(&self) -> Result<NodeInfoState, Error> {
         let peer_state_value = self
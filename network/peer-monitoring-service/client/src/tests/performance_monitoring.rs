@@ -7,9 +7,9 @@ use crate::{
         mock::MockMonitoringServer,
         utils::{
             disabled_latency_monitoring_config, disabled_network_monitoring_config,
-            disabled_node_monitoring_config, initialize_and_verify_peer_states, spawn_with_timeout,
-            start_peer_monitor, verify_empty_peer_states, wait_for_peer_state_update,
-            wait_for_request_failure,
+            disabled_node_health_monitoring_config, disabled_node_monitoring_config,
+            initialize_and_verify_peer_states, spawn_with_timeout, start_peer_monitor,
+            verify_empty_peer_states, wait_for_peer_state_update, wait_for_request_failure,
         },
     },
     PeerMonitorState,
@@ -297,6 +297,7 @@ fn config_with_performance_requests() -> NodeConfig {
         peer_monitoring_service: PeerMonitoringServiceConfig {
             latency_monitoring: disabled_latency_monitoring_config(),
             network_monitoring: disabled_network_monitoring_config(),
+            node_health_monitoring: disabled_node_health_monitoring_config(),
             node_monitoring: disabled_node_monitoring_config(),
             performance_monitoring: PerformanceMonitoringConfig {
                 direct_send_interval_usec: 10_000_000, // 10 seconds
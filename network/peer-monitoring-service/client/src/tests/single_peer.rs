@@ -7,21 +7,23 @@ use crate::{
         mock::MockMonitoringServer,
         utils::{
             config_with_latency_ping_requests, config_with_network_info_requests,
-            config_with_node_info_requests, config_with_only_latency_and_network_requests,
-            create_connected_peers_map, create_network_info_response,
-            create_random_network_info_response, create_random_node_info_response,
+            config_with_node_health_info_requests, config_with_node_info_requests,
+            config_with_only_latency_and_network_requests, create_connected_peers_map,
+            create_network_info_response, create_random_network_info_response,
+            create_random_node_health_response, create_random_node_info_response,
             elapse_latency_update_interval, elapse_metadata_updater_interval,
             elapse_network_info_update_interval, elapse_node_info_update_interval,
             initialize_and_verify_peer_states, start_peer_metadata_updater, start_peer_monitor,
             update_latency_info_for_peer, update_network_info_for_peer,
             verify_all_requests_and_respond, verify_and_handle_latency_ping,
-            verify_and_handle_network_info_request, verify_and_handle_node_info_request,
-            verify_empty_peer_states, verify_latency_request_and_respond,
-            verify_network_info_request_and_respond, verify_node_info_request_and_respond,
-            verify_peer_latency_state, verify_peer_network_state, verify_peer_node_state,
-            wait_for_latency_ping_failure, wait_for_monitoring_latency_update,
-            wait_for_monitoring_network_update, wait_for_network_info_request_failure,
-            wait_for_node_info_request_failure, wait_for_peer_state_update,
+            verify_and_handle_network_info_request, verify_and_handle_node_health_info_request,
+            verify_and_handle_node_info_request, verify_empty_peer_states,
+            verify_latency_request_and_respond, verify_network_info_request_and_respond,
+            verify_node_info_request_and_respond, verify_peer_latency_state,
+            verify_peer_network_state, verify_peer_node_state, wait_for_latency_ping_failure,
+            wait_for_monitoring_latency_update, wait_for_monitoring_network_update,
+            wait_for_network_info_request_failure, wait_for_node_info_request_failure,
+            wait_for_peer_state_update,
         },
     },
     PeerState,
@@ -657,6 +659,55 @@ async fn test_network_info_request_failures() {
     );
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn test_node_health_info_requests() {
+    // Create the peer monitoring client and server
+    let network_id = NetworkId::Public;
+    let (peer_monitoring_client, mut mock_monitoring_server, peer_monitor_state, time_service) =
+        MockMonitoringServer::new(vec![network_id]);
+
+    // Create a node config where only node health infos refresh
+    let node_config = config_with_node_health_info_requests();
+
+    // Spawn the peer monitoring client
+    start_peer_monitor(
+        peer_monitoring_client,
+        &peer_monitor_state,
+        &time_service,
+        &node_config,
+    )
+    .await;
+
+    // Add a connected fullnode peer
+    let fullnode_peer = mock_monitoring_server.add_new_peer(network_id, PeerRole::Unknown);
+
+    // Initialize all the peer states by running the peer monitor once
+    let mock_time = time_service.into_mock();
+    let _ = initialize_and_verify_peer_states(
+        &network_id,
+        &mut mock_monitoring_server,
+        &peer_monitor_state,
+        &node_config,
+        &fullnode_peer,
+        &mock_time,
+    )
+    .await;
+
+    // Handle many node health info requests and responses
+    for _ in 0..20 {
+        verify_and_handle_node_health_info_request(
+            &network_id,
+            &mut mock_monitoring_server,
+            &peer_monitor_state,
+            &node_config,
+            &fullnode_peer,
+            &mock_time,
+            create_random_node_health_response(),
+        )
+        .await;
+    }
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn test_node_info_requests() {
     // Create the peer monitoring client and server
@@ -9,8 +9,8 @@ use crate::{
 };
 use aptos_config::{
     config::{
-        LatencyMonitoringConfig, NetworkMonitoringConfig, NodeConfig, NodeMonitoringConfig,
-        PeerMonitoringServiceConfig, PeerRole, PerformanceMonitoringConfig,
+        LatencyMonitoringConfig, NetworkMonitoringConfig, NodeConfig, NodeHealthMonitoringConfig,
+        NodeMonitoringConfig, PeerMonitoringServiceConfig, PeerRole, PerformanceMonitoringConfig,
     },
     network_id::{NetworkId, PeerNetworkId},
 };
@@ -18,8 +18,9 @@ use aptos_network::application::{interface::NetworkClient, storage::PeersAndMeta
 use aptos_peer_monitoring_service_types::{
     request::{LatencyPingRequest, PeerMonitoringServiceRequest},
     response::{
-        ConnectionMetadata, LatencyPingResponse, NetworkInformationResponse,
-        NodeInformationResponse, PeerMonitoringServiceResponse, ServerProtocolVersionResponse,
+        ConnectionMetadata, LatencyPingResponse, MempoolHealthSummary, NetworkInformationResponse,
+        NodeHealthInformationResponse, NodeInformationResponse, PeerMonitoringServiceResponse,
+        ServerProtocolVersionResponse,
     },
     PeerMonitoringServiceMessage,
 };
@@ -49,6 +50,7 @@ pub fn config_with_latency_ping_requests() -> NodeConfig {
     NodeConfig {
         peer_monitoring_service: PeerMonitoringServiceConfig {
             network_monitoring: disabled_network_monitoring_config(),
+            node_health_monitoring: disabled_node_health_monitoring_config(),
             node_monitoring: disabled_node_monitoring_config(),
             performance_monitoring: disabled_performance_monitoring_config(),
             ..Default::default()
@@ -62,6 +64,7 @@ pub fn config_with_network_info_requests() -> NodeConfig {
     NodeConfig {
         peer_monitoring_service: PeerMonitoringServiceConfig {
             latency_monitoring: disabled_latency_monitoring_config(),
+            node_health_monitoring: disabled_node_health_monitoring_config(),
             node_monitoring: disabled_node_monitoring_config(),
             performance_monitoring: disabled_performance_monitoring_config(),
             ..Default::default()
@@ -76,6 +79,21 @@ pub fn config_with_node_info_requests() -> NodeConfig {
         peer_monitoring_service: PeerMonitoringServiceConfig {
             latency_monitoring: disabled_latency_monitoring_config(),
             network_monitoring: disabled_network_monitoring_config(),
+            node_health_monitoring: disabled_node_health_monitoring_config(),
+            performance_monitoring: disabled_performance_monitoring_config(),
+            ..Default::default()
+        },
+        ..Default::default()
+    }
+}
+
+/// Returns a config where only node health infos are refreshed
+pub fn config_with_node_health_info_requests() -> NodeConfig {
+    NodeConfig {
+        peer_monitoring_service: PeerMonitoringServiceConfig {
+            latency_monitoring: disabled_latency_monitoring_config(),
+            network_monitoring: disabled_network_monitoring_config(),
+            node_monitoring: disabled_node_monitoring_config(),
             performance_monitoring: disabled_performance_monitoring_config(),
             ..Default::default()
         },
@@ -87,6 +105,7 @@ pub fn config_with_node_info_requests() -> NodeConfig {
 pub fn config_with_only_latency_and_network_requests() -> NodeConfig {
     NodeConfig {
         peer_monitoring_service: PeerMonitoringServiceConfig {
+            node_health_monitoring: disabled_node_health_monitoring_config(),
             node_monitoring: disabled_node_monitoring_config(),
             performance_monitoring: disabled_performance_monitoring_config(),
             ..Default::default()
@@ -132,6 +151,23 @@ pub fn create_network_info_response(
     }
 }
 
+/// Creates a node health response with the given data
+pub fn create_node_health_response(
+    consensus_epoch: u64,
+    consensus_round: u64,
+    mempool_summary: MempoolHealthSummary,
+    state_sync_lag_usecs: u64,
+) -> NodeHealthInformationResponse {
+    NodeHealthInformationResponse {
+        consensus_epoch,
+        consensus_round,
+        ledger_prune_window: None,
+        mempool_summary,
+        state_prune_window: None,
+        state_sync_lag_usecs,
+    }
+}
+
 /// Creates a node info response with the given data
 pub fn create_node_info_response(
     build_information: BTreeMap<String, String>,
@@ -167,6 +203,14 @@ pub fn disabled_network_monitoring_config() -> NetworkMonitoringConfig {
     }
 }
 
+/// Returns a node health monitoring config where node health infos are disabled
+pub fn disabled_node_health_monitoring_config() -> NodeHealthMonitoringConfig {
+    NodeHealthMonitoringConfig {
+        node_health_request_interval_ms: UNREALISTIC_INTERVAL_MS,
+        ..Default::default()
+    }
+}
+
 /// Returns a node monitoring config where node infos are disabled
 pub fn disabled_node_monitoring_config() -> NodeMonitoringConfig {
     NodeMonitoringConfig {
@@ -211,6 +255,17 @@ pub async fn elapse_network_info_update_interval(
         .await;
 }
 
+/// Elapses enough time for a node health info update to occur
+pub async fn elapse_node_health_info_update_interval(
+    node_config: NodeConfig,
+    mock_time: MockTimeService,
+) {
+    let node_health_monitoring_config = node_config.peer_monitoring_service.node_health_monitoring;
+    mock_time
+        .advance_ms_async(node_health_monitoring_config.node_health_request_interval_ms + 1)
+        .await;
+}
+
 /// Elapses enough time for a node info update to occur
 pub async fn elapse_node_info_update_interval(node_config: NodeConfig, mock_time: MockTimeService) {
     let node_monitoring_config = node_config.peer_monitoring_service.node_monitoring;
@@ -331,6 +386,26 @@ pub fn create_random_network_info_response() -> NetworkInformationResponse {
     create_network_info_response(&connected_peers, distance_from_validators)
 }
 
+/// Creates a new node health response with random values
+pub fn create_random_node_health_response() -> NodeHealthInformationResponse {
+    // Create the random values
+    let consensus_epoch = get_random_u64();
+    let consensus_round = get_random_u64();
+    let mempool_summary = MempoolHealthSummary {
+        num_transactions: get_random_u64(),
+        oldest_transaction_age_ms: get_random_u64(),
+    };
+    let state_sync_lag_usecs = get_random_u64();
+
+    // Create and return the node health response
+    create_node_health_response(
+        consensus_epoch,
+        consensus_round,
+        mempool_summary,
+        state_sync_lag_usecs,
+    )
+}
+
 /// Creates a new network info response with random values
 pub fn create_random_node_info_response() -> NodeInformationResponse {
     // Create the random values
@@ -566,6 +641,41 @@ pub async fn verify_and_handle_network_info_request(
     );
 }
 
+/// Elapses enough time for a node health info request and handles the response
+pub async fn verify_and_handle_node_health_info_request(
+    network_id: &NetworkId,
+    mock_monitoring_server: &mut MockMonitoringServer,
+    peer_monitor_state: &PeerMonitorState,
+    node_config: &NodeConfig,
+    peer_network_id: &PeerNetworkId,
+    mock_time: &MockTimeService,
+    node_health_response: NodeHealthInformationResponse,
+) {
+    // Elapse enough time for a node health info update
+    let time_before_update = mock_time.now();
+    elapse_node_health_info_update_interval(node_config.clone(), mock_time.clone()).await;
+
+    // Verify that a single node health info request is received and respond
+    verify_node_health_info_request_and_respond(
+        network_id,
+        mock_monitoring_server,
+        node_health_response.clone(),
+    )
+    .await;
+
+    // Wait until the node health info state is updated by the client
+    wait_for_peer_state_update(
+        time_before_update,
+        peer_monitor_state,
+        peer_network_id,
+        vec![PeerStateKey::NodeHealthInfo],
+    )
+    .await;
+
+    // Verify the node health info state
+    verify_peer_node_health_state(peer_monitor_state, peer_network_id, node_health_response);
+}
+
 /// Elapses enough time for a node info request and handles the response
 pub async fn verify_and_handle_node_info_request(
     network_id: &NetworkId,
@@ -644,6 +754,11 @@ pub async fn verify_all_requests_and_respond(
                         network_information_response.clone().unwrap(),
                     )
                 },
+                PeerMonitoringServiceRequest::GetNodeHealthInformation => {
+                    PeerMonitoringServiceResponse::NodeHealthInformation(
+                        create_random_node_health_response(),
+                    )
+                },
                 PeerMonitoringServiceRequest::GetNodeInformation => {
                     PeerMonitoringServiceResponse::NodeInformation(
                         node_information_response.clone().unwrap(),
@@ -799,6 +914,39 @@ pub async fn verify_network_info_request_and_respond(
     .await;
 }
 
+/// Verifies that a node health info request is received by
+/// the server and responds with the given response.
+pub async fn verify_node_health_info_request_and_respond(
+    network_id: &NetworkId,
+    mock_monitoring_server: &mut MockMonitoringServer,
+    node_health_response: NodeHealthInformationResponse,
+) {
+    // Create a task that waits for the request and sends a response
+    let handle_request = async move {
+        // Process the node health info request
+        let network_request = mock_monitoring_server
+            .next_request(network_id)
+            .await
+            .unwrap();
+        let response = match network_request.peer_monitoring_service_request {
+            PeerMonitoringServiceRequest::GetNodeHealthInformation => {
+                PeerMonitoringServiceResponse::NodeHealthInformation(node_health_response)
+            },
+            request => panic!("Unexpected monitoring request received: {:?}", request),
+        };
+
+        // Send the response
+        network_request.response_sender.send(Ok(response));
+    };
+
+    // Spawn the task with a timeout
+    spawn_with_timeout(
+        handle_request,
+        "Timed-out while waiting for a node health info request",
+    )
+    .await;
+}
+
 /// Verifies that a node info request is received by the
 /// server and sends a response based on the given arguments.
 pub async fn verify_node_info_request_and_respond(
@@ -964,6 +1112,31 @@ pub fn verify_peer_node_state(
     );
 }
 
+/// Verifies the node health state of the peer monitor
+pub fn verify_peer_node_health_state(
+    peer_monitor_state: &PeerMonitorState,
+    peer_network_id: &PeerNetworkId,
+    expected_node_health_response: NodeHealthInformationResponse,
+) {
+    // Fetch the peer monitoring metadata
+    let peer_states = peer_monitor_state.peer_states.read();
+    let peer_state = peer_states.get(peer_network_id).unwrap();
+
+    // Verify the latest node health state
+    let node_health_info_state = peer_state.get_node_health_info_state().unwrap();
+    let latest_node_health_response = node_health_info_state
+        .get_latest_node_health_response()
+        .unwrap();
+    assert_eq!(latest_node_health_response, expected_node_health_response);
+
+    // Verify the peer monitoring metadata contains the latest node health response
+    let peer_monitoring_metadata = peer_state.extract_peer_monitoring_metadata().unwrap();
+    assert_eq!(
+        peer_monitoring_metadata.latest_node_health_response,
+        Some(expected_node_health_response)
+    );
+}
+
 /// Waits for the peer monitor state to be updated with
 /// a latency ping failure.
 pub async fn wait_for_latency_ping_failure(
@@ -14,13 +14,15 @@ use aptos_config::{
     config::{BaseConfig, NodeConfig},
     network_id::NetworkId,
 };
+use aptos_infallible::RwLock;
 use aptos_logger::prelude::*;
 use aptos_network::application::storage::PeersAndMetadata;
 use aptos_peer_monitoring_service_types::{
     request::{LatencyPingRequest, PeerMonitoringServiceRequest},
     response::{
-        ConnectionMetadata, LatencyPingResponse, NetworkInformationResponse,
-        NodeInformationResponse, PeerMonitoringServiceResponse, ServerProtocolVersionResponse,
+        ConnectionMetadata, LatencyPingResponse, MempoolHealthSummary, NetworkInformationResponse,
+        NodeHealthInformationResponse, NodeInformationResponse, PeerMonitoringServiceResponse,
+        ServerProtocolVersionResponse,
     },
     PeerMonitoringServiceError, Result, MAX_DISTANCE_FROM_VALIDATORS,
 };
@@ -46,6 +48,7 @@ pub const PEER_MONITORING_SERVER_VERSION: u64 = 1;
 pub struct PeerMonitoringServiceServer<T> {
     base_config: BaseConfig,
     bounded_executor: BoundedExecutor,
+    mempool_health_summary: Arc<RwLock<MempoolHealthSummary>>,
     network_requests: PeerMonitoringServiceNetworkEvents,
     peers_and_metadata: Arc<PeersAndMetadata>,
     start_time: Instant,
@@ -57,6 +60,7 @@ impl<T: StorageReaderInterface> PeerMonitoringServiceServer<T> {
     pub fn new(
         node_config: NodeConfig,
         executor: Handle,
+        mempool_health_summary: Arc<RwLock<MempoolHealthSummary>>,
         network_requests: PeerMonitoringServiceNetworkEvents,
         peers_and_metadata: Arc<PeersAndMetadata>,
         storage: T,
@@ -72,6 +76,7 @@ impl<T: StorageReaderInterface> PeerMonitoringServiceServer<T> {
         Self {
             base_config,
             bounded_executor,
+            mempool_health_summary,
             network_requests,
             peers_and_metadata,
             start_time,
@@ -98,6 +103,7 @@ impl<T: StorageReaderInterface> PeerMonitoringServiceServer<T> {
             // All handler methods are currently CPU-bound so we want
             // to spawn on the blocking thread pool.
             let base_config = self.base_config.clone();
+            let mempool_health_summary = self.mempool_health_summary.clone();
             let peers_and_metadata = self.peers_and_metadata.clone();
             let start_time = self.start_time;
             let storage = self.storage.clone();
@@ -106,6 +112,7 @@ impl<T: StorageReaderInterface> PeerMonitoringServiceServer<T> {
                 .spawn_blocking(move || {
                     let response = Handler::new(
                         base_config,
+                        mempool_health_summary,
                         peers_and_metadata,
                         start_time,
                         storage,
@@ -129,6 +136,7 @@ impl<T: StorageReaderInterface> PeerMonitoringServiceServer<T> {
 #[derive(Clone)]
 pub struct Handler<T> {
     base_config: BaseConfig,
+    mempool_health_summary: Arc<RwLock<MempoolHealthSummary>>,
     peers_and_metadata: Arc<PeersAndMetadata>,
     start_time: Instant,
     storage: T,
@@ -138,6 +146,7 @@ pub struct Handler<T> {
 impl<T: StorageReaderInterface> Handler<T> {
     pub fn new(
         base_config: BaseConfig,
+        mempool_health_summary: Arc<RwLock<MempoolHealthSummary>>,
         peers_and_metadata: Arc<PeersAndMetadata>,
         start_time: Instant,
         storage: T,
@@ -145,6 +154,7 @@ impl<T: StorageReaderInterface> Handler<T> {
     ) -> Self {
         Self {
             base_config,
+            mempool_health_summary,
             peers_and_metadata,
             start_time,
             storage,
@@ -178,6 +188,9 @@ impl<T: StorageReaderInterface> Handler<T> {
                 self.get_server_protocol_version()
             },
             PeerMonitoringServiceRequest::GetNodeInformation => self.get_node_information(),
+            PeerMonitoringServiceRequest::GetNodeHealthInformation => {
+                self.get_node_health_information()
+            },
             PeerMonitoringServiceRequest::LatencyPing(request) => self.handle_latency_ping(request),
 
             #[cfg(feature = "network-perf-test")] // Disabled by default
@@ -285,6 +298,35 @@ impl<T: StorageReaderInterface> Handler<T> {
         ))
     }
 
+    fn get_node_health_information(&self) -> Result<PeerMonitoringServiceResponse, Error> {
+        // Get the consensus and mempool information
+        let (consensus_epoch, consensus_round) =
+            self.storage.get_latest_committed_epoch_and_round()?;
+        let mempool_summary = *self.mempool_health_summary.read();
+
+        // Calculate the sync lag (i.e., how far the latest ledger timestamp is behind our clock)
+        let ledger_timestamp_usecs = self.storage.get_ledger_timestamp_usecs()?;
+        let current_time_usecs = self.time_service.now_unix_time().as_micros() as u64;
+        let state_sync_lag_usecs = current_time_usecs.saturating_sub(ledger_timestamp_usecs);
+
+        // Get the storage pruning windows
+        let ledger_prune_window = self.storage.get_ledger_prune_window()?;
+        let state_prune_window = self.storage.get_state_prune_window()?;
+
+        // Create and return the response
+        let node_health_information_response = NodeHealthInformationResponse {
+            consensus_epoch,
+            consensus_round,
+            ledger_prune_window,
+            mempool_summary,
+            state_prune_window,
+            state_sync_lag_usecs,
+        };
+        Ok(PeerMonitoringServiceResponse::NodeHealthInformation(
+            node_health_information_response,
+        ))
+    }
+
     fn handle_latency_ping(
         &self,
         latency_ping_request: &LatencyPingRequest,
@@ -12,11 +12,20 @@ pub trait StorageReaderInterface: Clone + Send + 'static {
     /// Returns the highest synced epoch and version
     fn get_highest_synced_epoch_and_version(&self) -> Result<(u64, u64), Error>;
 
+    /// Returns the epoch and round of the latest committed block
+    fn get_latest_committed_epoch_and_round(&self) -> Result<(u64, u64), Error>;
+
     /// Returns the ledger timestamp of the blockchain in microseconds
     fn get_ledger_timestamp_usecs(&self) -> Result<u64, Error>;
 
     /// Returns the lowest available version in storage
     fn get_lowest_available_version(&self) -> Result<u64, Error>;
+
+    /// Returns the ledger prune window (or None, if the ledger pruner is disabled)
+    fn get_ledger_prune_window(&self) -> Result<Option<u64>, Error>;
+
+    /// Returns the state snapshot prune window (or None, if the state pruner is disabled)
+    fn get_state_prune_window(&self) -> Result<Option<u64>, Error>;
 }
 
 /// The underlying implementation of the StorageReaderInterface, used by the
@@ -47,6 +56,11 @@ impl StorageReaderInterface for StorageReader {
         Ok((latest_ledger_info.epoch(), latest_ledger_info.version()))
     }
 
+    fn get_latest_committed_epoch_and_round(&self) -> Result<(u64, u64), Error> {
+        let latest_ledger_info = self.get_latest_ledger_info()?;
+        Ok((latest_ledger_info.epoch(), latest_ledger_info.round()))
+    }
+
     fn get_ledger_timestamp_usecs(&self) -> Result<u64, Error> {
         let latest_ledger_info = self.get_latest_ledger_info()?;
         Ok(latest_ledger_info.timestamp_usecs())
@@ -61,4 +75,36 @@ impl StorageReaderInterface for StorageReader {
             Error::StorageErrorEncountered("get_first_txn_version() returned None!".into())
         })
     }
+
+    fn get_ledger_prune_window(&self) -> Result<Option<u64>, Error> {
+        let ledger_pruner_enabled = self
+            .storage
+            .is_ledger_pruner_enabled()
+            .map_err(|error| Error::StorageErrorEncountered(error.to_string()))?;
+        if !ledger_pruner_enabled {
+            return Ok(None);
+        }
+
+        let ledger_prune_window = self
+            .storage
+            .get_ledger_prune_window()
+            .map_err(|error| Error::StorageErrorEncountered(error.to_string()))?;
+        Ok(Some(ledger_prune_window as u64))
+    }
+
+    fn get_state_prune_window(&self) -> Result<Option<u64>, Error> {
+        let state_pruner_enabled = self
+            .storage
+            .is_state_merkle_pruner_enabled()
+            .map_err(|error| Error::StorageErrorEncountered(error.to_string()))?;
+        if !state_pruner_enabled {
+            return Ok(None);
+        }
+
+        let state_prune_window = self
+            .storage
+            .get_epoch_snapshot_prune_window()
+            .map_err(|error| Error::StorageErrorEncountered(error.to_string()))?;
+        Ok(Some(state_prune_window as u64))
+    }
 }
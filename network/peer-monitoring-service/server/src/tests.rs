@@ -13,6 +13,7 @@ use aptos_config::{
     network_id::{NetworkId, PeerNetworkId},
 };
 use aptos_crypto::HashValue;
+use aptos_infallible::RwLock;
 use aptos_logger::Level;
 use aptos_netcore::transport::ConnectionOrigin;
 use aptos_network::{
@@ -30,13 +31,13 @@ use aptos_network::{
 use aptos_peer_monitoring_service_types::{
     request::{LatencyPingRequest, PeerMonitoringServiceRequest},
     response::{
-        NetworkInformationResponse, NodeInformationResponse, PeerMonitoringServiceResponse,
-        ServerProtocolVersionResponse,
+        MempoolHealthSummary, NetworkInformationResponse, NodeHealthInformationResponse,
+        NodeInformationResponse, PeerMonitoringServiceResponse, ServerProtocolVersionResponse,
     },
     PeerMonitoringMetadata, PeerMonitoringServiceError, PeerMonitoringServiceMessage,
 };
 use aptos_storage_interface::{DbReader, ExecutedTrees, Order};
-use aptos_time_service::{MockTimeService, TimeService};
+use aptos_time_service::{MockTimeService, TimeService, TimeServiceTrait};
 use aptos_types::{
     account_address::AccountAddress,
     aggregate_signature::AggregateSignature,
@@ -134,7 +135,7 @@ async fn test_get_network_information_fullnode() {
         distance_from_validators: peer_distance_1,
     };
     let peer_monitoring_metadata_1 =
-        PeerMonitoringMetadata::new(None, Some(latest_network_info_response), None, None);
+        PeerMonitoringMetadata::new(None, Some(latest_network_info_response), None, None, None);
     peers_and_metadata
         .update_peer_monitoring_metadata(peer_network_id_1, peer_monitoring_metadata_1.clone())
         .unwrap();
@@ -154,7 +155,7 @@ async fn test_get_network_information_fullnode() {
         distance_from_validators: peer_distance_1,
     };
     let peer_monitoring_metadata_1 =
-        PeerMonitoringMetadata::new(None, Some(latest_network_info_response), None, None);
+        PeerMonitoringMetadata::new(None, Some(latest_network_info_response), None, None, None);
     peers_and_metadata
         .update_peer_monitoring_metadata(peer_network_id_1, peer_monitoring_metadata_1.clone())
         .unwrap();
@@ -182,7 +183,7 @@ async fn test_get_network_information_fullnode() {
         distance_from_validators: peer_distance_2,
     };
     let peer_monitoring_metadata_2 =
-        PeerMonitoringMetadata::new(None, Some(latest_network_info_response), None, None);
+        PeerMonitoringMetadata::new(None, Some(latest_network_info_response), None, None, None);
     peers_and_metadata
         .insert_connection_metadata(peer_network_id_2, connection_metadata_2.clone())
         .unwrap();
@@ -251,7 +252,7 @@ async fn test_get_network_information_validator() {
         distance_from_validators: peer_distance_1,
     };
     let peer_monitoring_metadata_1 =
-        PeerMonitoringMetadata::new(None, Some(latest_network_info_response), None, None);
+        PeerMonitoringMetadata::new(None, Some(latest_network_info_response), None, None, None);
     peers_and_metadata
         .update_peer_monitoring_metadata(peer_network_id_1, peer_monitoring_metadata_1.clone())
         .unwrap();
@@ -270,7 +271,7 @@ async fn test_get_network_information_validator() {
         distance_from_validators: peer_distance_2,
     };
     let peer_monitoring_metadata_2 =
-        PeerMonitoringMetadata::new(None, Some(latest_network_info_response), None, None);
+        PeerMonitoringMetadata::new(None, Some(latest_network_info_response), None, None, None);
     peers_and_metadata
         .insert_connection_metadata(peer_network_id_2, connection_metadata_2.clone())
         .unwrap();
@@ -365,6 +366,80 @@ async fn test_get_node_information() {
     }
 }
 
+#[tokio::test]
+async fn test_get_node_health_information() {
+    // Setup the mock data
+    let consensus_epoch = 10;
+    let consensus_round = 5050;
+    let ledger_timestamp_usecs = 1_000_000;
+    let block_info = BlockInfo::new(
+        consensus_epoch,
+        consensus_round,
+        HashValue::zero(),
+        HashValue::zero(),
+        100,
+        ledger_timestamp_usecs,
+        None,
+    );
+    let latest_ledger_info = LedgerInfoWithSignatures::new(
+        LedgerInfo::new(block_info, HashValue::zero()),
+        AggregateSignature::empty(),
+    );
+    let ledger_prune_window = 90_000_000;
+
+    // Create the mock storage reader (with the state pruner disabled)
+    let mut mock_db_reader = create_mock_db_reader();
+    mock_db_reader
+        .expect_get_latest_ledger_info()
+        .returning(move || Ok(latest_ledger_info.clone()));
+    mock_db_reader
+        .expect_is_ledger_pruner_enabled()
+        .returning(|| Ok(true));
+    mock_db_reader
+        .expect_get_ledger_prune_window()
+        .returning(move || Ok(ledger_prune_window));
+    mock_db_reader
+        .expect_is_state_merkle_pruner_enabled()
+        .returning(|| Ok(false));
+
+    // Create the peer monitoring client and server
+    let storage_reader = StorageReader::new(Arc::new(mock_db_reader));
+    let (mut mock_client, service, time_service, _) =
+        MockClient::new(None, None, Some(storage_reader));
+    tokio::spawn(service.start());
+
+    // Handle several node health requests with new mempool summaries and sync lags
+    for i in 0..10 {
+        // Update the mempool summary
+        let mempool_summary = MempoolHealthSummary {
+            num_transactions: i * 100,
+            oldest_transaction_age_ms: i * 1000,
+        };
+        *mock_client.mempool_health_summary.write() = mempool_summary;
+
+        // Elapse some time so that the node appears to lag behind
+        let state_sync_lag_usecs = i * 250_000;
+        let current_time_usecs = time_service.now_unix_time().as_micros() as u64;
+        time_service.advance(Duration::from_micros(
+            ledger_timestamp_usecs + state_sync_lag_usecs - current_time_usecs,
+        ));
+
+        // Process a client request to fetch the node health and verify the response
+        let request = PeerMonitoringServiceRequest::GetNodeHealthInformation;
+        let response = mock_client.send_request(request).await.unwrap();
+        let expected_response =
+            PeerMonitoringServiceResponse::NodeHealthInformation(NodeHealthInformationResponse {
+                consensus_epoch,
+                consensus_round,
+                ledger_prune_window: Some(ledger_prune_window as u64),
+                mempool_summary,
+                state_prune_window: None,
+                state_sync_lag_usecs,
+            });
+        assert_eq!(response, expected_response);
+    }
+}
+
 #[tokio::test]
 async fn test_latency_ping_request() {
     // Create the peer monitoring client and server
@@ -500,6 +575,7 @@ async fn verify_node_information(
 // A wrapper around the inbound network interface/channel for easily sending
 /// mock client requests to a peer monitoring service server.
 struct MockClient {
+    mempool_health_summary: Arc<RwLock<MempoolHealthSummary>>,
     peer_manager_notifiers:
         HashMap<NetworkId, aptos_channel::Sender<(PeerId, ProtocolId), PeerManagerNotification>>,
 }
@@ -556,9 +632,11 @@ impl MockClient {
         let mock_time_service = TimeService::mock();
         let storage_reader =
             storage_reader.unwrap_or_else(|| StorageReader::new(Arc::new(create_mock_db_reader())));
+        let mempool_health_summary = Arc::new(RwLock::new(MempoolHealthSummary::default()));
         let peer_monitoring_server = PeerMonitoringServiceServer::new(
             node_config,
             executor,
+            mempool_health_summary.clone(),
             peer_monitoring_network_events,
             peers_and_metadata.clone(),
             storage_reader,
@@ -567,6 +645,7 @@ impl MockClient {
 
         // Create the client
         let mock_client = Self {
+            mempool_health_summary,
             peer_manager_notifiers,
         };
 
@@ -770,6 +849,12 @@ mock! {
             chunk_size: usize,
         ) -> anyhow::Result<StateValueChunkWithProof>;
 
+        fn is_state_merkle_pruner_enabled(&self) -> anyhow::Result<bool>;
+
         fn get_epoch_snapshot_prune_window(&self) -> anyhow::Result<usize>;
+
+        fn is_ledger_pruner_enabled(&self) -> anyhow::Result<bool>;
+
+        fn get_ledger_prune_window(&self) -> anyhow::Result<usize>;
     }
 }
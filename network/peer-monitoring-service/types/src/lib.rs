@@ -3,7 +3,9 @@
 
 #![forbid(unsafe_code)]
 
-use crate::response::{NetworkInformationResponse, NodeInformationResponse};
+use crate::response::{
+    NetworkInformationResponse, NodeHealthInformationResponse, NodeInformationResponse,
+};
 use request::PeerMonitoringServiceRequest;
 use response::PeerMonitoringServiceResponse;
 use serde::{Deserialize, Serialize};
@@ -15,6 +17,8 @@ use thiserror::Error;
 
 pub mod request;
 pub mod response;
+#[cfg(test)]
+mod tests;
 
 pub type Result<T, E = PeerMonitoringServiceError> = ::std::result::Result<T, E>;
 
@@ -46,6 +50,7 @@ pub struct PeerMonitoringMetadata {
     pub average_ping_latency_secs: Option<f64>, // The average latency ping for the peer
     pub latest_network_info_response: Option<NetworkInformationResponse>, // The latest network info response
     pub latest_node_info_response: Option<NodeInformationResponse>, // The latest node info response
+    pub latest_node_health_response: Option<NodeHealthInformationResponse>, // The latest node health response
     pub internal_client_state: Option<String>, // A detailed client state string for debugging and logging
 }
 
@@ -58,12 +63,14 @@ impl PeerMonitoringMetadata {
         average_ping_latency_secs: Option<f64>,
         latest_network_info_response: Option<NetworkInformationResponse>,
         latest_node_info_response: Option<NodeInformationResponse>,
+        latest_node_health_response: Option<NodeHealthInformationResponse>,
         internal_client_state: Option<String>,
     ) -> Self {
         PeerMonitoringMetadata {
             average_ping_latency_secs,
             latest_network_info_response,
             latest_node_info_response,
+            latest_node_health_response,
             internal_client_state,
         }
     }
@@ -74,10 +81,12 @@ impl Display for PeerMonitoringMetadata {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
             f,
-            "{{ average_ping_latency_secs: {}, latest_network_info_response: {}, latest_node_info_response: {} }}",
+            "{{ average_ping_latency_secs: {}, latest_network_info_response: {}, latest_node_info_response: {}, \
+            latest_node_health_response: {} }}",
             display_format_option(&self.average_ping_latency_secs),
             display_format_option(&self.latest_network_info_response),
             display_format_option(&self.latest_node_info_response),
+            display_format_option(&self.latest_node_health_response),
         )
     }
 }
@@ -88,10 +97,12 @@ impl Debug for PeerMonitoringMetadata {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
             f,
-            "{{ average_ping_latency_secs: {}, latest_network_info_response: {}, latest_node_info_response: {} }}",
+            "{{ average_ping_latency_secs: {}, latest_network_info_response: {}, latest_node_info_response: {}, \
+            latest_node_health_response: {} }}",
             debug_format_option(&self.average_ping_latency_secs),
             debug_format_option(&self.latest_network_info_response),
             debug_format_option(&self.latest_node_info_response),
+            debug_format_option(&self.latest_node_health_response),
         )
     }
 }
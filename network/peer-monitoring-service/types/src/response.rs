@@ -16,6 +16,7 @@ pub enum PeerMonitoringServiceResponse {
     NetworkInformation(NetworkInformationResponse), // Holds the response for network information
     NodeInformation(NodeInformationResponse), // Holds the response for node information
     ServerProtocolVersion(ServerProtocolVersionResponse), // Returns the current server protocol version
+    NodeHealthInformation(NodeHealthInformationResponse), // Holds the response for node health information
 
     #[cfg(feature = "network-perf-test")] // Disabled by default
     PerformanceMonitoring(PerformanceMonitoringResponse), // A response for performance monitoring requests
@@ -29,6 +30,7 @@ impl PeerMonitoringServiceResponse {
             Self::NetworkInformation(_) => "network_information",
             Self::NodeInformation(_) => "node_information",
             Self::ServerProtocolVersion(_) => "server_protocol_version",
+            Self::NodeHealthInformation(_) => "node_health_information",
 
             #[cfg(feature = "network-perf-test")] // Disabled by default
             Self::PerformanceMonitoring(_) => "performance_monitoring_response",
@@ -124,6 +126,56 @@ impl Display for NodeInformationResponse {
     }
 }
 
+/// A response for the node health information request
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct NodeHealthInformationResponse {
+    pub consensus_epoch: u64, // The epoch of the latest committed block
+    pub consensus_round: u64, // The round of the latest committed block
+    pub ledger_prune_window: Option<u64>, // The ledger pruning window (None if the pruner is disabled)
+    pub mempool_summary: MempoolHealthSummary, // A summary of the node's mempool
+    pub state_prune_window: Option<u64>, // The state snapshot pruning window (None if the pruner is disabled)
+    pub state_sync_lag_usecs: u64, // The lag (in microseconds) between the latest ledger timestamp and the node's clock
+}
+
+impl NodeHealthInformationResponse {
+    /// Returns true iff the node is lagging behind the rest of the
+    /// network by more than the given maximum sync lag.
+    pub fn is_lagging(&self, max_sync_lag_secs: u64) -> bool {
+        self.state_sync_lag_usecs / 1_000_000 > max_sync_lag_secs
+    }
+
+    /// Returns true iff the node's mempool holds more than the given
+    /// maximum number of transactions (i.e., the node is overloaded).
+    pub fn is_overloaded(&self, max_mempool_transactions: u64) -> bool {
+        self.mempool_summary.num_transactions > max_mempool_transactions
+    }
+}
+
+// Display formatting provides a high-level summary of the response
+impl Display for NodeHealthInformationResponse {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{{ consensus_epoch: {:?}, consensus_round: {:?}, ledger_prune_window: {:?}, \
+            mempool_summary: {:?}, state_prune_window: {:?}, state_sync_lag_usecs: {:?} }}",
+            self.consensus_epoch,
+            self.consensus_round,
+            self.ledger_prune_window,
+            self.mempool_summary,
+            self.state_prune_window,
+            self.state_sync_lag_usecs,
+        )
+    }
+}
+
+/// A summary of the health of a node's mempool. This is periodically
+/// updated by mempool and reported to peers by the monitoring server.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct MempoolHealthSummary {
+    pub num_transactions: u64,          // The number of transactions in mempool
+    pub oldest_transaction_age_ms: u64, // The age (ms) of the oldest transaction in mempool
+}
+
 #[derive(Clone, Debug, Error)]
 #[error("Unexpected response variant: {0}")]
 pub struct UnexpectedResponseError(pub String);
@@ -156,6 +208,20 @@ impl TryFrom<PeerMonitoringServiceResponse> for NetworkInformationResponse {
     }
 }
 
+impl TryFrom<PeerMonitoringServiceResponse> for NodeHealthInformationResponse {
+    type Error = UnexpectedResponseError;
+
+    fn try_from(response: PeerMonitoringServiceResponse) -> crate::Result<Self, Self::Error> {
+        match response {
+            PeerMonitoringServiceResponse::NodeHealthInformation(inner) => Ok(inner),
+            _ => Err(UnexpectedResponseError(format!(
+                "expected node_health_information_response, found {}",
+                response.get_label()
+            ))),
+        }
+    }
+}
+
 impl TryFrom<PeerMonitoringServiceResponse> for NodeInformationResponse {
     type Error = UnexpectedResponseError;
 
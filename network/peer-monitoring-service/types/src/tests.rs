@@ -0,0 +1,104 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    request::{LatencyPingRequest, PeerMonitoringServiceRequest},
+    response::{
+        LatencyPingResponse, MempoolHealthSummary, NetworkInformationResponse,
+        NodeHealthInformationResponse, NodeInformationResponse, PeerMonitoringServiceResponse,
+        ServerProtocolVersionResponse,
+    },
+};
+use serde::{de::DeserializeOwned, Serialize};
+use std::{collections::BTreeMap, fmt::Debug, time::Duration};
+
+#[test]
+fn test_request_variant_indices() {
+    // The variant indices are part of the wire format, so they must never change
+    let requests = vec![
+        (PeerMonitoringServiceRequest::GetNetworkInformation, 0),
+        (PeerMonitoringServiceRequest::GetNodeInformation, 1),
+        (PeerMonitoringServiceRequest::GetServerProtocolVersion, 2),
+        (
+            PeerMonitoringServiceRequest::LatencyPing(LatencyPingRequest { ping_counter: 10 }),
+            3,
+        ),
+        (PeerMonitoringServiceRequest::GetNodeHealthInformation, 4),
+    ];
+
+    // Verify the variant index and the BCS round-trip for each request
+    for (request, variant_index) in requests {
+        verify_variant_index_and_round_trip(request, variant_index);
+    }
+}
+
+#[test]
+fn test_response_variant_indices() {
+    // The variant indices are part of the wire format, so they must never change
+    let node_information_response = NodeInformationResponse {
+        build_information: BTreeMap::new(),
+        highest_synced_epoch: 1,
+        highest_synced_version: 100,
+        ledger_timestamp_usecs: 1000,
+        lowest_available_version: 10,
+        uptime: Duration::from_secs(50),
+    };
+    let node_health_response = NodeHealthInformationResponse {
+        consensus_epoch: 1,
+        consensus_round: 20,
+        ledger_prune_window: Some(1_000),
+        mempool_summary: MempoolHealthSummary {
+            num_transactions: 5,
+            oldest_transaction_age_ms: 500,
+        },
+        state_prune_window: None,
+        state_sync_lag_usecs: 2_000_000,
+    };
+    let responses = vec![
+        (
+            PeerMonitoringServiceResponse::LatencyPing(LatencyPingResponse { ping_counter: 10 }),
+            0,
+        ),
+        (
+            PeerMonitoringServiceResponse::NetworkInformation(NetworkInformationResponse {
+                connected_peers: BTreeMap::new(),
+                distance_from_validators: 1,
+            }),
+            1,
+        ),
+        (
+            PeerMonitoringServiceResponse::NodeInformation(node_information_response),
+            2,
+        ),
+        (
+            PeerMonitoringServiceResponse::ServerProtocolVersion(ServerProtocolVersionResponse {
+                version: 1,
+            }),
+            3,
+        ),
+        (
+            PeerMonitoringServiceResponse::NodeHealthInformation(node_health_response),
+            4,
+        ),
+    ];
+
+    // Verify the variant index and the BCS round-trip for each response
+    for (response, variant_index) in responses {
+        verify_variant_index_and_round_trip(response, variant_index);
+    }
+}
+
+/// Verifies that the given message is serialized with the expected
+/// variant index and that it round-trips through BCS.
+fn verify_variant_index_and_round_trip<T: Debug + DeserializeOwned + PartialEq + Serialize>(
+    message: T,
+    variant_index: u8,
+) {
+    // Verify the variant index (encoded as a single ULEB128 byte)
+    let serialized_message = bcs::to_bytes(&message).unwrap();
+    assert_eq!(serialized_message[0], variant_index);
+
+    // Verify the message round-trips
+    let deserialized_message: T = bcs::from_bytes(&serialized_message).unwrap();
+    assert_eq!(deserialized_message, message);
+}
@@ -56,6 +56,24 @@ pub trait NetworkClientInterface<Message: NetworkMessageTrait>: Clone + Send + S
     /// Note: this method does not guarantee message delivery or handle responses.
     fn send_to_peers(&self, _message: Message, _peers: &[PeerNetworkId]) -> Result<(), Error>;
 
+    /// Same as `send_to_peer`, but the given protocols (sorted by preference)
+    /// are preferred over the client's direct send protocols.
+    fn send_to_peer_with_preferred_protocols(
+        &self,
+        _message: Message,
+        _peer: PeerNetworkId,
+        _preferred_protocols: &[ProtocolId],
+    ) -> Result<(), Error>;
+
+    /// Same as `send_to_peers`, but the given protocols (sorted by preference)
+    /// are preferred over the client's direct send protocols.
+    fn send_to_peers_with_preferred_protocols(
+        &self,
+        _message: Message,
+        _peers: &[PeerNetworkId],
+        _preferred_protocols: &[ProtocolId],
+    ) -> Result<(), Error>;
+
     /// Sends the given message to the specified peer with the corresponding
     /// timeout. Awaits a response from the peer, or hits the timeout
     /// (whichever occurs first).
@@ -65,6 +83,25 @@ pub trait NetworkClientInterface<Message: NetworkMessageTrait>: Clone + Send + S
         _rpc_timeout: Duration,
         _peer: PeerNetworkId,
     ) -> Result<Message, Error>;
+
+    /// Same as `send_to_peer_rpc`, but also returns the number of bytes
+    /// of the response (as received over the wire).
+    async fn send_to_peer_rpc_with_response_size(
+        &self,
+        _message: Message,
+        _rpc_timeout: Duration,
+        _peer: PeerNetworkId,
+    ) -> Result<(Message, usize), Error>;
+
+    /// Same as `send_to_peer_rpc`, but the given protocols (sorted by
+    /// preference) are preferred over the client's RPC protocols.
+    async fn send_to_peer_rpc_with_preferred_protocols(
+        &self,
+        _message: Message,
+        _rpc_timeout: Duration,
+        _peer: PeerNetworkId,
+        _preferred_protocols: &[ProtocolId],
+    ) -> Result<Message, Error>;
 }
 
 /// A network component that can be used by client applications (e.g., consensus,
@@ -114,14 +151,16 @@ impl<Message: NetworkMessageTrait + Clone> NetworkClient<Message> {
     }
 
     /// Selects the preferred protocol for the specified peer. The preferred protocols
-    /// should be sorted from most to least preferable.
+    /// should be sorted from most to least preferable, and are followed by the
+    /// (also sorted) client protocols.
     fn get_preferred_protocol_for_peer(
         &self,
         peer: &PeerNetworkId,
         preferred_protocols: &[ProtocolId],
+        client_protocols: &[ProtocolId],
     ) -> Result<ProtocolId, Error> {
         let protocols_supported_by_peer = self.get_supported_protocols(peer)?;
-        for protocol in preferred_protocols {
+        for protocol in preferred_protocols.iter().chain(client_protocols) {
             if protocols_supported_by_peer.contains(*protocol) {
                 return Ok(*protocol);
             }
@@ -164,20 +203,43 @@ impl<Message: NetworkMessageTrait> NetworkClientInterface<Message> for NetworkCl
     }
 
     fn send_to_peer(&self, message: Message, peer: PeerNetworkId) -> Result<(), Error> {
+        self.send_to_peer_with_preferred_protocols(message, peer, &[])
+    }
+
+    fn send_to_peers(&self, message: Message, peers: &[PeerNetworkId]) -> Result<(), Error> {
+        self.send_to_peers_with_preferred_protocols(message, peers, &[])
+    }
+
+    fn send_to_peer_with_preferred_protocols(
+        &self,
+        message: Message,
+        peer: PeerNetworkId,
+        preferred_protocols: &[ProtocolId],
+    ) -> Result<(), Error> {
         let network_sender = self.get_sender_for_network_id(&peer.network_id())?;
-        let direct_send_protocol_id = self
-            .get_preferred_protocol_for_peer(&peer, &self.direct_send_protocols_and_preferences)?;
+        let direct_send_protocol_id = self.get_preferred_protocol_for_peer(
+            &peer,
+            preferred_protocols,
+            &self.direct_send_protocols_and_preferences,
+        )?;
         Ok(network_sender.send_to(peer.peer_id(), direct_send_protocol_id, message)?)
     }
 
-    fn send_to_peers(&self, message: Message, peers: &[PeerNetworkId]) -> Result<(), Error> {
+    fn send_to_peers_with_preferred_protocols(
+        &self,
+        message: Message,
+        peers: &[PeerNetworkId],
+        preferred_protocols: &[ProtocolId],
+    ) -> Result<(), Error> {
         // Sort peers by protocol
         let mut peers_per_protocol = HashMap::new();
         let mut peers_without_a_protocol = vec![];
         for peer in peers {
-            match self
-                .get_preferred_protocol_for_peer(peer, &self.direct_send_protocols_and_preferences)
-            {
+            match self.get_preferred_protocol_for_peer(
+                peer,
+                preferred_protocols,
+                &self.direct_send_protocols_and_preferences,
+            ) {
                 Ok(protocol) => peers_per_protocol
                     .entry(protocol)
                     .or_insert_with(Vec::new)
@@ -217,9 +279,37 @@ impl<Message: NetworkMessageTrait> NetworkClientInterface<Message> for NetworkCl
         rpc_timeout: Duration,
         peer: PeerNetworkId,
     ) -> Result<Message, Error> {
+        self.send_to_peer_rpc_with_preferred_protocols(message, rpc_timeout, peer, &[])
+            .await
+    }
+
+    async fn send_to_peer_rpc_with_response_size(
+        &self,
+        message: Message,
+        rpc_timeout: Duration,
+        peer: PeerNetworkId,
+    ) -> Result<(Message, usize), Error> {
         let network_sender = self.get_sender_for_network_id(&peer.network_id())?;
         let rpc_protocol_id =
-            self.get_preferred_protocol_for_peer(&peer, &self.rpc_protocols_and_preferences)?;
+            self.get_preferred_protocol_for_peer(&peer, &[], &self.rpc_protocols_and_preferences)?;
+        Ok(network_sender
+            .send_rpc_with_response_size(peer.peer_id(), rpc_protocol_id, message, rpc_timeout)
+            .await?)
+    }
+
+    async fn send_to_peer_rpc_with_preferred_protocols(
+        &self,
+        message: Message,
+        rpc_timeout: Duration,
+        peer: PeerNetworkId,
+        preferred_protocols: &[ProtocolId],
+    ) -> Result<Message, Error> {
+        let network_sender = self.get_sender_for_network_id(&peer.network_id())?;
+        let rpc_protocol_id = self.get_preferred_protocol_for_peer(
+            &peer,
+            preferred_protocols,
+            &self.rpc_protocols_and_preferences,
+        )?;
         Ok(network_sender
             .send_rpc(peer.peer_id(), rpc_protocol_id, message, rpc_timeout)
             .await?)
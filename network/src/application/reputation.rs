@@ -0,0 +1,229 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::counters;
+use aptos_config::{
+    config::PeerReputationConfig,
+    network_id::{NetworkId, PeerNetworkId},
+};
+use aptos_infallible::RwLock;
+use aptos_logger::prelude::*;
+use aptos_time_service::{TimeService, TimeServiceTrait};
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+/// Scores are bounded, so that a peer can't build up enough goodwill to misbehave for long
+const MAX_SCORE: f64 = 100.0;
+const MIN_SCORE: f64 = -1000.0;
+/// Scores closer to zero than this are forgotten, unless the peer is banned
+const MIN_TRACKED_SCORE: f64 = 1.0;
+/// The max time to ban a peer for, no matter how many times it has been banned before
+const MAX_BAN_DURATION: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// An event reported by an application about the behaviour of a peer
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PeerEvent {
+    /// The peer sent a valid and useful message, request or response
+    Useful,
+    /// The peer sent something that isn't actively malicious but doesn't help either, e.g.,
+    /// a request that can't be served or a response that fails to deserialize
+    NotUseful,
+    /// The peer sent something that only a faulty or malicious peer would send, e.g.,
+    /// an invalid proof or signature
+    Malicious,
+}
+
+impl PeerEvent {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PeerEvent::Useful => "useful",
+            PeerEvent::NotUseful => "not_useful",
+            PeerEvent::Malicious => "malicious",
+        }
+    }
+
+    fn score_delta(&self) -> f64 {
+        match self {
+            PeerEvent::Useful => 1.0,
+            PeerEvent::NotUseful => -5.0,
+            PeerEvent::Malicious => -50.0,
+        }
+    }
+}
+
+/// A peer that is currently banned
+#[derive(Clone, Debug, PartialEq)]
+pub struct BannedPeer {
+    pub peer_network_id: PeerNetworkId,
+    pub score: f64,
+    pub remaining_ban_duration: Duration,
+    pub num_bans: u32,
+}
+
+/// The reputation of a single peer
+#[derive(Clone, Debug)]
+struct PeerReputation {
+    score: f64,                    // The score at the time of the last update
+    last_update_time: Instant,     // The time of the last update (the score decays from there)
+    banned_until: Option<Instant>, // The time at which the current ban (if any) expires
+    num_bans: u32,                 // The number of times the peer has been banned
+}
+
+impl PeerReputation {
+    fn new(now: Instant) -> Self {
+        Self {
+            score: 0.0,
+            last_update_time: now,
+            banned_until: None,
+            num_bans: 0,
+        }
+    }
+
+    /// Returns the score decayed up to the given time
+    fn score_at(&self, now: Instant, config: &PeerReputationConfig) -> f64 {
+        let elapsed_secs = now.duration_since(self.last_update_time).as_secs_f64();
+        let half_life_secs = config.score_half_life_secs.max(1) as f64;
+        self.score * 0.5f64.powf(elapsed_secs / half_life_secs)
+    }
+
+    fn is_banned(&self, now: Instant) -> bool {
+        self.banned_until
+            .map_or(false, |banned_until| now < banned_until)
+    }
+}
+
+/// Tracks the reputation of all peers of the node, as reported by the applications. Unlike the
+/// peer metadata, reputations outlive the connections, so that banned peers stay banned when
+/// they reconnect.
+#[derive(Debug)]
+pub struct PeerReputations {
+    configs: HashMap<NetworkId, PeerReputationConfig>,
+    reputations: RwLock<HashMap<PeerNetworkId, PeerReputation>>,
+    time_service: TimeService,
+}
+
+impl PeerReputations {
+    /// Creates the reputations with the given configs. The default config is used for
+    /// the networks without one.
+    pub fn new(
+        configs: HashMap<NetworkId, PeerReputationConfig>,
+        time_service: TimeService,
+    ) -> Self {
+        Self {
+            configs,
+            reputations: RwLock::new(HashMap::new()),
+            time_service,
+        }
+    }
+
+    /// Updates the score of the peer according to the event, and bans the peer
+    /// if the score drops below the ban threshold. Trusted peers are only scored.
+    pub fn report_event(&self, peer_network_id: PeerNetworkId, event: PeerEvent, is_trusted: bool) {
+        counters::peer_reputation_events(&peer_network_id.network_id(), event.as_str()).inc();
+
+        let config = self.get_config(&peer_network_id.network_id());
+        let now = self.time_service.now();
+        let mut reputations = self.reputations.write();
+        let reputation = reputations
+            .entry(peer_network_id)
+            .or_insert_with(|| PeerReputation::new(now));
+
+        // Decay the score up to now, and apply the event
+        reputation.score =
+            (reputation.score_at(now, &config) + event.score_delta()).clamp(MIN_SCORE, MAX_SCORE);
+        reputation.last_update_time = now;
+
+        // Ban the peer if it has fallen below the threshold (and isn't already banned)
+        if !is_trusted
+            && can_ban(&peer_network_id, &config)
+            && reputation.score < config.ban_threshold
+            && !reputation.is_banned(now)
+        {
+            let ban_duration = Duration::from_secs(config.ban_duration_secs)
+                .saturating_mul(2u32.saturating_pow(reputation.num_bans))
+                .min(MAX_BAN_DURATION);
+            reputation.banned_until = Some(now + ban_duration);
+            reputation.num_bans += 1;
+            counters::peer_bans(&peer_network_id.network_id()).inc();
+            warn!(
+                "Banning peer {} for {:?}, its score dropped to {} (after {:?} event)",
+                peer_network_id, ban_duration, reputation.score, event
+            );
+        }
+    }
+
+    /// Returns the current score of the peer (zero if nothing was reported)
+    pub fn get_score(&self, peer_network_id: &PeerNetworkId) -> f64 {
+        let config = self.get_config(&peer_network_id.network_id());
+        let now = self.time_service.now();
+        self.reputations
+            .read()
+            .get(peer_network_id)
+            .map_or(0.0, |reputation| reputation.score_at(now, &config))
+    }
+
+    /// Returns true iff the peer is currently banned
+    pub fn is_banned(&self, peer_network_id: &PeerNetworkId) -> bool {
+        if !can_ban(
+            peer_network_id,
+            &self.get_config(&peer_network_id.network_id()),
+        ) {
+            return false;
+        }
+        let now = self.time_service.now();
+        self.reputations
+            .read()
+            .get(peer_network_id)
+            .map_or(false, |reputation| reputation.is_banned(now))
+    }
+
+    /// Returns all the peers that are currently banned
+    pub fn get_banned_peers(&self) -> Vec<BannedPeer> {
+        let now = self.time_service.now();
+        self.reputations
+            .read()
+            .iter()
+            .filter(|(peer_network_id, reputation)| {
+                can_ban(
+                    peer_network_id,
+                    &self.get_config(&peer_network_id.network_id()),
+                ) && reputation.is_banned(now)
+            })
+            .map(|(peer_network_id, reputation)| BannedPeer {
+                peer_network_id: *peer_network_id,
+                score: reputation.score_at(now, &self.get_config(&peer_network_id.network_id())),
+                remaining_ban_duration: reputation
+                    .banned_until
+                    .map(|banned_until| banned_until.duration_since(now))
+                    .unwrap_or_default(),
+                num_bans: reputation.num_bans,
+            })
+            .collect()
+    }
+
+    /// Forgets the peers whose score has decayed back to (almost) zero, and which aren't banned.
+    /// The number of bans of a peer is forgotten along with it.
+    pub fn garbage_collect(&self) {
+        let now = self.time_service.now();
+        self.reputations
+            .write()
+            .retain(|peer_network_id, reputation| {
+                let config = self.get_config(&peer_network_id.network_id());
+                reputation.is_banned(now)
+                    || reputation.score_at(now, &config).abs() >= MIN_TRACKED_SCORE
+            });
+    }
+
+    fn get_config(&self, network_id: &NetworkId) -> PeerReputationConfig {
+        self.configs.get(network_id).copied().unwrap_or_default()
+    }
+}
+
+/// Returns true iff the peer can be banned. Only peers of the public network are ever
+/// banned: the validator set is decided on chain, and the peers of the VFN network are
+/// operated by the same party as the node.
+fn can_ban(peer_network_id: &PeerNetworkId, config: &PeerReputationConfig) -> bool {
+    config.enable_bans && peer_network_id.network_id().is_public_network()
+}
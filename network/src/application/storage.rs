@@ -6,6 +6,7 @@ use crate::{
     application::{
         error::Error,
         metadata::{ConnectionState, PeerMetadata},
+        reputation::{BannedPeer, PeerEvent, PeerReputations},
     },
     transport::{ConnectionId, ConnectionMetadata},
     ProtocolId,
@@ -16,6 +17,7 @@ use aptos_config::{
 };
 use aptos_infallible::RwLock;
 use aptos_peer_monitoring_service_types::PeerMonitoringMetadata;
+use aptos_time_service::TimeService;
 use aptos_types::PeerId;
 use std::{
     collections::{hash_map::Entry, HashMap},
@@ -30,14 +32,28 @@ use std::{
 pub struct PeersAndMetadata {
     peers_and_metadata: HashMap<NetworkId, RwLock<HashMap<PeerId, PeerMetadata>>>,
     trusted_peers: HashMap<NetworkId, Arc<RwLock<PeerSet>>>,
+    peer_reputations: PeerReputations,
 }
 
 impl PeersAndMetadata {
     pub fn new(network_ids: &[NetworkId]) -> Arc<PeersAndMetadata> {
+        Self::new_with_peer_reputations(
+            network_ids,
+            PeerReputations::new(HashMap::new(), TimeService::real()),
+        )
+    }
+
+    /// Creates the container with the given peer reputations (e.g., to use
+    /// the reputation configs of the networks).
+    pub fn new_with_peer_reputations(
+        network_ids: &[NetworkId],
+        peer_reputations: PeerReputations,
+    ) -> Arc<PeersAndMetadata> {
         // Create the container
         let mut peers_and_metadata = PeersAndMetadata {
             peers_and_metadata: HashMap::new(),
             trusted_peers: HashMap::new(),
+            peer_reputations,
         };
 
         // Initialize each network mapping and trusted peer set
@@ -239,6 +255,50 @@ impl PeersAndMetadata {
         }
     }
 
+    /// Reports an event about the behaviour of the given peer, which updates its
+    /// reputation (and may get the peer banned, unless it is trusted).
+    pub fn report_peer_event(&self, peer_network_id: PeerNetworkId, event: PeerEvent) {
+        let is_trusted = self.is_trusted_peer(&peer_network_id);
+        self.peer_reputations
+            .report_event(peer_network_id, event, is_trusted)
+    }
+
+    /// Returns the current reputation score of the given peer
+    pub fn get_peer_score(&self, peer_network_id: &PeerNetworkId) -> f64 {
+        self.peer_reputations.get_score(peer_network_id)
+    }
+
+    /// Returns true iff the given peer is currently banned. Trusted peers are never
+    /// banned (even if they were banned before becoming trusted).
+    pub fn is_peer_banned(&self, peer_network_id: &PeerNetworkId) -> bool {
+        !self.is_trusted_peer(peer_network_id) && self.peer_reputations.is_banned(peer_network_id)
+    }
+
+    /// Returns all the peers that are currently banned
+    pub fn get_banned_peers(&self) -> Vec<BannedPeer> {
+        self.peer_reputations
+            .get_banned_peers()
+            .into_iter()
+            .filter(|banned_peer| !self.is_trusted_peer(&banned_peer.peer_network_id))
+            .collect()
+    }
+
+    /// Forgets the reputations that have decayed back to neutral
+    pub fn garbage_collect_peer_reputations(&self) {
+        self.peer_reputations.garbage_collect()
+    }
+
+    /// Returns true iff the peer is in the trusted peer set of its network
+    fn is_trusted_peer(&self, peer_network_id: &PeerNetworkId) -> bool {
+        self.trusted_peers
+            .get(&peer_network_id.network_id())
+            .map_or(false, |trusted_peers| {
+                trusted_peers
+                    .read()
+                    .contains_key(&peer_network_id.peer_id())
+            })
+    }
+
     /// A helper method that returns the peers and metadata for the specified network
     fn get_peer_metadata_for_network(
         &self,
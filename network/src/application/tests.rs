@@ -7,6 +7,7 @@ use crate::{
         error::Error,
         interface::{NetworkClient, NetworkClientInterface, NetworkServiceEvents},
         metadata::{ConnectionState, PeerMetadata},
+        reputation::{PeerEvent, PeerReputations},
         storage::PeersAndMetadata,
     },
     peer_manager::{
@@ -22,9 +23,10 @@ use crate::{
 };
 use aptos_channels::{aptos_channel, message_queues::QueueStyle};
 use aptos_config::{
-    config::{Peer, PeerRole},
+    config::{Peer, PeerReputationConfig, PeerRole},
     network_id::{NetworkId, PeerNetworkId},
 };
+use aptos_time_service::{MockTimeService, TimeService};
 use aptos_types::PeerId;
 use futures::channel::oneshot;
 use futures_util::StreamExt;
@@ -677,6 +679,238 @@ async fn test_network_client_network_senders_rpc() {
     .await;
 }
 
+#[tokio::test]
+async fn test_network_client_preferred_protocols() {
+    // Create the peers and metadata container
+    let network_ids = [NetworkId::Validator];
+    let peers_and_metadata = PeersAndMetadata::new(&network_ids);
+
+    // Create two peers (only the first supports the preferred protocol)
+    let (peer_network_id_1, _) = create_peer_and_connection(
+        NetworkId::Validator,
+        vec![
+            ProtocolId::QuorumStoreDirectSendCompressed,
+            ProtocolId::ConsensusDirectSendCompressed,
+        ],
+        peers_and_metadata.clone(),
+    );
+    let (peer_network_id_2, _) = create_peer_and_connection(
+        NetworkId::Validator,
+        vec![ProtocolId::ConsensusDirectSendCompressed],
+        peers_and_metadata.clone(),
+    );
+
+    // Create a network client with network senders
+    let (
+        network_senders,
+        network_events,
+        mut outbound_request_receivers,
+        mut inbound_request_senders,
+    ) = create_network_sender_and_events(&network_ids);
+    let network_client: NetworkClient<DummyMessage> = NetworkClient::new(
+        vec![
+            ProtocolId::ConsensusDirectSendCompressed,
+            ProtocolId::QuorumStoreDirectSendCompressed,
+        ],
+        vec![],
+        network_senders,
+        peers_and_metadata.clone(),
+    );
+
+    // Extract the network and events
+    let mut network_and_events = network_events.into_network_and_events();
+    let mut validator_network_events = network_and_events.remove(&NetworkId::Validator).unwrap();
+
+    // Verify that the client protocols are used by default
+    let dummy_message = DummyMessage::new(10101);
+    network_client
+        .send_to_peer(dummy_message.clone(), peer_network_id_1)
+        .unwrap();
+    wait_for_network_event(
+        peer_network_id_1,
+        &mut outbound_request_receivers,
+        &mut inbound_request_senders,
+        &mut validator_network_events,
+        false,
+        Some(ProtocolId::ConsensusDirectSendCompressed),
+        None,
+        dummy_message,
+    )
+    .await;
+
+    // Verify that the preferred protocol is used for the peers that support it
+    let preferred_protocols = [ProtocolId::QuorumStoreDirectSendCompressed];
+    for (peer_network_id, expected_protocol_id) in [
+        (
+            peer_network_id_1,
+            ProtocolId::QuorumStoreDirectSendCompressed,
+        ),
+        (peer_network_id_2, ProtocolId::ConsensusDirectSendCompressed),
+    ] {
+        let dummy_message = DummyMessage::new(2323);
+        network_client
+            .send_to_peers_with_preferred_protocols(
+                dummy_message.clone(),
+                &[peer_network_id],
+                &preferred_protocols,
+            )
+            .unwrap();
+        wait_for_network_event(
+            peer_network_id,
+            &mut outbound_request_receivers,
+            &mut inbound_request_senders,
+            &mut validator_network_events,
+            false,
+            Some(expected_protocol_id),
+            None,
+            dummy_message,
+        )
+        .await;
+    }
+}
+
+#[test]
+fn test_peer_reputations_decay() {
+    // Create the peers and metadata container with a mock time service
+    let (peers_and_metadata, time_service) = create_peers_and_metadata_with_reputations();
+    let peer_network_id = PeerNetworkId::new(NetworkId::Public, PeerId::random());
+
+    // Verify that peers without any events have a zero score
+    assert_eq!(peers_and_metadata.get_peer_score(&peer_network_id), 0.0);
+
+    // Report a malicious event and verify the score
+    peers_and_metadata.report_peer_event(peer_network_id, PeerEvent::Malicious);
+    assert_eq!(peers_and_metadata.get_peer_score(&peer_network_id), -50.0);
+
+    // Elapse a half-life and verify the score has halved
+    time_service.advance_secs(PeerReputationConfig::default().score_half_life_secs);
+    assert_eq!(peers_and_metadata.get_peer_score(&peer_network_id), -25.0);
+
+    // Report a useful event and verify the score is applied on top of the decayed score
+    peers_and_metadata.report_peer_event(peer_network_id, PeerEvent::Useful);
+    assert_eq!(peers_and_metadata.get_peer_score(&peer_network_id), -24.0);
+}
+
+#[test]
+fn test_peer_reputations_bans() {
+    // Create the peers and metadata container with a mock time service
+    let (peers_and_metadata, time_service) = create_peers_and_metadata_with_reputations();
+    let peer_network_id = PeerNetworkId::new(NetworkId::Public, PeerId::random());
+
+    // Report malicious events until the score reaches the ban threshold
+    peers_and_metadata.report_peer_event(peer_network_id, PeerEvent::Malicious);
+    peers_and_metadata.report_peer_event(peer_network_id, PeerEvent::Malicious);
+    assert!(!peers_and_metadata.is_peer_banned(&peer_network_id));
+    assert!(peers_and_metadata.get_banned_peers().is_empty());
+
+    // Report another malicious event and verify the peer is banned
+    peers_and_metadata.report_peer_event(peer_network_id, PeerEvent::Malicious);
+    assert!(peers_and_metadata.is_peer_banned(&peer_network_id));
+    let banned_peers = peers_and_metadata.get_banned_peers();
+    assert_eq!(banned_peers.len(), 1);
+    assert_eq!(banned_peers[0].peer_network_id, peer_network_id);
+    assert_eq!(banned_peers[0].num_bans, 1);
+
+    // Elapse the ban duration and verify the peer is no longer banned
+    let ban_duration_secs = PeerReputationConfig::default().ban_duration_secs;
+    time_service.advance_secs(ban_duration_secs);
+    assert!(!peers_and_metadata.is_peer_banned(&peer_network_id));
+
+    // Ban the peer again and verify the ban duration has doubled
+    for _ in 0..10 {
+        peers_and_metadata.report_peer_event(peer_network_id, PeerEvent::Malicious);
+    }
+    let banned_peers = peers_and_metadata.get_banned_peers();
+    assert_eq!(banned_peers[0].num_bans, 2);
+    assert_eq!(
+        banned_peers[0].remaining_ban_duration,
+        Duration::from_secs(2 * ban_duration_secs)
+    );
+}
+
+#[test]
+fn test_peer_reputations_no_validator_bans() {
+    // Create the peers and metadata container with a mock time service
+    let (peers_and_metadata, _) = create_peers_and_metadata_with_reputations();
+    let peer_network_id = PeerNetworkId::new(NetworkId::Validator, PeerId::random());
+
+    // Report many malicious events and verify the validator is never banned
+    for _ in 0..100 {
+        peers_and_metadata.report_peer_event(peer_network_id, PeerEvent::Malicious);
+    }
+    assert!(peers_and_metadata.get_peer_score(&peer_network_id) < 0.0);
+    assert!(!peers_and_metadata.is_peer_banned(&peer_network_id));
+    assert!(peers_and_metadata.get_banned_peers().is_empty());
+}
+
+#[test]
+fn test_peer_reputations_no_vfn_bans() {
+    // Create the peers and metadata container with a mock time service
+    let (peers_and_metadata, _) = create_peers_and_metadata_with_reputations();
+    let peer_network_id = PeerNetworkId::new(NetworkId::Vfn, PeerId::random());
+
+    // Report many malicious events and verify the VFN network peer is never banned
+    for _ in 0..100 {
+        peers_and_metadata.report_peer_event(peer_network_id, PeerEvent::Malicious);
+    }
+    assert!(peers_and_metadata.get_peer_score(&peer_network_id) < 0.0);
+    assert!(!peers_and_metadata.is_peer_banned(&peer_network_id));
+    assert!(peers_and_metadata.get_banned_peers().is_empty());
+}
+
+#[test]
+fn test_peer_reputations_no_trusted_peer_bans() {
+    // Create the peers and metadata container with a mock time service
+    let (peers_and_metadata, _) = create_peers_and_metadata_with_reputations();
+    let peer_network_id = PeerNetworkId::new(NetworkId::Public, PeerId::random());
+
+    // Ban the (untrusted) public peer
+    for _ in 0..3 {
+        peers_and_metadata.report_peer_event(peer_network_id, PeerEvent::Malicious);
+    }
+    assert!(peers_and_metadata.is_peer_banned(&peer_network_id));
+
+    // Add the peer to the trusted peers (e.g., as a seed) and verify it is no longer banned
+    let trusted_peers = peers_and_metadata
+        .get_trusted_peers(&NetworkId::Public)
+        .unwrap();
+    let peer = Peer::new(vec![], HashSet::new(), PeerRole::Upstream);
+    trusted_peers.write().insert(peer_network_id.peer_id(), peer);
+    assert!(!peers_and_metadata.is_peer_banned(&peer_network_id));
+    assert!(peers_and_metadata.get_banned_peers().is_empty());
+
+    // Report many more malicious events and verify the trusted peer is never banned
+    for _ in 0..100 {
+        peers_and_metadata.report_peer_event(peer_network_id, PeerEvent::Malicious);
+    }
+    assert!(peers_and_metadata.get_peer_score(&peer_network_id) < 0.0);
+    assert!(!peers_and_metadata.is_peer_banned(&peer_network_id));
+    assert!(peers_and_metadata.get_banned_peers().is_empty());
+}
+
+#[test]
+fn test_peer_reputations_garbage_collection() {
+    // Create the peers and metadata container with a mock time service
+    let (peers_and_metadata, time_service) = create_peers_and_metadata_with_reputations();
+    let peer_network_id_1 = PeerNetworkId::new(NetworkId::Public, PeerId::random());
+    let peer_network_id_2 = PeerNetworkId::new(NetworkId::Public, PeerId::random());
+
+    // Report a useful event for the first peer and ban the second peer
+    peers_and_metadata.report_peer_event(peer_network_id_1, PeerEvent::Useful);
+    for _ in 0..3 {
+        peers_and_metadata.report_peer_event(peer_network_id_2, PeerEvent::Malicious);
+    }
+
+    // Elapse a half-life and garbage collect the reputations
+    time_service.advance_secs(PeerReputationConfig::default().score_half_life_secs);
+    peers_and_metadata.garbage_collect_peer_reputations();
+
+    // Verify the first peer was forgotten, but the banned peer was not
+    assert_eq!(peers_and_metadata.get_peer_score(&peer_network_id_1), 0.0);
+    assert!(peers_and_metadata.get_peer_score(&peer_network_id_2) < 0.0);
+    assert!(peers_and_metadata.is_peer_banned(&peer_network_id_2));
+}
+
 /// Verifies that the available peers are correct
 fn check_available_peers(
     network_client: &NetworkClient<DummyMessage>,
@@ -739,6 +973,18 @@ fn compare_vectors_ignore_order<T: Clone + Debug + Ord>(
     assert_eq!(vector_1, vector_2);
 }
 
+/// Creates a peers and metadata container (for the validator, VFN and public
+/// networks) that uses the returned mock time service for peer reputations.
+fn create_peers_and_metadata_with_reputations() -> (Arc<PeersAndMetadata>, MockTimeService) {
+    let time_service = TimeService::mock();
+    let peer_reputations = PeerReputations::new(HashMap::new(), time_service.clone());
+    let peers_and_metadata = PeersAndMetadata::new_with_peer_reputations(
+        &[NetworkId::Validator, NetworkId::Vfn, NetworkId::Public],
+        peer_reputations,
+    );
+    (peers_and_metadata, time_service.into_mock())
+}
+
 /// Returns an aptos channel for testing
 fn create_aptos_channel<K: Eq + Hash + Clone, T>(
 ) -> (aptos_channel::Sender<K, T>, aptos_channel::Receiver<K, T>) {
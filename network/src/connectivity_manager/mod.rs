@@ -36,7 +36,7 @@ use crate::{
 };
 use aptos_config::{
     config::{Peer, PeerRole, PeerSet},
-    network_id::NetworkContext,
+    network_id::{NetworkContext, PeerNetworkId},
 };
 use aptos_crypto::x25519;
 use aptos_infallible::RwLock;
@@ -123,7 +123,7 @@ pub struct ConnectivityManager<TBackoff> {
 }
 
 /// Different sources for peer addresses, ordered by priority (Onchain=highest,
-/// Config=lowest).
+/// PeerExchange=lowest, as its addresses are gossiped by untrusted peers).
 #[repr(u8)]
 #[derive(Copy, Clone, Eq, Hash, PartialEq, Ord, PartialOrd, NumVariants, Serialize)]
 pub enum DiscoverySource {
@@ -131,6 +131,7 @@ pub enum DiscoverySource {
     File,
     Rest,
     Config,
+    PeerExchange,
 }
 
 impl fmt::Debug for DiscoverySource {
@@ -146,6 +147,7 @@ impl fmt::Display for DiscoverySource {
             DiscoverySource::File => "File",
             DiscoverySource::Config => "Config",
             DiscoverySource::Rest => "Rest",
+            DiscoverySource::PeerExchange => "PeerExchange",
         })
     }
 }
@@ -431,6 +433,12 @@ where
         }
     }
 
+    /// Returns true iff the peer has been banned for a low reputation
+    fn is_peer_banned(&self, peer_id: &PeerId) -> bool {
+        let peer_network_id = PeerNetworkId::new(self.network_context.network_id(), *peer_id);
+        self.peers_and_metadata.is_peer_banned(&peer_network_id)
+    }
+
     /// Disconnect from all peers that are no longer eligible.
     ///
     /// For instance, a validator might leave the validator set after a
@@ -484,6 +492,38 @@ where
         }
     }
 
+    /// Disconnect from all peers that have been banned for a low reputation.
+    async fn close_banned_connections(&mut self) {
+        let banned_peers: Vec<PeerId> = self
+            .connected
+            .keys()
+            .filter(|peer_id| self.is_peer_banned(peer_id))
+            .cloned()
+            .collect();
+
+        for banned_peer in banned_peers {
+            info!(
+                NetworkSchema::new(&self.network_context).remote_peer(&banned_peer),
+                "{} Closing connection to banned peer {}",
+                self.network_context,
+                banned_peer.short_str()
+            );
+
+            if let Err(disconnect_error) =
+                self.connection_reqs_tx.disconnect_peer(banned_peer).await
+            {
+                info!(
+                    NetworkSchema::new(&self.network_context).remote_peer(&banned_peer),
+                    error = %disconnect_error,
+                    "{} Failed to close connection to banned peer {}, error: {}",
+                    self.network_context,
+                    banned_peer.short_str(),
+                    disconnect_error
+                );
+            }
+        }
+    }
+
     /// Cancel all pending dials to peers that are no longer eligible.
     ///
     /// For instance, a validator might leave the validator set after a
@@ -536,6 +576,7 @@ where
                     && !self.connected.contains_key(peer_id) // The node is not already connected.
                     && !self.dial_queue.contains_key(peer_id) // There is no pending dial to this node.
                     && roles_to_dial.contains(&peer.role) // We can dial this role
+                    && !self.is_peer_banned(peer_id) // The node is not banned
             })
             .collect();
 
@@ -669,6 +710,10 @@ where
         self.cancel_stale_dials().await;
         // Disconnect from connected peers that are no longer eligible.
         self.close_stale_connections().await;
+        // Disconnect from connected peers that have been banned, and forget the reputations
+        // that have decayed back to neutral.
+        self.close_banned_connections().await;
+        self.peers_and_metadata.garbage_collect_peer_reputations();
         // Dial peers which are eligible but are neither connected nor queued for dialing in the
         // future.
         self.dial_eligible_peers(pending_dials);
@@ -3,7 +3,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::protocols::wire::handshake::v1::ProtocolId;
-use aptos_config::network_id::NetworkContext;
+use aptos_config::network_id::{NetworkContext, NetworkId};
 use aptos_metrics_core::{
     register_histogram_vec, register_int_counter_vec, register_int_gauge, register_int_gauge_vec,
     Histogram, HistogramVec, IntCounter, IntCounterVec, IntGauge, IntGaugeVec,
@@ -264,6 +264,50 @@ pub fn inbound_rpc_handler_latency(
     ])
 }
 
+pub static APTOS_NETWORK_OUTBOUND_QUEUE_DEPTH: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register_int_gauge_vec!(
+        "aptos_network_outbound_queue_depth",
+        "Number of outbound messages waiting to be scheduled on the connections to peers",
+        &["role_type", "network_id", "peer_id", "protocol_id"]
+    )
+    .unwrap()
+});
+
+pub fn outbound_queue_depth(network_context: &NetworkContext, protocol_id: ProtocolId) -> IntGauge {
+    APTOS_NETWORK_OUTBOUND_QUEUE_DEPTH.with_label_values(&[
+        network_context.role().as_str(),
+        network_context.network_id().as_str(),
+        network_context.peer_id().short_str().as_str(),
+        protocol_id.as_str(),
+    ])
+}
+
+pub static APTOS_NETWORK_PEER_REPUTATION_EVENTS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_network_peer_reputation_events",
+        "Number of peer behaviour events reported by the applications",
+        &["network_id", "event"]
+    )
+    .unwrap()
+});
+
+pub fn peer_reputation_events(network_id: &NetworkId, event: &str) -> IntCounter {
+    APTOS_NETWORK_PEER_REPUTATION_EVENTS.with_label_values(&[network_id.as_str(), event])
+}
+
+pub static APTOS_NETWORK_PEER_BANS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_network_peer_bans",
+        "Number of times peers have been banned for a low reputation",
+        &["network_id"]
+    )
+    .unwrap()
+});
+
+pub fn peer_bans(network_id: &NetworkId) -> IntCounter {
+    APTOS_NETWORK_PEER_BANS.with_label_values(&[network_id.as_str()])
+}
+
 pub static APTOS_NETWORK_DIRECT_SEND_MESSAGES: Lazy<IntCounterVec> = Lazy::new(|| {
     register_int_counter_vec!(
         "aptos_network_direct_send_messages",
@@ -415,6 +459,15 @@ pub static PENDING_MULTIPLEX_STREAM: Lazy<IntGauge> = Lazy::new(|| {
     .unwrap()
 });
 
+/// Counter of messages read from the substreams of a connection pending in queue to be handled
+pub static PENDING_SUBSTREAM_MESSAGES: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!(
+        "aptos_network_pending_substream_messages",
+        "Number of pending messages read from substreams"
+    )
+    .unwrap()
+});
+
 /// Counter of pending requests in Direct Send
 pub static PENDING_DIRECT_SEND_REQUESTS: Lazy<IntGauge> = Lazy::new(|| {
     register_int_gauge!(
@@ -557,3 +610,34 @@ pub fn network_application_outbound_traffic(
         ])
         .observe(size as f64);
 }
+
+/// Counters for the messages recorded by the network traffic capture
+pub static TRAFFIC_CAPTURED_MESSAGES: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_network_traffic_captured_messages",
+        "Number of messages recorded by the network traffic capture",
+        &["network_id", "direction"]
+    )
+    .unwrap()
+});
+
+/// Counters for the messages dropped by the network traffic capture (e.g., because the
+/// capture writer fell behind)
+pub static TRAFFIC_CAPTURE_DROPPED_MESSAGES: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_network_traffic_capture_dropped_messages",
+        "Number of messages dropped by the network traffic capture",
+        &["network_id"]
+    )
+    .unwrap()
+});
+
+/// Counters for the errors encountered when writing the network traffic capture
+pub static TRAFFIC_CAPTURE_ERRORS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_network_traffic_capture_errors",
+        "Number of errors encountered when writing the network traffic capture",
+        &["network_id"]
+    )
+    .unwrap()
+});
@@ -11,6 +11,7 @@
 
 use aptos_crypto::{noise, x25519};
 use aptos_logger::prelude::*;
+use aptos_netcore::transport::{quic::QuicStreams, Substreams};
 use futures::{
     io::{AsyncRead, AsyncWrite},
     ready,
@@ -362,6 +363,15 @@ where
 // ---------------------
 //
 
+impl<TSocket> Substreams for NoiseStream<TSocket>
+where
+    TSocket: Substreams,
+{
+    fn substreams(&self) -> Option<QuicStreams> {
+        self.socket.substreams()
+    }
+}
+
 impl<TSocket> AsyncRead for NoiseStream<TSocket>
 where
     TSocket: AsyncRead + Unpin,
@@ -21,22 +21,30 @@ use crate::{
         RECEIVED_LABEL, SENT_LABEL,
     },
     logging::NetworkSchema,
+    peer::scheduler::{OutboundScheduler, Scheduled},
     peer_manager::{PeerManagerError, TransportNotification},
     protocols::{
         direct_send::Message,
         rpc::{InboundRpcRequest, InboundRpcs, OutboundRpcRequest, OutboundRpcs},
         stream::{InboundStreamBuffer, OutboundStream, StreamMessage},
-        wire::messaging::v1::{
-            DirectSendMsg, ErrorCode, MultiplexMessage, MultiplexMessageSink,
-            MultiplexMessageStream, NetworkMessage, Priority, ReadError, WriteError,
+        wire::{
+            capture::{CaptureDirection, TrafficCapture},
+            messaging::v1::{
+                DirectSendMsg, ErrorCode, MultiplexMessage, MultiplexMessageSink,
+                MultiplexMessageStream, NetworkMessage, Priority, ReadError, WriteError,
+            },
         },
     },
     transport::{self, Connection, ConnectionMetadata},
     ProtocolId,
 };
 use aptos_channels::aptos_channel;
-use aptos_config::network_id::NetworkContext;
+use aptos_config::{config::OutboundQosConfig, network_id::NetworkContext};
 use aptos_logger::prelude::*;
+use aptos_netcore::transport::{
+    quic::{QuicRecvStream, QuicStreams},
+    Substreams,
+};
 use aptos_short_hex_str::AsShortHexStr;
 use aptos_time_service::{TimeService, TimeServiceTrait};
 use aptos_types::PeerId;
@@ -44,18 +52,26 @@ use bytes::Bytes;
 use futures::{
     self,
     channel::oneshot,
+    future::{self, FutureExt},
     io::{AsyncRead, AsyncWrite},
     stream::StreamExt,
-    SinkExt,
+    Sink, SinkExt,
 };
 use futures_util::stream::select;
 use serde::Serialize;
-use std::{fmt, panic, time::Duration};
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    fmt, panic,
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
 use tokio::runtime::Handle;
 use tokio_util::compat::{
     FuturesAsyncReadCompatExt, TokioAsyncReadCompatExt, TokioAsyncWriteCompatExt,
 };
 
+mod scheduler;
 #[cfg(test)]
 mod test;
 
@@ -71,6 +87,11 @@ pub enum PeerRequest {
     SendDirectSend(Message),
 }
 
+/// A message for the writer task, with the protocol it belongs to so that it can be written on the
+/// protocol's own stream when the connection has substreams. Only connection-level error messages
+/// don't belong to any protocol.
+pub type WriteRequest = (Option<ProtocolId>, NetworkMessage);
+
 /// Notifications that [`Peer`] sends to the [`PeerManager`](crate::peer_manager::PeerManager).
 #[derive(Debug, PartialEq)]
 pub enum PeerNotification {
@@ -138,11 +159,15 @@ pub struct Peer<TSocket> {
     max_message_size: usize,
     /// Inbound stream buffer
     inbound_stream: InboundStreamBuffer,
+    /// Scheduling of the outbound messages between the classes of traffic
+    outbound_qos_config: OutboundQosConfig,
+    /// Capture of the messages exchanged with the peer (if enabled)
+    traffic_capture: Option<TrafficCapture>,
 }
 
 impl<TSocket> Peer<TSocket>
 where
-    TSocket: AsyncRead + AsyncWrite + Send + 'static,
+    TSocket: AsyncRead + AsyncWrite + Substreams + Send + 'static,
 {
     #[allow(clippy::too_many_arguments)]
     pub fn new(
@@ -158,6 +183,8 @@ where
         max_concurrent_outbound_rpcs: u32,
         max_frame_size: usize,
         max_message_size: usize,
+        outbound_qos_config: OutboundQosConfig,
+        traffic_capture: Option<TrafficCapture>,
     ) -> Self {
         let Connection {
             metadata: connection_metadata,
@@ -191,6 +218,8 @@ where
             max_frame_size,
             max_message_size,
             inbound_stream: InboundStreamBuffer::new(max_fragments),
+            outbound_qos_config,
+            traffic_capture,
         }
     }
 
@@ -209,13 +238,31 @@ where
         );
 
         // Split the connection into a ReadHalf and a WriteHalf.
-        let (read_socket, write_socket) =
-            tokio::io::split(self.connection.take().unwrap().compat());
+        let socket = self.connection.take().unwrap();
+        let substreams = socket.substreams();
+        let (read_socket, write_socket) = tokio::io::split(socket.compat());
 
         let mut reader =
             MultiplexMessageStream::new(read_socket.compat(), self.max_frame_size).fuse();
         let writer = MultiplexMessageSink::new(write_socket.compat_write(), self.max_frame_size);
 
+        // Messages read from the substreams of the connection, if it has any. Those are always
+        // complete messages, as the fragments of streamed messages are reassembled per substream.
+        let (substream_msgs_tx, substream_msgs_rx) =
+            aptos_channels::new(1024, &counters::PENDING_SUBSTREAM_MESSAGES);
+        let mut substream_reader = substream_msgs_rx.fuse();
+        if let Some(substreams) = substreams.clone() {
+            Self::start_substream_reader_task(
+                &self.executor,
+                self.connection_metadata.clone(),
+                self.network_context,
+                substreams,
+                substream_msgs_tx,
+                self.max_frame_size,
+                self.max_message_size,
+            );
+        }
+
         // Start writer "process" as a separate task. We receive two handles to
         // communicate with the task:
         //   1. `write_reqs_tx`: Queue of pending NetworkMessages to write.
@@ -226,6 +273,9 @@ where
             self.connection_metadata.clone(),
             self.network_context,
             writer,
+            substreams,
+            self.outbound_qos_config,
+            self.traffic_capture.clone(),
             self.max_frame_size,
             self.max_message_size,
         );
@@ -267,6 +317,23 @@ where
                         None => self.shutdown(DisconnectReason::ConnectionLost),
                     }
                 },
+                // Handle a new inbound message read from one of the substreams. The connection
+                // is only considered lost once the socket itself is closed.
+                maybe_message = substream_reader.next() => {
+                    if let Some(message) = maybe_message {
+                        if let Err(err) = self.handle_inbound_message(message, &mut write_reqs_tx).await {
+                            warn!(
+                                NetworkSchema::new(&self.network_context)
+                                    .connection_metadata(&self.connection_metadata),
+                                error = %err,
+                                "{} Error in handling inbound message from peer: {}, error: {}",
+                                self.network_context,
+                                remote_peer_id.short_str(),
+                                err
+                            );
+                        }
+                    }
+                },
                 // Drive the queue of pending inbound rpcs. When one is fulfilled
                 // by an upstream protocol, send the response to the remote peer.
                 maybe_response = self.inbound_rpcs.next_completed_response() => {
@@ -298,33 +365,157 @@ where
     // 2. The second channel is used to instruct the task to close the connection and terminate.
     // If outbound messages are queued when the task receives a close instruction, it discards
     // them and immediately closes the connection.
+    // The messages are written in the order picked by the `OutboundScheduler`, which shares the
+    // connection between the classes of traffic according to `outbound_qos_config`.
+    // If the connection has substreams, the messages of every protocol are written on a separate
+    // substream, opened on the first message of the protocol, so that they don't block each other.
+    // The scheduler skips the classes whose writer has no room for their next message, so the task
+    // never waits on a full writer while another one could make progress.
+    #[allow(clippy::too_many_arguments)]
     fn start_writer_task(
         executor: &Handle,
         time_service: TimeService,
         connection_metadata: ConnectionMetadata,
         network_context: NetworkContext,
-        mut writer: MultiplexMessageSink<impl AsyncWrite + Unpin + Send + 'static>,
+        writer: MultiplexMessageSink<impl AsyncWrite + Unpin + Send + 'static>,
+        substreams: Option<QuicStreams>,
+        outbound_qos_config: OutboundQosConfig,
+        traffic_capture: Option<TrafficCapture>,
         max_frame_size: usize,
         max_message_size: usize,
-    ) -> (aptos_channels::Sender<NetworkMessage>, oneshot::Sender<()>) {
+    ) -> (aptos_channels::Sender<WriteRequest>, oneshot::Sender<()>) {
         let remote_peer_id = connection_metadata.remote_peer_id;
-        let (write_reqs_tx, mut write_reqs_rx): (aptos_channels::Sender<NetworkMessage>, _) =
+        let (write_reqs_tx, mut write_reqs_rx): (aptos_channels::Sender<WriteRequest>, _) =
             aptos_channels::new(1024, &counters::PENDING_WIRE_MESSAGES);
         let (close_tx, mut close_rx) = oneshot::channel();
 
-        let (mut msg_tx, msg_rx) = aptos_channels::new(1024, &counters::PENDING_MULTIPLEX_MESSAGE);
-        let (stream_msg_tx, stream_msg_rx) =
-            aptos_channels::new(1024, &counters::PENDING_MULTIPLEX_STREAM);
+        let mut socket_writer = StreamWriter::start(
+            executor,
+            time_service.clone(),
+            connection_metadata.clone(),
+            network_context,
+            writer,
+            max_frame_size,
+            max_message_size,
+        );
+        let executor_clone = executor.clone();
+        let multiplex_task = async move {
+            let mut scheduler =
+                OutboundScheduler::new(network_context, outbound_qos_config, time_service.clone());
+            let mut substream_writers: HashMap<ProtocolId, StreamWriter> = HashMap::new();
+            // The protocols whose writer had no room for the next message of their class
+            let mut blocked_protocols: Vec<Option<ProtocolId>> = Vec::new();
+            loop {
+                if !matches!(close_rx.try_recv(), Ok(None)) {
+                    break;
+                }
+                // Hand all the pending requests over to the scheduler, so that it picks the next
+                // message among them
+                while !scheduler.is_full() {
+                    match write_reqs_rx.next().now_or_never() {
+                        Some(Some(request)) => scheduler.push(request),
+                        _ => break,
+                    }
+                }
 
-        // this task ends when the multiplex task ends (by dropping the senders)
-        let writer_task = async move {
-            let mut stream = select(msg_rx, stream_msg_rx);
-            let log_context =
-                NetworkSchema::new(&network_context).connection_metadata(&connection_metadata);
-            while let Some(message) = stream.next().await {
-                if let Err(err) = writer.send(&message).await {
+                blocked_protocols.clear();
+                let has_substreams = substreams.is_some();
+                let scheduled = future::poll_fn(|cx| {
+                    Poll::Ready(scheduler.next_message(|protocol_id| {
+                        let is_writable = poll_writer_ready(
+                            &mut socket_writer,
+                            &mut substream_writers,
+                            has_substreams,
+                            protocol_id,
+                            cx,
+                        );
+                        if !is_writable {
+                            blocked_protocols.push(protocol_id);
+                        }
+                        is_writable
+                    }))
+                })
+                .await;
+                let (protocol_id, message) = match scheduled {
+                    Scheduled::Ready(request) => request,
+                    scheduled => {
+                        // Wait for a new request, a rate limit to refill or a writer to have room
+                        let throttled_until = match scheduled {
+                            Scheduled::Throttled(until) => Some(until),
+                            Scheduled::Blocked(until) => until,
+                            _ => None,
+                        };
+                        let throttle = match throttled_until {
+                            Some(until) => time_service.sleep_until(until).left_future(),
+                            None => future::pending().right_future(),
+                        };
+                        let writable = future::poll_fn(|cx| {
+                            let is_writable = blocked_protocols.iter().any(|protocol_id| {
+                                poll_writer_ready(
+                                    &mut socket_writer,
+                                    &mut substream_writers,
+                                    has_substreams,
+                                    *protocol_id,
+                                    cx,
+                                )
+                            });
+                            if is_writable {
+                                Poll::Ready(())
+                            } else {
+                                Poll::Pending
+                            }
+                        });
+                        if scheduler.is_full() {
+                            futures::select! {
+                                _ = throttle.fuse() => {},
+                                _ = writable.fuse() => {},
+                                _ = close_rx => break,
+                            }
+                        } else {
+                            futures::select! {
+                                request = write_reqs_rx.select_next_some() => scheduler.push(request),
+                                _ = throttle.fuse() => {},
+                                _ = writable.fuse() => {},
+                                _ = close_rx => break,
+                            }
+                        }
+                        continue;
+                    },
+                };
+                let writer = match (&substreams, protocol_id) {
+                    (Some(substreams), Some(protocol_id)) => {
+                        match substream_writers.entry(protocol_id) {
+                            Entry::Occupied(entry) => entry.into_mut(),
+                            Entry::Vacant(entry) => match substreams.open_stream().await {
+                                Ok(stream) => entry.insert(StreamWriter::start(
+                                    &executor_clone,
+                                    time_service.clone(),
+                                    connection_metadata.clone(),
+                                    network_context,
+                                    MultiplexMessageSink::new(stream, max_frame_size),
+                                    max_frame_size,
+                                    max_message_size,
+                                )),
+                                Err(err) => {
+                                    warn!(
+                                        error = %err,
+                                        "{} Error in opening a substream for protocol {} to peer: {}, falling back to the socket",
+                                        network_context,
+                                        protocol_id,
+                                        remote_peer_id.short_str(),
+                                    );
+                                    &mut socket_writer
+                                },
+                            },
+                        }
+                    },
+                    _ => &mut socket_writer,
+                };
+                if let Some(traffic_capture) = &traffic_capture {
+                    traffic_capture.capture(remote_peer_id, CaptureDirection::Outbound, &message);
+                }
+                if let Err(err) = writer.write(message).await {
                     warn!(
-                        log_context,
                         error = %err,
                         "{} Error in sending message to peer: {}",
                         network_context,
@@ -332,85 +523,46 @@ where
                     );
                 }
             }
-            info!(
-                log_context,
-                "{} Closing connection to peer: {}",
-                network_context,
-                remote_peer_id.short_str()
-            );
-            let flush_and_close = async {
-                writer.flush().await?;
-                writer.close().await?;
-                Ok(()) as Result<(), WriteError>
-            };
-            match time_service
-                .timeout(transport::TRANSPORT_TIMEOUT, flush_and_close)
-                .await
-            {
-                Err(_) => {
-                    info!(
-                        log_context,
-                        "{} Timeout in flush/close of connection to peer: {}",
-                        network_context,
-                        remote_peer_id.short_str()
-                    );
-                },
-                Ok(Err(err)) => {
-                    info!(
-                        log_context,
-                        error = %err,
-                        "{} Failure in flush/close of connection to peer: {}, error: {}",
-                        network_context,
-                        remote_peer_id.short_str(),
-                        err
-                    );
-                },
-                Ok(Ok(())) => {
-                    info!(
-                        log_context,
-                        "{} Closed connection to peer: {}",
-                        network_context,
-                        remote_peer_id.short_str()
-                    );
-                },
-            }
-        };
-        let multiplex_task = async move {
-            let mut outbound_stream =
-                OutboundStream::new(max_frame_size, max_message_size, stream_msg_tx);
-            loop {
-                futures::select! {
-                    message = write_reqs_rx.select_next_some() => {
-                        // either channel full would block the other one
-                        let result = if outbound_stream.should_stream(&message) {
-                            outbound_stream.stream_message(message).await
-                        } else {
-                            msg_tx.send(MultiplexMessage::Message(message)).await.map_err(|_| anyhow::anyhow!("Writer task ended"))
-                        };
-                        if let Err(err) = result {
-                            warn!(
-                                error = %err,
-                                "{} Error in sending message to peer: {}",
-                                network_context,
-                                remote_peer_id.short_str(),
-                            );
-                        }
-                    },
-                    _ = close_rx => {
-                        break;
-                    }
-                }
-            }
         };
-        executor.spawn(writer_task);
         executor.spawn(multiplex_task);
         (write_reqs_tx, close_tx)
     }
 
+    // Start a task accepting the substreams opened by the remote peer, and a task per substream
+    // reading its messages, which are sent to the returned channel.
+    fn start_substream_reader_task(
+        executor: &Handle,
+        connection_metadata: ConnectionMetadata,
+        network_context: NetworkContext,
+        substreams: QuicStreams,
+        substream_msgs_tx: aptos_channels::Sender<Result<MultiplexMessage, ReadError>>,
+        max_frame_size: usize,
+        max_message_size: usize,
+    ) {
+        let executor_clone = executor.clone();
+        // this task ends when the connection is closed
+        let accept_task = async move {
+            while let Ok(stream) = substreams.accept_stream().await {
+                executor_clone.spawn(read_substream(
+                    connection_metadata.clone(),
+                    network_context,
+                    stream,
+                    substream_msgs_tx.clone(),
+                    max_frame_size,
+                    max_message_size,
+                ));
+            }
+        };
+        executor.spawn(accept_task);
+    }
+
     async fn handle_inbound_network_message(
         &mut self,
         message: NetworkMessage,
     ) -> Result<(), PeerManagerError> {
+        if let Some(traffic_capture) = &self.traffic_capture {
+            traffic_capture.capture(self.remote_peer_id(), CaptureDirection::Inbound, &message);
+        }
         match message {
             NetworkMessage::DirectSendMsg(message) => self.handle_inbound_direct_send(message),
             NetworkMessage::Error(error_msg) => {
@@ -466,7 +618,7 @@ where
     async fn handle_inbound_message(
         &mut self,
         message: Result<MultiplexMessage, ReadError>,
-        write_reqs_tx: &mut aptos_channels::Sender<NetworkMessage>,
+        write_reqs_tx: &mut aptos_channels::Sender<WriteRequest>,
     ) -> Result<(), PeerManagerError> {
         trace!(
             NetworkSchema::new(&self.network_context)
@@ -488,7 +640,7 @@ where
                     let error_code = ErrorCode::parsing_error(*message_type, *protocol_id);
                     let message = NetworkMessage::Error(error_code);
 
-                    write_reqs_tx.send(message).await?;
+                    write_reqs_tx.send((None, message)).await?;
                     return Err(err.into());
                 },
                 ReadError::IoError(_) => {
@@ -547,7 +699,7 @@ where
     async fn handle_outbound_request(
         &mut self,
         request: PeerRequest,
-        write_reqs_tx: &mut aptos_channels::Sender<NetworkMessage>,
+        write_reqs_tx: &mut aptos_channels::Sender<WriteRequest>,
     ) {
         trace!(
             "Peer {} PeerRequest::{:?}",
@@ -571,7 +723,7 @@ where
                     raw_msg: Vec::from(message.mdata.as_ref()),
                 });
 
-                match write_reqs_tx.send(message).await {
+                match write_reqs_tx.send((Some(protocol_id), message)).await {
                     Ok(_) => {
                         counters::direct_send_messages(&self.network_context, SENT_LABEL).inc();
                         counters::direct_send_bytes(&self.network_context, SENT_LABEL)
@@ -668,3 +820,181 @@ where
         );
     }
 }
+
+/// Number of messages buffered by a `StreamWriter` ahead of its writer task. Kept small, as the
+/// messages are ordered by the scheduler before reaching the writer.
+const MAX_BUFFERED_WRITES: usize = 16;
+
+/// Writes the messages sent to it on a socket or substream, streaming the large ones.
+struct StreamWriter {
+    msg_tx: aptos_channels::Sender<MultiplexMessage>,
+    outbound_stream: OutboundStream,
+}
+
+impl StreamWriter {
+    fn start(
+        executor: &Handle,
+        time_service: TimeService,
+        connection_metadata: ConnectionMetadata,
+        network_context: NetworkContext,
+        mut writer: MultiplexMessageSink<impl AsyncWrite + Unpin + Send + 'static>,
+        max_frame_size: usize,
+        max_message_size: usize,
+    ) -> Self {
+        let remote_peer_id = connection_metadata.remote_peer_id;
+        let (msg_tx, msg_rx) =
+            aptos_channels::new(MAX_BUFFERED_WRITES, &counters::PENDING_MULTIPLEX_MESSAGE);
+        let (stream_msg_tx, stream_msg_rx) =
+            aptos_channels::new(1024, &counters::PENDING_MULTIPLEX_STREAM);
+
+        // this task ends when the multiplex task ends (by dropping the senders)
+        let writer_task = async move {
+            let mut stream = select(msg_rx, stream_msg_rx);
+            let log_context =
+                NetworkSchema::new(&network_context).connection_metadata(&connection_metadata);
+            while let Some(message) = stream.next().await {
+                if let Err(err) = writer.send(&message).await {
+                    warn!(
+                        log_context,
+                        error = %err,
+                        "{} Error in sending message to peer: {}",
+                        network_context,
+                        remote_peer_id.short_str(),
+                    );
+                }
+            }
+            info!(
+                log_context,
+                "{} Closing connection to peer: {}",
+                network_context,
+                remote_peer_id.short_str()
+            );
+            let flush_and_close = async {
+                writer.flush().await?;
+                writer.close().await?;
+                Ok(()) as Result<(), WriteError>
+            };
+            match time_service
+                .timeout(transport::TRANSPORT_TIMEOUT, flush_and_close)
+                .await
+            {
+                Err(_) => {
+                    info!(
+                        log_context,
+                        "{} Timeout in flush/close of connection to peer: {}",
+                        network_context,
+                        remote_peer_id.short_str()
+                    );
+                },
+                Ok(Err(err)) => {
+                    info!(
+                        log_context,
+                        error = %err,
+                        "{} Failure in flush/close of connection to peer: {}, error: {}",
+                        network_context,
+                        remote_peer_id.short_str(),
+                        err
+                    );
+                },
+                Ok(Ok(())) => {
+                    info!(
+                        log_context,
+                        "{} Closed connection to peer: {}",
+                        network_context,
+                        remote_peer_id.short_str()
+                    );
+                },
+            }
+        };
+        executor.spawn(writer_task);
+
+        Self {
+            msg_tx,
+            outbound_stream: OutboundStream::new(max_frame_size, max_message_size, stream_msg_tx),
+        }
+    }
+
+    /// Whether the writer has room for another message, registering the task to be woken up
+    /// otherwise. The fragments of a streamed message go through a larger channel of their own,
+    /// which isn't accounted for.
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> bool {
+        // A closed channel is ready, for the write to fail right away
+        Pin::new(&mut self.msg_tx).poll_ready(cx).is_ready()
+    }
+
+    async fn write(&mut self, message: NetworkMessage) -> anyhow::Result<()> {
+        // either channel full would block the other one
+        if self.outbound_stream.should_stream(&message) {
+            self.outbound_stream.stream_message(message).await
+        } else {
+            self.msg_tx
+                .send(MultiplexMessage::Message(message))
+                .await
+                .map_err(|_| anyhow::anyhow!("Writer task ended"))
+        }
+    }
+}
+
+/// Polls whether the writer of the given protocol has room for another message. A substream not
+/// opened yet has room.
+fn poll_writer_ready(
+    socket_writer: &mut StreamWriter,
+    substream_writers: &mut HashMap<ProtocolId, StreamWriter>,
+    has_substreams: bool,
+    protocol_id: Option<ProtocolId>,
+    cx: &mut Context<'_>,
+) -> bool {
+    match protocol_id {
+        Some(protocol_id) if has_substreams => substream_writers
+            .get_mut(&protocol_id)
+            .map_or(true, |writer| writer.poll_ready(cx)),
+        _ => socket_writer.poll_ready(cx),
+    }
+}
+
+/// Reads the messages of a substream until the remote peer closes it, reassembling the streamed
+/// ones as the substream has its own sequence of fragments.
+async fn read_substream(
+    connection_metadata: ConnectionMetadata,
+    network_context: NetworkContext,
+    stream: QuicRecvStream,
+    mut substream_msgs_tx: aptos_channels::Sender<Result<MultiplexMessage, ReadError>>,
+    max_frame_size: usize,
+    max_message_size: usize,
+) {
+    let mut reader = MultiplexMessageStream::new(stream, max_frame_size);
+    let mut inbound_stream = InboundStreamBuffer::new(max_message_size / max_frame_size);
+    while let Some(message) = reader.next().await {
+        let message = match message {
+            Ok(MultiplexMessage::Stream(message)) => {
+                let result = match message {
+                    StreamMessage::Header(header) => {
+                        inbound_stream.new_stream(header).map(|_| None)
+                    },
+                    StreamMessage::Fragment(fragment) => inbound_stream.append_fragment(fragment),
+                };
+                match result {
+                    Ok(Some(message)) => Ok(MultiplexMessage::Message(message)),
+                    Ok(None) => continue,
+                    Err(err) => {
+                        warn!(
+                            NetworkSchema::new(&network_context)
+                                .connection_metadata(&connection_metadata),
+                            error = %err,
+                            "{} Error in handling streamed message from peer: {}, error: {}",
+                            network_context,
+                            connection_metadata.remote_peer_id.short_str(),
+                            err
+                        );
+                        continue;
+                    },
+                }
+            },
+            message => message,
+        };
+        if substream_msgs_tx.send(message).await.is_err() {
+            // the Peer is shutting down
+            break;
+        }
+    }
+}
@@ -0,0 +1,588 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Scheduling of the outbound messages of a [`Peer`](crate::peer::Peer) between classes of
+//! traffic.
+//!
+//! Messages are queued per [`TrafficClass`] and dequeued by deficit round robin: on each round,
+//! every class with queued messages may send up to its weight times [`QUANTUM_BYTES`], carrying
+//! over what it couldn't use for a message too large to fit. A class can additionally be limited
+//! to a number of bytes/s, in which case it is skipped until its token bucket has refilled. A
+//! class is also skipped while the writer of its next message has no room for it, so that a slow
+//! substream doesn't hold back the other classes.
+
+use crate::{
+    counters, peer::WriteRequest, protocols::wire::messaging::v1::NetworkMessage, ProtocolId,
+};
+use aptos_config::{
+    config::{OutboundQosConfig, TrafficClassConfig},
+    network_id::NetworkContext,
+};
+use aptos_rate_limiter::rate_limit::Bucket;
+use aptos_time_service::{TimeService, TimeServiceTrait};
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+/// Maximum number of messages queued in the scheduler. Once reached, the writer stops taking new
+/// write requests, so that the senders are back pressured.
+pub const MAX_QUEUED_MESSAGES: usize = 1024;
+
+/// Number of bytes a class may send per round, for each unit of its weight
+const QUANTUM_BYTES: usize = 64 * 1024;
+
+/// The classes of traffic the outbound bandwidth is shared between, from the highest to the lowest
+/// default weight.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum TrafficClass {
+    /// Consensus and consensus observer messages
+    Consensus = 0,
+    /// Quorum store messages, sent over their own protocols so that the (large) batches don't
+    /// compete with the consensus messages
+    QuorumStore = 1,
+    Mempool = 2,
+    StateSync = 3,
+    PeerMonitoring = 4,
+}
+
+impl TrafficClass {
+    const ALL: [TrafficClass; 5] = [
+        TrafficClass::Consensus,
+        TrafficClass::QuorumStore,
+        TrafficClass::Mempool,
+        TrafficClass::StateSync,
+        TrafficClass::PeerMonitoring,
+    ];
+
+    pub fn from_protocol(protocol_id: ProtocolId) -> Self {
+        use ProtocolId::*;
+        match protocol_id {
+            ConsensusRpcBcs
+            | ConsensusDirectSendBcs
+            | ConsensusDirectSendJson
+            | ConsensusRpcJson
+            | ConsensusRpcCompressed
+            | ConsensusDirectSendCompressed
+            | ConsensusObserver
+            | ConsensusObserverRpc
+            | ConsensusRpcZstd
+            | ConsensusDirectSendZstd
+            | ConsensusRpcZstdDictionary
+            | ConsensusDirectSendZstdDictionary => TrafficClass::Consensus,
+            QuorumStoreRpcCompressed | QuorumStoreDirectSendCompressed => TrafficClass::QuorumStore,
+            MempoolDirectSend
+            | MempoolRpc
+            | MempoolDirectSendZstd
+            | MempoolDirectSendZstdDictionary => TrafficClass::Mempool,
+            StateSyncDirectSend | StorageServiceRpc => TrafficClass::StateSync,
+            DiscoveryDirectSend | HealthCheckerRpc | PeerMonitoringServiceRpc => {
+                TrafficClass::PeerMonitoring
+            },
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            TrafficClass::Consensus => "consensus",
+            TrafficClass::QuorumStore => "quorum_store",
+            TrafficClass::Mempool => "mempool",
+            TrafficClass::StateSync => "state_sync",
+            TrafficClass::PeerMonitoring => "peer_monitoring",
+        }
+    }
+
+    fn config(self, config: &OutboundQosConfig) -> TrafficClassConfig {
+        match self {
+            TrafficClass::Consensus => config.consensus,
+            TrafficClass::QuorumStore => config.quorum_store,
+            TrafficClass::Mempool => config.mempool,
+            TrafficClass::StateSync => config.state_sync,
+            TrafficClass::PeerMonitoring => config.peer_monitoring,
+        }
+    }
+}
+
+/// The outcome of asking the scheduler for the next message to write.
+#[derive(Debug)]
+pub enum Scheduled {
+    /// The next message to write
+    Ready(WriteRequest),
+    /// All the queued messages are held back by the rate limits, until the given time (of the
+    /// time service)
+    Throttled(Instant),
+    /// The queued messages wait for their writers to have room for them. Some of them may also be
+    /// held back by the rate limits, until the given time.
+    Blocked(Option<Instant>),
+    /// No message is queued
+    Idle,
+}
+
+/// The queue of a single class of traffic
+struct ClassQueue {
+    quantum: usize,
+    /// Number of bytes the class may still send in the current round
+    deficit: usize,
+    messages: VecDeque<(ProtocolId, NetworkMessage)>,
+    bucket: Option<Bucket>,
+    /// The time (of the time service) until which the class is held back by its rate limit
+    throttled_until: Option<Instant>,
+}
+
+impl ClassQueue {
+    fn new(
+        network_context: &NetworkContext,
+        class: TrafficClass,
+        config: TrafficClassConfig,
+    ) -> Self {
+        let bucket = config.byte_rate_limit.map(|rate| {
+            let rate = rate.max(1);
+            Bucket::new(
+                "outbound_qos".to_string(),
+                network_context.to_string(),
+                class.as_str().to_string(),
+                rate,
+                rate,
+                rate,
+                None,
+            )
+        });
+        Self {
+            quantum: (config.weight.max(1) as usize).saturating_mul(QUANTUM_BYTES),
+            deficit: 0,
+            messages: VecDeque::new(),
+            bucket,
+            throttled_until: None,
+        }
+    }
+
+    /// Takes the tokens for a message of the given size out of the bucket, or returns how long it
+    /// takes until enough of them are available. The buckets refill on the system clock, so only
+    /// the wait is handed over to the time service.
+    fn acquire_tokens(&mut self, size: usize) -> Result<(), Duration> {
+        let bucket = match self.bucket.as_mut() {
+            Some(bucket) => bucket,
+            None => return Ok(()),
+        };
+        let refill_time = match bucket.acquire_all_tokens(size) {
+            Ok(()) => return Ok(()),
+            Err(Some(time)) => time,
+            // The message is larger than the bucket, so it would never make it through at once.
+            // Let it through whenever there are tokens, emptying the bucket.
+            Err(None) => match bucket.acquire_tokens(size) {
+                Ok(_) => return Ok(()),
+                Err(time) => time,
+            },
+        };
+        Err(refill_time.saturating_duration_since(Instant::now()))
+    }
+}
+
+/// Queues the outbound messages of a connection, and picks the next one to write.
+pub struct OutboundScheduler {
+    network_context: NetworkContext,
+    time_service: TimeService,
+    enabled: bool,
+    /// Messages written ahead of the classes and in FIFO order: the connection-level messages
+    /// without a protocol, and all the messages when the scheduling is disabled.
+    unscheduled: VecDeque<WriteRequest>,
+    /// The queues, indexed by `TrafficClass`
+    classes: Vec<ClassQueue>,
+    /// The class currently visited by the round robin
+    current: usize,
+    /// Whether the current class has already received its quantum for this visit
+    topped_up: bool,
+    num_queued: usize,
+}
+
+impl OutboundScheduler {
+    pub fn new(
+        network_context: NetworkContext,
+        config: OutboundQosConfig,
+        time_service: TimeService,
+    ) -> Self {
+        let classes = TrafficClass::ALL
+            .iter()
+            .map(|class| ClassQueue::new(&network_context, *class, class.config(&config)))
+            .collect();
+        Self {
+            network_context,
+            time_service,
+            enabled: config.enabled,
+            unscheduled: VecDeque::new(),
+            classes,
+            current: 0,
+            topped_up: false,
+            num_queued: 0,
+        }
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.num_queued >= MAX_QUEUED_MESSAGES
+    }
+
+    pub fn push(&mut self, request: WriteRequest) {
+        if let Some(protocol_id) = request.0 {
+            counters::outbound_queue_depth(&self.network_context, protocol_id).inc();
+        }
+        self.num_queued += 1;
+        match request {
+            (Some(protocol_id), message) if self.enabled => {
+                let class = TrafficClass::from_protocol(protocol_id);
+                self.classes[class as usize]
+                    .messages
+                    .push_back((protocol_id, message));
+            },
+            request => self.unscheduled.push_back(request),
+        }
+    }
+
+    /// Dequeues the next message to write, if any may be written now. `is_writable` tells whether
+    /// the writer of the given protocol has room for another message.
+    pub fn next_message(
+        &mut self,
+        mut is_writable: impl FnMut(Option<ProtocolId>) -> bool,
+    ) -> Scheduled {
+        let mut blocked = false;
+        if let Some((protocol_id, _)) = self.unscheduled.front() {
+            if is_writable(*protocol_id) {
+                let request = self
+                    .unscheduled
+                    .pop_front()
+                    .expect("The unscheduled queue must not be empty");
+                self.dequeued(request.0);
+                return Scheduled::Ready(request);
+            }
+            blocked = true;
+        }
+
+        let now = self.time_service.now();
+        let mut throttled_until: Option<Instant> = None;
+        // Number of consecutive classes visited that had nothing they could send. A full turn of
+        // those means there's nothing to send at all.
+        let mut idle_visits = 0;
+        while idle_visits < self.classes.len() {
+            let class = &mut self.classes[self.current];
+            let (protocol_id, size) = match class.messages.front() {
+                Some((protocol_id, message)) => (*protocol_id, message.data_len()),
+                None => {
+                    class.deficit = 0;
+                    idle_visits += 1;
+                    self.advance();
+                    continue;
+                },
+            };
+            if let Some(until) = class.throttled_until {
+                if until > now {
+                    throttled_until = Some(throttled_until.map_or(until, |time| time.min(until)));
+                    idle_visits += 1;
+                    self.advance();
+                    continue;
+                }
+                class.throttled_until = None;
+            }
+            if !is_writable(Some(protocol_id)) {
+                // Skipped (keeping its deficit) until its writer has room for the message
+                blocked = true;
+                idle_visits += 1;
+                self.advance();
+                continue;
+            }
+
+            idle_visits = 0;
+            if !self.topped_up {
+                class.deficit = class.deficit.saturating_add(class.quantum);
+                self.topped_up = true;
+            }
+            if size > class.deficit {
+                self.advance();
+                continue;
+            }
+            if let Err(wait) = class.acquire_tokens(size) {
+                // Visited again right away, to be skipped as throttled
+                class.throttled_until = Some(now + wait);
+                continue;
+            }
+
+            class.deficit -= size;
+            let (protocol_id, message) = class
+                .messages
+                .pop_front()
+                .expect("The queue of the class must not be empty");
+            self.dequeued(Some(protocol_id));
+            return Scheduled::Ready((Some(protocol_id), message));
+        }
+
+        match (blocked, throttled_until) {
+            (true, throttled_until) => Scheduled::Blocked(throttled_until),
+            (false, Some(until)) => Scheduled::Throttled(until),
+            (false, None) => Scheduled::Idle,
+        }
+    }
+
+    fn advance(&mut self) {
+        self.current = (self.current + 1) % self.classes.len();
+        self.topped_up = false;
+    }
+
+    fn dequeued(&mut self, protocol_id: Option<ProtocolId>) {
+        if let Some(protocol_id) = protocol_id {
+            counters::outbound_queue_depth(&self.network_context, protocol_id).dec();
+        }
+        self.num_queued -= 1;
+    }
+}
+
+impl Drop for OutboundScheduler {
+    fn drop(&mut self) {
+        // The messages still queued when the connection closes are discarded
+        let protocol_ids = self
+            .unscheduled
+            .iter()
+            .filter_map(|(protocol_id, _)| *protocol_id)
+            .chain(
+                self.classes
+                    .iter()
+                    .flat_map(|class| class.messages.iter().map(|(protocol_id, _)| *protocol_id)),
+            );
+        for protocol_id in protocol_ids {
+            counters::outbound_queue_depth(&self.network_context, protocol_id).dec();
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::protocols::wire::messaging::v1::{DirectSendMsg, ErrorCode};
+
+    fn message(protocol_id: ProtocolId, size: usize) -> WriteRequest {
+        (
+            Some(protocol_id),
+            NetworkMessage::DirectSendMsg(DirectSendMsg {
+                protocol_id,
+                priority: 0,
+                raw_msg: vec![0; size],
+            }),
+        )
+    }
+
+    fn scheduler(config: OutboundQosConfig) -> OutboundScheduler {
+        OutboundScheduler::new(NetworkContext::mock(), config, TimeService::mock())
+    }
+
+    fn next_protocol(scheduler: &mut OutboundScheduler) -> Option<ProtocolId> {
+        match scheduler.next_message(|_| true) {
+            Scheduled::Ready((protocol_id, _)) => protocol_id,
+            scheduled => panic!("Expected a message, got: {:?}", scheduled),
+        }
+    }
+
+    #[test]
+    fn test_weighted_share() {
+        let mut scheduler = scheduler(OutboundQosConfig::default());
+        for _ in 0..40 {
+            scheduler.push(message(ProtocolId::StorageServiceRpc, QUANTUM_BYTES));
+        }
+        for _ in 0..40 {
+            scheduler.push(message(ProtocolId::ConsensusDirectSendBcs, QUANTUM_BYTES));
+        }
+
+        // Every round, consensus sends 16 messages for the 2 of state sync
+        for _ in 0..2 {
+            for _ in 0..16 {
+                assert_eq!(
+                    next_protocol(&mut scheduler),
+                    Some(ProtocolId::ConsensusDirectSendBcs)
+                );
+            }
+            for _ in 0..2 {
+                assert_eq!(
+                    next_protocol(&mut scheduler),
+                    Some(ProtocolId::StorageServiceRpc)
+                );
+            }
+        }
+
+        // Once consensus is done, state sync has the connection to itself
+        for _ in 0..8 {
+            assert_eq!(
+                next_protocol(&mut scheduler),
+                Some(ProtocolId::ConsensusDirectSendBcs)
+            );
+        }
+        for _ in 0..36 {
+            assert_eq!(
+                next_protocol(&mut scheduler),
+                Some(ProtocolId::StorageServiceRpc)
+            );
+        }
+        assert!(matches!(scheduler.next_message(|_| true), Scheduled::Idle));
+    }
+
+    #[test]
+    fn test_consensus_before_quorum_store() {
+        let mut scheduler = scheduler(OutboundQosConfig::default());
+        for _ in 0..40 {
+            scheduler.push(message(
+                ProtocolId::QuorumStoreDirectSendCompressed,
+                QUANTUM_BYTES,
+            ));
+        }
+        for _ in 0..40 {
+            scheduler.push(message(ProtocolId::ConsensusDirectSendBcs, QUANTUM_BYTES));
+        }
+
+        // Every round, consensus is served first and sends 16 messages for the 8 of quorum store
+        for _ in 0..2 {
+            for _ in 0..16 {
+                assert_eq!(
+                    next_protocol(&mut scheduler),
+                    Some(ProtocolId::ConsensusDirectSendBcs)
+                );
+            }
+            for _ in 0..8 {
+                assert_eq!(
+                    next_protocol(&mut scheduler),
+                    Some(ProtocolId::QuorumStoreDirectSendCompressed)
+                );
+            }
+        }
+
+        // Consensus traffic pushed while quorum store is backlogged is still served first
+        scheduler.push(message(ProtocolId::ConsensusRpcBcs, QUANTUM_BYTES));
+        for _ in 0..8 {
+            assert_eq!(
+                next_protocol(&mut scheduler),
+                Some(ProtocolId::ConsensusDirectSendBcs)
+            );
+        }
+        assert_eq!(
+            next_protocol(&mut scheduler),
+            Some(ProtocolId::ConsensusRpcBcs)
+        );
+        for _ in 0..24 {
+            assert_eq!(
+                next_protocol(&mut scheduler),
+                Some(ProtocolId::QuorumStoreDirectSendCompressed)
+            );
+        }
+        assert!(matches!(scheduler.next_message(|_| true), Scheduled::Idle));
+    }
+
+    #[test]
+    fn test_large_message() {
+        let mut scheduler = scheduler(OutboundQosConfig::default());
+        scheduler.push(message(ProtocolId::MempoolDirectSend, 100 * QUANTUM_BYTES));
+        assert_eq!(
+            next_protocol(&mut scheduler),
+            Some(ProtocolId::MempoolDirectSend)
+        );
+        assert!(matches!(scheduler.next_message(|_| true), Scheduled::Idle));
+    }
+
+    #[test]
+    fn test_unscheduled_first() {
+        let mut scheduler = scheduler(OutboundQosConfig::default());
+        scheduler.push(message(ProtocolId::ConsensusRpcBcs, 10));
+        scheduler.push((None, NetworkMessage::Error(ErrorCode::parsing_error(0, 0))));
+        assert_eq!(next_protocol(&mut scheduler), None);
+        assert_eq!(
+            next_protocol(&mut scheduler),
+            Some(ProtocolId::ConsensusRpcBcs)
+        );
+    }
+
+    #[test]
+    fn test_disabled() {
+        let config = OutboundQosConfig {
+            enabled: false,
+            ..OutboundQosConfig::default()
+        };
+        let mut scheduler = scheduler(config);
+        let protocol_ids = [
+            ProtocolId::PeerMonitoringServiceRpc,
+            ProtocolId::StorageServiceRpc,
+            ProtocolId::ConsensusRpcBcs,
+        ];
+        for protocol_id in protocol_ids {
+            scheduler.push(message(protocol_id, 10));
+        }
+        for protocol_id in protocol_ids {
+            assert_eq!(next_protocol(&mut scheduler), Some(protocol_id));
+        }
+    }
+
+    #[test]
+    fn test_rate_limit() {
+        let config = OutboundQosConfig {
+            state_sync: TrafficClassConfig {
+                weight: 1,
+                byte_rate_limit: Some(1000),
+            },
+            ..OutboundQosConfig::default()
+        };
+        let mut scheduler = scheduler(config);
+        scheduler.push(message(ProtocolId::StorageServiceRpc, 600));
+        scheduler.push(message(ProtocolId::StorageServiceRpc, 600));
+        scheduler.push(message(ProtocolId::MempoolDirectSend, 600));
+
+        // The bucket starts full, and only has room for the first message
+        assert_eq!(
+            next_protocol(&mut scheduler),
+            Some(ProtocolId::MempoolDirectSend)
+        );
+        assert_eq!(
+            next_protocol(&mut scheduler),
+            Some(ProtocolId::StorageServiceRpc)
+        );
+        match scheduler.next_message(|_| true) {
+            Scheduled::Throttled(until) => assert!(until > scheduler.time_service.now()),
+            scheduled => panic!("Expected to be throttled, got: {:?}", scheduled),
+        }
+    }
+
+    #[test]
+    fn test_blocked_writer() {
+        let mut scheduler = scheduler(OutboundQosConfig::default());
+        scheduler.push(message(ProtocolId::ConsensusDirectSendBcs, 10));
+        scheduler.push(message(ProtocolId::ConsensusDirectSendBcs, 10));
+        scheduler.push(message(ProtocolId::MempoolDirectSend, 10));
+
+        // Consensus is skipped while its writer is full, and mempool goes first
+        let consensus_blocked = |protocol_id: Option<ProtocolId>| {
+            protocol_id != Some(ProtocolId::ConsensusDirectSendBcs)
+        };
+        match scheduler.next_message(consensus_blocked) {
+            Scheduled::Ready((protocol_id, _)) => {
+                assert_eq!(protocol_id, Some(ProtocolId::MempoolDirectSend))
+            },
+            scheduled => panic!("Expected a message, got: {:?}", scheduled),
+        }
+        assert!(matches!(
+            scheduler.next_message(consensus_blocked),
+            Scheduled::Blocked(None)
+        ));
+
+        // Once its writer has room again, consensus is sent
+        for _ in 0..2 {
+            assert_eq!(
+                next_protocol(&mut scheduler),
+                Some(ProtocolId::ConsensusDirectSendBcs)
+            );
+        }
+        assert!(matches!(scheduler.next_message(|_| true), Scheduled::Idle));
+    }
+
+    #[test]
+    fn test_is_full() {
+        let mut scheduler = scheduler(OutboundQosConfig::default());
+        for _ in 0..MAX_QUEUED_MESSAGES {
+            assert!(!scheduler.is_full());
+            scheduler.push(message(ProtocolId::MempoolDirectSend, 10));
+        }
+        assert!(scheduler.is_full());
+        next_protocol(&mut scheduler);
+        assert!(!scheduler.is_full());
+    }
+}
@@ -24,9 +24,12 @@ use crate::{
     ProtocolId,
 };
 use aptos_channels::{self, aptos_channel, message_queues::QueueStyle};
-use aptos_config::{config::PeerRole, network_id::NetworkContext};
+use aptos_config::{
+    config::{OutboundQosConfig, PeerRole},
+    network_id::NetworkContext,
+};
 use aptos_memsocket::MemorySocket;
-use aptos_netcore::transport::ConnectionOrigin;
+use aptos_netcore::transport::{quic::QuicTransport, ConnectionOrigin, Substreams, Transport};
 use aptos_time_service::{MockTimeService, TimeService};
 use aptos_types::{network_address::NetworkAddress, PeerId};
 use bytes::Bytes;
@@ -57,6 +60,26 @@ fn build_test_peer(
     aptos_channel::Receiver<ProtocolId, PeerNotification>,
 ) {
     let (a, b) = MemorySocket::new_pair();
+    let (peer, peer_handle, connection_notifs_rx, peer_notifs_rx) =
+        build_test_peer_with_socket(executor, time_service, origin, a);
+
+    (peer, peer_handle, b, connection_notifs_rx, peer_notifs_rx)
+}
+
+fn build_test_peer_with_socket<TSocket>(
+    executor: Handle,
+    time_service: TimeService,
+    origin: ConnectionOrigin,
+    socket: TSocket,
+) -> (
+    Peer<TSocket>,
+    PeerHandle,
+    aptos_channels::Receiver<TransportNotification<TSocket>>,
+    aptos_channel::Receiver<ProtocolId, PeerNotification>,
+)
+where
+    TSocket: AsyncRead + AsyncWrite + Substreams + Send + 'static,
+{
     let peer_id = PeerId::random();
     let connection = Connection {
         metadata: ConnectionMetadata::new(
@@ -68,7 +91,7 @@ fn build_test_peer(
             ProtocolIdSet::empty(),
             PeerRole::Unknown,
         ),
-        socket: a,
+        socket,
     };
 
     let (connection_notifs_tx, connection_notifs_rx) = aptos_channels::new_test(1);
@@ -90,10 +113,12 @@ fn build_test_peer(
         MAX_CONCURRENT_OUTBOUND_RPCS,
         MAX_FRAME_SIZE,
         MAX_MESSAGE_SIZE,
+        OutboundQosConfig::default(),
+        None,
     );
     let peer_handle = PeerHandle(peer_reqs_tx);
 
-    (peer, peer_handle, b, connection_notifs_rx, peer_notifs_rx)
+    (peer, peer_handle, connection_notifs_rx, peer_notifs_rx)
 }
 
 fn build_test_connected_peers(
@@ -947,3 +972,103 @@ fn peers_send_multiplex() {
 
     rt.block_on(future::join3(peer_a.start(), peer_b.start(), test));
 }
+
+// With a QUIC connection, the messages of every protocol are sent and received on a substream of
+// their own, while the socket keeps working.
+#[test]
+fn peer_send_recv_message_quic_substreams() {
+    ::aptos_logger::Logger::init_for_testing();
+    let rt = Runtime::new().unwrap();
+    let protocols = [
+        ProtocolId::ConsensusDirectSendBcs,
+        ProtocolId::MempoolDirectSend,
+    ];
+    let direct_send_msg = |protocol_id: ProtocolId, data: &str| {
+        MultiplexMessage::Message(NetworkMessage::DirectSendMsg(DirectSendMsg {
+            protocol_id,
+            priority: 0,
+            raw_msg: Vec::from(data),
+        }))
+    };
+    let recv_msg = |protocol_id: ProtocolId, data: &str| {
+        PeerNotification::RecvMessage(Message {
+            protocol_id,
+            mdata: Bytes::from(data.to_owned()),
+        })
+    };
+
+    let (_listener, remote_socket, socket) = rt.block_on(async {
+        let transport = QuicTransport::default();
+        let (mut listener, addr) = transport
+            .listen_on("/ip4/127.0.0.1/udp/0/quic".parse().unwrap())
+            .unwrap();
+        let mut remote_socket = transport
+            .dial(PeerId::random(), addr)
+            .unwrap()
+            .await
+            .unwrap();
+        // The listener only sees the socket once the dialer writes to it
+        MultiplexMessageSink::new(&mut remote_socket, MAX_FRAME_SIZE)
+            .send(&direct_send_msg(PROTOCOL, "hello socket"))
+            .await
+            .unwrap();
+        let (inbound, _addr) = listener.next().await.unwrap().unwrap();
+        (listener, remote_socket, inbound.await.unwrap())
+    });
+    let remote_substreams = remote_socket.substreams().unwrap();
+    let (peer, mut peer_handle, _connection_notifs_rx, mut peer_notifs_rx) =
+        build_test_peer_with_socket(
+            rt.handle().clone(),
+            TimeService::mock(),
+            ConnectionOrigin::Inbound,
+            socket,
+        );
+
+    let test = async move {
+        // The message written on the socket is received
+        let received = peer_notifs_rx.next().await.unwrap();
+        assert_eq!(received, recv_msg(PROTOCOL, "hello socket"));
+
+        // Messages of both protocols are sent, interleaved
+        for _ in 0..10 {
+            for protocol_id in protocols {
+                peer_handle.send_direct_send(Message {
+                    protocol_id,
+                    mdata: Bytes::from("hello world"),
+                });
+            }
+        }
+
+        // Every protocol has its own substream, carrying all of its messages
+        let mut substream_protocols = HashSet::new();
+        for _ in protocols {
+            let stream = remote_substreams.accept_stream().await.unwrap();
+            let mut stream = MultiplexMessageStream::new(stream, MAX_FRAME_SIZE);
+            let protocol_id = match stream.next().await.unwrap().unwrap() {
+                MultiplexMessage::Message(NetworkMessage::DirectSendMsg(msg)) => msg.protocol_id,
+                msg => panic!("Unexpected message: {:?}", msg),
+            };
+            assert!(substream_protocols.insert(protocol_id));
+            for _ in 1..10 {
+                let msg = stream.next().await.unwrap().unwrap();
+                assert_eq!(msg, direct_send_msg(protocol_id, "hello world"));
+            }
+        }
+        assert_eq!(substream_protocols, HashSet::from(protocols));
+
+        // Messages received on the substreams opened by the remote peer are delivered too
+        for protocol_id in protocols {
+            let stream = remote_substreams.open_stream().await.unwrap();
+            let mut sink = MultiplexMessageSink::new(stream, MAX_FRAME_SIZE);
+            sink.send(&direct_send_msg(protocol_id, "namaste"))
+                .await
+                .unwrap();
+            let received = peer_notifs_rx.next().await.unwrap();
+            assert_eq!(received, recv_msg(protocol_id, "namaste"));
+        }
+
+        // Shut the peer down, which closes the connection
+        drop(peer_handle);
+    };
+    rt.block_on(future::join(peer.start(), test));
+}
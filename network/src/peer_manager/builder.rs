@@ -12,18 +12,22 @@ use crate::{
     },
     protocols::{
         network::{NetworkClientConfig, NetworkServiceConfig},
-        wire::handshake::v1::ProtocolIdSet,
+        wire::{capture::TrafficCapture, handshake::v1::ProtocolIdSet},
     },
-    transport::{self, AptosNetTransport, Connection, APTOS_TCP_TRANSPORT},
+    transport::{self, AptosNetTransport, Connection, APTOS_QUIC_TRANSPORT, APTOS_TCP_TRANSPORT},
     ProtocolId,
 };
 use aptos_channels::{self, aptos_channel, message_queues::QueueStyle};
-use aptos_config::{config::HANDSHAKE_VERSION, network_id::NetworkContext};
+use aptos_config::{
+    config::{OutboundQosConfig, HANDSHAKE_VERSION},
+    network_id::NetworkContext,
+};
 use aptos_crypto::x25519;
 use aptos_logger::prelude::*;
 #[cfg(any(test, feature = "testing", feature = "fuzzing"))]
 use aptos_netcore::transport::memory::MemoryTransport;
 use aptos_netcore::transport::{
+    quic::{QuicSocket, QuicTransport},
     tcp::{TCPBufferCfg, TcpSocket, TcpTransport},
     Transport,
 };
@@ -77,6 +81,8 @@ struct PeerManagerContext {
     channel_size: usize,
     max_frame_size: usize,
     max_message_size: usize,
+    outbound_qos_config: OutboundQosConfig,
+    traffic_capture: Option<TrafficCapture>,
     inbound_connection_limit: usize,
     tcp_buffer_cfg: TCPBufferCfg,
 }
@@ -100,6 +106,7 @@ impl PeerManagerContext {
         channel_size: usize,
         max_frame_size: usize,
         max_message_size: usize,
+        outbound_qos_config: OutboundQosConfig,
         inbound_connection_limit: usize,
         tcp_buffer_cfg: TCPBufferCfg,
     ) -> Self {
@@ -117,6 +124,8 @@ impl PeerManagerContext {
             channel_size,
             max_frame_size,
             max_message_size,
+            outbound_qos_config,
+            traffic_capture: None,
             inbound_connection_limit,
             tcp_buffer_cfg,
         }
@@ -142,11 +151,13 @@ impl PeerManagerContext {
 type MemoryPeerManager =
     PeerManager<AptosNetTransport<MemoryTransport>, NoiseStream<aptos_memsocket::MemorySocket>>;
 type TcpPeerManager = PeerManager<AptosNetTransport<TcpTransport>, NoiseStream<TcpSocket>>;
+type QuicPeerManager = PeerManager<AptosNetTransport<QuicTransport>, NoiseStream<QuicSocket>>;
 
 enum TransportPeerManager {
     #[cfg(any(test, feature = "testing", feature = "fuzzing"))]
     Memory(MemoryPeerManager),
     Tcp(TcpPeerManager),
+    Quic(QuicPeerManager),
 }
 
 pub struct PeerManagerBuilder {
@@ -174,6 +185,7 @@ impl PeerManagerBuilder {
         max_concurrent_network_reqs: usize,
         max_frame_size: usize,
         max_message_size: usize,
+        outbound_qos_config: OutboundQosConfig,
         enable_proxy_protocol: bool,
         inbound_connection_limit: usize,
         tcp_buffer_cfg: TCPBufferCfg,
@@ -210,6 +222,7 @@ impl PeerManagerBuilder {
                 channel_size,
                 max_frame_size,
                 max_message_size,
+                outbound_qos_config,
                 inbound_connection_limit,
                 tcp_buffer_cfg,
             )),
@@ -288,6 +301,22 @@ impl PeerManagerBuilder {
                     executor,
                 )))
             },
+            [Ip4(_), Udp(_), Quic] | [Ip6(_), Udp(_), Quic] => {
+                Some(TransportPeerManager::Quic(self.build_with_transport(
+                    AptosNetTransport::new(
+                        APTOS_QUIC_TRANSPORT,
+                        self.network_context,
+                        self.time_service.clone(),
+                        key,
+                        auth_mode,
+                        HANDSHAKE_VERSION,
+                        chain_id,
+                        protos,
+                        enable_proxy_protocol,
+                    ),
+                    executor,
+                )))
+            },
             #[cfg(any(test, feature = "testing", feature = "fuzzing"))]
             [Memory(_)] => Some(TransportPeerManager::Memory(self.build_with_transport(
                 AptosNetTransport::new(
@@ -305,7 +334,8 @@ impl PeerManagerBuilder {
             ))),
             _ => panic!(
                 "{} Unsupported listen_address: '{}', expected '/memory/<port>', \
-                 '/ip4/<addr>/tcp/<port>', or '/ip6/<addr>/tcp/<port>'.",
+                 '/ip4/<addr>/tcp/<port>', '/ip6/<addr>/tcp/<port>', \
+                 '/ip4/<addr>/udp/<port>/quic', or '/ip6/<addr>/udp/<port>/quic'.",
                 self.network_context, self.listen_address
             ),
         };
@@ -345,6 +375,8 @@ impl PeerManagerBuilder {
             pm_context.max_concurrent_network_reqs,
             pm_context.max_frame_size,
             pm_context.max_message_size,
+            pm_context.outbound_qos_config,
+            pm_context.traffic_capture,
             pm_context.inbound_connection_limit,
         );
 
@@ -376,6 +408,7 @@ impl PeerManagerBuilder {
             #[cfg(any(test, feature = "testing", feature = "fuzzing"))]
             TransportPeerManager::Memory(pm) => self.start_peer_manager(pm, executor),
             TransportPeerManager::Tcp(pm) => self.start_peer_manager(pm, executor),
+            TransportPeerManager::Quic(pm) => self.start_peer_manager(pm, executor),
         }
     }
 
@@ -386,6 +419,14 @@ impl PeerManagerBuilder {
             .add_connection_event_listener()
     }
 
+    /// Captures the messages exchanged with all peers
+    pub fn add_traffic_capture(&mut self, traffic_capture: TrafficCapture) {
+        self.peer_manager_context
+            .as_mut()
+            .expect("Cannot add a traffic capture if PeerManager has already been built.")
+            .traffic_capture = Some(traffic_capture);
+    }
+
     pub fn get_tcp_buffers_cfg(&self) -> TCPBufferCfg {
         self.peer_manager_context
             .as_ref()
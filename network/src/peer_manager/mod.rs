@@ -23,7 +23,10 @@ use crate::{
     ProtocolId,
 };
 use aptos_channels::{self, aptos_channel, message_queues::QueueStyle};
-use aptos_config::network_id::{NetworkContext, PeerNetworkId};
+use aptos_config::{
+    config::OutboundQosConfig,
+    network_id::{NetworkContext, PeerNetworkId},
+};
 use aptos_logger::prelude::*;
 use aptos_netcore::transport::{ConnectionOrigin, Transport};
 use aptos_short_hex_str::AsShortHexStr;
@@ -56,7 +59,7 @@ pub use self::error::PeerManagerError;
 use crate::{
     application::{error::Error, storage::PeersAndMetadata},
     peer_manager::transport::{TransportHandler, TransportRequest},
-    protocols::network::SerializedRequest,
+    protocols::{network::SerializedRequest, wire::capture::TrafficCapture},
 };
 use aptos_config::config::PeerRole;
 use aptos_types::account_address::AccountAddress;
@@ -117,6 +120,10 @@ where
     max_frame_size: usize,
     /// Max network message size
     max_message_size: usize,
+    /// Scheduling of the outbound messages to each peer
+    outbound_qos_config: OutboundQosConfig,
+    /// Capture of the messages exchanged with all peers (if enabled)
+    traffic_capture: Option<TrafficCapture>,
     /// Inbound connection limit separate of outbound connections
     inbound_connection_limit: usize,
 }
@@ -146,6 +153,8 @@ where
         max_concurrent_network_reqs: usize,
         max_frame_size: usize,
         max_message_size: usize,
+        outbound_qos_config: OutboundQosConfig,
+        traffic_capture: Option<TrafficCapture>,
         inbound_connection_limit: usize,
     ) -> Self {
         let (transport_notifs_tx, transport_notifs_rx) = aptos_channels::new(
@@ -188,6 +197,8 @@ where
             channel_size,
             max_frame_size,
             max_message_size,
+            outbound_qos_config,
+            traffic_capture,
             inbound_connection_limit,
         }
     }
@@ -354,6 +365,25 @@ where
             },
         };
 
+        // Refuse inbound connections from banned peers (outbound connections to them are
+        // never dialed by the ConnectivityManager)
+        let peer_network_id = PeerNetworkId::new(
+            self.network_context.network_id(),
+            conn.metadata.remote_peer_id,
+        );
+        if conn.metadata.origin == ConnectionOrigin::Inbound
+            && self.peers_and_metadata.is_peer_banned(&peer_network_id)
+        {
+            info!(
+                NetworkSchema::new(&self.network_context)
+                    .connection_metadata_with_address(&conn.metadata),
+                "{} Connection rejected from banned peer: {}", self.network_context, conn.metadata
+            );
+            counters::connections_rejected(&self.network_context, conn.metadata.origin).inc();
+            self.disconnect(conn);
+            return;
+        }
+
         // Verify that we have not reached the max connection limit for unknown inbound peers
         if conn.metadata.origin == ConnectionOrigin::Inbound {
             // Everything below here is meant for unknown peers only. The role comes from
@@ -673,6 +703,8 @@ where
             constants::MAX_CONCURRENT_OUTBOUND_RPCS,
             self.max_frame_size,
             self.max_message_size,
+            self.outbound_qos_config,
+            self.traffic_capture.clone(),
         );
         self.executor.spawn(peer.start());
 
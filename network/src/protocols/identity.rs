@@ -4,10 +4,11 @@
 
 //! Protocol used to exchange supported protocol information with a remote.
 
-use crate::protocols::wire::handshake::v1::HandshakeMsg;
+use crate::protocols::wire::handshake::v1::{CompressionDictionariesMsg, HandshakeMsg};
 use aptos_netcore::framing::{read_u16frame, write_u16frame};
 use bytes::BytesMut;
 use futures::io::{AsyncRead, AsyncWrite, AsyncWriteExt};
+use serde::{de::DeserializeOwned, Serialize};
 use std::io;
 
 /// The Handshake exchange protocol.
@@ -18,34 +19,57 @@ pub async fn exchange_handshake<T>(
 where
     T: AsyncRead + AsyncWrite + Unpin,
 {
-    // Send serialized handshake message to remote peer.
-    let msg = bcs::to_bytes(own_handshake).map_err(|e| {
+    exchange_message(own_handshake, socket, "identity").await
+}
+
+/// Exchanges the zstd dictionaries accepted by each peer. This must only be
+/// called after the handshake, iff the peers share a zstd dictionary protocol
+/// (otherwise the peer won't send its dictionaries).
+pub async fn exchange_compression_dictionaries<T>(
+    own_dictionaries: &CompressionDictionariesMsg,
+    socket: &mut T,
+) -> io::Result<CompressionDictionariesMsg>
+where
+    T: AsyncRead + AsyncWrite + Unpin,
+{
+    exchange_message(own_dictionaries, socket, "compression dictionaries").await
+}
+
+/// Sends the given message to the remote peer and reads the peer's message
+async fn exchange_message<T, M>(own_message: &M, socket: &mut T, label: &str) -> io::Result<M>
+where
+    T: AsyncRead + AsyncWrite + Unpin,
+    M: DeserializeOwned + Serialize,
+{
+    // Send serialized message to remote peer.
+    let msg = bcs::to_bytes(own_message).map_err(|e| {
         io::Error::new(
             io::ErrorKind::InvalidData,
-            format!("Failed to serialize identity msg: {}", e),
+            format!("Failed to serialize {} msg: {}", label, e),
         )
     })?;
     write_u16frame(socket, &msg).await?;
     socket.flush().await?;
 
-    // Read handshake message from the Remote
+    // Read message from the Remote
     let mut response = BytesMut::new();
     read_u16frame(socket, &mut response).await?;
-    let identity = bcs::from_bytes(&response).map_err(|e| {
+    bcs::from_bytes(&response).map_err(|e| {
         io::Error::new(
             io::ErrorKind::InvalidData,
-            format!("Failed to parse identity msg: {}", e),
+            format!("Failed to parse {} msg: {}", label, e),
         )
-    })?;
-    Ok(identity)
+    })
 }
 
 #[cfg(test)]
 mod tests {
     use crate::{
         protocols::{
-            identity::exchange_handshake,
-            wire::handshake::v1::{HandshakeMsg, MessagingProtocolVersion, ProtocolIdSet},
+            identity::{exchange_compression_dictionaries, exchange_handshake},
+            wire::handshake::v1::{
+                CompressionDictionariesMsg, HandshakeMsg, MessagingProtocolVersion, ProtocolIdSet,
+            },
         },
         ProtocolId,
     };
@@ -121,6 +145,39 @@ mod tests {
         block_on(join(server, client));
     }
 
+    #[test]
+    fn simple_compression_dictionaries_exchange() {
+        let (mut outbound, mut inbound) = build_test_connection();
+
+        // Create the client and server dictionary messages
+        let server_dictionaries = CompressionDictionariesMsg {
+            dictionary_ids: vec![1, 2],
+        };
+        let client_dictionaries = CompressionDictionariesMsg {
+            dictionary_ids: vec![3],
+        };
+        let server_dictionaries_clone = server_dictionaries.clone();
+        let client_dictionaries_clone = client_dictionaries.clone();
+
+        // Verify each side receives the dictionaries of the other
+        let server = async move {
+            let dictionaries =
+                exchange_compression_dictionaries(&server_dictionaries, &mut inbound)
+                    .await
+                    .unwrap();
+            assert_eq!(dictionaries, client_dictionaries_clone);
+        };
+        let client = async move {
+            let dictionaries =
+                exchange_compression_dictionaries(&client_dictionaries, &mut outbound)
+                    .await
+                    .unwrap();
+            assert_eq!(dictionaries, server_dictionaries_clone);
+        };
+
+        block_on(join(server, client));
+    }
+
     #[test]
     fn handshake_chain_id_mismatch() {
         let (mut outbound, mut inbound) = MemorySocket::new_pair();
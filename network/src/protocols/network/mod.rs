@@ -394,6 +394,21 @@ impl<TMessage: Message> NetworkSender<TMessage> {
         req_msg: TMessage,
         timeout: Duration,
     ) -> Result<TMessage, RpcError> {
+        let (res_msg, _) = self
+            .send_rpc_with_response_size(recipient, protocol, req_msg, timeout)
+            .await?;
+        Ok(res_msg)
+    }
+
+    /// Same as [`send_rpc`](Self::send_rpc), but also returns the number of bytes of the
+    /// response as received from the recipient.
+    pub async fn send_rpc_with_response_size(
+        &self,
+        recipient: PeerId,
+        protocol: ProtocolId,
+        req_msg: TMessage,
+        timeout: Duration,
+    ) -> Result<(TMessage, usize), RpcError> {
         // serialize request
         let req_data = protocol.to_bytes(&req_msg)?.into();
         let res_data = self
@@ -401,7 +416,7 @@ impl<TMessage: Message> NetworkSender<TMessage> {
             .send_rpc(recipient, protocol, req_data, timeout)
             .await?;
         let res_msg: TMessage = protocol.from_bytes(&res_data)?;
-        Ok(res_msg)
+        Ok((res_msg, res_data.len()))
     }
 }
 
@@ -0,0 +1,459 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module defines an opt-in capture of the messages sent and received on a network. The
+//! metadata of every (complete, i.e., reassembled) [`NetworkMessage`] and optionally its encoded
+//! payload are appended to rotating files, which can be decoded and filtered offline using the
+//! application message types (see the `aptos-network-capture-inspector` tool).
+//!
+//! Capturing never blocks the peer actors: the messages are handed to a dedicated writer thread
+//! over a bounded channel, and are dropped from the capture when the writer falls behind.
+
+use crate::{
+    counters,
+    protocols::wire::{
+        handshake::v1::ProtocolId,
+        messaging::v1::{NetworkMessage, RequestId},
+    },
+};
+use anyhow::Context;
+use aptos_config::{config::TrafficCaptureConfig, network_id::NetworkId};
+use aptos_infallible::duration_since_epoch;
+use aptos_logger::prelude::*;
+use aptos_types::PeerId;
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    fs::File,
+    io::{BufReader, BufWriter, ErrorKind, Read, Write},
+    path::{Path, PathBuf},
+    sync::mpsc,
+};
+
+const CAPTURE_FILE_PREFIX: &str = "network-capture-";
+const CAPTURE_FILE_EXTENSION: &str = "bcs";
+
+/// Whether a message was sent or received by the capturing node
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum CaptureDirection {
+    Inbound,
+    Outbound,
+}
+
+impl CaptureDirection {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CaptureDirection::Inbound => "inbound",
+            CaptureDirection::Outbound => "outbound",
+        }
+    }
+}
+
+/// The type of a captured [`NetworkMessage`]
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum CapturedMessageType {
+    Error,
+    RpcRequest,
+    RpcResponse,
+    DirectSend,
+}
+
+impl CapturedMessageType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CapturedMessageType::Error => "error",
+            CapturedMessageType::RpcRequest => "rpc_request",
+            CapturedMessageType::RpcResponse => "rpc_response",
+            CapturedMessageType::DirectSend => "direct_send",
+        }
+    }
+}
+
+/// A single captured message. Rpc responses don't carry a protocol on the wire, so their
+/// protocol is found by matching the request id against the captured requests.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct CapturedMessage {
+    pub timestamp_usecs: u64,
+    pub network_id: NetworkId,
+    pub peer_id: PeerId,
+    pub direction: CaptureDirection,
+    pub message_type: CapturedMessageType,
+    pub protocol_id: Option<ProtocolId>,
+    pub request_id: Option<RequestId>,
+    pub size_bytes: u64,
+    /// The payload, as encoded by the protocol (i.e., possibly compressed)
+    pub payload: Option<Vec<u8>>,
+}
+
+impl CapturedMessage {
+    pub fn new(
+        timestamp_usecs: u64,
+        network_id: NetworkId,
+        peer_id: PeerId,
+        direction: CaptureDirection,
+        message: &NetworkMessage,
+        include_payload: bool,
+    ) -> Self {
+        let (message_type, protocol_id, request_id, payload) = match message {
+            NetworkMessage::Error(_) => (CapturedMessageType::Error, None, None, None),
+            NetworkMessage::RpcRequest(request) => (
+                CapturedMessageType::RpcRequest,
+                Some(request.protocol_id),
+                Some(request.request_id),
+                Some(&request.raw_request),
+            ),
+            NetworkMessage::RpcResponse(response) => (
+                CapturedMessageType::RpcResponse,
+                None,
+                Some(response.request_id),
+                Some(&response.raw_response),
+            ),
+            NetworkMessage::DirectSendMsg(message) => (
+                CapturedMessageType::DirectSend,
+                Some(message.protocol_id),
+                None,
+                Some(&message.raw_msg),
+            ),
+        };
+        Self {
+            timestamp_usecs,
+            network_id,
+            peer_id,
+            direction,
+            message_type,
+            protocol_id,
+            request_id,
+            size_bytes: message.data_len() as u64,
+            payload: payload.filter(|_| include_payload).cloned(),
+        }
+    }
+}
+
+/// A handle to the traffic capture of a network. It is cheap to clone, so that every peer
+/// actor owns one.
+#[derive(Clone)]
+pub struct TrafficCapture {
+    network_id: NetworkId,
+    include_payloads: bool,
+    message_tx: mpsc::SyncSender<CapturedMessage>,
+}
+
+impl TrafficCapture {
+    /// Creates a traffic capture (and its writer thread) for the given network
+    pub fn new(network_id: NetworkId, config: &TrafficCaptureConfig) -> anyhow::Result<Self> {
+        let mut writer = CaptureWriter::new(
+            config.capture_dir.clone(),
+            network_id,
+            config.max_file_size_bytes,
+            config.max_num_files,
+        )?;
+        let (message_tx, message_rx) = mpsc::sync_channel(config.max_pending_messages);
+        std::thread::Builder::new()
+            .name(format!("capture-{}", network_id.as_str().to_lowercase()))
+            .spawn(move || {
+                while let Ok(message) = message_rx.recv() {
+                    if let Err(error) = writer.write_message(&message) {
+                        counters::TRAFFIC_CAPTURE_ERRORS
+                            .with_label_values(&[network_id.as_str()])
+                            .inc();
+                        error!(error = ?error, "Failed to write the network traffic capture!");
+                    }
+                }
+            })?;
+        Ok(Self {
+            network_id,
+            include_payloads: config.include_payloads,
+            message_tx,
+        })
+    }
+
+    /// Captures the given message sent to (or received from) the peer
+    pub fn capture(&self, peer_id: PeerId, direction: CaptureDirection, message: &NetworkMessage) {
+        let message = CapturedMessage::new(
+            duration_since_epoch().as_micros() as u64,
+            self.network_id,
+            peer_id,
+            direction,
+            message,
+            self.include_payloads,
+        );
+        match self.message_tx.try_send(message) {
+            Ok(()) => counters::TRAFFIC_CAPTURED_MESSAGES
+                .with_label_values(&[self.network_id.as_str(), direction.as_str()])
+                .inc(),
+            Err(_) => counters::TRAFFIC_CAPTURE_DROPPED_MESSAGES
+                .with_label_values(&[self.network_id.as_str()])
+                .inc(),
+        }
+    }
+}
+
+/// Appends length-prefixed BCS messages to the current capture file of the network, starting a
+/// new file whenever the current one grows too large and deleting the oldest files.
+struct CaptureWriter {
+    capture_dir: PathBuf,
+    file_prefix: String,
+    max_file_size_bytes: u64,
+    max_num_files: usize,
+    file_index: u64,
+    file_size_bytes: u64,
+    file: BufWriter<File>,
+}
+
+impl CaptureWriter {
+    fn new(
+        capture_dir: PathBuf,
+        network_id: NetworkId,
+        max_file_size_bytes: u64,
+        max_num_files: usize,
+    ) -> anyhow::Result<Self> {
+        fs::create_dir_all(&capture_dir).with_context(|| {
+            format!("Failed to create the capture directory: {:?}", capture_dir)
+        })?;
+
+        // Never overwrite the captures of a previous run
+        let file_prefix = format!(
+            "{}{}-",
+            CAPTURE_FILE_PREFIX,
+            network_id.as_str().to_lowercase()
+        );
+        let file_index = list_capture_files(&capture_dir, &file_prefix)?
+            .last()
+            .map_or(0, |(index, _)| index + 1);
+        let file = create_capture_file(&capture_dir, &file_prefix, file_index)?;
+        Ok(Self {
+            capture_dir,
+            file_prefix,
+            max_file_size_bytes,
+            max_num_files,
+            file_index,
+            file_size_bytes: 0,
+            file,
+        })
+    }
+
+    fn write_message(&mut self, message: &CapturedMessage) -> anyhow::Result<()> {
+        let bytes = bcs::to_bytes(message)?;
+        self.file.write_all(&(bytes.len() as u32).to_be_bytes())?;
+        self.file.write_all(&bytes)?;
+
+        self.file_size_bytes += (bytes.len() + 4) as u64;
+        if self.file_size_bytes >= self.max_file_size_bytes {
+            self.rotate()?;
+        }
+        Ok(())
+    }
+
+    fn rotate(&mut self) -> anyhow::Result<()> {
+        self.file.flush()?;
+        self.file_index += 1;
+        self.file = create_capture_file(&self.capture_dir, &self.file_prefix, self.file_index)?;
+        self.file_size_bytes = 0;
+
+        let capture_files = list_capture_files(&self.capture_dir, &self.file_prefix)?;
+        let num_files_to_delete = capture_files.len().saturating_sub(self.max_num_files);
+        for (_, path) in capture_files.into_iter().take(num_files_to_delete) {
+            fs::remove_file(&path)
+                .with_context(|| format!("Failed to remove the capture file: {:?}", path))?;
+        }
+        Ok(())
+    }
+}
+
+impl Drop for CaptureWriter {
+    fn drop(&mut self) {
+        // The messages are buffered, so flush whatever is left once the network shuts down
+        if let Err(error) = self.file.flush() {
+            warn!(error = ?error, "Failed to flush the network traffic capture!");
+        }
+    }
+}
+
+fn create_capture_file(
+    capture_dir: &Path,
+    file_prefix: &str,
+    file_index: u64,
+) -> anyhow::Result<BufWriter<File>> {
+    let path = capture_dir.join(format!(
+        "{}{:010}.{}",
+        file_prefix, file_index, CAPTURE_FILE_EXTENSION
+    ));
+    let file = File::create(&path)
+        .with_context(|| format!("Failed to create the capture file: {:?}", path))?;
+    Ok(BufWriter::new(file))
+}
+
+/// Returns all capture files in the directory with the given prefix, sorted by their index. The
+/// prefix may leave out the network (e.g., to list the captures of all networks), as the index is
+/// always the part of the file name after the last `-`.
+fn list_capture_files(
+    capture_dir: &Path,
+    file_prefix: &str,
+) -> anyhow::Result<Vec<(u64, PathBuf)>> {
+    let mut capture_files = vec![];
+    for dir_entry in fs::read_dir(capture_dir)? {
+        let path = dir_entry?.path();
+        let index = path
+            .file_name()
+            .and_then(|file_name| file_name.to_str())
+            .and_then(|file_name| file_name.strip_prefix(file_prefix))
+            .and_then(|file_name| file_name.strip_suffix(CAPTURE_FILE_EXTENSION))
+            .and_then(|file_name| file_name.trim_end_matches('.').rsplit('-').next())
+            .and_then(|index| index.parse::<u64>().ok());
+        if let Some(index) = index {
+            capture_files.push((index, path));
+        }
+    }
+    capture_files.sort();
+    Ok(capture_files)
+}
+
+/// Reads the captured messages from the given capture file or directory (in which case the
+/// captures of all networks are read), sorted by their timestamp. A truncated message at the end
+/// of a file (e.g., because the node crashed) is ignored.
+pub fn read_capture(path: &Path) -> anyhow::Result<Vec<CapturedMessage>> {
+    let capture_files = if path.is_dir() {
+        list_capture_files(path, CAPTURE_FILE_PREFIX)?
+            .into_iter()
+            .map(|(_, path)| path)
+            .collect()
+    } else {
+        vec![path.to_path_buf()]
+    };
+
+    let mut messages = vec![];
+    for path in capture_files {
+        let mut reader = BufReader::new(
+            File::open(&path)
+                .with_context(|| format!("Failed to open the capture file: {:?}", path))?,
+        );
+        loop {
+            let mut length_bytes = [0u8; 4];
+            match reader.read_exact(&mut length_bytes) {
+                Ok(()) => {},
+                Err(error) if error.kind() == ErrorKind::UnexpectedEof => break,
+                Err(error) => return Err(error.into()),
+            }
+            let mut bytes = vec![0; u32::from_be_bytes(length_bytes) as usize];
+            match reader.read_exact(&mut bytes) {
+                Ok(()) => {},
+                Err(error) if error.kind() == ErrorKind::UnexpectedEof => break,
+                Err(error) => return Err(error.into()),
+            }
+            messages.push(
+                bcs::from_bytes(&bytes)
+                    .with_context(|| format!("Corrupted capture file: {:?}", path))?,
+            );
+        }
+    }
+    messages.sort_by_key(|message: &CapturedMessage| message.timestamp_usecs);
+    Ok(messages)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocols::wire::messaging::v1::{DirectSendMsg, RpcResponse};
+    use aptos_temppath::TempPath;
+
+    fn create_captured_message(timestamp_usecs: u64, network_id: NetworkId) -> CapturedMessage {
+        let message = NetworkMessage::DirectSendMsg(DirectSendMsg {
+            protocol_id: ProtocolId::MempoolDirectSend,
+            priority: 0,
+            raw_msg: vec![0; 10],
+        });
+        CapturedMessage::new(
+            timestamp_usecs,
+            network_id,
+            PeerId::ZERO,
+            CaptureDirection::Outbound,
+            &message,
+            true,
+        )
+    }
+
+    #[test]
+    fn test_captured_message_metadata() {
+        // Verify that the payload is only kept when requested
+        let message = NetworkMessage::RpcResponse(RpcResponse {
+            request_id: 7,
+            priority: 0,
+            raw_response: vec![1, 2, 3],
+        });
+        for include_payload in [false, true] {
+            let captured_message = CapturedMessage::new(
+                0,
+                NetworkId::Validator,
+                PeerId::ONE,
+                CaptureDirection::Inbound,
+                &message,
+                include_payload,
+            );
+            assert_eq!(
+                captured_message.message_type,
+                CapturedMessageType::RpcResponse
+            );
+            assert_eq!(captured_message.protocol_id, None);
+            assert_eq!(captured_message.request_id, Some(7));
+            assert_eq!(captured_message.size_bytes, 3);
+            assert_eq!(
+                captured_message.payload,
+                include_payload.then(|| vec![1, 2, 3])
+            );
+        }
+    }
+
+    #[test]
+    fn test_capture_rotation() {
+        let capture_dir = TempPath::new();
+        let message_size = bcs::to_bytes(&create_captured_message(0, NetworkId::Public))
+            .unwrap()
+            .len() as u64
+            + 4;
+
+        // Create writers (for two networks) that fit two messages per file and keep three files
+        let mut public_writer = CaptureWriter::new(
+            capture_dir.path().to_path_buf(),
+            NetworkId::Public,
+            2 * message_size,
+            3,
+        )
+        .unwrap();
+        let mut vfn_writer = CaptureWriter::new(
+            capture_dir.path().to_path_buf(),
+            NetworkId::Vfn,
+            2 * message_size,
+            3,
+        )
+        .unwrap();
+        for timestamp_usecs in 0..10 {
+            public_writer
+                .write_message(&create_captured_message(timestamp_usecs, NetworkId::Public))
+                .unwrap();
+        }
+        vfn_writer
+            .write_message(&create_captured_message(100, NetworkId::Vfn))
+            .unwrap();
+        drop(public_writer);
+        drop(vfn_writer);
+
+        // Verify only the newest files of the public network were kept
+        let capture_files =
+            list_capture_files(capture_dir.path(), "network-capture-public-").unwrap();
+        let indices: Vec<_> = capture_files.iter().map(|(index, _)| *index).collect();
+        assert_eq!(indices, vec![3, 4, 5]);
+
+        // Verify the files of all networks are listed without the network in the prefix
+        let capture_files = list_capture_files(capture_dir.path(), CAPTURE_FILE_PREFIX).unwrap();
+        let indices: Vec<_> = capture_files.iter().map(|(index, _)| *index).collect();
+        assert_eq!(indices, vec![0, 3, 4, 5]);
+
+        // Verify the remaining messages of both networks are read back in order
+        let timestamps: Vec<_> = read_capture(capture_dir.path())
+            .unwrap()
+            .into_iter()
+            .map(|message| message.timestamp_usecs)
+            .collect();
+        assert_eq!(timestamps, vec![6, 7, 8, 9, 100]);
+    }
+}
@@ -47,7 +47,7 @@ pub enum ProtocolId {
     ConsensusDirectSendBcs = 1,
     MempoolDirectSend = 2,
     StateSyncDirectSend = 3,
-    DiscoveryDirectSend = 4, // Currently unused
+    DiscoveryDirectSend = 4, // Used by peer exchange discovery
     HealthCheckerRpc = 5,
     ConsensusDirectSendJson = 6, // Json provides flexibility for backwards compatible upgrade
     ConsensusRpcJson = 7,
@@ -56,12 +56,24 @@ pub enum ProtocolId {
     PeerMonitoringServiceRpc = 10,
     ConsensusRpcCompressed = 11,
     ConsensusDirectSendCompressed = 12,
+    ConsensusObserver = 13,
+    ConsensusObserverRpc = 14,
+    ConsensusRpcZstd = 15,
+    ConsensusDirectSendZstd = 16,
+    MempoolDirectSendZstd = 17,
+    ConsensusRpcZstdDictionary = 18,
+    ConsensusDirectSendZstdDictionary = 19,
+    MempoolDirectSendZstdDictionary = 20,
+    QuorumStoreRpcCompressed = 21,
+    QuorumStoreDirectSendCompressed = 22,
 }
 
 /// The encoding types for Protocols
 enum Encoding {
     Bcs(usize),
     CompressedBcs(usize),
+    ZstdCompressedBcs(usize),
+    ZstdDictionaryCompressedBcs(usize),
     Json,
 }
 
@@ -82,6 +94,16 @@ impl ProtocolId {
             PeerMonitoringServiceRpc => "PeerMonitoringServiceRpc",
             ConsensusRpcCompressed => "ConsensusRpcCompressed",
             ConsensusDirectSendCompressed => "ConsensusDirectSendCompressed",
+            ConsensusObserver => "ConsensusObserver",
+            ConsensusObserverRpc => "ConsensusObserverRpc",
+            ConsensusRpcZstd => "ConsensusRpcZstd",
+            ConsensusDirectSendZstd => "ConsensusDirectSendZstd",
+            MempoolDirectSendZstd => "MempoolDirectSendZstd",
+            ConsensusRpcZstdDictionary => "ConsensusRpcZstdDictionary",
+            ConsensusDirectSendZstdDictionary => "ConsensusDirectSendZstdDictionary",
+            MempoolDirectSendZstdDictionary => "MempoolDirectSendZstdDictionary",
+            QuorumStoreRpcCompressed => "QuorumStoreRpcCompressed",
+            QuorumStoreDirectSendCompressed => "QuorumStoreDirectSendCompressed",
         }
     }
 
@@ -100,6 +122,16 @@ impl ProtocolId {
             ProtocolId::PeerMonitoringServiceRpc,
             ProtocolId::ConsensusRpcCompressed,
             ProtocolId::ConsensusDirectSendCompressed,
+            ProtocolId::ConsensusObserver,
+            ProtocolId::ConsensusObserverRpc,
+            ProtocolId::ConsensusRpcZstd,
+            ProtocolId::ConsensusDirectSendZstd,
+            ProtocolId::MempoolDirectSendZstd,
+            ProtocolId::ConsensusRpcZstdDictionary,
+            ProtocolId::ConsensusDirectSendZstdDictionary,
+            ProtocolId::MempoolDirectSendZstdDictionary,
+            ProtocolId::QuorumStoreRpcCompressed,
+            ProtocolId::QuorumStoreDirectSendCompressed,
         ]
     }
 
@@ -107,10 +139,25 @@ impl ProtocolId {
     fn encoding(self) -> Encoding {
         match self {
             ProtocolId::ConsensusDirectSendJson | ProtocolId::ConsensusRpcJson => Encoding::Json,
-            ProtocolId::ConsensusDirectSendCompressed | ProtocolId::ConsensusRpcCompressed => {
-                Encoding::CompressedBcs(RECURSION_LIMIT)
-            },
+            ProtocolId::ConsensusDirectSendCompressed
+            | ProtocolId::ConsensusRpcCompressed
+            | ProtocolId::QuorumStoreDirectSendCompressed
+            | ProtocolId::QuorumStoreRpcCompressed => Encoding::CompressedBcs(RECURSION_LIMIT),
+            ProtocolId::ConsensusObserver => Encoding::CompressedBcs(RECURSION_LIMIT),
             ProtocolId::MempoolDirectSend => Encoding::CompressedBcs(USER_INPUT_RECURSION_LIMIT),
+            ProtocolId::ConsensusDirectSendZstd | ProtocolId::ConsensusRpcZstd => {
+                Encoding::ZstdCompressedBcs(RECURSION_LIMIT)
+            },
+            ProtocolId::MempoolDirectSendZstd => {
+                Encoding::ZstdCompressedBcs(USER_INPUT_RECURSION_LIMIT)
+            },
+            ProtocolId::ConsensusDirectSendZstdDictionary
+            | ProtocolId::ConsensusRpcZstdDictionary => {
+                Encoding::ZstdDictionaryCompressedBcs(RECURSION_LIMIT)
+            },
+            ProtocolId::MempoolDirectSendZstdDictionary => {
+                Encoding::ZstdDictionaryCompressedBcs(USER_INPUT_RECURSION_LIMIT)
+            },
             ProtocolId::MempoolRpc => Encoding::Bcs(USER_INPUT_RECURSION_LIMIT),
             _ => Encoding::Bcs(RECURSION_LIMIT),
         }
@@ -119,10 +166,18 @@ impl ProtocolId {
     /// Returns the compression client label based on the current protocol id
     fn get_compression_client(self) -> CompressionClient {
         match self {
-            ProtocolId::ConsensusDirectSendCompressed | ProtocolId::ConsensusRpcCompressed => {
-                CompressionClient::Consensus
-            },
-            ProtocolId::MempoolDirectSend => CompressionClient::Mempool,
+            ProtocolId::ConsensusDirectSendCompressed
+            | ProtocolId::ConsensusRpcCompressed
+            | ProtocolId::ConsensusDirectSendZstd
+            | ProtocolId::ConsensusRpcZstd
+            | ProtocolId::ConsensusDirectSendZstdDictionary
+            | ProtocolId::ConsensusRpcZstdDictionary
+            | ProtocolId::QuorumStoreDirectSendCompressed
+            | ProtocolId::QuorumStoreRpcCompressed => CompressionClient::Consensus,
+            ProtocolId::ConsensusObserver => CompressionClient::ConsensusObserver,
+            ProtocolId::MempoolDirectSend
+            | ProtocolId::MempoolDirectSendZstd
+            | ProtocolId::MempoolDirectSendZstdDictionary => CompressionClient::Mempool,
             protocol_id => unreachable!(
                 "The given protocol ({:?}) should not be using compression!",
                 protocol_id
@@ -130,6 +185,13 @@ impl ProtocolId {
         }
     }
 
+    /// Returns true iff the protocol compresses messages using a zstd
+    /// dictionary. Such protocols may only be used once the peer has
+    /// advertised the dictionary (see [`CompressionDictionariesMsg`]).
+    pub fn is_zstd_dictionary_protocol(self) -> bool {
+        matches!(self.encoding(), Encoding::ZstdDictionaryCompressedBcs(_))
+    }
+
     #[cfg(test)]
     pub fn mock() -> Self {
         ProtocolId::DiscoveryDirectSend
@@ -148,6 +210,26 @@ impl ProtocolId {
                 )
                 .map_err(|e| anyhow!("{:?}", e))
             },
+            Encoding::ZstdCompressedBcs(limit) => {
+                let compression_client = self.get_compression_client();
+                let bcs_bytes = self.bcs_encode(value, limit)?;
+                aptos_compression::zstd_compress(
+                    bcs_bytes,
+                    compression_client,
+                    MAX_APPLICATION_MESSAGE_SIZE,
+                )
+                .map_err(|e| anyhow!("{:?}", e))
+            },
+            Encoding::ZstdDictionaryCompressedBcs(limit) => {
+                let compression_client = self.get_compression_client();
+                let bcs_bytes = self.bcs_encode(value, limit)?;
+                aptos_compression::zstd_compress_with_dictionary(
+                    bcs_bytes,
+                    compression_client,
+                    MAX_APPLICATION_MESSAGE_SIZE,
+                )
+                .map_err(|e| anyhow!("{:?}", e))
+            },
             Encoding::Json => serde_json::to_vec(value).map_err(|e| anyhow!("{:?}", e)),
         }
     }
@@ -165,6 +247,16 @@ impl ProtocolId {
                 .map_err(|e| anyhow! {"{:?}", e})?;
                 self.bcs_decode(&raw_bytes, limit)
             },
+            Encoding::ZstdCompressedBcs(limit) | Encoding::ZstdDictionaryCompressedBcs(limit) => {
+                let compression_client = self.get_compression_client();
+                let raw_bytes = aptos_compression::zstd_decompress(
+                    &bytes.to_vec(),
+                    compression_client,
+                    MAX_APPLICATION_MESSAGE_SIZE,
+                )
+                .map_err(|e| anyhow!("{:?}", e))?;
+                self.bcs_decode(&raw_bytes, limit)
+            },
             Encoding::Json => serde_json::from_slice(bytes).map_err(|e| anyhow!("{:?}", e)),
         }
     }
@@ -249,6 +341,32 @@ impl ProtocolIdSet {
     pub fn insert(&mut self, protocol: ProtocolId) {
         self.0.set(protocol as u16)
     }
+
+    /// Returns true iff the set contains a zstd dictionary protocol.
+    pub fn contains_zstd_dictionary_protocols(&self) -> bool {
+        self.iter()
+            .any(|protocol| protocol.is_zstd_dictionary_protocol())
+    }
+
+    /// Returns the set without the zstd dictionary protocols that can't be
+    /// used with the peer, i.e., those whose compression dictionary (if any)
+    /// was not advertised by the peer in the given [`CompressionDictionariesMsg`].
+    pub fn filter_zstd_dictionary_protocols(
+        &self,
+        peer_dictionaries: &CompressionDictionariesMsg,
+    ) -> ProtocolIdSet {
+        self.iter()
+            .filter(|protocol| {
+                !protocol.is_zstd_dictionary_protocol()
+                    || aptos_compression::dictionary::get_compression_dictionary_id(
+                        &protocol.get_compression_client(),
+                    )
+                    .map_or(false, |dictionary_id| {
+                        peer_dictionaries.dictionary_ids.contains(&dictionary_id)
+                    })
+            })
+            .collect()
+    }
 }
 
 impl FromIterator<ProtocolId> for ProtocolIdSet {
@@ -263,6 +381,29 @@ impl<'a> FromIterator<&'a ProtocolId> for ProtocolIdSet {
     }
 }
 
+//
+// CompressionDictionariesMsg
+//
+
+/// The IDs of the zstd dictionaries a peer can decompress. If both peers share
+/// a zstd dictionary protocol after the handshake, they exchange this message
+/// so that each only compresses with a dictionary the other has advertised.
+/// Otherwise, the dictionary protocol is dropped and the peers fall back to
+/// the dictionary-less protocols (e.g., [`ProtocolId::ConsensusRpcZstd`]).
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct CompressionDictionariesMsg {
+    pub dictionary_ids: Vec<u32>,
+}
+
+impl CompressionDictionariesMsg {
+    /// Returns the message advertising the dictionaries known to this node
+    pub fn from_local_dictionaries() -> Self {
+        Self {
+            dictionary_ids: aptos_compression::dictionary::get_decompression_dictionary_ids(),
+        }
+    }
+}
+
 //
 // MessageProtocolVersion
 //
@@ -3,7 +3,8 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use super::*;
-use std::iter::FromIterator;
+use aptos_compression::dictionary::{set_compression_dictionary, CompressionDictionary};
+use std::{iter::FromIterator, sync::Arc};
 
 // Ensure serialization of MessagingProtocolVersion enum takes 1 byte.
 #[test]
@@ -183,3 +184,91 @@ fn ignore_unknown_protocols() {
         ProtocolIdSet::empty(),
     );
 }
+
+#[test]
+fn zstd_protocols_round_trip() {
+    let message: Vec<u64> = (0..1000).collect();
+    for protocol in [
+        ProtocolId::ConsensusRpcZstd,
+        ProtocolId::ConsensusDirectSendZstd,
+        ProtocolId::MempoolDirectSendZstd,
+        ProtocolId::ConsensusRpcZstdDictionary,
+        ProtocolId::ConsensusDirectSendZstdDictionary,
+        ProtocolId::MempoolDirectSendZstdDictionary,
+    ] {
+        // Verify the message is compressed and can be decoded
+        let bytes = protocol.to_bytes(&message).unwrap();
+        assert!(bytes.len() < bcs::to_bytes(&message).unwrap().len());
+        assert_eq!(protocol.from_bytes::<Vec<u64>>(&bytes).unwrap(), message);
+
+        // Verify lz4 compressed messages are rejected
+        let lz4_bytes = ProtocolId::ConsensusRpcCompressed
+            .to_bytes(&message)
+            .unwrap();
+        assert!(protocol.from_bytes::<Vec<u64>>(&lz4_bytes).is_err());
+    }
+}
+
+#[test]
+fn zstd_dictionary_protocols_filtering() {
+    // Train a dictionary and use it to compress mempool messages
+    let samples: Vec<Vec<u8>> = (0..2000)
+        .map(|index| {
+            format!("mempool transaction {} from sender {}", index, index % 7).into_bytes()
+        })
+        .collect();
+    let dictionary = CompressionDictionary::train(&samples, 4 * 1024).unwrap();
+    let dictionary_id = dictionary.id();
+    set_compression_dictionary(CompressionClient::Mempool, Arc::new(dictionary));
+
+    // Verify the dictionary is advertised to peers
+    assert!(CompressionDictionariesMsg::from_local_dictionaries()
+        .dictionary_ids
+        .contains(&dictionary_id));
+
+    // Verify the sets containing dictionary protocols are identified
+    let protocols = ProtocolIdSet::from_iter([
+        ProtocolId::ConsensusRpcZstdDictionary,
+        ProtocolId::MempoolDirectSendZstdDictionary,
+        ProtocolId::MempoolDirectSendZstd,
+        ProtocolId::MempoolDirectSend,
+    ]);
+    assert!(protocols.contains_zstd_dictionary_protocols());
+    assert!(
+        !ProtocolIdSet::from_iter([ProtocolId::MempoolDirectSendZstd])
+            .contains_zstd_dictionary_protocols()
+    );
+
+    // Verify the mempool dictionary protocol is kept if the peer knows the dictionary
+    // (the consensus dictionary protocol is dropped, as consensus has no dictionary)
+    let peer_dictionaries = CompressionDictionariesMsg {
+        dictionary_ids: vec![dictionary_id],
+    };
+    assert_eq!(
+        protocols.filter_zstd_dictionary_protocols(&peer_dictionaries),
+        ProtocolIdSet::from_iter([
+            ProtocolId::MempoolDirectSendZstdDictionary,
+            ProtocolId::MempoolDirectSendZstd,
+            ProtocolId::MempoolDirectSend,
+        ]),
+    );
+
+    // Verify all dictionary protocols are dropped if the peer doesn't know the dictionary
+    let peer_dictionaries = CompressionDictionariesMsg {
+        dictionary_ids: vec![dictionary_id.wrapping_add(1)],
+    };
+    assert_eq!(
+        protocols.filter_zstd_dictionary_protocols(&peer_dictionaries),
+        ProtocolIdSet::from_iter([
+            ProtocolId::MempoolDirectSendZstd,
+            ProtocolId::MempoolDirectSend,
+        ]),
+    );
+    assert_eq!(
+        protocols.filter_zstd_dictionary_protocols(&CompressionDictionariesMsg::default()),
+        ProtocolIdSet::from_iter([
+            ProtocolId::MempoolDirectSendZstd,
+            ProtocolId::MempoolDirectSend,
+        ]),
+    );
+}
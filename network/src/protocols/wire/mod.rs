@@ -9,5 +9,6 @@
 //! handshake protocol on an end-point, and that is advertised as part of its discovery
 //! NetworkAddress.
 
+pub mod capture;
 pub mod handshake;
 pub mod messaging;
@@ -9,6 +9,7 @@
 //!
 
 use aptos_memsocket::MemorySocket;
+use aptos_netcore::transport::Substreams;
 use futures::{
     io::{AsyncRead, AsyncWrite},
     ready,
@@ -165,6 +166,8 @@ impl AsyncWrite for ReadOnlyTestSocketVec {
     }
 }
 
+impl Substreams for ReadOnlyTestSocketVec {}
+
 /// Read based on the mode set
 impl AsyncRead for ReadOnlyTestSocketVec {
     fn poll_read(
@@ -6,8 +6,10 @@ use crate::{
     logging::NetworkSchema,
     noise::{stream::NoiseStream, AntiReplayTimestamps, HandshakeAuthMode, NoiseUpgrader},
     protocols::{
-        identity::exchange_handshake,
-        wire::handshake::v1::{HandshakeMsg, MessagingProtocolVersion, ProtocolIdSet},
+        identity::{exchange_compression_dictionaries, exchange_handshake},
+        wire::handshake::v1::{
+            CompressionDictionariesMsg, HandshakeMsg, MessagingProtocolVersion, ProtocolIdSet,
+        },
     },
 };
 use aptos_config::{
@@ -19,17 +21,22 @@ use aptos_id_generator::{IdGenerator, U32IdGenerator};
 use aptos_logger::prelude::*;
 // Re-exposed for aptos-network-checker
 pub use aptos_netcore::transport::tcp::{resolve_and_connect, TCPBufferCfg, TcpSocket};
-use aptos_netcore::transport::{proxy_protocol, tcp, ConnectionOrigin, Transport};
+use aptos_netcore::transport::{
+    proxy_protocol, quic, tcp, ConnectionOrigin, Substreams, Transport,
+};
 use aptos_short_hex_str::AsShortHexStr;
 use aptos_time_service::{timeout, TimeService, TimeServiceTrait};
 use aptos_types::{
     chain_id::ChainId,
-    network_address::{parse_dns_tcp, parse_ip_tcp, parse_memory, NetworkAddress},
+    network_address::{
+        parse_dns_tcp, parse_dns_udp_quic, parse_ip_tcp, parse_ip_udp_quic, parse_memory,
+        NetworkAddress,
+    },
     PeerId,
 };
 use futures::{
     future::{Future, FutureExt},
-    io::{AsyncRead, AsyncWrite},
+    io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt},
     stream::{Stream, StreamExt, TryStreamExt},
 };
 use serde::{Deserialize, Serialize};
@@ -58,10 +65,25 @@ pub const APTOS_TCP_TRANSPORT: tcp::TcpTransport = tcp::TcpTransport {
     tcp_buff_cfg: tcp::TCPBufferCfg::new(),
 };
 
+/// quic::Transport with Aptos-specific configuration applied.
+pub const APTOS_QUIC_TRANSPORT: quic::QuicTransport = quic::QuicTransport {
+    // Keep NATs and firewalls from dropping idle connections.
+    keep_alive_interval: Duration::from_secs(5),
+    max_idle_timeout: TRANSPORT_TIMEOUT,
+    // `Peer` opens one stream per protocol.
+    max_concurrent_streams: 256,
+};
+
 /// A trait alias for "socket-like" things.
-pub trait TSocket: AsyncRead + AsyncWrite + Send + fmt::Debug + Unpin + 'static {}
+pub trait TSocket:
+    AsyncRead + AsyncWrite + Substreams + Send + fmt::Debug + Unpin + 'static
+{
+}
 
-impl<T> TSocket for T where T: AsyncRead + AsyncWrite + Send + fmt::Debug + Unpin + 'static {}
+impl<T> TSocket for T where
+    T: AsyncRead + AsyncWrite + Substreams + Send + fmt::Debug + Unpin + 'static
+{
+}
 
 /// Unique local identifier for a connection.
 #[derive(Clone, Copy, Debug, Default, Deserialize, Eq, Hash, PartialEq, Serialize)]
@@ -237,6 +259,29 @@ fn add_pp_addr(proxy_protocol_enabled: bool, error: io::Error, addr: &NetworkAdd
     }
 }
 
+/// The additional streams of a QUIC connection are only protected by its TLS session, whose
+/// certificates aren't verified. Both ends compare the keying material exported from the
+/// session over the authenticated Noise channel, to make sure that the session ends at the
+/// peer we just authenticated rather than at someone in the middle.
+async fn verify_substreams<T: TSocket>(socket: &mut NoiseStream<T>) -> io::Result<()> {
+    let substreams = match socket.substreams() {
+        Some(substreams) => substreams,
+        None => return Ok(()),
+    };
+    let keying_material = substreams.export_keying_material()?;
+    socket.write_all(&keying_material).await?;
+    socket.flush().await?;
+    let mut remote_keying_material = [0; 32];
+    socket.read_exact(&mut remote_keying_material).await?;
+    if keying_material != remote_keying_material {
+        return Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            "QUIC keying material doesn't match the remote peer's",
+        ));
+    }
+    Ok(())
+}
+
 /// Upgrade an inbound connection. This means we run a Noise IK handshake for
 /// authentication and then negotiate common supported protocols. If
 /// `ctxt.noise.auth_mode` is `HandshakeAuthMode::Mutual( anti_replay_timestamps , trusted_peers )`,
@@ -290,6 +335,10 @@ async fn upgrade_inbound<T: TSocket>(
     let remote_pubkey = socket.get_remote_static();
     let addr = addr.append_prod_protos(remote_pubkey, HANDSHAKE_VERSION);
 
+    verify_substreams(&mut socket)
+        .await
+        .map_err(|err| add_pp_addr(proxy_protocol_enabled, err, &addr))?;
+
     // exchange HandshakeMsg
     let handshake_msg = HandshakeMsg {
         supported_protocols: ctxt.supported_protocols.clone(),
@@ -316,6 +365,12 @@ async fn upgrade_inbound<T: TSocket>(
             )
         })?;
 
+    // only keep the zstd dictionary protocols the peer can decompress
+    let application_protocols =
+        negotiate_compression_dictionaries(application_protocols, &mut socket)
+            .await
+            .map_err(|err| add_pp_addr(proxy_protocol_enabled, err, &addr))?;
+
     // return successful connection
     Ok(Connection {
         socket,
@@ -372,6 +427,8 @@ pub async fn upgrade_outbound<T: TSocket>(
     // sanity check: Noise IK should always guarantee this is true
     debug_assert_eq!(remote_pubkey, socket.get_remote_static());
 
+    verify_substreams(&mut socket).await?;
+
     // exchange HandshakeMsg
     let handshake_msg = HandshakeMsg {
         supported_protocols: ctxt.supported_protocols.clone(),
@@ -391,6 +448,10 @@ pub async fn upgrade_outbound<T: TSocket>(
             io::Error::new(io::ErrorKind::Other, e)
         })?;
 
+    // only keep the zstd dictionary protocols the peer can decompress
+    let application_protocols =
+        negotiate_compression_dictionaries(application_protocols, &mut socket).await?;
+
     // return successful connection
     Ok(Connection {
         socket,
@@ -406,6 +467,27 @@ pub async fn upgrade_outbound<T: TSocket>(
     })
 }
 
+/// If the negotiated application protocols include a zstd dictionary protocol,
+/// exchange the dictionaries known to each peer and drop the dictionary protocols
+/// whose dictionary the peer doesn't know. Both peers negotiate the same protocols,
+/// so either both or neither of them perform the exchange (and peers that don't
+/// support the dictionary protocols are unaffected).
+async fn negotiate_compression_dictionaries<T>(
+    application_protocols: ProtocolIdSet,
+    socket: &mut T,
+) -> io::Result<ProtocolIdSet>
+where
+    T: AsyncRead + AsyncWrite + Unpin,
+{
+    if !application_protocols.contains_zstd_dictionary_protocols() {
+        return Ok(application_protocols);
+    }
+
+    let own_dictionaries = CompressionDictionariesMsg::from_local_dictionaries();
+    let remote_dictionaries = exchange_compression_dictionaries(&own_dictionaries, socket).await?;
+    Ok(application_protocols.filter_zstd_dictionary_protocols(&remote_dictionaries))
+}
+
 /// The common AptosNet Transport.
 ///
 /// The base transport layer is pluggable, so long as it provides a reliable,
@@ -482,13 +564,15 @@ where
         let (base_transport_protos, base_transport_suffix) = parse_ip_tcp(protos)
             .map(|x| (&protos[..2], x.1))
             .or_else(|| parse_dns_tcp(protos).map(|x| (&protos[..2], x.1)))
+            .or_else(|| parse_ip_udp_quic(protos).map(|x| (&protos[..3], x.1)))
+            .or_else(|| parse_dns_udp_quic(protos).map(|x| (&protos[..3], x.1)))
             .or_else(|| parse_memory(protos).map(|x| (&protos[..1], x.1)))
             .ok_or_else(|| {
                 io::Error::new(
                     io::ErrorKind::InvalidInput,
                     format!(
                         "Unexpected dialing network address: '{}', expected: \
-                         memory, ip+tcp, or dns+tcp",
+                         memory, ip+tcp, dns+tcp, ip+udp+quic, or dns+udp+quic",
                         addr
                     ),
                 )
@@ -534,6 +618,9 @@ where
     /// `/dns/<ipaddr>/tcp/<port>` or
     /// `/dns4/<ipaddr>/tcp/<port>` or
     /// `/dns6/<ipaddr>/tcp/<port>`
+    ///
+    /// If the base transport is `QuicTransport`, then `/<base_transport>` is one
+    /// of the above with `/tcp/<port>` replaced by `/udp/<port>/quic`.
     pub fn dial(
         &self,
         peer_id: PeerId,
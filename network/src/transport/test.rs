@@ -581,3 +581,42 @@ fn test_tcp_transport_rejects_unauthed_dialer() {
         expect_ip4_tcp_noise_addr,
     );
 }
+
+//////////////////////////////////////
+// AptosNetTransport<QuicTransport> //
+//////////////////////////////////////
+
+/// Check that the network address matches the format
+/// `"/ip4/<ipaddr>/udp/<port>/quic/noise-ik/<pubkey>/handshake/<version>"`
+fn expect_ip4_quic_noise_addr(addr: &NetworkAddress) {
+    assert!(
+        matches!(addr.as_slice(), [
+            Ip4(_),
+            Udp(_),
+            Quic,
+            NoiseIK(_),
+            Handshake(_)
+        ]),
+        "addr: '{}'",
+        addr
+    );
+}
+
+#[test]
+fn test_quic_transport_mutual_auth() {
+    test_transport_success(
+        APTOS_QUIC_TRANSPORT,
+        Auth::Mutual,
+        "/ip4/127.0.0.1/udp/0/quic",
+        expect_ip4_quic_noise_addr,
+    );
+}
+
+#[test]
+fn test_quic_transport_rejects_unauthed_dialer() {
+    test_transport_rejects_unauthed_dialer(
+        APTOS_QUIC_TRANSPORT,
+        "/ip4/127.0.0.1/udp/0/quic",
+        expect_ip4_quic_noise_addr,
+    );
+}
@@ -4,6 +4,7 @@ use crate::{
     network_controller::{inbound_handler::InboundHandler, Message, MessageType, NetworkMessage},
     NetworkClient,
 };
+use aptos_logger::warn;
 use aptos_retrier::{fixed_retry_strategy, retry};
 use crossbeam_channel::{Receiver, Select};
 use std::{
@@ -107,6 +108,14 @@ impl OutboundHandler {
         ))
         .unwrap();
 
-        retry(fixed_retry_strategy(5, 20), || network_client.write(&msg)).unwrap();
+        // An unreachable peer must not take down the outbound handler (and with it every other
+        // outbound channel), so the message is dropped and it is up to the caller to detect that
+        // the peer is not responding.
+        if let Err(e) = retry(fixed_retry_strategy(5, 20), || network_client.write(&msg)) {
+            warn!(
+                "Dropping outbound message {:?} to {}: {:?}",
+                message_type, remote_addr, e
+            );
+        }
     }
 }
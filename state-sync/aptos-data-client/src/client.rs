@@ -36,7 +36,7 @@ use aptos_storage_service_types::{
     responses::{StorageServerSummary, StorageServiceResponse, TransactionOrOutputListWithProof},
     Epoch, StorageServiceMessage,
 };
-use aptos_time_service::TimeService;
+use aptos_time_service::{TimeService, TimeServiceTrait};
 use aptos_types::{
     epoch_change::EpochChangeProof,
     ledger_info::LedgerInfoWithSignatures,
@@ -44,8 +44,13 @@ use aptos_types::{
     transaction::{TransactionListWithProof, TransactionOutputListWithProof, Version},
 };
 use async_trait::async_trait;
+use futures::future::{self, Either};
 use rand::prelude::SliceRandom;
-use std::{fmt, sync::Arc, time::Duration};
+use std::{
+    fmt,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 use tokio::runtime::Handle;
 
 // Useful constants
@@ -82,6 +87,8 @@ pub struct AptosDataClient {
     global_summary_cache: Arc<RwLock<GlobalDataSummary>>,
     /// Used for generating the next request/response id.
     response_id_generator: Arc<U64IdGenerator>,
+    /// Time service used for the request hedging delays.
+    time_service: TimeService,
 }
 
 impl AptosDataClient {
@@ -104,6 +111,7 @@ impl AptosDataClient {
             ))),
             global_summary_cache: Arc::new(RwLock::new(GlobalDataSummary::empty())),
             response_id_generator: Arc::new(U64IdGenerator::new()),
+            time_service: time_service.clone(),
         };
 
         // Create the data summary poller
@@ -175,21 +183,33 @@ impl AptosDataClient {
     pub(crate) fn choose_peer_for_request(
         &self,
         request: &StorageServiceRequest,
+    ) -> crate::error::Result<PeerNetworkId, Error> {
+        self.choose_peer_for_request_with_exclusions(request, &[])
+    }
+
+    /// Choose a connected peer (that is not excluded) that can service
+    /// the given request. Returns an error if no such peer can be found.
+    fn choose_peer_for_request_with_exclusions(
+        &self,
+        request: &StorageServiceRequest,
+        excluded_peers: &[PeerNetworkId],
     ) -> crate::error::Result<PeerNetworkId, Error> {
         // All requests should be sent to prioritized peers (if possible).
         // If none can handle the request, fall back to the regular peers.
         let (priority_peers, regular_peers) = self.get_priority_and_regular_peers()?;
-        let priority_serviceable = self.identify_serviceable(priority_peers, request);
+        let priority_serviceable =
+            self.identify_serviceable(priority_peers, request, excluded_peers);
         let serviceable_peers = if !priority_serviceable.is_empty() {
             priority_serviceable
         } else {
-            self.identify_serviceable(regular_peers, request)
+            self.identify_serviceable(regular_peers, request, excluded_peers)
         };
 
-        // Randomly select a peer to handle the request
-        serviceable_peers
-            .choose(&mut rand::thread_rng())
-            .copied()
+        // Avoid peers that report they are lagging (if other peers are available)
+        let serviceable_peers = self.remove_lagging_peers(serviceable_peers);
+
+        // Select a peer to handle the request
+        self.select_peer_for_request(&serviceable_peers)
             .ok_or_else(|| {
                 Error::DataIsUnavailable(
                     format!("No connected peers are advertising that they can serve this data! Request: {:?}",request),
@@ -197,15 +217,50 @@ impl AptosDataClient {
             })
     }
 
+    /// Selects one of the given serviceable peers. If latency-aware peer
+    /// selection is enabled, peers with low latencies and high throughputs
+    /// are favored. Otherwise, a peer is selected at random.
+    fn select_peer_for_request(
+        &self,
+        serviceable_peers: &[PeerNetworkId],
+    ) -> Option<PeerNetworkId> {
+        if self.data_client_config.enable_latency_aware_peer_selection {
+            self.peer_states
+                .read()
+                .choose_peer_by_weight(serviceable_peers)
+        } else {
+            serviceable_peers.choose(&mut rand::thread_rng()).copied()
+        }
+    }
+
+    /// Removes the peers that report they are lagging from the given set of
+    /// serviceable peers. If all peers are lagging, the set is left unchanged.
+    fn remove_lagging_peers(&self, serviceable_peers: Vec<PeerNetworkId>) -> Vec<PeerNetworkId> {
+        let peer_states = self.peer_states.read();
+        let non_lagging_peers: Vec<_> = serviceable_peers
+            .iter()
+            .filter(|peer| !peer_states.is_lagging_peer(peer))
+            .copied()
+            .collect();
+
+        if non_lagging_peers.is_empty() {
+            serviceable_peers
+        } else {
+            non_lagging_peers
+        }
+    }
+
     /// Identifies the peers in the given set of prospective peers
-    /// that can service the specified request.
+    /// that can service the specified request (and are not excluded).
     fn identify_serviceable(
         &self,
         prospective_peers: Vec<PeerNetworkId>,
         request: &StorageServiceRequest,
+        excluded_peers: &[PeerNetworkId],
     ) -> Vec<PeerNetworkId> {
         prospective_peers
             .into_iter()
+            .filter(|peer| !excluded_peers.contains(peer))
             .filter(|peer| self.peer_states.read().can_service_request(peer, request))
             .collect::<Vec<_>>()
     }
@@ -328,10 +383,74 @@ impl AptosDataClient {
             error
         })?;
         let _timer = start_request_timer(&metrics::REQUEST_LATENCIES, &request.get_label(), peer);
+
+        // Optimistic fetches that take longer than usual are hedged
+        if request.data_request.is_optimistic_fetch()
+            && self.data_client_config.enable_optimistic_fetch_hedging
+        {
+            let hedging_delay = self.peer_states.read().get_optimistic_fetch_hedging_delay(
+                self.data_client_config.optimistic_fetch_hedging_percentile,
+            );
+            if let Some(hedging_delay) = hedging_delay {
+                return self
+                    .send_hedged_request_and_decode(
+                        peer,
+                        request,
+                        request_timeout_ms,
+                        hedging_delay,
+                    )
+                    .await;
+            }
+        }
+
         self.send_request_to_peer_and_decode(peer, request, request_timeout_ms)
             .await
     }
 
+    /// Sends a request to the given peer and decodes the response. If no
+    /// response is received before the hedging delay, the request is also
+    /// sent to another peer, and the first successful response is returned.
+    async fn send_hedged_request_and_decode<T, E>(
+        &self,
+        peer: PeerNetworkId,
+        request: StorageServiceRequest,
+        request_timeout_ms: u64,
+        hedging_delay: Duration,
+    ) -> crate::error::Result<Response<T>>
+    where
+        T: TryFrom<StorageServiceResponse, Error = E>,
+        E: Into<Error>,
+    {
+        // Send the request to the selected peer and wait for the hedging delay
+        let primary_request =
+            self.send_request_to_peer_and_decode(peer, request.clone(), request_timeout_ms);
+        futures::pin_mut!(primary_request);
+        let hedging_timer = self.time_service.sleep(hedging_delay);
+        futures::pin_mut!(hedging_timer);
+        if let Either::Left((result, _)) =
+            future::select(primary_request.as_mut(), hedging_timer).await
+        {
+            return result;
+        }
+
+        // Send the request to another peer (if one can service the request)
+        let hedged_peer = match self.choose_peer_for_request_with_exclusions(&request, &[peer]) {
+            Ok(hedged_peer) => hedged_peer,
+            Err(_) => return primary_request.await,
+        };
+        increment_request_counter(&metrics::HEDGED_REQUESTS, &request.get_label(), hedged_peer);
+        let hedged_request =
+            self.send_request_to_peer_and_decode(hedged_peer, request, request_timeout_ms);
+        futures::pin_mut!(hedged_request);
+
+        // Return the first successful response (or the last error)
+        match future::select(primary_request, hedged_request).await {
+            Either::Left((Ok(response), _)) | Either::Right((Ok(response), _)) => Ok(response),
+            Either::Left((Err(_), hedged_request)) => hedged_request.await,
+            Either::Right((Err(_), primary_request)) => primary_request.await,
+        }
+    }
+
     /// Sends a request to a specific peer and decodes the response
     pub async fn send_request_to_peer_and_decode<T, E>(
         &self,
@@ -394,16 +513,17 @@ impl AptosDataClient {
         increment_request_counter(&metrics::SENT_REQUESTS, &request.get_label(), peer);
 
         // Send the request and process the result
+        let request_start_time = Instant::now();
         let result = self
             .storage_service_client
-            .send_request(
+            .send_request_with_response_size(
                 peer,
                 Duration::from_millis(request_timeout_ms),
                 request.clone(),
             )
             .await;
         match result {
-            Ok(response) => {
+            Ok((response, num_response_bytes)) => {
                 trace!(
                     (LogSchema::new(LogEntry::StorageServiceResponse)
                         .event(LogEvent::ResponseSuccess)
@@ -421,6 +541,12 @@ impl AptosDataClient {
                 // is successful or failed but not both; on the other hand, this
                 // feels simpler for the consumer.
                 self.peer_states.write().update_score_success(peer);
+                self.peer_states.write().update_response_metrics(
+                    peer,
+                    &request,
+                    request_start_time.elapsed(),
+                    num_response_bytes as u64,
+                );
 
                 // Package up all of the context needed to fully report an error
                 // with this RPC.
@@ -471,7 +597,7 @@ impl AptosDataClient {
                     peer,
                 );
 
-                self.notify_bad_response(id, peer, &request, ErrorType::NotUseful);
+                self.notify_request_failure(peer);
                 Err(client_error)
             },
         }
@@ -490,6 +616,11 @@ impl AptosDataClient {
             .update_score_error(peer, error_type);
     }
 
+    /// Updates the score of the peer who failed to respond to a request
+    fn notify_request_failure(&self, peer: PeerNetworkId) {
+        self.peer_states.write().update_score_request_failure(peer);
+    }
+
     /// Creates a storage service request using the given data request
     /// and sends it across the network
     async fn create_and_send_storage_request<T, E>(
@@ -511,6 +642,23 @@ impl AptosDataClient {
     pub(crate) fn get_peer_states(&self) -> PeerStates {
         self.peer_states.read().clone()
     }
+
+    /// Updates the response metrics of the given peer for testing
+    #[cfg(test)]
+    pub(crate) fn update_response_metrics(
+        &self,
+        peer: PeerNetworkId,
+        request: &StorageServiceRequest,
+        latency: Duration,
+        num_response_bytes: u64,
+    ) {
+        self.peer_states.write().update_response_metrics(
+            peer,
+            request,
+            latency,
+            num_response_bytes,
+        );
+    }
 }
 
 #[async_trait]
@@ -48,6 +48,16 @@ pub static ERROR_RESPONSES: Lazy<IntCounterVec> = Lazy::new(|| {
     .unwrap()
 });
 
+/// Counter for tracking hedged requests (i.e., requests sent to a second peer)
+pub static HEDGED_REQUESTS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_data_client_hedged_requests",
+        "Counters related to hedged requests",
+        &["request_types", "network"]
+    )
+    .unwrap()
+});
+
 // Latency buckets for network latencies (seconds)
 const REQUEST_LATENCY_BUCKETS_SECS: [f64; 18] = [
     0.05, 0.1, 0.2, 0.3, 0.5, 0.75, 1.0, 1.5, 2.0, 3.0, 5.0, 7.5, 10.0, 15.0, 20.0, 30.0, 40.0,
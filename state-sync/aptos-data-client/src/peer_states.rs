@@ -12,15 +12,17 @@ use aptos_config::{
 };
 use aptos_logger::prelude::*;
 use aptos_netcore::transport::ConnectionOrigin;
-use aptos_network::application::storage::PeersAndMetadata;
+use aptos_network::application::{reputation::PeerEvent, storage::PeersAndMetadata};
 use aptos_storage_service_types::{
     requests::StorageServiceRequest, responses::StorageServerSummary,
 };
 use itertools::Itertools;
+use rand::seq::SliceRandom;
 use std::{
     cmp::min,
-    collections::{HashMap, HashSet},
+    collections::{HashMap, HashSet, VecDeque},
     sync::Arc,
+    time::Duration,
 };
 
 /// Scores for peer rankings based on preferences and behavior.
@@ -36,6 +38,19 @@ const MALICIOUS_MULTIPLIER: f64 = 0.8;
 /// Ignore a peer when their score dips below this threshold.
 const IGNORE_PEER_THRESHOLD: f64 = 25.0;
 
+/// The weight of each new sample in the response latency and throughput averages.
+const RESPONSE_METRICS_EWMA_WEIGHT: f64 = 0.2;
+/// Responses smaller than this are too small to estimate the peer's throughput.
+const MIN_THROUGHPUT_SAMPLE_BYTES: u64 = 16 * 1024; // 16 KiB
+/// The minimum selection weight of a peer. This ensures that slow peers are
+/// still selected occasionally (so that we notice if they become faster).
+const MIN_SELECTION_WEIGHT: f64 = 0.05;
+/// The number of recent optimistic fetch latencies used to calculate hedging delays.
+const MAX_OPTIMISTIC_FETCH_LATENCY_SAMPLES: usize = 100;
+/// The minimum number of optimistic fetch latencies required to hedge fetches.
+const MIN_OPTIMISTIC_FETCH_LATENCY_SAMPLES: usize = 10;
+
+#[derive(Clone, Copy, Debug)]
 pub(crate) enum ErrorType {
     /// A response or error that's not actively malicious but also doesn't help
     /// us make progress, e.g., timeouts, remote errors, invalid data, etc...
@@ -45,6 +60,15 @@ pub(crate) enum ErrorType {
     Malicious,
 }
 
+impl From<ErrorType> for PeerEvent {
+    fn from(error: ErrorType) -> Self {
+        match error {
+            ErrorType::NotUseful => PeerEvent::NotUseful,
+            ErrorType::Malicious => PeerEvent::Malicious,
+        }
+    }
+}
+
 impl From<ResponseError> for ErrorType {
     fn from(error: ResponseError) -> Self {
         match error {
@@ -63,6 +87,12 @@ pub struct PeerState {
     storage_summary: Option<StorageServerSummary>,
     /// For now, a simplified port of the original state-sync v1 scoring system.
     score: f64,
+    /// The moving average of the peer's response latencies (in milliseconds),
+    /// or `None` if the peer hasn't responded to a request yet.
+    response_latency_ms: Option<f64>,
+    /// The moving average of the peer's response throughput (in bytes per
+    /// second), or `None` if the peer hasn't responded with enough data yet.
+    response_throughput_bps: Option<f64>,
 }
 
 impl Default for PeerState {
@@ -70,6 +100,8 @@ impl Default for PeerState {
         Self {
             storage_summary: None,
             score: STARTING_SCORE,
+            response_latency_ms: None,
+            response_throughput_bps: None,
         }
     }
 }
@@ -102,6 +134,21 @@ impl PeerState {
         };
         self.score = f64::max(self.score * multiplier, MIN_SCORE);
     }
+
+    /// Updates the response latency and throughput averages of the peer
+    fn update_response_metrics(&mut self, latency: Duration, num_response_bytes: u64) {
+        let latency_ms = latency.as_secs_f64() * 1000.0;
+        self.response_latency_ms =
+            Some(update_moving_average(self.response_latency_ms, latency_ms));
+
+        if num_response_bytes >= MIN_THROUGHPUT_SAMPLE_BYTES && latency_ms > 0.0 {
+            let throughput_bps = num_response_bytes as f64 / latency.as_secs_f64();
+            self.response_throughput_bps = Some(update_moving_average(
+                self.response_throughput_bps,
+                throughput_bps,
+            ));
+        }
+    }
 }
 
 /// Contains all of the unbanned peers' most recent [`StorageServerSummary`] data
@@ -113,6 +160,7 @@ pub(crate) struct PeerStates {
     peer_to_state: HashMap<PeerNetworkId, PeerState>,
     in_flight_priority_polls: HashSet<PeerNetworkId>, // The priority peers with in-flight polls
     in_flight_regular_polls: HashSet<PeerNetworkId>,  // The regular peers with in-flight polls
+    optimistic_fetch_latencies_ms: VecDeque<u64>,     // The latencies of recent optimistic fetches
     peers_and_metadata: Arc<PeersAndMetadata>,
 }
 
@@ -128,6 +176,7 @@ impl PeerStates {
             peer_to_state: HashMap::new(),
             in_flight_priority_polls: HashSet::new(),
             in_flight_regular_polls: HashSet::new(),
+            optimistic_fetch_latencies_ms: VecDeque::new(),
             peers_and_metadata,
         }
     }
@@ -157,6 +206,9 @@ impl PeerStates {
 
     /// Updates the score of the peer according to a successful operation
     pub fn update_score_success(&mut self, peer: PeerNetworkId) {
+        self.peers_and_metadata
+            .report_peer_event(peer, PeerEvent::Useful);
+
         let old_score = self.peer_to_state.entry(peer).or_default().score;
         self.peer_to_state
             .entry(peer)
@@ -173,8 +225,24 @@ impl PeerStates {
         }
     }
 
-    /// Updates the score of the peer according to an error
+    /// Updates the score of the peer according to an error in its response. The
+    /// error is also reported to the shared peer reputations.
     pub fn update_score_error(&mut self, peer: PeerNetworkId, error: ErrorType) {
+        self.peers_and_metadata
+            .report_peer_event(peer, PeerEvent::from(error));
+        self.update_local_score_error(peer, error);
+    }
+
+    /// Updates the score of the peer according to a request that failed without
+    /// a response (e.g., a timeout or a disconnection). This says nothing about
+    /// the behaviour of the peer (it may just be overloaded or far away), so the
+    /// failure is not reported to the shared peer reputations.
+    pub fn update_score_request_failure(&mut self, peer: PeerNetworkId) {
+        self.update_local_score_error(peer, ErrorType::NotUseful);
+    }
+
+    /// Updates the local score of the peer according to an error
+    fn update_local_score_error(&mut self, peer: PeerNetworkId, error: ErrorType) {
         let old_score = self.peer_to_state.entry(peer).or_default().score;
         self.peer_to_state
             .entry(peer)
@@ -191,6 +259,127 @@ impl PeerStates {
         }
     }
 
+    /// Updates the response metrics of the peer according to a successful response.
+    /// Optimistic fetches are only answered once new data is available, so their
+    /// latencies are tracked separately (to calculate hedging delays).
+    pub fn update_response_metrics(
+        &mut self,
+        peer: PeerNetworkId,
+        request: &StorageServiceRequest,
+        latency: Duration,
+        num_response_bytes: u64,
+    ) {
+        if request.data_request.is_optimistic_fetch() {
+            if self.optimistic_fetch_latencies_ms.len() >= MAX_OPTIMISTIC_FETCH_LATENCY_SAMPLES {
+                self.optimistic_fetch_latencies_ms.pop_front();
+            }
+            self.optimistic_fetch_latencies_ms
+                .push_back(latency.as_millis() as u64);
+        } else {
+            self.peer_to_state
+                .entry(peer)
+                .or_default()
+                .update_response_metrics(latency, num_response_bytes);
+        }
+    }
+
+    /// Returns the delay after which an optimistic fetch should be hedged, i.e.,
+    /// the given percentile of the recent optimistic fetch latencies. Returns
+    /// `None` if there are not enough latencies to calculate the delay.
+    pub fn get_optimistic_fetch_hedging_delay(&self, percentile: u64) -> Option<Duration> {
+        let num_latencies = self.optimistic_fetch_latencies_ms.len();
+        if num_latencies < MIN_OPTIMISTIC_FETCH_LATENCY_SAMPLES {
+            return None;
+        }
+
+        let mut latencies: Vec<u64> = self.optimistic_fetch_latencies_ms.iter().copied().collect();
+        latencies.sort_unstable();
+        let index = ((num_latencies - 1) as u64 * min(percentile, 100) / 100) as usize;
+        latencies.get(index).copied().map(Duration::from_millis)
+    }
+
+    /// Selects one of the given peers, favoring peers with low response latencies
+    /// and high response throughputs. Peers that haven't been measured yet are
+    /// treated as the best peers (so that they are explored).
+    pub fn choose_peer_by_weight(&self, peers: &[PeerNetworkId]) -> Option<PeerNetworkId> {
+        // Estimate the latency and throughput of each peer
+        let estimates: Vec<_> = peers
+            .iter()
+            .map(|peer| {
+                let peer_state = self.peer_to_state.get(peer);
+                let latency_ms = peer_state
+                    .and_then(|peer_state| peer_state.response_latency_ms)
+                    .or_else(|| self.get_ping_latency_ms(peer));
+                let throughput_bps =
+                    peer_state.and_then(|peer_state| peer_state.response_throughput_bps);
+                (*peer, latency_ms, throughput_bps)
+            })
+            .collect();
+
+        // Weight each peer relative to the lowest latency and the highest throughput
+        let min_latency_ms = estimates
+            .iter()
+            .filter_map(|(_, latency_ms, _)| *latency_ms)
+            .fold(f64::INFINITY, f64::min);
+        let max_throughput_bps = estimates
+            .iter()
+            .filter_map(|(_, _, throughput_bps)| *throughput_bps)
+            .fold(0.0, f64::max);
+        let weighted_peers: Vec<_> = estimates
+            .into_iter()
+            .map(|(peer, latency_ms, throughput_bps)| {
+                let latency_weight = match latency_ms {
+                    Some(latency_ms) if latency_ms > 0.0 => min_latency_ms / latency_ms,
+                    _ => 1.0,
+                };
+                let throughput_weight = match throughput_bps {
+                    Some(throughput_bps) if max_throughput_bps > 0.0 => {
+                        throughput_bps / max_throughput_bps
+                    },
+                    _ => 1.0,
+                };
+                let weight = (latency_weight + throughput_weight) / 2.0;
+                (peer, f64::max(weight, MIN_SELECTION_WEIGHT))
+            })
+            .collect();
+
+        weighted_peers
+            .choose_weighted(&mut rand::thread_rng(), |(_, weight)| *weight)
+            .ok()
+            .map(|(peer, _)| *peer)
+    }
+
+    /// Returns true iff the peer reports (via the peer monitoring service)
+    /// that it is lagging behind the rest of the network.
+    pub fn is_lagging_peer(&self, peer: &PeerNetworkId) -> bool {
+        self.peers_and_metadata
+            .get_metadata_for_peer(*peer)
+            .ok()
+            .and_then(|peer_metadata| {
+                peer_metadata
+                    .get_peer_monitoring_metadata()
+                    .latest_node_health_response
+            })
+            .map(|node_health_response| {
+                node_health_response.is_lagging(self.data_client_config.max_peer_sync_lag_secs)
+            })
+            .unwrap_or(false)
+    }
+
+    /// Returns the average ping latency of the peer (as measured by the peer
+    /// monitoring service), if one exists.
+    fn get_ping_latency_ms(&self, peer: &PeerNetworkId) -> Option<f64> {
+        self.peers_and_metadata
+            .get_metadata_for_peer(*peer)
+            .ok()
+            .and_then(|peer_metadata| {
+                peer_metadata
+                    .get_peer_monitoring_metadata()
+                    .average_ping_latency_secs
+            })
+            .map(|latency_secs| latency_secs * 1000.0)
+    }
+
     /// Returns the number of in-flight priority polls
     pub fn num_in_flight_priority_polls(&self) -> u64 {
         self.in_flight_priority_polls.len() as u64
@@ -377,6 +566,17 @@ impl PeerStates {
     }
 }
 
+/// Returns the exponentially weighted moving average after adding the given sample
+fn update_moving_average(average: Option<f64>, sample: f64) -> f64 {
+    match average {
+        Some(average) => {
+            (RESPONSE_METRICS_EWMA_WEIGHT * sample)
+                + ((1.0 - RESPONSE_METRICS_EWMA_WEIGHT) * average)
+        },
+        None => sample,
+    }
+}
+
 /// To calculate the optimal chunk size, we take the median for each
 /// chunk size parameter. This works well when we have an honest
 /// majority that mostly agrees on the same chunk sizes.
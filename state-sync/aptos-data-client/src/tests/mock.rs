@@ -18,11 +18,12 @@ use aptos_network::{
     application::{interface::NetworkClient, metadata::ConnectionState, storage::PeersAndMetadata},
     peer_manager::{ConnectionRequestSender, PeerManagerRequest, PeerManagerRequestSender},
     protocols::{
-        network::{NetworkSender, NewNetworkSender},
+        network::{NetworkSender, NewNetworkSender, RpcError},
         wire::handshake::v1::ProtocolId,
     },
     transport::ConnectionMetadata,
 };
+use aptos_peer_monitoring_service_types::PeerMonitoringMetadata;
 use aptos_storage_interface::DbReader;
 use aptos_storage_service_client::StorageServiceClient;
 use aptos_storage_service_server::network::{NetworkRequest, ResponseSender};
@@ -38,13 +39,12 @@ use aptos_types::{
 };
 use async_trait::async_trait;
 use futures::StreamExt;
-use maplit::hashmap;
 use mockall::mock;
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc};
 
 /// A simple mock network for testing the data client
 pub struct MockNetwork {
-    network_id: NetworkId,
+    peer_network_ids: HashMap<PeerId, NetworkId>, // The networks of the added peers
     peer_mgr_reqs_rx: aptos_channel::Receiver<(PeerId, ProtocolId), PeerManagerRequest>,
     peers_and_metadata: Arc<PeersAndMetadata>,
 }
@@ -68,12 +68,14 @@ impl MockNetwork {
         let networks = networks
             .unwrap_or_else(|| vec![NetworkId::Validator, NetworkId::Vfn, NetworkId::Public]);
         let peers_and_metadata = PeersAndMetadata::new(&networks);
-        let client_network_id = NetworkId::Validator;
+        let network_senders = networks
+            .iter()
+            .map(|network_id| (*network_id, network_sender.clone()))
+            .collect();
         let network_client = NetworkClient::new(
             vec![],
             vec![ProtocolId::StorageServiceRpc],
-            hashmap! {
-            client_network_id => network_sender},
+            network_senders,
             peers_and_metadata.clone(),
         );
 
@@ -95,7 +97,7 @@ impl MockNetwork {
 
         // Create the mock network
         let mock_network = Self {
-            network_id: client_network_id,
+            peer_network_ids: HashMap::new(),
             peer_mgr_reqs_rx,
             peers_and_metadata,
         };
@@ -137,6 +139,7 @@ impl MockNetwork {
         self.peers_and_metadata
             .insert_connection_metadata(peer_network_id, connection_metadata)
             .unwrap();
+        self.peer_network_ids.insert(peer_id, network_id);
 
         // Return the new peer
         peer_network_id
@@ -152,6 +155,17 @@ impl MockNetwork {
         self.update_peer_state(peer, ConnectionState::Connected);
     }
 
+    /// Updates the peer monitoring metadata of the given peer
+    pub fn update_peer_monitoring_metadata(
+        &mut self,
+        peer: PeerNetworkId,
+        peer_monitoring_metadata: PeerMonitoringMetadata,
+    ) {
+        self.peers_and_metadata
+            .update_peer_monitoring_metadata(peer, peer_monitoring_metadata)
+            .unwrap();
+    }
+
     /// Updates the state of the given peer
     fn update_peer_state(&mut self, peer: PeerNetworkId, state: ConnectionState) {
         self.peers_and_metadata
@@ -163,7 +177,7 @@ impl MockNetwork {
     pub async fn next_request(&mut self) -> Option<NetworkRequest> {
         match self.peer_mgr_reqs_rx.next().await {
             Some(PeerManagerRequest::SendRpc(peer_id, network_request)) => {
-                let peer_network_id = PeerNetworkId::new(self.network_id, peer_id);
+                let peer_network_id = self.get_peer_network_id(peer_id);
                 let protocol_id = network_request.protocol_id;
                 let data = network_request.data;
                 let res_tx = network_request.res_tx;
@@ -186,6 +200,32 @@ impl MockNetwork {
             None => None,
         }
     }
+
+    /// Times out the next request sent from the client, and returns the peer it was sent to
+    pub async fn time_out_next_request(&mut self) -> Option<PeerNetworkId> {
+        match self.peer_mgr_reqs_rx.next().await {
+            Some(PeerManagerRequest::SendRpc(peer_id, network_request)) => {
+                let _ = network_request.res_tx.send(Err(RpcError::TimedOut));
+                Some(self.get_peer_network_id(peer_id))
+            },
+            Some(PeerManagerRequest::SendDirectSend(_, _)) => panic!("Unexpected direct send msg"),
+            None => None,
+        }
+    }
+
+    /// Returns the peers and metadata container of the network
+    pub fn get_peers_and_metadata(&self) -> Arc<PeersAndMetadata> {
+        self.peers_and_metadata.clone()
+    }
+
+    /// Returns the network id of the given (added) peer
+    fn get_peer_network_id(&self, peer_id: PeerId) -> PeerNetworkId {
+        let network_id = *self
+            .peer_network_ids
+            .get(&peer_id)
+            .expect("The request was sent to an unknown peer!");
+        PeerNetworkId::new(network_id, peer_id)
+    }
 }
 
 /// Creates a mock data client for testing
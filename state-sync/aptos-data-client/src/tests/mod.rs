@@ -7,4 +7,5 @@ pub mod mock;
 mod peers;
 mod poller;
 mod priority;
+mod selection;
 mod utils;
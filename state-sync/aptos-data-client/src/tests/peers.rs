@@ -8,9 +8,9 @@ use crate::{
     poller::poll_peer,
     tests::{mock::MockNetwork, utils},
 };
-use aptos_config::network_id::PeerNetworkId;
+use aptos_config::network_id::{NetworkId, PeerNetworkId};
 use aptos_storage_service_types::{
-    requests::DataRequest,
+    requests::{DataRequest, StorageServiceRequest},
     responses::{CompleteDataRange, DataResponse, StorageServerSummary, StorageServiceResponse},
     StorageServiceError,
 };
@@ -165,6 +165,40 @@ async fn bad_peer_is_eventually_banned_callback() {
         .contains(&CompleteDataRange::new(0, 200).unwrap()));
 }
 
+#[tokio::test]
+async fn upstream_peer_timeouts_are_not_reputation_events() {
+    ::aptos_logger::Logger::init_for_testing();
+    let (mut mock_network, _, client, _) = MockNetwork::new(None, None, None);
+    let peers_and_metadata = mock_network.get_peers_and_metadata();
+
+    // Add the upstream validator of the VFN
+    let upstream_peer = mock_network.add_peer_with_network_id(NetworkId::Vfn, true);
+    client.update_summary(upstream_peer, utils::create_storage_summary(100));
+
+    // Spawn a handler that times out every request
+    tokio::spawn(async move { while mock_network.time_out_next_request().await.is_some() {} });
+
+    // Send many requests to the upstream peer and verify they all time out
+    let request_timeout = client.get_response_timeout_ms();
+    for _ in 0..100 {
+        let storage_request =
+            StorageServiceRequest::new(DataRequest::GetStorageServerSummary, false);
+        let result = client
+            .send_request_to_peer_and_decode::<StorageServerSummary, _>(
+                upstream_peer,
+                storage_request,
+                request_timeout,
+            )
+            .await;
+        assert_matches!(result, Err(Error::TimeoutWaitingForResponse(_)));
+    }
+
+    // Verify the timeouts were not reported to the peer reputations, and the upstream
+    // peer was not banned.
+    assert_eq!(peers_and_metadata.get_peer_score(&upstream_peer), 0.0);
+    assert!(!peers_and_metadata.is_peer_banned(&upstream_peer));
+}
+
 #[tokio::test]
 async fn bad_peer_is_eventually_added_back() {
     ::aptos_logger::Logger::init_for_testing();
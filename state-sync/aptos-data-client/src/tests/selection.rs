@@ -0,0 +1,203 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    interface::AptosDataClientInterface,
+    tests::{mock::MockNetwork, utils},
+};
+use aptos_config::network_id::PeerNetworkId;
+use aptos_peer_monitoring_service_types::{
+    response::{MempoolHealthSummary, NodeHealthInformationResponse},
+    PeerMonitoringMetadata,
+};
+use aptos_storage_service_types::{
+    requests::{
+        DataRequest, NewTransactionsWithProofRequest, StorageServiceRequest,
+        TransactionsWithProofRequest,
+    },
+    responses::{DataResponse, StorageServiceResponse},
+};
+use aptos_types::transaction::TransactionListWithProof;
+use std::{collections::HashMap, time::Duration};
+
+#[tokio::test]
+async fn latency_aware_peer_selection() {
+    ::aptos_logger::Logger::init_for_testing();
+    let (mut mock_network, _, client, _) = MockNetwork::new(None, None, None);
+
+    // Add two priority peers that advertise the data
+    let fast_peer = mock_network.add_peer(true);
+    let slow_peer = mock_network.add_peer(true);
+    for peer in [fast_peer, slow_peer] {
+        client.update_summary(peer, utils::create_storage_summary(100));
+    }
+
+    // Record the response metrics of both peers (the fast peer responds 100x faster)
+    let storage_request = create_transactions_request(100);
+    let num_response_bytes = 1024 * 1024;
+    client.update_response_metrics(
+        fast_peer,
+        &storage_request,
+        Duration::from_millis(10),
+        num_response_bytes,
+    );
+    client.update_response_metrics(
+        slow_peer,
+        &storage_request,
+        Duration::from_millis(1000),
+        num_response_bytes,
+    );
+
+    // Verify the fast peer is favored, but the slow peer is still selected
+    let selection_counts = count_peer_selections(&client, &storage_request, 1000);
+    let fast_peer_count = selection_counts.get(&fast_peer).copied().unwrap_or(0);
+    let slow_peer_count = selection_counts.get(&slow_peer).copied().unwrap_or(0);
+    assert!(fast_peer_count > slow_peer_count * 5);
+    assert!(slow_peer_count > 0);
+
+    // Add a new peer (without any response metrics) and verify it is explored
+    let new_peer = mock_network.add_peer(true);
+    client.update_summary(new_peer, utils::create_storage_summary(100));
+    let selection_counts = count_peer_selections(&client, &storage_request, 1000);
+    assert!(selection_counts.get(&new_peer).copied().unwrap_or(0) > slow_peer_count);
+}
+
+#[tokio::test]
+async fn lagging_peer_selection() {
+    ::aptos_logger::Logger::init_for_testing();
+    let (mut mock_network, _, client, _) = MockNetwork::new(None, None, None);
+
+    // Add two priority peers that advertise the data
+    let healthy_peer = mock_network.add_peer(true);
+    let lagging_peer = mock_network.add_peer(true);
+    for peer in [healthy_peer, lagging_peer] {
+        client.update_summary(peer, utils::create_storage_summary(100));
+    }
+
+    // Update the node health of both peers (one of which is lagging)
+    mock_network.update_peer_monitoring_metadata(healthy_peer, create_node_health_metadata(1));
+    mock_network.update_peer_monitoring_metadata(lagging_peer, create_node_health_metadata(3600));
+
+    // Verify the lagging peer is never selected
+    let storage_request = create_transactions_request(100);
+    let selection_counts = count_peer_selections(&client, &storage_request, 100);
+    assert_eq!(selection_counts.get(&healthy_peer).copied(), Some(100));
+    assert_eq!(selection_counts.get(&lagging_peer), None);
+
+    // Update the health of the healthy peer so that both peers are lagging
+    mock_network.update_peer_monitoring_metadata(healthy_peer, create_node_health_metadata(3600));
+
+    // Verify both peers are selected again (as there are no alternatives)
+    let selection_counts = count_peer_selections(&client, &storage_request, 100);
+    assert!(selection_counts.get(&healthy_peer).copied().unwrap_or(0) > 0);
+    assert!(selection_counts.get(&lagging_peer).copied().unwrap_or(0) > 0);
+}
+
+#[tokio::test]
+async fn optimistic_fetch_hedging() {
+    ::aptos_logger::Logger::init_for_testing();
+    let (mut mock_network, mock_time, client, _) = MockNetwork::new(None, None, None);
+
+    // Add two priority peers that advertise the data
+    let known_version = 1000;
+    let peer_1 = mock_network.add_peer(true);
+    let peer_2 = mock_network.add_peer(true);
+    for peer in [peer_1, peer_2] {
+        client.update_summary(peer, utils::create_storage_summary(known_version));
+    }
+
+    // Record the latencies of several fast optimistic fetches
+    let optimistic_fetch_request = StorageServiceRequest::new(
+        DataRequest::GetNewTransactionsWithProof(NewTransactionsWithProofRequest {
+            known_version,
+            known_epoch: 0,
+            include_events: false,
+        }),
+        true,
+    );
+    for _ in 0..20 {
+        client.update_response_metrics(
+            peer_1,
+            &optimistic_fetch_request,
+            Duration::from_millis(10),
+            0,
+        );
+    }
+
+    // Spawn a handler that never responds to the first request (but
+    // responds to the hedged request, sent once the hedging delay elapses).
+    tokio::spawn(async move {
+        let unanswered_request = mock_network.next_request().await.unwrap();
+        mock_time.advance_ms_async(10).await;
+        let hedged_request = mock_network.next_request().await.unwrap();
+        assert_ne!(
+            unanswered_request.peer_network_id,
+            hedged_request.peer_network_id
+        );
+
+        let data_response = DataResponse::NewTransactionsWithProof((
+            TransactionListWithProof::new_empty(),
+            utils::create_ledger_info(known_version + 1),
+        ));
+        hedged_request
+            .response_sender
+            .send(Ok(StorageServiceResponse::new(data_response, true).unwrap()));
+
+        // Keep the first request pending until the test ends
+        let _ = mock_network.next_request().await;
+        drop(unanswered_request);
+    });
+
+    // Send the optimistic fetch and verify the hedged response is returned
+    let response = client
+        .get_new_transactions_with_proof(known_version, 0, false, 10_000)
+        .await
+        .unwrap();
+    assert_eq!(
+        response.payload.1.ledger_info().version(),
+        known_version + 1
+    );
+}
+
+/// Counts the number of times each peer is selected for the given request
+fn count_peer_selections(
+    client: &crate::client::AptosDataClient,
+    storage_request: &StorageServiceRequest,
+    num_selections: usize,
+) -> HashMap<PeerNetworkId, usize> {
+    let mut selection_counts = HashMap::new();
+    for _ in 0..num_selections {
+        let peer = client.choose_peer_for_request(storage_request).unwrap();
+        *selection_counts.entry(peer).or_insert(0) += 1;
+    }
+    selection_counts
+}
+
+/// Creates peer monitoring metadata with a node health response that reports the given sync lag
+fn create_node_health_metadata(sync_lag_secs: u64) -> PeerMonitoringMetadata {
+    let node_health_response = NodeHealthInformationResponse {
+        consensus_epoch: 0,
+        consensus_round: 0,
+        ledger_prune_window: None,
+        mempool_summary: MempoolHealthSummary::default(),
+        state_prune_window: None,
+        state_sync_lag_usecs: sync_lag_secs * 1_000_000,
+    };
+    PeerMonitoringMetadata {
+        latest_node_health_response: Some(node_health_response),
+        ..Default::default()
+    }
+}
+
+/// Creates a transactions request for the given version range
+fn create_transactions_request(end_version: u64) -> StorageServiceRequest {
+    StorageServiceRequest::new(
+        DataRequest::GetTransactionsWithProof(TransactionsWithProofRequest {
+            proof_version: end_version,
+            start_version: 0,
+            end_version,
+            include_events: false,
+        }),
+        true,
+    )
+}
@@ -13,7 +13,7 @@ use aptos_types::{
 };
 
 /// Creates a test ledger info at the given version
-fn create_ledger_info(version: Version) -> LedgerInfoWithSignatures {
+pub fn create_ledger_info(version: Version) -> LedgerInfoWithSignatures {
     LedgerInfoWithSignatures::new(
         LedgerInfo::new(
             BlockInfo::new(0, 0, HashValue::zero(), HashValue::zero(), version, 0, None),
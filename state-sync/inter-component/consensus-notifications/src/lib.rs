@@ -62,6 +62,21 @@ pub fn new_consensus_notifier_listener_pair(
     (consensus_notifier, consensus_listener)
 }
 
+/// This method returns an (ObserverPauseNotifier, ObserverPauseListener) pair that can be
+/// used to allow state sync to pause the consensus observer (e.g., before state sync resumes
+/// syncing and writing to storage).
+///
+/// Note: state sync should take the notifier and the consensus observer should take the listener.
+pub fn new_observer_pause_notifier_listener_pair() -> (ObserverPauseNotifier, ObserverPauseListener)
+{
+    let (notification_sender, notification_receiver) = mpsc::unbounded();
+
+    let observer_pause_notifier = ObserverPauseNotifier::new(notification_sender);
+    let observer_pause_listener = ObserverPauseListener::new(notification_receiver);
+
+    (observer_pause_notifier, observer_pause_listener)
+}
+
 /// The consensus component responsible for sending notifications and requests to
 /// state sync.
 ///
@@ -215,6 +230,81 @@ impl FusedStream for ConsensusNotificationListener {
     }
 }
 
+/// The state sync component responsible for pausing the consensus observer.
+#[derive(Clone, Debug)]
+pub struct ObserverPauseNotifier {
+    notification_sender: mpsc::UnboundedSender<ObserverPauseNotification>,
+}
+
+impl ObserverPauseNotifier {
+    fn new(notification_sender: mpsc::UnboundedSender<ObserverPauseNotification>) -> Self {
+        ObserverPauseNotifier {
+            notification_sender,
+        }
+    }
+
+    /// Notifies the consensus observer to pause execution. The returned receiver
+    /// resolves once the observer has stopped its execution pipeline.
+    pub fn pause_observer(&self) -> Result<oneshot::Receiver<()>, Error> {
+        let (pause_notification, callback_receiver) = ObserverPauseNotification::new();
+        self.notification_sender
+            .unbounded_send(pause_notification)
+            .map_err(|error| {
+                Error::NotificationError(format!(
+                    "Failed to notify the consensus observer to pause! Error: {:?}",
+                    error
+                ))
+            })?;
+        Ok(callback_receiver)
+    }
+}
+
+/// The consensus observer component responsible for handling pause notifications.
+#[derive(Debug)]
+pub struct ObserverPauseListener {
+    notification_receiver: mpsc::UnboundedReceiver<ObserverPauseNotification>,
+}
+
+impl ObserverPauseListener {
+    fn new(notification_receiver: mpsc::UnboundedReceiver<ObserverPauseNotification>) -> Self {
+        ObserverPauseListener {
+            notification_receiver,
+        }
+    }
+}
+
+impl Stream for ObserverPauseListener {
+    type Item = ObserverPauseNotification;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Pin::new(&mut self.get_mut().notification_receiver).poll_next(cx)
+    }
+}
+
+impl FusedStream for ObserverPauseListener {
+    fn is_terminated(&self) -> bool {
+        self.notification_receiver.is_terminated()
+    }
+}
+
+/// A notification to pause the consensus observer (so that state sync can write to storage).
+#[derive(Debug)]
+pub struct ObserverPauseNotification {
+    callback: oneshot::Sender<()>,
+}
+
+impl ObserverPauseNotification {
+    fn new() -> (Self, oneshot::Receiver<()>) {
+        let (callback, callback_receiver) = oneshot::channel();
+        (ObserverPauseNotification { callback }, callback_receiver)
+    }
+
+    /// Acknowledges the notification once the observer's execution pipeline is stopped
+    pub fn acknowledge(self) {
+        let _ = self.callback.send(());
+    }
+}
+
 #[derive(Debug)]
 pub enum ConsensusNotification {
     NotifyCommit(ConsensusCommitNotification),
@@ -415,6 +505,29 @@ mod tests {
         assert_err!(notify_result);
     }
 
+    #[test]
+    fn test_observer_pause_notifications() {
+        // Create the observer pause notifier and listener
+        let (observer_pause_notifier, mut observer_pause_listener) =
+            crate::new_observer_pause_notifier_listener_pair();
+
+        // Send a pause notification and verify it isn't acknowledged yet
+        let mut callback_receiver = observer_pause_notifier.pause_observer().unwrap();
+        assert_matches!(callback_receiver.try_recv(), Ok(None));
+
+        // Acknowledge the notification and verify the acknowledgement arrives
+        let pause_notification = observer_pause_listener
+            .select_next_some()
+            .now_or_never()
+            .unwrap();
+        pause_notification.acknowledge();
+        assert_matches!(callback_receiver.try_recv(), Ok(Some(())));
+
+        // Drop the listener and verify the notifier returns an error
+        drop(observer_pause_listener);
+        assert_err!(observer_pause_notifier.pause_observer());
+    }
+
     fn create_user_transaction() -> Transaction {
         let private_key = Ed25519PrivateKey::generate_for_testing();
         let public_key = private_key.public_key();
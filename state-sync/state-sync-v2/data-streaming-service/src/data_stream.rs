@@ -121,7 +121,7 @@ impl<T: AptosDataClientInterface + Send + Clone + 'static> DataStream<T> {
         let data_stream_listener = DataStreamListener::new(data_stream_id, notification_receiver);
 
         // Create a new stream engine
-        let stream_engine = StreamEngine::new(stream_request, advertised_data)?;
+        let stream_engine = StreamEngine::new(data_stream_config, stream_request, advertised_data)?;
 
         // Create a new data stream
         let data_stream = Self {
@@ -376,7 +376,8 @@ impl<T: AptosDataClientInterface + Send + Clone + 'static> DataStream<T> {
     }
 
     /// Processes any data client responses that have been received. Note: the
-    /// responses must be processed in FIFO order.
+    /// responses must be processed in FIFO order, unless the stream engine
+    /// allows them to be sent out of order.
     pub async fn process_data_responses(
         &mut self,
         global_data_summary: GlobalDataSummary,
@@ -430,7 +431,7 @@ impl<T: AptosDataClientInterface + Send + Clone + 'static> DataStream<T> {
                     },
                 }
             } else {
-                break; // The first response hasn't arrived yet.
+                break; // The next response hasn't arrived yet.
             }
         }
 
@@ -440,9 +441,18 @@ impl<T: AptosDataClientInterface + Send + Clone + 'static> DataStream<T> {
     }
 
     /// Pops and returns the first pending client response if the response has
-    /// been received. Returns `None` otherwise.
+    /// been received. Returns `None` otherwise. If the stream engine allows
+    /// out of order responses, the first received response is returned instead.
     fn pop_pending_response_queue(&mut self) -> Result<Option<PendingClientResponse>, Error> {
+        let can_send_out_of_order = self.stream_engine.can_send_out_of_order();
         let sent_data_requests = self.get_sent_data_requests()?;
+        if can_send_out_of_order {
+            let received_response_index = sent_data_requests
+                .iter()
+                .position(|data_request| data_request.lock().client_response.is_some());
+            return Ok(received_response_index.and_then(|index| sent_data_requests.remove(index)));
+        }
+
         let pending_client_response = if let Some(data_request) = sent_data_requests.front() {
             if data_request.lock().client_response.is_some() {
                 // We've received a response! Pop the requests off the queue.
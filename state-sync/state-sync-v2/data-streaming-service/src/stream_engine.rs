@@ -23,6 +23,7 @@ use crate::{
         Epoch, GetAllEpochEndingLedgerInfosRequest, GetAllStatesRequest, StreamRequest,
     },
 };
+use aptos_config::config::DataStreamingServiceConfig;
 use aptos_data_client::{
     global_summary::{AdvertisedData, GlobalDataSummary},
     interface::ResponsePayload,
@@ -31,7 +32,7 @@ use aptos_id_generator::{IdGenerator, U64IdGenerator};
 use aptos_logger::prelude::*;
 use aptos_types::{ledger_info::LedgerInfoWithSignatures, transaction::Version};
 use enum_dispatch::enum_dispatch;
-use std::{cmp, sync::Arc};
+use std::{cmp, collections::BTreeMap, sync::Arc};
 
 macro_rules! invalid_client_request {
     ($client_request:expr, $stream_engine:expr) => {
@@ -78,6 +79,15 @@ pub trait DataStreamEngine {
     /// Returns true iff the stream has sent all data to the stream listener.
     fn is_stream_complete(&self) -> bool;
 
+    /// Returns true iff responses can be sent along the stream out of order
+    /// (i.e., as soon as they arrive, and not in the order they were requested).
+    ///
+    /// Note: Most engines require data to be sent in order, so a default
+    /// implementation that returns false is provided.
+    fn can_send_out_of_order(&self) -> bool {
+        false
+    }
+
     /// Notifies the data stream engine that a timeout was encountered when
     /// trying to send the subscription request.
     ///
@@ -117,6 +127,7 @@ pub enum StreamEngine {
 
 impl StreamEngine {
     pub fn new(
+        data_stream_config: DataStreamingServiceConfig,
         stream_request: &StreamRequest,
         advertised_data: &AdvertisedData,
     ) -> Result<Self, Error> {
@@ -130,7 +141,9 @@ impl StreamEngine {
             StreamRequest::ContinuouslyStreamTransactionsOrOutputs(_) => {
                 Ok(ContinuousTransactionStreamEngine::new(stream_request)?.into())
             },
-            StreamRequest::GetAllStates(request) => Ok(StateStreamEngine::new(request)?.into()),
+            StreamRequest::GetAllStates(request) => {
+                Ok(StateStreamEngine::new(data_stream_config, request)?.into())
+            },
             StreamRequest::GetAllEpochEndingLedgerInfos(request) => {
                 Ok(EpochEndingStreamEngine::new(request, advertised_data)?.into())
             },
@@ -172,10 +185,21 @@ pub struct StateStreamEngine {
 
     // True iff all data has been sent across the stream.
     pub stream_is_complete: bool,
+
+    // The maximum number of state value chunks that can be sent along the
+    // stream ahead of the next stream index (i.e., out of order).
+    pub max_out_of_order_chunks: u64,
+
+    // The state index ranges (start index to end index) that have already been
+    // sent along the stream out of order, i.e., after the next stream index.
+    pub sent_out_of_order_ranges: BTreeMap<u64, u64>,
 }
 
 impl StateStreamEngine {
-    fn new(request: &GetAllStatesRequest) -> Result<Self, Error> {
+    fn new(
+        data_stream_config: DataStreamingServiceConfig,
+        request: &GetAllStatesRequest,
+    ) -> Result<Self, Error> {
         Ok(StateStreamEngine {
             request: request.clone(),
             state_num_requested: false,
@@ -183,9 +207,69 @@ impl StateStreamEngine {
             next_stream_index: request.start_index,
             next_request_index: request.start_index,
             stream_is_complete: false,
+            max_out_of_order_chunks: data_stream_config.max_out_of_order_state_chunks,
+            sent_out_of_order_ranges: BTreeMap::new(),
         })
     }
 
+    /// Returns the highest state index that can currently be requested. If
+    /// chunks can be sent out of order, this bounds how far requests can get
+    /// ahead of the stream (as the listener must buffer out of order chunks).
+    fn get_max_request_index(
+        &self,
+        end_state_index: u64,
+        state_chunk_size: u64,
+    ) -> Result<u64, Error> {
+        if self.max_out_of_order_chunks == 0 {
+            return Ok(end_state_index);
+        }
+
+        let max_request_index = self
+            .max_out_of_order_chunks
+            .checked_add(1)
+            .and_then(|num_chunks| num_chunks.checked_mul(state_chunk_size))
+            .and_then(|num_states| num_states.checked_add(self.next_stream_index))
+            .and_then(|index| index.checked_sub(1))
+            .ok_or_else(|| Error::IntegerOverflow("Max request index has overflown!".into()))?;
+        Ok(cmp::min(end_state_index, max_request_index))
+    }
+
+    /// Verifies the given state index range can be sent along the stream
+    /// and updates the sent state tracking accordingly.
+    fn update_sent_state_tracking(
+        &mut self,
+        start_index: u64,
+        end_index: u64,
+    ) -> Result<(), Error> {
+        // Verify the indices of the chunk
+        if self.max_out_of_order_chunks == 0 {
+            verify_client_request_indices(self.next_stream_index, start_index, end_index)?;
+        } else if start_index < self.next_stream_index
+            || end_index < start_index
+            || end_index >= self.next_request_index
+            || self.sent_out_of_order_ranges.contains_key(&start_index)
+        {
+            return Err(Error::UnexpectedErrorEncountered(format!(
+                "Invalid state index range found! Start index: {:?}, end index: {:?}, \
+                next stream index: {:?}, next request index: {:?}",
+                start_index, end_index, self.next_stream_index, self.next_request_index
+            )));
+        }
+
+        // Move the next stream index past all contiguous sent ranges
+        self.sent_out_of_order_ranges.insert(start_index, end_index);
+        while let Some(end_index) = self
+            .sent_out_of_order_ranges
+            .remove(&self.next_stream_index)
+        {
+            self.next_stream_index = end_index
+                .checked_add(1)
+                .ok_or_else(|| Error::IntegerOverflow("Next stream index has overflown!".into()))?;
+        }
+
+        Ok(())
+    }
+
     fn update_request_tracking(
         &mut self,
         client_requests: &[DataClientRequest],
@@ -225,13 +309,19 @@ impl DataStreamEngine for StateStreamEngine {
             let end_state_index = number_of_states
                 .checked_sub(1)
                 .ok_or_else(|| Error::IntegerOverflow("End state index has overflown!".into()))?;
+            let state_chunk_size = global_data_summary.optimal_chunk_sizes.state_chunk_size;
+            let max_request_index =
+                self.get_max_request_index(end_state_index, state_chunk_size)?;
+            if self.next_request_index > max_request_index {
+                return Ok(vec![]); // Wait for the stream to catch up
+            }
 
             // Create the client requests
             let client_requests = create_data_client_requests(
                 self.next_request_index,
-                end_state_index,
+                max_request_index,
                 max_number_of_requests,
-                global_data_summary.optimal_chunk_sizes.state_chunk_size,
+                state_chunk_size,
                 self.clone().into(),
             )?;
             self.update_request_tracking(&client_requests)?;
@@ -267,6 +357,10 @@ impl DataStreamEngine for StateStreamEngine {
         self.stream_is_complete
     }
 
+    fn can_send_out_of_order(&self) -> bool {
+        self.max_out_of_order_chunks > 0 && self.number_of_states.is_some()
+    }
+
     fn transform_client_response_into_notification(
         &mut self,
         client_request: &DataClientRequest,
@@ -275,24 +369,11 @@ impl DataStreamEngine for StateStreamEngine {
     ) -> Result<Option<DataNotification>, Error> {
         match client_request {
             StateValuesWithProof(request) => {
-                verify_client_request_indices(
-                    self.next_stream_index,
-                    request.start_index,
-                    request.end_index,
-                )?;
-
                 // Update the local stream notification tracker
-                self.next_stream_index = request.end_index.checked_add(1).ok_or_else(|| {
-                    Error::IntegerOverflow("Next stream index has overflown!".into())
-                })?;
+                self.update_sent_state_tracking(request.start_index, request.end_index)?;
 
                 // Check if the stream is complete
-                if request.end_index
-                    == self
-                        .get_number_of_states()?
-                        .checked_sub(1)
-                        .ok_or_else(|| Error::IntegerOverflow("End index has overflown!".into()))?
-                {
+                if self.next_stream_index == self.get_number_of_states()? {
                     self.stream_is_complete = true;
                 }
 
@@ -6,8 +6,9 @@ use crate::{
     data_notification::{
         DataClientRequest, DataPayload, EpochEndingLedgerInfosRequest,
         NewTransactionOutputsWithProofRequest, NewTransactionsOrOutputsWithProofRequest,
-        NewTransactionsWithProofRequest, PendingClientResponse, TransactionOutputsWithProofRequest,
-        TransactionsOrOutputsWithProofRequest, TransactionsWithProofRequest,
+        NewTransactionsWithProofRequest, PendingClientResponse, StateValuesWithProofRequest,
+        TransactionOutputsWithProofRequest, TransactionsOrOutputsWithProofRequest,
+        TransactionsWithProofRequest,
     },
     data_stream::{DataStream, DataStreamListener},
     streaming_client::{
@@ -316,11 +317,12 @@ async fn test_epoch_stream_out_of_order_responses() {
 
 #[tokio::test]
 async fn test_state_stream_out_of_order_responses() {
-    // Create a state value data stream
+    // Create a state value data stream (that sends chunks in order)
     let max_concurrent_state_requests = 6;
     let streaming_service_config = DataStreamingServiceConfig {
         max_concurrent_requests: 1,
         max_concurrent_state_requests,
+        max_out_of_order_state_chunks: 0,
         ..Default::default()
     };
     let (mut data_stream, mut stream_listener) = create_state_value_stream(
@@ -390,6 +392,50 @@ async fn test_state_stream_out_of_order_responses() {
     assert_none!(stream_listener.select_next_some().now_or_never());
 }
 
+#[tokio::test]
+async fn test_state_stream_out_of_order_window() {
+    // Create a state value data stream (that sends chunks out of order)
+    let streaming_service_config = DataStreamingServiceConfig {
+        max_concurrent_requests: 1,
+        max_concurrent_state_requests: 6,
+        max_out_of_order_state_chunks: 2,
+        ..Default::default()
+    };
+    let (mut data_stream, mut stream_listener) = create_state_value_stream(
+        AptosDataClientConfig::default(),
+        streaming_service_config,
+        MIN_ADVERTISED_STATES,
+    );
+
+    // Initialize the data stream and set a response for the number of state values
+    let global_data_summary = create_global_data_summary(1);
+    initialize_data_requests(&mut data_stream, &global_data_summary);
+    set_num_state_values_response_in_queue(&mut data_stream, 0);
+    process_data_responses(&mut data_stream, &global_data_summary).await;
+
+    // Verify only three requests have been made (the window limits the requests)
+    verify_pending_state_value_requests(&mut data_stream, &[0, 1, 2]);
+
+    // Set a response for the third request and verify a notification is sent
+    set_state_value_response_in_queue(&mut data_stream, 2);
+    process_data_responses(&mut data_stream, &global_data_summary).await;
+    verify_state_value_notification(&mut stream_listener).await;
+    verify_pending_state_value_requests(&mut data_stream, &[0, 1]);
+
+    // Set a response for the first request and verify the window moves by one
+    set_state_value_response_in_queue(&mut data_stream, 0);
+    process_data_responses(&mut data_stream, &global_data_summary).await;
+    verify_state_value_notification(&mut stream_listener).await;
+    verify_pending_state_value_requests(&mut data_stream, &[1, 3]);
+
+    // Set a response for the second request and verify the window moves past the third
+    set_state_value_response_in_queue(&mut data_stream, 0);
+    process_data_responses(&mut data_stream, &global_data_summary).await;
+    verify_state_value_notification(&mut stream_listener).await;
+    verify_pending_state_value_requests(&mut data_stream, &[3, 4, 5]);
+    assert_none!(stream_listener.select_next_some().now_or_never());
+}
+
 #[tokio::test]
 async fn test_continuous_stream_epoch_change_retry() {
     // Create a test streaming service config
@@ -1132,6 +1178,7 @@ fn set_state_value_response_in_queue(
             last_key: Default::default(),
             raw_values: vec![],
             proof: SparseMerkleRangeProof::new(vec![]),
+            left_siblings: vec![],
             root_hash: Default::default(),
         }),
     )));
@@ -1291,6 +1338,36 @@ fn get_pending_client_request(
     client_request
 }
 
+/// Verifies the pending client requests on the given stream are state
+/// value requests (of a single state) for the specified indices.
+fn verify_pending_state_value_requests(
+    data_stream: &mut DataStream<MockAptosDataClient>,
+    expected_indices: &[u64],
+) {
+    let (sent_requests, _) = data_stream.get_sent_requests_and_notifications();
+    let num_sent_requests = sent_requests.as_ref().unwrap().len();
+    assert_eq!(num_sent_requests, expected_indices.len());
+    for (i, expected_index) in expected_indices.iter().enumerate() {
+        assert_eq!(
+            get_pending_client_request(data_stream, i),
+            DataClientRequest::StateValuesWithProof(StateValuesWithProofRequest {
+                version: MIN_ADVERTISED_STATES,
+                start_index: *expected_index,
+                end_index: *expected_index,
+            })
+        );
+    }
+}
+
+/// Verifies that a state value notification is received along the stream
+async fn verify_state_value_notification(stream_listener: &mut DataStreamListener) {
+    let data_notification = get_data_notification(stream_listener).await.unwrap();
+    assert_matches!(
+        data_notification.data_payload,
+        DataPayload::StateValuesWithProof(_)
+    );
+}
+
 /// Waits for a subscription notification along the given
 /// listener and continues to drive progress until one is received.
 /// Verifies the notification when it is received.
@@ -9,6 +9,7 @@ use crate::{
     streaming_client::{GetAllEpochEndingLedgerInfosRequest, StreamRequest},
     tests::utils::initialize_logger,
 };
+use aptos_config::config::DataStreamingServiceConfig;
 use aptos_data_client::{
     global_summary::{GlobalDataSummary, OptimalChunkSizes},
     interface::ResponsePayload,
@@ -144,7 +145,11 @@ fn test_epoch_ending_stream_engine() {
 
     // Try to create a stream engine where there is no advertised data
     // and verify an error is returned.
-    let result = StreamEngine::new(&stream_request, &GlobalDataSummary::empty().advertised_data);
+    let result = StreamEngine::new(
+        DataStreamingServiceConfig::default(),
+        &stream_request,
+        &GlobalDataSummary::empty().advertised_data,
+    );
     assert_matches!(result, Err(Error::DataIsUnavailable(_)));
 
     // Create a data summary with various advertised epoch ranges (highest is one)
@@ -158,7 +163,11 @@ fn test_epoch_ending_stream_engine() {
     ];
 
     // Try to create a stream engine where the highest epoch is one
-    let result = StreamEngine::new(&stream_request, &global_data_summary.advertised_data);
+    let result = StreamEngine::new(
+        DataStreamingServiceConfig::default(),
+        &stream_request,
+        &global_data_summary.advertised_data,
+    );
     assert_ok!(result);
 
     // Create a global data summary with non-zero advertised epoch ranges
@@ -173,7 +182,13 @@ fn test_epoch_ending_stream_engine() {
     ];
 
     // Create a new data stream engine and verify the highest epoch is chosen
-    match StreamEngine::new(&stream_request, &global_data_summary.advertised_data).unwrap() {
+    match StreamEngine::new(
+        DataStreamingServiceConfig::default(),
+        &stream_request,
+        &global_data_summary.advertised_data,
+    )
+    .unwrap()
+    {
         StreamEngine::EpochEndingStreamEngine(stream_engine) => {
             assert_eq!(stream_engine.end_epoch, 1000);
         },
@@ -258,7 +273,13 @@ fn create_epoch_ending_stream_engine(start_epoch: u64, end_epoch: u64) -> EpochE
         .epoch_ending_ledger_infos = vec![CompleteDataRange::new(start_epoch, end_epoch).unwrap()];
 
     // Create a new epoch ending stream engine
-    match StreamEngine::new(&stream_request, &global_data_summary.advertised_data).unwrap() {
+    match StreamEngine::new(
+        DataStreamingServiceConfig::default(),
+        &stream_request,
+        &global_data_summary.advertised_data,
+    )
+    .unwrap()
+    {
         StreamEngine::EpochEndingStreamEngine(stream_engine) => stream_engine,
         unexpected_engine => {
             panic!(
@@ -19,7 +19,8 @@ use crate::{
     },
 };
 use aptos_config::config::{AptosDataClientConfig, DataStreamingServiceConfig};
-use claims::{assert_le, assert_matches, assert_ok, assert_some};
+use claims::{assert_ge, assert_le, assert_matches, assert_none, assert_ok, assert_some};
+use std::collections::BTreeMap;
 
 macro_rules! unexpected_payload_type {
     ($received:expr) => {
@@ -125,6 +126,52 @@ async fn test_notifications_state_values_limited_chunks() {
     }
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn test_notifications_state_values_out_of_order() {
+    // Create a new streaming client and service that sends chunks out of order
+    let streaming_client = create_streaming_client_and_service_with_out_of_order_chunks();
+
+    // Request a state value stream and get a data stream listener
+    let mut stream_listener = streaming_client
+        .get_all_state_values(MAX_ADVERTISED_STATES, None)
+        .await
+        .unwrap();
+
+    // Read the data notifications from the stream and buffer the chunks
+    // that arrive ahead of the next expected index (as the bootstrapper does).
+    let mut next_expected_index = 0;
+    let mut pending_chunks = BTreeMap::new();
+    loop {
+        let data_notification = get_data_notification(&mut stream_listener).await.unwrap();
+        match data_notification.data_payload {
+            DataPayload::StateValuesWithProof(state_values_with_proof) => {
+                // Verify the chunk hasn't already been received
+                let first_index = state_values_with_proof.first_index;
+                assert_ge!(first_index, next_expected_index);
+                assert_none!(pending_chunks.get(&first_index));
+
+                // Verify the last index matches the state value list length
+                let num_state_values = state_values_with_proof.raw_values.len() as u64;
+                assert_eq!(
+                    state_values_with_proof.last_index,
+                    first_index + num_state_values - 1,
+                );
+
+                // Process all chunks that are now in order
+                pending_chunks.insert(first_index, num_state_values);
+                while let Some(num_state_values) = pending_chunks.remove(&next_expected_index) {
+                    next_expected_index += num_state_values;
+                }
+            },
+            DataPayload::EndOfStream => {
+                assert!(pending_chunks.is_empty());
+                return assert_eq!(next_expected_index, TOTAL_NUM_STATE_VALUES);
+            },
+            data_payload => unexpected_payload_type!(data_payload),
+        }
+    }
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn test_notifications_state_values_multiple_streams() {
     // Create a new streaming client and service
@@ -1492,6 +1539,23 @@ fn create_streaming_client_and_service_with_chunk_limits() -> StreamingServiceCl
     create_streaming_client_and_spawn_server(false, true, true)
 }
 
+fn create_streaming_client_and_service_with_out_of_order_chunks() -> StreamingServiceClient {
+    // Use the default limit on the number of out of order state chunks
+    let data_streaming_service_config = DataStreamingServiceConfig {
+        max_concurrent_requests: 3,
+        max_concurrent_state_requests: 6,
+        ..Default::default()
+    };
+    let (client, service) = create_streaming_client_and_server_with_config(
+        false,
+        false,
+        false,
+        data_streaming_service_config,
+    );
+    tokio::spawn(service.start_service());
+    client
+}
+
 fn create_streaming_client_and_spawn_server(
     data_beyond_highest_advertised: bool,
     limit_chunk_sizes: bool,
@@ -1513,6 +1577,32 @@ pub fn create_streaming_client_and_server(
 ) -> (
     StreamingServiceClient,
     DataStreamingService<MockAptosDataClient>,
+) {
+    // Create the data streaming service config (the state value
+    // tests expect the chunks to be sent along the stream in order).
+    let data_streaming_service_config = DataStreamingServiceConfig {
+        max_concurrent_requests: 3,
+        max_concurrent_state_requests: 6,
+        max_out_of_order_state_chunks: 0,
+        ..Default::default()
+    };
+
+    create_streaming_client_and_server_with_config(
+        data_beyond_highest_advertised,
+        limit_chunk_sizes,
+        skip_emulate_network_latencies,
+        data_streaming_service_config,
+    )
+}
+
+fn create_streaming_client_and_server_with_config(
+    data_beyond_highest_advertised: bool,
+    limit_chunk_sizes: bool,
+    skip_emulate_network_latencies: bool,
+    data_streaming_service_config: DataStreamingServiceConfig,
+) -> (
+    StreamingServiceClient,
+    DataStreamingService<MockAptosDataClient>,
 ) {
     initialize_logger();
 
@@ -1530,13 +1620,6 @@ pub fn create_streaming_client_and_server(
         true,
     );
 
-    // Create the data streaming service config
-    let data_streaming_service_config = DataStreamingServiceConfig {
-        max_concurrent_requests: 3,
-        max_concurrent_state_requests: 6,
-        ..Default::default()
-    };
-
     // Create the streaming service and connect it to the listener
     let streaming_service = DataStreamingService::new(
         aptos_data_client_config,
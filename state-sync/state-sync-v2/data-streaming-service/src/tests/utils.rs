@@ -276,6 +276,7 @@ impl AptosDataClientInterface for MockAptosDataClient {
             last_key: HashValue::random(),
             raw_values: state_keys_and_values,
             proof: SparseMerkleRangeProof::new(vec![]),
+            left_siblings: vec![],
             root_hash: HashValue::zero(),
         };
         Ok(create_data_client_response(state_value_chunk_with_proof))
@@ -246,6 +246,12 @@ pub(crate) struct StateValueSyncer {
     // processed -- i.e., sent to the storage synchronizer).
     next_state_index_to_process: u64,
 
+    // The first and last indices of the state value chunks that were received
+    // ahead of the next state index to process (i.e., out of order). These chunks
+    // have already been verified and sent to the storage synchronizer (which
+    // commits them once all preceding chunks have been processed).
+    pending_state_value_chunks: BTreeMap<u64, u64>,
+
     // The transaction output (inc. info and proof) for the version we're syncing
     transaction_output_to_sync: Option<TransactionOutputListWithProof>,
 }
@@ -256,6 +262,7 @@ impl StateValueSyncer {
             initialized_state_snapshot_receiver: false,
             ledger_info_to_sync: None,
             next_state_index_to_process: 0,
+            pending_state_value_chunks: BTreeMap::new(),
             transaction_output_to_sync: None,
         }
     }
@@ -277,6 +284,37 @@ impl StateValueSyncer {
     pub fn update_next_state_index_to_process(&mut self, next_state_index_to_process: u64) {
         self.next_state_index_to_process = next_state_index_to_process;
     }
+
+    /// Marks the state value chunk with the given indices as pending (i.e.,
+    /// it was processed out of order)
+    pub fn add_pending_state_value_chunk(&mut self, first_index: u64, last_index: u64) {
+        self.pending_state_value_chunks
+            .insert(first_index, last_index);
+    }
+
+    /// Removes the pending state value chunk that starts at the next state
+    /// index to process and returns its last index (if one exists)
+    pub fn take_next_pending_state_value_chunk(&mut self) -> Option<u64> {
+        self.pending_state_value_chunks
+            .remove(&self.next_state_index_to_process)
+    }
+
+    /// Clears all pending state value chunks (e.g., when the stream is reset)
+    pub fn clear_pending_state_value_chunks(&mut self) {
+        self.pending_state_value_chunks.clear();
+    }
+
+    /// Returns the next state index to process for testing purposes
+    #[cfg(test)]
+    pub(crate) fn get_next_state_index_to_process(&self) -> u64 {
+        self.next_state_index_to_process
+    }
+
+    /// Returns the number of pending state value chunks for testing purposes
+    #[cfg(test)]
+    pub(crate) fn get_num_pending_state_value_chunks(&self) -> usize {
+        self.pending_state_value_chunks.len()
+    }
 }
 
 /// A simple component that manages the bootstrapping of the node
@@ -353,9 +391,14 @@ impl<
         }
     }
 
-    /// Returns the bootstrapping mode of the node
+    /// Returns the bootstrapping mode of the node. Nodes that restore from
+    /// backups have already restored the backed up data to storage (before
+    /// state sync started), so they sync the rest from their peers.
     fn get_bootstrapping_mode(&self) -> BootstrappingMode {
-        self.driver_configuration.config.bootstrapping_mode
+        match self.driver_configuration.config.bootstrapping_mode {
+            BootstrappingMode::RestoreFromBackup => BootstrappingMode::ExecuteOrApplyFromGenesis,
+            bootstrapping_mode => bootstrapping_mode,
+        }
     }
 
     /// Returns true iff the node has already completed bootstrapping
@@ -881,17 +924,24 @@ impl<
         notification_id: NotificationId,
         state_value_chunk_with_proof: &StateValueChunkWithProof,
     ) -> Result<(), Error> {
-        // Verify the payload start index is valid
-        let expected_start_index = self.state_value_syncer.next_state_index_to_process;
-        if expected_start_index != state_value_chunk_with_proof.first_index {
+        // Verify the payload start index is valid. Chunks may arrive out of
+        // order, but they must not overlap already processed or pending chunks.
+        let next_state_index_to_process = self.state_value_syncer.next_state_index_to_process;
+        let first_index = state_value_chunk_with_proof.first_index;
+        if first_index < next_state_index_to_process
+            || self
+                .state_value_syncer
+                .pending_state_value_chunks
+                .contains_key(&first_index)
+        {
             self.reset_active_stream(Some(NotificationAndFeedback::new(
                 notification_id,
                 NotificationFeedback::InvalidPayloadData,
             )))
             .await?;
             return Err(Error::VerificationError(format!(
-                "The start index of the state values was invalid! Expected: {:?}, received: {:?}",
-                expected_start_index, state_value_chunk_with_proof.first_index
+                "The start index of the state values was invalid! Next index to process: {:?}, received: {:?}",
+                next_state_index_to_process, first_index
             )));
         }
 
@@ -987,12 +1037,35 @@ impl<
             )));
         }
 
-        // Process the state values chunk and proof
-        let last_state_value_index = state_value_chunk_with_proof.last_index;
-        if let Err(error) = self
-            .storage_synchronizer
-            .save_state_values(notification_id, state_value_chunk_with_proof)
-        {
+        // Verify the chunk proof (chunks are verified independently, so
+        // out of order chunks can be verified and persisted on arrival).
+        if let Err(error) = state_value_chunk_with_proof.verify() {
+            self.reset_active_stream(Some(NotificationAndFeedback::new(
+                notification_id,
+                NotificationFeedback::PayloadProofFailed,
+            )))
+            .await?;
+            return Err(Error::VerificationError(format!(
+                "The states chunk with proof failed verification! Error: {:?}",
+                error,
+            )));
+        }
+
+        // Process the state values chunk and proof. If the chunk is ahead of
+        // the next state index to process, the storage synchronizer persists
+        // the state values now and commits the chunk once it's in order.
+        let first_state_value_index = state_value_chunk_with_proof.first_index;
+        let mut last_state_value_index = state_value_chunk_with_proof.last_index;
+        let chunk_in_order =
+            first_state_value_index == self.state_value_syncer.next_state_index_to_process;
+        let save_result = if chunk_in_order {
+            self.storage_synchronizer
+                .save_state_values(notification_id, state_value_chunk_with_proof)
+        } else {
+            self.storage_synchronizer
+                .save_out_of_order_state_values(notification_id, state_value_chunk_with_proof)
+        };
+        if let Err(error) = save_result {
             self.reset_active_stream(Some(NotificationAndFeedback::new(
                 notification_id,
                 NotificationFeedback::InvalidPayloadData,
@@ -1003,16 +1076,29 @@ impl<
                 error,
             )));
         }
+        if !chunk_in_order {
+            self.state_value_syncer
+                .add_pending_state_value_chunk(first_state_value_index, last_state_value_index);
+            return Ok(());
+        }
 
-        // Update the next state value index to process
-        self.state_value_syncer.next_state_index_to_process =
-            last_state_value_index.checked_add(1).ok_or_else(|| {
-                Error::IntegerOverflow(
-                    "The next state value index to process has overflown!".into(),
-                )
-            })?;
-
-        Ok(())
+        // Update the next state value index to process (including
+        // all pending chunks that are now in order).
+        loop {
+            self.state_value_syncer.next_state_index_to_process =
+                last_state_value_index.checked_add(1).ok_or_else(|| {
+                    Error::IntegerOverflow(
+                        "The next state value index to process has overflown!".into(),
+                    )
+                })?;
+            match self
+                .state_value_syncer
+                .take_next_pending_state_value_chunk()
+            {
+                Some(last_index) => last_state_value_index = last_index,
+                None => return Ok(()),
+            }
+        }
     }
 
     /// Process a single epoch ending payload
@@ -1502,6 +1588,7 @@ impl<
 
         self.active_data_stream = None;
         self.speculative_stream_state = None;
+        self.state_value_syncer.clear_pending_state_value_chunks();
         Ok(())
     }
 
@@ -13,8 +13,8 @@ use crate::{
     metrics::ExecutingComponent,
     notification_handlers::{
         CommitNotification, CommitNotificationListener, CommittedTransactions,
-        ConsensusNotificationHandler, ErrorNotification, ErrorNotificationListener,
-        MempoolNotificationHandler, StorageServiceNotificationHandler,
+        ConsensusNotificationHandler, ConsensusObserverHandler, ErrorNotification,
+        ErrorNotificationListener, MempoolNotificationHandler, StorageServiceNotificationHandler,
     },
     storage_synchronizer::StorageSynchronizerInterface,
     utils,
@@ -58,14 +58,23 @@ pub struct DriverConfiguration {
 
     // The trusted waypoint for the node
     pub waypoint: Waypoint,
+
+    // Whether the node executes blocks forwarded by a consensus publisher
+    pub consensus_observer_enabled: bool,
 }
 
 impl DriverConfiguration {
-    pub fn new(config: StateSyncDriverConfig, role: RoleType, waypoint: Waypoint) -> Self {
+    pub fn new(
+        config: StateSyncDriverConfig,
+        role: RoleType,
+        waypoint: Waypoint,
+        consensus_observer_enabled: bool,
+    ) -> Self {
         Self {
             config,
             role,
             waypoint,
+            consensus_observer_enabled,
         }
     }
 }
@@ -91,6 +100,9 @@ pub struct StateSyncDriver<
     // The handler for notifications from consensus
     consensus_notification_handler: ConsensusNotificationHandler,
 
+    // The handler that pauses the consensus observer before syncing
+    consensus_observer_handler: ConsensusObserverHandler,
+
     // The component that manages the continuous syncing of the node
     continuous_syncer: ContinuousSyncer<StorageSyncer, StreamingClient>,
 
@@ -146,6 +158,7 @@ impl<
         client_notification_listener: ClientNotificationListener,
         commit_notification_listener: CommitNotificationListener,
         consensus_notification_handler: ConsensusNotificationHandler,
+        consensus_observer_handler: ConsensusObserverHandler,
         driver_configuration: DriverConfiguration,
         error_notification_listener: ErrorNotificationListener,
         event_subscription_service: Arc<Mutex<EventSubscriptionService>>,
@@ -183,6 +196,7 @@ impl<
             client_notification_listener,
             commit_notification_listener,
             consensus_notification_handler,
+            consensus_observer_handler,
             continuous_syncer,
             aptos_data_client,
             driver_configuration,
@@ -232,9 +246,10 @@ impl<
 
     /// Handles a notification sent by consensus
     async fn handle_consensus_notification(&mut self, notification: ConsensusNotification) {
-        // Verify the notification: full nodes shouldn't receive notifications
-        // and consensus should only send notifications after bootstrapping!
-        let result = if self.driver_configuration.role == RoleType::FullNode {
+        // Verify the notification: full nodes shouldn't receive notifications (unless
+        // they run the consensus observer) and consensus should only send notifications
+        // after bootstrapping!
+        let result = if !self.is_consensus_or_observer_enabled() {
             Err(Error::FullNodeConsensusNotification(format!(
                 "Received consensus notification: {:?}",
                 notification
@@ -303,6 +318,7 @@ impl<
             ))
         );
         self.update_consensus_commit_metrics(&consensus_commit_notification);
+        self.consensus_observer_handler.handle_observer_commit();
 
         // TODO(joshlind): can we get consensus to forward the events?
 
@@ -386,7 +402,13 @@ impl<
             utils::fetch_latest_synced_ledger_info(self.storage.clone())?;
         self.consensus_notification_handler
             .initialize_sync_request(sync_notification, latest_synced_ledger_info)
-            .await
+            .await?;
+
+        // If we're already at the target, consensus (or the observer) is now in control
+        if !self.active_sync_request() {
+            self.consensus_observer_handler.handle_observer_resumed();
+        }
+        Ok(())
     }
 
     /// Handles a client notification sent by the driver client
@@ -525,6 +547,7 @@ impl<
         if !self.active_sync_request() {
             self.continuous_syncer.reset_active_stream(None).await?;
             self.storage_synchronizer.finish_chunk_executor(); // Consensus is now in control
+            self.consensus_observer_handler.handle_observer_resumed();
         }
         Ok(())
     }
@@ -539,9 +562,25 @@ impl<
         self.driver_configuration.role == RoleType::Validator
     }
 
-    /// Returns true iff consensus is currently executing
+    /// Returns true iff this node is a fullnode running the consensus observer
+    fn is_consensus_observer(&self) -> bool {
+        self.driver_configuration.role == RoleType::FullNode
+            && self.driver_configuration.consensus_observer_enabled
+    }
+
+    /// Returns true iff this node runs consensus, or observes it as a fullnode
+    fn is_consensus_or_observer_enabled(&self) -> bool {
+        self.is_validator() || self.is_consensus_observer()
+    }
+
+    /// Returns true iff consensus (or the consensus observer) is currently executing.
+    /// Note: the observer must pause execution before state sync can take over, to
+    /// ensure that the observer and state sync never write to storage concurrently.
     fn check_if_consensus_executing(&self) -> bool {
-        self.is_validator() && self.bootstrapper.is_bootstrapped() && !self.active_sync_request()
+        self.is_consensus_or_observer_enabled()
+            && self.bootstrapper.is_bootstrapped()
+            && !self.active_sync_request()
+            && (self.is_validator() || !self.consensus_observer_handler.is_observer_paused())
     }
 
     /// Checks if the connection deadline has passed. If so, validators with
@@ -596,6 +635,14 @@ impl<
                 .message("Error found when checking the sync request progress!"));
         }
 
+        // Pause the consensus observer if it has stopped committing
+        if self.is_consensus_observer()
+            && self.bootstrapper.is_bootstrapped()
+            && !self.active_sync_request()
+        {
+            self.consensus_observer_handler.check_observer_progress();
+        }
+
         // If consensus is executing, there's nothing to do
         if self.check_if_consensus_executing() {
             trace!(LogSchema::new(LogEntry::Driver)
@@ -8,12 +8,13 @@ use crate::{
     metadata_storage::MetadataStorageInterface,
     notification_handlers::{
         CommitNotification, CommitNotificationListener, ConsensusNotificationHandler,
-        ErrorNotificationListener, MempoolNotificationHandler, StorageServiceNotificationHandler,
+        ConsensusObserverHandler, ErrorNotificationListener, MempoolNotificationHandler,
+        StorageServiceNotificationHandler,
     },
     storage_synchronizer::StorageSynchronizer,
 };
 use aptos_config::config::NodeConfig;
-use aptos_consensus_notifications::ConsensusNotificationListener;
+use aptos_consensus_notifications::{ConsensusNotificationListener, ObserverPauseNotifier};
 use aptos_data_client::client::AptosDataClient;
 use aptos_data_streaming_service::streaming_client::StreamingServiceClient;
 use aptos_event_notifications::{EventNotificationSender, EventSubscriptionService};
@@ -54,6 +55,7 @@ impl DriverFactory {
         storage_service_notification_sender: StorageServiceNotifier,
         metadata_storage: MetadataStorage,
         consensus_listener: ConsensusNotificationListener,
+        observer_pause_notifier: ObserverPauseNotifier,
         event_subscription_service: EventSubscriptionService,
         aptos_data_client: AptosDataClient,
         streaming_service_client: StreamingServiceClient,
@@ -69,6 +71,7 @@ impl DriverFactory {
             storage_service_notification_sender,
             metadata_storage,
             consensus_listener,
+            observer_pause_notifier,
             event_subscription_service,
             aptos_data_client,
             streaming_service_client,
@@ -95,6 +98,7 @@ impl DriverFactory {
         storage_service_notification_sender: StorageServiceNotifier,
         metadata_storage: MetadataStorage,
         consensus_listener: ConsensusNotificationListener,
+        observer_pause_notifier: ObserverPauseNotifier,
         mut event_subscription_service: EventSubscriptionService,
         aptos_data_client: AptosDataClient,
         streaming_service_client: StreamingServiceClient,
@@ -122,6 +126,14 @@ impl DriverFactory {
         let (commit_notification_sender, commit_notification_listener) =
             CommitNotificationListener::new();
         let consensus_notification_handler = ConsensusNotificationHandler::new(consensus_listener);
+        let consensus_observer_handler = ConsensusObserverHandler::new(
+            node_config
+                .state_sync
+                .state_sync_driver
+                .max_consensus_observer_silence_ms,
+            observer_pause_notifier,
+            time_service.clone(),
+        );
         let (error_notification_sender, error_notification_listener) =
             ErrorNotificationListener::new();
         let mempool_notification_handler = MempoolNotificationHandler::new(
@@ -162,6 +174,7 @@ impl DriverFactory {
             node_config.state_sync.state_sync_driver,
             node_config.base.role,
             waypoint,
+            node_config.consensus_observer.observer_enabled,
         );
 
         // Create the state sync driver
@@ -169,6 +182,7 @@ impl DriverFactory {
             client_notification_listener,
             commit_notification_listener,
             consensus_notification_handler,
+            consensus_observer_handler,
             driver_configuration,
             error_notification_listener,
             event_subscription_service,
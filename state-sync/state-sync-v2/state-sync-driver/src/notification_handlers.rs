@@ -8,7 +8,7 @@ use crate::{
 };
 use aptos_consensus_notifications::{
     ConsensusCommitNotification, ConsensusNotification, ConsensusNotificationListener,
-    ConsensusSyncNotification,
+    ConsensusSyncNotification, ObserverPauseNotifier,
 };
 use aptos_data_streaming_service::data_notification::NotificationId;
 use aptos_event_notifications::{EventNotificationSender, EventSubscriptionService};
@@ -16,17 +16,23 @@ use aptos_infallible::Mutex;
 use aptos_logger::prelude::*;
 use aptos_mempool_notifications::MempoolNotificationSender;
 use aptos_storage_service_notifications::StorageServiceNotificationSender;
+use aptos_time_service::{TimeService, TimeServiceTrait};
 use aptos_types::{
     contract_event::ContractEvent,
     ledger_info::LedgerInfoWithSignatures,
     transaction::{Transaction, Version},
 };
-use futures::{channel::mpsc, stream::FusedStream, Stream};
+use futures::{
+    channel::{mpsc, oneshot},
+    stream::FusedStream,
+    Stream,
+};
 use serde::Serialize;
 use std::{
     pin::Pin,
     sync::Arc,
     task::{Context, Poll},
+    time::{Duration, Instant},
 };
 
 /// A notification for new data that has been committed to storage
@@ -393,6 +399,116 @@ impl FusedStream for ErrorNotificationListener {
     }
 }
 
+/// A simple handler that pauses the consensus observer before state sync takes over
+/// (e.g., when the observer stops committing). This ensures that the observer's
+/// execution pipeline and state sync never write to storage at the same time.
+pub struct ConsensusObserverHandler {
+    // The maximum time (without observer commits) before the observer is paused
+    max_observer_silence: Duration,
+
+    // The notifier used to pause the consensus observer
+    observer_pause_notifier: ObserverPauseNotifier,
+
+    // Whether the observer has paused execution. The observer starts paused,
+    // and only resumes once it has synced to a commit decision.
+    observer_paused: bool,
+
+    // The pause acknowledgement we're still waiting on from the observer (if any)
+    pending_pause_ack: Option<oneshot::Receiver<()>>,
+
+    // The time of the last observer commit (or the time the observer resumed)
+    last_observer_activity: Option<Instant>,
+
+    // The time service
+    time_service: TimeService,
+}
+
+impl ConsensusObserverHandler {
+    pub fn new(
+        max_observer_silence_ms: u64,
+        observer_pause_notifier: ObserverPauseNotifier,
+        time_service: TimeService,
+    ) -> Self {
+        Self {
+            max_observer_silence: Duration::from_millis(max_observer_silence_ms),
+            observer_pause_notifier,
+            observer_paused: true,
+            pending_pause_ack: None,
+            last_observer_activity: None,
+            time_service,
+        }
+    }
+
+    /// Returns true iff the observer has paused execution (i.e., state sync can write to storage)
+    pub fn is_observer_paused(&self) -> bool {
+        self.observer_paused
+    }
+
+    /// Handles a commit made by the observer
+    pub fn handle_observer_commit(&mut self) {
+        self.last_observer_activity = Some(self.time_service.now());
+    }
+
+    /// Handles the observer resuming execution (i.e., after it has synced to a commit)
+    pub fn handle_observer_resumed(&mut self) {
+        // Any outstanding pause request was handled before the observer resumed
+        self.pending_pause_ack = None;
+        self.observer_paused = false;
+        self.last_observer_activity = Some(self.time_service.now());
+    }
+
+    /// Checks if the observer has acknowledged a pending pause request, and
+    /// sends a new pause request if the observer has stopped committing.
+    pub fn check_observer_progress(&mut self) {
+        // Check if the observer has acknowledged the pending pause request
+        if let Some(pending_pause_ack) = self.pending_pause_ack.as_mut() {
+            match pending_pause_ack.try_recv() {
+                Ok(None) => return, // The observer hasn't paused yet
+                Ok(Some(())) => info!(LogSchema::new(LogEntry::NotificationHandler)
+                    .message("The consensus observer has paused execution!")),
+                Err(_) => warn!(LogSchema::new(LogEntry::NotificationHandler).message(
+                    "The consensus observer dropped the pause request! It is likely not running."
+                )),
+            }
+            self.pending_pause_ack = None;
+            self.observer_paused = true;
+            return;
+        }
+
+        // If the observer is executing but idle, pause it so that state sync can take over
+        if !self.observer_paused && !self.is_observer_committing() {
+            info!(LogSchema::new(LogEntry::NotificationHandler)
+                .message("The consensus observer stopped committing! Pausing it before syncing."));
+            match self.observer_pause_notifier.pause_observer() {
+                Ok(pending_pause_ack) => self.pending_pause_ack = Some(pending_pause_ack),
+                Err(error) => {
+                    // The observer is no longer running, so there's nothing to pause
+                    warn!(
+                        LogSchema::new(LogEntry::NotificationHandler).message(&format!(
+                            "Failed to pause the consensus observer! Error: {:?}",
+                            error
+                        ))
+                    );
+                    self.observer_paused = true;
+                },
+            }
+        }
+    }
+
+    /// Returns true iff the observer committed (or resumed) recently. Otherwise, the
+    /// observer may not be receiving any blocks (e.g., it has no publisher to
+    /// subscribe to), so the node must continue syncing.
+    fn is_observer_committing(&self) -> bool {
+        self.last_observer_activity
+            .map_or(false, |last_observer_activity| {
+                self.time_service
+                    .now()
+                    .duration_since(last_observer_activity)
+                    < self.max_observer_silence
+            })
+    }
+}
+
 /// A simple handler for sending notifications to mempool
 #[derive(Clone)]
 pub struct MempoolNotificationHandler<M> {
@@ -39,6 +39,7 @@ use futures::{
     SinkExt, StreamExt,
 };
 use std::{
+    collections::BTreeMap,
     future::Future,
     sync::{
         atomic::{AtomicU64, Ordering},
@@ -93,7 +94,8 @@ pub trait StorageSynchronizerInterface {
     /// to be executed/applied or committed.
     fn pending_storage_data(&self) -> bool;
 
-    /// Saves the given state values to storage.
+    /// Saves the given state values to storage. The chunk must follow the
+    /// previously saved chunk (i.e., state values are saved in order).
     ///
     /// Note: this requires that `initialize_state_synchronizer` has been
     /// called.
@@ -103,6 +105,19 @@ pub trait StorageSynchronizerInterface {
         state_value_chunk_with_proof: StateValueChunkWithProof,
     ) -> Result<(), Error>;
 
+    /// Saves the given state values to storage before all preceding chunks
+    /// have been saved (i.e., out of order). The state values are written
+    /// immediately, and the chunk is committed once all preceding chunks
+    /// have been saved (using `save_state_values`).
+    ///
+    /// Note: this assumes that the chunk has already been verified, and
+    /// requires that `initialize_state_synchronizer` has been called.
+    fn save_out_of_order_state_values(
+        &mut self,
+        notification_id: NotificationId,
+        state_value_chunk_with_proof: StateValueChunkWithProof,
+    ) -> Result<(), Error>;
+
     /// Resets the chunk executor. This is required to support continuous
     /// interaction between consensus and state sync.
     fn reset_chunk_executor(&self) -> Result<(), Error>;
@@ -257,6 +272,25 @@ impl<
             Ok(())
         }
     }
+
+    /// Notifies the state snapshot receiver of new state value chunks
+    fn notify_state_snapshot_receiver(
+        &mut self,
+        storage_data_chunk: StorageDataChunk,
+    ) -> Result<(), Error> {
+        let state_snapshot_notifier = self.state_snapshot_notifier.as_mut().ok_or_else(|| {
+            Error::UnexpectedError("The state snapshot receiver has not been initialized!".into())
+        })?;
+        if let Err(error) = state_snapshot_notifier.try_send(storage_data_chunk) {
+            Err(Error::UnexpectedError(format!(
+                "Failed to send storage data chunk to state snapshot listener: {:?}",
+                error
+            )))
+        } else {
+            increment_pending_data_chunks(self.pending_data_chunks.clone());
+            Ok(())
+        }
+    }
 }
 
 #[async_trait]
@@ -336,20 +370,19 @@ impl<
         notification_id: NotificationId,
         state_value_chunk_with_proof: StateValueChunkWithProof,
     ) -> Result<(), Error> {
-        let state_snapshot_notifier = self.state_snapshot_notifier.as_mut().ok_or_else(|| {
-            Error::UnexpectedError("The state snapshot receiver has not been initialized!".into())
-        })?;
         let storage_data_chunk =
             StorageDataChunk::States(notification_id, state_value_chunk_with_proof);
-        if let Err(error) = state_snapshot_notifier.try_send(storage_data_chunk) {
-            Err(Error::UnexpectedError(format!(
-                "Failed to send storage data chunk to state snapshot listener: {:?}",
-                error
-            )))
-        } else {
-            increment_pending_data_chunks(self.pending_data_chunks.clone());
-            Ok(())
-        }
+        self.notify_state_snapshot_receiver(storage_data_chunk)
+    }
+
+    fn save_out_of_order_state_values(
+        &mut self,
+        notification_id: NotificationId,
+        state_value_chunk_with_proof: StateValueChunkWithProof,
+    ) -> Result<(), Error> {
+        let storage_data_chunk =
+            StorageDataChunk::OutOfOrderStates(notification_id, state_value_chunk_with_proof);
+        self.notify_state_snapshot_receiver(storage_data_chunk)
     }
 
     fn reset_chunk_executor(&self) -> Result<(), Error> {
@@ -372,6 +405,7 @@ impl<
 #[derive(Debug)]
 enum StorageDataChunk {
     States(NotificationId, StateValueChunkWithProof),
+    OutOfOrderStates(NotificationId, StateValueChunkWithProof),
     Transactions(
         NotificationId,
         TransactionListWithProof,
@@ -649,54 +683,90 @@ fn spawn_state_snapshot_receiver<
 
         // Handle state value chunks
         let target_ledger_info = &target_ledger_info;
+        let mut out_of_order_chunks = BTreeMap::new();
         while let Some(storage_data_chunk) = state_snapshot_listener.next().await {
             // Process the chunk
             match storage_data_chunk {
                 StorageDataChunk::States(notification_id, states_with_proof) => {
-                    let all_states_synced = states_with_proof.is_last_chunk();
-                    let last_committed_state_index = states_with_proof.last_index;
-
-                    // Attempt to commit the chunk
-                    let num_state_values = states_with_proof.raw_values.len();
-                    let commit_result = state_snapshot_receiver.add_chunk(
-                        states_with_proof.raw_values,
-                        states_with_proof.proof.clone(),
-                    );
-                    match commit_result {
-                        Ok(()) => {
-                            // Update the logs and metrics
-                            info!(
-                                LogSchema::new(LogEntry::StorageSynchronizer).message(&format!(
-                                    "Committed a new state value chunk! Chunk size: {:?}, last persisted index: {:?}",
-                                    num_state_values,
-                                    last_committed_state_index
-                                ))
-                            );
-
-                            let operation_label =
-                                metrics::StorageSynchronizerOperations::SyncedStates.get_label();
-                            metrics::set_gauge(
-                                &metrics::STORAGE_SYNCHRONIZER_OPERATIONS,
-                                operation_label,
-                                last_committed_state_index,
-                            );
-                            metrics::observe_value(
-                                &metrics::STORAGE_SYNCHRONIZER_CHUNK_SIZES,
-                                operation_label,
-                                num_state_values as u64,
-                            );
-
-                            if !all_states_synced {
-                                // Update the metadata storage with the last committed state index
-                                if let Err(error) = metadata_storage
-                                    .clone()
-                                    .update_last_persisted_state_value_index(
-                                        target_ledger_info,
+                    // Commit the chunk, followed by any out of order chunks that are now in order
+                    let mut next_chunk = Some((notification_id, states_with_proof));
+                    while let Some((notification_id, states_with_proof)) = next_chunk.take() {
+                        let all_states_synced = states_with_proof.is_last_chunk();
+                        let last_committed_state_index = states_with_proof.last_index;
+
+                        // Attempt to commit the chunk
+                        let num_state_values = states_with_proof.raw_values.len();
+                        let commit_result = state_snapshot_receiver.add_chunk(
+                            states_with_proof.raw_values,
+                            states_with_proof.proof.clone(),
+                        );
+                        match commit_result {
+                            Ok(()) => {
+                                // Update the logs and metrics
+                                info!(
+                                    LogSchema::new(LogEntry::StorageSynchronizer).message(&format!(
+                                        "Committed a new state value chunk! Chunk size: {:?}, last persisted index: {:?}",
+                                        num_state_values,
+                                        last_committed_state_index
+                                    ))
+                                );
+
+                                let operation_label =
+                                    metrics::StorageSynchronizerOperations::SyncedStates
+                                        .get_label();
+                                metrics::set_gauge(
+                                    &metrics::STORAGE_SYNCHRONIZER_OPERATIONS,
+                                    operation_label,
+                                    last_committed_state_index,
+                                );
+                                metrics::observe_value(
+                                    &metrics::STORAGE_SYNCHRONIZER_CHUNK_SIZES,
+                                    operation_label,
+                                    num_state_values as u64,
+                                );
+
+                                if !all_states_synced {
+                                    // Update the metadata storage with the last committed state index
+                                    if let Err(error) = metadata_storage
+                                        .clone()
+                                        .update_last_persisted_state_value_index(
+                                            target_ledger_info,
+                                            last_committed_state_index,
+                                            all_states_synced,
+                                        )
+                                    {
+                                        let error = format!("Failed to update the last persisted state index at version: {:?}! Error: {:?}", version, error);
+                                        send_storage_synchronizer_error(
+                                            error_notification_sender.clone(),
+                                            notification_id,
+                                            error,
+                                        )
+                                        .await;
+                                    }
+
+                                    // Commit the next out of order chunk (if it's now in order)
+                                    next_chunk = take_next_out_of_order_chunk(
+                                        &mut out_of_order_chunks,
                                         last_committed_state_index,
-                                        all_states_synced,
-                                    )
+                                    );
+                                    continue;
+                                }
+
+                                // Finalize storage and send a commit notification
+                                if let Err(error) = finalize_storage_and_send_commit(
+                                    chunk_executor,
+                                    &mut commit_notification_sender,
+                                    metadata_storage,
+                                    state_snapshot_receiver,
+                                    storage,
+                                    &epoch_change_proofs,
+                                    target_output_with_proof,
+                                    version,
+                                    target_ledger_info,
+                                    last_committed_state_index,
+                                )
+                                .await
                                 {
-                                    let error = format!("Failed to update the last persisted state index at version: {:?}! Error: {:?}", version, error);
                                     send_storage_synchronizer_error(
                                         error_notification_sender.clone(),
                                         notification_id,
@@ -705,37 +775,39 @@ fn spawn_state_snapshot_receiver<
                                     .await;
                                 }
                                 decrement_pending_data_chunks(pending_transaction_chunks.clone());
-                                continue; // Wait for the next chunk
-                            }
-
-                            // Finalize storage and send a commit notification
-                            if let Err(error) = finalize_storage_and_send_commit(
-                                chunk_executor,
-                                &mut commit_notification_sender,
-                                metadata_storage,
-                                state_snapshot_receiver,
-                                storage,
-                                &epoch_change_proofs,
-                                target_output_with_proof,
-                                version,
-                                target_ledger_info,
-                                last_committed_state_index,
-                            )
-                            .await
-                            {
+                                return; // There's nothing left to do!
+                            },
+                            Err(error) => {
+                                let error = format!(
+                                    "Failed to commit state value chunk! Error: {:?}",
+                                    error
+                                );
                                 send_storage_synchronizer_error(
                                     error_notification_sender.clone(),
                                     notification_id,
                                     error,
                                 )
                                 .await;
-                            }
-                            decrement_pending_data_chunks(pending_transaction_chunks.clone());
-                            return; // There's nothing left to do!
+                            },
+                        }
+                    }
+                },
+                StorageDataChunk::OutOfOrderStates(notification_id, states_with_proof) => {
+                    // Write the state values now, and commit the chunk once it's in order
+                    match state_snapshot_receiver
+                        .add_out_of_order_chunk(&states_with_proof.raw_values)
+                    {
+                        Ok(()) => {
+                            out_of_order_chunks.insert(
+                                states_with_proof.first_index,
+                                (notification_id, states_with_proof),
+                            );
                         },
                         Err(error) => {
-                            let error =
-                                format!("Failed to commit state value chunk! Error: {:?}", error);
+                            let error = format!(
+                                "Failed to write out of order state value chunk! Error: {:?}",
+                                error
+                            );
                             send_storage_synchronizer_error(
                                 error_notification_sender.clone(),
                                 notification_id,
@@ -762,6 +834,26 @@ fn spawn_state_snapshot_receiver<
     spawn(runtime, receiver)
 }
 
+/// Removes and returns the out of order state value chunk that follows the
+/// last committed state index (if one exists). Chunks that were committed
+/// in the meantime (e.g., after a stream reset) are dropped.
+fn take_next_out_of_order_chunk(
+    out_of_order_chunks: &mut BTreeMap<u64, (NotificationId, StateValueChunkWithProof)>,
+    last_committed_state_index: u64,
+) -> Option<(NotificationId, StateValueChunkWithProof)> {
+    while let Some(entry) = out_of_order_chunks.first_entry() {
+        let (_, states_with_proof) = entry.get();
+        if states_with_proof.last_index <= last_committed_state_index {
+            entry.remove(); // The chunk has already been committed
+        } else if states_with_proof.first_index <= last_committed_state_index.saturating_add(1) {
+            return Some(entry.remove());
+        } else {
+            return None; // The chunk is still out of order
+        }
+    }
+    None
+}
+
 /// Spawns a dedicated task that applies the given output chunk. We use
 /// `spawn_blocking` so that the heavy synchronous function doesn't
 /// block the async thread.
@@ -13,13 +13,15 @@ use crate::{
         utils::{
             create_data_stream_listener, create_empty_epoch_state, create_epoch_ending_ledger_info,
             create_full_node_driver_configuration, create_global_summary,
-            create_output_list_with_proof, create_random_epoch_ending_ledger_info,
+            create_output_list_with_proof, create_output_list_with_state_root_hash,
+            create_random_epoch_ending_ledger_info, create_state_value_chunks,
             create_transaction_list_with_proof,
         },
     },
     utils::OutputFallbackHandler,
 };
 use aptos_config::config::BootstrappingMode;
+use aptos_crypto::HashValue;
 use aptos_data_client::global_summary::GlobalDataSummary;
 use aptos_data_streaming_service::{
     data_notification::{DataNotification, DataPayload, NotificationId},
@@ -27,6 +29,7 @@ use aptos_data_streaming_service::{
 };
 use aptos_time_service::TimeService;
 use aptos_types::{
+    state_store::state_value::StateValueChunkWithProof,
     transaction::{TransactionOutputListWithProof, Version},
     waypoint::Waypoint,
 };
@@ -1014,6 +1017,268 @@ async fn test_snapshot_sync_lag_panic() {
         .unwrap();
 }
 
+#[tokio::test]
+async fn test_snapshot_sync_out_of_order_chunks() {
+    // Create test data
+    let synced_version = GENESIS_TRANSACTION_VERSION; // Genesis is the highest synced
+    let highest_version = 1000;
+    let highest_ledger_info = create_random_epoch_ending_ledger_info(highest_version, 1);
+    let (root_hash, state_value_chunks) = create_state_value_chunks(5, 100);
+    let output_list_with_proof = create_output_list_with_state_root_hash(root_hash);
+
+    // Create a driver configuration with a genesis waypoint and state syncing
+    // (we process a single notification each time progress is driven).
+    let mut driver_configuration = create_full_node_driver_configuration();
+    driver_configuration.config.bootstrapping_mode = BootstrappingMode::DownloadLatestStates;
+    driver_configuration
+        .config
+        .max_consecutive_stream_notifications = 1;
+
+    // Create the mock streaming client
+    let mut mock_streaming_client = create_mock_streaming_client();
+    let (mut notification_sender_1, data_stream_listener_1) = create_data_stream_listener();
+    let data_stream_id_1 = data_stream_listener_1.data_stream_id;
+    mock_streaming_client
+        .expect_get_all_state_values()
+        .times(1)
+        .with(eq(highest_version), eq(Some(0)))
+        .return_once(move |_, _| Ok(data_stream_listener_1));
+    let invalid_notification_id = 5;
+    mock_streaming_client
+        .expect_terminate_stream_with_feedback()
+        .times(1)
+        .with(
+            eq(data_stream_id_1),
+            eq(Some(NotificationAndFeedback::new(
+                invalid_notification_id,
+                NotificationFeedback::InvalidPayloadData,
+            ))),
+        )
+        .return_const(Ok(()));
+
+    // Create the mock storage synchronizer and expect the out of order chunks
+    // to be saved on arrival (and the in order chunk to be saved in order).
+    let mut mock_storage_synchronizer = create_ready_storage_synchronizer(true);
+    mock_storage_synchronizer
+        .expect_initialize_state_synchronizer()
+        .times(1)
+        .returning(|_, _, _| Ok(tokio::spawn(async {})));
+    let mut expectation_sequence = Sequence::new();
+    for (notification_id, first_index, in_order) in [
+        (1, 200, false),
+        (2, 100, false),
+        (3, 0, true),
+        (4, 400, false),
+    ] {
+        let expected_chunk =
+            move |id: &NotificationId, state_value_chunk_with_proof: &StateValueChunkWithProof| {
+                *id == notification_id && state_value_chunk_with_proof.first_index == first_index
+            };
+        if in_order {
+            mock_storage_synchronizer
+                .expect_save_state_values()
+                .times(1)
+                .withf(expected_chunk)
+                .return_const(Ok(()))
+                .in_sequence(&mut expectation_sequence);
+        } else {
+            mock_storage_synchronizer
+                .expect_save_out_of_order_state_values()
+                .times(1)
+                .withf(expected_chunk)
+                .return_const(Ok(()))
+                .in_sequence(&mut expectation_sequence);
+        }
+    }
+
+    // Create the mock metadata storage
+    let mut metadata_storage = MockMetadataStorage::new();
+    metadata_storage
+        .expect_previous_snapshot_sync_target()
+        .returning(move || Ok(None));
+
+    // Create the bootstrapper
+    let mut bootstrapper = create_bootstrapper_with_storage_synchronizer(
+        driver_configuration,
+        mock_streaming_client,
+        metadata_storage,
+        mock_storage_synchronizer,
+        synced_version,
+    );
+
+    // Insert an epoch ending ledger info into the verified states of the bootstrapper
+    manipulate_verified_epoch_states(&mut bootstrapper, true, true, Some(highest_version));
+
+    // Manually insert a transaction output to sync
+    bootstrapper
+        .get_state_value_syncer()
+        .set_transaction_output_to_sync(output_list_with_proof);
+
+    // Create a global data summary
+    let mut global_data_summary = create_global_summary(1);
+    global_data_summary.advertised_data.synced_ledger_infos = vec![highest_ledger_info.clone()];
+
+    // Drive progress to start the state value stream
+    drive_progress(&mut bootstrapper, &global_data_summary, false)
+        .await
+        .unwrap();
+
+    // Send the next two chunks out of order and verify they are pending
+    for (notification_id, chunk_index) in [(1, 2), (2, 1)] {
+        let data_notification = DataNotification {
+            notification_id,
+            data_payload: DataPayload::StateValuesWithProof(
+                state_value_chunks[chunk_index].clone(),
+            ),
+        };
+        notification_sender_1.send(data_notification).await.unwrap();
+        drive_progress(&mut bootstrapper, &global_data_summary, false)
+            .await
+            .unwrap();
+    }
+    let state_value_syncer = bootstrapper.get_state_value_syncer();
+    assert_eq!(state_value_syncer.get_next_state_index_to_process(), 0);
+    assert_eq!(state_value_syncer.get_num_pending_state_value_chunks(), 2);
+
+    // Send the first chunk and verify all chunks are processed in order
+    let data_notification = DataNotification {
+        notification_id: 3,
+        data_payload: DataPayload::StateValuesWithProof(state_value_chunks[0].clone()),
+    };
+    notification_sender_1.send(data_notification).await.unwrap();
+    drive_progress(&mut bootstrapper, &global_data_summary, false)
+        .await
+        .unwrap();
+    let state_value_syncer = bootstrapper.get_state_value_syncer();
+    assert_eq!(state_value_syncer.get_next_state_index_to_process(), 300);
+    assert_eq!(state_value_syncer.get_num_pending_state_value_chunks(), 0);
+
+    // Send a chunk ahead of the next index and verify it is pending
+    let data_notification = DataNotification {
+        notification_id: 4,
+        data_payload: DataPayload::StateValuesWithProof(state_value_chunks[4].clone()),
+    };
+    notification_sender_1.send(data_notification).await.unwrap();
+    drive_progress(&mut bootstrapper, &global_data_summary, false)
+        .await
+        .unwrap();
+    assert_eq!(
+        bootstrapper
+            .get_state_value_syncer()
+            .get_num_pending_state_value_chunks(),
+        1
+    );
+
+    // Send a chunk that overlaps the pending chunk
+    let data_notification = DataNotification {
+        notification_id: invalid_notification_id,
+        data_payload: DataPayload::StateValuesWithProof(state_value_chunks[4].clone()),
+    };
+    notification_sender_1.send(data_notification).await.unwrap();
+
+    // Drive progress and verify the stream is reset and the pending chunks are cleared
+    let error = drive_progress(&mut bootstrapper, &global_data_summary, false)
+        .await
+        .unwrap_err();
+    assert_matches!(error, Error::VerificationError(_));
+    let state_value_syncer = bootstrapper.get_state_value_syncer();
+    assert_eq!(state_value_syncer.get_next_state_index_to_process(), 300);
+    assert_eq!(state_value_syncer.get_num_pending_state_value_chunks(), 0);
+}
+
+#[tokio::test]
+async fn test_snapshot_sync_invalid_chunk_proof() {
+    // Create test data
+    let synced_version = GENESIS_TRANSACTION_VERSION; // Genesis is the highest synced
+    let highest_version = 1000;
+    let highest_ledger_info = create_random_epoch_ending_ledger_info(highest_version, 1);
+    let (root_hash, state_value_chunks) = create_state_value_chunks(3, 100);
+    let output_list_with_proof = create_output_list_with_state_root_hash(root_hash);
+
+    // Create a driver configuration with a genesis waypoint and state syncing
+    let mut driver_configuration = create_full_node_driver_configuration();
+    driver_configuration.config.bootstrapping_mode = BootstrappingMode::DownloadLatestStates;
+
+    // Create the mock streaming client
+    let mut mock_streaming_client = create_mock_streaming_client();
+    let (mut notification_sender_1, data_stream_listener_1) = create_data_stream_listener();
+    let data_stream_id_1 = data_stream_listener_1.data_stream_id;
+    mock_streaming_client
+        .expect_get_all_state_values()
+        .times(1)
+        .with(eq(highest_version), eq(Some(0)))
+        .return_once(move |_, _| Ok(data_stream_listener_1));
+    let invalid_notification_id = 1;
+    mock_streaming_client
+        .expect_terminate_stream_with_feedback()
+        .times(1)
+        .with(
+            eq(data_stream_id_1),
+            eq(Some(NotificationAndFeedback::new(
+                invalid_notification_id,
+                NotificationFeedback::PayloadProofFailed,
+            ))),
+        )
+        .return_const(Ok(()));
+
+    // Create the mock storage synchronizer (no chunks should be saved)
+    let mut mock_storage_synchronizer = create_ready_storage_synchronizer(true);
+    mock_storage_synchronizer
+        .expect_initialize_state_synchronizer()
+        .times(1)
+        .returning(|_, _, _| Ok(tokio::spawn(async {})));
+
+    // Create the mock metadata storage
+    let mut metadata_storage = MockMetadataStorage::new();
+    metadata_storage
+        .expect_previous_snapshot_sync_target()
+        .returning(move || Ok(None));
+
+    // Create the bootstrapper
+    let mut bootstrapper = create_bootstrapper_with_storage_synchronizer(
+        driver_configuration,
+        mock_streaming_client,
+        metadata_storage,
+        mock_storage_synchronizer,
+        synced_version,
+    );
+
+    // Insert an epoch ending ledger info into the verified states of the bootstrapper
+    manipulate_verified_epoch_states(&mut bootstrapper, true, true, Some(highest_version));
+
+    // Manually insert a transaction output to sync
+    bootstrapper
+        .get_state_value_syncer()
+        .set_transaction_output_to_sync(output_list_with_proof);
+
+    // Create a global data summary
+    let mut global_data_summary = create_global_summary(1);
+    global_data_summary.advertised_data.synced_ledger_infos = vec![highest_ledger_info.clone()];
+
+    // Drive progress to start the state value stream
+    drive_progress(&mut bootstrapper, &global_data_summary, false)
+        .await
+        .unwrap();
+
+    // Send an out of order chunk with invalid left siblings
+    let mut state_value_chunk_with_proof = state_value_chunks[2].clone();
+    state_value_chunk_with_proof.left_siblings = vec![HashValue::random()];
+    let data_notification = DataNotification {
+        notification_id: invalid_notification_id,
+        data_payload: DataPayload::StateValuesWithProof(state_value_chunk_with_proof),
+    };
+    notification_sender_1.send(data_notification).await.unwrap();
+
+    // Drive progress and verify the chunk fails verification on arrival
+    let error = drive_progress(&mut bootstrapper, &global_data_summary, false)
+        .await
+        .unwrap_err();
+    assert_matches!(error, Error::VerificationError(_));
+    let state_value_syncer = bootstrapper.get_state_value_syncer();
+    assert_eq!(state_value_syncer.get_next_state_index_to_process(), 0);
+    assert_eq!(state_value_syncer.get_num_pending_state_value_chunks(), 0);
+}
+
 #[tokio::test]
 async fn test_waypoint_mismatch() {
     // Create a waypoint
@@ -1207,12 +1472,30 @@ fn create_bootstrapper_with_storage(
     latest_synced_version: Version,
     expect_reset_executor: bool,
 ) -> Bootstrapper<MockMetadataStorage, MockStorageSynchronizer, MockStreamingClient> {
-    // Initialize the logger for tests
-    aptos_logger::Logger::init_for_testing();
-
     // Create the mock storage synchronizer
     let mock_storage_synchronizer = create_ready_storage_synchronizer(expect_reset_executor);
 
+    create_bootstrapper_with_storage_synchronizer(
+        driver_configuration,
+        mock_streaming_client,
+        mock_metadata_storage,
+        mock_storage_synchronizer,
+        latest_synced_version,
+    )
+}
+
+/// Creates a bootstrapper for testing with a mock metadata storage
+/// and a mock storage synchronizer.
+fn create_bootstrapper_with_storage_synchronizer(
+    driver_configuration: DriverConfiguration,
+    mock_streaming_client: MockStreamingClient,
+    mock_metadata_storage: MockMetadataStorage,
+    mock_storage_synchronizer: MockStorageSynchronizer,
+    latest_synced_version: Version,
+) -> Bootstrapper<MockMetadataStorage, MockStorageSynchronizer, MockStreamingClient> {
+    // Initialize the logger for tests
+    aptos_logger::Logger::init_for_testing();
+
     // Create the mock db reader with only genesis loaded
     let mut mock_database_reader = create_mock_db_reader();
     mock_database_reader
@@ -358,6 +358,8 @@ async fn create_driver_for_tests(
     // Create consensus and mempool notifiers and listeners
     let (consensus_notifier, consensus_listener) =
         aptos_consensus_notifications::new_consensus_notifier_listener_pair(5000);
+    let (observer_pause_notifier, _) =
+        aptos_consensus_notifications::new_observer_pause_notifier_listener_pair();
     let (mempool_notifier, mempool_listener) =
         aptos_mempool_notifications::new_mempool_notifier_listener_pair();
 
@@ -403,6 +405,7 @@ async fn create_driver_for_tests(
             storage_service_notifier,
             metadata_storage,
             consensus_listener,
+            observer_pause_notifier,
             event_subscription_service,
             aptos_data_client,
             streaming_service_client,
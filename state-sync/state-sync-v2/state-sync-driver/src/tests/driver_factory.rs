@@ -9,7 +9,9 @@ use aptos_config::{
     },
     utils::get_genesis_txn,
 };
-use aptos_consensus_notifications::new_consensus_notifier_listener_pair;
+use aptos_consensus_notifications::{
+    new_consensus_notifier_listener_pair, new_observer_pause_notifier_listener_pair,
+};
 use aptos_data_client::client::AptosDataClient;
 use aptos_data_streaming_service::streaming_client::new_streaming_service_client_listener_pair;
 use aptos_db::AptosDB;
@@ -52,6 +54,7 @@ fn test_new_initialized_configs() {
     // Create mempool and consensus notifiers
     let (mempool_notifier, _) = new_mempool_notifier_listener_pair();
     let (_, consensus_listener) = new_consensus_notifier_listener_pair(0);
+    let (observer_pause_notifier, _) = new_observer_pause_notifier_listener_pair();
 
     // Create the event subscription service and a reconfig subscriber
     let mut event_subscription_service = EventSubscriptionService::new(
@@ -98,6 +101,7 @@ fn test_new_initialized_configs() {
         storage_service_notifier,
         metadata_storage,
         consensus_listener,
+        observer_pause_notifier,
         event_subscription_service,
         aptos_data_client,
         streaming_service_client,
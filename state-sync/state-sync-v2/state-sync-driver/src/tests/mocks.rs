@@ -347,6 +347,8 @@ mock! {
     impl StateSnapshotReceiver<StateKey, StateValue> for SnapshotReceiver {
         fn add_chunk(&mut self, chunk: Vec<(StateKey, StateValue)>, proof: SparseMerkleRangeProof) -> Result<()>;
 
+        fn add_out_of_order_chunk(&mut self, chunk: &[(StateKey, StateValue)]) -> Result<()>;
+
         fn finish(self) -> Result<()>;
 
         fn finish_box(self: Box<Self>) -> Result<()>;
@@ -462,6 +464,12 @@ mock! {
             state_value_chunk_with_proof: StateValueChunkWithProof,
         ) -> Result<(), crate::error::Error>;
 
+        fn save_out_of_order_state_values(
+            &mut self,
+            notification_id: NotificationId,
+            state_value_chunk_with_proof: StateValueChunkWithProof,
+        ) -> Result<(), crate::error::Error>;
+
         fn reset_chunk_executor(&self) -> Result<(), crate::error::Error>;
 
         fn finish_chunk_executor(&self);
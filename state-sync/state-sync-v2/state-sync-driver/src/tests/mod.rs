@@ -8,5 +8,6 @@ mod driver;
 mod driver_factory;
 mod metadata_storage;
 mod mocks;
+mod notification_handlers;
 mod storage_synchronizer;
 mod utils;
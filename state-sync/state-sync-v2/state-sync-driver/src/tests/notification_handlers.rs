@@ -0,0 +1,134 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::notification_handlers::ConsensusObserverHandler;
+use aptos_consensus_notifications::{
+    new_observer_pause_notifier_listener_pair, ObserverPauseListener,
+};
+use aptos_time_service::TimeService;
+use futures::{FutureExt, StreamExt};
+
+// Useful test constants
+const MAX_OBSERVER_SILENCE_MS: u64 = 1000;
+
+#[test]
+fn test_observer_idle_sync_and_resume() {
+    // Create a consensus observer handler
+    let time_service = TimeService::mock();
+    let (observer_pause_notifier, mut observer_pause_listener) =
+        new_observer_pause_notifier_listener_pair();
+    let mut observer_handler = ConsensusObserverHandler::new(
+        MAX_OBSERVER_SILENCE_MS,
+        observer_pause_notifier,
+        time_service.clone(),
+    );
+
+    // Verify the observer starts paused (i.e., state sync can run before the first commit)
+    observer_handler.check_observer_progress();
+    assert!(observer_handler.is_observer_paused());
+    verify_no_pause_notification(&mut observer_pause_listener);
+
+    // The observer syncs to a commit and resumes execution
+    observer_handler.handle_observer_resumed();
+    assert!(!observer_handler.is_observer_paused());
+
+    // Verify the observer isn't paused while it keeps committing
+    let mock_time_service = time_service.into_mock();
+    for _ in 0..5 {
+        mock_time_service.advance_ms(MAX_OBSERVER_SILENCE_MS / 2);
+        observer_handler.handle_observer_commit();
+        observer_handler.check_observer_progress();
+        assert!(!observer_handler.is_observer_paused());
+    }
+    verify_no_pause_notification(&mut observer_pause_listener);
+
+    // The observer goes idle and verify it is asked to pause
+    mock_time_service.advance_ms(MAX_OBSERVER_SILENCE_MS + 1);
+    observer_handler.check_observer_progress();
+    let pause_notification = observer_pause_listener
+        .select_next_some()
+        .now_or_never()
+        .unwrap();
+
+    // Verify state sync doesn't take over until the observer acknowledges the pause
+    observer_handler.check_observer_progress();
+    assert!(!observer_handler.is_observer_paused());
+    pause_notification.acknowledge();
+    observer_handler.check_observer_progress();
+    assert!(observer_handler.is_observer_paused());
+
+    // Verify the observer isn't asked to pause again while state sync is running
+    mock_time_service.advance_ms(MAX_OBSERVER_SILENCE_MS * 10);
+    observer_handler.check_observer_progress();
+    assert!(observer_handler.is_observer_paused());
+    verify_no_pause_notification(&mut observer_pause_listener);
+
+    // The observer syncs to a new commit and resumes execution
+    observer_handler.handle_observer_resumed();
+    observer_handler.check_observer_progress();
+    assert!(!observer_handler.is_observer_paused());
+    verify_no_pause_notification(&mut observer_pause_listener);
+}
+
+#[test]
+fn test_observer_resume_before_pause_ack() {
+    // Create a consensus observer handler and resume the observer
+    let time_service = TimeService::mock();
+    let (observer_pause_notifier, mut observer_pause_listener) =
+        new_observer_pause_notifier_listener_pair();
+    let mut observer_handler = ConsensusObserverHandler::new(
+        MAX_OBSERVER_SILENCE_MS,
+        observer_pause_notifier,
+        time_service.clone(),
+    );
+    observer_handler.handle_observer_resumed();
+
+    // The observer goes idle and is asked to pause
+    time_service
+        .into_mock()
+        .advance_ms(MAX_OBSERVER_SILENCE_MS + 1);
+    observer_handler.check_observer_progress();
+    let pause_notification = observer_pause_listener
+        .select_next_some()
+        .now_or_never()
+        .unwrap();
+
+    // The observer resumes (e.g., after syncing to a commit) and then handles
+    // the stale pause request. Verify the stale acknowledgement is ignored.
+    observer_handler.handle_observer_resumed();
+    pause_notification.acknowledge();
+    observer_handler.check_observer_progress();
+    assert!(!observer_handler.is_observer_paused());
+}
+
+#[test]
+fn test_observer_not_running() {
+    // Create a consensus observer handler and resume the observer
+    let time_service = TimeService::mock();
+    let (observer_pause_notifier, observer_pause_listener) =
+        new_observer_pause_notifier_listener_pair();
+    let mut observer_handler = ConsensusObserverHandler::new(
+        MAX_OBSERVER_SILENCE_MS,
+        observer_pause_notifier,
+        time_service.clone(),
+    );
+    observer_handler.handle_observer_resumed();
+
+    // Stop the observer and let it go idle
+    drop(observer_pause_listener);
+    time_service
+        .into_mock()
+        .advance_ms(MAX_OBSERVER_SILENCE_MS + 1);
+
+    // Verify state sync takes over (there's nothing to pause)
+    observer_handler.check_observer_progress();
+    assert!(observer_handler.is_observer_paused());
+}
+
+/// Verifies that no pause notification was sent to the observer
+fn verify_no_pause_notification(observer_pause_listener: &mut ObserverPauseListener) {
+    assert!(observer_pause_listener
+        .select_next_some()
+        .now_or_never()
+        .is_none());
+}
@@ -16,7 +16,7 @@ use crate::{
         },
         utils::{
             create_epoch_ending_ledger_info, create_event, create_output_list_with_proof,
-            create_state_value_chunk_with_proof, create_transaction,
+            create_state_value_chunk_with_proof, create_state_value_chunks, create_transaction,
             create_transaction_list_with_proof, verify_commit_notification,
         },
     },
@@ -37,7 +37,7 @@ use aptos_types::{
 };
 use claims::assert_matches;
 use futures::StreamExt;
-use mockall::predicate::always;
+use mockall::{predicate::always, Sequence};
 use std::{sync::Arc, time::Duration};
 use tokio::task::JoinHandle;
 
@@ -420,6 +420,94 @@ async fn test_save_states_completion() {
     verify_no_pending_data(&storage_synchronizer);
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn test_save_states_out_of_order() {
+    // Create test data
+    let target_ledger_info = create_epoch_ending_ledger_info();
+    let output_list_with_proof = create_output_list_with_proof();
+    let (_, state_value_chunks) = create_state_value_chunks(3, 10);
+
+    // Setup the mock snapshot receiver to expect the out of order chunks to be
+    // written on arrival, and all chunks to be added in order.
+    let mut snapshot_receiver = create_mock_receiver();
+    let mut expectation_sequence = Sequence::new();
+    for chunk_index in [2, 1] {
+        let expected_values = state_value_chunks[chunk_index].raw_values.clone();
+        snapshot_receiver
+            .expect_add_out_of_order_chunk()
+            .times(1)
+            .withf(move |chunk| chunk == expected_values.as_slice())
+            .returning(|_| Ok(()))
+            .in_sequence(&mut expectation_sequence);
+    }
+    for state_value_chunk in &state_value_chunks {
+        let expected_values = state_value_chunk.raw_values.clone();
+        snapshot_receiver
+            .expect_add_chunk()
+            .times(1)
+            .withf(move |chunk, _| chunk == &expected_values)
+            .returning(|_, _| Ok(()))
+            .in_sequence(&mut expectation_sequence);
+    }
+    snapshot_receiver.expect_finish_box().returning(|| Ok(()));
+
+    // Setup the mock executor
+    let mut chunk_executor = create_mock_executor();
+    chunk_executor.expect_reset().returning(|| Ok(()));
+
+    // Setup the mock db writer
+    let mut db_writer = create_mock_db_writer();
+    db_writer
+        .expect_get_state_snapshot_receiver()
+        .with(always(), always())
+        .return_once(move |_, _| Ok(Box::new(snapshot_receiver)));
+    db_writer
+        .expect_finalize_state_snapshot()
+        .returning(|_, _, _| Ok(()));
+
+    // Create the storage synchronizer
+    let (mut commit_listener, _, _, _, _, mut storage_synchronizer, _, _) =
+        create_storage_synchronizer(
+            chunk_executor,
+            create_mock_reader_writer(None, Some(db_writer)),
+        );
+
+    // Initialize the state synchronizer
+    let state_synchronizer_handle = storage_synchronizer
+        .initialize_state_synchronizer(
+            vec![target_ledger_info.clone()],
+            target_ledger_info,
+            output_list_with_proof.clone(),
+        )
+        .unwrap();
+
+    // Save the last two chunks out of order, followed by the first chunk
+    storage_synchronizer
+        .save_out_of_order_state_values(0, state_value_chunks[2].clone())
+        .unwrap();
+    storage_synchronizer
+        .save_out_of_order_state_values(1, state_value_chunks[1].clone())
+        .unwrap();
+    storage_synchronizer
+        .save_state_values(2, state_value_chunks[0].clone())
+        .unwrap();
+
+    // Verify we get a commit notification
+    let expected_committed_transactions = CommittedTransactions {
+        events: output_list_with_proof.transactions_and_outputs[0]
+            .1
+            .events()
+            .to_vec(),
+        transactions: vec![output_list_with_proof.transactions_and_outputs[0].0.clone()],
+    };
+    verify_snapshot_commit_notification(&mut commit_listener, expected_committed_transactions)
+        .await;
+
+    // The handler should return as we've finished writing all states
+    state_synchronizer_handle.await.unwrap();
+    verify_no_pending_data(&storage_synchronizer);
+}
+
 #[tokio::test(flavor = "multi_thread")]
 #[should_panic]
 async fn test_save_states_dropped_error_listener() {
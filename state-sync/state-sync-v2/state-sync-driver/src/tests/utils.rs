@@ -6,6 +6,7 @@ use crate::driver::DriverConfiguration;
 use aptos_config::config::{RoleType, StateSyncDriverConfig};
 use aptos_crypto::{
     ed25519::{Ed25519PrivateKey, Ed25519Signature},
+    hash::{CryptoHash, SPARSE_MERKLE_PLACEHOLDER_HASH},
     HashValue, PrivateKey, Uniform,
 };
 use aptos_data_client::global_summary::GlobalDataSummary;
@@ -27,9 +28,13 @@ use aptos_types::{
     ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
     on_chain_config::ValidatorSet,
     proof::{
-        SparseMerkleRangeProof, TransactionAccumulatorRangeProof, TransactionInfoListWithProof,
+        SparseMerkleInternalNode, SparseMerkleLeafNode, SparseMerkleRangeProof,
+        TransactionAccumulatorRangeProof, TransactionInfoListWithProof,
+    },
+    state_store::{
+        state_key::StateKey,
+        state_value::{StateValue, StateValueChunkWithProof},
     },
-    state_store::state_value::StateValueChunkWithProof,
     transaction::{
         ExecutionStatus, RawTransaction, Script, SignedTransaction, Transaction, TransactionInfo,
         TransactionListWithProof, TransactionOutput, TransactionOutputListWithProof,
@@ -72,6 +77,7 @@ pub fn create_full_node_driver_configuration() -> DriverConfiguration {
         config,
         role,
         waypoint,
+        consensus_observer_enabled: false,
     }
 }
 
@@ -102,6 +108,23 @@ pub fn create_output_list_with_proof() -> TransactionOutputListWithProof {
     )
 }
 
+/// Creates a test transaction output list with proof where the transaction
+/// info has the given state checkpoint (root) hash
+pub fn create_output_list_with_state_root_hash(
+    state_root_hash: HashValue,
+) -> TransactionOutputListWithProof {
+    let mut output_list_with_proof = create_output_list_with_proof();
+    output_list_with_proof.proof.transaction_infos = vec![TransactionInfo::new(
+        HashValue::random(),
+        HashValue::random(),
+        HashValue::random(),
+        Some(state_root_hash),
+        0,
+        ExecutionStatus::Success,
+    )];
+    output_list_with_proof
+}
+
 /// Creates a random epoch ending ledger info with the specified values
 pub fn create_random_epoch_ending_ledger_info(
     version: Version,
@@ -152,10 +175,111 @@ pub fn create_state_value_chunk_with_proof(last_chunk: bool) -> StateValueChunkW
         last_key: HashValue::random(),
         raw_values: vec![],
         proof: SparseMerkleRangeProof::new(right_siblings),
+        left_siblings: vec![],
         root_hash: HashValue::random(),
     }
 }
 
+/// Creates the given number of consecutive test state value chunks (each
+/// containing `chunk_size` state values). Each chunk can be verified
+/// independently against the returned root hash.
+pub fn create_state_value_chunks(
+    num_chunks: u64,
+    chunk_size: u64,
+) -> (HashValue, Vec<StateValueChunkWithProof>) {
+    // Create the state values (sorted by hashed state key)
+    let mut raw_values: Vec<_> = (0..num_chunks * chunk_size)
+        .map(|index| {
+            (
+                StateKey::raw(index.to_le_bytes().to_vec()),
+                StateValue::new_legacy(index.to_be_bytes().to_vec()),
+            )
+        })
+        .collect();
+    raw_values.sort_by_key(|(state_key, _)| state_key.hash());
+    let leaves: Vec<_> = raw_values
+        .iter()
+        .map(|(state_key, state_value)| {
+            SparseMerkleLeafNode::new(state_key.hash(), state_value.hash())
+        })
+        .collect();
+    let root_hash = compute_subtree_hash(&leaves, 0);
+
+    // Split the state values into chunks (each with the siblings on both sides)
+    let state_value_chunks = raw_values
+        .chunks(chunk_size as usize)
+        .zip(0..)
+        .map(|(raw_values, chunk_index)| {
+            let first_index = chunk_index * chunk_size;
+            let first_key = raw_values.first().unwrap().0.hash();
+            let last_key = raw_values.last().unwrap().0.hash();
+            StateValueChunkWithProof {
+                first_index,
+                last_index: first_index + chunk_size - 1,
+                first_key,
+                last_key,
+                raw_values: raw_values.to_vec(),
+                proof: SparseMerkleRangeProof::new(get_siblings(&leaves, last_key, 0, false)),
+                left_siblings: get_siblings(&leaves, first_key, 0, true),
+                root_hash,
+            }
+        })
+        .collect();
+
+    (root_hash, state_value_chunks)
+}
+
+/// Computes the hash of the subtree (at the given depth) that contains the
+/// given leaves (sorted by key).
+fn compute_subtree_hash(leaves: &[SparseMerkleLeafNode], depth: usize) -> HashValue {
+    match leaves {
+        [] => *SPARSE_MERKLE_PLACEHOLDER_HASH,
+        [leaf] => leaf.hash(),
+        _ => {
+            let (left_leaves, right_leaves) = split_leaves(leaves, depth);
+            SparseMerkleInternalNode::new(
+                compute_subtree_hash(left_leaves, depth + 1),
+                compute_subtree_hash(right_leaves, depth + 1),
+            )
+            .hash()
+        },
+    }
+}
+
+/// Returns the siblings on the left (or right) of the path to the given key
+/// in the subtree (at the given depth) that contains the given leaves. The
+/// siblings near the bottom are at the beginning of the vector.
+fn get_siblings(
+    leaves: &[SparseMerkleLeafNode],
+    key: HashValue,
+    depth: usize,
+    left: bool,
+) -> Vec<HashValue> {
+    if leaves.len() <= 1 {
+        return vec![];
+    }
+
+    let (left_leaves, right_leaves) = split_leaves(leaves, depth);
+    let (path_leaves, sibling_leaves) = if key.bit(depth) {
+        (right_leaves, left_leaves)
+    } else {
+        (left_leaves, right_leaves)
+    };
+    let mut siblings = get_siblings(path_leaves, key, depth + 1, left);
+    if key.bit(depth) == left {
+        siblings.push(compute_subtree_hash(sibling_leaves, depth + 1));
+    }
+    siblings
+}
+
+/// Splits the given leaves (sorted by key) by the bit at the given depth
+fn split_leaves(
+    leaves: &[SparseMerkleLeafNode],
+    depth: usize,
+) -> (&[SparseMerkleLeafNode], &[SparseMerkleLeafNode]) {
+    leaves.split_at(leaves.partition_point(|leaf| !leaf.key().bit(depth)))
+}
+
 /// Creates a single test transaction
 pub fn create_transaction() -> Transaction {
     let private_key = Ed25519PrivateKey::generate_for_testing();
@@ -48,13 +48,31 @@ impl<NetworkClient: NetworkClientInterface<StorageServiceMessage>>
         timeout: Duration,
         request: StorageServiceRequest,
     ) -> Result<StorageServiceResponse, Error> {
-        let response = self
+        let (response, _) = self
+            .send_request_with_response_size(recipient, timeout, request)
+            .await?;
+        Ok(response)
+    }
+
+    /// Sends the request and returns the response, along with the number
+    /// of bytes of the response message received from the network.
+    pub async fn send_request_with_response_size(
+        &self,
+        recipient: PeerNetworkId,
+        timeout: Duration,
+        request: StorageServiceRequest,
+    ) -> Result<(StorageServiceResponse, usize), Error> {
+        let (response, num_bytes) = self
             .network_client
-            .send_to_peer_rpc(StorageServiceMessage::Request(request), timeout, recipient)
+            .send_to_peer_rpc_with_response_size(
+                StorageServiceMessage::Request(request),
+                timeout,
+                recipient,
+            )
             .await
             .map_err(|error| Error::NetworkError(error.to_string()))?;
         match response {
-            StorageServiceMessage::Response(Ok(response)) => Ok(response),
+            StorageServiceMessage::Response(Ok(response)) => Ok((response, num_bytes)),
             StorageServiceMessage::Response(Err(err)) => Err(Error::StorageServiceError(err)),
             StorageServiceMessage::Request(request) => Err(Error::NetworkError(format!(
                 "Got storage service request instead of response! Request: {:?}",
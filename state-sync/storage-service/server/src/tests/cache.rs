@@ -169,6 +169,7 @@ async fn test_cachable_requests_eviction() {
         last_key: HashValue::random(),
         raw_values: vec![],
         proof: SparseMerkleRangeProof::new(vec![]),
+        left_siblings: vec![],
         root_hash: HashValue::random(),
     };
 
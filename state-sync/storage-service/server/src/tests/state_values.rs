@@ -40,6 +40,7 @@ async fn test_get_states_with_proof() {
             last_key: HashValue::random(),
             raw_values: vec![],
             proof: SparseMerkleRangeProof::new(vec![]),
+            left_siblings: vec![],
             root_hash: HashValue::random(),
         };
 
@@ -87,6 +88,7 @@ async fn test_get_states_with_proof_chunk_limit() {
         last_key: HashValue::random(),
         raw_values: vec![],
         proof: SparseMerkleRangeProof::new(vec![]),
+        left_siblings: vec![],
         root_hash: HashValue::random(),
     };
 
@@ -252,6 +254,7 @@ async fn get_states_with_proof_network_limit(network_limit_bytes: u64) {
                 last_key: HashValue::random(),
                 raw_values: create_state_keys_and_values(chunk_size, min_bytes_per_state_value),
                 proof: SparseMerkleRangeProof::new(vec![]),
+                left_siblings: vec![],
                 root_hash: HashValue::random(),
             };
             db_reader
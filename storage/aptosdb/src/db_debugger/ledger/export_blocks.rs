@@ -0,0 +1,63 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{db_debugger::common::DbDir, transaction_store::TransactionStore};
+use anyhow::{ensure, Result};
+use aptos_types::transaction::{Transaction, Version};
+use clap::Parser;
+use std::{path::PathBuf, sync::Arc};
+
+#[derive(Parser)]
+#[clap(
+    about = "Export the transactions in a version range as BCS encoded blocks, e.g. to replay them in the block partitioner."
+)]
+pub struct Cmd {
+    #[clap(flatten)]
+    db_dir: DbDir,
+
+    start_version: Version,
+
+    num_versions: usize,
+
+    #[clap(long, value_parser)]
+    output_file: PathBuf,
+}
+
+impl Cmd {
+    pub fn run(self) -> Result<()> {
+        let store = TransactionStore::new(Arc::new(self.db_dir.open_ledger_db()?));
+
+        // A block starts with its BlockMetadata transaction. Transactions before the first one in
+        // the range are exported as a (partial) block of their own.
+        let mut blocks: Vec<Vec<Transaction>> = vec![];
+        let mut current_block = vec![];
+        let mut num_txns = 0;
+        for res in store.get_transaction_iter(self.start_version, self.num_versions)? {
+            let txn = res?;
+            if matches!(txn, Transaction::BlockMetadata(_)) && !current_block.is_empty() {
+                blocks.push(std::mem::take(&mut current_block));
+            }
+            current_block.push(txn);
+            num_txns += 1;
+        }
+        if !current_block.is_empty() {
+            blocks.push(current_block);
+        }
+        ensure!(
+            num_txns == self.num_versions,
+            "Didn't see all versions requested, got {} out of {}",
+            num_txns,
+            self.num_versions,
+        );
+
+        std::fs::write(&self.output_file, bcs::to_bytes(&blocks)?)?;
+        println!(
+            "Exported {} transactions in {} blocks to {:?}.",
+            num_txns,
+            blocks.len(),
+            self.output_file
+        );
+
+        Ok(())
+    }
+}
@@ -3,6 +3,8 @@
 
 mod check_range_proof;
 mod check_txn_info_hashes;
+mod export_blocks;
+mod train_compression_dictionary;
 
 use anyhow::Result;
 
@@ -11,6 +13,8 @@ use anyhow::Result;
 pub enum Cmd {
     CheckTransactionInfoHashes(check_txn_info_hashes::Cmd),
     CheckRangeProof(check_range_proof::Cmd),
+    ExportBlocks(export_blocks::Cmd),
+    TrainCompressionDictionary(train_compression_dictionary::Cmd),
 }
 
 impl Cmd {
@@ -18,6 +22,8 @@ impl Cmd {
         match self {
             Self::CheckTransactionInfoHashes(cmd) => cmd.run(),
             Self::CheckRangeProof(cmd) => cmd.run(),
+            Self::ExportBlocks(cmd) => cmd.run(),
+            Self::TrainCompressionDictionary(cmd) => cmd.run(),
         }
     }
 }
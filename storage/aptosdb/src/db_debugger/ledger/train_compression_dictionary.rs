@@ -0,0 +1,71 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{db_debugger::common::DbDir, transaction_store::TransactionStore};
+use anyhow::{ensure, Result};
+use aptos_compression::dictionary::CompressionDictionary;
+use aptos_types::transaction::Version;
+use clap::Parser;
+use std::{path::PathBuf, sync::Arc};
+
+#[derive(Parser)]
+#[clap(
+    about = "Train a zstd compression dictionary on the BCS encoded transactions (and optionally write sets) in a version range."
+)]
+pub struct Cmd {
+    #[clap(flatten)]
+    db_dir: DbDir,
+
+    start_version: Version,
+
+    num_versions: usize,
+
+    #[clap(
+        long,
+        default_value_t = 112_640,
+        help = "The max size of the dictionary (in bytes)."
+    )]
+    max_dictionary_size: usize,
+
+    #[clap(long, help = "Also sample the write sets of the transactions.")]
+    include_write_sets: bool,
+
+    #[clap(long, value_parser)]
+    output_file: PathBuf,
+}
+
+impl Cmd {
+    pub fn run(self) -> Result<()> {
+        let store = TransactionStore::new(Arc::new(self.db_dir.open_ledger_db()?));
+
+        // Gather the samples
+        let mut samples = vec![];
+        for res in store.get_transaction_iter(self.start_version, self.num_versions)? {
+            samples.push(bcs::to_bytes(&res?)?);
+        }
+        if self.include_write_sets {
+            for res in store.get_write_set_iter(self.start_version, self.num_versions)? {
+                samples.push(bcs::to_bytes(&res?)?);
+            }
+        }
+        ensure!(
+            !samples.is_empty(),
+            "No samples found in the version range!"
+        );
+
+        // Train the dictionary
+        let sample_bytes: usize = samples.iter().map(|sample| sample.len()).sum();
+        let dictionary = CompressionDictionary::train(&samples, self.max_dictionary_size)?;
+        std::fs::write(&self.output_file, dictionary.bytes())?;
+        println!(
+            "Trained dictionary {} ({} bytes) on {} samples ({} bytes) and wrote it to {:?}.",
+            dictionary.id(),
+            dictionary.bytes().len(),
+            samples.len(),
+            sample_bytes,
+            self.output_file
+        );
+
+        Ok(())
+    }
+}
@@ -239,6 +239,14 @@ impl StateMerkleDb {
         JellyfishMerkleTree::new(self).get_range_proof(rightmost_key, version)
     }
 
+    pub fn get_range_left_siblings(
+        &self,
+        leftmost_key: HashValue,
+        version: Version,
+    ) -> Result<Vec<HashValue>> {
+        JellyfishMerkleTree::new(self).get_range_left_siblings(leftmost_key, version)
+    }
+
     pub fn get_root_hash(&self, version: Version) -> Result<HashValue> {
         JellyfishMerkleTree::new(self).get_root_hash(version)
     }
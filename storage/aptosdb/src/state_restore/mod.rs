@@ -14,7 +14,12 @@ use aptos_types::{
     transaction::Version,
 };
 use serde::{Deserialize, Serialize};
-use std::{collections::HashMap, hash::Hash, str::FromStr, sync::Arc};
+use std::{
+    collections::{HashMap, HashSet},
+    hash::Hash,
+    str::FromStr,
+    sync::Arc,
+};
 
 #[cfg(test)]
 mod restore_test;
@@ -36,12 +41,12 @@ impl StateSnapshotProgress {
 }
 
 pub trait StateValueWriter<K, V>: Send + Sync {
-    /// Writes a kv batch into storage.
+    /// Writes a kv batch into storage, along with the new restore progress (if any).
     fn write_kv_batch(
         &self,
         version: Version,
         kv_batch: &StateValueBatch<K, Option<V>>,
-        progress: StateSnapshotProgress,
+        progress: Option<StateSnapshotProgress>,
     ) -> Result<()>;
 
     fn write_usage(&self, version: Version, usage: StateStorageUsage) -> Result<()>;
@@ -91,7 +96,10 @@ impl<K: Key + CryptoHash + Eq + Hash, V: Value> StateValueRestore<K, V> {
         Self { version, db }
     }
 
-    pub fn add_chunk(&mut self, mut chunk: Vec<(K, V)>) -> Result<()> {
+    /// Adds the next chunk (in order) and updates the progress. If the values of the chunk
+    /// have already been written (i.e., the chunk arrived out of order), only the progress
+    /// is updated.
+    pub fn add_chunk(&mut self, mut chunk: Vec<(K, V)>, values_written: bool) -> Result<()> {
         // load progress
         let progress_opt = self.db.get_progress(self.version)?;
 
@@ -119,17 +127,31 @@ impl<K: Key + CryptoHash + Eq + Hash, V: Value> StateValueRestore<K, V> {
             usage.add_item(k.key_size() + v.value_size());
         }
 
-        let kv_batch: StateValueBatch<K, Option<V>> = chunk
-            .into_iter()
-            .map(|(k, v)| ((k, self.version), Some(v)))
-            .collect();
+        let kv_batch: StateValueBatch<K, Option<V>> = if values_written {
+            StateValueBatch::new()
+        } else {
+            chunk
+                .into_iter()
+                .map(|(k, v)| ((k, self.version), Some(v)))
+                .collect()
+        };
         self.db.write_kv_batch(
             self.version,
             &kv_batch,
-            StateSnapshotProgress::new(last_key_hash, usage),
+            Some(StateSnapshotProgress::new(last_key_hash, usage)),
         )
     }
 
+    /// Writes the values of a chunk that arrived out of order (i.e., ahead of the
+    /// progress). The progress is only updated once the chunk is added in order.
+    pub fn add_out_of_order_chunk(&mut self, chunk: &[(K, V)]) -> Result<()> {
+        let kv_batch: StateValueBatch<K, Option<V>> = chunk
+            .iter()
+            .map(|(k, v)| ((k.clone(), self.version), Some(v.clone())))
+            .collect();
+        self.db.write_kv_batch(self.version, &kv_batch, None)
+    }
+
     pub fn finish(self) -> Result<()> {
         let progress = self.db.get_progress(self.version)?;
         self.db.write_usage(
@@ -150,6 +172,8 @@ pub struct StateSnapshotRestore<K, V> {
     tree_restore: Arc<Mutex<Option<JellyfishMerkleRestore<K>>>>,
     kv_restore: Arc<Mutex<Option<StateValueRestore<K, V>>>>,
     restore_mode: StateSnapshotRestoreMode,
+    /// The (first, last) key hashes of the chunks whose values were written out of order
+    out_of_order_chunks: HashSet<(HashValue, HashValue)>,
 }
 
 impl<K: Key + CryptoHash + Hash + Eq, V: Value> StateSnapshotRestore<K, V> {
@@ -173,6 +197,7 @@ impl<K: Key + CryptoHash + Hash + Eq, V: Value> StateSnapshotRestore<K, V> {
                 version,
             )))),
             restore_mode,
+            out_of_order_chunks: HashSet::new(),
         })
     }
 
@@ -194,6 +219,7 @@ impl<K: Key + CryptoHash + Hash + Eq, V: Value> StateSnapshotRestore<K, V> {
                 version,
             )))),
             restore_mode,
+            out_of_order_chunks: HashSet::new(),
         })
     }
 
@@ -230,6 +256,13 @@ impl<K: Key + CryptoHash + Hash + Eq, V: Value> StateSnapshotReceiver<K, V>
     for StateSnapshotRestore<K, V>
 {
     fn add_chunk(&mut self, chunk: Vec<(K, V)>, proof: SparseMerkleRangeProof) -> Result<()> {
+        let values_written = match (chunk.first(), chunk.last()) {
+            (Some((first_key, _)), Some((last_key, _))) => self
+                .out_of_order_chunks
+                .remove(&(CryptoHash::hash(first_key), CryptoHash::hash(last_key))),
+            _ => false,
+        };
+
         let kv_fn = || {
             let _timer = OTHER_TIMERS_SECONDS
                 .with_label_values(&["state_value_add_chunk"])
@@ -238,7 +271,7 @@ impl<K: Key + CryptoHash + Hash + Eq, V: Value> StateSnapshotReceiver<K, V>
                 .lock()
                 .as_mut()
                 .unwrap()
-                .add_chunk(chunk.clone())
+                .add_chunk(chunk.clone(), values_written)
         };
 
         let tree_fn = || {
@@ -267,6 +300,30 @@ impl<K: Key + CryptoHash + Hash + Eq, V: Value> StateSnapshotReceiver<K, V>
         Ok(())
     }
 
+    fn add_out_of_order_chunk(&mut self, chunk: &[(K, V)]) -> Result<()> {
+        // The tree (and the usage) can only be restored in order
+        if self.restore_mode == StateSnapshotRestoreMode::TreeOnly {
+            return Ok(());
+        }
+        let (first_key, last_key) = match (chunk.first(), chunk.last()) {
+            (Some((first_key, _)), Some((last_key, _))) => (first_key, last_key),
+            _ => return Ok(()),
+        };
+
+        let _timer = OTHER_TIMERS_SECONDS
+            .with_label_values(&["state_value_add_out_of_order_chunk"])
+            .start_timer();
+        self.kv_restore
+            .lock()
+            .as_mut()
+            .unwrap()
+            .add_out_of_order_chunk(chunk)?;
+        self.out_of_order_chunks
+            .insert((CryptoHash::hash(first_key), CryptoHash::hash(last_key)));
+
+        Ok(())
+    }
+
     fn finish(self) -> Result<()> {
         match self.restore_mode {
             StateSnapshotRestoreMode::KvOnly => self.kv_restore.lock().take().unwrap().finish()?,
@@ -76,7 +76,7 @@ where
         &self,
         version: Version,
         kv_batch: &StateValueBatch<K, Option<V>>,
-        progress: StateSnapshotProgress,
+        progress: Option<StateSnapshotProgress>,
     ) -> Result<()> {
         for (k, v) in kv_batch {
             if let Some(v) = v {
@@ -85,7 +85,9 @@ where
                 self.kv_store.write().remove(k);
             }
         }
-        self.progress_store.write().insert(version, progress);
+        if let Some(progress) = progress {
+            self.progress_store.write().insert(version, progress);
+        }
         Ok(())
     }
 
@@ -217,6 +219,44 @@ proptest! {
         assert_success(&restore_db, expected_root_hash, &all, version);
     }
 
+    #[test]
+    fn test_restore_out_of_order(
+        (all, batch1_size) in arb_btree_map(2)
+            .prop_flat_map(|btree| {
+                let len = btree.len();
+                (Just(btree), 1..len)
+            })
+    ) {
+        let (db, version) = init_mock_store(&all.clone().into_values().collect());
+        let tree = JellyfishMerkleTree::new(&db);
+        let expected_root_hash = tree.get_root_hash(version).unwrap();
+        let batch1: Vec<_> = all.clone().into_iter().take(batch1_size).collect();
+        let batch2: Vec<_> = all.clone().into_iter().skip(batch1_size).collect();
+
+        let restore_db = Arc::new(MockSnapshotStore::default());
+        let mut restore =
+            StateSnapshotRestore::new(&restore_db, &restore_db, version, expected_root_hash, true /* async_commit */, StateSnapshotRestoreMode::Default).unwrap();
+
+        // Write the second batch out of order and verify the progress isn't updated
+        let batch2_kvs: Vec<_> = batch2.iter().map(|(_, kv)| kv.clone()).collect();
+        restore.add_out_of_order_chunk(&batch2_kvs).unwrap();
+        for (key, value) in batch2_kvs.iter() {
+            prop_assert_eq!(&restore_db.get_value_at_version(&(key.clone(), version)).unwrap(), value);
+        }
+        prop_assert!(restore_db.get_progress(version).unwrap().is_none());
+
+        // Add both batches in order
+        for batch in [batch1, batch2] {
+            let proof = tree
+                .get_range_proof(batch.last().map(|(key, _value)| *key).unwrap(), version)
+                .unwrap();
+            restore.add_chunk(batch.into_iter().map(|(_, kv)| kv).collect(), proof).unwrap();
+        }
+        restore.finish().unwrap();
+
+        assert_success(&restore_db, expected_root_hash, &all, version);
+    }
+
     #[test]
     fn test_overwrite(
         btree in arb_btree_map(1),
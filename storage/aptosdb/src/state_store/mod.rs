@@ -615,6 +615,17 @@ impl StateStore {
         self.state_merkle_db.get_range_proof(rightmost_key, version)
     }
 
+    /// Gets the siblings on the left of a range of accounts (i.e., so that the
+    /// range can be proven without the accounts before it).
+    pub fn get_value_range_left_siblings(
+        &self,
+        leftmost_key: HashValue,
+        version: Version,
+    ) -> Result<Vec<HashValue>> {
+        self.state_merkle_db
+            .get_range_left_siblings(leftmost_key, version)
+    }
+
     /// Put the write sets on top of current state
     pub fn put_write_sets(
         &self,
@@ -1010,6 +1021,7 @@ impl StateStore {
         let first_key = state_key_values.first().expect("checked to exist").0.hash();
         let last_key = state_key_values.last().expect("checked to exist").0.hash();
         let proof = self.get_value_range_proof(last_key, version)?;
+        let left_siblings = self.get_value_range_left_siblings(first_key, version)?;
         let root_hash = self.get_root_hash(version)?;
 
         Ok(StateValueChunkWithProof {
@@ -1019,6 +1031,7 @@ impl StateStore {
             last_key,
             raw_values: state_key_values,
             proof,
+            left_siblings,
             root_hash,
         })
     }
@@ -1107,7 +1120,7 @@ impl StateValueWriter<StateKey, StateValue> for StateStore {
         &self,
         version: Version,
         node_batch: &StateValueBatch,
-        progress: StateSnapshotProgress,
+        progress: Option<StateSnapshotProgress>,
     ) -> Result<()> {
         let _timer = OTHER_TIMERS_SECONDS
             .with_label_values(&["state_value_writer_write_chunk"])
@@ -1117,10 +1130,12 @@ impl StateValueWriter<StateKey, StateValue> for StateStore {
             .par_iter()
             .map(|(k, v)| batch.put::<StateValueSchema>(k, v))
             .collect::<Result<Vec<_>>>()?;
-        batch.put::<DbMetadataSchema>(
-            &DbMetadataKey::StateSnapshotRestoreProgress(version),
-            &DbMetadataValue::StateSnapshotProgress(progress),
-        )?;
+        if let Some(progress) = progress {
+            batch.put::<DbMetadataSchema>(
+                &DbMetadataKey::StateSnapshotRestoreProgress(version),
+                &DbMetadataValue::StateSnapshotProgress(progress),
+            )?;
+        }
         // TODO(grao): Support sharding here.
         self.state_kv_db.commit_raw_batch(batch)
     }
@@ -468,6 +468,54 @@ proptest! {
         );
     }
 
+    #[test]
+    fn test_restore_out_of_order(
+        (input, batch_size) in hash_map(any::<StateKey>(), any::<StateValue>(), 2..1000)
+            .prop_flat_map(|input| {
+                let len = input.len();
+                (Just(input), 1..len)
+            })
+    ) {
+        let tmp_dir1 = TempPath::new();
+        let db1 = AptosDB::new_for_test(&tmp_dir1);
+        let store1 = &db1.state_store;
+        init_store(store1, input.clone().into_iter());
+
+        let version = (input.len() - 1) as Version;
+        let expected_root_hash = store1.get_root_hash(version).unwrap();
+
+        // Fetch all chunks and verify each of them independently
+        let chunks: Vec<_> = (0..input.len())
+            .step_by(batch_size)
+            .map(|current_idx| store1.get_value_chunk_with_proof(version, current_idx, batch_size).unwrap())
+            .collect();
+        for chunk in chunks.iter() {
+            prop_assert_eq!(chunk.root_hash, expected_root_hash);
+            prop_assert!(chunk.verify().is_ok());
+        }
+
+        let tmp_dir2 = TempPath::new();
+        let db2 = AptosDB::new_for_test(&tmp_dir2);
+        let store2 = &db2.state_store;
+
+        // Write all chunks (except the first) out of order, and then add all chunks in order
+        let mut restore = store2.get_snapshot_receiver(version, expected_root_hash).unwrap();
+        for chunk in chunks.iter().skip(1).rev() {
+            restore.add_out_of_order_chunk(&chunk.raw_values).unwrap();
+        }
+        for chunk in chunks {
+            restore.add_chunk(chunk.raw_values, chunk.proof).unwrap();
+        }
+
+        restore.finish_box().unwrap();
+        let actual_root_hash = store2.get_root_hash(version).unwrap();
+        prop_assert_eq!(actual_root_hash, expected_root_hash);
+        prop_assert_eq!(
+            store2.get_value_count(version).unwrap(),
+            input.len()
+        );
+    }
+
     #[test]
     fn test_get_rightmost_leaf(
         (input, batch1_size) in hash_map(any::<StateKey>(), any::<StateValue>(), 2..1000)
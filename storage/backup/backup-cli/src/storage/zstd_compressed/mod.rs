@@ -0,0 +1,221 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+#[cfg(test)]
+mod tests;
+
+use super::{BackupHandle, BackupHandleRef, FileHandle, FileHandleRef};
+use crate::storage::{BackupStorage, ShellSafeName, TextLine};
+use anyhow::Result;
+use aptos_compression::{
+    dictionary::{is_zstd_frame, set_compression_dictionary, CompressionDictionary},
+    metrics::CompressionClient,
+};
+use aptos_logger::info;
+use async_trait::async_trait;
+use clap::Parser;
+use futures::{
+    future::BoxFuture,
+    task::{Context, Poll},
+    Future, FutureExt,
+};
+use std::{io::Cursor, path::PathBuf, sync::Arc};
+use tokio::{
+    io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt},
+    macros::support::Pin,
+};
+
+/// The number of bytes needed to identify a zstd frame.
+const ZSTD_MAGIC_SIZE: usize = 4;
+
+#[derive(Parser, Clone, Debug, Default)]
+pub struct ZstdCompressionOpt {
+    #[clap(
+        long,
+        help = "Compress the backup files with zstd. Compressed files are detected (and \
+        decompressed) on read regardless of this flag."
+    )]
+    pub zstd_compression: bool,
+    #[clap(
+        long,
+        value_parser,
+        help = "A pre-trained zstd dictionary to compress the backup files with. The same \
+        dictionary must be provided when reading the files back."
+    )]
+    pub zstd_dictionary: Option<PathBuf>,
+}
+
+impl ZstdCompressionOpt {
+    /// Loads the dictionary (if any) and wraps the given storage
+    pub fn wrap_storage(self, storage: Arc<dyn BackupStorage>) -> Result<Arc<dyn BackupStorage>> {
+        if let Some(dictionary_path) = &self.zstd_dictionary {
+            let dictionary = CompressionDictionary::load(dictionary_path)?;
+            info!(
+                "Loaded zstd dictionary {} from {:?} for backup files.",
+                dictionary.id(),
+                dictionary_path
+            );
+            set_compression_dictionary(CompressionClient::Backup, Arc::new(dictionary));
+        }
+        Ok(Arc::new(ZstdCompressed::new(
+            storage,
+            self.zstd_compression,
+        )))
+    }
+}
+
+/// A storage wrapper that compresses the backup files with zstd. Files are
+/// detected as compressed by the zstd magic number, so backups written before
+/// compression was enabled (or with it disabled) remain readable. Metadata
+/// files are never compressed.
+pub struct ZstdCompressed {
+    storage: Arc<dyn BackupStorage>,
+    compress: bool,
+}
+
+impl ZstdCompressed {
+    pub fn new(storage: Arc<dyn BackupStorage>, compress: bool) -> Self {
+        Self { storage, compress }
+    }
+}
+
+#[async_trait]
+impl BackupStorage for ZstdCompressed {
+    async fn create_backup(&self, name: &ShellSafeName) -> Result<BackupHandle> {
+        self.storage.create_backup(name).await
+    }
+
+    async fn create_for_write(
+        &self,
+        backup_handle: &BackupHandleRef,
+        name: &ShellSafeName,
+    ) -> Result<(FileHandle, Box<dyn AsyncWrite + Send + Unpin>)> {
+        let (file_handle, file) = self.storage.create_for_write(backup_handle, name).await?;
+        if self.compress {
+            Ok((file_handle, Box::new(ZstdCompressingWriter::new(file))))
+        } else {
+            Ok((file_handle, file))
+        }
+    }
+
+    async fn open_for_read(
+        &self,
+        file_handle: &FileHandleRef,
+    ) -> Result<Box<dyn AsyncRead + Send + Unpin>> {
+        let mut file = self.storage.open_for_read(file_handle).await?;
+
+        // Read enough bytes to tell if the file is compressed
+        let mut prefix = Vec::with_capacity(ZSTD_MAGIC_SIZE);
+        while prefix.len() < ZSTD_MAGIC_SIZE {
+            let mut byte = [0u8; 1];
+            if file.read(&mut byte).await? == 0 {
+                break;
+            }
+            prefix.push(byte[0]);
+        }
+        if !is_zstd_frame(&prefix) {
+            return Ok(Box::new(Cursor::new(prefix).chain(file)));
+        }
+
+        // Decompress the whole file (files are written in whole chunks anyway)
+        let mut compressed_bytes = prefix;
+        file.read_to_end(&mut compressed_bytes).await?;
+        let raw_bytes = tokio::task::spawn_blocking(move || {
+            aptos_compression::zstd_decompress(
+                &compressed_bytes,
+                CompressionClient::Backup,
+                usize::MAX,
+            )
+        })
+        .await??;
+        Ok(Box::new(Cursor::new(raw_bytes)))
+    }
+
+    async fn list_metadata_files(&self) -> Result<Vec<FileHandle>> {
+        self.storage.list_metadata_files().await
+    }
+
+    async fn backup_metadata_file(&self, file_handle: &FileHandleRef) -> Result<()> {
+        self.storage.backup_metadata_file(file_handle).await
+    }
+
+    async fn save_metadata_lines(
+        &self,
+        name: &ShellSafeName,
+        lines: &[TextLine],
+    ) -> Result<FileHandle> {
+        self.storage.save_metadata_lines(name, lines).await
+    }
+}
+
+/// Buffers everything written to the file, and compresses and writes it to the
+/// underlying file on shutdown. This is fine because the backup controllers
+/// already buffer each chunk in memory before writing it.
+struct ZstdCompressingWriter {
+    file: Option<Box<dyn AsyncWrite + Send + Unpin>>,
+    buffer: Vec<u8>,
+    shutdown_fut: Option<BoxFuture<'static, Result<()>>>,
+}
+
+impl ZstdCompressingWriter {
+    fn new(file: Box<dyn AsyncWrite + Send + Unpin>) -> Self {
+        Self {
+            file: Some(file),
+            buffer: vec![],
+            shutdown_fut: None,
+        }
+    }
+}
+
+impl AsyncWrite for ZstdCompressingWriter {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<Result<usize, tokio::io::Error>> {
+        if self.shutdown_fut.is_some() {
+            Poll::Ready(Err(tokio::io::ErrorKind::BrokenPipe.into()))
+        } else {
+            self.buffer.extend_from_slice(buf);
+            Poll::Ready(Ok(buf.len()))
+        }
+    }
+
+    fn poll_flush(
+        self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+    ) -> Poll<Result<(), tokio::io::Error>> {
+        // Nothing is written to the underlying file until shutdown
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_shutdown(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), tokio::io::Error>> {
+        if self.shutdown_fut.is_none() {
+            let mut file = self.file.take().unwrap();
+            let buffer = std::mem::take(&mut self.buffer);
+            self.shutdown_fut = Some(
+                async move {
+                    let compressed_bytes = tokio::task::spawn_blocking(move || {
+                        aptos_compression::zstd_compress_with_dictionary(
+                            buffer,
+                            CompressionClient::Backup,
+                            usize::MAX,
+                        )
+                    })
+                    .await??;
+                    file.write_all(&compressed_bytes).await?;
+                    file.shutdown().await?;
+                    Ok(())
+                }
+                .boxed(),
+            );
+        }
+
+        Pin::new(self.shutdown_fut.as_mut().unwrap())
+            .poll(cx)
+            .map_err(|e| tokio::io::Error::new(tokio::io::ErrorKind::Other, e))
+    }
+}
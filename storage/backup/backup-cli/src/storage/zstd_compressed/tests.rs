@@ -0,0 +1,96 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use crate::storage::{
+    local_fs::LocalFs,
+    test_util::{
+        arb_backups, arb_metadata_files, test_save_and_list_metadata_files_impl,
+        test_write_and_read_impl,
+    },
+};
+use aptos_temppath::TempPath;
+use proptest::prelude::*;
+use std::str::FromStr;
+use tokio::runtime::Runtime;
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(10))]
+
+    #[test]
+    fn test_write_and_read(
+        backups in arb_backups()
+    ) {
+        let tmpdir = TempPath::new();
+        tmpdir.create_as_dir().unwrap();
+        let store = ZstdCompressed::new(Arc::new(LocalFs::new(tmpdir.path().to_path_buf())), true);
+
+        let rt = Runtime::new().unwrap();
+        rt.block_on(test_write_and_read_impl(Box::new(store), backups));
+    }
+
+    #[test]
+    fn test_save_list_metadata_files(
+        input in arb_metadata_files(),
+    ) {
+        let tmpdir = TempPath::new();
+        tmpdir.create_as_dir().unwrap();
+        let store = ZstdCompressed::new(Arc::new(LocalFs::new(tmpdir.path().to_path_buf())), true);
+
+        let rt = Runtime::new().unwrap();
+        rt.block_on(test_save_and_list_metadata_files_impl(Box::new(store), input));
+    }
+}
+
+#[test]
+fn test_compressed_and_uncompressed_files() {
+    let rt = Runtime::new().unwrap();
+    rt.block_on(test_compressed_and_uncompressed_files_impl());
+}
+
+async fn test_compressed_and_uncompressed_files_impl() {
+    let tmpdir = TempPath::new();
+    tmpdir.create_as_dir().unwrap();
+    let local_fs = Arc::new(LocalFs::new(tmpdir.path().to_path_buf()));
+    let compressed_store = ZstdCompressed::new(local_fs.clone(), true);
+    let uncompressed_store = ZstdCompressed::new(local_fs.clone(), false);
+
+    let backup_name = ShellSafeName::from_str("backup").unwrap();
+    let backup_handle = compressed_store.create_backup(&backup_name).await.unwrap();
+    let content = "transaction ".repeat(1000).into_bytes();
+
+    // Write the same content with and without compression
+    let mut file_handles = vec![];
+    for (name, store) in [
+        ("compressed", &compressed_store),
+        ("uncompressed", &uncompressed_store),
+    ] {
+        let name = ShellSafeName::from_str(name).unwrap();
+        let (file_handle, mut file) = store.create_for_write(&backup_handle, &name).await.unwrap();
+        file.write_all(&content).await.unwrap();
+        file.shutdown().await.unwrap();
+        file_handles.push(file_handle);
+    }
+
+    // Verify only the first file was compressed on disk
+    let compressed_bytes = std::fs::read(tmpdir.path().join(&file_handles[0])).unwrap();
+    assert!(is_zstd_frame(&compressed_bytes));
+    assert!(compressed_bytes.len() < content.len());
+    let uncompressed_bytes = std::fs::read(tmpdir.path().join(&file_handles[1])).unwrap();
+    assert_eq!(uncompressed_bytes, content);
+
+    // Verify both files are read back correctly by both stores
+    for store in [&compressed_store, &uncompressed_store] {
+        for file_handle in &file_handles {
+            let mut buf = vec![];
+            store
+                .open_for_read(file_handle)
+                .await
+                .unwrap()
+                .read_to_end(&mut buf)
+                .await
+                .unwrap();
+            assert_eq!(buf, content);
+        }
+    }
+}
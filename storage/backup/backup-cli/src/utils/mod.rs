@@ -173,7 +173,7 @@ impl StateValueWriter<StateKey, StateValue> for MockStore {
         &self,
         _version: Version,
         _kv_batch: &StateValueBatch<StateKey, Option<StateValue>>,
-        _progress: StateSnapshotProgress,
+        _progress: Option<StateSnapshotProgress>,
     ) -> Result<()> {
         Ok(())
     }
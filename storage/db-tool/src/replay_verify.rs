@@ -56,6 +56,13 @@ pub struct Opt {
     txns_to_skip: Vec<Version>,
     #[clap(long, help = "Do not quit right away when a replay issue is detected.")]
     lazy_quit: bool,
+    #[clap(
+        long,
+        value_parser,
+        help = "Dump the expected and actual outputs of the transactions failing verification \
+        to this directory."
+    )]
+    mismatch_dump_dir: Option<PathBuf>,
 }
 
 impl Opt {
@@ -80,7 +87,9 @@ impl Opt {
             self.start_version.unwrap_or(0),
             self.end_version.unwrap_or(Version::MAX),
             self.validate_modules,
-            VerifyExecutionMode::verify_except(self.txns_to_skip).set_lazy_quit(self.lazy_quit),
+            VerifyExecutionMode::verify_except(self.txns_to_skip)
+                .set_lazy_quit(self.lazy_quit)
+                .set_mismatch_dump_dir(self.mismatch_dump_dir),
         )?
         .run()
         .await
@@ -7,8 +7,9 @@ use crate::{
     node_type::NodeType,
     test_helper::{
         arb_existent_kvs_and_nonexistent_keys, arb_kv_pair_with_distinct_last_nibble,
-        arb_tree_with_index, gen_value, test_get_leaf_count, test_get_range_proof,
-        test_get_with_proof, test_get_with_proof_with_distinct_last_nibble, ValueBlob,
+        arb_tree_with_index, gen_value, test_get_leaf_count, test_get_range_left_siblings,
+        test_get_range_proof, test_get_with_proof, test_get_with_proof_with_distinct_last_nibble,
+        ValueBlob,
     },
 };
 use aptos_crypto::{hash::SPARSE_MERKLE_PLACEHOLDER_HASH, HashValue};
@@ -802,6 +803,11 @@ proptest! {
         test_get_range_proof((btree, n))
     }
 
+    #[test]
+    fn proptest_get_range_left_siblings((btree, n) in arb_tree_with_index::<ValueBlob>(1000)) {
+        test_get_range_left_siblings((btree, n))
+    }
+
     #[test]
     fn proptest_get_leaf_count(keys in hash_set(any::<HashValue>(), 3..2000)) {
         test_get_leaf_count(keys)
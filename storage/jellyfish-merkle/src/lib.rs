@@ -820,6 +820,35 @@ where
         Ok(SparseMerkleRangeProof::new(siblings))
     }
 
+    /// Gets the siblings on the left of the path from root to `leftmost_key_to_prove` at
+    /// `version`. Together with a range proof, these prove a list of keys starting from
+    /// `leftmost_key_to_prove` (see `SparseMerkleRangeProof::verify_range`).
+    pub fn get_range_left_siblings(
+        &self,
+        leftmost_key_to_prove: HashValue,
+        version: Version,
+    ) -> Result<Vec<HashValue>> {
+        let (account, proof) = self.get_with_proof(leftmost_key_to_prove, version)?;
+        ensure!(account.is_some(), "leftmost_key_to_prove must exist.");
+
+        let siblings = proof
+            .siblings()
+            .iter()
+            .rev()
+            .zip(leftmost_key_to_prove.iter_bits())
+            .filter_map(|(sibling, bit)| {
+                // We only need to keep the siblings on the left.
+                if bit {
+                    Some(*sibling)
+                } else {
+                    None
+                }
+            })
+            .rev()
+            .collect();
+        Ok(siblings)
+    }
+
     #[cfg(test)]
     pub fn get(&self, key: HashValue, version: Version) -> Result<Option<HashValue>> {
         Ok(self.get_with_proof(key, version)?.0.map(|x| x.0))
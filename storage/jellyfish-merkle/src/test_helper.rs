@@ -10,7 +10,7 @@ use aptos_crypto::{
 use aptos_crypto_derive::{BCSCryptoHash, CryptoHasher};
 use aptos_storage_interface::jmt_update_refs;
 use aptos_types::{
-    proof::{SparseMerkleInternalNode, SparseMerkleRangeProof},
+    proof::{SparseMerkleInternalNode, SparseMerkleLeafNode, SparseMerkleRangeProof},
     transaction::Version,
 };
 use proptest::{
@@ -191,6 +191,30 @@ pub fn test_get_range_proof<V: TestKey>((btree, n): (BTreeMap<HashValue, (HashVa
     );
 }
 
+pub fn test_get_range_left_siblings<V: TestKey>(
+    (btree, n): (BTreeMap<HashValue, (HashValue, V)>, usize),
+) {
+    let (db, version) = init_mock_db(&btree.clone().into_iter().collect());
+    let tree = JellyfishMerkleTree::new(&db);
+
+    // Prove the range from the nth key to the middle of the remaining keys
+    let last_index = n + (btree.len() - n) / 2;
+    let nth_key = *btree.keys().nth(n).unwrap();
+    let last_key = *btree.keys().nth(last_index).unwrap();
+    let left_siblings = tree.get_range_left_siblings(nth_key, version).unwrap();
+    let proof = tree.get_range_proof(last_key, version).unwrap();
+    let root_hash = tree.get_root_hash(version).unwrap();
+    let leaves: Vec<_> = btree
+        .iter()
+        .skip(n)
+        .take(last_index - n + 1)
+        .map(|(key, value)| SparseMerkleLeafNode::new(*key, value.0))
+        .collect();
+    assert!(proof
+        .verify_range(root_hash, &leaves, &left_siblings)
+        .is_ok());
+}
+
 fn test_existent_keys_impl<V: TestKey>(
     tree: &JellyfishMerkleTree<'_, MockTreeStore<V>, V>,
     version: Version,
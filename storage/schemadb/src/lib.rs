@@ -280,6 +280,14 @@ impl DB {
         Ok(self.inner.flush_cf(self.get_cf_handle(cf_name)?)?)
     }
 
+    /// Compacts the whole key range of the column family, physically removing the deleted
+    /// entries from disk.
+    pub fn compact_cf(&self, cf_name: &str) -> Result<()> {
+        self.inner
+            .compact_range_cf(self.get_cf_handle(cf_name)?, None::<&[u8]>, None::<&[u8]>);
+        Ok(())
+    }
+
     pub fn get_property(&self, cf_name: &str, property_name: &str) -> Result<u64> {
         self.inner
             .property_int_value_cf(self.get_cf_handle(cf_name)?, property_name)?
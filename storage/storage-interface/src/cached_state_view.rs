@@ -7,7 +7,7 @@ use crate::{
 use anyhow::Result;
 use aptos_crypto::{hash::CryptoHash, HashValue};
 use aptos_scratchpad::{FrozenSparseMerkleTree, SparseMerkleTree, StateStoreStatus};
-use aptos_state_view::{StateViewId, TStateView};
+use aptos_state_view::{in_memory_state_view::InMemoryStateView, StateViewId, TStateView};
 use aptos_types::{
     proof::SparseMerkleProofExt,
     state_store::{
@@ -229,6 +229,26 @@ impl TStateView for CachedStateView {
     fn get_usage(&self) -> Result<StateStorageUsage> {
         Ok(self.speculative_state.usage())
     }
+
+    /// Only contains the state values that have been read (or primed) so far.
+    fn as_in_memory_state_view(&self) -> InMemoryStateView {
+        InMemoryStateView::new(
+            self.sharded_state_cache
+                .iter()
+                .flat_map(|shard| {
+                    shard
+                        .iter()
+                        .filter_map(|entry| {
+                            let (_, value_opt) = entry.value();
+                            value_opt
+                                .as_ref()
+                                .map(|value| (entry.key().clone(), value.clone()))
+                        })
+                        .collect::<Vec<_>>()
+                })
+                .collect(),
+        )
+    }
 }
 
 pub struct CachedDbStateView {
@@ -58,6 +58,11 @@ pub const MAX_REQUEST_LIMIT: u64 = 10000;
 pub trait StateSnapshotReceiver<K, V>: Send {
     fn add_chunk(&mut self, chunk: Vec<(K, V)>, proof: SparseMerkleRangeProof) -> Result<()>;
 
+    /// Writes the values of a chunk that arrived ahead of the chunks added so far. The chunk
+    /// must already be verified (see `SparseMerkleRangeProof::verify_range`) and must still be
+    /// added in order (using `add_chunk`) to restore the tree, but its values aren't rewritten.
+    fn add_out_of_order_chunk(&mut self, chunk: &[(K, V)]) -> Result<()>;
+
     fn finish(self) -> Result<()>;
 
     fn finish_box(self: Box<Self>) -> Result<()>;
@@ -11,6 +11,7 @@ pub enum OnChainExecutionConfig {
     V1(ExecutionConfigV1),
     V2(ExecutionConfigV2),
     V3(ExecutionConfigV3),
+    V4(ExecutionConfigV4),
 }
 
 /// The public interface that exposes all values with safe fallback.
@@ -21,15 +22,24 @@ impl OnChainExecutionConfig {
             OnChainExecutionConfig::V1(config) => config.transaction_shuffler_type.clone(),
             OnChainExecutionConfig::V2(config) => config.transaction_shuffler_type.clone(),
             OnChainExecutionConfig::V3(config) => config.transaction_shuffler_type.clone(),
+            OnChainExecutionConfig::V4(config) => config.transaction_shuffler_type.clone(),
         }
     }
 
-    /// The per-block gas limit being used.
+    /// The plain per-block gas limit being used, i.e. the limit on the accumulated execution and
+    /// io gas of the block. `None` if there is no limit, or if the conflict-aware limit is used
+    /// instead, which is applied by the block executor itself.
     pub fn block_gas_limit(&self) -> Option<u64> {
+        self.block_gas_limit_type().block_gas_limit()
+    }
+
+    /// The type of the per-block gas limit being used.
+    pub fn block_gas_limit_type(&self) -> BlockGasLimitType {
         match &self {
-            OnChainExecutionConfig::V1(_config) => None,
-            OnChainExecutionConfig::V2(config) => config.block_gas_limit,
-            OnChainExecutionConfig::V3(config) => config.block_gas_limit,
+            OnChainExecutionConfig::V1(_config) => BlockGasLimitType::NoLimit,
+            OnChainExecutionConfig::V2(config) => config.block_gas_limit.into(),
+            OnChainExecutionConfig::V3(config) => config.block_gas_limit.into(),
+            OnChainExecutionConfig::V4(config) => config.block_gas_limit_type,
         }
     }
 
@@ -39,6 +49,7 @@ impl OnChainExecutionConfig {
             OnChainExecutionConfig::V1(_config) => TransactionDeduperType::NoDedup,
             OnChainExecutionConfig::V2(_config) => TransactionDeduperType::NoDedup,
             OnChainExecutionConfig::V3(config) => config.transaction_deduper_type.clone(),
+            OnChainExecutionConfig::V4(config) => config.transaction_deduper_type.clone(),
         }
     }
 }
@@ -113,12 +124,80 @@ impl Default for ExecutionConfigV3 {
     }
 }
 
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+pub struct ExecutionConfigV4 {
+    pub transaction_shuffler_type: TransactionShufflerType,
+    pub block_gas_limit_type: BlockGasLimitType,
+    pub transaction_deduper_type: TransactionDeduperType,
+}
+
+impl Default for ExecutionConfigV4 {
+    fn default() -> Self {
+        Self {
+            transaction_shuffler_type: TransactionShufflerType::NoShuffling,
+            block_gas_limit_type: BlockGasLimitType::NoLimit,
+            transaction_deduper_type: TransactionDeduperType::NoDedup,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")] // cannot use tag = "type" as nested enums cannot work, and bcs doesn't support it
+pub enum BlockGasLimitType {
+    NoLimit,
+    /// The block is cut once the accumulated execution and io gas of the committed transactions
+    /// reaches the limit.
+    Limit(u64),
+    /// The block is cut once its effective parallel cost reaches `effective_block_gas_limit`.
+    /// The effective cost is the larger of the execution and io gas along the longest chain of
+    /// conflicting transactions (a transaction conflicts with the last earlier transaction that
+    /// wrote a location it read), and the total execution and io gas divided by `parallelism`.
+    ConflictAwareV1 {
+        effective_block_gas_limit: u64,
+        parallelism: u32,
+    },
+}
+
+impl BlockGasLimitType {
+    /// The plain per-block gas limit, if that is the type of limit being used.
+    pub fn block_gas_limit(&self) -> Option<u64> {
+        match self {
+            BlockGasLimitType::Limit(limit) => Some(*limit),
+            BlockGasLimitType::NoLimit | BlockGasLimitType::ConflictAwareV1 { .. } => None,
+        }
+    }
+
+    /// Whether blocks can be cut by the limit. Such blocks don't end with a state checkpoint
+    /// transaction, one is appended after the last committed transaction instead.
+    pub fn is_limited(&self) -> bool {
+        *self != BlockGasLimitType::NoLimit
+    }
+}
+
+impl From<Option<u64>> for BlockGasLimitType {
+    fn from(maybe_block_gas_limit: Option<u64>) -> Self {
+        match maybe_block_gas_limit {
+            Some(limit) => BlockGasLimitType::Limit(limit),
+            None => BlockGasLimitType::NoLimit,
+        }
+    }
+}
+
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
 #[serde(rename_all = "snake_case")] // cannot use tag = "type" as nested enums cannot work, and bcs doesn't support it
 pub enum TransactionShufflerType {
     NoShuffling,
     DeprecatedSenderAwareV1(u32),
     SenderAwareV2(u32),
+    /// Spreads apart transactions whose (estimated) read/write sets overlap, within the given
+    /// conflict window size.
+    ConflictAwareV1(u32),
+    /// Orders transactions by gas unit price, but only within consecutive windows of
+    /// `fairness_window_size` transactions, and then spreads apart conflicting transactions.
+    FeeAwareV1 {
+        fairness_window_size: u32,
+        conflict_window_size: u32,
+    },
 }
 
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
@@ -192,6 +271,61 @@ mod test {
             TransactionShufflerType::SenderAwareV2(32)
         ));
         assert!(matches!(result.block_gas_limit(), None));
+
+        // V3 test with the fee aware shuffler
+        let config = OnChainExecutionConfig::V3(ExecutionConfigV3 {
+            transaction_shuffler_type: TransactionShufflerType::FeeAwareV1 {
+                fairness_window_size: 64,
+                conflict_window_size: 32,
+            },
+            block_gas_limit: None,
+            transaction_deduper_type: TransactionDeduperType::TxnHashAndAuthenticatorV1,
+        });
+
+        let s = serde_yaml::to_string(&config).unwrap();
+        let result = serde_yaml::from_str::<OnChainExecutionConfig>(&s).unwrap();
+        assert_eq!(
+            result.transaction_shuffler_type(),
+            TransactionShufflerType::FeeAwareV1 {
+                fairness_window_size: 64,
+                conflict_window_size: 32,
+            }
+        );
+        let result =
+            bcs::from_bytes::<OnChainExecutionConfig>(&bcs::to_bytes(&config).unwrap()).unwrap();
+        assert_eq!(result, config);
+
+        // V4 test with the conflict-aware block gas limit
+        let config = OnChainExecutionConfig::V4(ExecutionConfigV4 {
+            transaction_shuffler_type: TransactionShufflerType::SenderAwareV2(32),
+            block_gas_limit_type: BlockGasLimitType::ConflictAwareV1 {
+                effective_block_gas_limit: 20000,
+                parallelism: 8,
+            },
+            transaction_deduper_type: TransactionDeduperType::TxnHashAndAuthenticatorV1,
+        });
+
+        let s = serde_yaml::to_string(&config).unwrap();
+        let result = serde_yaml::from_str::<OnChainExecutionConfig>(&s).unwrap();
+        assert_eq!(
+            result.block_gas_limit_type(),
+            BlockGasLimitType::ConflictAwareV1 {
+                effective_block_gas_limit: 20000,
+                parallelism: 8,
+            }
+        );
+        // The plain limit doesn't apply.
+        assert_eq!(result.block_gas_limit(), None);
+        let result =
+            bcs::from_bytes::<OnChainExecutionConfig>(&bcs::to_bytes(&config).unwrap()).unwrap();
+        assert_eq!(result, config);
+
+        // V4 test with a plain block gas limit
+        let config = OnChainExecutionConfig::V4(ExecutionConfigV4 {
+            block_gas_limit_type: BlockGasLimitType::Limit(rand_gas_limit),
+            ..ExecutionConfigV4::default()
+        });
+        assert_eq!(config.block_gas_limit(), Some(rand_gas_limit));
     }
 
     #[test]
@@ -767,6 +767,144 @@ impl SparseMerkleRangeProof {
 
         Ok(())
     }
+
+    /// Verifies that the given consecutive leaves (sorted by key) exist in the tree and that the
+    /// resulting root hash matches the expected root hash. Unlike `verify`, this doesn't require
+    /// any knowledge of the leaves before the range. Instead, `left_siblings` are the siblings on
+    /// the left of the path from root to the first leaf (the ones near the bottom are at the
+    /// beginning of the vector). In the above example, proving that `[d, e]` exists in the tree
+    /// would require the siblings `[hash(b, c), a]` on the left and `[X, h]` on the right.
+    pub fn verify_range(
+        &self,
+        expected_root_hash: HashValue,
+        leaves: &[SparseMerkleLeafNode],
+        left_siblings: &[HashValue],
+    ) -> Result<()> {
+        let (first_leaf, last_leaf) = match (leaves.first(), leaves.last()) {
+            (Some(first_leaf), Some(last_leaf)) => (first_leaf, last_leaf),
+            _ => bail!("The range of leaves to verify is empty."),
+        };
+        ensure!(
+            leaves
+                .windows(2)
+                .all(|leaf_pair| leaf_pair[0].key() < leaf_pair[1].key()),
+            "The leaves are not sorted by key or contain duplicates.",
+        );
+
+        // Collect the subtrees on the left of the range, the leaves in the range
+        // and the subtrees on the right of the range, and rebuild the tree.
+        let mut nodes = range_siblings(first_leaf.key(), left_siblings, true)?;
+        nodes.extend(leaves.iter().map(|leaf| RangeNode::Leaf {
+            key: leaf.key(),
+            hash: leaf.hash(),
+        }));
+        nodes.extend(range_siblings(
+            last_leaf.key(),
+            &self.right_siblings,
+            false,
+        )?);
+        let current_hash = compute_range_root_hash(nodes.iter().collect(), 0)?;
+
+        ensure!(
+            current_hash == expected_root_hash,
+            "{}: Root hashes do not match. Actual root hash: {:x}. Expected root hash: {:x}.",
+            type_name::<Self>(),
+            current_hash,
+            expected_root_hash,
+        );
+
+        Ok(())
+    }
+}
+
+/// A node used to rebuild the tree from a range of leaves (see `verify_range`)
+enum RangeNode {
+    /// A leaf in the range
+    Leaf { key: HashValue, hash: HashValue },
+    /// A subtree on the boundary of the range. The subtree is the sibling of
+    /// the node at `depth` on the path from root to `key`.
+    Sibling {
+        key: HashValue,
+        depth: usize,
+        hash: HashValue,
+    },
+}
+
+impl RangeNode {
+    /// Returns the bit that decides which child (at the given depth) contains the node
+    fn bit(&self, depth: usize) -> bool {
+        match self {
+            RangeNode::Leaf { key, .. } => key.bit(depth),
+            RangeNode::Sibling {
+                key,
+                depth: sibling_depth,
+                ..
+            } => key.bit(depth) != (depth + 1 == *sibling_depth),
+        }
+    }
+}
+
+/// Returns the non-empty sibling subtrees on one side of the path from root to `key`.
+/// The siblings are ordered from the bottom level to the root level.
+fn range_siblings(key: HashValue, siblings: &[HashValue], left: bool) -> Result<Vec<RangeNode>> {
+    let sibling_depths: Vec<_> = key
+        .iter_bits()
+        .enumerate()
+        .filter(|(_, bit)| *bit == left)
+        .map(|(index, _)| index + 1)
+        .collect();
+    ensure!(
+        siblings.len() <= sibling_depths.len(),
+        "Too many siblings: {}. Max number of siblings: {}.",
+        siblings.len(),
+        sibling_depths.len(),
+    );
+
+    Ok(siblings
+        .iter()
+        .rev()
+        .zip(sibling_depths)
+        .filter(|(hash, _)| **hash != *SPARSE_MERKLE_PLACEHOLDER_HASH)
+        .map(|(hash, depth)| RangeNode::Sibling {
+            key,
+            depth,
+            hash: *hash,
+        })
+        .collect())
+}
+
+/// Computes the root hash of the subtree (at the given depth) that contains the given nodes
+fn compute_range_root_hash(nodes: Vec<&RangeNode>, depth: usize) -> Result<HashValue> {
+    match nodes.as_slice() {
+        [] => return Ok(*SPARSE_MERKLE_PLACEHOLDER_HASH),
+        [RangeNode::Leaf { hash, .. }] => return Ok(*hash),
+        [RangeNode::Sibling {
+            depth: sibling_depth,
+            hash,
+            ..
+        }] if *sibling_depth == depth => return Ok(*hash),
+        _ => (),
+    }
+
+    // The subtree must be split further, so a sibling can't be rooted at this depth
+    ensure!(
+        depth < HashValue::LENGTH_IN_BITS
+            && nodes.iter().all(|node| match node {
+                RangeNode::Leaf { .. } => true,
+                RangeNode::Sibling {
+                    depth: sibling_depth,
+                    ..
+                } => *sibling_depth > depth,
+            }),
+        "The siblings overlap the range of leaves at depth: {}.",
+        depth,
+    );
+    let (left_nodes, right_nodes) = nodes.into_iter().partition(|node| !node.bit(depth));
+    Ok(SparseMerkleInternalNode::new(
+        compute_range_root_hash(left_nodes, depth + 1)?,
+        compute_range_root_hash(right_nodes, depth + 1)?,
+    )
+    .hash())
 }
 
 /// `TransactionInfo` and a `TransactionAccumulatorProof` connecting it to the ledger root.
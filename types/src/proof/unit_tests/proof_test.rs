@@ -11,9 +11,9 @@ use crate::{
     ledger_info::LedgerInfo,
     proof::{
         definition::MAX_ACCUMULATOR_PROOF_DEPTH, AccumulatorExtensionProof, AccumulatorRangeProof,
-        SparseMerkleInternalNode, SparseMerkleLeafNode, TestAccumulatorInternalNode,
-        TestAccumulatorProof, TransactionAccumulatorInternalNode, TransactionAccumulatorProof,
-        TransactionInfoListWithProof, TransactionInfoWithProof,
+        SparseMerkleInternalNode, SparseMerkleLeafNode, SparseMerkleRangeProof,
+        TestAccumulatorInternalNode, TestAccumulatorProof, TransactionAccumulatorInternalNode,
+        TransactionAccumulatorProof, TransactionInfoListWithProof, TransactionInfoWithProof,
     },
     state_store::state_value::StateValue,
     transaction::{
@@ -266,6 +266,148 @@ fn test_verify_three_element_sparse_merkle() {
     }
 }
 
+#[test]
+fn test_verify_sparse_merkle_range() {
+    //                   root
+    //                  /     \
+    //                 /       \
+    //                /         \
+    //               o           o
+    //              / \         / \
+    //             a   o       o   h
+    //                / \     / \
+    //               o   d   e   X
+    //              / \         / \
+    //             b   c       f   g
+    let leaves: Vec<_> = [
+        0b0000_0000,
+        0b0100_0000,
+        0b0101_0000,
+        0b0110_0000,
+        0b1000_0000,
+        0b1010_0000,
+        0b1011_0000,
+        0b1100_0000,
+    ]
+    .iter()
+    .map(|first_byte| {
+        let mut key = [0; HashValue::LENGTH];
+        key[0] = *first_byte;
+        SparseMerkleLeafNode::new(HashValue::new(key), HashValue::random())
+    })
+    .collect();
+    let leaf_hashes: Vec<_> = leaves.iter().map(|leaf| leaf.hash()).collect();
+    let (a, b, c, d, e, f, g, h) = (
+        leaf_hashes[0],
+        leaf_hashes[1],
+        leaf_hashes[2],
+        leaf_hashes[3],
+        leaf_hashes[4],
+        leaf_hashes[5],
+        leaf_hashes[6],
+        leaf_hashes[7],
+    );
+    let internal_bc_hash = SparseMerkleInternalNode::new(b, c).hash();
+    let internal_bcd_hash = SparseMerkleInternalNode::new(internal_bc_hash, d).hash();
+    let internal_left_hash = SparseMerkleInternalNode::new(a, internal_bcd_hash).hash();
+    let internal_x_hash = SparseMerkleInternalNode::new(f, g).hash();
+    let internal_ex_hash = SparseMerkleInternalNode::new(e, internal_x_hash).hash();
+    let internal_right_hash = SparseMerkleInternalNode::new(internal_ex_hash, h).hash();
+    let root_hash = SparseMerkleInternalNode::new(internal_left_hash, internal_right_hash).hash();
+
+    // The entire tree doesn't require any siblings
+    let proof = SparseMerkleRangeProof::new(vec![]);
+    assert!(proof.verify_range(root_hash, &leaves, &[]).is_ok());
+
+    // The range from the leftmost leaf only requires the right siblings
+    let proof = SparseMerkleRangeProof::new(vec![internal_x_hash, h]);
+    assert!(proof.verify_range(root_hash, &leaves[..5], &[]).is_ok());
+
+    // A range in the middle of the tree requires the siblings on both sides
+    let left_siblings = [internal_bc_hash, a];
+    assert!(proof
+        .verify_range(root_hash, &leaves[3..5], &left_siblings)
+        .is_ok());
+
+    // The range to the rightmost leaf only requires the left siblings
+    let proof = SparseMerkleRangeProof::new(vec![]);
+    let left_siblings = [f, e, internal_left_hash];
+    assert!(proof
+        .verify_range(root_hash, &leaves[6..], &left_siblings)
+        .is_ok());
+
+    // Verify that missing, extra or invalid siblings fail verification
+    let proof = SparseMerkleRangeProof::new(vec![internal_x_hash, h]);
+    assert!(proof
+        .verify_range(root_hash, &leaves[3..5], &[internal_bc_hash])
+        .is_err());
+    assert!(proof
+        .verify_range(root_hash, &leaves[3..5], &[internal_bc_hash, a, a, a])
+        .is_err());
+    assert!(proof
+        .verify_range(root_hash, &leaves[3..5], &[a, internal_bc_hash])
+        .is_err());
+    let proof = SparseMerkleRangeProof::new(vec![h, internal_x_hash]);
+    assert!(proof
+        .verify_range(root_hash, &leaves[3..5], &[internal_bc_hash, a])
+        .is_err());
+
+    // Verify that missing, unsorted or modified leaves fail verification
+    let proof = SparseMerkleRangeProof::new(vec![internal_x_hash, h]);
+    let left_siblings = [b, a];
+    assert!(proof
+        .verify_range(root_hash, &[leaves[2], leaves[4]], &left_siblings)
+        .is_err());
+    let left_siblings = [internal_bc_hash, a];
+    assert!(proof
+        .verify_range(root_hash, &[leaves[4], leaves[3]], &left_siblings)
+        .is_err());
+    let modified_leaf = SparseMerkleLeafNode::new(leaves[4].key(), HashValue::random());
+    assert!(proof
+        .verify_range(root_hash, &[leaves[3], modified_leaf], &left_siblings)
+        .is_err());
+    assert!(proof.verify_range(root_hash, &[], &left_siblings).is_err());
+}
+
+#[test]
+fn test_verify_sparse_merkle_range_with_placeholders() {
+    //            root
+    //           /    \
+    //          a      default
+    //         / \
+    //     key1   b
+    //           / \
+    //       key2   key3
+    let key1 = b"hello".test_only_hash();
+    let key2 = b"world".test_only_hash();
+    let key3 = b"!".test_only_hash();
+    let leaf1 = SparseMerkleLeafNode::new(key1, StateValue::from(b"1".to_vec()).hash());
+    let leaf2 = SparseMerkleLeafNode::new(key2, StateValue::from(b"2".to_vec()).hash());
+    let leaf3 = SparseMerkleLeafNode::new(key3, StateValue::from(b"3".to_vec()).hash());
+    let internal_b_hash = SparseMerkleInternalNode::new(leaf2.hash(), leaf3.hash()).hash();
+    let internal_a_hash = SparseMerkleInternalNode::new(leaf1.hash(), internal_b_hash).hash();
+    let root_hash =
+        SparseMerkleInternalNode::new(internal_a_hash, *SPARSE_MERKLE_PLACEHOLDER_HASH).hash();
+
+    // Verify the ranges that start at each leaf
+    let proof = SparseMerkleRangeProof::new(vec![*SPARSE_MERKLE_PLACEHOLDER_HASH]);
+    assert!(proof
+        .verify_range(root_hash, &[leaf1, leaf2, leaf3], &[])
+        .is_ok());
+    assert!(proof
+        .verify_range(root_hash, &[leaf2, leaf3], &[leaf1.hash()])
+        .is_ok());
+    assert!(proof
+        .verify_range(root_hash, &[leaf3], &[leaf2.hash(), leaf1.hash()])
+        .is_ok());
+
+    // Verify that the ranges fail verification if the left siblings are missing
+    assert!(proof.verify_range(root_hash, &[leaf2, leaf3], &[]).is_err());
+    assert!(proof
+        .verify_range(root_hash, &[leaf3], &[leaf2.hash()])
+        .is_err());
+}
+
 #[test]
 fn test_verify_transaction() {
     //            root
@@ -2,9 +2,12 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    on_chain_config::CurrentTimeMicroseconds, proof::SparseMerkleRangeProof,
-    state_store::state_key::StateKey, transaction::Version,
+    on_chain_config::CurrentTimeMicroseconds,
+    proof::{SparseMerkleLeafNode, SparseMerkleRangeProof},
+    state_store::state_key::StateKey,
+    transaction::Version,
 };
+use anyhow::{bail, ensure};
 use aptos_crypto::{
     hash::{CryptoHash, SPARSE_MERKLE_PLACEHOLDER_HASH},
     HashValue,
@@ -171,9 +174,6 @@ impl CryptoHash for StateValue {
     }
 }
 
-/// TODO(joshlind): add a proof implementation (e.g., verify()) and unit tests
-/// for these once we start supporting them.
-///
 /// A single chunk of all state values at a specific version.
 /// Note: this is similar to `StateSnapshotChunk` but all data is included
 /// in the struct itself and not behind pointers/handles to file locations.
@@ -186,10 +186,37 @@ pub struct StateValueChunkWithProof {
     pub last_key: HashValue,  // The last hashed state key in chunk
     pub raw_values: Vec<(StateKey, StateValue)>, // The hashed state key and and raw state value.
     pub proof: SparseMerkleRangeProof, // The proof to ensure the chunk is in the hashed states
+    pub left_siblings: Vec<HashValue>, // The siblings on the left of the first key (bottom first)
     pub root_hash: HashValue, // The root hash of the sparse merkle tree for this chunk
 }
 
 impl StateValueChunkWithProof {
+    /// Verifies that the state values in this chunk exist in the sparse merkle
+    /// tree with the chunk root hash. This doesn't depend on any other chunks
+    /// (i.e., chunks can be verified in any order).
+    pub fn verify(&self) -> anyhow::Result<()> {
+        let leaves: Vec<_> = self
+            .raw_values
+            .iter()
+            .map(|(state_key, state_value)| {
+                SparseMerkleLeafNode::new(CryptoHash::hash(state_key), state_value.hash())
+            })
+            .collect();
+        let (first_leaf, last_leaf) = match (leaves.first(), leaves.last()) {
+            (Some(first_leaf), Some(last_leaf)) => (first_leaf, last_leaf),
+            _ => bail!("The state value chunk is empty!"),
+        };
+        ensure!(
+            first_leaf.key() == self.first_key && last_leaf.key() == self.last_key,
+            "The first and last keys don't match the state values! First key: {:?}, last key: {:?}",
+            self.first_key,
+            self.last_key,
+        );
+
+        self.proof
+            .verify_range(self.root_hash, &leaves, &self.left_siblings)
+    }
+
     /// Returns true iff this chunk is the last chunk (i.e., there are no
     /// more state values to write to storage after this chunk).
     pub fn is_last_chunk(&self) -> bool {
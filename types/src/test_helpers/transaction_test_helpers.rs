@@ -5,6 +5,7 @@
 use crate::{
     account_address::AccountAddress,
     chain_id::ChainId,
+    on_chain_config::BlockGasLimitType,
     transaction::{
         authenticator::AccountAuthenticator, Module, RawTransaction, RawTransactionWithData,
         Script, SignedTransaction, Transaction, TransactionPayload,
@@ -16,8 +17,8 @@ const MAX_GAS_AMOUNT: u64 = 1_000_000;
 const TEST_GAS_PRICE: u64 = 100;
 
 // The block gas limit parameter for executor tests
-pub const BLOCK_GAS_LIMIT: Option<u64> = Some(1000);
-// pub const BLOCK_GAS_LIMIT: Option<u64> = None;
+pub const BLOCK_GAS_LIMIT: BlockGasLimitType = BlockGasLimitType::Limit(1000);
+// pub const BLOCK_GAS_LIMIT: BlockGasLimitType = BlockGasLimitType::NoLimit;
 
 static EMPTY_SCRIPT: &[u8] = include_bytes!("empty_script.mv");
 
@@ -245,9 +246,9 @@ pub fn get_test_txn_with_chain_id(
 
 pub fn block(
     mut user_txns: Vec<Transaction>,
-    maybe_block_gas_limit: Option<u64>,
+    block_gas_limit_type: BlockGasLimitType,
 ) -> Vec<Transaction> {
-    if maybe_block_gas_limit.is_none() {
+    if !block_gas_limit_type.is_limited() {
         user_txns.push(Transaction::StateCheckpoint(HashValue::random()));
     }
     user_txns
@@ -105,6 +105,27 @@ impl AnalyzedTransaction {
         self.predictable_transaction
     }
 
+    /// Whether the transaction is one of the user transactions the read / write hints are known
+    /// for. Converting other user transactions into an `AnalyzedTransaction` is not supported.
+    pub fn is_supported_user_transaction(txn: &Transaction) -> bool {
+        match txn {
+            Transaction::UserTransaction(signed_txn) => match signed_txn.payload() {
+                TransactionPayload::EntryFunction(func) => matches!(
+                    (
+                        *func.module().address(),
+                        func.module().name().as_str(),
+                        func.function().as_str(),
+                    ),
+                    (AccountAddress::ONE, "coin", "transfer")
+                        | (AccountAddress::ONE, "aptos_account", "transfer")
+                        | (AccountAddress::ONE, "aptos_account", "create_account")
+                ),
+                _ => false,
+            },
+            _ => false,
+        }
+    }
+
     pub fn sender(&self) -> Option<AccountAddress> {
         match &self.transaction {
             Transaction::UserTransaction(signed_txn) => Some(signed_txn.sender()),